@@ -14,6 +14,7 @@ pub mod slash_command_settings;
 mod slash_command_working_set;
 mod streaming_diff;
 mod terminal_inline_assistant;
+mod usage_view;
 
 use crate::slash_command::project_command::ProjectSlashCommandFeatureFlag;
 pub use crate::slash_command_working_set::{SlashCommandId, SlashCommandWorkingSet};
@@ -35,7 +36,7 @@ use language_model::{
 pub use patch::*;
 pub use prompts::PromptBuilder;
 use prompts::PromptLoadingParams;
-use semantic_index::{CloudEmbeddingProvider, SemanticDb};
+use semantic_index::{SemanticDb, SemanticIndexSettings};
 use serde::{Deserialize, Serialize};
 use settings::{update_settings_file, Settings, SettingsStore};
 use slash_command::search_command::SearchSlashCommandFeatureFlag;
@@ -198,6 +199,7 @@ pub fn init(
     cx.set_global(Assistant::default());
     AssistantSettings::register(cx);
     SlashCommandSettings::register(cx);
+    SemanticIndexSettings::register(cx);
 
     // TODO: remove this when 0.148.0 is released.
     if AssistantSettings::get_global(cx).using_outdated_settings_version {
@@ -223,10 +225,17 @@ pub fn init(
                 return Ok(());
             }
 
-            let embedding_provider = CloudEmbeddingProvider::new(client.clone());
+            let (db_name, embedding_provider) = cx.update(|cx| {
+                let settings = SemanticIndexSettings::get_global(cx);
+                (
+                    settings.db_name(),
+                    settings.embedding_provider(client.clone(), cx.background_executor().clone()),
+                )
+            })?;
+            let embedding_provider = embedding_provider.await?;
             let semantic_index = SemanticDb::new(
-                paths::embeddings_dir().join("semantic-index-db.0.mdb"),
-                Arc::new(embedding_provider),
+                paths::embeddings_dir().join(db_name),
+                embedding_provider,
                 &mut cx,
             )
             .await?;
@@ -242,6 +251,7 @@ pub fn init(
     assistant_slash_command::init(cx);
     assistant_tool::init(cx);
     assistant_panel::init(cx);
+    usage_view::init(cx);
     context_server::init(cx);
 
     let prompt_builder = prompts::PromptBuilder::new(Some(PromptLoadingParams {
@@ -93,7 +93,7 @@ use workspace::{
     ToolbarItemLocation, ToolbarItemView, Workspace,
 };
 use workspace::{searchable::SearchableItemHandle, DraggedTab};
-use zed_actions::InlineAssist;
+use zed_actions::{InlineAssist, ShowUsage};
 
 pub fn init(cx: &mut AppContext) {
     workspace::FollowableViewRegistry::register::<ContextEditor>(cx);
@@ -480,6 +480,7 @@ impl AssistantPanel {
                                         .action("History", Box::new(DeployHistory))
                                         .action("Prompt Library", Box::new(DeployPromptLibrary))
                                         .action("Configure", Box::new(ShowConfiguration))
+                                        .action("Usage", Box::new(ShowUsage))
                                         .action(zoom_label, Box::new(ToggleZoom))
                                 }))
                             }),
@@ -1671,7 +1672,21 @@ impl ContextEditor {
         if request_type == RequestType::SuggestEdits && !self.context.read(cx).contains_files(cx) {
             self.last_error = Some(AssistError::FileRequired);
             cx.notify();
-        } else if let Some(user_message) = self
+            return;
+        }
+
+        let confirm_budget = language_models::confirm_usage_budget(cx);
+        cx.spawn(|this, mut cx| async move {
+            if confirm_budget.await? {
+                this.update(&mut cx, |this, cx| this.start_completion(request_type, cx))?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn start_completion(&mut self, request_type: RequestType, cx: &mut ViewContext<Self>) {
+        if let Some(user_message) = self
             .context
             .update(cx, |context, cx| context.assist(request_type, cx))
         {
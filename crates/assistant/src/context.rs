@@ -28,11 +28,11 @@ use language_model::{
     LanguageModel, LanguageModelCacheConfiguration, LanguageModelCompletionEvent,
     LanguageModelImage, LanguageModelRegistry, LanguageModelRequest, LanguageModelRequestMessage,
     LanguageModelRequestTool, LanguageModelToolResult, LanguageModelToolUse,
-    LanguageModelToolUseId, MessageContent, Role, StopReason,
+    LanguageModelToolUseId, MessageContent, Role, StopReason, UsageReporter,
 };
 use language_models::{
     provider::cloud::{MaxMonthlySpendReachedError, PaymentRequiredError},
-    report_assistant_event,
+    report_assistant_event, usage_project_name,
 };
 use open_ai::Model as OpenAiModel;
 use paths::contexts_dir;
@@ -1327,11 +1327,19 @@ impl Context {
         };
 
         let model = Arc::clone(model);
+        let project_name = self.usage_project_name(cx);
         self.pending_cache_warming_task = cx.spawn(|this, mut cx| {
             async move {
+                let usage_reporter = UsageReporter::new(model.as_ref(), project_name, &cx);
                 match model.stream_completion(request, &cx).await {
                     Ok(mut stream) => {
-                        stream.next().await;
+                        // The response is short, so read all of it to record what writing the
+                        // cache cost.
+                        while let Some(event) = stream.next().await {
+                            if let Ok(LanguageModelCompletionEvent::UsageUpdate(usage)) = event {
+                                usage_reporter.report(usage);
+                            }
+                        }
                         log::info!("Cache warming completed successfully");
                     }
                     Err(e) => {
@@ -1348,6 +1356,11 @@ impl Context {
         });
     }
 
+    fn usage_project_name(&self, cx: &AppContext) -> Option<SharedString> {
+        let project = self.project.as_ref()?;
+        usage_project_name(project.read(cx), cx)
+    }
+
     pub fn update_cache_status_for_completion(&mut self, cx: &mut ModelContext<Self>) {
         let cached_message_ids: Vec<MessageId> = self
             .messages_metadata
@@ -2268,9 +2281,11 @@ impl Context {
             .unwrap();
 
         let pending_completion_id = post_inc(&mut self.completion_count);
+        let project_name = self.usage_project_name(cx);
 
         let task = cx.spawn({
             |this, mut cx| async move {
+                let usage_reporter = UsageReporter::new(model.as_ref(), project_name, &cx);
                 let stream = model.stream_completion(request, &cx);
                 let assistant_message_id = assistant_message.id;
                 let mut response_latency = None;
@@ -2303,6 +2318,9 @@ impl Context {
                                     LanguageModelCompletionEvent::Stop(reason) => {
                                         stop_reason = reason;
                                     }
+                                    LanguageModelCompletionEvent::UsageUpdate(usage) => {
+                                        usage_reporter.report(usage);
+                                    }
                                     LanguageModelCompletionEvent::Text(chunk) => {
                                         buffer.edit(
                                             [(
@@ -2901,9 +2919,10 @@ impl Context {
                 cache: false,
             });
 
+            let project_name = self.usage_project_name(cx);
             self.pending_summary = cx.spawn(|this, mut cx| {
                 async move {
-                    let stream = model.stream_completion_text(request, &cx);
+                    let stream = model.stream_completion_text(request, project_name, &cx);
                     let mut messages = stream.await?;
 
                     let mut replaced = !replace_old;
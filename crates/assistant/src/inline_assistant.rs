@@ -34,7 +34,7 @@ use language_model::{
     LanguageModelTextStream, Role,
 };
 use language_model_selector::LanguageModelSelector;
-use language_models::report_assistant_event;
+use language_models::{report_assistant_event, usage_project_name};
 use multi_buffer::MultiBufferRow;
 use parking_lot::Mutex;
 use project::{CodeAction, ProjectTransaction};
@@ -1017,11 +1017,12 @@ impl InlineAssistant {
         }
 
         let assistant_panel_context = assist.assistant_panel_context(cx);
+        let project_name = assist.project_name(cx);
 
         assist
             .codegen
             .update(cx, |codegen, cx| {
-                codegen.start(user_prompt, assistant_panel_context, cx)
+                codegen.start(user_prompt, assistant_panel_context, project_name, cx)
             })
             .log_err();
     }
@@ -2331,6 +2332,11 @@ impl InlineAssist {
         }
     }
 
+    fn project_name(&self, cx: &WindowContext) -> Option<SharedString> {
+        let workspace = self.workspace.as_ref()?.upgrade()?;
+        usage_project_name(workspace.read(cx).project().read(cx), cx)
+    }
+
     pub fn count_tokens(&self, cx: &WindowContext) -> BoxFuture<'static, Result<TokenCounts>> {
         let Some(user_prompt) = self.user_prompt(cx) else {
             return future::ready(Err(anyhow!("no user prompt"))).boxed();
@@ -2456,6 +2462,7 @@ impl Codegen {
         &mut self,
         user_prompt: String,
         assistant_panel_context: Option<LanguageModelRequest>,
+        project_name: Option<SharedString>,
         cx: &mut ModelContext<Self>,
     ) -> Result<()> {
         let alternative_models = LanguageModelRegistry::read_global(cx)
@@ -2493,6 +2500,7 @@ impl Codegen {
                     user_prompt.clone(),
                     assistant_panel_context.clone(),
                     model.clone(),
+                    project_name.clone(),
                     cx,
                 )
             })?;
@@ -2732,6 +2740,7 @@ impl CodegenAlternative {
         user_prompt: String,
         assistant_panel_context: Option<LanguageModelRequest>,
         model: Arc<dyn LanguageModel>,
+        project_name: Option<SharedString>,
         cx: &mut ModelContext<Self>,
     ) -> Result<()> {
         if let Some(transformation_transaction_id) = self.transformation_transaction_id.take() {
@@ -2752,8 +2761,12 @@ impl CodegenAlternative {
                 let request = self.build_request(user_prompt, assistant_panel_context, cx)?;
                 self.request = Some(request.clone());
 
-                cx.spawn(|_, cx| async move { model.stream_completion_text(request, &cx).await })
-                    .boxed_local()
+                cx.spawn(|_, cx| async move {
+                    model
+                        .stream_completion_text(request, project_name, &cx)
+                        .await
+                })
+                .boxed_local()
             };
         self.handle_stream(telemetry_id, provider_id.to_string(), api_key, stream, cx);
         Ok(())
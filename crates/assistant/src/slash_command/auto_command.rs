@@ -9,8 +9,9 @@ use gpui::{AppContext, AsyncAppContext, Task, WeakView};
 use language::{CodeLabel, LspAdapterDelegate};
 use language_model::{
     LanguageModelCompletionEvent, LanguageModelRegistry, LanguageModelRequest,
-    LanguageModelRequestMessage, Role,
+    LanguageModelRequestMessage, Role, UsageReporter,
 };
+use language_models::usage_project_name;
 use semantic_index::{FileSummary, SemanticDb};
 use smol::channel;
 use std::sync::{atomic::AtomicBool, Arc};
@@ -109,6 +110,7 @@ impl SlashCommand for AutoCommand {
         let argument = arguments.join(" ");
         let original_prompt = argument.to_string();
         let project = workspace.read(cx).project().clone();
+        let project_name = usage_project_name(project.read(cx), cx);
         let Some(project_index) =
             cx.update_global(|index: &mut SemanticDb, cx| index.project_index(project, cx))
         else {
@@ -120,7 +122,7 @@ impl SlashCommand for AutoCommand {
                 .read_with(&cx, |project_index, cx| project_index.all_summaries(cx))?
                 .await?;
 
-            commands_for_summaries(&summaries, &original_prompt, &cx).await
+            commands_for_summaries(&summaries, &original_prompt, project_name, &cx).await
         });
 
         // As a convenience, append /auto's argument to the end of the prompt
@@ -187,6 +189,7 @@ struct CommandToRun {
 async fn commands_for_summaries(
     summaries: &[FileSummary],
     original_prompt: &str,
+    project_name: Option<SharedString>,
     cx: &AsyncAppContext,
 ) -> Result<Vec<CommandToRun>> {
     if summaries.is_empty() {
@@ -277,9 +280,10 @@ async fn commands_for_summaries(
             let request = make_request(prompt.clone());
             let model = model.clone();
             let tx = tx.clone();
+            let usage_reporter = UsageReporter::new(model.as_ref(), project_name.clone(), cx);
             let stream = model.stream_completion(request, &cx);
 
-            (stream, tx)
+            (stream, usage_reporter, tx)
         })
         .collect::<Vec<_>>();
 
@@ -288,18 +292,22 @@ async fn commands_for_summaries(
             let futures = completion_streams
                 .into_iter()
                 .enumerate()
-                .map(|(ix, (stream, tx))| async move {
+                .map(|(ix, (stream, usage_reporter, tx))| async move {
                     let start = std::time::Instant::now();
                     let events = stream.await?;
                     log::info!("Time taken for awaiting /await chunk stream #{ix}: {:?}", start.elapsed());
 
                     let completion: String = events
-                        .filter_map(|event| async {
-                            if let Ok(LanguageModelCompletionEvent::Text(text)) = event {
-                                Some(text)
-                            } else {
-                                None
-                            }
+                        .filter_map(|event| {
+                            let text = match event {
+                                Ok(LanguageModelCompletionEvent::Text(text)) => Some(text),
+                                Ok(LanguageModelCompletionEvent::UsageUpdate(usage)) => {
+                                    usage_reporter.report(usage);
+                                    None
+                                }
+                                _ => None,
+                            };
+                            async move { text }
                         })
                         .collect()
                         .await;
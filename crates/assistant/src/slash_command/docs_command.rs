@@ -8,10 +8,11 @@ use assistant_slash_command::{
     ArgumentCompletion, SlashCommand, SlashCommandOutput, SlashCommandOutputSection,
     SlashCommandResult,
 };
-use gpui::{AppContext, BackgroundExecutor, Model, Task, WeakView};
+use gpui::{AppContext, BackgroundExecutor, Global, Model, Task, WeakView};
 use indexed_docs::{
-    DocsDotRsProvider, IndexedDocsRegistry, IndexedDocsStore, LocalRustdocProvider, PackageName,
-    ProviderId,
+    DocsDotRsProvider, IndexedDocsProvider, IndexedDocsRegistry, IndexedDocsStore, LocalDocsRoots,
+    LocalHtmlDocsProvider, LocalRustdocProvider, MarkdownDocsProvider, NodeModulesDocsProvider,
+    PackageName, ProviderId, PythonDocsProvider,
 };
 use language::{BufferSnapshot, LspAdapterDelegate};
 use project::{Project, ProjectPath};
@@ -21,6 +22,12 @@ use workspace::Workspace;
 
 pub(crate) struct DocsSlashCommand;
 
+/// The roots of the local docs providers, which are shared by all workspaces.
+#[derive(Default)]
+struct GlobalLocalDocsRoots(LocalDocsRoots);
+
+impl Global for GlobalLocalDocsRoots {}
+
 impl DocsSlashCommand {
     pub const NAME: &'static str = "docs";
 
@@ -93,6 +100,78 @@ impl DocsSlashCommand {
         }
     }
 
+    /// Ensures that the indexed doc providers for local, offline docs are registered, and
+    /// that they look for docs in the worktrees of the given workspace.
+    ///
+    /// The providers are registered once and shared by all workspaces, so their roots are
+    /// updated whenever docs are requested, rather than fixed to the first workspace.
+    fn ensure_local_doc_providers_are_registered(
+        &self,
+        workspace: Option<WeakView<Workspace>>,
+        cx: &mut AppContext,
+    ) {
+        let index_provider_deps = maybe!({
+            let workspace = workspace.ok_or_else(|| anyhow!("no workspace"))?;
+            let workspace = workspace
+                .upgrade()
+                .ok_or_else(|| anyhow!("workspace was dropped"))?;
+            let project = workspace.read(cx).project().read(cx);
+            let fs = project.fs().clone();
+            let roots = project
+                .visible_worktrees(cx)
+                .filter(|worktree| worktree.read(cx).is_local())
+                .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+                .collect::<Vec<_>>();
+
+            anyhow::Ok((fs, roots))
+        });
+
+        let Some((fs, roots)) = index_provider_deps.log_err() else {
+            return;
+        };
+        let local_docs_roots = cx.default_global::<GlobalLocalDocsRoots>().0.clone();
+        local_docs_roots.set(roots);
+
+        let indexed_docs_registry = IndexedDocsRegistry::global(cx);
+        let provider_ids = [
+            LocalHtmlDocsProvider::id(),
+            NodeModulesDocsProvider::id(),
+            PythonDocsProvider::id(),
+            MarkdownDocsProvider::id(),
+        ];
+        if provider_ids.iter().all(|id| {
+            indexed_docs_registry
+                .get_provider_store(id.clone())
+                .is_some()
+        }) {
+            return;
+        }
+
+        let providers: [Box<dyn IndexedDocsProvider + Send + Sync + 'static>; 4] = [
+            Box::new(LocalHtmlDocsProvider::new(
+                fs.clone(),
+                local_docs_roots.clone(),
+            )),
+            Box::new(NodeModulesDocsProvider::new(
+                fs.clone(),
+                local_docs_roots.clone(),
+            )),
+            Box::new(PythonDocsProvider::new(
+                fs.clone(),
+                local_docs_roots.clone(),
+            )),
+            Box::new(MarkdownDocsProvider::new(fs, local_docs_roots)),
+        ];
+        for provider in providers {
+            if indexed_docs_registry
+                .get_provider_store(provider.id())
+                .is_none()
+            {
+                indexed_docs_registry.register_provider(provider);
+            }
+        }
+    }
+
     /// Runs just-in-time indexing for a given package, in case the slash command
     /// is run without any entries existing in the index.
     fn run_just_in_time_indexing(
@@ -167,7 +246,8 @@ impl SlashCommand for DocsSlashCommand {
         workspace: Option<WeakView<Workspace>>,
         cx: &mut WindowContext,
     ) -> Task<Result<Vec<ArgumentCompletion>>> {
-        self.ensure_rust_doc_providers_are_registered(workspace, cx);
+        self.ensure_rust_doc_providers_are_registered(workspace.clone(), cx);
+        self.ensure_local_doc_providers_are_registered(workspace, cx);
 
         let indexed_docs_registry = IndexedDocsRegistry::global(cx);
         let args = DocsSlashCommandArgs::parse(arguments);
@@ -272,7 +352,7 @@ impl SlashCommand for DocsSlashCommand {
         arguments: &[String],
         _context_slash_command_output_sections: &[SlashCommandOutputSection<language::Anchor>],
         _context_buffer: BufferSnapshot,
-        _workspace: WeakView<Workspace>,
+        workspace: WeakView<Workspace>,
         _delegate: Option<Arc<dyn LspAdapterDelegate>>,
         cx: &mut WindowContext,
     ) -> Task<SlashCommandResult> {
@@ -280,6 +360,8 @@ impl SlashCommand for DocsSlashCommand {
             return Task::ready(Err(anyhow!("missing an argument")));
         };
 
+        self.ensure_local_doc_providers_are_registered(Some(workspace), cx);
+
         let args = DocsSlashCommandArgs::parse(arguments);
         let executor = cx.background_executor().clone();
         let task = cx.background_executor().spawn({
@@ -442,6 +524,12 @@ fn package_term(provider: &ProviderId) -> &'static str {
     if provider == &DocsDotRsProvider::id() || provider == &LocalRustdocProvider::id() {
         return "crate";
     }
+    if provider == &MarkdownDocsProvider::id() {
+        return "book";
+    }
+    if provider == &LocalHtmlDocsProvider::id() {
+        return "site";
+    }
 
     "package"
 }
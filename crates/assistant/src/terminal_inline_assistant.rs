@@ -21,7 +21,7 @@ use language_model::{
     LanguageModelRegistry, LanguageModelRequest, LanguageModelRequestMessage, Role,
 };
 use language_model_selector::LanguageModelSelector;
-use language_models::report_assistant_event;
+use language_models::{report_assistant_event, usage_project_name};
 use settings::{update_settings_file, Settings};
 use std::{
     cmp,
@@ -207,11 +207,16 @@ impl TerminalInlineAssistant {
             .log_err();
 
         let codegen = assist.codegen.clone();
+        let project_name = assist
+            .workspace
+            .as_ref()
+            .and_then(|workspace| workspace.upgrade())
+            .and_then(|workspace| usage_project_name(workspace.read(cx).project().read(cx), cx));
         let Some(request) = self.request_for_inline_assist(assist_id, cx).log_err() else {
             return;
         };
 
-        codegen.update(cx, |codegen, cx| codegen.start(request, cx));
+        codegen.update(cx, |codegen, cx| codegen.start(request, project_name, cx));
     }
 
     fn stop_assist(&mut self, assist_id: TerminalInlineAssistId, cx: &mut WindowContext) {
@@ -1079,7 +1084,12 @@ impl Codegen {
         }
     }
 
-    pub fn start(&mut self, prompt: LanguageModelRequest, cx: &mut ModelContext<Self>) {
+    pub fn start(
+        &mut self,
+        prompt: LanguageModelRequest,
+        project_name: Option<SharedString>,
+        cx: &mut ModelContext<Self>,
+    ) {
         let Some(model) = LanguageModelRegistry::read_global(cx).active_model() else {
             return;
         };
@@ -1092,7 +1102,9 @@ impl Codegen {
         self.generation = cx.spawn(|this, mut cx| async move {
             let model_telemetry_id = model.telemetry_id();
             let model_provider_id = model.provider_id();
-            let response = model.stream_completion_text(prompt, &cx).await;
+            let response = model
+                .stream_completion_text(prompt, project_name, &cx)
+                .await;
             let generate = async {
                 let message_id = response
                     .as_ref()
@@ -0,0 +1,320 @@
+use chrono::{Datelike as _, Duration, Local, NaiveDate};
+use gpui::{AppContext, EventEmitter, FocusHandle, FocusableView, Model, Subscription, Task};
+use language_model::{
+    DailyModelUsage, LanguageModelUsage, LanguageModelUsageEvent, ProjectUsage, TokenUsage,
+};
+use language_models::AllLanguageModelSettings;
+use settings::{Settings, SettingsStore};
+use ui::prelude::*;
+use util::ResultExt as _;
+use workspace::item::{Item, ItemEvent};
+use workspace::Workspace;
+use zed_actions::ShowUsage;
+
+/// The number of days shown in the per-day breakdown.
+const HISTORY_DAYS: i64 = 30;
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(
+        |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
+            workspace.register_action(|workspace, _: &ShowUsage, cx| {
+                let Some(usage) = LanguageModelUsage::global(cx) else {
+                    return;
+                };
+
+                let existing = workspace
+                    .active_pane()
+                    .read(cx)
+                    .items()
+                    .find_map(|item| item.downcast::<UsageView>());
+                if let Some(existing) = existing {
+                    workspace.activate_item(&existing, true, true, cx);
+                } else {
+                    let usage_view = cx.new_view(|cx| UsageView::new(usage, cx));
+                    workspace.add_item_to_active_pane(Box::new(usage_view), None, true, cx);
+                }
+            });
+        },
+    )
+    .detach();
+}
+
+/// Shows the tokens used and the estimated cost of language model requests made from this machine.
+pub struct UsageView {
+    focus_handle: FocusHandle,
+    today: NaiveDate,
+    daily_usage: Vec<DailyModelUsage>,
+    project_usage: Vec<ProjectUsage>,
+    _load_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl UsageView {
+    pub fn new(usage: Model<LanguageModelUsage>, cx: &mut ViewContext<Self>) -> Self {
+        let subscriptions = vec![
+            cx.subscribe(&usage, |this, usage, event, cx| match event {
+                LanguageModelUsageEvent::Recorded => this.reload(usage, cx),
+            }),
+            cx.observe_global::<SettingsStore>(|_, cx| cx.notify()),
+        ];
+
+        let mut this = Self {
+            focus_handle: cx.focus_handle(),
+            today: Local::now().date_naive(),
+            daily_usage: Vec::new(),
+            project_usage: Vec::new(),
+            _load_task: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.reload(usage, cx);
+        this
+    }
+
+    fn reload(&mut self, usage: Model<LanguageModelUsage>, cx: &mut ViewContext<Self>) {
+        let today = Local::now().date_naive();
+        let start_of_month = today.with_day(1).unwrap_or(today);
+        let since = (today - Duration::days(HISTORY_DAYS - 1)).min(start_of_month);
+        let daily_usage = usage.read(cx).daily_model_usage(since, cx);
+        let project_usage = usage.read(cx).project_usage(start_of_month, cx);
+
+        self._load_task = cx.spawn(|this, mut cx| async move {
+            let daily_usage = daily_usage.await.log_err().unwrap_or_default();
+            let project_usage = project_usage.await.log_err().unwrap_or_default();
+            this.update(&mut cx, |this, cx| {
+                this.today = today;
+                this.daily_usage = daily_usage;
+                this.project_usage = project_usage;
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn cost_since(&self, since: NaiveDate) -> f64 {
+        self.daily_usage
+            .iter()
+            .filter(|usage| usage.day >= since)
+            .map(|usage| usage.cost_usd)
+            .sum()
+    }
+
+    fn render_spending(
+        &self,
+        label: &'static str,
+        spent_usd: f64,
+        budget_usd: Option<f64>,
+        cx: &ViewContext<Self>,
+    ) -> impl IntoElement {
+        let over_budget = budget_usd.map_or(false, |budget_usd| spent_usd >= budget_usd);
+        let budget = match budget_usd {
+            Some(budget_usd) => format!("of {} budget", format_cost(budget_usd)),
+            None => "no budget set".to_string(),
+        };
+
+        v_flex()
+            .flex_1()
+            .p_3()
+            .gap_1()
+            .rounded_md()
+            .border_1()
+            .border_color(cx.theme().colors().border)
+            .child(Label::new(label).size(LabelSize::Small).color(Color::Muted))
+            .child(
+                Label::new(format_cost(spent_usd))
+                    .size(LabelSize::Large)
+                    .color(if over_budget {
+                        Color::Error
+                    } else {
+                        Color::Default
+                    }),
+            )
+            .child(
+                Label::new(budget)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+    }
+
+    fn render_section(
+        &self,
+        title: &'static str,
+        headers: [&'static str; 5],
+        rows: Vec<[SharedString; 5]>,
+    ) -> impl IntoElement {
+        let body = if rows.is_empty() {
+            Label::new("No usage recorded yet.")
+                .size(LabelSize::Small)
+                .color(Color::Muted)
+                .into_any_element()
+        } else {
+            v_flex()
+                .children(rows.into_iter().map(|row| render_row(row, Color::Default)))
+                .into_any_element()
+        };
+
+        v_flex()
+            .gap_1()
+            .child(Headline::new(title).size(HeadlineSize::XSmall))
+            .child(render_row(headers.map(SharedString::from), Color::Muted))
+            .child(body)
+    }
+}
+
+fn render_row(cells: [SharedString; 5], color: Color) -> impl IntoElement {
+    h_flex()
+        .gap_2()
+        .py_0p5()
+        .children(cells.into_iter().enumerate().map(move |(ix, cell)| {
+            div()
+                .when(ix < 2, |cell| cell.w(rems(12.)))
+                .when(ix >= 2, |cell| cell.w(rems(7.)))
+                .child(Label::new(cell).size(LabelSize::Small).color(color))
+        }))
+}
+
+fn format_cost(cost_usd: f64) -> String {
+    format!("${cost_usd:.2}")
+}
+
+fn format_tokens(usage: TokenUsage) -> SharedString {
+    let tokens = usage.total_tokens();
+    if tokens >= 1_000_000 {
+        format!("{:.1}M", tokens as f64 / 1_000_000.).into()
+    } else if tokens >= 1_000 {
+        format!("{:.1}k", tokens as f64 / 1_000.).into()
+    } else {
+        tokens.to_string().into()
+    }
+}
+
+impl EventEmitter<ItemEvent> for UsageView {}
+
+impl FocusableView for UsageView {
+    fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for UsageView {
+    type Event = ItemEvent;
+
+    fn tab_icon(&self, _cx: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::ZedAssistant))
+    }
+
+    fn tab_content_text(&self, _cx: &WindowContext) -> Option<SharedString> {
+        Some("Language Model Usage".into())
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
+
+impl Render for UsageView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let budget = AllLanguageModelSettings::get_global(cx).usage_budget;
+        let start_of_month = self.today.with_day(1).unwrap_or(self.today);
+        let history_start = self.today - Duration::days(HISTORY_DAYS - 1);
+
+        let daily_rows = self
+            .daily_usage
+            .iter()
+            .filter(|usage| usage.day >= history_start)
+            .map(|usage| {
+                [
+                    usage.day.format("%b %-d, %Y").to_string().into(),
+                    usage.model_name.clone().into(),
+                    usage.request_count.to_string().into(),
+                    format_tokens(usage.usage),
+                    format_cost(usage.cost_usd).into(),
+                ]
+            })
+            .collect();
+        let project_rows = self
+            .project_usage
+            .iter()
+            .map(|usage| {
+                [
+                    usage
+                        .project
+                        .clone()
+                        .unwrap_or_else(|| "No project".to_string())
+                        .into(),
+                    "".into(),
+                    usage.request_count.to_string().into(),
+                    format_tokens(usage.usage),
+                    format_cost(usage.cost_usd).into(),
+                ]
+            })
+            .collect();
+
+        v_flex()
+            .id("language-model-usage")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .p_4()
+            .gap_4()
+            .overflow_y_scroll()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                v_flex()
+                    .gap_1()
+                    .child(Headline::new("Language Model Usage"))
+                    .child(
+                        Label::new(
+                            "Costs are estimated locally from each model's list price and may differ from what you are billed.",
+                        )
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .gap_4()
+                    .child(self.render_spending(
+                        "Today",
+                        self.cost_since(self.today),
+                        budget.daily_usd,
+                        cx,
+                    ))
+                    .child(self.render_spending(
+                        "This Month",
+                        self.cost_since(start_of_month),
+                        budget.monthly_usd,
+                        cx,
+                    )),
+            )
+            .child(self.render_section(
+                "By Day and Model",
+                ["Day", "Model", "Requests", "Tokens", "Cost"],
+                daily_rows,
+            ))
+            .child(self.render_section(
+                "By Project This Month",
+                ["Project", "", "Requests", "Tokens", "Cost"],
+                project_rows,
+            ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_tokens() {
+        let usage = |input_tokens, output_tokens| TokenUsage {
+            input_tokens,
+            output_tokens,
+            ..Default::default()
+        };
+        assert_eq!(format_tokens(usage(400, 20)), "420");
+        assert_eq!(format_tokens(usage(12_000, 345)), "12.3k");
+        assert_eq!(format_tokens(usage(2_500_000, 0)), "2.5M");
+    }
+}
@@ -1,6 +1,8 @@
+use std::sync::Arc;
+
 use editor::{Editor, EditorElement, EditorStyle};
 use gpui::{AppContext, FocusableView, Model, TextStyle, View};
-use language_model::{LanguageModelRegistry, LanguageModelRequestTool};
+use language_model::{LanguageModel, LanguageModelRegistry, LanguageModelRequestTool};
 use picker::Picker;
 use settings::Settings;
 use theme::ThemeSettings;
@@ -56,6 +58,26 @@ impl MessageEditor {
         let model_registry = LanguageModelRegistry::read_global(cx);
         let model = model_registry.active_model()?;
 
+        let confirm_budget = language_models::confirm_usage_budget(cx);
+        cx.spawn(|this, mut cx| async move {
+            if confirm_budget.await? {
+                this.update(&mut cx, |this, cx| {
+                    this.start_completion(request_kind, model, cx)
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+
+        None
+    }
+
+    fn start_completion(
+        &mut self,
+        request_kind: RequestKind,
+        model: Arc<dyn LanguageModel>,
+        cx: &mut ViewContext<Self>,
+    ) {
         let user_message = self.editor.update(cx, |editor, cx| {
             let text = editor.text(cx);
             editor.clear(cx);
@@ -81,8 +103,6 @@ impl MessageEditor {
 
             thread.stream_completion(request, model, cx)
         });
-
-        None
     }
 }
 
@@ -5,13 +5,17 @@ use assistant_tool::ToolWorkingSet;
 use collections::HashMap;
 use futures::future::Shared;
 use futures::{FutureExt as _, StreamExt as _};
-use gpui::{AppContext, EventEmitter, ModelContext, SharedString, Task};
+use gpui::{AppContext, EventEmitter, Model, ModelContext, SharedString, Task};
 use language_model::{
     LanguageModel, LanguageModelCompletionEvent, LanguageModelRequest, LanguageModelRequestMessage,
     LanguageModelToolResult, LanguageModelToolUse, LanguageModelToolUseId, MessageContent, Role,
-    StopReason,
+    StopReason, UsageReporter,
 };
-use language_models::provider::cloud::{MaxMonthlySpendReachedError, PaymentRequiredError};
+use language_models::{
+    provider::cloud::{MaxMonthlySpendReachedError, PaymentRequiredError},
+    usage_project_name,
+};
+use project::Project;
 use serde::{Deserialize, Serialize};
 use util::post_inc;
 use uuid::Uuid;
@@ -60,6 +64,7 @@ pub struct Thread {
     next_message_id: MessageId,
     completion_count: usize,
     pending_completions: Vec<PendingCompletion>,
+    project: Model<Project>,
     tools: Arc<ToolWorkingSet>,
     tool_uses_by_message: HashMap<MessageId, Vec<LanguageModelToolUse>>,
     tool_results_by_message: HashMap<MessageId, Vec<LanguageModelToolResult>>,
@@ -67,13 +72,18 @@ pub struct Thread {
 }
 
 impl Thread {
-    pub fn new(tools: Arc<ToolWorkingSet>, _cx: &mut ModelContext<Self>) -> Self {
+    pub fn new(
+        project: Model<Project>,
+        tools: Arc<ToolWorkingSet>,
+        _cx: &mut ModelContext<Self>,
+    ) -> Self {
         Self {
             id: ThreadId::new(),
             messages: Vec::new(),
             next_message_id: MessageId(0),
             completion_count: 0,
             pending_completions: Vec::new(),
+            project,
             tools,
             tool_uses_by_message: HashMap::default(),
             tool_results_by_message: HashMap::default(),
@@ -178,8 +188,10 @@ impl Thread {
         cx: &mut ModelContext<Self>,
     ) {
         let pending_completion_id = post_inc(&mut self.completion_count);
+        let project_name = usage_project_name(self.project.read(cx), cx);
 
         let task = cx.spawn(|thread, mut cx| async move {
+            let usage_reporter = UsageReporter::new(model.as_ref(), project_name, &cx);
             let stream = model.stream_completion(request, &cx);
             let stream_completion = async {
                 let mut events = stream.await?;
@@ -202,6 +214,9 @@ impl Thread {
                             LanguageModelCompletionEvent::Stop(reason) => {
                                 stop_reason = reason;
                             }
+                            LanguageModelCompletionEvent::UsageUpdate(usage) => {
+                                usage_reporter.report(usage);
+                            }
                             LanguageModelCompletionEvent::Text(chunk) => {
                                 if let Some(last_message) = thread.messages.last_mut() {
                                     if last_message.role == Role::Assistant {
@@ -62,7 +62,7 @@ impl ThreadStore {
     }
 
     pub fn create_thread(&mut self, cx: &mut ModelContext<Self>) -> Model<Thread> {
-        let thread = cx.new_model(|cx| Thread::new(self.tools.clone(), cx));
+        let thread = cx.new_model(|cx| Thread::new(self.project.clone(), self.tools.clone(), cx));
         self.threads.push(thread.clone());
         thread
     }
@@ -147,7 +147,7 @@ impl ThreadStore {
         use language_model::Role;
 
         self.threads.push(cx.new_model(|cx| {
-            let mut thread = Thread::new(self.tools.clone(), cx);
+            let mut thread = Thread::new(self.project.clone(), self.tools.clone(), cx);
             thread.insert_user_message("Hello! Can you help me understand quantum computing?", cx);
             thread.insert_message(Role::Assistant, "Of course! I'd be happy to help you understand quantum computing. Quantum computing is a fascinating field that uses the principles of quantum mechanics to process information. Unlike classical computers that use bits (0s and 1s), quantum computers use quantum bits or 'qubits'. These qubits can exist in multiple states simultaneously, a property called superposition. This allows quantum computers to perform certain calculations much faster than classical computers. What specific aspect of quantum computing would you like to know more about?", cx);
             thread.insert_user_message("That's interesting! Can you explain how quantum entanglement is used in quantum computing?", cx);
@@ -156,7 +156,7 @@ impl ThreadStore {
         }));
 
         self.threads.push(cx.new_model(|cx| {
-            let mut thread = Thread::new(self.tools.clone(), cx);
+            let mut thread = Thread::new(self.project.clone(), self.tools.clone(), cx);
             thread.insert_user_message("Can you show me an example of Rust code for a simple web server?", cx);
             thread.insert_message(Role::Assistant, "Certainly! Here's an example of a simple web server in Rust using the `actix-web` framework:
 
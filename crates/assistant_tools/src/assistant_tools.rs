@@ -1,13 +1,19 @@
 mod now_tool;
+mod propose_edits_tool;
+mod search_project_tool;
 
 use assistant_tool::ToolRegistry;
 use gpui::AppContext;
 
 use crate::now_tool::NowTool;
+use crate::propose_edits_tool::ProposeEditsTool;
+use crate::search_project_tool::SearchProjectTool;
 
 pub fn init(cx: &mut AppContext) {
     assistant_tool::init(cx);
 
     let registry = ToolRegistry::global(cx);
     registry.register_tool(NowTool);
+    registry.register_tool(ProposeEditsTool);
+    registry.register_tool(SearchProjectTool);
 }
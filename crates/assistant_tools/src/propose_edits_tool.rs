@@ -0,0 +1,240 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context as _, Result};
+use assistant_tool::Tool;
+use editor::{ProposedChangeLocation, ProposedChangesEditor};
+use gpui::{Model, Task, WeakView, WindowContext};
+use language::{Buffer, BufferSnapshot, Point, ToPoint as _};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// The number of unchanged lines shown around each proposed edit.
+const CONTEXT_LINES: u32 = 3;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ProposeEditsToolInput {
+    /// A short title describing the changes as a whole.
+    title: String,
+    /// The edits to propose. Edits to the same file must not overlap.
+    edits: Vec<ProposedEdit>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ProposedEdit {
+    /// The path of the file to edit, relative to the project root.
+    path: String,
+    /// The exact text to replace, which must occur exactly once in the file.
+    /// When empty, the new text is appended to the end of the file.
+    old_text: String,
+    /// The text to replace it with.
+    new_text: String,
+}
+
+pub struct ProposeEditsTool;
+
+impl Tool for ProposeEditsTool {
+    fn name(&self) -> String {
+        "propose_edits".into()
+    }
+
+    fn description(&self) -> String {
+        "Proposes edits to one or more files in the project. The edits are not written to disk; instead they are staged in a review tab where the user can accept or reject each change. Use this tool whenever you want to change code in the project.".into()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        let schema = schemars::schema_for!(ProposeEditsToolInput);
+        serde_json::to_value(&schema).unwrap()
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        workspace: WeakView<workspace::Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<String>> {
+        let input: ProposeEditsToolInput = match serde_json::from_value(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))),
+        };
+        let Some(workspace) = workspace.upgrade() else {
+            return Task::ready(Err(anyhow!("workspace was dropped")));
+        };
+        if input.edits.is_empty() {
+            return Task::ready(Err(anyhow!("no edits were provided")));
+        }
+
+        let project = workspace.read(cx).project().clone();
+        let mut edits_by_path = BTreeMap::<String, Vec<ProposedEdit>>::new();
+        for edit in input.edits {
+            edits_by_path
+                .entry(edit.path.clone())
+                .or_default()
+                .push(edit);
+        }
+
+        let mut open_buffer_tasks = Vec::new();
+        for (path, edits) in edits_by_path {
+            let open_buffer_task = project.update(cx, |project, cx| {
+                let project_path = project.find_project_path(Path::new(&path), cx)?;
+                Some(project.open_buffer(project_path, cx))
+            });
+            let Some(open_buffer_task) = open_buffer_task else {
+                return Task::ready(Err(anyhow!("{path} was not found in the project")));
+            };
+            open_buffer_tasks.push((path, edits, open_buffer_task));
+        }
+
+        cx.spawn(|mut cx| async move {
+            let mut resolved_edits = Vec::<(Model<Buffer>, Vec<(Range<usize>, String)>)>::new();
+            let mut locations = Vec::new();
+            for (path, edits, open_buffer_task) in open_buffer_tasks {
+                let buffer = open_buffer_task
+                    .await
+                    .with_context(|| format!("failed to open {path}"))?;
+                let snapshot = buffer.read_with(&cx, |buffer, _| buffer.snapshot())?;
+                let edits = resolve_edits(&path, &snapshot.text(), edits)?;
+                locations.push(ProposedChangeLocation {
+                    buffer: buffer.clone(),
+                    ranges: context_ranges(&snapshot, &edits),
+                });
+                resolved_edits.push((buffer, edits));
+            }
+
+            let file_count = resolved_edits.len();
+            let edit_count = resolved_edits
+                .iter()
+                .map(|(_, edits)| edits.len())
+                .sum::<usize>();
+            let editor = cx.new_view(|cx| {
+                let editor =
+                    ProposedChangesEditor::new(input.title, locations, Some(project.clone()), cx);
+                for (buffer, edits) in resolved_edits {
+                    if let Some(branch) = editor.branch_buffer_for_base(&buffer) {
+                        branch.update(cx, |branch, cx| {
+                            branch.edit(edits, None, cx);
+                        });
+                    }
+                }
+                editor.recalculate_all_buffer_diffs();
+                editor
+            })?;
+
+            workspace.update(&mut cx, |workspace, cx| {
+                workspace.add_item_to_active_pane(Box::new(editor), None, false, cx);
+            })?;
+
+            Ok(format!(
+                "Proposed {edit_count} edit(s) across {file_count} file(s). The user can now review them and decide which ones to apply; they have not been written to disk yet."
+            ))
+        })
+    }
+}
+
+/// Converts the edits to a single file into offset ranges within its text,
+/// sorted by position.
+fn resolve_edits(
+    path: &str,
+    text: &str,
+    edits: Vec<ProposedEdit>,
+) -> Result<Vec<(Range<usize>, String)>> {
+    let mut resolved_edits = Vec::with_capacity(edits.len());
+    for edit in edits {
+        let range = if edit.old_text.is_empty() {
+            text.len()..text.len()
+        } else {
+            let mut matches = text.match_indices(&edit.old_text);
+            let (start, _) = matches
+                .next()
+                .ok_or_else(|| anyhow!("the text to replace was not found in {path}"))?;
+            if matches.next().is_some() {
+                return Err(anyhow!(
+                    "the text to replace occurs more than once in {path}; include more surrounding lines"
+                ));
+            }
+            start..start + edit.old_text.len()
+        };
+        resolved_edits.push((range, edit.new_text));
+    }
+
+    resolved_edits.sort_by_key(|(range, _)| (range.start, range.end));
+    for pair in resolved_edits.windows(2) {
+        if pair[0].0.end > pair[1].0.start {
+            return Err(anyhow!("two of the edits to {path} overlap"));
+        }
+    }
+    Ok(resolved_edits)
+}
+
+/// Returns the excerpt ranges to show for the given edits, including a few
+/// lines of context and with overlapping ranges merged.
+fn context_ranges(
+    snapshot: &BufferSnapshot,
+    edits: &[(Range<usize>, String)],
+) -> Vec<Range<Point>> {
+    let max_row = snapshot.max_point().row;
+    let mut ranges: Vec<Range<Point>> = Vec::new();
+    for (range, _) in edits {
+        let start_row = range
+            .start
+            .to_point(snapshot)
+            .row
+            .saturating_sub(CONTEXT_LINES);
+        let end_row = (range.end.to_point(snapshot).row + CONTEXT_LINES).min(max_row);
+        let range = Point::new(start_row, 0)..Point::new(end_row, snapshot.line_len(end_row));
+
+        match ranges.last_mut() {
+            Some(last) if last.end.row + 1 >= range.start.row => last.end = range.end,
+            _ => ranges.push(range),
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(old_text: &str, new_text: &str) -> ProposedEdit {
+        ProposedEdit {
+            path: "src/main.rs".into(),
+            old_text: old_text.into(),
+            new_text: new_text.into(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_edits() {
+        let text = "fn main() {\n    println!(\"hi\");\n}\n";
+        assert_eq!(
+            resolve_edits(
+                "src/main.rs",
+                text,
+                vec![
+                    edit("\"hi\"", "\"hello\""),
+                    edit("fn main()", "pub fn main()")
+                ]
+            )
+            .unwrap(),
+            vec![
+                (0..9, "pub fn main()".to_string()),
+                (25..29, "\"hello\"".to_string()),
+            ]
+        );
+        assert_eq!(
+            resolve_edits("src/main.rs", text, vec![edit("", "// end\n")]).unwrap(),
+            vec![(text.len()..text.len(), "// end\n".to_string())]
+        );
+
+        assert!(resolve_edits("src/main.rs", text, vec![edit("missing", "")]).is_err());
+        assert!(resolve_edits("src/main.rs", text, vec![edit("i", "")]).is_err());
+        assert!(resolve_edits(
+            "src/main.rs",
+            text,
+            vec![edit("fn main", ""), edit("main()", "")]
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,97 @@
+use std::fmt::Write as _;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use assistant_tool::Tool;
+use gpui::{Task, WeakView, WindowContext};
+use schemars::JsonSchema;
+use semantic_index::{ExactMatchReranker, HybridSearchOptions, SemanticDb};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SearchProjectToolInput {
+    /// The text to search for. Can be an identifier, a phrase or a description of the code.
+    query: String,
+    /// The maximum number of excerpts to return.
+    limit: Option<usize>,
+}
+
+pub struct SearchProjectTool;
+
+impl Tool for SearchProjectTool {
+    fn name(&self) -> String {
+        "search_project".into()
+    }
+
+    fn description(&self) -> String {
+        "Searches the current project for code excerpts matching a query, combining keyword and semantic matching. Use this tool to find where something is defined or used before answering questions about the codebase.".into()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        let schema = schemars::schema_for!(SearchProjectToolInput);
+        serde_json::to_value(&schema).unwrap()
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        workspace: WeakView<workspace::Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<String>> {
+        let input: SearchProjectToolInput = match serde_json::from_value(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))),
+        };
+        let Some(workspace) = workspace.upgrade() else {
+            return Task::ready(Err(anyhow!("workspace was dropped")));
+        };
+        if !cx.has_global::<SemanticDb>() {
+            return Task::ready(Err(anyhow!("project search is not available")));
+        }
+
+        let project = workspace.read(cx).project().clone();
+        let fs = project.read(cx).fs().clone();
+        let Some(project_index) =
+            cx.update_global(|index: &mut SemanticDb, cx| index.project_index(project, cx))
+        else {
+            return Task::ready(Err(anyhow!("no project indexer")));
+        };
+
+        cx.spawn(|cx| async move {
+            let results = project_index
+                .read_with(&cx, |project_index, cx| {
+                    project_index.hybrid_search(
+                        input.query.clone(),
+                        HybridSearchOptions {
+                            limit: input.limit.unwrap_or(10),
+                            reranker: Some(Arc::new(ExactMatchReranker)),
+                            ..Default::default()
+                        },
+                        cx,
+                    )
+                })?
+                .await?;
+            let loaded_results = SemanticDb::load_results(results, &fs, &cx).await?;
+            if loaded_results.is_empty() {
+                return Ok(format!("No results found for {:?}.", input.query));
+            }
+
+            let mut text = String::new();
+            for result in loaded_results {
+                writeln!(
+                    text,
+                    "```{}:{}-{}",
+                    result.full_path.display(),
+                    result.row_range.start() + 1,
+                    result.row_range.end() + 1
+                )?;
+                text.push_str(&result.excerpt_content);
+                if !text.ends_with('\n') {
+                    text.push('\n');
+                }
+                writeln!(text, "```\n")?;
+            }
+            Ok(text)
+        })
+    }
+}
@@ -7,7 +7,7 @@ use audio::Audio;
 use call_settings::CallSettings;
 use client::{proto, ChannelId, Client, TypedEnvelope, User, UserStore, ZED_ALWAYS_ACTIVE};
 use collections::HashSet;
-use futures::{channel::oneshot, future::Shared, Future, FutureExt};
+use futures::{channel::oneshot, future::Shared, Future, FutureExt, StreamExt};
 use gpui::{
     AppContext, AsyncAppContext, Context, EventEmitter, Global, Model, ModelContext, Subscription,
     Task, WeakModel,
@@ -76,6 +76,11 @@ pub struct ActiveCall {
     room: Option<(Model<Room>, Vec<Subscription>)>,
     pending_room_creation: Option<Shared<Task<Result<Model<Room>, Arc<anyhow::Error>>>>>,
     location: Option<WeakModel<Project>>,
+    /// Projects that we kept editing after losing our connection to a call, which are
+    /// reconnected to once we're in a call where they're shared again.
+    offline_projects: HashSet<WeakModel<Project>>,
+    /// The channel whose call we lost our connection to, which is rejoined when we reconnect.
+    offline_channel_id: Option<ChannelId>,
     _join_debouncer: OneAtATime,
     pending_invites: HashSet<u64>,
     incoming_call: (
@@ -85,16 +90,29 @@ pub struct ActiveCall {
     client: Arc<Client>,
     user_store: Model<UserStore>,
     _subscriptions: Vec<client::Subscription>,
+    _maintain_offline_projects: Task<Result<()>>,
 }
 
 impl EventEmitter<Event> for ActiveCall {}
 
 impl ActiveCall {
     fn new(client: Arc<Client>, user_store: Model<UserStore>, cx: &mut ModelContext<Self>) -> Self {
+        let mut client_status = client.status();
+        let maintain_offline_projects = cx.spawn(|this, mut cx| async move {
+            while let Some(status) = client_status.next().await {
+                if status.is_connected() {
+                    this.update(&mut cx, |this, cx| this.rejoin_offline_channel(cx))?;
+                }
+            }
+            anyhow::Ok(())
+        });
+
         Self {
             room: None,
             pending_room_creation: None,
             location: None,
+            offline_projects: Default::default(),
+            offline_channel_id: None,
             pending_invites: Default::default(),
             incoming_call: watch::channel(),
             _join_debouncer: OneAtATime { cancel: None },
@@ -104,6 +122,7 @@ impl ActiveCall {
             ],
             client,
             user_store,
+            _maintain_offline_projects: maintain_offline_projects,
         }
     }
 
@@ -439,10 +458,17 @@ impl ActiveCall {
                     self.room = None;
                     Task::ready(Ok(()))
                 } else {
+                    let channel_id = room.read(cx).channel_id();
+                    let offline_channel_id = self.offline_channel_id.take();
+                    let rejoined_offline_channel =
+                        channel_id.is_some() && channel_id == offline_channel_id;
                     let subscriptions = vec![
                         cx.observe(&room, |this, room, cx| {
                             if room.read(cx).status().is_offline() {
                                 this.set_room(None, cx).detach_and_log_err(cx);
+                                this.room_went_offline(&room, cx);
+                            } else {
+                                this.reconnect_offline_projects(&room, false, cx);
                             }
 
                             cx.notify();
@@ -454,7 +480,7 @@ impl ActiveCall {
                         .location
                         .as_ref()
                         .and_then(|location| location.upgrade());
-                    let channel_id = room.read(cx).channel_id();
+                    self.reconnect_offline_projects(&room, rejoined_offline_channel, cx);
                     cx.emit(Event::RoomJoined { channel_id });
                     room.update(cx, |room, cx| room.set_location(location.as_ref(), cx))
                 }
@@ -465,6 +491,80 @@ impl ActiveCall {
         }
     }
 
+    fn room_went_offline(&mut self, room: &Model<Room>, cx: &mut ModelContext<Self>) {
+        let offline_projects = room.update(cx, |room, _| room.take_offline_projects());
+        if offline_projects.is_empty() {
+            return;
+        }
+
+        self.offline_projects.extend(offline_projects);
+        self.offline_channel_id = room.read(cx).channel_id();
+        self.rejoin_offline_channel(cx);
+    }
+
+    fn rejoin_offline_channel(&mut self, cx: &mut ModelContext<Self>) {
+        if self.room.is_some()
+            || self.pending_room_creation.is_some()
+            || !self.client.status().borrow().is_connected()
+        {
+            return;
+        }
+
+        if let Some(channel_id) = self.offline_channel_id {
+            log::info!("rejoining channel {channel_id} to reconnect offline projects");
+            self.join_channel(channel_id, cx).detach_and_log_err(cx);
+        }
+    }
+
+    /// Reconnects the offline projects that are shared in the given room. When rejoining the
+    /// channel we lost our connection to, all of them are reconnected, so that any that were
+    /// closed in the meantime are reported.
+    fn reconnect_offline_projects(
+        &mut self,
+        room: &Model<Room>,
+        rejoined_offline_channel: bool,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if self.offline_projects.is_empty() {
+            return;
+        }
+
+        let mut projects_to_reconnect = Vec::new();
+        self.offline_projects.retain(|project| {
+            let Some(project) = project.upgrade() else {
+                return false;
+            };
+            let Some(project_id) = project.read(cx).remote_id() else {
+                return false;
+            };
+            if !project.read(cx).is_offline() {
+                return false;
+            }
+
+            let is_shared = room
+                .read(cx)
+                .remote_participants()
+                .values()
+                .any(|participant| {
+                    participant
+                        .projects
+                        .iter()
+                        .any(|project| project.id == project_id)
+                });
+            if is_shared || rejoined_offline_channel {
+                projects_to_reconnect.push(project);
+                false
+            } else {
+                true
+            }
+        });
+
+        for project in projects_to_reconnect {
+            room.update(cx, |room, cx| room.reconnect_project(project, cx))
+                .detach_and_log_err(cx);
+        }
+    }
+
     pub fn room(&self) -> Option<&Model<Room>> {
         self.room.as_ref().map(|(room, _)| room)
     }
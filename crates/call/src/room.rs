@@ -64,6 +64,8 @@ pub struct Room {
     status: RoomStatus,
     shared_projects: HashSet<WeakModel<Project>>,
     joined_projects: HashSet<WeakModel<Project>>,
+    /// Joined projects that were kept open for editing after the connection was lost.
+    offline_projects: HashSet<WeakModel<Project>>,
     local_participant: LocalParticipant,
     remote_participants: BTreeMap<u64, RemoteParticipant>,
     pending_participants: Vec<Arc<User>>,
@@ -200,6 +202,7 @@ impl Room {
             status: RoomStatus::Online,
             shared_projects: Default::default(),
             joined_projects: Default::default(),
+            offline_projects: Default::default(),
             participant_user_ids: Default::default(),
             local_participant: Default::default(),
             remote_participants: Default::default(),
@@ -493,13 +496,54 @@ impl Room {
         // we leave the room and return an error.
         if let Some(this) = this.upgrade() {
             log::info!("reconnection failed, leaving room");
-            this.update(&mut cx, |this, cx| this.leave(cx))?.await?;
+            this.update(&mut cx, |this, cx| {
+                this.take_joined_projects_offline(cx);
+                this.leave(cx)
+            })?
+            .await?;
         }
         Err(anyhow!(
             "can't reconnect to room: client failed to re-establish connection"
         ))
     }
 
+    /// Keeps the projects we can edit open after losing our connection, instead of closing
+    /// them, so that they can be reconnected to once we're back in a call.
+    fn take_joined_projects_offline(&mut self, cx: &mut ModelContext<Self>) {
+        self.joined_projects.retain(|project| {
+            let Some(project) = project.upgrade() else {
+                return false;
+            };
+            project.update(cx, |project, cx| project.went_offline(cx));
+            if project.read(cx).is_offline() {
+                self.offline_projects.insert(project.downgrade());
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    pub(crate) fn take_offline_projects(&mut self) -> HashSet<WeakModel<Project>> {
+        mem::take(&mut self.offline_projects)
+    }
+
+    /// Reconnects to a project that we kept editing after losing our connection, sending the
+    /// host the edits we made in the meantime.
+    pub(crate) fn reconnect_project(
+        &mut self,
+        project: Model<Project>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let reconnect = project.update(cx, |project, cx| project.reconnect_to_host(cx));
+        cx.spawn(|this, mut cx| async move {
+            reconnect.await?;
+            this.update(&mut cx, |this, _| {
+                this.joined_projects.insert(project.downgrade());
+            })
+        })
+    }
+
     fn rejoin(&mut self, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
         let mut projects = HashMap::default();
         let mut reshared_projects = Vec::new();
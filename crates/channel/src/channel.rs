@@ -1,6 +1,7 @@
 mod channel_buffer;
 mod channel_chat;
 mod channel_store;
+mod review_threads;
 
 use client::{Client, UserStore};
 use gpui::{AppContext, Model};
@@ -12,6 +13,9 @@ pub use channel_chat::{
     MessageParams,
 };
 pub use channel_store::{Channel, ChannelEvent, ChannelMembership, ChannelStore};
+pub use review_threads::{
+    ReviewComment, ReviewThread, ReviewThreadAnchorRange, ReviewThreads, ReviewThreadsEvent,
+};
 
 #[cfg(test)]
 mod channel_store_tests;
@@ -20,4 +24,5 @@ pub fn init(client: &Arc<Client>, user_store: Model<UserStore>, cx: &mut AppCont
     channel_store::init(client, user_store, cx);
     channel_buffer::init(&client.clone().into());
     channel_chat::init(&client.clone().into());
+    review_threads::init(&client.clone().into());
 }
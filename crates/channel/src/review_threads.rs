@@ -0,0 +1,450 @@
+use crate::Channel;
+use anyhow::{anyhow, Result};
+use client::{
+    proto,
+    user::{User, UserStore},
+    ChannelId, Client, Subscription, TypedEnvelope,
+};
+use collections::{BTreeMap, HashSet};
+use gpui::{AsyncAppContext, Context, EventEmitter, Model, ModelContext, Task, WeakModel};
+use language::proto::{deserialize_anchor_range, serialize_anchor_range};
+use rpc::AnyProtoClient;
+use std::{ops::Range, sync::Arc};
+use text::{Anchor, BufferSnapshot, OffsetRangeExt};
+use time::OffsetDateTime;
+
+/// The code review threads that have been started in a channel.
+pub struct ReviewThreads {
+    pub channel_id: ChannelId,
+    threads: BTreeMap<u64, ReviewThread>,
+    user_store: Model<UserStore>,
+    client: Arc<Client>,
+    _subscription: Subscription,
+}
+
+/// A discussion about a range of a file.
+#[derive(Clone, Debug)]
+pub struct ReviewThread {
+    pub id: u64,
+    /// The path of the file, starting with the name of its worktree.
+    pub path: Arc<str>,
+    /// The range that was commented on, as it was when the thread was started.
+    pub range: Range<usize>,
+    /// The text that was in `range` when the thread was started.
+    pub excerpt: Arc<str>,
+    /// The range in the buffer that the thread was started in, if it was started in a shared
+    /// project that's still shared.
+    pub anchor_range: Option<ReviewThreadAnchorRange>,
+    pub resolved: bool,
+    pub comments: Vec<ReviewComment>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReviewThreadAnchorRange {
+    /// The remote id of the project that the anchors' buffer belongs to.
+    pub project_id: u64,
+    pub range: Range<Anchor>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ReviewComment {
+    pub id: u64,
+    pub author: Arc<User>,
+    pub body: String,
+    pub timestamp: OffsetDateTime,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReviewThreadsEvent {
+    ThreadUpdated { thread_id: u64 },
+    ThreadsReloaded,
+}
+
+impl EventEmitter<ReviewThreadsEvent> for ReviewThreads {}
+
+pub fn init(client: &AnyProtoClient) {
+    client.add_model_message_handler(ReviewThreads::handle_thread_updated);
+}
+
+impl ReviewThreads {
+    pub async fn new(
+        channel: Arc<Channel>,
+        user_store: Model<UserStore>,
+        client: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Model<Self>> {
+        let channel_id = channel.id;
+        let subscription = client.subscribe_to_entity(channel_id.0)?;
+        let response = client
+            .request(proto::GetReviewThreads {
+                channel_id: channel_id.0,
+            })
+            .await?;
+        let threads = ReviewThread::from_proto_vec(response.threads, &user_store, &mut cx).await?;
+
+        cx.new_model(|cx| Self {
+            channel_id,
+            threads: threads
+                .into_iter()
+                .map(|thread| (thread.id, thread))
+                .collect(),
+            user_store,
+            client,
+            _subscription: subscription.set_model(&cx.handle(), &mut cx.to_async()),
+        })
+    }
+
+    pub fn thread(&self, thread_id: u64) -> Option<&ReviewThread> {
+        self.threads.get(&thread_id)
+    }
+
+    /// Returns the threads that were started on the file at the given path.
+    pub fn threads_for_path<'a>(
+        &'a self,
+        path: &'a str,
+    ) -> impl 'a + Iterator<Item = &'a ReviewThread> {
+        self.threads
+            .values()
+            .filter(move |thread| thread.path.as_ref() == path)
+    }
+
+    /// Starts a thread on a range of the given buffer, with `body` as its first comment.
+    ///
+    /// If the buffer belongs to a shared project, pass the project's remote id so that the thread
+    /// stays attached to the range while it's edited.
+    pub fn create_thread(
+        &mut self,
+        path: Arc<str>,
+        project_id: Option<u64>,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+        body: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<u64>> {
+        if body.trim().is_empty() {
+            return Task::ready(Err(anyhow!("comment can't be empty")));
+        }
+
+        let anchor_range = project_id.map(|_| {
+            serialize_anchor_range(
+                buffer.anchor_before(range.start)..buffer.anchor_after(range.end),
+            )
+        });
+        let request = self.client.request(proto::CreateReviewThread {
+            channel_id: self.channel_id.0,
+            path: path.to_string(),
+            start_offset: range.start as u64,
+            end_offset: range.end as u64,
+            excerpt: buffer.text_for_range(range).collect(),
+            body,
+            project_id,
+            anchor_range,
+        });
+        cx.spawn(|this, mut cx| async move {
+            let thread = request
+                .await?
+                .thread
+                .ok_or_else(|| anyhow!("invalid review thread"))?;
+            Self::insert_thread(this, thread, &mut cx).await
+        })
+    }
+
+    pub fn add_comment(
+        &mut self,
+        thread_id: u64,
+        body: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        if body.trim().is_empty() {
+            return Task::ready(Err(anyhow!("comment can't be empty")));
+        }
+
+        let request = self
+            .client
+            .request(proto::AddReviewComment { thread_id, body });
+        cx.spawn(|this, mut cx| async move {
+            let thread = request
+                .await?
+                .thread
+                .ok_or_else(|| anyhow!("invalid review thread"))?;
+            Self::insert_thread(this, thread, &mut cx).await?;
+            Ok(())
+        })
+    }
+
+    pub fn set_resolved(
+        &mut self,
+        thread_id: u64,
+        resolved: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let request = self.client.request(proto::SetReviewThreadResolved {
+            thread_id,
+            resolved,
+        });
+        cx.spawn(|this, mut cx| async move {
+            let thread = request
+                .await?
+                .thread
+                .ok_or_else(|| anyhow!("invalid review thread"))?;
+            Self::insert_thread(this, thread, &mut cx).await?;
+            Ok(())
+        })
+    }
+
+    /// Fetches the channel's threads again, to catch up on any changes that were missed while
+    /// disconnected.
+    pub(crate) fn reload(&mut self, cx: &mut ModelContext<Self>) {
+        let user_store = self.user_store.clone();
+        let request = self.client.request(proto::GetReviewThreads {
+            channel_id: self.channel_id.0,
+        });
+        cx.spawn(|this, mut cx| async move {
+            let response = request.await?;
+            let threads =
+                ReviewThread::from_proto_vec(response.threads, &user_store, &mut cx).await?;
+            this.update(&mut cx, |this, cx| {
+                this.threads = threads
+                    .into_iter()
+                    .map(|thread| (thread.id, thread))
+                    .collect();
+                cx.emit(ReviewThreadsEvent::ThreadsReloaded);
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    async fn insert_thread(
+        this: WeakModel<Self>,
+        thread: proto::ReviewThread,
+        cx: &mut AsyncAppContext,
+    ) -> Result<u64> {
+        let user_store = this.update(cx, |this, _| this.user_store.clone())?;
+        let thread = ReviewThread::from_proto(thread, &user_store, cx).await?;
+        let thread_id = thread.id;
+        this.update(cx, |this, cx| {
+            this.threads.insert(thread_id, thread);
+            cx.emit(ReviewThreadsEvent::ThreadUpdated { thread_id });
+            cx.notify();
+        })?;
+        Ok(thread_id)
+    }
+
+    async fn handle_thread_updated(
+        this: Model<Self>,
+        message: TypedEnvelope<proto::ReviewThreadUpdated>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let thread = message
+            .payload
+            .thread
+            .ok_or_else(|| anyhow!("empty review thread"))?;
+        Self::insert_thread(this.downgrade(), thread, &mut cx).await?;
+        Ok(())
+    }
+}
+
+impl ReviewThread {
+    /// Finds the range that this thread is about in the current contents of its file.
+    ///
+    /// When the buffer is the one the thread was started in, from the same shared project,
+    /// the thread's anchors are used. Otherwise the file may have changed since the thread was
+    /// started, so this looks for the occurrence of the original text closest to where it used
+    /// to be. Returns `None` if the text is no longer in the file.
+    pub fn locate(
+        &self,
+        buffer: &BufferSnapshot,
+        project_id: Option<u64>,
+    ) -> Option<Range<Anchor>> {
+        if let Some(anchor_range) = self
+            .anchor_range
+            .as_ref()
+            .filter(|anchor_range| Some(anchor_range.project_id) == project_id)
+        {
+            let range = &anchor_range.range;
+            if buffer.can_resolve(&range.start) && buffer.can_resolve(&range.end) {
+                // If all of the range's text was deleted, the thread is outdated unless the
+                // text can be found elsewhere.
+                if self.excerpt.is_empty() || !range.to_offset(buffer).is_empty() {
+                    return Some(range.clone());
+                }
+            }
+        }
+
+        let start = buffer.clip_offset(self.range.start, text::Bias::Left);
+        let range = if self.excerpt.is_empty() {
+            start..start
+        } else {
+            let end = buffer.clip_offset(self.range.end, text::Bias::Right);
+            if buffer.text_for_range(start..end).collect::<String>() == *self.excerpt {
+                start..end
+            } else {
+                let text = buffer.as_rope().to_string();
+                let offset = text
+                    .match_indices(self.excerpt.as_ref())
+                    .map(|(offset, _)| offset)
+                    .min_by_key(|offset| offset.abs_diff(self.range.start))?;
+                offset..offset + self.excerpt.len()
+            }
+        };
+        Some(buffer.anchor_before(range.start)..buffer.anchor_after(range.end))
+    }
+
+    pub async fn from_proto(
+        thread: proto::ReviewThread,
+        user_store: &Model<UserStore>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<Self> {
+        let mut comments = Vec::with_capacity(thread.comments.len());
+        for comment in thread.comments {
+            let author = user_store
+                .update(cx, |user_store, cx| {
+                    user_store.get_user(comment.author_id, cx)
+                })?
+                .await?;
+            comments.push(ReviewComment {
+                id: comment.id,
+                author,
+                body: comment.body,
+                timestamp: OffsetDateTime::from_unix_timestamp(comment.timestamp as i64)?,
+            });
+        }
+        Ok(Self {
+            id: thread.id,
+            path: thread.path.into(),
+            range: thread.start_offset as usize..thread.end_offset as usize,
+            excerpt: thread.excerpt.into(),
+            anchor_range: thread.project_id.zip(thread.anchor_range).and_then(
+                |(project_id, range)| {
+                    Some(ReviewThreadAnchorRange {
+                        project_id,
+                        range: deserialize_anchor_range(range).ok()?,
+                    })
+                },
+            ),
+            resolved: thread.resolved,
+            comments,
+        })
+    }
+
+    pub async fn from_proto_vec(
+        threads: Vec<proto::ReviewThread>,
+        user_store: &Model<UserStore>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<Vec<Self>> {
+        let unique_user_ids = threads
+            .iter()
+            .flat_map(|thread| thread.comments.iter().map(|comment| comment.author_id))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        user_store
+            .update(cx, |user_store, cx| {
+                user_store.get_users(unique_user_ids, cx)
+            })?
+            .await?;
+
+        let mut result = Vec::with_capacity(threads.len());
+        for thread in threads {
+            result.push(Self::from_proto(thread, user_store, cx).await?);
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use text::{Buffer, BufferId, ToOffset};
+
+    fn thread(range: Range<usize>, excerpt: &str) -> ReviewThread {
+        ReviewThread {
+            id: 1,
+            path: "zed/src/main.rs".into(),
+            range,
+            excerpt: excerpt.into(),
+            anchor_range: None,
+            resolved: false,
+            comments: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_locate_review_thread() {
+        let mut buffer = Buffer::new(
+            0,
+            BufferId::new(1).unwrap(),
+            "fn a() {}\nfn b() {}\nfn a() {}\n".into(),
+        );
+        let thread = thread(20..29, "fn a() {}");
+        let to_offsets = |buffer: &Buffer, range: Range<Anchor>| {
+            range.start.to_offset(buffer)..range.end.to_offset(buffer)
+        };
+
+        let range = thread.locate(&buffer, None).unwrap();
+        assert_eq!(to_offsets(&buffer, range.clone()), 20..29);
+
+        // The anchors follow the range as the file is edited.
+        buffer.edit([(0..0, "// a\n")]);
+        assert_eq!(to_offsets(&buffer, range), 25..34);
+
+        // When the file has changed since the thread was started, the occurrence of its
+        // text that's closest to its original position is used.
+        assert_eq!(
+            to_offsets(&buffer, thread.locate(&buffer, None).unwrap()),
+            25..34
+        );
+
+        buffer.edit([(25..34, "fn c() {}")]);
+        assert_eq!(
+            to_offsets(&buffer, thread.locate(&buffer, None).unwrap()),
+            5..14
+        );
+
+        buffer.edit([(5..14, "")]);
+        assert_eq!(thread.locate(&buffer, None), None);
+    }
+
+    #[test]
+    fn test_locate_review_thread_with_anchors() {
+        let mut buffer = Buffer::new(
+            0,
+            BufferId::new(1).unwrap(),
+            "fn a() {}\nfn b() {}\n".into(),
+        );
+        let mut thread = thread(10..19, "fn b() {}");
+        thread.anchor_range = Some(ReviewThreadAnchorRange {
+            project_id: 1,
+            range: buffer.anchor_before(10)..buffer.anchor_after(19),
+        });
+        let locate = |buffer: &Buffer, project_id| {
+            thread
+                .locate(buffer, project_id)
+                .map(|range| range.to_offset(buffer))
+        };
+
+        // A copy of the range's text is inserted closer to where it started, which the anchors
+        // aren't confused by.
+        buffer.edit([(0..0, "fn b() {}\n")]);
+        assert_eq!(locate(&buffer, Some(1)), Some(20..29));
+        assert_eq!(locate(&buffer, Some(2)), Some(0..9));
+        assert_eq!(locate(&buffer, None), Some(0..9));
+
+        // Edits within the range keep it attached.
+        buffer.edit([(23..24, "c")]);
+        assert_eq!(locate(&buffer, Some(1)), Some(20..29));
+
+        // Once all of its text is deleted, the thread falls back to searching for it.
+        buffer.edit([(20..29, "")]);
+        assert_eq!(locate(&buffer, Some(1)), Some(0..9));
+
+        // Anchors can't be resolved in a different buffer.
+        let other_buffer = Buffer::new(
+            0,
+            BufferId::new(2).unwrap(),
+            "fn a() {}\nfn b() {}\n".into(),
+        );
+        assert_eq!(locate(&other_buffer, Some(1)), Some(10..19));
+    }
+}
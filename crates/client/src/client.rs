@@ -1045,7 +1045,10 @@ impl Client {
                 ))?
             };
 
-            Url::parse(&collab_url).context("invalid rpc url")
+            // Self-hosted servers redirect to a path on the same host.
+            Url::parse(&url)
+                .and_then(|url| url.join(&collab_url))
+                .context("invalid rpc url")
         }
     }
 
@@ -0,0 +1,8 @@
+CREATE TABLE "project_replica_reservations" (
+    "project_id" INTEGER NOT NULL REFERENCES projects (id) ON DELETE CASCADE,
+    "replica_id" INTEGER NOT NULL,
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    PRIMARY KEY (project_id, replica_id)
+);
+
+CREATE INDEX "index_project_replica_reservations_on_user_id" ON "project_replica_reservations" ("user_id");
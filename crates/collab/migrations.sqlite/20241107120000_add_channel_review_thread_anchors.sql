@@ -0,0 +1,2 @@
+ALTER TABLE "channel_review_threads" ADD COLUMN "project_id" INTEGER REFERENCES projects (id) ON DELETE SET NULL;
+ALTER TABLE "channel_review_threads" ADD COLUMN "anchor_range" BLOB;
@@ -0,0 +1,10 @@
+-- SQLite can't drop a NOT NULL constraint, so the column is replaced with a new one.
+DROP INDEX "index_users_on_github_user_id";
+ALTER TABLE "users" ADD COLUMN "new_github_user_id" INTEGER;
+
+-- Users that sign in with a password used to be given negative GitHub user IDs.
+UPDATE "users" SET "new_github_user_id" = "github_user_id" WHERE "github_user_id" >= 0;
+
+ALTER TABLE "users" DROP COLUMN "github_user_id";
+ALTER TABLE "users" RENAME COLUMN "new_github_user_id" TO "github_user_id";
+CREATE UNIQUE INDEX "index_users_on_github_user_id" ON "users" ("github_user_id");
@@ -0,0 +1,25 @@
+CREATE TABLE "channel_review_threads" (
+    "id" SERIAL PRIMARY KEY,
+    "channel_id" INTEGER NOT NULL REFERENCES channels (id) ON DELETE CASCADE,
+    "author_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "path" VARCHAR NOT NULL,
+    "start_offset" INTEGER NOT NULL,
+    "end_offset" INTEGER NOT NULL,
+    "excerpt" TEXT NOT NULL,
+    "project_id" INTEGER REFERENCES projects (id) ON DELETE SET NULL,
+    "anchor_range" BYTEA,
+    "created_at" TIMESTAMP NOT NULL DEFAULT now(),
+    "resolved" BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX "index_channel_review_threads_on_channel_id" ON "channel_review_threads" ("channel_id");
+
+CREATE TABLE "channel_review_comments" (
+    "id" SERIAL PRIMARY KEY,
+    "thread_id" INTEGER NOT NULL REFERENCES channel_review_threads (id) ON DELETE CASCADE,
+    "author_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "body" TEXT NOT NULL,
+    "created_at" TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX "index_channel_review_comments_on_thread_id" ON "channel_review_comments" ("thread_id");
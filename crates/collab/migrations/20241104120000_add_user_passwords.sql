@@ -0,0 +1,4 @@
+CREATE TABLE "user_passwords" (
+    "user_id" INTEGER PRIMARY KEY REFERENCES users (id) ON DELETE CASCADE,
+    "hash" VARCHAR NOT NULL
+);
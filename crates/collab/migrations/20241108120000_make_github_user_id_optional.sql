@@ -0,0 +1,4 @@
+ALTER TABLE users ALTER COLUMN github_user_id DROP NOT NULL;
+
+-- Users that sign in with a password used to be given negative GitHub user IDs.
+UPDATE users SET github_user_id = NULL WHERE github_user_id < 0;
@@ -95,17 +95,19 @@ impl std::fmt::Display for SystemIdHeader {
 }
 
 pub fn routes(rpc_server: Arc<rpc::Server>) -> Router<(), Body> {
-    Router::new()
+    let mut router = Router::new()
         .route("/user", get(get_authenticated_user))
         .route("/users/:id/access_tokens", post(create_access_token))
         .route("/rpc_server_snapshot", get(get_rpc_server_snapshot))
-        .merge(billing::router())
-        .merge(contributors::router())
-        .layer(
-            ServiceBuilder::new()
-                .layer(Extension(rpc_server))
-                .layer(middleware::from_fn(validate_api_token)),
-        )
+        .merge(contributors::router());
+    if !rpc_server.config().is_self_hosted() {
+        router = router.merge(billing::router());
+    }
+    router.layer(
+        ServiceBuilder::new()
+            .layer(Extension(rpc_server))
+            .layer(middleware::from_fn(validate_api_token)),
+    )
 }
 
 pub async fn validate_api_token<B>(req: Request<B>, next: Next<B>) -> impl IntoResponse {
@@ -1,7 +1,7 @@
 use crate::{
-    db::{self, AccessTokenId, Database, UserId},
+    db::{self, AccessTokenId, Database, User, UserId},
     rpc::Principal,
-    AppState, Error, Result,
+    AppState, Error, RateLimit, Result,
 };
 use anyhow::{anyhow, Context};
 use axum::{
@@ -11,9 +11,10 @@ use axum::{
 };
 use base64::prelude::*;
 use prometheus::{exponential_buckets, register_histogram, Histogram};
+use rand::thread_rng;
 pub use rpc::auth::random_token;
 use scrypt::{
-    password_hash::{PasswordHash, PasswordVerifier},
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Scrypt,
 };
 use serde::{Deserialize, Serialize};
@@ -231,10 +232,75 @@ pub async fn verify_access_token(
     })
 }
 
+/// Hashes the password of a user that signs in to a self-hosted server.
+pub fn hash_password(password: &str) -> Result<String> {
+    // Avoid slow hashing in debug mode.
+    let params = if cfg!(debug_assertions) {
+        scrypt::Params::new(1, 1, 1, scrypt::Params::RECOMMENDED_LEN).unwrap()
+    } else {
+        scrypt::Params::recommended()
+    };
+
+    Ok(Scrypt
+        .hash_password_customized(
+            password.as_bytes(),
+            None,
+            None,
+            params,
+            &SaltString::generate(thread_rng()),
+        )
+        .map_err(anyhow::Error::new)?
+        .to_string())
+}
+
+/// Returns whether the given password is the user's. Users that sign in with GitHub don't
+/// have passwords, and neither do logins without a user.
+///
+/// The password is hashed either way, so that how long this takes doesn't reveal which logins
+/// have passwords.
+pub async fn verify_password(user: Option<&User>, password: &str, db: &Database) -> Result<bool> {
+    static DUMMY_PASSWORD_HASH: OnceLock<String> = OnceLock::new();
+
+    let password_hash = match user {
+        Some(user) => db.get_user_password_hash(user.id).await?,
+        None => None,
+    };
+    let (password_hash, is_dummy) = match &password_hash {
+        Some(password_hash) => (password_hash.as_str(), false),
+        None => {
+            let dummy_hash = DUMMY_PASSWORD_HASH
+                .get_or_init(|| hash_password(&random_token()).expect("failed to hash password"));
+            (dummy_hash.as_str(), true)
+        }
+    };
+
+    let password_hash = PasswordHash::new(password_hash).map_err(anyhow::Error::new)?;
+    let is_valid = Scrypt
+        .verify_password(password.as_bytes(), &password_hash)
+        .is_ok();
+    Ok(is_valid && !is_dummy)
+}
+
+/// Limits how often someone can try to sign in as a user with a password, so that it can't
+/// be guessed by brute force.
+pub struct PasswordSigninRateLimit;
+
+impl RateLimit for PasswordSigninRateLimit {
+    fn capacity(&self) -> usize {
+        10
+    }
+
+    fn refill_duration(&self) -> chrono::Duration {
+        chrono::Duration::minutes(15)
+    }
+
+    fn db_name(&self) -> &'static str {
+        "password-signin"
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use rand::thread_rng;
-    use scrypt::password_hash::{PasswordHasher, SaltString};
     use sea_orm::EntityTrait;
 
     use super::*;
@@ -327,6 +393,50 @@ mod test {
         ));
     }
 
+    #[gpui::test]
+    async fn test_verify_password(cx: &mut gpui::TestAppContext) {
+        let test_db = crate::db::TestDb::sqlite(cx.executor().clone());
+        let db = test_db.db();
+
+        let user = db
+            .create_user_with_password("alice", &hash_password("hunter2").unwrap(), false)
+            .await
+            .unwrap();
+        assert_eq!(user.github_user_id, None);
+
+        assert!(verify_password(Some(&user), "hunter2", db).await.unwrap());
+        assert!(!verify_password(Some(&user), "hunter3", db).await.unwrap());
+        assert!(!verify_password(None, "hunter2", db).await.unwrap());
+
+        // Users that sign in with GitHub don't have passwords.
+        let bob = db
+            .create_user(
+                "bob@example.com",
+                false,
+                NewUserParams {
+                    github_login: "bob".into(),
+                    github_user_id: 2,
+                },
+            )
+            .await
+            .unwrap();
+        let bob = db.get_user_by_id(bob.user_id).await.unwrap().unwrap();
+        assert!(!verify_password(Some(&bob), "", db).await.unwrap());
+
+        // Logins can't be reused.
+        assert!(db
+            .create_user_with_password("bob", &hash_password("hunter2").unwrap(), false)
+            .await
+            .is_err());
+
+        let other_user = db
+            .create_user_with_password("carol", &hash_password("hunter2").unwrap(), true)
+            .await
+            .unwrap();
+        assert_eq!(other_user.github_user_id, None);
+        assert!(other_user.admin);
+    }
+
     async fn create_previous_access_token(
         user_id: UserId,
         impersonated_user_id: Option<UserId>,
@@ -749,6 +749,8 @@ impl ProjectCollaborator {
 pub struct LeftProject {
     pub id: ProjectId,
     pub should_unshare: bool,
+    /// Whether the collaborator that left kept their replica id, and may reconnect later.
+    pub can_reconnect: bool,
     pub connection_ids: Vec<ConnectionId>,
 }
 
@@ -89,6 +89,8 @@ id_type!(NotificationKindId);
 id_type!(ProjectCollaboratorId);
 id_type!(ProjectId);
 id_type!(ReplicaId);
+id_type!(ReviewCommentId);
+id_type!(ReviewThreadId);
 id_type!(RoomId);
 id_type!(RoomParticipantId);
 id_type!(ServerId);
@@ -15,6 +15,7 @@ pub mod notifications;
 pub mod processed_stripe_events;
 pub mod projects;
 pub mod rate_buckets;
+pub mod review_threads;
 pub mod rooms;
 pub mod servers;
 pub mod users;
@@ -1,4 +1,5 @@
 use anyhow::Context as _;
+use rpc::{ErrorCode, ErrorCodeExt};
 use util::ResultExt;
 
 use super::*;
@@ -563,6 +564,83 @@ impl Database {
         .await
     }
 
+    /// Adds a guest who lost their connection back to a project with the replica id they had
+    /// before, so that they can send the edits they made while they were offline.
+    pub async fn reconnect_to_project(
+        &self,
+        reconnected_project: &proto::RejoinProject,
+        replica_id: ReplicaId,
+        connection: ConnectionId,
+        user_id: UserId,
+    ) -> Result<TransactionGuard<(RejoinedProject, ChannelRole)>> {
+        let project_id = ProjectId::from_proto(reconnected_project.id);
+        self.project_transaction(project_id, |tx| async move {
+            if project::Entity::find_by_id(project_id)
+                .one(&*tx)
+                .await?
+                .is_none()
+            {
+                Err(ErrorCode::NoSuchProject.anyhow())?;
+            }
+            let (_, role) = self
+                .access_project(project_id, connection, Capability::ReadWrite, &tx)
+                .await?;
+
+            let old_collaborator = project_collaborator::Entity::find()
+                .filter(
+                    Condition::all()
+                        .add(project_collaborator::Column::ProjectId.eq(project_id))
+                        .add(project_collaborator::Column::ReplicaId.eq(replica_id)),
+                )
+                .one(&*tx)
+                .await?;
+            let old_connection_id = if let Some(old_collaborator) = old_collaborator {
+                // The guest's old connection hasn't been cleaned up yet, so take its place.
+                if old_collaborator.user_id != user_id || old_collaborator.is_host {
+                    Err(anyhow!("replica id {replica_id} is in use"))?;
+                }
+                let old_connection_id = old_collaborator.connection();
+                project_collaborator::Entity::update(project_collaborator::ActiveModel {
+                    connection_id: ActiveValue::set(connection.id as i32),
+                    connection_server_id: ActiveValue::set(ServerId(connection.owner_id as i32)),
+                    ..old_collaborator.into_active_model()
+                })
+                .exec(&*tx)
+                .await?;
+                old_connection_id
+            } else {
+                project_replica_reservation::Entity::find_by_id((project_id, replica_id))
+                    .one(&*tx)
+                    .await?
+                    .filter(|reservation| reservation.user_id == user_id)
+                    .ok_or_else(|| anyhow!("replica id {replica_id} is no longer reserved"))?;
+                project_replica_reservation::Entity::delete_by_id((project_id, replica_id))
+                    .exec(&*tx)
+                    .await?;
+                project_collaborator::ActiveModel {
+                    project_id: ActiveValue::set(project_id),
+                    connection_id: ActiveValue::set(connection.id as i32),
+                    connection_server_id: ActiveValue::set(ServerId(connection.owner_id as i32)),
+                    user_id: ActiveValue::set(user_id),
+                    replica_id: ActiveValue::set(replica_id),
+                    is_host: ActiveValue::set(false),
+                    ..Default::default()
+                }
+                .insert(&*tx)
+                .await?;
+                connection
+            };
+
+            let mut project = self
+                .rejoin_project_internal(&tx, reconnected_project, user_id, connection)
+                .await?
+                .ok_or_else(|| anyhow!("failed to reconnect to project"))?;
+            project.old_connection_id = old_connection_id;
+            Ok((project, role))
+        })
+        .await
+    }
+
     async fn join_project_internal(
         &self,
         project: project::Model,
@@ -575,10 +653,18 @@ impl Database {
             .find_related(project_collaborator::Entity)
             .all(tx)
             .await?;
-        let replica_ids = collaborators
+        let mut replica_ids = collaborators
             .iter()
             .map(|c| c.replica_id)
             .collect::<HashSet<_>>();
+        replica_ids.extend(
+            project_replica_reservation::Entity::find()
+                .filter(project_replica_reservation::Column::ProjectId.eq(project.id))
+                .all(tx)
+                .await?
+                .into_iter()
+                .map(|reservation| reservation.replica_id),
+        );
         let mut replica_id = ReplicaId(1);
         while replica_ids.contains(&replica_id) {
             replica_id.0 += 1;
@@ -820,6 +906,7 @@ impl Database {
             let left_project = LeftProject {
                 id: project_id,
                 should_unshare: connection == project.host_connection()?,
+                can_reconnect: false,
                 connection_ids,
             };
             Ok((room, left_project))
@@ -0,0 +1,230 @@
+use super::*;
+use prost::Message;
+use std::ops::Range;
+use time::OffsetDateTime;
+
+impl Database {
+    /// Returns all of the review threads in the given channel, including resolved ones.
+    pub async fn get_review_threads(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+    ) -> Result<Vec<proto::ReviewThread>> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &tx)
+                .await?;
+
+            let threads = channel_review_thread::Entity::find()
+                .filter(channel_review_thread::Column::ChannelId.eq(channel_id))
+                .order_by_asc(channel_review_thread::Column::Id)
+                .all(&*tx)
+                .await?;
+            self.load_review_threads(threads, &tx).await
+        })
+        .await
+    }
+
+    /// Starts a review thread on a range of a file, with its first comment. The range can also
+    /// be given as anchors in the buffer of a shared project, which are used to find it for as
+    /// long as that project is shared.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_review_thread(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        path: &str,
+        range: Range<usize>,
+        anchor_range: Option<(ProjectId, proto::AnchorRange)>,
+        excerpt: &str,
+        body: &str,
+        timestamp: OffsetDateTime,
+    ) -> Result<proto::ReviewThread> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_member(&channel, user_id, &tx)
+                .await?;
+
+            let (project_id, anchor_range) = anchor_range.unzip();
+            let timestamp = timestamp.to_offset(time::UtcOffset::UTC);
+            let timestamp = time::PrimitiveDateTime::new(timestamp.date(), timestamp.time());
+            let thread = channel_review_thread::ActiveModel {
+                id: ActiveValue::NotSet,
+                channel_id: ActiveValue::Set(channel_id),
+                author_id: ActiveValue::Set(user_id),
+                path: ActiveValue::Set(path.to_string()),
+                start_offset: ActiveValue::Set(range.start as i32),
+                end_offset: ActiveValue::Set(range.end as i32),
+                excerpt: ActiveValue::Set(excerpt.to_string()),
+                project_id: ActiveValue::Set(project_id),
+                anchor_range: ActiveValue::Set(
+                    anchor_range.map(|anchor_range| anchor_range.encode_to_vec()),
+                ),
+                created_at: ActiveValue::Set(timestamp),
+                resolved: ActiveValue::Set(false),
+            }
+            .insert(&*tx)
+            .await?;
+            channel_review_comment::ActiveModel {
+                id: ActiveValue::NotSet,
+                thread_id: ActiveValue::Set(thread.id),
+                author_id: ActiveValue::Set(user_id),
+                body: ActiveValue::Set(body.to_string()),
+                created_at: ActiveValue::Set(timestamp),
+            }
+            .insert(&*tx)
+            .await?;
+
+            self.load_review_thread(thread, &tx).await
+        })
+        .await
+    }
+
+    /// Adds a comment to the end of a review thread.
+    pub async fn add_review_comment(
+        &self,
+        thread_id: ReviewThreadId,
+        user_id: UserId,
+        body: &str,
+        timestamp: OffsetDateTime,
+    ) -> Result<proto::ReviewThread> {
+        self.transaction(|tx| async move {
+            let thread = self
+                .get_review_thread_for_member(thread_id, user_id, &tx)
+                .await?;
+
+            let timestamp = timestamp.to_offset(time::UtcOffset::UTC);
+            let timestamp = time::PrimitiveDateTime::new(timestamp.date(), timestamp.time());
+            channel_review_comment::ActiveModel {
+                id: ActiveValue::NotSet,
+                thread_id: ActiveValue::Set(thread.id),
+                author_id: ActiveValue::Set(user_id),
+                body: ActiveValue::Set(body.to_string()),
+                created_at: ActiveValue::Set(timestamp),
+            }
+            .insert(&*tx)
+            .await?;
+
+            self.load_review_thread(thread, &tx).await
+        })
+        .await
+    }
+
+    /// Marks a review thread as resolved, or reopens it.
+    pub async fn set_review_thread_resolved(
+        &self,
+        thread_id: ReviewThreadId,
+        user_id: UserId,
+        resolved: bool,
+    ) -> Result<proto::ReviewThread> {
+        self.transaction(|tx| async move {
+            let thread = self
+                .get_review_thread_for_member(thread_id, user_id, &tx)
+                .await?;
+            let thread = channel_review_thread::ActiveModel {
+                id: ActiveValue::Unchanged(thread.id),
+                resolved: ActiveValue::Set(resolved),
+                ..Default::default()
+            }
+            .update(&*tx)
+            .await?;
+
+            self.load_review_thread(thread, &tx).await
+        })
+        .await
+    }
+
+    async fn get_review_thread_for_member(
+        &self,
+        thread_id: ReviewThreadId,
+        user_id: UserId,
+        tx: &DatabaseTransaction,
+    ) -> Result<channel_review_thread::Model> {
+        let thread = channel_review_thread::Entity::find_by_id(thread_id)
+            .one(tx)
+            .await?
+            .ok_or_else(|| anyhow!("no such review thread"))?;
+        let channel = self.get_channel_internal(thread.channel_id, tx).await?;
+        self.check_user_is_channel_member(&channel, user_id, tx)
+            .await?;
+        Ok(thread)
+    }
+
+    async fn load_review_thread(
+        &self,
+        thread: channel_review_thread::Model,
+        tx: &DatabaseTransaction,
+    ) -> Result<proto::ReviewThread> {
+        let thread = self
+            .load_review_threads(vec![thread], tx)
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("review thread not found"))?;
+        Ok(thread)
+    }
+
+    async fn load_review_threads(
+        &self,
+        threads: Vec<channel_review_thread::Model>,
+        tx: &DatabaseTransaction,
+    ) -> Result<Vec<proto::ReviewThread>> {
+        let mut threads = threads
+            .into_iter()
+            .map(|thread| {
+                let anchor_range = thread.project_id.zip(thread.anchor_range).and_then(
+                    |(project_id, anchor_range)| {
+                        let anchor_range =
+                            proto::AnchorRange::decode(anchor_range.as_slice()).ok()?;
+                        Some((project_id, anchor_range))
+                    },
+                );
+                let (project_id, anchor_range) = anchor_range.unzip();
+                proto::ReviewThread {
+                    id: thread.id.to_proto(),
+                    channel_id: thread.channel_id.to_proto(),
+                    path: thread.path,
+                    start_offset: thread.start_offset as u64,
+                    end_offset: thread.end_offset as u64,
+                    excerpt: thread.excerpt,
+                    resolved: thread.resolved,
+                    comments: Vec::new(),
+                    project_id: project_id.map(|project_id| project_id.to_proto()),
+                    anchor_range,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut comments = channel_review_comment::Entity::find()
+            .filter(
+                channel_review_comment::Column::ThreadId
+                    .is_in(threads.iter().map(|thread| thread.id)),
+            )
+            .order_by_asc(channel_review_comment::Column::ThreadId)
+            .order_by_asc(channel_review_comment::Column::Id)
+            .stream(tx)
+            .await?;
+
+        let mut thread_ix = 0;
+        while let Some(comment) = comments.next().await {
+            let comment = comment?;
+            let thread_id = comment.thread_id.to_proto();
+            while let Some(thread) = threads.get_mut(thread_ix) {
+                if thread.id < thread_id {
+                    thread_ix += 1;
+                } else {
+                    if thread.id == thread_id {
+                        thread.comments.push(proto::ReviewComment {
+                            id: comment.id.to_proto(),
+                            author_id: comment.author_id.to_proto(),
+                            body: comment.body,
+                            timestamp: comment.created_at.assume_utc().unix_timestamp() as u64,
+                        });
+                    }
+                    break;
+                }
+            }
+        }
+
+        Ok(threads)
+    }
+}
@@ -748,8 +748,12 @@ impl Database {
             .await?;
         let self_collaborator = if let Some(self_collaborator_ix) = collaborators
             .iter()
-            .position(|collaborator| collaborator.user_id == user_id)
-        {
+            .position(|collaborator| collaborator.connection() == connection)
+            .or_else(|| {
+                collaborators
+                    .iter()
+                    .position(|collaborator| collaborator.user_id == user_id)
+            }) {
             collaborators.swap_remove(self_collaborator_ix)
         } else {
             return Ok(None);
@@ -859,6 +863,7 @@ impl Database {
                     .await?;
 
                 let mut left_projects = HashMap::default();
+                let mut replica_reservations = Vec::new();
                 let mut collaborators = project_collaborator::Entity::find()
                     .filter(project_collaborator::Column::ProjectId.is_in(project_ids))
                     .stream(&*tx)
@@ -873,11 +878,22 @@ impl Database {
                                 id: collaborator.project_id,
                                 connection_ids: Default::default(),
                                 should_unshare: false,
+                                can_reconnect: false,
                             });
 
                     let collaborator_connection_id = collaborator.connection();
                     if collaborator_connection_id != connection {
                         left_project.connection_ids.push(collaborator_connection_id);
+                    } else if !collaborator.is_host && leaving_participant.answering_connection_lost
+                    {
+                        // Guests who lost their connection may have kept editing, so hold on
+                        // to their replica id until they reconnect and send us those edits.
+                        left_project.can_reconnect = true;
+                        replica_reservations.push(project_replica_reservation::ActiveModel {
+                            project_id: ActiveValue::set(collaborator.project_id),
+                            replica_id: ActiveValue::set(collaborator.replica_id),
+                            user_id: ActiveValue::set(collaborator.user_id),
+                        });
                     }
 
                     if collaborator.is_host && collaborator.connection() == connection {
@@ -886,6 +902,20 @@ impl Database {
                 }
                 drop(collaborators);
 
+                if !replica_reservations.is_empty() {
+                    project_replica_reservation::Entity::insert_many(replica_reservations)
+                        .on_conflict(
+                            OnConflict::columns([
+                                project_replica_reservation::Column::ProjectId,
+                                project_replica_reservation::Column::ReplicaId,
+                            ])
+                            .do_nothing()
+                            .to_owned(),
+                        )
+                        .exec_without_returning(&*tx)
+                        .await?;
+                }
+
                 // Leave projects.
                 project_collaborator::Entity::delete_many()
                     .filter(
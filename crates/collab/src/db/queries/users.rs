@@ -15,7 +15,7 @@ impl Database {
             let user = user::Entity::insert(user::ActiveModel {
                 email_address: ActiveValue::set(Some(email_address.into())),
                 github_login: ActiveValue::set(params.github_login.clone()),
-                github_user_id: ActiveValue::set(params.github_user_id),
+                github_user_id: ActiveValue::set(Some(params.github_user_id)),
                 admin: ActiveValue::set(admin),
                 metrics_id: ActiveValue::set(Uuid::new_v4()),
                 ..Default::default()
@@ -143,7 +143,7 @@ impl Database {
             .await?
         {
             let mut user_by_github_login = user_by_github_login.into_active_model();
-            user_by_github_login.github_user_id = ActiveValue::set(github_user_id);
+            user_by_github_login.github_user_id = ActiveValue::set(Some(github_user_id));
             user_by_github_login.github_user_created_at =
                 ActiveValue::set(Some(github_user_created_at));
             Ok(user_by_github_login.update(tx).await?)
@@ -151,7 +151,7 @@ impl Database {
             let user = user::Entity::insert(user::ActiveModel {
                 email_address: ActiveValue::set(github_email.map(|email| email.into())),
                 github_login: ActiveValue::set(github_login.into()),
-                github_user_id: ActiveValue::set(github_user_id),
+                github_user_id: ActiveValue::set(Some(github_user_id)),
                 github_user_created_at: ActiveValue::set(Some(github_user_created_at)),
                 admin: ActiveValue::set(false),
                 invite_count: ActiveValue::set(0),
@@ -246,6 +246,55 @@ impl Database {
         .await
     }
 
+    /// Creates a user that signs in with a password instead of a GitHub account, which is how
+    /// users sign in to self-hosted servers.
+    pub async fn create_user_with_password(
+        &self,
+        login: &str,
+        password_hash: &str,
+        admin: bool,
+    ) -> Result<User> {
+        self.transaction(|tx| async move {
+            let existing_user = user::Entity::find()
+                .filter(user::Column::GithubLogin.eq(login))
+                .one(&*tx)
+                .await?;
+            if existing_user.is_some() {
+                Err(anyhow!("a user named {login} already exists"))?;
+            }
+
+            let user = user::Entity::insert(user::ActiveModel {
+                github_login: ActiveValue::set(login.into()),
+                github_user_id: ActiveValue::set(None),
+                admin: ActiveValue::set(admin),
+                metrics_id: ActiveValue::set(Uuid::new_v4()),
+                ..Default::default()
+            })
+            .exec_with_returning(&*tx)
+            .await?;
+            user_password::Entity::insert(user_password::ActiveModel {
+                user_id: ActiveValue::set(user.id),
+                hash: ActiveValue::set(password_hash.into()),
+            })
+            .exec(&*tx)
+            .await?;
+
+            Ok(user)
+        })
+        .await
+    }
+
+    /// Returns the hash of the given user's password, if they sign in with one.
+    pub async fn get_user_password_hash(&self, id: UserId) -> Result<Option<String>> {
+        self.transaction(|tx| async move {
+            Ok(user_password::Entity::find_by_id(id)
+                .one(&*tx)
+                .await?
+                .map(|password| password.hash))
+        })
+        .await
+    }
+
     /// hard delete the user.
     pub async fn destroy_user(&self, id: UserId) -> Result<()> {
         self.transaction(|tx| async move {
@@ -253,6 +302,7 @@ impl Database {
                 .filter(access_token::Column::UserId.eq(id))
                 .exec(&*tx)
                 .await?;
+            user_password::Entity::delete_by_id(id).exec(&*tx).await?;
             user::Entity::delete_by_id(id).exec(&*tx).await?;
             Ok(())
         })
@@ -375,6 +425,7 @@ impl Database {
         self.transaction(|tx| async move {
             Ok(user::Entity::find()
                 .filter(user::Column::GithubUserCreatedAt.is_null())
+                .filter(user::Column::GithubUserId.is_not_null())
                 .all(&*tx)
                 .await?)
         })
@@ -11,6 +11,8 @@ pub mod channel_chat_participant;
 pub mod channel_member;
 pub mod channel_message;
 pub mod channel_message_mention;
+pub mod channel_review_comment;
+pub mod channel_review_thread;
 pub mod contact;
 pub mod contributor;
 pub mod embedding;
@@ -26,6 +28,7 @@ pub mod observed_channel_messages;
 pub mod processed_stripe_event;
 pub mod project;
 pub mod project_collaborator;
+pub mod project_replica_reservation;
 pub mod rate_buckets;
 pub mod room;
 pub mod room_participant;
@@ -33,6 +36,7 @@ pub mod server;
 pub mod signup;
 pub mod user;
 pub mod user_feature;
+pub mod user_password;
 pub mod worktree;
 pub mod worktree_diagnostic_summary;
 pub mod worktree_entry;
@@ -0,0 +1,49 @@
+use crate::db::{ChannelId, ProjectId, ReviewThreadId, UserId};
+use sea_orm::entity::prelude::*;
+use time::PrimitiveDateTime;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "channel_review_threads")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: ReviewThreadId,
+    pub channel_id: ChannelId,
+    pub author_id: UserId,
+    pub path: String,
+    pub start_offset: i32,
+    pub end_offset: i32,
+    pub excerpt: String,
+    /// The shared project whose buffer `anchor_range` refers to. It's cleared when the
+    /// project is unshared, because the anchors can't be resolved after that.
+    pub project_id: Option<ProjectId>,
+    /// An encoded `proto::AnchorRange`.
+    pub anchor_range: Option<Vec<u8>>,
+    pub created_at: PrimitiveDateTime,
+    pub resolved: bool,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::channel::Entity",
+        from = "Column::ChannelId",
+        to = "super::channel::Column::Id"
+    )]
+    Channel,
+    #[sea_orm(has_many = "super::channel_review_comment::Entity")]
+    Comments,
+}
+
+impl Related<super::channel::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Channel.def()
+    }
+}
+
+impl Related<super::channel_review_comment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Comments.def()
+    }
+}
@@ -10,9 +10,10 @@ mod extension_tests;
 mod feature_flag_tests;
 mod message_tests;
 mod processed_stripe_event_tests;
+mod review_thread_tests;
 mod user_tests;
 
-use crate::migrations::run_database_migrations;
+use crate::migrations::{run_embedded_database_migrations, POSTGRES_MIGRATIONS, SQLITE_MIGRATIONS};
 
 use super::*;
 use gpui::BackgroundExecutor;
@@ -44,17 +45,17 @@ impl TestDb {
             let mut db = Database::new(options, Executor::Deterministic(background))
                 .await
                 .unwrap();
-            let sql = include_str!(concat!(
-                env!("CARGO_MANIFEST_DIR"),
-                "/migrations.sqlite/20221109000000_test_schema.sql"
-            ));
-            db.pool
-                .execute(sea_orm::Statement::from_string(
-                    db.pool.get_database_backend(),
-                    sql,
-                ))
-                .await
-                .unwrap();
+            // Each connection to an in-memory database gets its own database, so apply the
+            // migrations through the pool rather than with a separate connection.
+            for migration in SQLITE_MIGRATIONS.iter() {
+                db.pool
+                    .execute(sea_orm::Statement::from_string(
+                        db.pool.get_database_backend(),
+                        migration.sql.to_string(),
+                    ))
+                    .await
+                    .unwrap();
+            }
             db.initialize_notification_kinds().await.unwrap();
             db
         });
@@ -93,8 +94,7 @@ impl TestDb {
             let mut db = Database::new(options, Executor::Deterministic(background))
                 .await
                 .unwrap();
-            let migrations_path = concat!(env!("CARGO_MANIFEST_DIR"), "/migrations");
-            run_database_migrations(db.options(), migrations_path)
+            run_embedded_database_migrations(db.options(), &POSTGRES_MIGRATIONS)
                 .await
                 .unwrap();
             db.initialize_notification_kinds().await.unwrap();
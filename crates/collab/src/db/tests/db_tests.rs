@@ -45,25 +45,25 @@ async fn test_get_users(db: &Arc<Database>) {
             (
                 user_ids[0],
                 "user1".to_string(),
-                1,
+                Some(1),
                 Some("user1@example.com".to_string()),
             ),
             (
                 user_ids[1],
                 "user2".to_string(),
-                2,
+                Some(2),
                 Some("user2@example.com".to_string()),
             ),
             (
                 user_ids[2],
                 "user3".to_string(),
-                3,
+                Some(3),
                 Some("user3@example.com".to_string()),
             ),
             (
                 user_ids[3],
                 "user4".to_string(),
-                4,
+                Some(4),
                 Some("user4@example.com".to_string()),
             )
         ]
@@ -106,7 +106,7 @@ async fn test_get_or_create_user_by_github_account(db: &Arc<Database>) {
         .unwrap();
     assert_eq!(user.id, user_id2);
     assert_eq!(&user.github_login, "the-new-login2");
-    assert_eq!(user.github_user_id, 102);
+    assert_eq!(user.github_user_id, Some(102));
 
     let user = db
         .get_or_create_user_by_github_account(
@@ -119,7 +119,7 @@ async fn test_get_or_create_user_by_github_account(db: &Arc<Database>) {
         .await
         .unwrap();
     assert_eq!(&user.github_login, "login3");
-    assert_eq!(user.github_user_id, 103);
+    assert_eq!(user.github_user_id, Some(103));
     assert_eq!(user.email_address, Some("user3@example.com".into()));
 }
 
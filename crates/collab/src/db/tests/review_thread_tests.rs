@@ -0,0 +1,74 @@
+use super::new_test_user;
+use crate::{
+    db::{ChannelRole, Database, ReviewThreadId},
+    test_both_dbs,
+};
+use std::sync::Arc;
+use time::OffsetDateTime;
+
+test_both_dbs!(
+    test_review_threads,
+    test_review_threads_postgres,
+    test_review_threads_sqlite
+);
+
+async fn test_review_threads(db: &Arc<Database>) {
+    let user_a = new_test_user(db, "user_a@example.com").await;
+    let user_b = new_test_user(db, "user_b@example.com").await;
+    let user_c = new_test_user(db, "user_c@example.com").await;
+    let channel = db.create_root_channel("channel", user_a).await.unwrap();
+    db.invite_channel_member(channel, user_b, user_a, ChannelRole::Member)
+        .await
+        .unwrap();
+    db.respond_to_channel_invite(channel, user_b, true)
+        .await
+        .unwrap();
+
+    let thread = db
+        .create_review_thread(
+            channel,
+            user_a,
+            "zed/src/main.rs",
+            10..15,
+            None,
+            "hello",
+            "Should this be a constant?",
+            OffsetDateTime::now_utc(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(thread.path, "zed/src/main.rs");
+    assert_eq!((thread.start_offset, thread.end_offset), (10, 15));
+    assert!(!thread.resolved);
+    assert_eq!(thread.comments.len(), 1);
+
+    let thread_id = ReviewThreadId::from_proto(thread.id);
+    db.add_review_comment(thread_id, user_b, "Good idea.", OffsetDateTime::now_utc())
+        .await
+        .unwrap();
+    let thread = db
+        .set_review_thread_resolved(thread_id, user_b, true)
+        .await
+        .unwrap();
+    assert!(thread.resolved);
+    assert_eq!(
+        thread
+            .comments
+            .iter()
+            .map(|comment| (comment.author_id, comment.body.as_str()))
+            .collect::<Vec<_>>(),
+        [
+            (user_a.to_proto(), "Should this be a constant?"),
+            (user_b.to_proto(), "Good idea."),
+        ]
+    );
+
+    let threads = db.get_review_threads(channel, user_b).await.unwrap();
+    assert_eq!(threads, [thread]);
+
+    // Users outside of the channel can't see or join the discussion.
+    db.get_review_threads(channel, user_c).await.unwrap_err();
+    db.add_review_comment(thread_id, user_c, "Hi!", OffsetDateTime::now_utc())
+        .await
+        .unwrap_err();
+}
@@ -10,6 +10,7 @@ pub mod migrations;
 mod rate_limiter;
 pub mod rpc;
 pub mod seed;
+pub mod self_hosted;
 pub mod stripe_billing;
 pub mod user_backfiller;
 
@@ -186,6 +187,7 @@ pub struct Config {
     pub stripe_api_key: Option<String>,
     pub supermaven_admin_api_key: Option<Arc<str>>,
     pub user_backfiller_github_access_token: Option<Arc<str>>,
+    pub self_hosted: Option<bool>,
 }
 
 impl Config {
@@ -193,6 +195,12 @@ impl Config {
         self.zed_environment == "development".into()
     }
 
+    /// Returns whether this server is run by someone other than Zed, in which case users sign
+    /// in with a password instead of GitHub, and billing, LLM and telemetry services are disabled.
+    pub fn is_self_hosted(&self) -> bool {
+        self.self_hosted.unwrap_or(false)
+    }
+
     /// Returns the base `zed.dev` URL.
     pub fn zed_dot_dev_url(&self) -> &str {
         match self.zed_environment.as_ref() {
@@ -246,6 +254,7 @@ impl Config {
             kinesis_access_key: None,
             kinesis_secret_key: None,
             kinesis_stream: None,
+            self_hosted: None,
         }
     }
 }
@@ -299,6 +308,7 @@ impl AppState {
             .llm_database_url
             .clone()
             .zip(config.llm_database_max_connections)
+            .filter(|_| !config.is_self_hosted())
         {
             let mut llm_db_options = db::ConnectOptions::new(llm_database_url);
             llm_db_options.max_connections(llm_database_max_connections);
@@ -325,6 +335,22 @@ impl AppState {
         };
 
         let db = Arc::new(db);
+        if config.is_self_hosted() {
+            return Ok(Arc::new(Self {
+                db: db.clone(),
+                llm_db,
+                live_kit_client,
+                blob_store_client: None,
+                stripe_client: None,
+                stripe_billing: None,
+                rate_limiter: Arc::new(RateLimiter::new(db)),
+                executor,
+                clickhouse_client: None,
+                kinesis_client: None,
+                config,
+            }));
+        }
+
         let stripe_client = build_stripe_client(&config).map(Arc::new).log_err();
         let this = Self {
             db: db.clone(),
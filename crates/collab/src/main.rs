@@ -1,4 +1,4 @@
-use anyhow::anyhow;
+use anyhow::{anyhow, Context as _};
 use axum::headers::HeaderMapExt;
 use axum::{
     extract::MatchedPath,
@@ -9,7 +9,7 @@ use axum::{
 use collab::api::billing::sync_llm_usage_with_stripe_periodically;
 use collab::api::CloudflareIpCountryHeader;
 use collab::llm::{db::LlmDatabase, log_usage_periodically};
-use collab::migrations::run_database_migrations;
+use collab::migrations::{run_database_migrations, run_embedded_database_migrations};
 use collab::user_backfiller::spawn_user_backfiller;
 use collab::{api::billing::poll_stripe_events_periodically, llm::LlmState, ServiceMode};
 use collab::{
@@ -69,6 +69,35 @@ async fn main() -> Result<()> {
                 collab::llm::db::seed_database(&config, &mut db, true).await?;
             }
         }
+        Some("add-user") => {
+            let login = args
+                .next()
+                .ok_or_else(|| anyhow!("usage: collab add-user <login> [--admin]"))?;
+            let admin = args.next().as_deref() == Some("--admin");
+            let config = envy::from_env::<Config>().expect("error loading config");
+            if !config.is_self_hosted() {
+                Err(anyhow!(
+                    "users can only be added with passwords when SELF_HOSTED is set"
+                ))?;
+            }
+
+            eprintln!("Enter a password for {login}:");
+            let mut password = String::new();
+            std::io::stdin()
+                .read_line(&mut password)
+                .context("failed to read password")?;
+            let password = password.trim_end_matches(['\r', '\n']);
+            if password.is_empty() {
+                Err(anyhow!("password can't be empty"))?;
+            }
+
+            let db_options = db::ConnectOptions::new(config.database_url.clone());
+            let db = Database::new(db_options, Executor::Production).await?;
+            let user = db
+                .create_user_with_password(&login, &collab::auth::hash_password(password)?, admin)
+                .await?;
+            println!("added user {} with id {}", user.github_login, user.id);
+        }
         Some("serve") => {
             let mode = match args.next().as_deref() {
                 Some("collab") => ServiceMode::Collab,
@@ -77,7 +106,7 @@ async fn main() -> Result<()> {
                 Some("all") => ServiceMode::All,
                 _ => {
                     return Err(anyhow!(
-                        "usage: collab <version | migrate | seed | serve <api|collab|llm|all>>"
+                        "usage: collab <version | migrate | seed | add-user <login> [--admin] | serve <api|collab|llm|all>>"
                     ))?;
                 }
             };
@@ -96,7 +125,10 @@ async fn main() -> Result<()> {
 
             let mut on_shutdown = None;
 
-            if mode.is_llm() {
+            // Self-hosted servers don't provide any of Zed's hosted services.
+            let self_hosted = config.is_self_hosted();
+
+            if mode.is_llm() && !self_hosted {
                 setup_llm_database(&config).await?;
 
                 let state = LlmState::new(config.clone(), Executor::Production).await?;
@@ -134,7 +166,11 @@ async fn main() -> Result<()> {
                     let rpc_server = collab::rpc::Server::new(epoch, state.clone());
                     rpc_server.start().await?;
 
-                    poll_stripe_events_periodically(state.clone(), rpc_server.clone());
+                    if self_hosted {
+                        app = app.merge(collab::self_hosted::routes());
+                    } else {
+                        poll_stripe_events_periodically(state.clone(), rpc_server.clone());
+                    }
 
                     app = app
                         .merge(collab::api::routes(rpc_server.clone()))
@@ -143,7 +179,7 @@ async fn main() -> Result<()> {
                     on_shutdown = Some(Box::new(move || rpc_server.teardown()));
                 }
 
-                if mode.is_api() {
+                if mode.is_api() && !self_hosted {
                     fetch_extensions_from_blob_store_periodically(state.clone());
                     spawn_user_backfiller(state.clone());
 
@@ -258,7 +294,7 @@ async fn main() -> Result<()> {
         }
         _ => {
             Err(anyhow!(
-                "usage: collab <version | migrate | seed | serve <api|collab|llm|all>>"
+                "usage: collab <version | migrate | seed | add-user <login> [--admin] | serve <api|collab|llm|all>>"
             ))?;
         }
     }
@@ -269,16 +305,17 @@ async fn setup_app_database(config: &Config) -> Result<()> {
     let db_options = db::ConnectOptions::new(config.database_url.clone());
     let mut db = Database::new(db_options, Executor::Production).await?;
 
-    let migrations_path = config.migrations_path.as_deref().unwrap_or_else(|| {
-        #[cfg(feature = "sqlite")]
-        let default_migrations = concat!(env!("CARGO_MANIFEST_DIR"), "/migrations.sqlite");
-        #[cfg(not(feature = "sqlite"))]
-        let default_migrations = concat!(env!("CARGO_MANIFEST_DIR"), "/migrations");
-
-        Path::new(default_migrations)
-    });
+    let migrations = match config.migrations_path.as_deref() {
+        Some(migrations_path) => run_database_migrations(db.options(), migrations_path).await?,
+        None => {
+            #[cfg(feature = "sqlite")]
+            let migrator = &collab::migrations::SQLITE_MIGRATIONS;
+            #[cfg(not(feature = "sqlite"))]
+            let migrator = &collab::migrations::POSTGRES_MIGRATIONS;
 
-    let migrations = run_database_migrations(db.options(), migrations_path).await?;
+            run_embedded_database_migrations(db.options(), migrator).await?
+        }
+    };
     for (migration, duration) in migrations {
         log::info!(
             "Migrated {} {} {:?}",
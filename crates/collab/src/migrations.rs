@@ -4,10 +4,20 @@ use std::time::Duration;
 use anyhow::{anyhow, Result};
 use collections::HashMap;
 use sea_orm::ConnectOptions;
-use sqlx::migrate::{Migrate, Migration, MigrationSource};
+use sqlx::migrate::{Migrate, Migration, MigrationSource, Migrator};
 use sqlx::Connection;
 
-/// Runs the database migrations for the specified database.
+/// The migrations for the app database on Postgres, embedded in the binary so that it can
+/// migrate its database wherever it runs.
+pub static POSTGRES_MIGRATIONS: Migrator = sqlx::migrate!("./migrations");
+
+/// The migrations for the app database on SQLite, which is what self-hosted servers use.
+///
+/// Self-hosted servers apply these to their existing databases when they're upgraded, so
+/// schema changes must be added as new migrations rather than by editing existing ones.
+pub static SQLITE_MIGRATIONS: Migrator = sqlx::migrate!("./migrations.sqlite");
+
+/// Runs the database migrations in the given directory for the specified database.
 pub async fn run_database_migrations(
     database_options: &ConnectOptions,
     migrations_path: impl AsRef<Path>,
@@ -15,7 +25,21 @@ pub async fn run_database_migrations(
     let migrations = MigrationSource::resolve(migrations_path.as_ref())
         .await
         .map_err(|err| anyhow!("failed to load migrations: {err:?}"))?;
+    apply_migrations(database_options, migrations).await
+}
 
+/// Runs the given embedded database migrations for the specified database.
+pub async fn run_embedded_database_migrations(
+    database_options: &ConnectOptions,
+    migrator: &Migrator,
+) -> Result<Vec<(Migration, Duration)>> {
+    apply_migrations(database_options, migrator.iter().cloned().collect()).await
+}
+
+async fn apply_migrations(
+    database_options: &ConnectOptions,
+    migrations: Vec<Migration>,
+) -> Result<Vec<(Migration, Duration)>> {
     let mut connection = sqlx::AnyConnection::connect(database_options.get_url()).await?;
 
     connection.ensure_migrations_table().await?;
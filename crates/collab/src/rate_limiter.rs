@@ -12,10 +12,15 @@ pub trait RateLimit: Send + Sync {
     fn db_name(&self) -> &'static str;
 }
 
+/// How many buckets for keys other than users are kept before the ones that have refilled
+/// are forgotten.
+const MAX_KEYED_BUCKETS: usize = 10_000;
+
 /// Used to enforce per-user rate limits
 pub struct RateLimiter {
     buckets: DashMap<(UserId, String), RateBucket>,
     dirty_buckets: DashSet<(UserId, String)>,
+    keyed_buckets: DashMap<(String, &'static str), RateBucket>,
     db: Arc<Database>,
 }
 
@@ -24,6 +29,7 @@ impl RateLimiter {
         RateLimiter {
             buckets: DashMap::new(),
             dirty_buckets: DashSet::new(),
+            keyed_buckets: DashMap::new(),
             db,
         }
     }
@@ -73,9 +79,34 @@ impl RateLimiter {
             self.dirty_buckets.insert(bucket_key);
             Ok(())
         } else {
-            Err(rpc::proto::ErrorCode::RateLimitExceeded
-                .message("rate limit exceeded".into())
-                .anyhow())?
+            Err(rate_limit_exceeded())
+        }
+    }
+
+    /// Returns an error if the specified `RateLimit` has been exceeded for `key`.
+    ///
+    /// This is for requests that aren't made by a signed-in user. Their buckets are only kept
+    /// in memory, rather than being saved to the database.
+    pub fn check_key(&self, limit: &dyn RateLimit, key: &str) -> Result<()> {
+        self.check_key_internal(limit, key, Utc::now())
+    }
+
+    fn check_key_internal(&self, limit: &dyn RateLimit, key: &str, now: DateTimeUtc) -> Result<()> {
+        if self.keyed_buckets.len() >= MAX_KEYED_BUCKETS {
+            self.keyed_buckets.retain(|_, bucket| {
+                bucket.refill(now);
+                bucket.token_count < bucket.capacity
+            });
+        }
+
+        let mut bucket = self
+            .keyed_buckets
+            .entry((key.to_string(), limit.db_name()))
+            .or_insert_with(|| RateBucket::new(limit, now));
+        if bucket.value_mut().allow(now) {
+            Ok(())
+        } else {
+            Err(rate_limit_exceeded())
         }
     }
 
@@ -124,6 +155,13 @@ impl RateLimiter {
     }
 }
 
+fn rate_limit_exceeded() -> Error {
+    rpc::proto::ErrorCode::RateLimitExceeded
+        .message("rate limit exceeded".into())
+        .anyhow()
+        .into()
+}
+
 #[derive(Clone, Debug)]
 struct RateBucket {
     capacity: usize,
@@ -285,6 +323,43 @@ mod tests {
             .unwrap_err();
     }
 
+    #[gpui::test]
+    async fn test_keyed_rate_limiter(cx: &mut TestAppContext) {
+        let test_db = TestDb::sqlite(cx.executor().clone());
+        let rate_limiter = RateLimiter::new(test_db.db().clone());
+        let rate_limit_a = Box::new(RateLimitA);
+        let rate_limit_b = Box::new(RateLimitB);
+        let mut now = Utc::now();
+
+        // Key 1 can access resource A two times before being rate-limited.
+        rate_limiter
+            .check_key_internal(&*rate_limit_a, "key-1", now)
+            .unwrap();
+        rate_limiter
+            .check_key_internal(&*rate_limit_a, "key-1", now)
+            .unwrap();
+        rate_limiter
+            .check_key_internal(&*rate_limit_a, "key-1", now)
+            .unwrap_err();
+
+        // Key 2 can access resource A and key 1 can access resource B.
+        rate_limiter
+            .check_key_internal(&*rate_limit_a, "key-2", now)
+            .unwrap();
+        rate_limiter
+            .check_key_internal(&*rate_limit_b, "key-1", now)
+            .unwrap();
+
+        // After 1s, key 1 can make another request before being rate-limited again.
+        now += Duration::seconds(1);
+        rate_limiter
+            .check_key_internal(&*rate_limit_a, "key-1", now)
+            .unwrap();
+        rate_limiter
+            .check_key_internal(&*rate_limit_a, "key-1", now)
+            .unwrap_err();
+    }
+
     struct RateLimitA;
 
     impl RateLimit for RateLimitA {
@@ -11,7 +11,7 @@ use crate::{
         RespondToChannelInvite, RoomId, ServerId, UpdatedChannelMessage, User, UserId,
     },
     executor::Executor,
-    AppState, Config, Error, RateLimit, Result,
+    self_hosted, AppState, Config, Error, RateLimit, Result,
 };
 use anyhow::{anyhow, bail, Context as _};
 use async_tungstenite::tungstenite::{
@@ -83,6 +83,7 @@ pub const CLEANUP_TIMEOUT: Duration = Duration::from_secs(15);
 
 const MESSAGE_COUNT_PER_PAGE: usize = 100;
 const MAX_MESSAGE_LEN: usize = 1024;
+const MAX_REVIEW_EXCERPT_LEN: usize = 64 * 1024;
 const NOTIFICATION_COUNT_PER_PAGE: usize = 50;
 
 type MessageHandler =
@@ -288,6 +289,7 @@ impl Server {
             .add_request_handler(share_project)
             .add_message_handler(unshare_project)
             .add_request_handler(join_project)
+            .add_request_handler(reconnect_to_project)
             .add_message_handler(leave_project)
             .add_request_handler(update_project)
             .add_request_handler(update_worktree)
@@ -370,6 +372,10 @@ impl Server {
             .add_request_handler(update_channel_message)
             .add_request_handler(get_channel_messages)
             .add_request_handler(get_channel_messages_by_id)
+            .add_request_handler(get_review_threads)
+            .add_request_handler(create_review_thread)
+            .add_request_handler(add_review_comment)
+            .add_request_handler(set_review_thread_resolved)
             .add_request_handler(get_notifications)
             .add_request_handler(mark_notification_as_read)
             .add_request_handler(move_channel)
@@ -387,6 +393,10 @@ impl Server {
             .add_request_handler(forward_mutating_project_request::<proto::SynchronizeContexts>)
             .add_message_handler(broadcast_project_message_from_host::<proto::AdvertiseContexts>)
             .add_message_handler(update_context)
+            .add_message_handler(broadcast_terminal_message_from_host::<proto::AdvertiseTerminals>)
+            .add_message_handler(broadcast_terminal_message_from_host::<proto::UpdateTerminal>)
+            .add_request_handler(forward_read_only_project_request::<proto::OpenTerminal>)
+            .add_request_handler(forward_mutating_project_request::<proto::TerminalInput>)
             .add_request_handler({
                 let app_state = app_state.clone();
                 move |request, response, session| {
@@ -559,6 +569,10 @@ impl Server {
         Ok(())
     }
 
+    pub fn config(&self) -> &Config {
+        &self.app_state.config
+    }
+
     pub fn teardown(&self) {
         self.peer.teardown();
         self.connection_pool.lock().reset();
@@ -1049,13 +1063,17 @@ impl Header for AppVersionHeader {
 }
 
 pub fn routes(server: Arc<Server>) -> Router<(), Body> {
-    Router::new()
+    let mut router = Router::new()
         .route("/rpc", get(handle_websocket_request))
         .layer(
             ServiceBuilder::new()
                 .layer(Extension(server.app_state.clone()))
                 .layer(middleware::from_fn(auth::validate_header)),
-        )
+        );
+    if server.app_state.config.is_self_hosted() {
+        router = router.layer(middleware::from_fn(self_hosted::redirect_rpc_url_requests));
+    }
+    router
         .route("/metrics", get(handle_metrics))
         .layer(Extension(server))
 }
@@ -1425,65 +1443,71 @@ fn notify_rejoined_projects(
     }
 
     for project in rejoined_projects {
-        for worktree in mem::take(&mut project.worktrees) {
-            // Stream this worktree's entries.
-            let message = proto::UpdateWorktree {
-                project_id: project.id.to_proto(),
-                worktree_id: worktree.id,
-                abs_path: worktree.abs_path.clone(),
-                root_name: worktree.root_name,
-                updated_entries: worktree.updated_entries,
-                removed_entries: worktree.removed_entries,
-                scan_id: worktree.scan_id,
-                is_last_update: worktree.completed_scan_id == worktree.scan_id,
-                updated_repositories: worktree.updated_repositories,
-                removed_repositories: worktree.removed_repositories,
-            };
-            for update in proto::split_worktree_update(message) {
-                session.peer.send(session.connection_id, update.clone())?;
-            }
+        send_rejoined_project(project, session)?;
+    }
+    Ok(())
+}
 
-            // Stream this worktree's diagnostics.
-            for summary in worktree.diagnostic_summaries {
-                session.peer.send(
-                    session.connection_id,
-                    proto::UpdateDiagnosticSummary {
-                        project_id: project.id.to_proto(),
-                        worktree_id: worktree.id,
-                        summary: Some(summary),
-                    },
-                )?;
-            }
+/// Streams the changes to a project that a guest missed while they were disconnected.
+fn send_rejoined_project(project: &mut RejoinedProject, session: &Session) -> Result<()> {
+    for worktree in mem::take(&mut project.worktrees) {
+        // Stream this worktree's entries.
+        let message = proto::UpdateWorktree {
+            project_id: project.id.to_proto(),
+            worktree_id: worktree.id,
+            abs_path: worktree.abs_path.clone(),
+            root_name: worktree.root_name,
+            updated_entries: worktree.updated_entries,
+            removed_entries: worktree.removed_entries,
+            scan_id: worktree.scan_id,
+            is_last_update: worktree.completed_scan_id == worktree.scan_id,
+            updated_repositories: worktree.updated_repositories,
+            removed_repositories: worktree.removed_repositories,
+        };
+        for update in proto::split_worktree_update(message) {
+            session.peer.send(session.connection_id, update.clone())?;
+        }
 
-            for settings_file in worktree.settings_files {
-                session.peer.send(
-                    session.connection_id,
-                    proto::UpdateWorktreeSettings {
-                        project_id: project.id.to_proto(),
-                        worktree_id: worktree.id,
-                        path: settings_file.path,
-                        content: Some(settings_file.content),
-                        kind: Some(settings_file.kind.to_proto().into()),
-                    },
-                )?;
-            }
+        // Stream this worktree's diagnostics.
+        for summary in worktree.diagnostic_summaries {
+            session.peer.send(
+                session.connection_id,
+                proto::UpdateDiagnosticSummary {
+                    project_id: project.id.to_proto(),
+                    worktree_id: worktree.id,
+                    summary: Some(summary),
+                },
+            )?;
         }
 
-        for language_server in &project.language_servers {
+        for settings_file in worktree.settings_files {
             session.peer.send(
                 session.connection_id,
-                proto::UpdateLanguageServer {
+                proto::UpdateWorktreeSettings {
                     project_id: project.id.to_proto(),
-                    language_server_id: language_server.id,
-                    variant: Some(
-                        proto::update_language_server::Variant::DiskBasedDiagnosticsUpdated(
-                            proto::LspDiskBasedDiagnosticsUpdated {},
-                        ),
-                    ),
+                    worktree_id: worktree.id,
+                    path: settings_file.path,
+                    content: Some(settings_file.content),
+                    kind: Some(settings_file.kind.to_proto().into()),
                 },
             )?;
         }
     }
+
+    for language_server in &project.language_servers {
+        session.peer.send(
+            session.connection_id,
+            proto::UpdateLanguageServer {
+                project_id: project.id.to_proto(),
+                language_server_id: language_server.id,
+                variant: Some(
+                    proto::update_language_server::Variant::DiskBasedDiagnosticsUpdated(
+                        proto::LspDiskBasedDiagnosticsUpdated {},
+                    ),
+                ),
+            },
+        )?;
+    }
     Ok(())
 }
 
@@ -1793,6 +1817,72 @@ async fn join_project(
     join_project_internal(response, session, project, replica_id)
 }
 
+/// Reconnects a guest to a project they lost their connection to, with the replica id they had
+/// before, so that they can send the edits they made while offline.
+async fn reconnect_to_project(
+    request: proto::ReconnectToProject,
+    response: Response<proto::ReconnectToProject>,
+    session: Session,
+) -> Result<()> {
+    let project_id = ProjectId::from_proto(request.project_id);
+    let replica_id = ReplicaId(request.replica_id as i32);
+    tracing::info!(%project_id, %replica_id, "reconnect to project");
+
+    let reconnected_project = proto::RejoinProject {
+        id: request.project_id,
+        worktrees: request.worktrees,
+    };
+    let db = session.db().await;
+    let (project, role) = &mut *db
+        .reconnect_to_project(
+            &reconnected_project,
+            replica_id,
+            session.connection_id,
+            session.user_id(),
+        )
+        .await?;
+    drop(db);
+
+    response.send(proto::ReconnectToProjectResponse {
+        project: Some(project.to_proto()),
+        role: (*role).into(),
+    })?;
+
+    for collaborator in &project.collaborators {
+        if project.old_connection_id == session.connection_id {
+            session
+                .peer
+                .send(
+                    collaborator.connection_id,
+                    proto::AddProjectCollaborator {
+                        project_id: project_id.to_proto(),
+                        collaborator: Some(proto::Collaborator {
+                            peer_id: Some(session.connection_id.into()),
+                            replica_id: replica_id.0 as u32,
+                            user_id: session.user_id().to_proto(),
+                            is_host: false,
+                        }),
+                    },
+                )
+                .trace_err();
+        } else {
+            session
+                .peer
+                .send(
+                    collaborator.connection_id,
+                    proto::UpdateProjectCollaborator {
+                        project_id: project_id.to_proto(),
+                        old_peer_id: Some(project.old_connection_id.into()),
+                        new_peer_id: Some(session.connection_id.into()),
+                    },
+                )
+                .trace_err();
+        }
+    }
+
+    send_rejoined_project(project, &session)
+}
+
 trait JoinProjectInternalResponse {
     fn send(self, result: proto::JoinProjectResponse) -> Result<()>;
 }
@@ -2292,6 +2382,21 @@ async fn broadcast_project_message_from_host<T: EntityMessage<Entity = ShareProj
     Ok(())
 }
 
+/// Broadcast the state of the host's shared terminals to the project's guests. Unlike other
+/// project messages, these can only be sent by the host, since they control what the guests see.
+async fn broadcast_terminal_message_from_host<T: EntityMessage<Entity = ShareProject>>(
+    request: T,
+    session: Session,
+) -> Result<()> {
+    let project_id = ProjectId::from_proto(request.remote_entity_id());
+    session
+        .db()
+        .await
+        .check_user_is_project_host(project_id, session.connection_id)
+        .await?;
+    broadcast_project_message_from_host(request, session).await
+}
+
 /// Start following another user in a call.
 async fn follow(
     request: proto::Follow,
@@ -3923,6 +4028,134 @@ async fn get_channel_messages_by_id(
     Ok(())
 }
 
+/// Retrieve the review threads in a channel
+async fn get_review_threads(
+    request: proto::GetReviewThreads,
+    response: Response<proto::GetReviewThreads>,
+    session: Session,
+) -> Result<()> {
+    let threads = session
+        .db()
+        .await
+        .get_review_threads(ChannelId::from_proto(request.channel_id), session.user_id())
+        .await?;
+    response.send(proto::GetReviewThreadsResponse { threads })?;
+    Ok(())
+}
+
+/// Start a review thread on a range of a file
+async fn create_review_thread(
+    request: proto::CreateReviewThread,
+    response: Response<proto::CreateReviewThread>,
+    session: Session,
+) -> Result<()> {
+    let body = validate_review_comment(&request.body)?;
+    if request.start_offset > request.end_offset {
+        return Err(anyhow!("invalid range"))?;
+    }
+    if request.excerpt.len() > MAX_REVIEW_EXCERPT_LEN {
+        return Err(anyhow!("range is too large"))?;
+    }
+    let thread = session
+        .db()
+        .await
+        .create_review_thread(
+            ChannelId::from_proto(request.channel_id),
+            session.user_id(),
+            &request.path,
+            request.start_offset as usize..request.end_offset as usize,
+            request
+                .project_id
+                .map(ProjectId::from_proto)
+                .zip(request.anchor_range),
+            &request.excerpt,
+            &body,
+            OffsetDateTime::now_utc(),
+        )
+        .await?;
+    broadcast_review_thread(&thread, &session).await;
+    response.send(proto::ReviewThreadResponse {
+        thread: Some(thread),
+    })?;
+    Ok(())
+}
+
+/// Reply to a review thread
+async fn add_review_comment(
+    request: proto::AddReviewComment,
+    response: Response<proto::AddReviewComment>,
+    session: Session,
+) -> Result<()> {
+    let body = validate_review_comment(&request.body)?;
+    let thread = session
+        .db()
+        .await
+        .add_review_comment(
+            db::ReviewThreadId::from_proto(request.thread_id),
+            session.user_id(),
+            &body,
+            OffsetDateTime::now_utc(),
+        )
+        .await?;
+    broadcast_review_thread(&thread, &session).await;
+    response.send(proto::ReviewThreadResponse {
+        thread: Some(thread),
+    })?;
+    Ok(())
+}
+
+/// Resolve or reopen a review thread
+async fn set_review_thread_resolved(
+    request: proto::SetReviewThreadResolved,
+    response: Response<proto::SetReviewThreadResolved>,
+    session: Session,
+) -> Result<()> {
+    let thread = session
+        .db()
+        .await
+        .set_review_thread_resolved(
+            db::ReviewThreadId::from_proto(request.thread_id),
+            session.user_id(),
+            request.resolved,
+        )
+        .await?;
+    broadcast_review_thread(&thread, &session).await;
+    response.send(proto::ReviewThreadResponse {
+        thread: Some(thread),
+    })?;
+    Ok(())
+}
+
+fn validate_review_comment(body: &str) -> Result<String> {
+    let body = body.trim();
+    if body.len() > MAX_MESSAGE_LEN {
+        return Err(anyhow!("comment is too long"))?;
+    }
+    if body.is_empty() {
+        return Err(anyhow!("comment can't be blank"))?;
+    }
+    Ok(body.to_string())
+}
+
+async fn broadcast_review_thread(thread: &proto::ReviewThread, session: &Session) {
+    let channel_id = ChannelId::from_proto(thread.channel_id);
+    let pool = session.connection_pool().await;
+    broadcast(
+        Some(session.connection_id),
+        pool.channel_connection_ids(channel_id)
+            .map(|(connection_id, _)| connection_id),
+        |connection_id| {
+            session.peer.send(
+                connection_id,
+                proto::ReviewThreadUpdated {
+                    channel_id: thread.channel_id,
+                    thread: Some(thread.clone()),
+                },
+            )
+        },
+    );
+}
+
 /// Retrieve the current users notifications
 async fn get_notifications(
     request: proto::GetNotifications,
@@ -4425,6 +4658,7 @@ fn project_left(project: &db::LeftProject, session: &Session) {
                     proto::RemoveProjectCollaborator {
                         project_id: project.id.to_proto(),
                         peer_id: Some(session.connection_id.into()),
+                        can_reconnect: project.can_reconnect,
                     },
                 )
                 .trace_err();
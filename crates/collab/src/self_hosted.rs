@@ -0,0 +1,236 @@
+//! Support for running collab on your own infrastructure.
+//!
+//! Zed signs in by opening `/native_app_signin` on its server URL in the browser, which normally
+//! goes to zed.dev and signs in with GitHub. A self-hosted server is its own server URL, so it
+//! serves those pages itself and lets users sign in with the passwords they were given with
+//! `collab add-user`.
+
+use crate::{auth, AppState, Result};
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, Query},
+    http::{self, Request, StatusCode},
+    middleware::Next,
+    response::{Html, IntoResponse, Redirect, Response},
+    routing::get,
+    Extension, Form, Router,
+};
+use serde::Deserialize;
+use std::{net::SocketAddr, sync::Arc};
+
+pub fn routes() -> Router<(), Body> {
+    Router::new()
+        .route(
+            "/native_app_signin",
+            get(get_native_app_signin).post(post_native_app_signin),
+        )
+        .route(
+            "/native_app_signin_succeeded",
+            get(get_native_app_signin_succeeded),
+        )
+}
+
+/// Zed finds the URL to connect to by requesting `/rpc` from its server URL and following the
+/// redirect, so send those requests back to `/rpc`, where they'll be upgraded to a websocket.
+pub async fn redirect_rpc_url_requests<B>(req: Request<B>, next: Next<B>) -> Response {
+    if req.headers().contains_key(http::header::UPGRADE) {
+        next.run(req).await
+    } else {
+        Redirect::temporary("/rpc").into_response()
+    }
+}
+
+#[derive(Deserialize)]
+struct NativeAppSigninParams {
+    native_app_port: u16,
+    native_app_public_key: String,
+}
+
+#[derive(Deserialize)]
+struct NativeAppSigninForm {
+    native_app_port: u16,
+    native_app_public_key: String,
+    login: String,
+    password: String,
+}
+
+async fn get_native_app_signin(Query(params): Query<NativeAppSigninParams>) -> Result<Response> {
+    signin_page(&params, None, StatusCode::OK)
+}
+
+async fn post_native_app_signin(
+    Extension(app): Extension<Arc<AppState>>,
+    ConnectInfo(address): ConnectInfo<SocketAddr>,
+    Form(form): Form<NativeAppSigninForm>,
+) -> Result<Response> {
+    let params = NativeAppSigninParams {
+        native_app_port: form.native_app_port,
+        native_app_public_key: form.native_app_public_key,
+    };
+    let incorrect_password = || {
+        signin_page(
+            &params,
+            Some("Incorrect username or password."),
+            StatusCode::UNAUTHORIZED,
+        )
+    };
+    // Attempts are limited for each address and login, so that someone guessing a user's
+    // password can't stop them from signing in elsewhere. Logins without a user are limited
+    // too, so that the limit doesn't reveal which logins exist, and successful attempts count
+    // towards it, so that once it's reached, even the right password is refused.
+    let rate_limit_key = format!("{} {}", address.ip(), form.login);
+    if app
+        .rate_limiter
+        .check_key(&auth::PasswordSigninRateLimit, &rate_limit_key)
+        .is_err()
+    {
+        return signin_page(
+            &params,
+            Some("Too many sign-in attempts. Try again later."),
+            StatusCode::TOO_MANY_REQUESTS,
+        );
+    }
+    let user = app.db.get_user_by_github_login(&form.login).await?;
+    if !auth::verify_password(user.as_ref(), &form.password, &app.db).await? {
+        return incorrect_password();
+    }
+    let Some(user) = user else {
+        return incorrect_password();
+    };
+
+    let access_token = auth::create_access_token(&app.db, user.id, None).await?;
+    let encrypted_access_token =
+        auth::encrypt_access_token(&access_token, params.native_app_public_key)?;
+    // The encrypted token is URL-safe base64, so it doesn't need escaping.
+    Ok(Redirect::to(&format!(
+        "http://127.0.0.1:{}?user_id={}&access_token={}",
+        params.native_app_port, user.id, encrypted_access_token
+    ))
+    .into_response())
+}
+
+async fn get_native_app_signin_succeeded() -> Html<String> {
+    page(
+        "Signed in",
+        "<p>You're signed in to Zed. You can close this tab and return to the app.</p>",
+    )
+}
+
+fn signin_page(
+    params: &NativeAppSigninParams,
+    error: Option<&str>,
+    status: StatusCode,
+) -> Result<Response> {
+    // The public key is echoed back in the form, so make sure it's really a key, which is
+    // URL-safe base64 and can't contain any markup.
+    rpc::auth::PublicKey::try_from(params.native_app_public_key.clone()).map_err(|_| {
+        crate::Error::http(StatusCode::BAD_REQUEST, "invalid public key".to_string())
+    })?;
+
+    let error = error
+        .map(|error| format!("<p class=\"error\">{error}</p>"))
+        .unwrap_or_default();
+    let body = format!(
+        r#"{error}
+<form method="post" action="/native_app_signin">
+  <input type="hidden" name="native_app_port" value="{port}">
+  <input type="hidden" name="native_app_public_key" value="{public_key}">
+  <label>Username <input name="login" autocomplete="username" autofocus required></label>
+  <label>Password <input name="password" type="password" autocomplete="current-password" required></label>
+  <button type="submit">Sign in</button>
+</form>"#,
+        port = params.native_app_port,
+        public_key = params.native_app_public_key,
+    );
+    Ok((status, page("Sign in to Zed", &body)).into_response())
+}
+
+fn page(title: &str, body: &str) -> Html<String> {
+    Html(format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+  <meta charset="utf-8">
+  <title>{title}</title>
+  <style>
+    body {{ font-family: system-ui, sans-serif; max-width: 24rem; margin: 4rem auto; }}
+    label, button {{ display: block; margin-top: 1rem; }}
+    input {{ display: block; width: 100%; margin-top: 0.25rem; }}
+    .error {{ color: #c00; }}
+  </style>
+</head>
+<body>
+  <h1>{title}</h1>
+  {body}
+</body>
+</html>"#
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{db::TestDb, executor::Executor, Config, RateLimit, RateLimiter};
+
+    #[gpui::test]
+    async fn test_password_signin_rate_limit(cx: &mut gpui::TestAppContext) {
+        let test_db = TestDb::sqlite(cx.executor().clone());
+        let db = test_db.db().clone();
+        db.create_user_with_password("alice", &auth::hash_password("hunter2").unwrap(), false)
+            .await
+            .unwrap();
+        let app = Arc::new(AppState {
+            db: db.clone(),
+            llm_db: None,
+            live_kit_client: None,
+            blob_store_client: None,
+            stripe_client: None,
+            stripe_billing: None,
+            rate_limiter: Arc::new(RateLimiter::new(db)),
+            executor: Executor::Deterministic(cx.executor()),
+            clickhouse_client: None,
+            kinesis_client: None,
+            config: Config::test(),
+        });
+
+        let (public_key, _) = rpc::auth::keypair().unwrap();
+        let public_key = String::try_from(public_key).unwrap();
+        let sign_in = |address: [u8; 4], login: &str, password: &str| {
+            post_native_app_signin(
+                Extension(app.clone()),
+                ConnectInfo(SocketAddr::from((address, 1234))),
+                Form(NativeAppSigninForm {
+                    native_app_port: 1234,
+                    native_app_public_key: public_key.clone(),
+                    login: login.into(),
+                    password: password.into(),
+                }),
+            )
+        };
+
+        let address_1 = [10, 0, 0, 1];
+        let address_2 = [10, 0, 0, 2];
+        let response = sign_in(address_1, "alice", "hunter2").await.unwrap();
+        assert!(response.status().is_redirection());
+        for _ in 0..auth::PasswordSigninRateLimit.capacity() - 1 {
+            let response = sign_in(address_1, "alice", "hunter3").await.unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        // Once the limit is reached, even the right password is refused.
+        let response = sign_in(address_1, "alice", "hunter2").await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        // The user can still sign in from elsewhere.
+        let response = sign_in(address_2, "alice", "hunter2").await.unwrap();
+        assert!(response.status().is_redirection());
+
+        // Logins without a user are limited in the same way.
+        for _ in 0..auth::PasswordSigninRateLimit.capacity() {
+            let response = sign_in(address_1, "bob", "hunter2").await.unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+        let response = sign_in(address_1, "bob", "hunter2").await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+}
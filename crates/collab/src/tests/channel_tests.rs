@@ -12,7 +12,9 @@ use rpc::{
     proto::{self, ChannelRole},
     RECEIVE_TIMEOUT,
 };
+use serde_json::json;
 use std::sync::Arc;
+use text::OffsetRangeExt;
 
 #[gpui::test]
 async fn test_core_channels(
@@ -1392,6 +1394,117 @@ async fn test_channel_moving(
     );
 }
 
+#[gpui::test]
+async fn test_review_threads_stay_attached_to_edited_ranges(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let channel_id = server
+        .make_channel("zed", None, (&client_a, cx_a), &mut [(&client_b, cx_b)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| call.join_channel(channel_id, cx))
+        .await
+        .unwrap();
+    active_call_b
+        .update(cx_b, |call, cx| call.join_channel(channel_id, cx))
+        .await
+        .unwrap();
+
+    client_a
+        .fs()
+        .insert_tree("/a", json!({ "main.rs": "fn a() {}\nfn b() {}\n" }))
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/a", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+    let buffer_a = project_a
+        .update(cx_a, |project, cx| {
+            project.open_buffer((worktree_id, "main.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let buffer_b = project_b
+        .update(cx_b, |project, cx| {
+            project.open_buffer((worktree_id, "main.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let review_threads_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_review_threads(channel_id, cx))
+        .await
+        .unwrap();
+    let review_threads_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| store.open_review_threads(channel_id, cx))
+        .await
+        .unwrap();
+
+    // Client A comments on the second function.
+    let snapshot = buffer_a.read_with(cx_a, |buffer, _| buffer.text_snapshot());
+    let thread_id = review_threads_a
+        .update(cx_a, |review_threads, cx| {
+            review_threads.create_thread(
+                "a/main.rs".into(),
+                Some(project_id),
+                &snapshot,
+                10..19,
+                "Should this be public?".into(),
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // Client B inserts a copy of the function before the original, and renames the original.
+    buffer_b.update(cx_b, |buffer, cx| {
+        buffer.edit([(0..0, "fn b() {}\n"), (13..14, "c")], None, cx)
+    });
+    executor.run_until_parked();
+    assert_eq!(
+        buffer_a.read_with(cx_a, |buffer, _| buffer.text()),
+        "fn b() {}\nfn a() {}\nfn c() {}\n"
+    );
+
+    // Both clients find the thread on the renamed function, rather than on the copy.
+    for (review_threads, buffer, cx) in [
+        (&review_threads_a, &buffer_a, &*cx_a),
+        (&review_threads_b, &buffer_b, &*cx_b),
+    ] {
+        let range = cx.read(|cx| {
+            let thread = review_threads.read(cx).thread(thread_id).unwrap();
+            let buffer = buffer.read(cx).text_snapshot();
+            thread
+                .locate(&buffer, Some(project_id))
+                .unwrap()
+                .to_offset(&buffer)
+        });
+        assert_eq!(range, 20..29);
+    }
+
+    // Outside of the shared project, the anchors can't be used, so the closest copy of the
+    // original text is found instead.
+    let range = cx_b.read(|cx| {
+        let thread = review_threads_b.read(cx).thread(thread_id).unwrap();
+        let buffer = buffer_b.read(cx).text_snapshot();
+        thread.locate(&buffer, None).unwrap().to_offset(&buffer)
+    });
+    assert_eq!(range, 0..9);
+}
+
 #[derive(Debug, PartialEq)]
 struct ExpectedChannel {
     depth: usize,
@@ -15,8 +15,8 @@ use fs::{FakeFs, Fs as _, RemoveOptions};
 use futures::{channel::mpsc, StreamExt as _};
 use git::repository::GitFileStatus;
 use gpui::{
-    px, size, AppContext, BackgroundExecutor, Model, Modifiers, MouseButton, MouseDownEvent,
-    TestAppContext, UpdateGlobal,
+    px, size, AppContext, BackgroundExecutor, Context as _, Model, Modifiers, MouseButton,
+    MouseDownEvent, TestAppContext, UpdateGlobal, VisualContext as _,
 };
 use language::{
     language_settings::{
@@ -30,8 +30,8 @@ use lsp::LanguageServerId;
 use parking_lot::Mutex;
 use project::lsp_store::FormatTarget;
 use project::{
-    lsp_store::FormatTrigger, search::SearchQuery, search::SearchResult, DiagnosticSummary,
-    HoverBlockKind, Project, ProjectPath,
+    lsp_store::FormatTrigger, search::SearchQuery, search::SearchResult,
+    terminal_sharing::TerminalAccess, DiagnosticSummary, HoverBlockKind, Project, ProjectPath,
 };
 use rand::prelude::*;
 use serde_json::json;
@@ -47,8 +47,10 @@ use std::{
     },
     time::Duration,
 };
+use terminal::{Terminal, TerminalBuilder};
+use terminal_view::{terminal_panel::TerminalPanel, TerminalView};
 use unindent::Unindent as _;
-use workspace::Pane;
+use workspace::{dock::Panel as _, Pane};
 
 #[ctor::ctor]
 fn init_logger() {
@@ -3831,6 +3833,94 @@ async fn test_leaving_project(
     });
 }
 
+#[gpui::test(iterations = 10)]
+async fn test_editing_offline(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree("/a", json!({ "a.txt": "a-contents" }))
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/a", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+
+    let buffer_a = project_a
+        .update(cx_a, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+    let buffer_b = project_b
+        .update(cx_b, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+
+    // Client B loses their connection for long enough to be removed from the call.
+    client_b.wait_for_current_user(cx_b).await;
+    server.forbid_connections();
+    server.disconnect_client(client_b.peer_id().unwrap());
+    executor.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    executor.run_until_parked();
+
+    project_a.read_with(cx_a, |project, _| {
+        assert_eq!(project.collaborators().len(), 0);
+    });
+
+    // Client B can keep editing their open buffers while offline.
+    project_b.read_with(cx_b, |project, cx| {
+        assert!(project.is_offline());
+        assert!(!project.is_read_only(cx));
+    });
+    buffer_b.update(cx_b, |buffer, cx| {
+        buffer.edit([(0..0, "offline-")], None, cx)
+    });
+    executor.run_until_parked();
+    buffer_a.read_with(cx_a, |buffer, _| assert_eq!(buffer.text(), "a-contents"));
+
+    // When client B rejoins the call, their edits are sent to the host.
+    server.allow_connections();
+    executor.advance_clock(RECEIVE_TIMEOUT);
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_b.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    active_call_b
+        .update(cx_b, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    project_b.read_with(cx_b, |project, cx| {
+        assert!(!project.is_offline());
+        assert!(!project.is_disconnected(cx));
+    });
+    project_a.read_with(cx_a, |project, _| {
+        assert_eq!(project.collaborators().len(), 1);
+    });
+    buffer_a.read_with(cx_a, |buffer, _| {
+        assert_eq!(buffer.text(), "offline-a-contents")
+    });
+    buffer_b.read_with(cx_b, |buffer, _| {
+        assert_eq!(buffer.text(), "offline-a-contents")
+    });
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_collaborating_with_diagnostics(
     executor: BackgroundExecutor,
@@ -6684,3 +6774,159 @@ async fn test_remote_git_branches(
 
     assert_eq!(host_branch.as_ref(), "totally-new-branch");
 }
+
+#[gpui::test(iterations = 10)]
+async fn test_terminal_sharing(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree("/a", json!({ "a.txt": "a-contents" }))
+        .await;
+    let (project_a, _) = client_a.build_local_project("/a", cx_a).await;
+    active_call_a
+        .update(cx_a, |call, cx| call.set_location(Some(&project_a), cx))
+        .await
+        .unwrap();
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+    active_call_b
+        .update(cx_b, |call, cx| call.set_location(Some(&project_b), cx))
+        .await
+        .unwrap();
+
+    let (workspace_a, cx_a) = client_a.build_workspace(&project_a, cx_a);
+    let (workspace_b, cx_b) = client_b.build_workspace(&project_b, cx_b);
+    let panel_a = workspace_a.update(cx_a, |workspace, cx| {
+        let panel = cx.new_view(|cx| TerminalPanel::new(workspace, cx));
+        workspace.add_panel(panel.clone(), cx);
+        panel
+    });
+    let panel_b = workspace_b.update(cx_b, |workspace, cx| {
+        let panel = cx.new_view(|cx| TerminalPanel::new(workspace, cx));
+        workspace.add_panel(panel.clone(), cx);
+        panel
+    });
+
+    fn screen_text(terminal: &Terminal) -> String {
+        let lines = terminal
+            .screen()
+            .lines
+            .iter()
+            .map(|line| line.iter().map(|cell| cell.c).collect::<String>())
+            .collect::<Vec<_>>();
+        lines
+            .iter()
+            .map(|line| line.trim_end())
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim_end()
+            .to_string()
+    }
+
+    // Client A opens a terminal in their terminal panel and shares it.
+    let (input_tx, mut input_rx) = mpsc::unbounded();
+    let terminal_a = cx_a.new_model(|cx| {
+        let mut terminal = TerminalBuilder::new_fake("zsh".into(), input_tx).subscribe(cx);
+        terminal.write_output(b"$ echo one\r\none\r\n$ ", cx);
+        terminal
+    });
+    let terminal_view_a = cx_a
+        .new_view(|cx| TerminalView::new(terminal_a.clone(), workspace_a.downgrade(), None, cx));
+    workspace_a.update(cx_a, |workspace, cx| {
+        let pane = panel_a.read(cx).pane().unwrap();
+        pane.update(cx, |pane, cx| {
+            pane.add_item(Box::new(terminal_view_a.clone()), true, true, None, cx)
+        });
+        workspace.toggle_panel_focus::<TerminalPanel>(cx);
+    });
+    let terminal_id = project_a
+        .update(cx_a, |project, cx| {
+            project.share_terminal(&terminal_a, TerminalAccess::ReadWrite, cx)
+        })
+        .unwrap();
+    executor.run_until_parked();
+
+    project_b.read_with(cx_b, |project, _| {
+        let terminals = project.remote_terminals().collect::<Vec<_>>();
+        assert_eq!(terminals.len(), 1);
+        assert_eq!(terminals[0].id, terminal_id);
+        assert_eq!(terminals[0].title, "zsh");
+        assert_eq!(terminals[0].access, TerminalAccess::ReadWrite);
+    });
+
+    // Client B follows client A into their terminal panel, and sees the shared terminal.
+    let peer_id_a = client_a.peer_id().unwrap();
+    workspace_b.update(cx_b, |workspace, cx| workspace.follow(peer_id_a, cx));
+    executor.run_until_parked();
+
+    let pane_b = panel_b.update(cx_b, |panel, _| panel.pane().unwrap());
+    let terminal_view_b = pane_b.update(cx_b, |pane, _| {
+        pane.active_item()
+            .and_then(|item| item.downcast::<TerminalView>())
+            .expect("follower should open the shared terminal")
+    });
+    let terminal_b = terminal_view_b.update(cx_b, |view, _| view.terminal().clone());
+    project_b.read_with(cx_b, |project, cx| {
+        assert!(terminal_b.read(cx).is_remote());
+        assert_eq!(
+            project.shared_terminal_id(&terminal_b, cx),
+            Some(terminal_id)
+        );
+    });
+    terminal_b.read_with(cx_b, |terminal, _| {
+        assert_eq!(screen_text(terminal), "$ echo one\none\n$");
+    });
+
+    // Output on the host's terminal is streamed to the guest.
+    terminal_a.update(cx_a, |terminal, cx| {
+        terminal.write_output(b"echo two\r\ntwo\r\n$ ", cx)
+    });
+    executor.advance_clock(Duration::from_millis(50));
+    executor.run_until_parked();
+    let host_text = terminal_a.read_with(cx_a, |terminal, _| screen_text(terminal));
+    assert_eq!(host_text, "$ echo one\none\n$ echo two\ntwo\n$");
+    terminal_b.read_with(cx_b, |terminal, _| {
+        assert_eq!(screen_text(terminal), host_text);
+    });
+
+    // What the guest types is sent to the host's terminal.
+    terminal_b.update(cx_b, |terminal, _| terminal.input("pwd\r".into()));
+    executor.run_until_parked();
+    assert_eq!(input_rx.next().await.unwrap(), b"pwd\r");
+
+    // When the host stops sharing the terminal, the guest's copy is closed, but the host's
+    // terminal stays open.
+    project_a.update(cx_a, |project, cx| {
+        project.unshare_terminal(terminal_id, cx)
+    });
+    executor.run_until_parked();
+
+    project_b.read_with(cx_b, |project, _| {
+        assert_eq!(project.remote_terminals().count(), 0);
+    });
+    pane_b.update(cx_b, |pane, _| {
+        assert!(pane.index_for_item(&terminal_view_b).is_none());
+    });
+    project_a.read_with(cx_a, |project, _| {
+        assert_eq!(project.terminal_access(&terminal_a), None);
+    });
+    panel_a.update(cx_a, |panel, cx| {
+        let pane = panel.pane().unwrap();
+        assert!(pane.read(cx).index_for_item(&terminal_view_a).is_some());
+    });
+}
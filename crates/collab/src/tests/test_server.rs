@@ -296,6 +296,7 @@ impl TestServer {
             notifications::init(client.clone(), user_store, cx);
             collab_ui::init(&app_state, cx);
             file_finder::init(cx);
+            terminal_view::init(cx);
             menu::init();
             settings::KeymapFile::load_asset(os_keymap, cx).unwrap();
             language_model::LanguageModelRegistry::test(cx);
@@ -552,6 +553,7 @@ impl TestServer {
                 stripe_api_key: None,
                 supermaven_admin_api_key: None,
                 user_backfiller_github_access_token: None,
+                self_hosted: None,
                 kinesis_region: None,
                 kinesis_stream: None,
                 kinesis_access_key: None,
@@ -73,11 +73,11 @@ impl UserBackfiller {
             self.db.get_users_missing_github_user_created_at().await?;
 
         for user in users_missing_github_user_created_at {
+            let Some(github_user_id) = user.github_user_id else {
+                continue;
+            };
             match self
-                .fetch_github_user(&format!(
-                    "https://api.github.com/user/{}",
-                    user.github_user_id
-                ))
+                .fetch_github_user(&format!("https://api.github.com/user/{github_user_id}"))
                 .await
             {
                 Ok(github_user) => {
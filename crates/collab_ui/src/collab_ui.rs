@@ -4,6 +4,8 @@ pub mod collab_panel;
 pub mod notification_panel;
 pub mod notifications;
 mod panel_settings;
+pub mod review_threads;
+pub mod terminal_sharing;
 
 use std::{rc::Rc, sync::Arc};
 
@@ -32,6 +34,8 @@ pub fn init(app_state: &Arc<AppState>, cx: &mut AppContext) {
     collab_panel::init(cx);
     notification_panel::init(cx);
     notifications::init(app_state, cx);
+    review_threads::init(cx);
+    terminal_sharing::init(cx);
     title_bar::init(cx);
 }
 
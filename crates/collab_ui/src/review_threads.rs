@@ -0,0 +1,697 @@
+use anyhow::anyhow;
+use call::ActiveCall;
+use channel::{ChannelStore, ReviewThread, ReviewThreads, ReviewThreadsEvent};
+use client::ChannelId;
+use collections::{HashMap, HashSet};
+use editor::{
+    display_map::{
+        BlockContext, BlockPlacement, BlockProperties, BlockStyle, CustomBlockId, RenderBlock,
+    },
+    Anchor, Bias, Editor, EditorEvent, EditorMode, ToOffset,
+};
+use gpui::{
+    actions, AppContext, FontWeight, Model, Render, Subscription, Task, View, ViewContext,
+    VisualContext as _, WeakView,
+};
+use std::{ops::Range, sync::Arc};
+use time::{OffsetDateTime, UtcOffset};
+use ui::{prelude::*, Avatar, Tooltip};
+use util::ResultExt;
+use workspace::{notifications::NotifyTaskExt, Workspace};
+
+actions!(collab, [StartReviewThread]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|editor: &mut Editor, cx| EditorReviewThreads::register(editor, cx))
+        .detach();
+}
+
+#[derive(Clone)]
+struct ReviewThreadsAddon {
+    _view: View<EditorReviewThreads>,
+}
+
+impl editor::Addon for ReviewThreadsAddon {
+    fn to_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Shows the review threads that were started on an editor's file in the channel of the
+/// current call, and lets the user start new ones.
+struct EditorReviewThreads {
+    editor: WeakView<Editor>,
+    channel_id: Option<ChannelId>,
+    review_threads: Option<Model<ReviewThreads>>,
+    displayed_threads: HashMap<u64, DisplayedThread>,
+    draft: Option<DraftThread>,
+    local_timezone: UtcOffset,
+    _open_review_threads: Task<()>,
+    _review_threads_subscription: Option<Subscription>,
+    _project_subscription: Option<Subscription>,
+    _subscriptions: Vec<Subscription>,
+}
+
+struct DisplayedThread {
+    /// Where the thread is in the editor. Once a thread has been found in the file, it
+    /// stays anchored to that range as the file is edited.
+    range: Range<Anchor>,
+    /// Whether the text the thread was started on is no longer in the file.
+    outdated: bool,
+    resolved: bool,
+    block_id: CustomBlockId,
+    reply_editor: View<Editor>,
+}
+
+struct DraftThread {
+    range: Range<Anchor>,
+    block_id: CustomBlockId,
+    editor: View<Editor>,
+}
+
+enum OpenReviewThreadGutter {}
+
+impl Render for EditorReviewThreads {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        gpui::Empty
+    }
+}
+
+impl EditorReviewThreads {
+    fn register(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+        if editor.mode() != EditorMode::Full || editor.buffer().read(cx).as_singleton().is_none() {
+            return;
+        }
+        let Some(active_call) = ActiveCall::try_global(cx) else {
+            return;
+        };
+
+        let editor_view = cx.view().clone();
+        let view = cx.new_view(|cx| {
+            let local_offset = chrono::Local::now().offset().local_minus_utc();
+            // The editor is still being constructed, so look for threads once it's done.
+            cx.defer(|this, cx| this.update_channel(cx));
+            Self {
+                editor: editor_view.downgrade(),
+                channel_id: None,
+                review_threads: None,
+                displayed_threads: HashMap::default(),
+                draft: None,
+                local_timezone: UtcOffset::from_whole_seconds(local_offset).unwrap(),
+                _open_review_threads: Task::ready(()),
+                _review_threads_subscription: None,
+                _project_subscription: None,
+                _subscriptions: vec![
+                    cx.observe(&active_call, |this, _, cx| this.update_channel(cx)),
+                    cx.subscribe(&editor_view, |this, _, event: &EditorEvent, cx| {
+                        // The file may have been renamed.
+                        if let EditorEvent::TitleChanged = event {
+                            this.refresh_threads(cx);
+                        }
+                    }),
+                ],
+            }
+        });
+
+        view.update(cx, |_, cx| {
+            let subscription = editor.register_action(cx.listener(Self::start_thread));
+            cx.on_release(|_, _, _| drop(subscription)).detach();
+        });
+        editor.register_addon(ReviewThreadsAddon { _view: view });
+    }
+
+    fn workspace(&self, cx: &mut ViewContext<Self>) -> Option<View<Workspace>> {
+        cx.window_handle()
+            .downcast::<Workspace>()
+            .and_then(|handle| handle.root(cx).ok())
+    }
+
+    /// The remote id of the shared project that the editor's buffer belongs to.
+    fn project_id(&self, cx: &mut ViewContext<Self>) -> Option<u64> {
+        self.workspace(cx)?.read(cx).project().read(cx).remote_id()
+    }
+
+    /// Returns the path that identifies the editor's file in review threads, which starts
+    /// with the name of its worktree so that it's the same for every collaborator.
+    fn path(&self, cx: &AppContext) -> Option<Arc<str>> {
+        let editor = self.editor.upgrade()?;
+        let buffer = editor.read(cx).buffer().read(cx).as_singleton()?;
+        let path = buffer.read(cx).file()?.full_path(cx);
+        let path = path
+            .iter()
+            .map(|component| component.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        Some(path.into())
+    }
+
+    /// Opens the review threads of the current call's channel, if this editor's project is
+    /// shared in it.
+    fn update_channel(&mut self, cx: &mut ViewContext<Self>) {
+        let project = self
+            .workspace(cx)
+            .map(|workspace| workspace.read(cx).project().clone());
+        if let Some(project) = project
+            .as_ref()
+            .filter(|_| self._project_subscription.is_none())
+        {
+            self._project_subscription = Some(cx.subscribe(project, |this, _, event, cx| {
+                if let project::Event::RemoteIdChanged(_) | project::Event::DisconnectedFromHost =
+                    event
+                {
+                    this.update_channel(cx);
+                }
+            }));
+        }
+
+        let channel_id = project
+            .filter(|project| project.read(cx).remote_id().is_some())
+            .and_then(|_| ActiveCall::try_global(cx)?.read(cx).channel_id(cx));
+        if channel_id == self.channel_id {
+            return;
+        }
+
+        self.channel_id = channel_id;
+        self.review_threads = None;
+        self._review_threads_subscription = None;
+        self.clear_threads(cx);
+        let Some(channel_id) = channel_id else {
+            self._open_review_threads = Task::ready(());
+            return;
+        };
+
+        let open_review_threads = ChannelStore::global(cx)
+            .update(cx, |store, cx| store.open_review_threads(channel_id, cx));
+        self._open_review_threads = cx.spawn(|this, mut cx| async move {
+            let Some(review_threads) = open_review_threads.await.log_err() else {
+                return;
+            };
+            this.update(&mut cx, |this, cx| {
+                if this.channel_id != Some(channel_id) {
+                    return;
+                }
+                this._review_threads_subscription = Some(cx.subscribe(
+                    &review_threads,
+                    |this, _, event, cx| match event {
+                        ReviewThreadsEvent::ThreadUpdated { thread_id } => {
+                            this.display_thread(*thread_id, cx);
+                            this.update_gutter(cx);
+                        }
+                        ReviewThreadsEvent::ThreadsReloaded => this.refresh_threads(cx),
+                    },
+                ));
+                this.review_threads = Some(review_threads);
+                this.refresh_threads(cx);
+            })
+            .ok();
+        });
+    }
+
+    fn refresh_threads(&mut self, cx: &mut ViewContext<Self>) {
+        let (Some(review_threads), Some(path)) = (self.review_threads.clone(), self.path(cx))
+        else {
+            self.clear_threads(cx);
+            return;
+        };
+
+        let thread_ids = review_threads
+            .read(cx)
+            .threads_for_path(&path)
+            .map(|thread| thread.id)
+            .collect::<HashSet<_>>();
+        let stale_thread_ids = self
+            .displayed_threads
+            .keys()
+            .filter(|thread_id| !thread_ids.contains(thread_id))
+            .copied()
+            .collect::<Vec<_>>();
+        for thread_id in stale_thread_ids {
+            self.remove_thread(thread_id, cx);
+        }
+        for thread_id in thread_ids {
+            self.display_thread(thread_id, cx);
+        }
+        self.update_gutter(cx);
+    }
+
+    fn clear_threads(&mut self, cx: &mut ViewContext<Self>) {
+        let mut block_ids = self
+            .displayed_threads
+            .drain()
+            .map(|(_, thread)| thread.block_id)
+            .collect::<HashSet<_>>();
+        block_ids.extend(self.draft.take().map(|draft| draft.block_id));
+        if let Some(editor) = self.editor.upgrade() {
+            editor.update(cx, |editor, cx| {
+                editor.remove_blocks(block_ids, None, cx);
+                editor.clear_gutter_highlights::<OpenReviewThreadGutter>(cx);
+            });
+        }
+    }
+
+    fn remove_thread(&mut self, thread_id: u64, cx: &mut ViewContext<Self>) {
+        let (Some(thread), Some(editor)) = (
+            self.displayed_threads.remove(&thread_id),
+            self.editor.upgrade(),
+        ) else {
+            return;
+        };
+        editor.update(cx, |editor, cx| {
+            editor.remove_blocks(HashSet::from_iter([thread.block_id]), None, cx);
+        });
+    }
+
+    /// Renders the given thread below the range it's about, or re-renders it if it's
+    /// already displayed.
+    fn display_thread(&mut self, thread_id: u64, cx: &mut ViewContext<Self>) {
+        let (Some(review_threads), Some(editor)) =
+            (self.review_threads.clone(), self.editor.upgrade())
+        else {
+            return;
+        };
+        let Some(thread) = review_threads.read(cx).thread(thread_id).cloned() else {
+            return;
+        };
+        if Some(&thread.path) != self.path(cx).as_ref() {
+            self.remove_thread(thread_id, cx);
+            return;
+        }
+
+        let height = thread_block_height(&thread);
+        if let Some(displayed) = self.displayed_threads.get_mut(&thread_id) {
+            displayed.resolved = thread.resolved;
+            let block_id = displayed.block_id;
+            let render = self.render_thread(thread, cx);
+            editor.update(cx, |editor, cx| {
+                editor.resize_blocks(HashMap::from_iter([(block_id, height)]), None, cx);
+                editor.replace_blocks(HashMap::from_iter([(block_id, render)]), None, cx);
+            });
+            return;
+        }
+
+        let project_id = self.project_id(cx);
+        let located = {
+            let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+            snapshot.as_singleton().and_then(|(excerpt_id, _, buffer)| {
+                let (range, outdated) = match thread.locate(buffer, project_id) {
+                    Some(range) => (range, false),
+                    None => {
+                        let offset = buffer.clip_offset(thread.range.start, Bias::Left);
+                        let anchor = buffer.anchor_before(offset);
+                        (anchor..anchor, true)
+                    }
+                };
+                let start = snapshot.anchor_in_excerpt(*excerpt_id, range.start)?;
+                let end = snapshot.anchor_in_excerpt(*excerpt_id, range.end)?;
+                Some((start..end, outdated))
+            })
+        };
+        let Some((range, outdated)) = located else {
+            return;
+        };
+
+        let reply_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Reply…", cx);
+            editor
+        });
+        self.displayed_threads.insert(
+            thread_id,
+            DisplayedThread {
+                range: range.clone(),
+                outdated,
+                resolved: thread.resolved,
+                block_id: CustomBlockId(0),
+                reply_editor,
+            },
+        );
+        let render = self.render_thread(thread, cx);
+        let block_id = editor.update(cx, |editor, cx| {
+            editor.insert_blocks(
+                [BlockProperties {
+                    placement: BlockPlacement::Below(range.end),
+                    height,
+                    style: BlockStyle::Flex,
+                    render,
+                    priority: 0,
+                }],
+                None,
+                cx,
+            )[0]
+        });
+        if let Some(displayed) = self.displayed_threads.get_mut(&thread_id) {
+            displayed.block_id = block_id;
+        }
+    }
+
+    fn update_gutter(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(editor) = self.editor.upgrade() else {
+            return;
+        };
+        let ranges = self
+            .displayed_threads
+            .values()
+            .filter(|thread| !thread.resolved)
+            .map(|thread| thread.range.clone())
+            .collect::<Vec<_>>();
+        editor.update(cx, |editor, cx| {
+            editor.highlight_gutter::<OpenReviewThreadGutter>(
+                &ranges,
+                |cx| cx.theme().status().info,
+                cx,
+            );
+        });
+    }
+
+    fn start_thread(&mut self, _: &StartReviewThread, cx: &mut ViewContext<Self>) {
+        if self.review_threads.is_none() {
+            if let Some(workspace) = self.workspace(cx) {
+                workspace.update(cx, |workspace, cx| {
+                    workspace.show_error(
+                        &anyhow!("Share this project in a channel's call to start a review thread"),
+                        cx,
+                    )
+                });
+            }
+            return;
+        }
+        let Some(editor) = self.editor.upgrade() else {
+            return;
+        };
+
+        self.cancel_draft(cx);
+        let selection = editor.read(cx).selections.newest_anchor().clone();
+        let range = selection.start..selection.end;
+        let draft_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Leave a comment…", cx);
+            editor
+        });
+        let render = self.render_draft(draft_editor.clone(), cx);
+        let block_id = editor.update(cx, |editor, cx| {
+            editor.insert_blocks(
+                [BlockProperties {
+                    placement: BlockPlacement::Below(range.end),
+                    height: 3,
+                    style: BlockStyle::Flex,
+                    render,
+                    priority: 0,
+                }],
+                None,
+                cx,
+            )[0]
+        });
+        cx.focus_view(&draft_editor);
+        self.draft = Some(DraftThread {
+            range,
+            block_id,
+            editor: draft_editor,
+        });
+    }
+
+    fn submit_draft(&mut self, cx: &mut ViewContext<Self>) {
+        let (Some(draft), Some(review_threads), Some(editor), Some(path)) = (
+            self.draft.as_ref(),
+            self.review_threads.clone(),
+            self.editor.upgrade(),
+            self.path(cx),
+        ) else {
+            return;
+        };
+        let body = draft.editor.read(cx).text(cx);
+        let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+            return;
+        };
+        let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+        let range = draft.range.start.to_offset(&snapshot)..draft.range.end.to_offset(&snapshot);
+        let buffer = buffer.read(cx).snapshot();
+        let project_id = self.project_id(cx);
+
+        let create_thread = review_threads.update(cx, |review_threads, cx| {
+            review_threads.create_thread(path, project_id, &buffer, range, body, cx)
+        });
+        cx.spawn(|this, mut cx| async move {
+            create_thread.await?;
+            this.update(&mut cx, |this, cx| this.cancel_draft(cx))?;
+            anyhow::Ok(())
+        })
+        .detach_and_notify_err(cx);
+    }
+
+    fn cancel_draft(&mut self, cx: &mut ViewContext<Self>) {
+        let (Some(draft), Some(editor)) = (self.draft.take(), self.editor.upgrade()) else {
+            return;
+        };
+        editor.update(cx, |editor, cx| {
+            editor.remove_blocks(HashSet::from_iter([draft.block_id]), None, cx);
+            editor.focus(cx);
+        });
+    }
+
+    fn submit_reply(&mut self, thread_id: u64, cx: &mut ViewContext<Self>) {
+        let (Some(thread), Some(review_threads)) = (
+            self.displayed_threads.get(&thread_id),
+            self.review_threads.clone(),
+        ) else {
+            return;
+        };
+        let body = thread.reply_editor.read(cx).text(cx);
+        if body.trim().is_empty() {
+            return;
+        }
+
+        let reply_editor = thread.reply_editor.clone();
+        let add_comment = review_threads.update(cx, |review_threads, cx| {
+            review_threads.add_comment(thread_id, body, cx)
+        });
+        cx.spawn(|_, mut cx| async move {
+            add_comment.await?;
+            reply_editor.update(&mut cx, |editor, cx| editor.clear(cx))?;
+            anyhow::Ok(())
+        })
+        .detach_and_notify_err(cx);
+    }
+
+    fn set_resolved(&mut self, thread_id: u64, resolved: bool, cx: &mut ViewContext<Self>) {
+        let Some(review_threads) = self.review_threads.clone() else {
+            return;
+        };
+        review_threads
+            .update(cx, |review_threads, cx| {
+                review_threads.set_resolved(thread_id, resolved, cx)
+            })
+            .detach_and_notify_err(cx);
+    }
+
+    fn render_thread(&self, thread: ReviewThread, cx: &mut ViewContext<Self>) -> RenderBlock {
+        let this = cx.view().downgrade();
+        let displayed = &self.displayed_threads[&thread.id];
+        let outdated = displayed.outdated;
+        let reply_editor = displayed.reply_editor.clone();
+        let local_timezone = self.local_timezone;
+
+        Arc::new(move |cx: &mut BlockContext| {
+            let thread_id = thread.id;
+            let header = h_flex()
+                .gap_2()
+                .child(
+                    Icon::new(IconName::MessageBubbles)
+                        .size(IconSize::Small)
+                        .color(Color::Muted),
+                )
+                .child(
+                    Label::new(if thread.resolved {
+                        format!("Resolved review thread ({})", thread.comments.len())
+                    } else {
+                        "Review thread".to_string()
+                    })
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+                )
+                .when(outdated, |this| {
+                    this.child(
+                        Label::new("Outdated")
+                            .size(LabelSize::Small)
+                            .color(Color::Warning),
+                    )
+                })
+                .child(div().flex_1())
+                .child(
+                    Button::new(
+                        "toggle-resolved",
+                        if thread.resolved { "Reopen" } else { "Resolve" },
+                    )
+                    .label_size(LabelSize::Small)
+                    .on_click({
+                        let this = this.clone();
+                        let resolved = !thread.resolved;
+                        move |_, cx| {
+                            this.update(cx, |this, cx| this.set_resolved(thread_id, resolved, cx))
+                                .ok();
+                        }
+                    }),
+                );
+
+            let comments = thread
+                .comments
+                .iter()
+                .filter(|_| !thread.resolved)
+                .map(|comment| {
+                    v_flex()
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .child(
+                                    Avatar::new(comment.author.avatar_uri.clone()).size(rems(1.)),
+                                )
+                                .child(
+                                    Label::new(comment.author.github_login.clone())
+                                        .size(LabelSize::Small)
+                                        .weight(FontWeight::BOLD),
+                                )
+                                .child(
+                                    Label::new(time_format::format_localized_timestamp(
+                                        comment.timestamp,
+                                        OffsetDateTime::now_utc(),
+                                        local_timezone,
+                                        time_format::TimestampFormat::EnhancedAbsolute,
+                                    ))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                                ),
+                        )
+                        .children(
+                            comment
+                                .body
+                                .lines()
+                                .map(|line| Label::new(line.to_string())),
+                        )
+                });
+
+            let reply = (!thread.resolved).then(|| {
+                h_flex()
+                    .gap_2()
+                    .child(div().flex_1().child(reply_editor.clone()))
+                    .child(
+                        Button::new("reply", "Reply")
+                            .label_size(LabelSize::Small)
+                            .tooltip(|cx| Tooltip::for_action("Reply", &menu::Confirm, cx))
+                            .on_click({
+                                let this = this.clone();
+                                move |_, cx| {
+                                    this.update(cx, |this, cx| this.submit_reply(thread_id, cx))
+                                        .ok();
+                                }
+                            }),
+                    )
+            });
+
+            v_flex()
+                .id(cx.block_id)
+                .block_mouse_down()
+                .pl(cx.gutter_dimensions.full_width())
+                .w(cx.max_width)
+                .py_1()
+                .on_action({
+                    let this = this.clone();
+                    move |_: &menu::Confirm, cx| {
+                        this.update(cx, |this, cx| this.submit_reply(thread_id, cx))
+                            .ok();
+                    }
+                })
+                .child(
+                    v_flex()
+                        .gap_1()
+                        .pl_2()
+                        .border_l_2()
+                        .border_color(if thread.resolved {
+                            cx.theme().colors().border_variant
+                        } else {
+                            cx.theme().status().info_border
+                        })
+                        .child(header)
+                        .children(comments)
+                        .children(reply),
+                )
+                .into_any_element()
+        })
+    }
+
+    fn render_draft(&self, editor: View<Editor>, cx: &mut ViewContext<Self>) -> RenderBlock {
+        let this = cx.view().downgrade();
+        Arc::new(move |cx: &mut BlockContext| {
+            v_flex()
+                .id(cx.block_id)
+                .block_mouse_down()
+                .pl(cx.gutter_dimensions.full_width())
+                .w(cx.max_width)
+                .py_1()
+                .on_action({
+                    let this = this.clone();
+                    move |_: &menu::Confirm, cx| {
+                        this.update(cx, |this, cx| this.submit_draft(cx)).ok();
+                    }
+                })
+                .on_action({
+                    let this = this.clone();
+                    move |_: &menu::Cancel, cx| {
+                        this.update(cx, |this, cx| this.cancel_draft(cx)).ok();
+                    }
+                })
+                .child(
+                    v_flex()
+                        .gap_1()
+                        .pl_2()
+                        .border_l_2()
+                        .border_color(cx.theme().status().info_border)
+                        .child(
+                            Label::new("New review thread")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .child(div().flex_1().child(editor.clone()))
+                                .child(
+                                    Button::new("comment", "Comment")
+                                        .label_size(LabelSize::Small)
+                                        .on_click({
+                                            let this = this.clone();
+                                            move |_, cx| {
+                                                this.update(cx, |this, cx| this.submit_draft(cx))
+                                                    .ok();
+                                            }
+                                        }),
+                                )
+                                .child(
+                                    Button::new("cancel", "Cancel")
+                                        .label_size(LabelSize::Small)
+                                        .on_click({
+                                            let this = this.clone();
+                                            move |_, cx| {
+                                                this.update(cx, |this, cx| this.cancel_draft(cx))
+                                                    .ok();
+                                            }
+                                        }),
+                                ),
+                        ),
+                )
+                .into_any_element()
+        })
+    }
+}
+
+/// The number of lines that a thread's block takes up.
+fn thread_block_height(thread: &ReviewThread) -> u32 {
+    if thread.resolved {
+        return 2;
+    }
+    let comment_lines = thread
+        .comments
+        .iter()
+        .map(|comment| 1 + comment.body.lines().count().max(1))
+        .sum::<usize>();
+    // The header, the reply editor, and some padding.
+    comment_lines as u32 + 3
+}
@@ -0,0 +1,92 @@
+use anyhow::{anyhow, Result};
+use gpui::{actions, AppContext, PromptLevel, View, ViewContext};
+use project::terminal_sharing::TerminalAccess;
+use terminal_view::TerminalView;
+use workspace::{
+    notifications::{DetachAndPromptErr, NotifyResultExt},
+    Workspace,
+};
+
+actions!(
+    collab,
+    [ShareTerminal, ShareTerminalReadOnly, StopSharingTerminal]
+);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ShareTerminal, cx| {
+            let Some(terminal_view) = active_terminal_view(workspace, cx).notify_err(workspace, cx)
+            else {
+                return;
+            };
+
+            // Collaborators that can type into a terminal can run anything on the host's
+            // machine, so make sure that's intended before letting them.
+            let answer = cx.prompt(
+                PromptLevel::Warning,
+                "Let collaborators type into this terminal?",
+                Some("They'll be able to run commands on your machine."),
+                &["Share Read-Write", "Share Read-Only", "Cancel"],
+            );
+            cx.spawn(|workspace, mut cx| async move {
+                let access = match answer.await? {
+                    0 => TerminalAccess::ReadWrite,
+                    1 => TerminalAccess::ReadOnly,
+                    _ => return Ok(()),
+                };
+                workspace.update(&mut cx, |workspace, cx| {
+                    share_terminal(workspace, &terminal_view, access, cx)
+                })?
+            })
+            .detach_and_prompt_err("Failed to share terminal", cx, |_, _| None);
+        });
+        workspace.register_action(|workspace, _: &ShareTerminalReadOnly, cx| {
+            active_terminal_view(workspace, cx)
+                .and_then(|terminal_view| {
+                    share_terminal(workspace, &terminal_view, TerminalAccess::ReadOnly, cx)
+                })
+                .notify_err(workspace, cx);
+        });
+        workspace.register_action(|workspace, _: &StopSharingTerminal, cx| {
+            let Some(terminal_view) = active_terminal_view(workspace, cx).notify_err(workspace, cx)
+            else {
+                return;
+            };
+            let terminal = terminal_view.read(cx).terminal().clone();
+            workspace.project().update(cx, |project, cx| {
+                if let Some(terminal_id) = project.shared_terminal_id(&terminal, cx) {
+                    if project.terminal_access(&terminal).is_some() {
+                        project.unshare_terminal(terminal_id, cx);
+                    }
+                }
+            });
+        });
+    })
+    .detach();
+}
+
+fn active_terminal_view(
+    workspace: &Workspace,
+    cx: &ViewContext<Workspace>,
+) -> Result<View<TerminalView>> {
+    workspace
+        .focused_pane(cx)
+        .read(cx)
+        .active_item()
+        .and_then(|item| item.downcast::<TerminalView>())
+        .ok_or_else(|| anyhow!("focus a terminal to share it"))
+}
+
+fn share_terminal(
+    workspace: &mut Workspace,
+    terminal_view: &View<TerminalView>,
+    access: TerminalAccess,
+    cx: &mut ViewContext<Workspace>,
+) -> Result<()> {
+    let terminal = terminal_view.read(cx).terminal().clone();
+    workspace.project().update(cx, |project, cx| {
+        project.share_terminal(&terminal, access, cx)
+    })?;
+    terminal_view.update(cx, |_, cx| cx.notify());
+    Ok(())
+}
@@ -1,9 +1,9 @@
-use sqlez_macros::sql;
+use sqlez_macros::{migrations, sql};
 
 use crate::{define_connection, query};
 
 define_connection!(pub static ref KEY_VALUE_STORE: KeyValueStore<()> =
-    &[sql!(
+    migrations![sql!(
         CREATE TABLE IF NOT EXISTS kv_store(
             key TEXT PRIMARY KEY,
             value TEXT NOT NULL
@@ -62,7 +62,7 @@ mod tests {
 }
 
 define_connection!(pub static ref GLOBAL_KEY_VALUE_STORE: GlobalKeyValueStore<()> =
-    &[sql!(
+    migrations![sql!(
         CREATE TABLE IF NOT EXISTS kv_store(
             key TEXT PRIMARY KEY,
             value TEXT NOT NULL
@@ -1,5 +1,167 @@
 #[macro_export]
 macro_rules! query {
+    // Selects marked `read` run on a pooled read only connection with `ThreadSafeConnection::read`,
+    // off the write queue, and see a snapshot of the database as of their first query. All other
+    // async queries run on the write queue.
+    ($vis:vis read async fn $id:ident() -> Result<Vec<$return_type:ty>> { $($sql:tt)+ }) => {
+        $vis async fn $id(&self) -> $crate::anyhow::Result<Vec<$return_type>> {
+            use $crate::anyhow::Context;
+
+            self.read(move |connection| {
+                let sql_stmt = $crate::sqlez_macros::sql!($($sql)+);
+
+                connection.select::<$return_type>(sql_stmt)?()
+                    .context(::std::format!(
+                        "Error in {}, select failed to execute or parse for: {}",
+                        ::std::stringify!($id),
+                        sql_stmt
+                    ))
+            }).await
+        }
+    };
+    ($vis:vis read async fn $id:ident($arg:ident: $arg_type:ty) -> Result<Vec<$return_type:ty>> { $($sql:tt)+ }) => {
+        $vis async fn $id(&self, $arg: $arg_type) -> $crate::anyhow::Result<Vec<$return_type>> {
+            use $crate::anyhow::Context;
+
+            self.read(move |connection| {
+                let sql_stmt = $crate::sqlez_macros::sql!($($sql)+);
+
+                connection.select_bound::<$arg_type, $return_type>(sql_stmt)?($arg)
+                    .context(::std::format!(
+                        "Error in {}, select_bound failed to execute or parse for: {}",
+                        ::std::stringify!($id),
+                        sql_stmt
+                    ))
+            }).await
+        }
+    };
+    ($vis:vis read async fn $id:ident($($arg:ident: $arg_type:ty),+) -> Result<Vec<$return_type:ty>> { $($sql:tt)+ }) => {
+        $vis async fn $id(&self, $($arg: $arg_type),+) -> $crate::anyhow::Result<Vec<$return_type>> {
+            use $crate::anyhow::Context;
+
+            self.read(move |connection| {
+                let sql_stmt = $crate::sqlez_macros::sql!($($sql)+);
+
+                connection.select_bound::<($($arg_type),+), $return_type>(sql_stmt)?(($($arg),+))
+                    .context(::std::format!(
+                        "Error in {}, select_bound failed to execute or parse for: {}",
+                        ::std::stringify!($id),
+                        sql_stmt
+                    ))
+            }).await
+        }
+    };
+    ($vis:vis read async fn $id:ident() -> Result<Option<$return_type:ty>> { $($sql:tt)+ }) => {
+        $vis async fn $id(&self) -> $crate::anyhow::Result<Option<$return_type>> {
+            use $crate::anyhow::Context;
+
+            self.read(move |connection| {
+                let sql_stmt = $crate::sqlez_macros::sql!($($sql)+);
+
+                connection.select_row::<$return_type>(sql_stmt)?()
+                    .context(::std::format!(
+                        "Error in {}, select_row failed to execute or parse for: {}",
+                        ::std::stringify!($id),
+                        sql_stmt
+                    ))
+            }).await
+        }
+    };
+    ($vis:vis read async fn $id:ident($arg:ident: $arg_type:ty) -> Result<Option<$return_type:ty>> { $($sql:tt)+ }) => {
+        $vis async fn $id(&self, $arg: $arg_type) -> $crate::anyhow::Result<Option<$return_type>> {
+            use $crate::anyhow::Context;
+
+            self.read(move |connection| {
+                let sql_stmt = $crate::sqlez_macros::sql!($($sql)+);
+
+                connection.select_row_bound::<$arg_type, $return_type>(sql_stmt)?($arg)
+                    .context(::std::format!(
+                        "Error in {}, select_row_bound failed to execute or parse for: {}",
+                        ::std::stringify!($id),
+                        sql_stmt
+                    ))
+            }).await
+        }
+    };
+    ($vis:vis read async fn $id:ident($($arg:ident: $arg_type:ty),+) -> Result<Option<$return_type:ty>> { $($sql:tt)+ }) => {
+        $vis async fn $id(&self, $($arg: $arg_type),+) -> $crate::anyhow::Result<Option<$return_type>> {
+            use $crate::anyhow::Context;
+
+            self.read(move |connection| {
+                let sql_stmt = $crate::sqlez_macros::sql!($($sql)+);
+
+                connection.select_row_bound::<($($arg_type),+), $return_type>(sql_stmt)?(($($arg),+))
+                    .context(::std::format!(
+                        "Error in {}, select_row_bound failed to execute or parse for: {}",
+                        ::std::stringify!($id),
+                        sql_stmt
+                    ))
+            }).await
+        }
+    };
+    ($vis:vis read async fn $id:ident() -> Result<$return_type:ty> { $($sql:tt)+ }) => {
+        $vis async fn $id(&self) -> $crate::anyhow::Result<$return_type> {
+            use $crate::anyhow::Context;
+
+            self.read(move |connection| {
+                let sql_stmt = $crate::sqlez_macros::sql!($($sql)+);
+
+                connection.select_row::<$return_type>(sql_stmt)?()
+                    .context(::std::format!(
+                        "Error in {}, select_row failed to execute or parse for: {}",
+                        ::std::stringify!($id),
+                        sql_stmt
+                    ))?
+                    .context(::std::format!(
+                        "Error in {}, select_row expected single row result but found none for: {}",
+                        ::std::stringify!($id),
+                        sql_stmt
+                    ))
+            }).await
+        }
+    };
+    ($vis:vis read async fn $id:ident($arg:ident: $arg_type:ty) -> Result<$return_type:ty> { $($sql:tt)+ }) => {
+        $vis async fn $id(&self, $arg: $arg_type) -> $crate::anyhow::Result<$return_type> {
+            use $crate::anyhow::Context;
+
+            self.read(move |connection| {
+                let sql_stmt = $crate::sqlez_macros::sql!($($sql)+);
+
+                connection.select_row_bound::<$arg_type, $return_type>(sql_stmt)?($arg)
+                    .context(::std::format!(
+                        "Error in {}, select_row_bound failed to execute or parse for: {}",
+                        ::std::stringify!($id),
+                        sql_stmt
+                    ))?
+                    .context(::std::format!(
+                        "Error in {}, select_row_bound expected single row result but found none for: {}",
+                        ::std::stringify!($id),
+                        sql_stmt
+                    ))
+            }).await
+        }
+    };
+    ($vis:vis read async fn $id:ident($($arg:ident: $arg_type:ty),+) -> Result<$return_type:ty> { $($sql:tt)+ }) => {
+        $vis async fn $id(&self, $($arg: $arg_type),+) -> $crate::anyhow::Result<$return_type> {
+            use $crate::anyhow::Context;
+
+            self.read(move |connection| {
+                let sql_stmt = $crate::sqlez_macros::sql!($($sql)+);
+
+                connection.select_row_bound::<($($arg_type),+), $return_type>(sql_stmt)?(($($arg),+))
+                    .context(::std::format!(
+                        "Error in {}, select_row_bound failed to execute or parse for: {}",
+                        ::std::stringify!($id),
+                        sql_stmt
+                    ))?
+                    .context(::std::format!(
+                        "Error in {}, select_row_bound expected single row result but found none for: {}",
+                        ::std::stringify!($id),
+                        sql_stmt
+                    ))
+            }).await
+        }
+    };
     ($vis:vis fn $id:ident() -> Result<()> { $($sql:tt)+ }) => {
         $vis fn $id(&self) -> $crate::anyhow::Result<()> {
             use $crate::anyhow::Context;
@@ -312,3 +474,148 @@ macro_rules! query {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use sqlez::{domain::Domain, thread_safe_connection::ThreadSafeConnection};
+    use sqlez_macros::sql;
+
+    use crate::open_db;
+
+    struct ReadDb(ThreadSafeConnection<ReadDb>);
+
+    impl std::ops::Deref for ReadDb {
+        type Target = ThreadSafeConnection<ReadDb>;
+
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+
+    impl Domain for ReadDb {
+        fn name() -> &'static str {
+            "read_db"
+        }
+
+        fn migrations() -> &'static [&'static str] {
+            &[sql!(CREATE TABLE items(name TEXT NOT NULL, count INTEGER NOT NULL) STRICT;)]
+        }
+    }
+
+    impl ReadDb {
+        query! {
+            async fn insert_item(name: String, count: i64) -> Result<()> {
+                INSERT INTO items(name, count) VALUES (?, ?)
+            }
+        }
+
+        query! {
+            read async fn item_names() -> Result<Vec<String>> {
+                SELECT name FROM items ORDER BY name
+            }
+        }
+
+        query! {
+            read async fn counts(name: String) -> Result<Vec<i64>> {
+                SELECT count FROM items WHERE name = ? ORDER BY count
+            }
+        }
+
+        query! {
+            read async fn items_between(min: i64, max: i64) -> Result<Vec<String>> {
+                SELECT name FROM items WHERE count >= ? AND count <= ? ORDER BY name
+            }
+        }
+
+        query! {
+            read async fn first_item() -> Result<Option<String>> {
+                SELECT name FROM items ORDER BY name LIMIT 1
+            }
+        }
+
+        query! {
+            read async fn largest_count(name: String) -> Result<Option<i64>> {
+                SELECT MAX(count) FROM items WHERE name = ?
+            }
+        }
+
+        query! {
+            read async fn find_item(name: String, count: i64) -> Result<Option<String>> {
+                SELECT name FROM items WHERE name = ? AND count = ?
+            }
+        }
+
+        query! {
+            read async fn item_count() -> Result<i64> {
+                SELECT COUNT(*) FROM items
+            }
+        }
+
+        query! {
+            read async fn total(name: String) -> Result<i64> {
+                SELECT SUM(count) FROM items WHERE name = ?
+            }
+        }
+
+        query! {
+            read async fn count_between(min: i64, max: i64) -> Result<i64> {
+                SELECT COUNT(*) FROM items WHERE count >= ? AND count <= ?
+            }
+        }
+
+        query! {
+            read async fn missing_item() -> Result<String> {
+                SELECT name FROM items WHERE count < 0
+            }
+        }
+    }
+
+    #[gpui::test]
+    async fn test_read_queries(cx: &mut gpui::TestAppContext) {
+        cx.executor().allow_parking();
+
+        let tempdir = tempfile::Builder::new()
+            .prefix("ReadQueries")
+            .tempdir()
+            .unwrap();
+        let db = ReadDb(
+            open_db(
+                tempdir.path(),
+                &release_channel::ReleaseChannel::Dev.dev_name(),
+            )
+            .await,
+        );
+
+        assert_eq!(db.item_names().await.unwrap(), Vec::<String>::new());
+        assert_eq!(db.first_item().await.unwrap(), None);
+        assert_eq!(db.item_count().await.unwrap(), 0);
+
+        db.insert_item("b".into(), 2).await.unwrap();
+        db.insert_item("a".into(), 3).await.unwrap();
+        db.insert_item("a".into(), 1).await.unwrap();
+
+        // Reads see writes that have completed, even though they run on other connections.
+        assert_eq!(db.item_names().await.unwrap(), ["a", "a", "b"]);
+        assert_eq!(db.counts("a".into()).await.unwrap(), [1, 3]);
+        assert_eq!(db.items_between(2, 3).await.unwrap(), ["a", "b"]);
+        assert_eq!(db.first_item().await.unwrap(), Some("a".into()));
+        assert_eq!(db.largest_count("a".into()).await.unwrap(), Some(3));
+        assert_eq!(db.find_item("b".into(), 2).await.unwrap(), Some("b".into()));
+        assert_eq!(db.find_item("b".into(), 3).await.unwrap(), None);
+        assert_eq!(db.item_count().await.unwrap(), 3);
+        assert_eq!(db.total("a".into()).await.unwrap(), 4);
+        assert_eq!(db.count_between(1, 2).await.unwrap(), 2);
+
+        let error = db.missing_item().await.unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("expected single row result but found none"));
+
+        // Read connections can't write.
+        assert!(db
+            .read(|connection| connection.exec(sql!(DELETE FROM items))?())
+            .await
+            .is_err());
+        assert_eq!(db.item_count().await.unwrap(), 3);
+    }
+}
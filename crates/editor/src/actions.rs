@@ -160,6 +160,11 @@ pub struct FoldAtLevel {
     pub level: u32,
 }
 
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct RunLanguageServerCommand {
+    pub name: String,
+}
+
 #[derive(PartialEq, Clone, Deserialize, Default)]
 pub struct SpawnNearestTask {
     #[serde(default)]
@@ -185,6 +190,7 @@ impl_actions!(
         MoveToBeginningOfLine,
         MoveToEndOfLine,
         MoveUpByLines,
+        RunLanguageServerCommand,
         SelectDownByLines,
         SelectNext,
         SelectPrevious,
@@ -210,6 +216,7 @@ gpui::actions!(
         AddSelectionBelow,
         ApplyAllDiffHunks,
         ApplyDiffHunk,
+        ApplyDiffHunksInFile,
         Backspace,
         Cancel,
         CancelLanguageServerWork,
@@ -295,6 +302,7 @@ gpui::actions!(
         NewlineAbove,
         NewlineBelow,
         NextInlineCompletion,
+        NextLargeFilePage,
         NextScreen,
         OpenContextMenu,
         OpenExcerpts,
@@ -310,8 +318,11 @@ gpui::actions!(
         PageUp,
         Paste,
         PreviousInlineCompletion,
+        PreviousLargeFilePage,
         Redo,
         RedoSelection,
+        RejectAllDiffHunks,
+        RejectDiffHunksInFile,
         Rename,
         RestartLanguageServer,
         RevealInFileManager,
@@ -320,6 +331,7 @@ gpui::actions!(
         ReloadFile,
         RevertSelectedHunks,
         Rewrap,
+        RollBackAppliedDiffHunks,
         ScrollCursorBottom,
         ScrollCursorCenter,
         ScrollCursorCenterTopBottom,
@@ -23,6 +23,7 @@ mod editor_settings_controls;
 mod element;
 mod git;
 mod highlight_matching_bracket;
+mod hot_exit;
 mod hover_links;
 mod hover_popover;
 mod hunk_diff;
@@ -30,6 +31,7 @@ mod indent_guides;
 mod inlay_hint_cache;
 pub mod items;
 mod linked_editing_ranges;
+mod local_history_view;
 mod lsp_ext;
 mod mouse_context_menu;
 pub mod movement;
@@ -299,6 +301,8 @@ pub fn init(cx: &mut AppContext) {
     workspace::register_project_item::<Editor>(cx);
     workspace::FollowableViewRegistry::register::<Editor>(cx);
     workspace::register_serializable_item::<Editor>(cx);
+    hot_exit::init(cx);
+    local_history_view::init(cx);
 
     cx.observe_new_views(
         |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
@@ -660,6 +664,9 @@ pub struct Editor {
     focused_block: Option<FocusedBlock>,
     next_scroll_position: NextScrollCursorCenterTopBottom,
     addons: HashMap<TypeId, Box<dyn Addon>>,
+    /// The text last reported to assistive technologies, along with the buffer's edit count
+    /// when it was read.
+    accessibility_text: Option<(usize, SharedString)>,
     _scroll_cursor_center_top_bottom_task: Task<()>,
 }
 
@@ -2177,6 +2184,7 @@ impl Editor {
             focused_block: None,
             next_scroll_position: NextScrollCursorCenterTopBottom::default(),
             addons: HashMap::default(),
+            accessibility_text: None,
             _scroll_cursor_center_top_bottom_task: Task::ready(()),
             text_style_refinement: None,
         };
@@ -9793,6 +9801,40 @@ impl Editor {
         }
     }
 
+    fn next_large_file_page(&mut self, _: &NextLargeFilePage, cx: &mut ViewContext<Self>) {
+        self.go_to_large_file_page(|page| page.checked_add(1), cx);
+    }
+
+    fn previous_large_file_page(&mut self, _: &PreviousLargeFilePage, cx: &mut ViewContext<Self>) {
+        self.go_to_large_file_page(|page| page.checked_sub(1), cx);
+    }
+
+    /// Shows another page of a large file, moving the cursor to the start of the page.
+    fn go_to_large_file_page(
+        &mut self,
+        page: impl FnOnce(usize) -> Option<usize>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return;
+        };
+        let page_changed = buffer.update(cx, |buffer, cx| {
+            let Some((_, current_page)) = buffer.large_file_page() else {
+                return false;
+            };
+            let Some(page) = page(current_page) else {
+                return false;
+            };
+            buffer.set_large_file_page(page, cx);
+            buffer
+                .large_file_page()
+                .map_or(false, |(_, page)| page != current_page)
+        });
+        if page_changed {
+            self.change_selections(Some(Autoscroll::fit()), cx, |s| s.select_ranges([0..0]));
+        }
+    }
+
     fn go_to_next_hunk(&mut self, _: &GoToHunk, cx: &mut ViewContext<Self>) {
         let snapshot = self.snapshot(cx);
         let selection = self.selections.newest::<Point>(cx);
@@ -12581,6 +12623,20 @@ impl Editor {
         cx.notify();
     }
 
+    /// Returns the text to report to assistive technologies, reading it from the buffer again
+    /// only after the buffer was edited or its excerpts changed, which doesn't count as an edit.
+    pub(crate) fn accessibility_text(&mut self, snapshot: &MultiBufferSnapshot) -> SharedString {
+        let edit_count = snapshot.edit_count();
+        match &self.accessibility_text {
+            Some((cached_edit_count, text)) if *cached_edit_count == edit_count => text.clone(),
+            _ => {
+                let text = SharedString::from(snapshot.text());
+                self.accessibility_text = Some((edit_count, text.clone()));
+                text
+            }
+        }
+    }
+
     fn on_buffer_event(
         &mut self,
         multibuffer: Model<MultiBuffer>,
@@ -12645,6 +12701,7 @@ impl Editor {
                 predecessor,
                 excerpts,
             } => {
+                self.accessibility_text = None;
                 self.tasks_update_task = Some(self.refresh_runnables(cx));
                 cx.emit(EditorEvent::ExcerptsAdded {
                     buffer: buffer.clone(),
@@ -12654,6 +12711,7 @@ impl Editor {
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
             }
             multi_buffer::Event::ExcerptsRemoved { ids } => {
+                self.accessibility_text = None;
                 self.refresh_inlay_hints(InlayHintRefreshReason::ExcerptsRemoved(ids.clone()), cx);
                 cx.emit(EditorEvent::ExcerptsRemoved { ids: ids.clone() })
             }
@@ -12661,6 +12719,7 @@ impl Editor {
                 cx.emit(EditorEvent::ExcerptsEdited { ids: ids.clone() })
             }
             multi_buffer::Event::ExcerptsExpanded { ids } => {
+                self.accessibility_text = None;
                 cx.emit(EditorEvent::ExcerptsExpanded { ids: ids.clone() })
             }
             multi_buffer::Event::Reparsed(buffer_id) => {
@@ -1191,6 +1191,49 @@ fn test_fold_at_level(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+fn test_accessibility_tree(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let buffer = cx.update(|cx| MultiBuffer::build_simple("héllo\nwörld", cx));
+    let editor = cx.add_window(|cx| build_editor(buffer, cx));
+    _ = editor.update(cx, |editor, cx| {
+        cx.focus_self();
+        editor.change_selections(None, cx, |s| s.select_ranges([10..3]));
+    });
+
+    let view = editor.root_view(cx).unwrap();
+    let cx = &mut VisualTestContext::from_window(*editor, cx);
+    cx.simulate_accessibility_activation();
+    let tree = cx.update(|cx| cx.accessibility_tree());
+    let node = tree.node(tree.focus()).unwrap();
+    assert_eq!(node.properties.role, gpui::Role::MultilineTextInput);
+    assert_eq!(node.properties.value.as_deref(), Some("héllo\nwörld"));
+    // The selection is reported in characters, from its tail to the caret.
+    assert_eq!(node.properties.text_selection, Some(8..2));
+    let text = node.properties.value.clone().unwrap();
+
+    // Moving the cursor redraws the editor, but reuses the text read from the buffer.
+    view.update(cx, |editor, cx| {
+        editor.change_selections(None, cx, |s| s.select_ranges([1..1]))
+    });
+    cx.run_until_parked();
+    let tree = cx.update(|cx| cx.accessibility_tree());
+    let node = tree.node(tree.focus()).unwrap();
+    assert_eq!(node.properties.text_selection, Some(1..1));
+    assert_eq!(
+        node.properties.value.as_ref().unwrap().as_ptr(),
+        text.as_ptr()
+    );
+
+    view.update(cx, |editor, cx| editor.insert("w", cx));
+    cx.run_until_parked();
+    let tree = cx.update(|cx| cx.accessibility_tree());
+    let node = tree.node(tree.focus()).unwrap();
+    assert_eq!(node.properties.value.as_deref(), Some("hwéllo\nwörld"));
+    assert_eq!(node.properties.text_selection, Some(2..2));
+}
+
 #[gpui::test]
 fn test_move_cursor(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
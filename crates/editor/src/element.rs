@@ -29,13 +29,13 @@ use collections::{BTreeMap, HashMap, HashSet};
 use git::{blame::BlameEntry, diff::DiffHunkStatus, Oid};
 use gpui::{
     anchored, deferred, div, fill, outline, point, px, quad, relative, size, svg,
-    transparent_black, Action, AnchorCorner, AnyElement, AvailableSpace, Bounds, ClipboardItem,
-    ContentMask, Corners, CursorStyle, DispatchPhase, Edges, Element, ElementInputHandler, Entity,
-    FontId, GlobalElementId, Hitbox, Hsla, InteractiveElement, IntoElement, Length,
-    ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad,
-    ParentElement, Pixels, ScrollDelta, ScrollWheelEvent, ShapedLine, SharedString, Size,
-    StatefulInteractiveElement, Style, Styled, TextRun, TextStyleRefinement, View, ViewContext,
-    WeakView, WindowContext,
+    transparent_black, AccessibilityProperties, AccessibilityState, Action, AnchorCorner,
+    AnyElement, AvailableSpace, Bounds, ClipboardItem, ContentMask, Corners, CursorStyle,
+    DispatchPhase, Edges, Element, ElementInputHandler, Entity, FontId, GlobalElementId, Hitbox,
+    Hsla, InteractiveElement, IntoElement, Length, ModifiersChangedEvent, MouseButton,
+    MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad, ParentElement, Pixels, Role,
+    ScrollDelta, ScrollWheelEvent, ShapedLine, SharedString, Size, StatefulInteractiveElement,
+    Style, Styled, TextRun, TextStyleRefinement, View, ViewContext, WeakView, WindowContext,
 };
 use gpui::{ClickEvent, Subscription};
 use itertools::Itertools;
@@ -169,6 +169,7 @@ impl EditorElement {
 
         crate::rust_analyzer_ext::apply_related_actions(view, cx);
         crate::clangd_ext::apply_related_actions(view, cx);
+        register_action(view, cx, crate::lsp_ext::run_language_server_command);
         register_action(view, cx, Editor::open_context_menu);
         register_action(view, cx, Editor::move_left);
         register_action(view, cx, Editor::move_right);
@@ -310,6 +311,8 @@ impl EditorElement {
         register_action(view, cx, Editor::go_to_prev_diagnostic);
         register_action(view, cx, Editor::go_to_next_hunk);
         register_action(view, cx, Editor::go_to_prev_hunk);
+        register_action(view, cx, Editor::next_large_file_page);
+        register_action(view, cx, Editor::previous_large_file_page);
         register_action(view, cx, |editor, a, cx| {
             editor.go_to_definition(a, cx).detach_and_log_err(cx);
         });
@@ -1868,6 +1871,15 @@ impl EditorElement {
             let is_relative = editor.should_use_relative_line_numbers(cx);
             (newest_selection_head, is_relative)
         });
+        // Large files show one page at a time, so the page's rows are numbered from where it
+        // starts in the file.
+        let first_row_number = self
+            .editor
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .as_singleton()
+            .map_or(0, |buffer| buffer.read(cx).large_file_start_row());
         let font_size = self.style.text.font_size.to_pixels(cx.rem_size());
 
         let relative_to = if is_relative {
@@ -1889,10 +1901,11 @@ impl EditorElement {
                     cx.theme().colors().editor_line_number
                 };
                 line_number.clear();
-                let default_number = multibuffer_row.0 + 1;
                 let number = relative_rows
                     .get(&DisplayRow(ix as u32 + rows.start.0))
-                    .unwrap_or(&default_number);
+                    .map_or(first_row_number + multibuffer_row.0 as u64 + 1, |number| {
+                        *number as u64
+                    });
                 write!(&mut line_number, "{number}").unwrap();
                 let run = TextRun {
                     len: line_number.len(),
@@ -3940,6 +3953,45 @@ impl EditorElement {
         }
     }
 
+    /// Describes the editor to assistive technologies, exposing its text and caret. The text is
+    /// only collected while an assistive technology is listening, since it can be large, and is
+    /// reused across frames until the buffer changes.
+    fn accessibility_properties(&self, cx: &mut WindowContext) -> Option<AccessibilityProperties> {
+        if !cx.is_accessibility_active() {
+            return None;
+        }
+
+        self.editor.update(cx, |editor, cx| {
+            let snapshot = editor.buffer.read(cx).snapshot(cx);
+            let text = editor.accessibility_text(&snapshot);
+            let selection = editor.selections.newest_anchor();
+            let character_offset = |anchor: &Anchor| {
+                let offset = snapshot.summary_for_anchor::<usize>(anchor);
+                text[..offset.min(text.len())].chars().count()
+            };
+            let text_selection =
+                character_offset(&selection.tail())..character_offset(&selection.head());
+
+            Some(AccessibilityProperties {
+                role: if matches!(editor.mode, EditorMode::SingleLine { .. }) {
+                    Role::TextInput
+                } else {
+                    Role::MultilineTextInput
+                },
+                label: editor
+                    .placeholder_text(cx)
+                    .map(|placeholder| placeholder.to_string().into()),
+                value: Some(text),
+                state: AccessibilityState {
+                    focused: editor.focus_handle.is_focused(cx),
+                    disabled: editor.read_only(cx),
+                    ..Default::default()
+                },
+                text_selection: Some(text_selection),
+            })
+        })
+    }
+
     fn paint_mouse_context_menu(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
         if let Some(mouse_context_menu) = layout.mouse_context_menu.as_mut() {
             mouse_context_menu.paint(cx);
@@ -5665,7 +5717,7 @@ impl Element for EditorElement {
 
     fn paint(
         &mut self,
-        _: Option<&GlobalElementId>,
+        global_id: Option<&GlobalElementId>,
         bounds: Bounds<gpui::Pixels>,
         _: &mut Self::RequestLayoutState,
         layout: &mut Self::PrepaintState,
@@ -5713,34 +5765,37 @@ impl Element for EditorElement {
                 }
             });
         let rem_size = self.rem_size(cx);
-        cx.with_rem_size(rem_size, |cx| {
-            cx.with_text_style(Some(text_style), |cx| {
-                cx.with_content_mask(Some(ContentMask { bounds }), |cx| {
-                    self.paint_mouse_listeners(layout, hovered_hunk, cx);
-                    self.paint_background(layout, cx);
-                    self.paint_indent_guides(layout, cx);
-
-                    if layout.gutter_hitbox.size.width > Pixels::ZERO {
-                        self.paint_blamed_display_rows(layout, cx);
-                        self.paint_line_numbers(layout, cx);
-                    }
+        let accessibility = self.accessibility_properties(cx);
+        cx.with_accessibility_node(global_id, accessibility, bounds, |cx| {
+            cx.with_rem_size(rem_size, |cx| {
+                cx.with_text_style(Some(text_style), |cx| {
+                    cx.with_content_mask(Some(ContentMask { bounds }), |cx| {
+                        self.paint_mouse_listeners(layout, hovered_hunk, cx);
+                        self.paint_background(layout, cx);
+                        self.paint_indent_guides(layout, cx);
+
+                        if layout.gutter_hitbox.size.width > Pixels::ZERO {
+                            self.paint_blamed_display_rows(layout, cx);
+                            self.paint_line_numbers(layout, cx);
+                        }
 
-                    self.paint_text(layout, cx);
+                        self.paint_text(layout, cx);
 
-                    if layout.gutter_hitbox.size.width > Pixels::ZERO {
-                        self.paint_gutter_highlights(layout, cx);
-                        self.paint_gutter_indicators(layout, cx);
-                    }
+                        if layout.gutter_hitbox.size.width > Pixels::ZERO {
+                            self.paint_gutter_highlights(layout, cx);
+                            self.paint_gutter_indicators(layout, cx);
+                        }
 
-                    if !layout.blocks.is_empty() {
-                        cx.with_element_namespace("blocks", |cx| {
-                            self.paint_blocks(layout, cx);
-                        });
-                    }
+                        if !layout.blocks.is_empty() {
+                            cx.with_element_namespace("blocks", |cx| {
+                                self.paint_blocks(layout, cx);
+                            });
+                        }
 
-                    self.paint_scrollbar(layout, cx);
-                    self.paint_mouse_context_menu(layout, cx);
-                });
+                        self.paint_scrollbar(layout, cx);
+                        self.paint_mouse_context_menu(layout, cx);
+                    });
+                })
             })
         })
     }
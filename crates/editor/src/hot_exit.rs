@@ -0,0 +1,369 @@
+//! Periodic snapshots of the dirty buffers in a workspace that aren't open in an editor of
+//! their own, such as buffers that were only edited through a multibuffer. Editors serialize
+//! their own buffers whenever they change, so together every dirty buffer can be restored
+//! after a crash.
+
+use crate::{
+    items::{restore_unsaved_contents, unsaved_contents},
+    persistence::{SerializedEditor, DB},
+    Editor,
+};
+use anyhow::Result;
+use collections::{HashMap, HashSet};
+use futures::{
+    channel::oneshot,
+    future::{self, Either},
+};
+use gpui::{AppContext, AsyncWindowContext, Task, ViewContext, WeakView};
+use project::project_settings::ProjectSettings;
+use settings::Settings as _;
+use std::time::Duration;
+use text::BufferId;
+use util::ResultExt as _;
+use workspace::{Workspace, WorkspaceId};
+
+const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(5);
+/// How long to wait for the workspace to be restored before restoring the buffers from the
+/// last session anyway. Without it, a workspace that fails to restore would never be
+/// snapshotted again.
+const RESTORE_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub(crate) fn init(cx: &mut AppContext) {
+    cx.observe_new_views(register).detach();
+}
+
+fn register(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    let Some(workspace_id) = workspace.database_id() else {
+        return;
+    };
+    if !workspace.project().read(cx).is_local() {
+        return;
+    }
+
+    let (restored_tx, restored_rx) = oneshot::channel();
+    let mut restored_tx = Some(restored_tx);
+    let workspace_handle = cx.view().clone();
+    cx.subscribe(&workspace_handle, move |_, _, event, _| {
+        if let workspace::Event::WorkspaceRestored = event {
+            if let Some(restored_tx) = restored_tx.take() {
+                restored_tx.send(()).ok();
+            }
+        }
+    })
+    .detach();
+
+    // The snapshots are only there to recover from crashes. When the workspace is closed
+    // normally, the user has already been asked about the dirty buffers without an editor.
+    cx.on_release(move |_, _, cx| {
+        cx.background_executor()
+            .spawn(clear_unsaved_buffers(workspace_id))
+            .detach();
+    })
+    .detach();
+    let quit_subscription = cx.on_app_quit(move |_| clear_unsaved_buffers(workspace_id));
+
+    cx.spawn(|workspace, cx| async move {
+        let _quit_subscription = quit_subscription;
+        maintain_snapshots(workspace, workspace_id, restored_rx, cx)
+            .await
+            .log_err();
+    })
+    .detach();
+}
+
+async fn clear_unsaved_buffers(workspace_id: WorkspaceId) {
+    DB.save_unsaved_buffers(workspace_id, Vec::new())
+        .await
+        .log_err();
+}
+
+async fn maintain_snapshots(
+    workspace: WeakView<Workspace>,
+    workspace_id: WorkspaceId,
+    restored: oneshot::Receiver<()>,
+    mut cx: AsyncWindowContext,
+) -> Result<()> {
+    // Buffers from the last session are reopened once the workspace's own items are back,
+    // and we mustn't overwrite them with a new snapshot before that.
+    let unsaved_buffers = DB.get_unsaved_buffers(workspace_id).await?;
+    if !unsaved_buffers.is_empty() {
+        let timeout = cx.background_executor().timer(RESTORE_TIMEOUT);
+        match future::select(restored, timeout).await {
+            Either::Left((restored, _)) => restored?,
+            Either::Right(_) => log::warn!(
+                "workspace wasn't restored within {RESTORE_TIMEOUT:?}, restoring its unsaved buffers anyway"
+            ),
+        }
+        let restore_unsaved_buffers = cx.update(|cx| {
+            ProjectSettings::get_global(cx)
+                .session
+                .restore_unsaved_buffers
+        })?;
+        if restore_unsaved_buffers {
+            restore_buffers(workspace.clone(), unsaved_buffers, &mut cx).await?;
+        }
+    }
+
+    let mut last_snapshot = None;
+    loop {
+        cx.background_executor().timer(SNAPSHOT_INTERVAL).await;
+        let snapshot = workspace.update(&mut cx, |workspace, cx| {
+            snapshot_buffers(workspace, workspace_id, &mut last_snapshot, cx)
+        })?;
+        if let Some(snapshot) = snapshot {
+            snapshot.await.log_err();
+        }
+    }
+}
+
+/// Stores the dirty buffers of the workspace that aren't open in an editor of their own,
+/// unless their versions are the same as in `last_snapshot`.
+pub(crate) fn snapshot_buffers(
+    workspace: &Workspace,
+    workspace_id: WorkspaceId,
+    last_snapshot: &mut Option<HashMap<BufferId, clock::Global>>,
+    cx: &AppContext,
+) -> Option<Task<Result<()>>> {
+    let project = workspace.project().read(cx);
+    let serialize_dirty_buffers = ProjectSettings::get_global(cx)
+        .session
+        .restore_unsaved_buffers
+        // Projects without worktrees aren't deserialized.
+        && project.visible_worktrees(cx).next().is_some();
+
+    let buffers_with_editors = workspace
+        .items_of_type::<Editor>(cx)
+        .filter_map(|editor| editor.read(cx).buffer().read(cx).as_singleton())
+        .map(|buffer| buffer.read(cx).remote_id())
+        .collect::<HashSet<_>>();
+
+    let mut versions = HashMap::default();
+    let mut buffers = Vec::new();
+    if serialize_dirty_buffers {
+        for buffer in project.opened_buffers(cx) {
+            let buffer = buffer.read(cx);
+            if !buffer.is_dirty() || buffers_with_editors.contains(&buffer.remote_id()) {
+                continue;
+            }
+            let Some(file) = buffer.file().and_then(|file| file.as_local()) else {
+                continue;
+            };
+            versions.insert(buffer.remote_id(), buffer.version());
+            buffers.push((
+                file.abs_path(cx),
+                buffer.snapshot(),
+                buffer.saved_version().clone(),
+                buffer.saved_mtime(),
+            ));
+        }
+    }
+
+    if last_snapshot.as_ref() == Some(&versions) {
+        return None;
+    }
+    *last_snapshot = Some(versions);
+
+    Some(cx.background_executor().spawn(async move {
+        let buffers = buffers
+            .into_iter()
+            .map(|(abs_path, snapshot, saved_version, mtime)| {
+                unsaved_contents(Some(abs_path), &snapshot, &saved_version, mtime)
+            })
+            .collect();
+        DB.save_unsaved_buffers(workspace_id, buffers).await
+    }))
+}
+
+/// Reopens the given buffers in editors and restores their unsaved contents.
+pub(crate) async fn restore_buffers(
+    workspace: WeakView<Workspace>,
+    buffers: Vec<SerializedEditor>,
+    cx: &mut AsyncWindowContext,
+) -> Result<()> {
+    for buffer in buffers {
+        let SerializedEditor {
+            abs_path: Some(abs_path),
+            contents: Some(contents),
+            base_contents,
+            mtime,
+            ..
+        } = buffer
+        else {
+            continue;
+        };
+
+        let open = workspace.update(cx, |workspace, cx| {
+            workspace.open_abs_path(abs_path, false, cx)
+        })?;
+        let Some(editor) = open
+            .await
+            .log_err()
+            .and_then(|item| item.downcast::<Editor>())
+        else {
+            continue;
+        };
+        editor.update(cx, |editor, cx| {
+            if let Some(buffer) = editor.buffer().read(cx).as_singleton() {
+                buffer.update(cx, |buffer, cx| {
+                    restore_unsaved_contents(buffer, contents, base_contents, mtime, cx);
+                });
+            }
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor_tests::init_test;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use std::path::PathBuf;
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_snapshot_and_restore_buffers(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/dir", json!({ "a.txt": "a", "b.txt": "b" }))
+            .await;
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+
+        // Edit one buffer without an editor, and one that's open in an editor
+        let unsaved_buffers = {
+            let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+            let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+            let buffer_a = project
+                .update(cx, |project, cx| {
+                    project.open_local_buffer("/dir/a.txt", cx)
+                })
+                .await
+                .unwrap();
+            buffer_a.update(cx, |buffer, cx| buffer.edit([(1..1, "bc")], None, cx));
+            let editor_b = workspace
+                .update(cx, |workspace, cx| {
+                    workspace.open_abs_path(PathBuf::from("/dir/b.txt"), false, cx)
+                })
+                .await
+                .unwrap()
+                .downcast::<Editor>()
+                .unwrap();
+            editor_b.update(cx, |editor, cx| editor.set_text("changed", cx));
+
+            let mut last_snapshot = None;
+            workspace
+                .update(cx, |workspace, cx| {
+                    snapshot_buffers(workspace, workspace_id, &mut last_snapshot, cx)
+                })
+                .unwrap()
+                .await
+                .unwrap();
+            let unsaved_buffers = DB.get_unsaved_buffers(workspace_id).await.unwrap();
+            assert_eq!(unsaved_buffers.len(), 1);
+            assert_eq!(
+                unsaved_buffers[0].abs_path,
+                Some(PathBuf::from("/dir/a.txt"))
+            );
+            assert_eq!(unsaved_buffers[0].contents.as_deref(), Some("abc"));
+            assert_eq!(unsaved_buffers[0].base_contents.as_deref(), Some("a"));
+            assert_eq!(
+                unsaved_buffers[0].mtime,
+                buffer_a.read_with(cx, |buffer, _| buffer.saved_mtime())
+            );
+
+            // Nothing is written when the buffers haven't changed since the last snapshot
+            assert!(workspace
+                .update(cx, |workspace, cx| {
+                    snapshot_buffers(workspace, workspace_id, &mut last_snapshot, cx)
+                })
+                .is_none());
+
+            unsaved_buffers
+        };
+
+        // The buffer comes back in an editor, as dirty as it was
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        workspace
+            .update(cx, |_, cx| {
+                cx.spawn(|workspace, mut cx| async move {
+                    restore_buffers(workspace, unsaved_buffers, &mut cx).await
+                })
+            })
+            .await
+            .unwrap();
+        workspace.update(cx, |workspace, cx| {
+            let editor = workspace
+                .active_item(cx)
+                .and_then(|item| item.downcast::<Editor>())
+                .unwrap();
+            let buffer = editor.read(cx).buffer().read(cx).as_singleton().unwrap();
+            let buffer = buffer.read(cx);
+            assert_eq!(buffer.text(), "abc");
+            assert!(buffer.is_dirty());
+            assert!(!buffer.has_conflict());
+            assert_eq!(
+                buffer.rope_for_version(buffer.saved_version()).to_string(),
+                "a"
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_restore_timeout_and_clear_on_quit(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        cx.executor().allow_parking();
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/dir", json!({ "a.txt": "a" })).await;
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+        DB.save_unsaved_buffers(
+            workspace_id,
+            vec![SerializedEditor {
+                abs_path: Some(PathBuf::from("/dir/a.txt")),
+                contents: Some("abc".to_owned()),
+                language: None,
+                mtime: None,
+                base_contents: Some("a".to_owned()),
+            }],
+        )
+        .await
+        .unwrap();
+
+        let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+        let app_state = cx.update(AppState::test);
+        let (workspace, cx) = cx.add_window_view(|cx| {
+            Workspace::new(Some(workspace_id), project.clone(), app_state, cx)
+        });
+
+        // The workspace is never restored, so the buffers are reopened after the timeout.
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            assert!(workspace.active_item(cx).is_none())
+        });
+        cx.executor().advance_clock(RESTORE_TIMEOUT);
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            let editor = workspace
+                .active_item(cx)
+                .and_then(|item| item.downcast::<Editor>())
+                .unwrap();
+            assert_eq!(editor.read(cx).text(cx), "abc");
+        });
+        assert_eq!(DB.get_unsaved_buffers(workspace_id).await.unwrap().len(), 1);
+
+        // Quitting normally clears the snapshots. The workspace is still alive, so this isn't
+        // the workspace being released.
+        cx.cx.update(|cx| cx.shutdown());
+        // Wait for the writes queued while quitting.
+        DB.write(|_| {}).await;
+        assert_eq!(
+            DB.get_unsaved_buffers(workspace_id).await.unwrap(),
+            Vec::new()
+        );
+        drop(workspace);
+    }
+}
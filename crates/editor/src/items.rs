@@ -8,16 +8,17 @@ use crate::{
 use anyhow::{anyhow, Context as _, Result};
 use collections::HashSet;
 use file_icons::FileIcons;
+use fs::MTime;
 use futures::future::try_join_all;
 use git::repository::GitFileStatus;
 use gpui::{
     point, AnyElement, AppContext, AsyncWindowContext, Context, Entity, EntityId, EventEmitter,
-    IntoElement, Model, ParentElement, Pixels, SharedString, Styled, Task, View, ViewContext,
-    VisualContext, WeakView, WindowContext,
+    IntoElement, Model, ModelContext, ParentElement, Pixels, SharedString, Styled, Task, View,
+    ViewContext, VisualContext, WeakView, WindowContext,
 };
 use language::{
-    proto::serialize_anchor as serialize_text_anchor, Bias, Buffer, CharKind, DiskState, Point,
-    SelectionGoal,
+    proto::serialize_anchor as serialize_text_anchor, Bias, Buffer, BufferSnapshot, CharKind,
+    DiskState, Point, SelectionGoal,
 };
 use lsp::DiagnosticSeverity;
 use multi_buffer::AnchorRangeExt;
@@ -36,7 +37,7 @@ use std::{
     cmp::{self, Ordering},
     iter,
     ops::Range,
-    path::Path,
+    path::{Path, PathBuf},
     sync::Arc,
 };
 use text::{BufferId, Selection};
@@ -972,6 +973,7 @@ impl SerializableItem for Editor {
                         contents: None,
                         language: None,
                         mtime: None,
+                        base_contents: None,
                     }
                 }
             }
@@ -1033,6 +1035,7 @@ impl SerializableItem for Editor {
                 abs_path: Some(abs_path),
                 contents,
                 mtime,
+                base_contents,
                 ..
             } => {
                 let project_item = project.update(cx, |project, cx| {
@@ -1059,17 +1062,13 @@ impl SerializableItem for Editor {
                             // by loading the file (git diff base, ...).
                             if let Some(buffer_text) = contents {
                                 buffer.update(&mut cx, |buffer, cx| {
-                                    // If we did restore an mtime, we want to store it on the buffer
-                                    // so that the next edit will mark the buffer as dirty/conflicted.
-                                    if mtime.is_some() {
-                                        buffer.did_reload(
-                                            buffer.version(),
-                                            buffer.line_ending(),
-                                            mtime,
-                                            cx,
-                                        );
-                                    }
-                                    buffer.set_text(buffer_text, cx);
+                                    restore_unsaved_contents(
+                                        buffer,
+                                        buffer_text,
+                                        base_contents,
+                                        mtime,
+                                        cx,
+                                    );
                                 })?;
                             }
 
@@ -1144,25 +1143,21 @@ impl SerializableItem for Editor {
 
         let is_dirty = buffer.read(cx).is_dirty();
         let mtime = buffer.read(cx).saved_mtime();
+        let saved_version = buffer.read(cx).saved_version().clone();
 
         let snapshot = buffer.read(cx).snapshot();
 
         Some(cx.spawn(|_this, cx| async move {
             cx.background_executor()
                 .spawn(async move {
-                    let (contents, language) = if serialize_dirty_buffers && is_dirty {
-                        let contents = snapshot.text();
-                        let language = snapshot.language().map(|lang| lang.name().to_string());
-                        (Some(contents), language)
+                    let editor = if serialize_dirty_buffers && is_dirty {
+                        unsaved_contents(abs_path, &snapshot, &saved_version, mtime)
                     } else {
-                        (None, None)
-                    };
-
-                    let editor = SerializedEditor {
-                        abs_path,
-                        contents,
-                        language,
-                        mtime,
+                        SerializedEditor {
+                            abs_path,
+                            mtime,
+                            ..Default::default()
+                        }
                     };
 
                     DB.save_serialized_editor(item_id, workspace_id, editor)
@@ -1184,6 +1179,47 @@ impl SerializableItem for Editor {
     }
 }
 
+/// Captures the unsaved state of a dirty buffer, including the text it was last saved with,
+/// so that [`restore_unsaved_contents`] can bring it back exactly.
+pub(crate) fn unsaved_contents(
+    abs_path: Option<PathBuf>,
+    snapshot: &BufferSnapshot,
+    saved_version: &clock::Global,
+    mtime: Option<MTime>,
+) -> SerializedEditor {
+    let base_contents = abs_path
+        .is_some()
+        .then(|| snapshot.rope_for_version(saved_version).to_string());
+    SerializedEditor {
+        abs_path,
+        contents: Some(snapshot.text()),
+        language: snapshot.language().map(|lang| lang.name().to_string()),
+        mtime,
+        base_contents,
+    }
+}
+
+/// Restores the contents of a buffer that was serialized while it was dirty.
+///
+/// If we did restore an mtime, we first put back the text the buffer was last saved with
+/// and store the mtime on the buffer, so that the restored contents mark the buffer as
+/// dirty, and as conflicted if the file has changed on disk since.
+pub(crate) fn restore_unsaved_contents(
+    buffer: &mut Buffer,
+    contents: String,
+    base_contents: Option<String>,
+    mtime: Option<MTime>,
+    cx: &mut ModelContext<Buffer>,
+) {
+    if mtime.is_some() {
+        if let Some(base_contents) = base_contents {
+            buffer.set_text(base_contents, cx);
+        }
+        buffer.did_reload(buffer.version(), buffer.line_ending(), mtime, cx);
+    }
+    buffer.set_text(contents, cx);
+}
+
 impl ProjectItem for Editor {
     type Item = Buffer;
 
@@ -1318,6 +1354,14 @@ impl SearchableItem for Editor {
     ) {
         let text = self.buffer.read(cx);
         let text = text.snapshot(cx);
+        if query.is_structural() {
+            if let Some(replacement) = structural_replacement_for(&text, identifier, query) {
+                self.transact(cx, |this, cx| {
+                    this.edit([(identifier.clone(), Arc::from(replacement))], cx);
+                });
+            }
+            return;
+        }
         let text = text.text_for_range(identifier.clone()).collect::<Vec<_>>();
         let text: Cow<_> = if text.len() == 1 {
             text.first().cloned().unwrap().into()
@@ -1342,6 +1386,12 @@ impl SearchableItem for Editor {
         let text = text.snapshot(cx);
         let mut edits = vec![];
         for m in matches {
+            if query.is_structural() {
+                if let Some(replacement) = structural_replacement_for(&text, m, query) {
+                    edits.push((m.clone(), Arc::from(replacement)));
+                }
+                continue;
+            }
             let text = text.text_for_range(m.clone()).collect::<Vec<_>>();
             let text: Cow<_> = if text.len() == 1 {
                 text.first().cloned().unwrap().into()
@@ -1500,6 +1550,18 @@ impl SearchableItem for Editor {
     }
 }
 
+/// Structural replacements depend on the syntax tree around a match, so they're computed
+/// in the buffer containing the match rather than from its text.
+fn structural_replacement_for(
+    snapshot: &MultiBufferSnapshot,
+    range: &Range<Anchor>,
+    query: &SearchQuery,
+) -> Option<String> {
+    let excerpt = snapshot.excerpt_containing(range.clone())?;
+    let buffer_range = excerpt.map_range_to_buffer(range.to_offset(snapshot));
+    query.structural_replacement_for(excerpt.buffer(), buffer_range)
+}
+
 pub fn active_match_index(
     ranges: &[Range<Anchor>],
     cursor: &Anchor,
@@ -1699,6 +1761,7 @@ mod tests {
                 contents: Some("fn main() {}".to_string()),
                 language: Some("Rust".to_string()),
                 mtime: Some(mtime),
+                base_contents: None,
             };
 
             DB.save_serialized_editor(item_id, workspace_id, serialized_editor.clone())
@@ -1730,6 +1793,7 @@ mod tests {
                 contents: None,
                 language: None,
                 mtime: None,
+                base_contents: None,
             };
 
             DB.save_serialized_editor(item_id, workspace_id, serialized_editor)
@@ -1765,6 +1829,7 @@ mod tests {
                 contents: Some("hello".to_string()),
                 language: Some("Rust".to_string()),
                 mtime: None,
+                base_contents: None,
             };
 
             DB.save_serialized_editor(item_id, workspace_id, serialized_editor)
@@ -1801,6 +1866,7 @@ mod tests {
                 contents: Some("fn main() {}".to_string()),
                 language: Some("Rust".to_string()),
                 mtime: Some(old_mtime),
+                base_contents: None,
             };
 
             DB.save_serialized_editor(item_id, workspace_id, serialized_editor)
@@ -1815,5 +1881,43 @@ mod tests {
                 assert!(editor.has_conflict(cx)); // The editor should have a conflict
             });
         }
+
+        // Test case 5: Deserialize with path, content, base content, and old mtime
+        {
+            let project = Project::test(fs.clone(), ["/file.rs".as_ref()], cx).await;
+            let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+            let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+
+            let item_id = 9678 as ItemId;
+            let old_mtime = MTime::from_seconds_and_nanos(0, 50);
+            let serialized_editor = SerializedEditor {
+                abs_path: Some(PathBuf::from("/file.rs")),
+                contents: Some("fn main() {}".to_string()),
+                language: Some("Rust".to_string()),
+                mtime: Some(old_mtime),
+                base_contents: Some("fn base() {}".to_string()),
+            };
+
+            DB.save_serialized_editor(item_id, workspace_id, serialized_editor)
+                .await
+                .unwrap();
+
+            let deserialized =
+                deserialize_editor(item_id, workspace_id, workspace, project, cx).await;
+
+            deserialized.update(cx, |editor, cx| {
+                assert_eq!(editor.text(cx), "fn main() {}");
+                assert!(editor.is_dirty(cx));
+                assert!(editor.has_conflict(cx));
+
+                // The buffer was last saved with the base contents, not with what's on disk now
+                let buffer = editor.buffer().read(cx).as_singleton().unwrap().read(cx);
+                assert_eq!(
+                    buffer.rope_for_version(buffer.saved_version()).to_string(),
+                    "fn base() {}"
+                );
+            });
+        }
     }
 }
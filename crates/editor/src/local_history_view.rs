@@ -0,0 +1,358 @@
+use crate::{
+    actions::{RevertFile, RevertSelectedHunks},
+    Editor, EditorEvent,
+};
+use gpui::{
+    actions, Action, AppContext, EventEmitter, FocusableView, Model, Render, Subscription, Task,
+    View,
+};
+use language::{Buffer, BufferEvent};
+use project::{
+    buffer_store::BufferChangeSet,
+    local_history::{LocalHistory, LocalHistoryEntry},
+    Project,
+};
+use std::{any::TypeId, path::PathBuf, sync::Arc};
+use time::{OffsetDateTime, UtcOffset};
+use ui::{prelude::*, ButtonLike, KeyBinding, ListItem, ListItemSpacing};
+use util::ResultExt as _;
+use workspace::{searchable::SearchableItemHandle, Item, Workspace};
+
+actions!(local_history, [OpenTimeline]);
+
+pub(crate) fn init(cx: &mut AppContext) {
+    cx.observe_new_views(LocalHistoryView::register).detach();
+}
+
+/// Shows the snapshots the local history has of a file, and the differences between the
+/// selected snapshot and the file's buffer, so that changes can be restored hunk by hunk.
+pub struct LocalHistoryView {
+    buffer: Model<Buffer>,
+    abs_path: PathBuf,
+    local_history: Arc<LocalHistory>,
+    /// Ordered from newest to oldest.
+    entries: Vec<LocalHistoryEntry>,
+    selected_entry: Option<LocalHistoryEntry>,
+    editor: View<Editor>,
+    change_set: Model<BufferChangeSet>,
+    title: SharedString,
+    load_entries_task: Task<()>,
+    load_snapshot_task: Task<()>,
+    _subscription: Subscription,
+}
+
+impl LocalHistoryView {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(Self::open_timeline);
+    }
+
+    fn open_timeline(workspace: &mut Workspace, _: &OpenTimeline, cx: &mut ViewContext<Workspace>) {
+        let Some(local_history) = LocalHistory::global(cx) else {
+            return;
+        };
+        let Some(buffer) = workspace
+            .active_item_as::<Editor>(cx)
+            .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton())
+        else {
+            return;
+        };
+        let Some(file) = buffer.read(cx).file().and_then(|file| file.as_local()) else {
+            return;
+        };
+        let abs_path = file.abs_path(cx);
+        let title = format!("History of {}", file.file_name(cx).to_string_lossy());
+
+        if let Some(existing) = workspace
+            .items_of_type::<Self>(cx)
+            .find(|view| view.read(cx).buffer == buffer)
+        {
+            workspace.activate_item(&existing, true, true, cx);
+        } else {
+            let view =
+                cx.new_view(|cx| Self::new(buffer, abs_path, title.into(), local_history, cx));
+            workspace.add_item_to_active_pane(Box::new(view), None, true, cx);
+        }
+    }
+
+    pub fn new(
+        buffer: Model<Buffer>,
+        abs_path: PathBuf,
+        title: SharedString,
+        local_history: Arc<LocalHistory>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let snapshot = buffer.read(cx).text_snapshot();
+        let change_set = cx.new_model(|_| BufferChangeSet::new(&snapshot));
+        // The diff is against the selected snapshot rather than the index, so the editor
+        // doesn't get a project that would add the buffer's git changes to it.
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::for_buffer(buffer.clone(), None, cx);
+            editor.set_expand_all_diff_hunks();
+            editor.diff_map.add_change_set(change_set.clone(), cx);
+            editor
+        });
+        let mut this = Self {
+            _subscription: cx.subscribe(&buffer, Self::on_buffer_event),
+            buffer,
+            abs_path,
+            local_history,
+            entries: Vec::new(),
+            selected_entry: None,
+            editor,
+            change_set,
+            title,
+            load_entries_task: Task::ready(()),
+            load_snapshot_task: Task::ready(()),
+        };
+        this.load_entries(cx);
+        this
+    }
+
+    /// Selects the given snapshot, and shows the changes made to the file since it was taken.
+    pub fn select_entry(&mut self, entry: LocalHistoryEntry, cx: &mut ViewContext<Self>) {
+        let local_history = self.local_history.clone();
+        let abs_path = self.abs_path.clone();
+        self.selected_entry = Some(entry.clone());
+        self.load_snapshot_task = cx.spawn(|this, mut cx| async move {
+            let Some(contents) = local_history.load(&abs_path, &entry).await.log_err() else {
+                return;
+            };
+            this.update(&mut cx, |this, cx| {
+                let snapshot = this.buffer.read(cx).text_snapshot();
+                this.change_set.update(cx, |change_set, cx| {
+                    let _ = change_set.set_base_text(contents, snapshot, cx);
+                });
+            })
+            .ok();
+        });
+        cx.notify();
+    }
+
+    fn load_entries(&mut self, cx: &mut ViewContext<Self>) {
+        let local_history = self.local_history.clone();
+        let abs_path = self.abs_path.clone();
+        self.load_entries_task = cx.spawn(|this, mut cx| async move {
+            let Some(mut entries) = local_history.entries(&abs_path).await.log_err() else {
+                return;
+            };
+            entries.reverse();
+            this.update(&mut cx, |this, cx| {
+                let selected_entry = this
+                    .selected_entry
+                    .clone()
+                    .filter(|selected_entry| entries.contains(selected_entry))
+                    .or_else(|| entries.first().cloned());
+                this.entries = entries;
+                match selected_entry {
+                    Some(entry) if Some(&entry) != this.selected_entry.as_ref() => {
+                        this.select_entry(entry, cx)
+                    }
+                    Some(_) => {}
+                    None => {
+                        this.selected_entry = None;
+                        let snapshot = this.buffer.read(cx).text_snapshot();
+                        this.change_set.update(cx, |change_set, cx| {
+                            change_set.unset_base_text(snapshot, cx)
+                        });
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn on_buffer_event(
+        &mut self,
+        buffer: Model<Buffer>,
+        event: &BufferEvent,
+        cx: &mut ViewContext<Self>,
+    ) {
+        match event {
+            BufferEvent::Edited | BufferEvent::Reloaded => {
+                let snapshot = buffer.read(cx).text_snapshot();
+                self.change_set.update(cx, |change_set, cx| {
+                    let _ = change_set.recalculate_diff(snapshot, cx);
+                });
+            }
+            // Saving records a new snapshot.
+            BufferEvent::Saved => self.load_entries(cx),
+            _ => {}
+        }
+    }
+
+    fn render_entries(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let now = OffsetDateTime::now_utc();
+        let timezone =
+            UtcOffset::from_whole_seconds(chrono::Local::now().offset().local_minus_utc())
+                .unwrap_or(UtcOffset::UTC);
+
+        v_flex()
+            .id("local-history-entries")
+            .w(rems(16.))
+            .h_full()
+            .flex_none()
+            .p_1()
+            .overflow_y_scroll()
+            .border_r_1()
+            .border_color(cx.theme().colors().border)
+            .when(self.entries.is_empty(), |this| {
+                this.child(
+                    div().p_2().child(
+                        Label::new("A snapshot is kept each time the file is saved.")
+                            .color(Color::Muted),
+                    ),
+                )
+            })
+            .children(self.entries.iter().enumerate().map(|(ix, entry)| {
+                let timestamp = time_format::format_localized_timestamp(
+                    OffsetDateTime::from(entry.timestamp),
+                    now,
+                    timezone,
+                    time_format::TimestampFormat::EnhancedAbsolute,
+                );
+                let entry = entry.clone();
+                ListItem::new(ix)
+                    .spacing(ListItemSpacing::Sparse)
+                    .selected(self.selected_entry.as_ref() == Some(&entry))
+                    .child(Label::new(timestamp))
+                    .on_click(cx.listener(move |this, _, cx| {
+                        this.select_entry(entry.clone(), cx);
+                    }))
+            }))
+    }
+
+    fn render_toolbar(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let focus_handle = self.editor.focus_handle(cx);
+        let has_selected_entry = self.selected_entry.is_some();
+        let mut action_button = |id: &'static str, label: &'static str, action: Box<dyn Action>| {
+            let focus_handle = focus_handle.clone();
+            let keybinding = KeyBinding::for_action_in(action.as_ref(), &focus_handle, cx)
+                .map(|binding| binding.into_any_element());
+            ButtonLike::new(id)
+                .child(Label::new(label))
+                .children(keybinding)
+                .disabled(!has_selected_entry)
+                .on_click(move |_event, cx| focus_handle.dispatch_action(action.as_ref(), cx))
+        };
+
+        h_flex()
+            .w_full()
+            .p_1()
+            .gap_1()
+            .justify_end()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(action_button(
+                "restore-selected-hunks",
+                "Restore Selected Hunks",
+                Box::new(RevertSelectedHunks),
+            ))
+            .child(action_button(
+                "restore-file",
+                "Restore Snapshot",
+                Box::new(RevertFile),
+            ))
+    }
+}
+
+impl Render for LocalHistoryView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        h_flex()
+            .size_full()
+            .key_context("LocalHistoryView")
+            .child(self.render_entries(cx))
+            .child(
+                v_flex()
+                    .size_full()
+                    .child(self.render_toolbar(cx))
+                    .child(div().flex_1().child(self.editor.clone())),
+            )
+    }
+}
+
+impl FocusableView for LocalHistoryView {
+    fn focus_handle(&self, cx: &AppContext) -> gpui::FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<EditorEvent> for LocalHistoryView {}
+
+impl Item for LocalHistoryView {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _cx: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::HistoryRerun))
+    }
+
+    fn tab_content_text(&self, _cx: &WindowContext) -> Option<SharedString> {
+        Some(self.title.clone())
+    }
+
+    fn as_searchable(&self, _: &View<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a View<Self>,
+        _: &'a AppContext,
+    ) -> Option<gpui::AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn added_to_workspace(&mut self, workspace: &mut Workspace, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, cx| {
+            Item::added_to_workspace(editor, workspace, cx)
+        });
+    }
+
+    fn deactivated(&mut self, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, Item::deactivated);
+    }
+
+    fn navigate(&mut self, data: Box<dyn std::any::Any>, cx: &mut ViewContext<Self>) -> bool {
+        self.editor
+            .update(cx, |editor, cx| Item::navigate(editor, data, cx))
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: workspace::ItemNavHistory,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            Item::set_nav_history(editor, nav_history, cx)
+        });
+    }
+
+    fn is_dirty(&self, cx: &AppContext) -> bool {
+        self.buffer.read(cx).is_dirty()
+    }
+
+    fn has_conflict(&self, cx: &AppContext) -> bool {
+        self.buffer.read(cx).has_conflict()
+    }
+
+    fn can_save(&self, cx: &AppContext) -> bool {
+        self.editor.read(cx).can_save(cx)
+    }
+
+    fn save(
+        &mut self,
+        format: bool,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<gpui::Result<()>> {
+        self.editor
+            .update(cx, |editor, cx| Item::save(editor, format, project, cx))
+    }
+}
@@ -1,11 +1,14 @@
 use std::sync::Arc;
 
-use crate::Editor;
-use gpui::{Model, WindowContext};
+use crate::{Editor, RunLanguageServerCommand};
+use anyhow::anyhow;
+use gpui::{Context as _, Model, ViewContext, VisualContext as _, WindowContext};
 use language::Buffer;
 use language::Language;
+use language::{point_to_lsp, LanguageServerCommandContext, LanguageServerCommandOutput};
 use lsp::LanguageServerId;
-use multi_buffer::Anchor;
+use multi_buffer::{Anchor, MultiBuffer};
+use text::ToPointUtf16;
 
 pub(crate) fn find_specific_language_server_in_selection<F>(
     editor: &Editor,
@@ -52,3 +55,121 @@ where
                 })
         })
 }
+
+/// Runs a command provided by one of the language servers for the buffer containing the
+/// newest selection.
+pub(crate) fn run_language_server_command(
+    editor: &mut Editor,
+    action: &RunLanguageServerCommand,
+    cx: &mut ViewContext<Editor>,
+) {
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+    let Some(workspace) = editor.workspace() else {
+        return;
+    };
+
+    let selection = editor.selections.newest_anchor().clone();
+    let multibuffer = editor.buffer().read(cx);
+    let Some((buffer, head)) = multibuffer.text_anchor_for_position(selection.head(), cx) else {
+        return;
+    };
+    let (start, end) = match (
+        multibuffer.text_anchor_for_position(selection.start, cx),
+        multibuffer.text_anchor_for_position(selection.end, cx),
+    ) {
+        (Some((start_buffer, start)), Some((end_buffer, end)))
+            if start_buffer == buffer && end_buffer == buffer =>
+        {
+            (start, end)
+        }
+        _ => (head, head),
+    };
+
+    let command = action.name.clone();
+    let Some((adapter, server)) = project
+        .read(cx)
+        .language_servers_for_buffer(buffer.read(cx), cx)
+        .find(|(adapter, _)| adapter.adapter.commands().contains(&command))
+        .map(|(adapter, server)| (adapter.clone(), server.clone()))
+    else {
+        log::warn!("no language server provides the command {command:?}");
+        return;
+    };
+
+    let buffer = buffer.read(cx);
+    let Some(abs_path) = buffer
+        .file()
+        .and_then(|file| file.as_local())
+        .map(|file| file.abs_path(cx))
+    else {
+        return;
+    };
+    let Ok(uri) = lsp::Url::from_file_path(&abs_path) else {
+        log::error!("failed to convert {abs_path:?} to a URI");
+        return;
+    };
+    let snapshot = buffer.snapshot();
+    let context = LanguageServerCommandContext {
+        uri,
+        cursor: point_to_lsp(head.to_point_utf16(&snapshot)),
+        selection: lsp::Range {
+            start: point_to_lsp(start.to_point_utf16(&snapshot)),
+            end: point_to_lsp(end.to_point_utf16(&snapshot)),
+        },
+    };
+    let language_registry = project.read(cx).languages().clone();
+
+    cx.spawn(|_editor, mut cx| async move {
+        let output = adapter
+            .adapter
+            .clone()
+            .run_command(command, context, server)
+            .await?;
+        match output {
+            LanguageServerCommandOutput::None => {}
+            LanguageServerCommandOutput::OpenDocument(uri) => {
+                let abs_path = uri
+                    .to_file_path()
+                    .map_err(|()| anyhow!("invalid file URI {uri}"))?;
+                workspace
+                    .update(&mut cx, |workspace, cx| {
+                        workspace.open_abs_path(abs_path, false, cx)
+                    })?
+                    .await?;
+            }
+            LanguageServerCommandOutput::OpenBuffer {
+                title,
+                text,
+                language,
+            } => {
+                let language = match language {
+                    Some(language) => Some(language_registry.language_for_name(&language.0).await?),
+                    None => None,
+                };
+                let buffer = project
+                    .update(&mut cx, |project, cx| project.create_buffer(cx))?
+                    .await?;
+                workspace.update(&mut cx, |workspace, cx| {
+                    buffer.update(cx, |buffer, cx| {
+                        buffer.edit([(0..0, text)], None, cx);
+                        buffer.set_language(language, cx)
+                    });
+                    let multibuffer =
+                        cx.new_model(|cx| MultiBuffer::singleton(buffer, cx).with_title(title));
+                    workspace.add_item_to_active_pane(
+                        Box::new(cx.new_view(|cx| {
+                            Editor::for_multibuffer(multibuffer, Some(project), true, cx)
+                        })),
+                        None,
+                        true,
+                        cx,
+                    );
+                })?;
+            }
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
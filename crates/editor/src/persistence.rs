@@ -15,11 +15,13 @@ pub(crate) struct SerializedEditor {
     pub(crate) contents: Option<String>,
     pub(crate) language: Option<String>,
     pub(crate) mtime: Option<MTime>,
+    /// The text the buffer had when it was last saved or reloaded, when it's dirty.
+    pub(crate) base_contents: Option<String>,
 }
 
 impl StaticColumnCount for SerializedEditor {
     fn column_count() -> usize {
-        5
+        6
     }
 }
 
@@ -42,6 +44,7 @@ impl Bind for SerializedEditor {
                 statement.bind::<Option<i32>>(&None, start_index)?
             }
         };
+        let start_index = statement.bind(&self.base_contents, start_index)?;
         Ok(start_index)
     }
 }
@@ -58,6 +61,8 @@ impl Column for SerializedEditor {
             Column::column(statement, start_index)?;
         let (mtime_nanos, start_index): (Option<i32>, i32) =
             Column::column(statement, start_index)?;
+        let (base_contents, start_index): (Option<String>, i32) =
+            Column::column(statement, start_index)?;
 
         let mtime = mtime_seconds
             .zip(mtime_nanos)
@@ -68,6 +73,7 @@ impl Column for SerializedEditor {
             contents,
             language,
             mtime,
+            base_contents,
         };
         Ok((editor, start_index))
     }
@@ -86,6 +92,17 @@ define_connection!(
     //   language: Option<String>,
     //   mtime_seconds: Option<i64>,
     //   mtime_nanos: Option<i32>,
+    //   base_contents: Option<String>,
+    // )
+    //
+    // unsaved_buffers(
+    //   workspace_id: usize,
+    //   path: PathBuf,
+    //   contents: String,
+    //   language: Option<String>,
+    //   mtime_seconds: Option<i64>,
+    //   mtime_nanos: Option<i32>,
+    //   base_contents: Option<String>,
     // )
     pub static ref DB: EditorDb<WorkspaceDb> =
         &[sql! (
@@ -134,13 +151,30 @@ define_connection!(
             ALTER TABLE editors ADD COLUMN mtime_seconds INTEGER DEFAULT NULL;
             ALTER TABLE editors ADD COLUMN mtime_nanos INTEGER DEFAULT NULL;
         ),
+        sql! (
+            ALTER TABLE editors ADD COLUMN base_contents TEXT DEFAULT NULL;
+
+            CREATE TABLE unsaved_buffers(
+                workspace_id INTEGER NOT NULL,
+                path BLOB NOT NULL,
+                contents TEXT NOT NULL,
+                language TEXT,
+                mtime_seconds INTEGER,
+                mtime_nanos INTEGER,
+                base_contents TEXT,
+                PRIMARY KEY(workspace_id, path),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+            ) STRICT;
+        ),
         ];
 );
 
 impl EditorDb {
     query! {
         pub fn get_serialized_editor(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<SerializedEditor>> {
-            SELECT path, contents, language, mtime_seconds, mtime_nanos FROM editors
+            SELECT path, contents, language, mtime_seconds, mtime_nanos, base_contents FROM editors
             WHERE item_id = ? AND workspace_id = ?
         }
     }
@@ -148,9 +182,9 @@ impl EditorDb {
     query! {
         pub async fn save_serialized_editor(item_id: ItemId, workspace_id: WorkspaceId, serialized_editor: SerializedEditor) -> Result<()> {
             INSERT INTO editors
-                (item_id, workspace_id, path, contents, language, mtime_seconds, mtime_nanos)
+                (item_id, workspace_id, path, contents, language, mtime_seconds, mtime_nanos, base_contents)
             VALUES
-                (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
             ON CONFLICT DO UPDATE SET
                 item_id = ?1,
                 workspace_id = ?2,
@@ -158,10 +192,51 @@ impl EditorDb {
                 contents = ?4,
                 language = ?5,
                 mtime_seconds = ?6,
-                mtime_nanos = ?7
+                mtime_nanos = ?7,
+                base_contents = ?8
+        }
+    }
+
+    // Returns the dirty buffers of a workspace that weren't open in an editor of their own
+    // when they were last snapshotted.
+    query! {
+        pub read async fn get_unsaved_buffers(workspace_id: WorkspaceId) -> Result<Vec<SerializedEditor>> {
+            SELECT path, contents, language, mtime_seconds, mtime_nanos, base_contents FROM unsaved_buffers
+            WHERE workspace_id = ?
+            ORDER BY path
         }
     }
 
+    /// Replaces the unsaved buffers stored for a workspace. Buffers without a path can't be
+    /// stored, because untitled buffers are always open in an editor of their own.
+    pub async fn save_unsaved_buffers(
+        &self,
+        workspace_id: WorkspaceId,
+        buffers: Vec<SerializedEditor>,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            conn.with_savepoint("save_unsaved_buffers", || {
+                conn.exec_bound(sql!(
+                    DELETE FROM unsaved_buffers WHERE workspace_id = ?
+                ))?(workspace_id)?;
+
+                let mut insert = conn.exec_bound(sql!(
+                    INSERT INTO unsaved_buffers
+                        (workspace_id, path, contents, language, mtime_seconds, mtime_nanos, base_contents)
+                    VALUES
+                        (?, ?, ?, ?, ?, ?, ?)
+                ))?;
+                for buffer in buffers {
+                    if buffer.abs_path.is_some() && buffer.contents.is_some() {
+                        insert((workspace_id, buffer))?;
+                    }
+                }
+                Ok(())
+            })
+        })
+        .await
+    }
+
     // Returns the scroll top row, and offset
     query! {
         pub fn get_scroll_position(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<(u32, f32, f32)>> {
@@ -228,6 +303,7 @@ mod tests {
             contents: None,
             language: None,
             mtime: None,
+            base_contents: None,
         };
 
         DB.save_serialized_editor(1234, workspace_id, serialized_editor.clone())
@@ -240,12 +316,13 @@ mod tests {
             .unwrap();
         assert_eq!(have, serialized_editor);
 
-        // Now update contents and language
+        // Now update contents, language and base contents
         let serialized_editor = SerializedEditor {
             abs_path: Some(PathBuf::from("testing.txt")),
             contents: Some("Test".to_owned()),
             language: Some("Go".to_owned()),
             mtime: None,
+            base_contents: Some("Base".to_owned()),
         };
 
         DB.save_serialized_editor(1234, workspace_id, serialized_editor.clone())
@@ -264,6 +341,7 @@ mod tests {
             contents: None,
             language: None,
             mtime: None,
+            base_contents: None,
         };
 
         DB.save_serialized_editor(1234, workspace_id, serialized_editor.clone())
@@ -282,6 +360,7 @@ mod tests {
             contents: None,
             language: None,
             mtime: Some(MTime::from_seconds_and_nanos(100, 42)),
+            base_contents: None,
         };
 
         DB.save_serialized_editor(1234, workspace_id, serialized_editor.clone())
@@ -294,4 +373,57 @@ mod tests {
             .unwrap();
         assert_eq!(have, serialized_editor);
     }
+
+    #[gpui::test]
+    async fn test_save_and_get_unsaved_buffers() {
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+
+        let first_buffer = SerializedEditor {
+            abs_path: Some(PathBuf::from("/a.txt")),
+            contents: Some("a".to_owned()),
+            language: None,
+            mtime: Some(MTime::from_seconds_and_nanos(100, 42)),
+            base_contents: Some("base a".to_owned()),
+        };
+        let second_buffer = SerializedEditor {
+            abs_path: Some(PathBuf::from("/b.txt")),
+            contents: Some("b".to_owned()),
+            language: Some("Go".to_owned()),
+            mtime: None,
+            base_contents: None,
+        };
+        let untitled_buffer = SerializedEditor {
+            abs_path: None,
+            contents: Some("untitled".to_owned()),
+            ..Default::default()
+        };
+
+        DB.save_unsaved_buffers(
+            workspace_id,
+            vec![second_buffer.clone(), untitled_buffer, first_buffer.clone()],
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            DB.get_unsaved_buffers(workspace_id).await.unwrap(),
+            vec![first_buffer.clone(), second_buffer]
+        );
+
+        // Saving again replaces the buffers that were stored before
+        DB.save_unsaved_buffers(workspace_id, vec![first_buffer.clone()])
+            .await
+            .unwrap();
+        assert_eq!(
+            DB.get_unsaved_buffers(workspace_id).await.unwrap(),
+            vec![first_buffer]
+        );
+
+        DB.save_unsaved_buffers(workspace_id, Vec::new())
+            .await
+            .unwrap();
+        assert_eq!(
+            DB.get_unsaved_buffers(workspace_id).await.unwrap(),
+            Vec::new()
+        );
+    }
 }
@@ -1,7 +1,12 @@
-use crate::{ApplyAllDiffHunks, Editor, EditorEvent, SemanticsProvider};
+use crate::{
+    ApplyAllDiffHunks, ApplyDiffHunksInFile, Editor, EditorEvent, RejectAllDiffHunks,
+    RejectDiffHunksInFile, RollBackAppliedDiffHunks, SemanticsProvider,
+};
 use collections::HashSet;
 use futures::{channel::mpsc, future::join_all};
-use gpui::{AppContext, EventEmitter, FocusableView, Model, Render, Subscription, Task, View};
+use gpui::{
+    Action, AppContext, EventEmitter, FocusableView, Model, Render, Subscription, Task, View,
+};
 use language::{Buffer, BufferEvent, Capability};
 use multi_buffer::{ExcerptRange, MultiBuffer};
 use project::{buffer_store::BufferChangeSet, Project};
@@ -36,6 +41,7 @@ struct BufferEntry {
 
 pub struct ProposedChangesEditorToolbar {
     current_editor: Option<View<ProposedChangesEditor>>,
+    _observe_current_editor: Option<Subscription>,
 }
 
 struct RecalculateDiff {
@@ -150,28 +156,95 @@ impl ProposedChangesEditor {
         cx.notify();
     }
 
+    /// Returns whether any proposed change has been neither applied nor rejected.
+    pub fn has_pending_changes(&self, cx: &AppContext) -> bool {
+        self.buffer_entries
+            .iter()
+            .any(|entry| Self::has_pending_changes_in_entry(entry, cx))
+    }
+
+    /// Returns whether any proposed change has been applied to its base buffer
+    /// and can be rolled back.
+    pub fn has_applied_changes(&self, cx: &AppContext) -> bool {
+        self.buffer_entries
+            .iter()
+            .any(|entry| entry.branch.read(cx).has_merges_into_base())
+    }
+
+    /// Applies every proposed change in the given base buffer and saves it.
+    pub fn apply_changes_in_buffer(
+        &mut self,
+        base_buffer: &Model<Buffer>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(entry) = self.entry_for_base(base_buffer) else {
+            return;
+        };
+        entry
+            .branch
+            .update(cx, |branch, cx| branch.merge_into_base(Vec::new(), cx));
+        self.save_base_buffers(cx);
+    }
+
+    /// Rejects every proposed change in the given base buffer that hasn't been
+    /// applied yet.
+    pub fn reject_changes_in_buffer(
+        &mut self,
+        base_buffer: &Model<Buffer>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(entry) = self.entry_for_base(base_buffer) {
+            Self::discard_branch_changes(entry, cx);
+        }
+    }
+
+    /// Applies every proposed change and saves the affected buffers.
+    pub fn apply_all_changes(&mut self, cx: &mut ViewContext<Self>) {
+        for entry in &self.buffer_entries {
+            if Self::has_pending_changes_in_entry(entry, cx) {
+                entry
+                    .branch
+                    .update(cx, |branch, cx| branch.merge_into_base(Vec::new(), cx));
+            }
+        }
+        self.save_base_buffers(cx);
+    }
+
+    /// Rejects every proposed change that hasn't been applied yet.
+    pub fn reject_all_changes(&mut self, cx: &mut ViewContext<Self>) {
+        for entry in &self.buffer_entries {
+            Self::discard_branch_changes(entry, cx);
+        }
+    }
+
+    /// Restores every buffer to the state it was in before the changes were
+    /// proposed.
+    ///
+    /// All changes that were applied from this editor are undone in their base
+    /// buffers together, and any changes that are still pending are rejected, so
+    /// the files are never left with only some of the changes rolled back.
+    pub fn roll_back(&mut self, cx: &mut ViewContext<Self>) {
+        let mut rolled_back_applied_changes = false;
+        for entry in &self.buffer_entries {
+            rolled_back_applied_changes |= entry
+                .branch
+                .update(cx, |branch, cx| branch.undo_merges_into_base(cx));
+            Self::discard_branch_changes(entry, cx);
+        }
+
+        if rolled_back_applied_changes {
+            self.save_base_buffers(cx);
+        }
+        cx.notify();
+    }
+
     pub fn reset_locations<T: ToOffset>(
         &mut self,
         locations: Vec<ProposedChangeLocation<T>>,
         cx: &mut ViewContext<Self>,
     ) {
-        // Undo all branch changes
         for entry in &self.buffer_entries {
-            let base_version = entry.base.read(cx).version();
-            entry.branch.update(cx, |buffer, cx| {
-                let undo_counts = buffer
-                    .operations()
-                    .iter()
-                    .filter_map(|(timestamp, _)| {
-                        if !base_version.observed(*timestamp) {
-                            Some((*timestamp, u32::MAX))
-                        } else {
-                            None
-                        }
-                    })
-                    .collect();
-                buffer.undo_operations(undo_counts, cx);
-            });
+            Self::discard_branch_changes(entry, cx);
         }
 
         self.multibuffer.update(cx, |multibuffer, cx| {
@@ -229,6 +302,84 @@ impl ProposedChangesEditor {
         });
     }
 
+    fn entry_for_base(&self, base_buffer: &Model<Buffer>) -> Option<&BufferEntry> {
+        self.buffer_entries
+            .iter()
+            .find(|entry| &entry.base == base_buffer)
+    }
+
+    /// Returns the base buffer of the file containing the newest selection.
+    fn base_buffer_for_selection(&self, cx: &AppContext) -> Option<Model<Buffer>> {
+        let editor = self.editor.read(cx);
+        let head = editor.selections.newest_anchor().head();
+        let (branch_buffer, _) = editor
+            .buffer()
+            .read(cx)
+            .text_anchor_for_position(head, cx)?;
+        self.buffer_entries
+            .iter()
+            .find(|entry| entry.branch == branch_buffer)
+            .map(|entry| entry.base.clone())
+    }
+
+    fn has_pending_changes_in_entry(entry: &BufferEntry, cx: &AppContext) -> bool {
+        let base_version = entry.base.read(cx).version();
+        entry
+            .branch
+            .read(cx)
+            .edits_since::<usize>(&base_version)
+            .next()
+            .is_some()
+    }
+
+    /// Undoes all of the changes in the branch buffer that haven't been applied
+    /// to its base buffer.
+    fn discard_branch_changes(entry: &BufferEntry, cx: &mut ViewContext<Self>) {
+        let base_version = entry.base.read(cx).version();
+        entry.branch.update(cx, |buffer, cx| {
+            let undo_counts = buffer
+                .operations()
+                .iter()
+                .filter_map(|(timestamp, _)| {
+                    if !base_version.observed(*timestamp) {
+                        Some((*timestamp, u32::MAX))
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+            buffer.undo_operations(undo_counts, cx);
+        });
+    }
+
+    fn save_base_buffers(&mut self, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, cx| {
+            if let Some(project) = editor.project.clone() {
+                editor.save(true, project, cx).detach_and_log_err(cx);
+            }
+        });
+    }
+
+    fn apply_changes_in_file(&mut self, _: &ApplyDiffHunksInFile, cx: &mut ViewContext<Self>) {
+        if let Some(base_buffer) = self.base_buffer_for_selection(cx) {
+            self.apply_changes_in_buffer(&base_buffer, cx);
+        }
+    }
+
+    fn reject_changes_in_file(&mut self, _: &RejectDiffHunksInFile, cx: &mut ViewContext<Self>) {
+        if let Some(base_buffer) = self.base_buffer_for_selection(cx) {
+            self.reject_changes_in_buffer(&base_buffer, cx);
+        }
+    }
+
+    fn reject_all(&mut self, _: &RejectAllDiffHunks, cx: &mut ViewContext<Self>) {
+        self.reject_all_changes(cx);
+    }
+
+    fn roll_back_applied(&mut self, _: &RollBackAppliedDiffHunks, cx: &mut ViewContext<Self>) {
+        self.roll_back(cx);
+    }
+
     pub fn recalculate_all_buffer_diffs(&self) {
         for (ix, entry) in self.buffer_entries.iter().enumerate().rev() {
             self.recalculate_diffs_tx
@@ -244,7 +395,7 @@ impl ProposedChangesEditor {
         &mut self,
         buffer: Model<Buffer>,
         event: &BufferEvent,
-        _cx: &mut ViewContext<Self>,
+        cx: &mut ViewContext<Self>,
     ) {
         match event {
             BufferEvent::Operation { .. } => {
@@ -254,6 +405,7 @@ impl ProposedChangesEditor {
                         debounce: true,
                     })
                     .ok();
+                cx.notify();
             }
             // BufferEvent::DiffBaseChanged => {
             //     self.recalculate_diffs_tx
@@ -269,10 +421,14 @@ impl ProposedChangesEditor {
 }
 
 impl Render for ProposedChangesEditor {
-    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         div()
             .size_full()
             .key_context("ProposedChangesEditor")
+            .on_action(cx.listener(Self::apply_changes_in_file))
+            .on_action(cx.listener(Self::reject_changes_in_file))
+            .on_action(cx.listener(Self::reject_all))
+            .on_action(cx.listener(Self::roll_back_applied))
             .child(self.editor.clone())
     }
 }
@@ -359,6 +515,7 @@ impl ProposedChangesEditorToolbar {
     pub fn new() -> Self {
         Self {
             current_editor: None,
+            _observe_current_editor: None,
         }
     }
 
@@ -373,20 +530,44 @@ impl ProposedChangesEditorToolbar {
 
 impl Render for ProposedChangesEditorToolbar {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
-        let button_like = ButtonLike::new("apply-changes").child(Label::new("Apply All"));
+        let Some(editor) = &self.current_editor else {
+            return h_flex().child(
+                ButtonLike::new("apply-changes")
+                    .child(Label::new("Apply All"))
+                    .disabled(true),
+            );
+        };
 
-        match &self.current_editor {
-            Some(editor) => {
-                let focus_handle = editor.focus_handle(cx);
-                let keybinding = KeyBinding::for_action_in(&ApplyAllDiffHunks, &focus_handle, cx)
-                    .map(|binding| binding.into_any_element());
+        let focus_handle = editor.focus_handle(cx);
+        let has_pending_changes = editor.read(cx).has_pending_changes(cx);
+        let has_applied_changes = editor.read(cx).has_applied_changes(cx);
+        let mut action_button = |id: &'static str, label: &'static str, action: Box<dyn Action>| {
+            let focus_handle = focus_handle.clone();
+            let keybinding = KeyBinding::for_action_in(action.as_ref(), &focus_handle, cx)
+                .map(|binding| binding.into_any_element());
+            ButtonLike::new(id)
+                .child(Label::new(label))
+                .children(keybinding)
+                .on_click(move |_event, cx| focus_handle.dispatch_action(action.as_ref(), cx))
+        };
 
-                button_like.children(keybinding).on_click({
-                    move |_event, cx| focus_handle.dispatch_action(&ApplyAllDiffHunks, cx)
-                })
-            }
-            None => button_like.disabled(true),
-        }
+        h_flex()
+            .gap_1()
+            .when(has_applied_changes, |this| {
+                this.child(action_button(
+                    "roll-back-changes",
+                    "Roll Back",
+                    Box::new(RollBackAppliedDiffHunks),
+                ))
+            })
+            .child(
+                action_button("reject-changes", "Reject All", Box::new(RejectAllDiffHunks))
+                    .disabled(!has_pending_changes),
+            )
+            .child(
+                action_button("apply-changes", "Apply All", Box::new(ApplyAllDiffHunks))
+                    .disabled(!has_pending_changes),
+            )
     }
 }
 
@@ -396,10 +577,14 @@ impl ToolbarItemView for ProposedChangesEditorToolbar {
     fn set_active_pane_item(
         &mut self,
         active_pane_item: Option<&dyn workspace::ItemHandle>,
-        _cx: &mut ViewContext<Self>,
+        cx: &mut ViewContext<Self>,
     ) -> workspace::ToolbarItemLocation {
         self.current_editor =
             active_pane_item.and_then(|item| item.downcast::<ProposedChangesEditor>());
+        self._observe_current_editor = self
+            .current_editor
+            .as_ref()
+            .map(|editor| cx.observe(editor, |_, _, cx| cx.notify()));
         self.get_toolbar_item_location()
     }
 }
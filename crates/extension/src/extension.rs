@@ -1,4 +1,5 @@
 pub mod extension_builder;
+mod extension_capabilities;
 mod extension_host_proxy;
 mod extension_manifest;
 mod types;
@@ -11,9 +12,10 @@ use anyhow::{anyhow, bail, Context as _, Result};
 use async_trait::async_trait;
 use fs::normalize_path;
 use gpui::{AppContext, Task};
-use language::LanguageName;
+use language::{LanguageName, LanguageServerCommandContext, LanguageServerCommandOutput};
 use semantic_version::SemanticVersion;
 
+pub use crate::extension_capabilities::*;
 pub use crate::extension_host_proxy::*;
 pub use crate::extension_manifest::*;
 pub use crate::types::*;
@@ -40,6 +42,15 @@ pub trait KeyValueStoreDelegate: Send + Sync + 'static {
     fn insert(&self, key: String, docs: String) -> Task<Result<()>>;
 }
 
+/// A running language server, which extensions can send messages to.
+///
+/// Parameters and results are represented as JSON strings.
+#[async_trait]
+pub trait LanguageServerDelegate: Send + Sync + 'static {
+    async fn request(&self, method: String, params: String) -> Result<String>;
+    fn notify(&self, method: String, params: String) -> Result<()>;
+}
+
 #[async_trait]
 pub trait Extension: Send + Sync + 'static {
     /// Returns the [`ExtensionManifest`] for this extension.
@@ -73,6 +84,30 @@ pub trait Extension: Send + Sync + 'static {
         worktree: Arc<dyn WorktreeDelegate>,
     ) -> Result<Option<String>>;
 
+    async fn handle_language_server_request(
+        &self,
+        language_server_id: LanguageServerName,
+        method: String,
+        params: String,
+        worktree: Arc<dyn WorktreeDelegate>,
+    ) -> Result<String>;
+
+    async fn handle_language_server_notification(
+        &self,
+        language_server_id: LanguageServerName,
+        method: String,
+        params: String,
+        worktree: Arc<dyn WorktreeDelegate>,
+    ) -> Result<()>;
+
+    async fn run_language_server_command(
+        &self,
+        language_server_id: LanguageServerName,
+        command: String,
+        context: LanguageServerCommandContext,
+        language_server: Arc<dyn LanguageServerDelegate>,
+    ) -> Result<LanguageServerCommandOutput>;
+
     async fn labels_for_completions(
         &self,
         language_server_id: LanguageServerName,
@@ -112,6 +147,12 @@ pub trait Extension: Send + Sync + 'static {
         package_name: Arc<str>,
         kv_store: Arc<dyn KeyValueStoreDelegate>,
     ) -> Result<()>;
+
+    async fn task_templates(
+        &self,
+        provider: Arc<str>,
+        worktree: Arc<dyn WorktreeDelegate>,
+    ) -> Result<Vec<TaskTemplate>>;
 }
 
 pub fn parse_wasm_extension_version(
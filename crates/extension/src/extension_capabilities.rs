@@ -0,0 +1,254 @@
+use std::{collections::BTreeSet, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+/// The wildcard that matches any network host or command.
+const WILDCARD: &str = "*";
+
+/// The capabilities an extension needs from the host, beyond access to its own working directory.
+///
+/// Extensions declare the capabilities they need in the `[capabilities]` section of their
+/// manifest, and the user grants them before the extension can use them.
+#[derive(Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct ExtensionCapabilities {
+    /// The hosts that the extension may make network requests to.
+    ///
+    /// `*` matches any host, and `*.example.com` matches any subdomain of `example.com`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub network: Vec<String>,
+    /// The parts of the filesystem that the extension may read.
+    #[serde(default)]
+    pub filesystem: FilesystemScope,
+    /// The commands that the extension may run, such as language servers.
+    ///
+    /// Commands are granted by name, and only allow running the binary of that name on the
+    /// user's `PATH`, not one that the extension downloaded itself. `*` matches any command.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub process: Vec<String>,
+}
+
+/// The parts of the filesystem that an extension may read.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilesystemScope {
+    /// Only the extension's own working directory.
+    #[default]
+    WorkDir,
+    /// The extension's working directory and the files in the user's worktrees.
+    Worktree,
+}
+
+impl ExtensionCapabilities {
+    /// Returns the capabilities assumed for extensions that don't declare any, which is
+    /// unrestricted access.
+    pub fn unrestricted() -> Self {
+        Self {
+            network: vec![WILDCARD.to_string()],
+            filesystem: FilesystemScope::Worktree,
+            process: vec![WILDCARD.to_string()],
+        }
+    }
+
+    /// Returns whether these capabilities are empty, granting nothing beyond the extension's
+    /// working directory.
+    pub fn is_empty(&self) -> bool {
+        self.network.is_empty()
+            && self.filesystem == FilesystemScope::WorkDir
+            && self.process.is_empty()
+    }
+
+    /// Returns whether the extension may make network requests to the given host.
+    pub fn allows_host(&self, host: &str) -> bool {
+        self.network
+            .iter()
+            .any(|pattern| host_matches(pattern, host))
+    }
+
+    /// Returns whether the extension may run the given command, which is looked up on the
+    /// user's `PATH`.
+    pub fn allows_command(&self, command: &str) -> bool {
+        self.process
+            .iter()
+            .any(|pattern| pattern == WILDCARD || pattern == command)
+    }
+
+    /// Returns whether the extension may run the binary at the given path.
+    ///
+    /// `resolved_path` is where the binary's file name is found on the user's `PATH`. Any other
+    /// binary with the same name, such as one in the extension's working directory, is only
+    /// allowed if the extension may run any command.
+    pub fn allows_binary(&self, path: &Path, resolved_path: Option<&Path>) -> bool {
+        if self.process.iter().any(|pattern| pattern == WILDCARD) {
+            return true;
+        }
+        let Some(file_name) = path.file_name().and_then(|file_name| file_name.to_str()) else {
+            return false;
+        };
+        self.allows_command(file_name) && resolved_path == Some(path)
+    }
+
+    /// Returns whether the extension may read files in the user's worktrees.
+    pub fn allows_worktree_read(&self) -> bool {
+        self.filesystem >= FilesystemScope::Worktree
+    }
+
+    /// Returns whether these capabilities include all of the `other` capabilities.
+    pub fn contains(&self, other: &Self) -> bool {
+        other
+            .network
+            .iter()
+            .all(|pattern| self.allows_host(pattern))
+            && other
+                .process
+                .iter()
+                .all(|pattern| self.allows_command(pattern))
+            && self.filesystem >= other.filesystem
+    }
+
+    /// Returns the capabilities that are in both these capabilities and the `other` ones.
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self {
+            network: self
+                .network
+                .iter()
+                .filter(|pattern| other.allows_host(pattern))
+                .chain(
+                    other
+                        .network
+                        .iter()
+                        .filter(|pattern| self.allows_host(pattern)),
+                )
+                .cloned()
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+            filesystem: self.filesystem.min(other.filesystem),
+            process: self
+                .process
+                .iter()
+                .filter(|pattern| other.allows_command(pattern))
+                .chain(
+                    other
+                        .process
+                        .iter()
+                        .filter(|pattern| self.allows_command(pattern)),
+                )
+                .cloned()
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    /// Returns a human-readable description of each capability, for presenting to the user.
+    pub fn descriptions(&self) -> Vec<String> {
+        let mut descriptions = Vec::new();
+        if self.network.iter().any(|pattern| pattern == WILDCARD) {
+            descriptions.push("Access the network".to_string());
+        } else if !self.network.is_empty() {
+            descriptions.push(format!("Access {}", self.network.join(", ")));
+        }
+        if self.allows_worktree_read() {
+            descriptions.push("Read files in your projects".to_string());
+        }
+        if self.process.iter().any(|pattern| pattern == WILDCARD) {
+            descriptions.push("Run any command".to_string());
+        } else if !self.process.is_empty() {
+            descriptions.push(format!("Run {}", self.process.join(", ")));
+        }
+        descriptions
+    }
+}
+
+fn host_matches(pattern: &str, host: &str) -> bool {
+    if pattern == WILDCARD || pattern.eq_ignore_ascii_case(host) {
+        return true;
+    }
+
+    // A wildcard host only matches other hosts that it covers, such as `*.example.com`
+    // matching `*.api.example.com`.
+    let host = host.strip_prefix("*.").unwrap_or(host);
+    pattern.strip_prefix("*.").map_or(false, |domain| {
+        let (host, domain) = (host.as_bytes(), domain.as_bytes());
+        host.len() > domain.len()
+            && host[host.len() - domain.len()..].eq_ignore_ascii_case(domain)
+            && host[host.len() - domain.len() - 1] == b'.'
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allows_host() {
+        let capabilities = ExtensionCapabilities {
+            network: vec!["api.github.com".into(), "*.githubusercontent.com".into()],
+            ..Default::default()
+        };
+
+        assert!(capabilities.allows_host("api.github.com"));
+        assert!(capabilities.allows_host("objects.githubusercontent.com"));
+        assert!(!capabilities.allows_host("githubusercontent.com"));
+        assert!(!capabilities.allows_host("evilgithubusercontent.com"));
+        assert!(!capabilities.allows_host("github.com"));
+        assert!(ExtensionCapabilities::unrestricted().allows_host("example.com"));
+    }
+
+    #[test]
+    fn test_allows_command() {
+        let capabilities = ExtensionCapabilities {
+            process: vec!["node".into()],
+            ..Default::default()
+        };
+
+        assert!(capabilities.allows_command("node"));
+        assert!(!capabilities.allows_command("nodemon"));
+        assert!(!capabilities.allows_command("/usr/local/bin/node"));
+        assert!(ExtensionCapabilities::unrestricted().allows_command("nodemon"));
+    }
+
+    #[test]
+    fn test_allows_binary() {
+        let capabilities = ExtensionCapabilities {
+            process: vec!["node".into()],
+            ..Default::default()
+        };
+        let node = Path::new("/usr/local/bin/node");
+
+        assert!(capabilities.allows_binary(node, Some(node)));
+        assert!(!capabilities.allows_binary(node, None));
+        assert!(!capabilities.allows_binary(Path::new("/extensions/work/gopher/node"), Some(node)));
+        assert!(!capabilities.allows_binary(
+            Path::new("/usr/local/bin/nodemon"),
+            Some(Path::new("/usr/local/bin/nodemon"))
+        ));
+        assert!(ExtensionCapabilities::unrestricted()
+            .allows_binary(Path::new("/extensions/work/gopher/node"), None));
+    }
+
+    #[test]
+    fn test_contains_and_intersection() {
+        let granted = ExtensionCapabilities {
+            network: vec!["*.github.com".into()],
+            filesystem: FilesystemScope::WorkDir,
+            process: vec!["gopls".into()],
+        };
+        let requested = ExtensionCapabilities {
+            network: vec!["api.github.com".into(), "proxy.golang.org".into()],
+            filesystem: FilesystemScope::Worktree,
+            process: vec!["gopls".into()],
+        };
+
+        assert!(!granted.contains(&requested));
+        assert!(ExtensionCapabilities::unrestricted().contains(&requested));
+        assert_eq!(
+            granted.intersection(&requested),
+            ExtensionCapabilities {
+                network: vec!["api.github.com".into()],
+                filesystem: FilesystemScope::WorkDir,
+                process: vec!["gopls".into()],
+            }
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::ExtensionCapabilities;
 use anyhow::{anyhow, Context, Result};
 use collections::{BTreeMap, HashMap};
 use fs::Fs;
@@ -70,6 +71,8 @@ pub struct ExtensionManifest {
     #[serde(default)]
     pub themes: Vec<PathBuf>,
     #[serde(default)]
+    pub icon_themes: Vec<PathBuf>,
+    #[serde(default)]
     pub languages: Vec<PathBuf>,
     #[serde(default)]
     pub grammars: BTreeMap<Arc<str>, GrammarManifestEntry>,
@@ -82,7 +85,14 @@ pub struct ExtensionManifest {
     #[serde(default)]
     pub indexed_docs_providers: BTreeMap<Arc<str>, IndexedDocsProviderEntry>,
     #[serde(default)]
+    pub task_providers: BTreeMap<Arc<str>, TaskProviderManifestEntry>,
+    #[serde(default)]
     pub snippets: Option<PathBuf>,
+    /// The capabilities the extension needs from the host.
+    ///
+    /// Extensions that don't declare their capabilities are assumed to need unrestricted access.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<ExtensionCapabilities>,
 }
 
 #[derive(Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
@@ -117,6 +127,18 @@ pub struct LanguageServerManifestEntry {
     pub language_ids: HashMap<String, String>,
     #[serde(default)]
     pub code_action_kinds: Option<Vec<lsp::CodeActionKind>>,
+    /// The methods of requests from the language server that aren't part of the LSP specification,
+    /// which the extension handles.
+    #[serde(default)]
+    pub custom_requests: Vec<String>,
+    /// The methods of notifications from the language server that aren't part of the LSP
+    /// specification, which the extension handles.
+    #[serde(default)]
+    pub custom_notifications: Vec<String>,
+    /// The names of the editor commands that the extension provides for buffers using this
+    /// language server.
+    #[serde(default)]
+    pub commands: Vec<String>,
 }
 
 impl LanguageServerManifestEntry {
@@ -149,7 +171,22 @@ pub struct SlashCommandManifestEntry {
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 pub struct IndexedDocsProviderEntry {}
 
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct TaskProviderManifestEntry {
+    /// The worktree-relative paths of the files that the tasks are computed from,
+    /// such as `package.json`. The tasks are recomputed whenever one of these changes.
+    #[serde(default)]
+    pub watched_paths: Vec<PathBuf>,
+}
+
 impl ExtensionManifest {
+    /// Returns the capabilities that the extension needs from the host.
+    pub fn requested_capabilities(&self) -> ExtensionCapabilities {
+        self.capabilities
+            .clone()
+            .unwrap_or_else(ExtensionCapabilities::unrestricted)
+    }
+
     pub async fn load(fs: Arc<dyn Fs>, extension_dir: &Path) -> Result<Self> {
         let extension_name = extension_dir
             .file_name()
@@ -199,6 +236,7 @@ fn manifest_from_old_manifest(
             themes.dedup();
             themes
         },
+        icon_themes: Vec::new(),
         languages: {
             let mut languages = manifest_json.languages.into_values().collect::<Vec<_>>();
             languages.sort();
@@ -214,6 +252,8 @@ fn manifest_from_old_manifest(
         context_servers: BTreeMap::default(),
         slash_commands: BTreeMap::default(),
         indexed_docs_providers: BTreeMap::default(),
+        task_providers: BTreeMap::default(),
         snippets: None,
+        capabilities: None,
     }
 }
@@ -1,16 +1,20 @@
 mod lsp;
 mod slash_command;
+mod task;
 
 use std::ops::Range;
 
+use serde::Serialize;
+
 pub use lsp::*;
 pub use slash_command::*;
+pub use task::*;
 
 /// A list of environment variables.
 pub type EnvVars = Vec<(String, String)>;
 
 /// A command.
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct Command {
     /// The command to execute.
     pub command: String,
@@ -21,7 +25,7 @@ pub struct Command {
 }
 
 /// A label containing some code.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct CodeLabel {
     /// The source code to parse with Tree-sitter.
     pub code: String,
@@ -32,7 +36,8 @@ pub struct CodeLabel {
 }
 
 /// A span within a code label.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum CodeLabelSpan {
     /// A range into the parsed code.
     CodeRange(Range<usize>),
@@ -41,7 +46,7 @@ pub enum CodeLabelSpan {
 }
 
 /// A span containing a code literal.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct CodeLabelSpanLiteral {
     /// The literal text.
     pub text: String,
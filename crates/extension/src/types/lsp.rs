@@ -1,7 +1,9 @@
 use std::option::Option;
 
+use serde::Deserialize;
+
 /// An LSP completion.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct Completion {
     pub label: String,
     pub label_details: Option<CompletionLabelDetails>,
@@ -11,7 +13,8 @@ pub struct Completion {
 }
 
 /// The kind of an LSP completion.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum CompletionKind {
     Text,
     Method,
@@ -42,14 +45,15 @@ pub enum CompletionKind {
 }
 
 /// Label details for an LSP completion.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct CompletionLabelDetails {
     pub detail: Option<String>,
     pub description: Option<String>,
 }
 
 /// Defines how to interpret the insert text in a completion item.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum InsertTextFormat {
     PlainText,
     Snippet,
@@ -57,14 +61,15 @@ pub enum InsertTextFormat {
 }
 
 /// An LSP symbol.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct Symbol {
     pub kind: SymbolKind,
     pub name: String,
 }
 
 /// The kind of an LSP symbol.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SymbolKind {
     File,
     Module,
@@ -1,5 +1,7 @@
 use std::ops::Range;
 
+use serde::Serialize;
+
 /// A slash command for use in the Assistant.
 #[derive(Debug, Clone)]
 pub struct SlashCommand {
@@ -14,7 +16,7 @@ pub struct SlashCommand {
 }
 
 /// The output of a slash command.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SlashCommandOutput {
     /// The text produced by the slash command.
     pub text: String,
@@ -23,7 +25,7 @@ pub struct SlashCommandOutput {
 }
 
 /// A section in the slash command output.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SlashCommandOutputSection {
     /// The range this section occupies.
     pub range: Range<usize>,
@@ -32,7 +34,7 @@ pub struct SlashCommandOutputSection {
 }
 
 /// A completion for a slash command argument.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SlashCommandArgumentCompletion {
     /// The label to display for this completion.
     pub label: String,
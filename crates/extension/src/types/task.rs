@@ -0,0 +1,20 @@
+use serde::Serialize;
+
+use crate::EnvVars;
+
+/// A template for a task that runs a command in the terminal.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskTemplate {
+    /// The human-readable name of the task.
+    pub label: String,
+    /// The command to run.
+    pub command: String,
+    /// The arguments to pass to the command.
+    pub args: Vec<String>,
+    /// The environment variables to set for the command.
+    pub env: EnvVars,
+    /// The directory to run the command in, relative to the worktree root.
+    pub cwd: Option<String>,
+    /// The tags used to match the task to runnables in the code.
+    pub tags: Vec<String>,
+}
@@ -27,8 +27,11 @@ pub use wit::{
     zed::extension::slash_command::{
         SlashCommand, SlashCommandArgumentCompletion, SlashCommandOutput, SlashCommandOutputSection,
     },
-    CodeLabel, CodeLabelSpan, CodeLabelSpanLiteral, Command, DownloadedFileType, EnvVars,
-    KeyValueStore, LanguageServerInstallationStatus, Project, Range, Worktree,
+    zed::extension::task::TaskTemplate,
+    CodeLabel, CodeLabelSpan, CodeLabelSpanLiteral, Command, DocumentPosition, DocumentRange,
+    DownloadedFileType, EnvVars, KeyValueStore, LanguageServer, LanguageServerCommandBuffer,
+    LanguageServerCommandContext, LanguageServerCommandOutput, LanguageServerInstallationStatus,
+    Project, Range, Worktree,
 };
 
 // Undocumented WIT re-exports.
@@ -92,6 +95,50 @@ pub trait Extension: Send + Sync {
         Ok(None)
     }
 
+    /// Handles a request from the language server that isn't part of the Language Server Protocol.
+    ///
+    /// Only requests whose methods are listed in the `custom_requests` of the language server in
+    /// the extension manifest are passed to the extension.
+    fn handle_language_server_request(
+        &mut self,
+        _language_server_id: &LanguageServerId,
+        method: String,
+        _params: serde_json::Value,
+        _worktree: &Worktree,
+    ) -> Result<serde_json::Value> {
+        Err(format!("unhandled request `{method}`"))
+    }
+
+    /// Handles a notification from the language server that isn't part of the Language Server
+    /// Protocol.
+    ///
+    /// Only notifications whose methods are listed in the `custom_notifications` of the language
+    /// server in the extension manifest are passed to the extension.
+    fn handle_language_server_notification(
+        &mut self,
+        _language_server_id: &LanguageServerId,
+        _method: String,
+        _params: serde_json::Value,
+        _worktree: &Worktree,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Runs one of the editor commands listed in the `commands` of the language server in the
+    /// extension manifest.
+    ///
+    /// The command can send its own requests to the language server, such as ones specific to
+    /// that language server.
+    fn run_language_server_command(
+        &mut self,
+        _language_server_id: &LanguageServerId,
+        command: String,
+        _context: LanguageServerCommandContext,
+        _language_server: &LanguageServer,
+    ) -> Result<LanguageServerCommandOutput> {
+        Err(format!("unknown command `{command}`"))
+    }
+
     /// Returns the label for the given completion.
     fn label_for_completion(
         &self,
@@ -156,6 +203,15 @@ pub trait Extension: Send + Sync {
     ) -> Result<(), String> {
         Err("`index_docs` not implemented".to_string())
     }
+
+    /// Returns the tasks that the specified task provider offers for the given worktree.
+    fn task_templates(
+        &self,
+        _provider_id: String,
+        _worktree: &Worktree,
+    ) -> Result<Vec<TaskTemplate>, String> {
+        Ok(Vec::new())
+    }
 }
 
 /// Registers the provided type as a Zed extension.
@@ -195,7 +251,7 @@ mod wit {
 
     wit_bindgen::generate!({
         skip: ["init-extension"],
-        path: "./wit/since_v0.2.0",
+        path: "./wit/since_v0.3.0",
     });
 }
 
@@ -232,6 +288,54 @@ impl wit::Guest for Component {
             .and_then(|value| serde_json::to_string(&value).ok()))
     }
 
+    fn handle_language_server_request(
+        language_server_id: String,
+        method: String,
+        params: String,
+        worktree: &Worktree,
+    ) -> Result<String, String> {
+        let language_server_id = LanguageServerId(language_server_id);
+        let params = serde_json::from_str(&params).map_err(|error| error.to_string())?;
+        let result = extension().handle_language_server_request(
+            &language_server_id,
+            method,
+            params,
+            worktree,
+        )?;
+        serde_json::to_string(&result).map_err(|error| error.to_string())
+    }
+
+    fn handle_language_server_notification(
+        language_server_id: String,
+        method: String,
+        params: String,
+        worktree: &Worktree,
+    ) -> Result<(), String> {
+        let language_server_id = LanguageServerId(language_server_id);
+        let params = serde_json::from_str(&params).map_err(|error| error.to_string())?;
+        extension().handle_language_server_notification(
+            &language_server_id,
+            method,
+            params,
+            worktree,
+        )
+    }
+
+    fn run_language_server_command(
+        language_server_id: String,
+        command: String,
+        context: LanguageServerCommandContext,
+        language_server: &LanguageServer,
+    ) -> Result<LanguageServerCommandOutput, String> {
+        let language_server_id = LanguageServerId(language_server_id);
+        extension().run_language_server_command(
+            &language_server_id,
+            command,
+            context,
+            language_server,
+        )
+    }
+
     fn labels_for_completions(
         language_server_id: String,
         completions: Vec<Completion>,
@@ -298,6 +402,13 @@ impl wit::Guest for Component {
     ) -> Result<(), String> {
         extension().index_docs(provider, package, database)
     }
+
+    fn task_templates(
+        provider_id: String,
+        worktree: &Worktree,
+    ) -> Result<Vec<TaskTemplate>, String> {
+        extension().task_templates(provider_id, worktree)
+    }
 }
 
 /// The ID of a language server.
@@ -332,6 +443,25 @@ impl fmt::Display for ContextServerId {
     }
 }
 
+impl LanguageServer {
+    /// Sends a request to the language server and returns its result.
+    pub fn request_json(
+        &self,
+        method: &str,
+        params: &serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let params = serde_json::to_string(params).map_err(|error| error.to_string())?;
+        let result = self.request(method, &params)?;
+        serde_json::from_str(&result).map_err(|error| error.to_string())
+    }
+
+    /// Sends a notification to the language server.
+    pub fn notify_json(&self, method: &str, params: &serde_json::Value) -> Result<()> {
+        let params = serde_json::to_string(params).map_err(|error| error.to_string())?;
+        self.notify(method, &params)
+    }
+}
+
 impl CodeLabelSpan {
     /// Returns a [`CodeLabelSpan::CodeRange`].
     pub fn code_range(range: impl Into<wit::Range>) -> Self {
@@ -1,3 +1,5 @@
+mod test_runner;
+
 use std::{
     collections::HashMap,
     env, fs,
@@ -8,13 +10,14 @@ use std::{
 
 use ::fs::{copy_recursive, CopyOptions, Fs, RealFs};
 use anyhow::{anyhow, bail, Context, Result};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use extension::{
     extension_builder::{CompileExtensionOptions, ExtensionBuilder},
     ExtensionManifest,
 };
 use language::LanguageConfig;
 use reqwest_client::ReqwestClient;
+use theme::IconThemeContent;
 use tree_sitter::{Language, Query, WasmStore};
 
 #[derive(Parser, Debug)]
@@ -24,11 +27,29 @@ struct Args {
     #[arg(long)]
     source_dir: PathBuf,
     /// The output directory to place the packaged extension.
+    ///
+    /// Required unless running a subcommand.
     #[arg(long)]
-    output_dir: PathBuf,
+    output_dir: Option<PathBuf>,
     /// The path to a directory where build dependencies are downloaded
     #[arg(long)]
     scratch_dir: PathBuf,
+    #[command(subcommand)]
+    command: Option<CliCommand>,
+}
+
+#[derive(Subcommand, Debug)]
+enum CliCommand {
+    /// Builds the extension and runs its test cases against the compiled WebAssembly.
+    Test {
+        /// The directory containing the test cases. Defaults to the `tests` directory of the
+        /// extension.
+        #[arg(long)]
+        tests_dir: Option<PathBuf>,
+        /// Overwrite the expected output of each test case with its actual output.
+        #[arg(long)]
+        update_expected: bool,
+    },
 }
 
 #[tokio::main]
@@ -48,12 +69,6 @@ async fn main() -> Result<()> {
         .scratch_dir
         .canonicalize()
         .context("failed to canonicalize scratch_dir")?;
-    let output_dir = if args.output_dir.is_relative() {
-        env::current_dir()?.join(&args.output_dir)
-    } else {
-        args.output_dir
-    };
-
     log::info!("loading extension manifest");
     let mut manifest = ExtensionManifest::load(fs.clone(), &extension_path).await?;
 
@@ -67,7 +82,7 @@ async fn main() -> Result<()> {
     );
     let http_client = Arc::new(ReqwestClient::user_agent(&user_agent)?);
 
-    let builder = ExtensionBuilder::new(http_client, scratch_dir);
+    let builder = ExtensionBuilder::new(http_client, scratch_dir.clone());
     builder
         .compile_extension(
             &extension_path,
@@ -80,6 +95,43 @@ async fn main() -> Result<()> {
     let grammars = test_grammars(&manifest, &extension_path, &mut wasm_store)?;
     test_languages(&manifest, &extension_path, &grammars)?;
     test_themes(&manifest, &extension_path, fs.clone()).await?;
+    test_icon_themes(&manifest, &extension_path, fs.clone()).await?;
+
+    if let Some(CliCommand::Test {
+        tests_dir,
+        update_expected,
+    }) = args.command
+    {
+        let tests_dir = tests_dir.unwrap_or_else(|| extension_path.join("tests"));
+        let work_dir = scratch_dir.join("test-work");
+        let summary = tokio::task::block_in_place(|| {
+            test_runner::run_tests(
+                extension_path,
+                manifest,
+                tests_dir,
+                work_dir,
+                update_expected,
+            )
+        })?;
+        println!(
+            "\ntest result: {} passed; {} failed",
+            summary.passed,
+            summary.failed.len()
+        );
+        if !summary.failed.is_empty() {
+            bail!("failed tests: {}", summary.failed.join(", "));
+        }
+        return Ok(());
+    }
+
+    let output_dir = args
+        .output_dir
+        .ok_or_else(|| anyhow!("--output-dir is required when packaging an extension"))?;
+    let output_dir = if output_dir.is_relative() {
+        env::current_dir()?.join(&output_dir)
+    } else {
+        output_dir
+    };
 
     let archive_dir = output_dir.join("archive");
     fs::remove_dir_all(&archive_dir).ok();
@@ -167,6 +219,40 @@ async fn copy_extension_resources(
         }
     }
 
+    if !manifest.icon_themes.is_empty() {
+        let output_icon_themes_dir = output_dir.join("icon_themes");
+        fs::create_dir_all(&output_icon_themes_dir)?;
+        for icon_theme_path in &manifest.icon_themes {
+            fs::copy(
+                extension_path.join(icon_theme_path),
+                output_icon_themes_dir.join(
+                    icon_theme_path
+                        .file_name()
+                        .ok_or_else(|| anyhow!("invalid icon theme path"))?,
+                ),
+            )
+            .with_context(|| {
+                format!("failed to copy icon theme '{}'", icon_theme_path.display())
+            })?;
+
+            // Icon paths are relative to the extension's root, so we keep them at the same place.
+            let icon_theme_family =
+                theme::read_icon_theme(&extension_path.join(icon_theme_path), fs.clone()).await?;
+            for icon_path in icon_theme_family
+                .themes
+                .iter()
+                .flat_map(icon_theme_icon_paths)
+            {
+                let output_icon_path = output_dir.join(icon_path);
+                if let Some(parent) = output_icon_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::copy(extension_path.join(icon_path), &output_icon_path)
+                    .with_context(|| format!("failed to copy icon '{icon_path}'"))?;
+            }
+        }
+    }
+
     if !manifest.languages.is_empty() {
         let output_languages_dir = output_dir.join("languages");
         fs::create_dir_all(&output_languages_dir)?;
@@ -272,3 +358,42 @@ async fn test_themes(
 
     Ok(())
 }
+
+async fn test_icon_themes(
+    manifest: &ExtensionManifest,
+    extension_path: &Path,
+    fs: Arc<dyn Fs>,
+) -> Result<()> {
+    for relative_icon_theme_path in &manifest.icon_themes {
+        let icon_theme_path = extension_path.join(relative_icon_theme_path);
+        let icon_theme_family = theme::read_icon_theme(&icon_theme_path, fs.clone()).await?;
+        for icon_theme in &icon_theme_family.themes {
+            for icon_path in icon_theme_icon_paths(icon_theme) {
+                if !extension_path.join(icon_path).is_file() {
+                    bail!(
+                        "icon theme {} references missing icon '{icon_path}'",
+                        icon_theme.name
+                    );
+                }
+            }
+        }
+        log::info!("loaded icon theme family {}", icon_theme_family.name);
+    }
+
+    Ok(())
+}
+
+/// Returns the paths of all icons referenced by the given icon theme.
+fn icon_theme_icon_paths(icon_theme: &IconThemeContent) -> impl Iterator<Item = &String> {
+    let directory_icons = &icon_theme.directory_icons;
+    let chevron_icons = &icon_theme.chevron_icons;
+    [
+        &directory_icons.collapsed,
+        &directory_icons.expanded,
+        &chevron_icons.collapsed,
+        &chevron_icons.expanded,
+    ]
+    .into_iter()
+    .flatten()
+    .chain(icon_theme.file_icons.values().map(|icon| &icon.path))
+}
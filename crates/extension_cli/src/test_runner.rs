@@ -0,0 +1,856 @@
+//! Runs an extension's test cases against its compiled WebAssembly.
+//!
+//! Each test case is a JSON file in the extension's `tests` directory, which names one of the
+//! extension's exports along with its arguments, the worktree it should see, and the responses to
+//! any HTTP requests it makes. The output of the export is compared against the JSON in the
+//! `<name>.expected.json` file next to the test case.
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Component, Path, PathBuf},
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+use ::fs::RealFs;
+use anyhow::{anyhow, Context as _, Result};
+use async_trait::async_trait;
+use extension::{
+    Completion, Extension, ExtensionHostProxy, ExtensionManifest, KeyValueStoreDelegate,
+    ProjectDelegate, SlashCommand, Symbol, WorktreeDelegate,
+};
+use extension_host::wasm_host::WasmHost;
+use gpui::{AppContext, AsyncAppContext, SemanticVersion, Task, UpdateGlobal as _};
+use http_client::{FakeHttpClient, Response};
+use language::LanguageName;
+use node_runtime::NodeRuntime;
+use project::Project;
+use serde::Deserialize;
+use serde_json::Value;
+use settings::SettingsStore;
+
+/// The ID of the worktree that test cases run against.
+const FIXTURE_WORKTREE_ID: u64 = 1;
+
+/// A single invocation of an extension export.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TestCase {
+    /// The export to call.
+    call: TestCall,
+    /// The directory to use as the worktree root, relative to the test case.
+    ///
+    /// Defaults to an empty directory.
+    #[serde(default)]
+    worktree: Option<PathBuf>,
+    /// The binaries that the worktree finds on its `PATH`.
+    #[serde(default)]
+    which: HashMap<String, String>,
+    /// The environment of the worktree's shell.
+    #[serde(default)]
+    shell_env: BTreeMap<String, String>,
+    /// The responses to the HTTP requests the extension is expected to make.
+    #[serde(default)]
+    http: Vec<HttpFixture>,
+    /// The user settings to use while running the test case.
+    #[serde(default)]
+    settings: Option<Value>,
+}
+
+/// An extension export, named as in the extension's WIT world, along with its arguments.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "export", rename_all = "kebab-case", deny_unknown_fields)]
+enum TestCall {
+    LanguageServerCommand {
+        language_server_id: String,
+        language_name: String,
+    },
+    LanguageServerInitializationOptions {
+        language_server_id: String,
+        language_name: String,
+    },
+    LanguageServerWorkspaceConfiguration {
+        language_server_id: String,
+    },
+    LabelsForCompletions {
+        language_server_id: String,
+        completions: Vec<Completion>,
+    },
+    LabelsForSymbols {
+        language_server_id: String,
+        symbols: Vec<Symbol>,
+    },
+    CompleteSlashCommandArgument {
+        command: String,
+        #[serde(default)]
+        arguments: Vec<String>,
+    },
+    RunSlashCommand {
+        command: String,
+        #[serde(default)]
+        arguments: Vec<String>,
+    },
+    ContextServerCommand {
+        context_server_id: String,
+    },
+    SuggestDocsPackages {
+        provider: String,
+    },
+    IndexDocs {
+        provider: String,
+        package: String,
+    },
+    TaskTemplates {
+        provider: String,
+    },
+}
+
+/// A canned response to an HTTP request.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct HttpFixture {
+    url: String,
+    #[serde(default = "default_http_status")]
+    status: u16,
+    /// The response body. Strings are sent as-is, and other values are sent as JSON.
+    #[serde(default)]
+    body: Option<Value>,
+    /// A file containing the response body, relative to the test case.
+    #[serde(default)]
+    body_file: Option<PathBuf>,
+}
+
+fn default_http_status() -> u16 {
+    200
+}
+
+#[derive(Debug, Default)]
+pub struct TestSummary {
+    pub passed: usize,
+    pub failed: Vec<String>,
+}
+
+impl TestSummary {
+    /// Reports the outcome of the named test case, and counts it towards the summary.
+    fn record(&mut self, name: String, outcome: Result<()>) {
+        match outcome {
+            Ok(()) => {
+                println!("test {name} ... ok");
+                self.passed += 1;
+            }
+            Err(error) => {
+                println!("test {name} ... FAILED\n{error:#}");
+                self.failed.push(name);
+            }
+        }
+    }
+}
+
+/// Runs the test cases in `tests_dir` against the extension's `extension.wasm`.
+///
+/// When `update_expected` is set, the expected outputs are overwritten with the actual ones.
+pub fn run_tests(
+    extension_path: PathBuf,
+    manifest: ExtensionManifest,
+    tests_dir: PathBuf,
+    work_dir: PathBuf,
+    update_expected: bool,
+) -> Result<TestSummary> {
+    if manifest.lib.kind.is_none() {
+        anyhow::bail!("extension {} has no WebAssembly to test", manifest.id);
+    }
+
+    let test_case_paths = test_case_paths(&tests_dir)?;
+    let wasm_bytes =
+        fs::read(extension_path.join("extension.wasm")).context("failed to read extension.wasm")?;
+    let manifest = Arc::new(manifest);
+
+    let summary = Rc::new(RefCell::new(None));
+    gpui::App::headless().run({
+        let summary = summary.clone();
+        move |cx| {
+            init(cx);
+            cx.spawn(|mut cx| async move {
+                let mut result = TestSummary::default();
+                for test_case_path in test_case_paths {
+                    let name = test_case_name(&test_case_path);
+                    let outcome = run_test_case(
+                        &test_case_path,
+                        &wasm_bytes,
+                        &manifest,
+                        &work_dir.join(&name),
+                        update_expected,
+                        &mut cx,
+                    )
+                    .await;
+                    result.record(name, outcome);
+                }
+
+                summary.borrow_mut().replace(result);
+                cx.update(|cx| cx.quit()).ok();
+            })
+            .detach();
+        }
+    });
+
+    summary
+        .take()
+        .ok_or_else(|| anyhow!("test run exited before completing"))
+}
+
+fn init(cx: &mut AppContext) {
+    settings::init(cx);
+    release_channel::init(SemanticVersion::default(), cx);
+    Project::init_settings(cx);
+    language::init(cx);
+    context_server_settings::init(cx);
+}
+
+fn test_case_paths(tests_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(tests_dir)
+        .with_context(|| format!("failed to list tests dir {}", tests_dir.display()))?
+    {
+        let entry = entry?;
+        let path = entry.path();
+        let is_test_case = entry.file_type()?.is_file()
+            && path.extension() == Some("json".as_ref())
+            && !path.to_string_lossy().ends_with(".expected.json");
+        if is_test_case {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+fn test_case_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+async fn run_test_case(
+    path: &Path,
+    wasm_bytes: &[u8],
+    manifest: &Arc<ExtensionManifest>,
+    work_dir: &Path,
+    update_expected: bool,
+    cx: &mut AsyncAppContext,
+) -> Result<()> {
+    let test_case = load_test_case(path)?;
+    let test_case_dir = path.parent().unwrap_or(Path::new("."));
+
+    fs::remove_dir_all(work_dir).ok();
+    fs::create_dir_all(work_dir)?;
+    let worktree_root = match &test_case.worktree {
+        Some(worktree) => test_case_dir
+            .join(worktree)
+            .canonicalize()
+            .with_context(|| format!("failed to find worktree {}", worktree.display()))?,
+        None => {
+            let worktree_root = work_dir.join("worktree");
+            fs::create_dir_all(&worktree_root)?;
+            worktree_root
+        }
+    };
+    let extension_work_dir = work_dir.join("work");
+
+    let user_settings = match &test_case.settings {
+        Some(settings) => settings.to_string(),
+        None => String::new(),
+    };
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| store.set_user_settings(&user_settings, cx))
+    })??;
+
+    let http_client = fake_http_client(&test_case.http, test_case_dir)?;
+    let wasm_host = cx.update(|cx| {
+        WasmHost::new(
+            Arc::new(RealFs::default()),
+            http_client,
+            NodeRuntime::unavailable(),
+            Arc::new(ExtensionHostProxy::new()),
+            extension_work_dir.clone(),
+            cx,
+        )
+    })?;
+    let extension = wasm_host
+        .load_extension(
+            wasm_bytes.to_vec(),
+            manifest,
+            manifest.requested_capabilities(),
+            cx.background_executor().clone(),
+        )
+        .await
+        .context("failed to load extension")?;
+
+    let worktree: Arc<dyn WorktreeDelegate> = Arc::new(FixtureWorktree {
+        root: worktree_root.clone(),
+        which: test_case.which,
+        shell_env: test_case.shell_env.into_iter().collect(),
+    });
+    let output = match call_export(&extension, manifest, test_case.call, worktree).await {
+        Ok(output) => serde_json::json!({ "ok": output }),
+        Err(error) => serde_json::json!({ "error": format!("{error:#}") }),
+    };
+    let output = replace_paths(
+        output,
+        &[
+            (&worktree_root, "$WORKTREE_ROOT"),
+            (&extension_work_dir, "$WORK_DIR"),
+        ],
+    );
+
+    check_output(
+        &output,
+        &path.with_extension("expected.json"),
+        update_expected,
+    )
+}
+
+fn load_test_case(path: &Path) -> Result<TestCase> {
+    let json = fs::read_to_string(path)
+        .with_context(|| format!("failed to read test case {}", path.display()))?;
+    serde_json::from_str(&json)
+        .with_context(|| format!("failed to parse test case {}", path.display()))
+}
+
+/// Compares the output of a test case against the JSON in `expected_path`, or overwrites it with
+/// the output when `update_expected` is set.
+fn check_output(output: &Value, expected_path: &Path, update_expected: bool) -> Result<()> {
+    if update_expected {
+        fs::write(expected_path, serde_json::to_string_pretty(output)? + "\n")
+            .with_context(|| format!("failed to write {}", expected_path.display()))?;
+        return Ok(());
+    }
+
+    let expected: Value = serde_json::from_str(
+        &fs::read_to_string(expected_path)
+            .with_context(|| format!("failed to read {}", expected_path.display()))?,
+    )
+    .with_context(|| format!("failed to parse {}", expected_path.display()))?;
+    if *output != expected {
+        anyhow::bail!(
+            "output did not match {}\nexpected: {}\n  actual: {}",
+            expected_path.display(),
+            serde_json::to_string_pretty(&expected)?,
+            serde_json::to_string_pretty(output)?
+        );
+    }
+
+    Ok(())
+}
+
+async fn call_export(
+    extension: &impl Extension,
+    manifest: &ExtensionManifest,
+    call: TestCall,
+    worktree: Arc<dyn WorktreeDelegate>,
+) -> Result<Value> {
+    let output = match call {
+        TestCall::LanguageServerCommand {
+            language_server_id,
+            language_name,
+        } => serde_json::to_value(
+            extension
+                .language_server_command(
+                    language_server_id.as_str().into(),
+                    LanguageName::new(&language_name),
+                    worktree,
+                )
+                .await?,
+        )?,
+        TestCall::LanguageServerInitializationOptions {
+            language_server_id,
+            language_name,
+        } => json_string_to_value(
+            extension
+                .language_server_initialization_options(
+                    language_server_id.as_str().into(),
+                    LanguageName::new(&language_name),
+                    worktree,
+                )
+                .await?,
+        ),
+        TestCall::LanguageServerWorkspaceConfiguration { language_server_id } => {
+            json_string_to_value(
+                extension
+                    .language_server_workspace_configuration(
+                        language_server_id.as_str().into(),
+                        worktree,
+                    )
+                    .await?,
+            )
+        }
+        TestCall::LabelsForCompletions {
+            language_server_id,
+            completions,
+        } => serde_json::to_value(
+            extension
+                .labels_for_completions(language_server_id.as_str().into(), completions)
+                .await?,
+        )?,
+        TestCall::LabelsForSymbols {
+            language_server_id,
+            symbols,
+        } => serde_json::to_value(
+            extension
+                .labels_for_symbols(language_server_id.as_str().into(), symbols)
+                .await?,
+        )?,
+        TestCall::CompleteSlashCommandArgument { command, arguments } => serde_json::to_value(
+            extension
+                .complete_slash_command_argument(slash_command(manifest, &command)?, arguments)
+                .await?,
+        )?,
+        TestCall::RunSlashCommand { command, arguments } => serde_json::to_value(
+            extension
+                .run_slash_command(
+                    slash_command(manifest, &command)?,
+                    arguments,
+                    Some(worktree),
+                )
+                .await?,
+        )?,
+        TestCall::ContextServerCommand { context_server_id } => serde_json::to_value(
+            extension
+                .context_server_command(context_server_id.into(), Arc::new(FixtureProject))
+                .await?,
+        )?,
+        TestCall::SuggestDocsPackages { provider } => {
+            serde_json::to_value(extension.suggest_docs_packages(provider.into()).await?)?
+        }
+        TestCall::IndexDocs { provider, package } => {
+            let kv_store = Arc::new(RecordingKeyValueStore::default());
+            extension
+                .index_docs(provider.into(), package.into(), kv_store.clone())
+                .await?;
+            let entries = kv_store.entries.lock().unwrap().clone();
+            serde_json::to_value(entries)?
+        }
+        TestCall::TaskTemplates { provider } => {
+            serde_json::to_value(extension.task_templates(provider.into(), worktree).await?)?
+        }
+    };
+
+    Ok(output)
+}
+
+fn slash_command(manifest: &ExtensionManifest, name: &str) -> Result<SlashCommand> {
+    let slash_command = manifest
+        .slash_commands
+        .get(name)
+        .ok_or_else(|| anyhow!("extension has no slash command named {name:?}"))?;
+    Ok(SlashCommand {
+        name: name.to_string(),
+        description: slash_command.description.clone(),
+        tooltip_text: String::new(),
+        requires_argument: slash_command.requires_argument,
+    })
+}
+
+/// Converts a JSON string returned by an extension into a value, so that it's compared
+/// structurally rather than character-by-character.
+fn json_string_to_value(json: Option<String>) -> Value {
+    match json {
+        Some(json) => serde_json::from_str(&json).unwrap_or(Value::String(json)),
+        None => Value::Null,
+    }
+}
+
+/// Replaces machine-specific paths in the output with placeholders, so that expected outputs
+/// can be checked in.
+fn replace_paths(value: Value, replacements: &[(&Path, &str)]) -> Value {
+    match value {
+        Value::String(mut string) => {
+            for (path, placeholder) in replacements {
+                string = string.replace(path.to_string_lossy().as_ref(), placeholder);
+            }
+            Value::String(string)
+        }
+        Value::Array(values) => Value::Array(
+            values
+                .into_iter()
+                .map(|value| replace_paths(value, replacements))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| (key, replace_paths(value, replacements)))
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+fn fake_http_client(
+    fixtures: &[HttpFixture],
+    test_case_dir: &Path,
+) -> Result<Arc<http_client::HttpClientWithUrl>> {
+    let mut responses = HashMap::default();
+    for fixture in fixtures {
+        let body = match (&fixture.body, &fixture.body_file) {
+            (Some(_), Some(_)) => {
+                anyhow::bail!(
+                    "HTTP fixture for {} has both body and body_file",
+                    fixture.url
+                )
+            }
+            (Some(Value::String(body)), None) => body.clone().into_bytes(),
+            (Some(body), None) => body.to_string().into_bytes(),
+            (None, Some(body_file)) => fs::read(test_case_dir.join(body_file))
+                .with_context(|| format!("failed to read {}", body_file.display()))?,
+            (None, None) => Vec::new(),
+        };
+        responses.insert(fixture.url.clone(), (fixture.status, body));
+    }
+    let responses = Arc::new(responses);
+
+    Ok(FakeHttpClient::create(move |request| {
+        let responses = responses.clone();
+        async move {
+            let url = request.uri().to_string();
+            let (status, body) = responses
+                .get(&url)
+                .ok_or_else(|| anyhow!("unexpected HTTP request to {url}"))?;
+            Ok(Response::builder()
+                .status(*status)
+                .body(body.clone().into())?)
+        }
+    }))
+}
+
+struct FixtureWorktree {
+    root: PathBuf,
+    which: HashMap<String, String>,
+    shell_env: Vec<(String, String)>,
+}
+
+impl FixtureWorktree {
+    /// Resolves a worktree-relative path, like a real worktree would, refusing paths that lead
+    /// outside of the fixture.
+    fn abs_path(&self, path: &Path) -> Result<PathBuf> {
+        let mut relative_path = PathBuf::new();
+        for component in path.components() {
+            match component {
+                Component::Normal(name) => relative_path.push(name),
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    if !relative_path.pop() {
+                        anyhow::bail!("path {path:?} is outside of the worktree");
+                    }
+                }
+                Component::RootDir | Component::Prefix(_) => {
+                    anyhow::bail!("path {path:?} is not relative to the worktree")
+                }
+            }
+        }
+
+        // Symlinks in the fixture could still lead elsewhere.
+        let root = self.root.canonicalize()?;
+        let abs_path = root
+            .join(relative_path)
+            .canonicalize()
+            .with_context(|| format!("no worktree entry for path {path:?}"))?;
+        if !abs_path.starts_with(&root) {
+            anyhow::bail!("path {path:?} is outside of the worktree");
+        }
+        Ok(abs_path)
+    }
+}
+
+#[async_trait]
+impl WorktreeDelegate for FixtureWorktree {
+    fn id(&self) -> u64 {
+        FIXTURE_WORKTREE_ID
+    }
+
+    fn root_path(&self) -> String {
+        self.root.to_string_lossy().into_owned()
+    }
+
+    async fn read_text_file(&self, path: PathBuf) -> Result<String> {
+        let abs_path = self.abs_path(&path)?;
+        fs::read_to_string(&abs_path).with_context(|| format!("failed to read {path:?}"))
+    }
+
+    async fn which(&self, binary_name: String) -> Option<String> {
+        self.which.get(&binary_name).cloned()
+    }
+
+    async fn shell_env(&self) -> Vec<(String, String)> {
+        self.shell_env.clone()
+    }
+}
+
+struct FixtureProject;
+
+impl ProjectDelegate for FixtureProject {
+    fn worktree_ids(&self) -> Vec<u64> {
+        vec![FIXTURE_WORKTREE_ID]
+    }
+}
+
+#[derive(Default)]
+struct RecordingKeyValueStore {
+    entries: Mutex<BTreeMap<String, String>>,
+}
+
+impl KeyValueStoreDelegate for RecordingKeyValueStore {
+    fn insert(&self, key: String, docs: String) -> Task<Result<()>> {
+        self.entries.lock().unwrap().insert(key, docs);
+        Task::ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use extension::extension_builder::{CompileExtensionOptions, ExtensionBuilder};
+    use reqwest_client::ReqwestClient;
+    use serde_json::json;
+
+    #[test]
+    fn test_finds_test_cases() {
+        let dir = tempfile::tempdir().unwrap();
+        for file in [
+            "b.json",
+            "b.expected.json",
+            "a.json",
+            "a.expected.json",
+            "README.md",
+        ] {
+            fs::write(dir.path().join(file), "{}").unwrap();
+        }
+        fs::create_dir(dir.path().join("worktree.json")).unwrap();
+
+        let paths = test_case_paths(dir.path()).unwrap();
+        assert_eq!(
+            paths,
+            [dir.path().join("a.json"), dir.path().join("b.json")]
+        );
+        assert_eq!(test_case_name(&paths[0]), "a");
+        assert!(test_case_paths(&dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_loads_test_cases() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("command.json");
+        fs::write(
+            &path,
+            json!({
+                "call": {
+                    "export": "language-server-command",
+                    "language_server_id": "gleam",
+                    "language_name": "Gleam"
+                },
+                "which": { "gleam": "/usr/bin/gleam" },
+                "http": [{ "url": "https://example.com", "body": "hello" }]
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let test_case = load_test_case(&path).unwrap();
+        assert!(matches!(
+            test_case.call,
+            TestCall::LanguageServerCommand { language_server_id, language_name }
+                if language_server_id == "gleam" && language_name == "Gleam"
+        ));
+        assert_eq!(test_case.which["gleam"], "/usr/bin/gleam");
+        assert_eq!(test_case.http[0].status, 200);
+        assert_eq!(test_case.worktree, None);
+
+        fs::write(
+            &path,
+            json!({ "call": { "export": "no-such-export" } }).to_string(),
+        )
+        .unwrap();
+        let error = load_test_case(&path).unwrap_err();
+        assert!(format!("{error:#}").contains("failed to parse test case"));
+
+        fs::write(
+            &path,
+            json!({
+                "call": { "export": "suggest-docs-packages", "provider": "rustdoc" },
+                "unknown": true
+            })
+            .to_string(),
+        )
+        .unwrap();
+        assert!(load_test_case(&path).is_err());
+    }
+
+    #[test]
+    fn test_compares_output_with_expected_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let expected_path = dir.path().join("command.expected.json");
+        let output = json!({ "ok": { "command": "gleam", "args": ["lsp"] } });
+
+        let error = check_output(&output, &expected_path, false).unwrap_err();
+        assert!(format!("{error:#}").contains("failed to read"));
+
+        check_output(&output, &expected_path, true).unwrap();
+        assert_eq!(
+            fs::read_to_string(&expected_path).unwrap(),
+            serde_json::to_string_pretty(&output).unwrap() + "\n"
+        );
+        check_output(&output, &expected_path, false).unwrap();
+
+        // Outputs are compared structurally, so formatting and key order don't matter.
+        fs::write(
+            &expected_path,
+            r#"{"ok":{"args":["lsp"],"command":"gleam"}}"#,
+        )
+        .unwrap();
+        check_output(&output, &expected_path, false).unwrap();
+
+        let error = check_output(&json!({ "error": "oops" }), &expected_path, false).unwrap_err();
+        let message = error.to_string();
+        assert!(message.starts_with(&format!("output did not match {}", expected_path.display())));
+        assert!(message.contains("\"oops\""));
+        assert!(message.contains("\"gleam\""));
+    }
+
+    #[test]
+    fn test_reports_failures() {
+        let mut summary = TestSummary::default();
+        summary.record("a".into(), Ok(()));
+        summary.record("b".into(), Err(anyhow!("output did not match")));
+        summary.record("c".into(), Ok(()));
+        summary.record("d".into(), Err(anyhow!("failed to load extension")));
+        assert_eq!(summary.passed, 2);
+        assert_eq!(summary.failed, ["b", "d"]);
+    }
+
+    #[test]
+    fn test_normalizes_output() {
+        let output = replace_paths(
+            json!({
+                "ok": {
+                    "command": "/tmp/work/gleam/bin/gleam",
+                    "args": ["--root", "/tmp/root"],
+                    "env": [["PORT", 8080]]
+                }
+            }),
+            &[
+                (Path::new("/tmp/root"), "$WORKTREE_ROOT"),
+                (Path::new("/tmp/work"), "$WORK_DIR"),
+            ],
+        );
+        assert_eq!(
+            output,
+            json!({
+                "ok": {
+                    "command": "$WORK_DIR/gleam/bin/gleam",
+                    "args": ["--root", "$WORKTREE_ROOT"],
+                    "env": [["PORT", 8080]]
+                }
+            })
+        );
+
+        assert_eq!(
+            json_string_to_value(Some(r#"{"a": 1}"#.into())),
+            json!({ "a": 1 })
+        );
+        assert_eq!(
+            json_string_to_value(Some("not json".into())),
+            json!("not json")
+        );
+        assert_eq!(json_string_to_value(None), Value::Null);
+    }
+
+    #[test]
+    fn test_rejects_http_fixtures_with_two_bodies() {
+        let fixtures = [HttpFixture {
+            url: "https://example.com".into(),
+            status: 200,
+            body: Some(json!("hello")),
+            body_file: Some("body.txt".into()),
+        }];
+        assert!(fake_http_client(&fixtures, Path::new(".")).is_err());
+
+        let fixtures = [HttpFixture {
+            url: "https://example.com".into(),
+            status: 200,
+            body: None,
+            body_file: Some("missing.txt".into()),
+        }];
+        assert!(fake_http_client(&fixtures, Path::new(".")).is_err());
+    }
+
+    #[test]
+    fn test_fixture_worktree_stays_within_its_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("root");
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("package.json"), "{}").unwrap();
+        fs::write(dir.path().join("secret.txt"), "secret").unwrap();
+        let worktree = FixtureWorktree {
+            root: root.clone(),
+            which: HashMap::default(),
+            shell_env: Vec::new(),
+        };
+
+        let package_json = root.canonicalize().unwrap().join("package.json");
+        assert_eq!(
+            worktree.abs_path("package.json".as_ref()).unwrap(),
+            package_json
+        );
+        assert_eq!(
+            worktree.abs_path("./src/../package.json".as_ref()).unwrap(),
+            package_json
+        );
+        assert!(worktree.abs_path("../secret.txt".as_ref()).is_err());
+        assert!(worktree.abs_path("src/../../secret.txt".as_ref()).is_err());
+        assert!(worktree.abs_path(&dir.path().join("secret.txt")).is_err());
+        assert!(worktree.abs_path("missing.txt".as_ref()).is_err());
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(dir.path().join("secret.txt"), root.join("link.txt"))
+                .unwrap();
+            assert!(worktree.abs_path("link.txt".as_ref()).is_err());
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_runs_test_extension() {
+        let root_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .parent()
+            .unwrap();
+        let extension_path = root_dir.join("extensions").join("test-extension");
+        let mut manifest = ExtensionManifest::load(Arc::new(RealFs::default()), &extension_path)
+            .await
+            .unwrap();
+        let http_client = Arc::new(ReqwestClient::user_agent("Zed Extension CLI tests").unwrap());
+        ExtensionBuilder::new(http_client, root_dir.join("target"))
+            .compile_extension(
+                &extension_path,
+                &mut manifest,
+                CompileExtensionOptions { release: false },
+            )
+            .await
+            .unwrap();
+
+        // The test cases checked in with the extension run against its compiled WebAssembly.
+        let work_dir = tempfile::tempdir().unwrap();
+        let summary = tokio::task::block_in_place(|| {
+            run_tests(
+                extension_path.clone(),
+                manifest,
+                extension_path.join("tests"),
+                work_dir.path().to_path_buf(),
+                false,
+            )
+        })
+        .unwrap();
+        assert_eq!(summary.failed, Vec::<String>::new());
+        assert_eq!(summary.passed, 1);
+    }
+}
@@ -15,7 +15,8 @@ pub use extension::ExtensionManifest;
 use extension::{
     ExtensionContextServerProxy, ExtensionGrammarProxy, ExtensionHostProxy,
     ExtensionIndexedDocsProviderProxy, ExtensionLanguageProxy, ExtensionLanguageServerProxy,
-    ExtensionSlashCommandProxy, ExtensionSnippetProxy, ExtensionThemeProxy,
+    ExtensionSlashCommandProxy, ExtensionSnippetProxy, ExtensionTaskProviderProxy,
+    ExtensionThemeProxy,
 };
 use fs::{Fs, RemoveOptions};
 use futures::{
@@ -27,8 +28,8 @@ use futures::{
     select_biased, AsyncReadExt as _, Future, FutureExt as _, StreamExt as _,
 };
 use gpui::{
-    actions, AppContext, AsyncAppContext, Context, EventEmitter, Global, Model, ModelContext, Task,
-    WeakModel,
+    actions, AppContext, AsyncAppContext, Context, EventEmitter, Global, Model, ModelContext,
+    SharedString, Task, WeakModel,
 };
 use http_client::{AsyncBody, HttpClient, HttpClientWithUrl};
 use language::{
@@ -58,7 +59,8 @@ use wasm_host::{
 };
 
 pub use extension::{
-    ExtensionLibraryKind, GrammarManifestEntry, OldExtensionManifest, SchemaVersion,
+    ExtensionCapabilities, ExtensionLibraryKind, FilesystemScope, GrammarManifestEntry,
+    OldExtensionManifest, SchemaVersion,
 };
 pub use extension_settings::ExtensionSettings;
 
@@ -112,6 +114,10 @@ pub struct ExtensionStore {
     pub modified_extensions: HashSet<Arc<str>>,
     pub wasm_host: Arc<WasmHost>,
     pub wasm_extensions: Vec<(Arc<ExtensionManifest>, WasmExtension)>,
+    pub granted_capabilities: BTreeMap<Arc<str>, ExtensionCapabilityGrant>,
+    pub granted_capabilities_path: PathBuf,
+    /// The extensions that have been disabled for this session, along with the reason why.
+    pub disabled_extensions: BTreeMap<Arc<str>, SharedString>,
     pub tasks: Vec<Task<()>>,
     pub ssh_clients: HashMap<String, WeakModel<SshRemoteClient>>,
     pub ssh_registered_tx: UnboundedSender<()>,
@@ -130,6 +136,9 @@ pub enum Event {
     StartedReloading,
     ExtensionInstalled(Arc<str>),
     ExtensionFailedToLoad(Arc<str>),
+    /// An extension was loaded that requests capabilities the user hasn't reviewed.
+    ExtensionCapabilitiesRequested(Arc<str>),
+    ExtensionDisabled(Arc<str>),
 }
 
 impl EventEmitter<Event> for ExtensionStore {}
@@ -142,6 +151,8 @@ impl Global for GlobalExtensionStore {}
 pub struct ExtensionIndex {
     pub extensions: BTreeMap<Arc<str>, ExtensionIndexEntry>,
     pub themes: BTreeMap<Arc<str>, ExtensionIndexThemeEntry>,
+    #[serde(default)]
+    pub icon_themes: BTreeMap<Arc<str>, ExtensionIndexIconThemeEntry>,
     pub languages: BTreeMap<LanguageName, ExtensionIndexLanguageEntry>,
 }
 
@@ -151,12 +162,29 @@ pub struct ExtensionIndexEntry {
     pub dev: bool,
 }
 
+/// The user's decision about the capabilities requested by an extension.
+#[derive(Clone, PartialEq, Eq, Debug, Default, Deserialize, Serialize)]
+pub struct ExtensionCapabilityGrant {
+    /// The capabilities that the user granted to the extension.
+    pub granted: ExtensionCapabilities,
+    /// The capabilities that the extension requested when the user made their decision.
+    ///
+    /// The user is asked again if a new version of the extension requests more than this.
+    pub reviewed: ExtensionCapabilities,
+}
+
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Deserialize, Serialize)]
 pub struct ExtensionIndexThemeEntry {
     pub extension: Arc<str>,
     pub path: PathBuf,
 }
 
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Deserialize, Serialize)]
+pub struct ExtensionIndexIconThemeEntry {
+    pub extension: Arc<str>,
+    pub path: PathBuf,
+}
+
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Deserialize, Serialize)]
 pub struct ExtensionIndexLanguageEntry {
     pub extension: Arc<str>,
@@ -225,6 +253,7 @@ impl ExtensionStore {
         let build_dir = build_dir.unwrap_or_else(|| extensions_dir.join("build"));
         let installed_dir = extensions_dir.join("installed");
         let index_path = extensions_dir.join("index.json");
+        let granted_capabilities_path = extensions_dir.join("granted_capabilities.json");
 
         let (reload_tx, mut reload_rx) = unbounded();
         let (connection_registered_tx, mut connection_registered_rx) = unbounded();
@@ -246,6 +275,9 @@ impl ExtensionStore {
                 cx,
             ),
             wasm_extensions: Vec::new(),
+            granted_capabilities: BTreeMap::default(),
+            granted_capabilities_path,
+            disabled_extensions: BTreeMap::default(),
             fs,
             http_client,
             telemetry,
@@ -259,15 +291,25 @@ impl ExtensionStore {
         // The extensions store maintains an index file, which contains a complete
         // list of the installed extensions and the resources that they provide.
         // This index is loaded synchronously on startup.
-        let (index_content, index_metadata, extensions_metadata) =
+        let (index_content, index_metadata, extensions_metadata, granted_capabilities_content) =
             cx.background_executor().block(async {
                 futures::join!(
                     this.fs.load(&this.index_path),
                     this.fs.metadata(&this.index_path),
                     this.fs.metadata(&this.installed_dir),
+                    this.fs.load(&this.granted_capabilities_path),
                 )
             });
 
+        let has_granted_capabilities = granted_capabilities_content.is_ok();
+        if let Ok(granted_capabilities_content) = granted_capabilities_content {
+            if let Some(granted_capabilities) =
+                serde_json::from_str(&granted_capabilities_content).log_err()
+            {
+                this.granted_capabilities = granted_capabilities;
+            }
+        }
+
         // Normally, there is no need to rebuild the index. But if the index file
         // is invalid or is out-of-date according to the filesystem mtimes, then
         // it must be asynchronously rebuilt.
@@ -289,6 +331,10 @@ impl ExtensionStore {
             }
         }
 
+        if !has_granted_capabilities {
+            this.grant_previously_installed_extensions(&extension_index, cx);
+        }
+
         // Immediately load all of the extensions in the initial manifest. If the
         // index needs to be rebuild, then enqueue
         let load_initial_extensions = this.extensions_updated(extension_index, cx);
@@ -418,6 +464,144 @@ impl ExtensionStore {
         &self.extension_index.extensions
     }
 
+    /// Returns the capabilities requested by the given extension that the user hasn't reviewed yet.
+    pub fn capabilities_pending_review(&self, extension_id: &str) -> Option<ExtensionCapabilities> {
+        let extension = self.extension_index.extensions.get(extension_id)?;
+        if extension.dev || extension.manifest.lib.kind.is_none() {
+            return None;
+        }
+
+        let requested = extension.manifest.requested_capabilities();
+        if requested.is_empty() {
+            return None;
+        }
+        match self.granted_capabilities.get(extension_id) {
+            Some(grant) if grant.reviewed.contains(&requested) => None,
+            _ => Some(requested),
+        }
+    }
+
+    /// Returns the IDs of the installed extensions whose requested capabilities the user hasn't
+    /// reviewed yet.
+    pub fn extensions_pending_capability_review(&self) -> Vec<Arc<str>> {
+        self.extension_index
+            .extensions
+            .keys()
+            .filter(|extension_id| self.capabilities_pending_review(extension_id).is_some())
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the capabilities that the given extension is allowed to use.
+    ///
+    /// Dev extensions are allowed to use everything they request.
+    pub fn effective_capabilities(&self, extension_id: &str) -> ExtensionCapabilities {
+        let Some(extension) = self.extension_index.extensions.get(extension_id) else {
+            return ExtensionCapabilities::default();
+        };
+
+        let requested = extension.manifest.requested_capabilities();
+        if extension.dev {
+            return requested;
+        }
+        self.granted_capabilities
+            .get(extension_id)
+            .map(|grant| grant.granted.intersection(&requested))
+            .unwrap_or_default()
+    }
+
+    /// Records the capabilities that the user granted to an extension, and reloads it so
+    /// that they take effect.
+    ///
+    /// The capabilities the extension currently requests are marked as reviewed, so the user
+    /// won't be asked about them again.
+    pub fn grant_capabilities(
+        &mut self,
+        extension_id: Arc<str>,
+        granted: ExtensionCapabilities,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(extension) = self.extension_index.extensions.get(&extension_id) else {
+            return;
+        };
+
+        let grant = ExtensionCapabilityGrant {
+            granted,
+            reviewed: extension.manifest.requested_capabilities(),
+        };
+        self.granted_capabilities
+            .insert(extension_id.clone(), grant);
+        self.save_granted_capabilities(cx);
+        cx.notify();
+        drop(self.reload(Some(extension_id), cx));
+    }
+
+    /// Grants the extensions that were installed before capabilities had to be granted
+    /// everything that they request, so that they keep working after upgrading.
+    ///
+    /// This only happens once, when there are no saved grants yet. Extensions installed
+    /// afterwards, or updated to request more, are reviewed by the user as usual.
+    fn grant_previously_installed_extensions(
+        &mut self,
+        index: &ExtensionIndex,
+        cx: &mut ModelContext<Self>,
+    ) {
+        for (extension_id, extension) in &index.extensions {
+            if extension.dev {
+                continue;
+            }
+            let requested = extension.manifest.requested_capabilities();
+            self.granted_capabilities.insert(
+                extension_id.clone(),
+                ExtensionCapabilityGrant {
+                    granted: requested.clone(),
+                    reviewed: requested,
+                },
+            );
+        }
+        self.save_granted_capabilities(cx);
+    }
+
+    fn save_granted_capabilities(&self, cx: &mut ModelContext<Self>) {
+        let fs = self.fs.clone();
+        let path = self.granted_capabilities_path.clone();
+        let granted_capabilities = self.granted_capabilities.clone();
+        cx.background_executor()
+            .spawn(async move {
+                let json = serde_json::to_string_pretty(&granted_capabilities)?;
+                fs.save(&path, &json.as_str().into(), Default::default())
+                    .await
+            })
+            .detach_and_log_err(cx);
+    }
+
+    /// Returns the reason that the given extension was disabled, if it has been.
+    pub fn disabled_reason(&self, extension_id: &str) -> Option<&SharedString> {
+        self.disabled_extensions.get(extension_id)
+    }
+
+    /// Disables an extension for the rest of the session, unloading its WebAssembly.
+    pub fn disable_extension(
+        &mut self,
+        extension_id: Arc<str>,
+        reason: SharedString,
+        cx: &mut ModelContext<Self>,
+    ) {
+        self.disabled_extensions
+            .insert(extension_id.clone(), reason);
+        cx.emit(Event::ExtensionDisabled(extension_id.clone()));
+        cx.notify();
+        drop(self.reload(Some(extension_id), cx));
+    }
+
+    /// Re-enables an extension that was disabled.
+    pub fn enable_extension(&mut self, extension_id: Arc<str>, cx: &mut ModelContext<Self>) {
+        if self.disabled_extensions.remove(&extension_id).is_some() {
+            cx.notify();
+            drop(self.reload(Some(extension_id), cx));
+        }
+    }
+
     pub fn dev_extensions(&self) -> impl Iterator<Item = &Arc<ExtensionManifest>> {
         self.extension_index
             .extensions
@@ -436,6 +620,23 @@ impl ExtensionStore {
             .filter_map(|(name, theme)| theme.extension.as_ref().eq(extension_id).then_some(name))
     }
 
+    /// Returns the names of icon themes provided by extensions.
+    pub fn extension_icon_themes<'a>(
+        &'a self,
+        extension_id: &'a str,
+    ) -> impl Iterator<Item = &'a Arc<str>> {
+        self.extension_index
+            .icon_themes
+            .iter()
+            .filter_map(|(name, icon_theme)| {
+                icon_theme
+                    .extension
+                    .as_ref()
+                    .eq(extension_id)
+                    .then_some(name)
+            })
+    }
+
     pub fn fetch_extensions(
         &self,
         search: Option<&str>,
@@ -808,8 +1009,14 @@ impl ExtensionStore {
             )
             .await?;
 
-            this.update(&mut cx, |this, cx| this.reload(None, cx))?
-                .await;
+            this.update(&mut cx, |this, cx| {
+                this.disabled_extensions.remove(&extension_id);
+                if this.granted_capabilities.remove(&extension_id).is_some() {
+                    this.save_granted_capabilities(cx);
+                }
+                this.reload(None, cx)
+            })?
+            .await;
             anyhow::Ok(())
         })
         .detach_and_log_err(cx)
@@ -1023,6 +1230,17 @@ impl ExtensionStore {
                 }
             })
             .collect::<Vec<_>>();
+        let icon_themes_to_remove = old_index
+            .icon_themes
+            .iter()
+            .filter_map(|(name, entry)| {
+                if extensions_to_unload.contains(&entry.extension) {
+                    Some(name.clone().into())
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
         let languages_to_remove = old_index
             .languages
             .iter()
@@ -1046,11 +1264,15 @@ impl ExtensionStore {
                         .remove_language_server(&language, language_server_name);
                 }
             }
+            for provider_id in extension.manifest.task_providers.keys() {
+                self.proxy.remove_task_provider(provider_id);
+            }
         }
 
         self.wasm_extensions
             .retain(|(extension, _)| !extensions_to_unload.contains(&extension.id));
         self.proxy.remove_user_themes(themes_to_remove);
+        self.proxy.remove_icon_themes(icon_themes_to_remove);
         self.proxy
             .remove_languages(&languages_to_remove, &grammars_to_remove);
 
@@ -1061,6 +1283,7 @@ impl ExtensionStore {
             .collect::<Vec<_>>();
         let mut grammars_to_add = Vec::new();
         let mut themes_to_add = Vec::new();
+        let mut icon_themes_to_add = Vec::new();
         let mut snippets_to_add = Vec::new();
         for extension_id in &extensions_to_load {
             let Some(extension) = new_index.extensions.get(extension_id) else {
@@ -1079,6 +1302,16 @@ impl ExtensionStore {
                 path.extend([Path::new(extension_id.as_ref()), theme_path.as_path()]);
                 path
             }));
+            icon_themes_to_add.extend(extension.manifest.icon_themes.iter().map(
+                |icon_theme_path| {
+                    let mut path = self.installed_dir.clone();
+                    path.extend([Path::new(extension_id.as_ref()), icon_theme_path.as_path()]);
+
+                    let icons_root_path = self.installed_dir.join(extension_id.as_ref());
+
+                    (path, icons_root_path)
+                },
+            ));
             snippets_to_add.extend(extension.manifest.snippets.iter().map(|snippets_path| {
                 let mut path = self.installed_dir.clone();
                 path.extend([Path::new(extension_id.as_ref()), snippets_path.as_path()]);
@@ -1129,9 +1362,24 @@ impl ExtensionStore {
         let extension_entries = extensions_to_load
             .iter()
             .filter_map(|name| new_index.extensions.get(name).cloned())
+            .filter(|extension| {
+                !self
+                    .disabled_extensions
+                    .contains_key(&extension.manifest.id)
+            })
             .collect::<Vec<_>>();
 
         self.extension_index = new_index;
+        let extension_capabilities = extension_entries
+            .iter()
+            .map(|extension| {
+                let extension_id = &extension.manifest.id;
+                (
+                    extension_id.clone(),
+                    self.effective_capabilities(extension_id),
+                )
+            })
+            .collect::<HashMap<_, _>>();
         cx.notify();
         cx.emit(Event::ExtensionsUpdated);
 
@@ -1147,6 +1395,13 @@ impl ExtensionStore {
                                 .log_err();
                         }
 
+                        for (icon_theme_path, icons_root_path) in icon_themes_to_add.into_iter() {
+                            proxy
+                                .load_icon_theme(icon_theme_path, icons_root_path, fs.clone())
+                                .await
+                                .log_err();
+                        }
+
                         for snippets_path in &snippets_to_add {
                             if let Some(snippets_contents) = fs.load(snippets_path).await.log_err()
                             {
@@ -1166,9 +1421,14 @@ impl ExtensionStore {
                 };
 
                 let extension_path = root_dir.join(extension.manifest.id.as_ref());
+                let capabilities = extension_capabilities
+                    .get(&extension.manifest.id)
+                    .cloned()
+                    .unwrap_or_default();
                 let wasm_extension = WasmExtension::load(
                     extension_path,
                     &extension.manifest,
+                    capabilities,
                     wasm_host.clone(),
                     &cx,
                 )
@@ -1224,10 +1484,25 @@ impl ExtensionStore {
                         this.proxy
                             .register_indexed_docs_provider(extension.clone(), provider_id.clone());
                     }
+
+                    for (provider_id, provider) in &manifest.task_providers {
+                        this.proxy.register_task_provider(
+                            extension.clone(),
+                            provider_id.clone(),
+                            provider.watched_paths.clone(),
+                        );
+                    }
+                }
+
+                for (manifest, _) in &wasm_extensions {
+                    if this.capabilities_pending_review(&manifest.id).is_some() {
+                        cx.emit(Event::ExtensionCapabilitiesRequested(manifest.id.clone()));
+                    }
                 }
 
                 this.wasm_extensions.extend(wasm_extensions);
                 this.proxy.reload_current_theme(cx);
+                this.proxy.reload_current_icon_theme(cx);
             })
             .ok();
         })
@@ -1365,6 +1640,38 @@ impl ExtensionStore {
             }
         }
 
+        if let Ok(mut icon_theme_paths) = fs.read_dir(&extension_dir.join("icon_themes")).await {
+            while let Some(icon_theme_path) = icon_theme_paths.next().await {
+                let icon_theme_path = icon_theme_path?;
+                let Ok(relative_path) = icon_theme_path.strip_prefix(&extension_dir) else {
+                    continue;
+                };
+
+                let Some(icon_theme_families) = proxy
+                    .list_icon_theme_names(icon_theme_path.clone(), fs.clone())
+                    .await
+                    .log_err()
+                else {
+                    continue;
+                };
+
+                let relative_path = relative_path.to_path_buf();
+                if !extension_manifest.icon_themes.contains(&relative_path) {
+                    extension_manifest.icon_themes.push(relative_path.clone());
+                }
+
+                for icon_theme_name in icon_theme_families {
+                    index.icon_themes.insert(
+                        icon_theme_name.into(),
+                        ExtensionIndexIconThemeEntry {
+                            extension: extension_id.clone(),
+                            path: relative_path.clone(),
+                        },
+                    );
+                }
+            }
+        }
+
         let extension_wasm_path = extension_dir.join("extension.wasm");
         if fs.is_file(&extension_wasm_path).await {
             extension_manifest
@@ -1444,6 +1751,9 @@ impl ExtensionStore {
                         id: id.to_string(),
                         version: entry.manifest.version.to_string(),
                         dev: entry.dev,
+                        capabilities: Some(headless_host::capabilities_to_proto(
+                            this.effective_capabilities(id),
+                        )),
                     })
                 })
                 .collect()
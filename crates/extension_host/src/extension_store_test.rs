@@ -5,16 +5,19 @@ use crate::{
 };
 use async_compression::futures::bufread::GzipEncoder;
 use collections::BTreeMap;
-use extension::ExtensionHostProxy;
+use extension::{ExtensionCapabilities, ExtensionHostProxy, FilesystemScope};
 use fs::{FakeFs, Fs, RealFs};
 use futures::{io::BufReader, AsyncReadExt, StreamExt};
 use gpui::{Context, SemanticVersion, TestAppContext};
 use http_client::{FakeHttpClient, Response};
-use language::{LanguageMatcher, LanguageRegistry, LanguageServerBinaryStatus};
+use language::{
+    LanguageMatcher, LanguageRegistry, LanguageServerBinaryStatus, LanguageServerCommandContext,
+    LanguageServerCommandOutput,
+};
 use lsp::LanguageServerName;
 use node_runtime::NodeRuntime;
 use parking_lot::Mutex;
-use project::{Project, DEFAULT_COMPLETION_CONTEXT};
+use project::{Project, TaskSourceKind, DEFAULT_COMPLETION_CONTEXT};
 use release_channel::AppVersion;
 use reqwest_client::ReqwestClient;
 use serde_json::json;
@@ -149,6 +152,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         authors: Vec::new(),
                         repository: None,
                         themes: Default::default(),
+                        icon_themes: Vec::new(),
                         lib: Default::default(),
                         languages: vec!["languages/erb".into(), "languages/ruby".into()],
                         grammars: [
@@ -161,7 +165,9 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         context_servers: BTreeMap::default(),
                         slash_commands: BTreeMap::default(),
                         indexed_docs_providers: BTreeMap::default(),
+                        task_providers: BTreeMap::default(),
                         snippets: None,
+                        capabilities: None,
                     }),
                     dev: false,
                 },
@@ -181,6 +187,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                             "themes/monokai-pro.json".into(),
                             "themes/monokai.json".into(),
                         ],
+                        icon_themes: Vec::new(),
                         lib: Default::default(),
                         languages: Default::default(),
                         grammars: BTreeMap::default(),
@@ -188,7 +195,9 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         context_servers: BTreeMap::default(),
                         slash_commands: BTreeMap::default(),
                         indexed_docs_providers: BTreeMap::default(),
+                        task_providers: BTreeMap::default(),
                         snippets: None,
+                        capabilities: None,
                     }),
                     dev: false,
                 },
@@ -258,6 +267,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
         ]
         .into_iter()
         .collect(),
+        icon_themes: BTreeMap::default(),
     };
 
     let proxy = Arc::new(ExtensionHostProxy::new());
@@ -344,6 +354,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                 authors: vec![],
                 repository: None,
                 themes: vec!["themes/gruvbox.json".into()],
+                icon_themes: Vec::new(),
                 lib: Default::default(),
                 languages: Default::default(),
                 grammars: BTreeMap::default(),
@@ -351,7 +362,9 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                 context_servers: BTreeMap::default(),
                 slash_commands: BTreeMap::default(),
                 indexed_docs_providers: BTreeMap::default(),
+                task_providers: BTreeMap::default(),
                 snippets: None,
+                capabilities: None,
             }),
             dev: false,
         },
@@ -451,6 +464,247 @@ async fn test_extension_store(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_extension_capability_grants(cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    let http_client = FakeHttpClient::with_200_response();
+    let node_runtime = NodeRuntime::unavailable();
+    let proxy = Arc::new(ExtensionHostProxy::new());
+
+    let manifest = |network: &str| {
+        format!(
+            r#"
+                id = "gopher"
+                name = "Gopher"
+                version = "1.0.0"
+                schema_version = 1
+
+                [lib]
+                kind = "Rust"
+
+                [capabilities]
+                network = [{network}]
+                filesystem = "worktree"
+                process = ["gopls"]
+            "#
+        )
+    };
+    fs.insert_tree(
+        "/the-extension-dir",
+        json!({
+            "installed": {
+                "gopher": {
+                    "extension.toml": manifest(r#""api.github.com""#),
+                }
+            }
+        }),
+    )
+    .await;
+
+    let new_store = |cx: &mut TestAppContext| {
+        cx.new_model(|cx| {
+            ExtensionStore::new(
+                PathBuf::from("/the-extension-dir"),
+                None,
+                proxy.clone(),
+                fs.clone(),
+                http_client.clone(),
+                http_client.clone(),
+                None,
+                node_runtime.clone(),
+                cx,
+            )
+        })
+    };
+
+    let store = new_store(cx);
+    cx.executor().advance_clock(RELOAD_DEBOUNCE_DURATION);
+    cx.executor().run_until_parked();
+
+    // Nothing is granted until the user reviews the requested capabilities.
+    let requested = ExtensionCapabilities {
+        network: vec!["api.github.com".into()],
+        filesystem: FilesystemScope::Worktree,
+        process: vec!["gopls".into()],
+    };
+    store.read_with(cx, |store, _| {
+        assert_eq!(
+            store.capabilities_pending_review("gopher"),
+            Some(requested.clone())
+        );
+        assert_eq!(
+            store.extensions_pending_capability_review(),
+            vec![Arc::<str>::from("gopher")]
+        );
+        assert!(store.effective_capabilities("gopher").is_empty());
+    });
+
+    // The extension can only use the granted capabilities that it requested.
+    store.update(cx, |store, cx| {
+        store.grant_capabilities(
+            "gopher".into(),
+            ExtensionCapabilities {
+                network: vec!["api.github.com".into(), "example.com".into()],
+                filesystem: FilesystemScope::WorkDir,
+                process: vec!["gopls".into()],
+            },
+            cx,
+        )
+    });
+    cx.executor().advance_clock(RELOAD_DEBOUNCE_DURATION);
+    cx.executor().run_until_parked();
+
+    let effective = ExtensionCapabilities {
+        network: vec!["api.github.com".into()],
+        filesystem: FilesystemScope::WorkDir,
+        process: vec!["gopls".into()],
+    };
+    store.read_with(cx, |store, _| {
+        assert_eq!(store.capabilities_pending_review("gopher"), None);
+        assert_eq!(store.effective_capabilities("gopher"), effective);
+    });
+
+    // Grants persist across restarts.
+    drop(store);
+    let store = new_store(cx);
+    cx.executor().run_until_parked();
+    store.read_with(cx, |store, _| {
+        assert_eq!(store.capabilities_pending_review("gopher"), None);
+        assert_eq!(store.effective_capabilities("gopher"), effective);
+    });
+
+    // A new version that requests more capabilities needs to be reviewed again.
+    fs.insert_file(
+        "/the-extension-dir/installed/gopher/extension.toml",
+        manifest(r#""api.github.com", "proxy.golang.org""#).into_bytes(),
+    )
+    .await;
+    #[allow(clippy::let_underscore_future)]
+    let _ = store.update(cx, |store, cx| store.reload(Some("gopher".into()), cx));
+    cx.executor().advance_clock(RELOAD_DEBOUNCE_DURATION);
+    cx.executor().run_until_parked();
+    store.read_with(cx, |store, _| {
+        assert!(store.capabilities_pending_review("gopher").is_some());
+        assert_eq!(store.effective_capabilities("gopher"), effective);
+    });
+
+    // Extensions can be disabled for the session, and re-enabled.
+    store.update(cx, |store, cx| {
+        store.disable_extension("gopher".into(), "Disabled for testing.".into(), cx)
+    });
+    store.read_with(cx, |store, _| {
+        assert_eq!(
+            store
+                .disabled_reason("gopher")
+                .map(|reason| reason.as_ref()),
+            Some("Disabled for testing.")
+        );
+    });
+    store.update(cx, |store, cx| store.enable_extension("gopher".into(), cx));
+    store.read_with(cx, |store, _| {
+        assert_eq!(store.disabled_reason("gopher"), None);
+    });
+}
+
+#[gpui::test]
+async fn test_previously_installed_extensions_are_granted_capabilities(cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    let http_client = FakeHttpClient::with_200_response();
+
+    let manifest = |id: &str| {
+        format!(
+            r#"
+                id = "{id}"
+                name = "{id}"
+                version = "1.0.0"
+                schema_version = 1
+
+                [lib]
+                kind = "Rust"
+
+                [capabilities]
+                network = ["api.github.com"]
+            "#
+        )
+    };
+    let index = ExtensionIndex {
+        extensions: [(
+            "gopher".into(),
+            ExtensionIndexEntry {
+                manifest: Arc::new(toml::from_str(&manifest("gopher")).unwrap()),
+                dev: false,
+            },
+        )]
+        .into_iter()
+        .collect(),
+        ..Default::default()
+    };
+    fs.insert_tree(
+        "/the-extension-dir",
+        json!({
+            "index.json": serde_json::to_string(&index).unwrap(),
+            "installed": {
+                "gopher": {
+                    "extension.toml": manifest("gopher"),
+                }
+            }
+        }),
+    )
+    .await;
+
+    let store = cx.new_model(|cx| {
+        ExtensionStore::new(
+            PathBuf::from("/the-extension-dir"),
+            None,
+            Arc::new(ExtensionHostProxy::new()),
+            fs.clone(),
+            http_client.clone(),
+            http_client.clone(),
+            None,
+            NodeRuntime::unavailable(),
+            cx,
+        )
+    });
+    cx.executor().advance_clock(RELOAD_DEBOUNCE_DURATION);
+    cx.executor().run_until_parked();
+
+    // Extensions that were installed before capabilities had to be granted keep everything
+    // they request.
+    let requested = ExtensionCapabilities {
+        network: vec!["api.github.com".into()],
+        ..Default::default()
+    };
+    store.read_with(cx, |store, _| {
+        assert_eq!(store.capabilities_pending_review("gopher"), None);
+        assert_eq!(store.effective_capabilities("gopher"), requested);
+    });
+
+    // Extensions installed afterwards still need to be reviewed.
+    fs.insert_tree(
+        "/the-extension-dir/installed/lua",
+        json!({
+            "extension.toml": manifest("lua"),
+        }),
+    )
+    .await;
+    #[allow(clippy::let_underscore_future)]
+    let _ = store.update(cx, |store, cx| store.reload(None, cx));
+    cx.executor().advance_clock(RELOAD_DEBOUNCE_DURATION);
+    cx.executor().run_until_parked();
+    store.read_with(cx, |store, _| {
+        assert_eq!(
+            store.capabilities_pending_review("lua"),
+            Some(requested.clone())
+        );
+        assert!(store.effective_capabilities("lua").is_empty());
+        assert_eq!(store.effective_capabilities("gopher"), requested);
+    });
+}
+
 #[gpui::test]
 async fn test_extension_store_with_test_extension(cx: &mut TestAppContext) {
     init_test(cx);
@@ -471,7 +725,8 @@ async fn test_extension_store_with_test_extension(cx: &mut TestAppContext) {
         "work": {}
     }));
     let project_dir = temp_tree(json!({
-        "test.gleam": ""
+        "test.gleam": "",
+        "scripts.txt": "build\ntest\n",
     }));
 
     let extensions_dir = extensions_dir.path().canonicalize().unwrap();
@@ -713,6 +968,108 @@ async fn test_extension_store_with_test_extension(cx: &mut TestAppContext) {
         ]
     );
 
+    // The extension handles the requests and notifications from the language server that are
+    // declared in its manifest, and no others.
+    let server = project
+        .update(cx, |project, cx| {
+            project.language_server_for_id(fake_server.server.server_id(), cx)
+        })
+        .unwrap();
+    assert!(server.has_handler("gleam/echo"));
+    assert!(server.has_handler("gleam/log"));
+    assert!(!server.has_handler("gleam/undeclared"));
+
+    fake_server
+        .server
+        .custom_notify("gleam/log", json!({ "message": "hello" }))
+        .unwrap();
+    cx.executor().run_until_parked();
+    let response = fake_server
+        .server
+        .custom_request("gleam/echo", json!({ "value": 1 }))
+        .await
+        .unwrap();
+    assert_eq!(
+        response,
+        json!({ "params": { "value": 1 }, "logs": [{ "message": "hello" }] })
+    );
+
+    // The extension runs the commands declared in its manifest, which can send requests to the
+    // language server.
+    fake_server
+        .server
+        .on_custom_request("gleam/ping", |params: serde_json::Value, _| async move {
+            Ok(json!({ "pong": params["uri"] }))
+        })
+        .detach();
+    let adapter = project.update(cx, |project, cx| {
+        project
+            .language_servers_for_buffer(buffer.read(cx), cx)
+            .next()
+            .map(|(adapter, _)| adapter.adapter.clone())
+            .unwrap()
+    });
+    assert_eq!(adapter.commands(), ["gleam.ping"]);
+    let uri = lsp::Url::from_file_path(project_dir.join("test.gleam")).unwrap();
+    let context = LanguageServerCommandContext {
+        uri: uri.clone(),
+        cursor: lsp::Position::new(0, 0),
+        selection: lsp::Range::default(),
+    };
+    assert_eq!(
+        adapter
+            .clone()
+            .run_command("gleam.ping".into(), context.clone(), server.clone())
+            .await
+            .unwrap(),
+        LanguageServerCommandOutput::OpenBuffer {
+            title: "Ping".into(),
+            text: json!({ "pong": uri }).to_string(),
+            language: None,
+        }
+    );
+    assert!(adapter
+        .run_command("gleam.undeclared".into(), context, server)
+        .await
+        .is_err());
+
+    // The tasks of the task providers declared in the extension's manifest are added to the
+    // project's task inventory.
+    cx.executor().run_until_parked();
+    let provider_tasks = project.update(cx, |project, cx| {
+        let worktree_id = project.worktrees(cx).next().unwrap().read(cx).id();
+        let inventory = project.task_store().read(cx).task_inventory().unwrap();
+        inventory
+            .read(cx)
+            .list_tasks(None, None, Some(worktree_id), cx)
+            .into_iter()
+            .map(|(source_kind, template)| {
+                assert_eq!(
+                    source_kind,
+                    TaskSourceKind::Provider {
+                        id: worktree_id,
+                        name: "scripts".into(),
+                    }
+                );
+                (
+                    template.label,
+                    template.command,
+                    template.args,
+                    template.cwd,
+                )
+            })
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(
+        provider_tasks,
+        ["build", "test"].map(|script| (
+            format!("run {script}"),
+            "./run".to_string(),
+            vec![script.to_string()],
+            Some("$ZED_WORKTREE_ROOT/scripts".to_string()),
+        ))
+    );
+
     // Simulate a new version of the language server being released
     language_server_version.lock().version = "v2.0.0".into();
     language_server_version.lock().binary_contents = "the-new-binary-contents".into();
@@ -4,8 +4,8 @@ use anyhow::{anyhow, Context as _, Result};
 use client::{proto, TypedEnvelope};
 use collections::{HashMap, HashSet};
 use extension::{
-    Extension, ExtensionHostProxy, ExtensionLanguageProxy, ExtensionLanguageServerProxy,
-    ExtensionManifest,
+    Extension, ExtensionCapabilities, ExtensionHostProxy, ExtensionLanguageProxy,
+    ExtensionLanguageServerProxy, ExtensionManifest, FilesystemScope,
 };
 use fs::{Fs, RemoveOptions, RenameOptions};
 use gpui::{AppContext, AsyncAppContext, Context, Model, ModelContext, Task, WeakModel};
@@ -21,6 +21,53 @@ pub struct ExtensionVersion {
     pub id: String,
     pub version: String,
     pub dev: bool,
+    /// The capabilities that the user granted to the extension on the client.
+    pub capabilities: ExtensionCapabilities,
+}
+
+impl ExtensionVersion {
+    pub fn from_proto(extension: proto::Extension) -> Self {
+        Self {
+            id: extension.id,
+            version: extension.version,
+            dev: extension.dev,
+            capabilities: extension
+                .capabilities
+                .map(capabilities_from_proto)
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn to_proto(self) -> proto::Extension {
+        proto::Extension {
+            id: self.id,
+            version: self.version,
+            dev: self.dev,
+            capabilities: Some(capabilities_to_proto(self.capabilities)),
+        }
+    }
+}
+
+pub fn capabilities_from_proto(
+    capabilities: proto::ExtensionCapabilities,
+) -> ExtensionCapabilities {
+    ExtensionCapabilities {
+        network: capabilities.network,
+        filesystem: if capabilities.read_worktree {
+            FilesystemScope::Worktree
+        } else {
+            FilesystemScope::WorkDir
+        },
+        process: capabilities.process,
+    }
+}
+
+pub fn capabilities_to_proto(capabilities: ExtensionCapabilities) -> proto::ExtensionCapabilities {
+    proto::ExtensionCapabilities {
+        read_worktree: capabilities.allows_worktree_read(),
+        network: capabilities.network,
+        process: capabilities.process,
+    }
 }
 
 pub struct HeadlessExtensionStore {
@@ -173,8 +220,16 @@ impl HeadlessExtensionStore {
             return Ok(());
         }
 
-        let wasm_extension: Arc<dyn Extension> =
-            Arc::new(WasmExtension::load(extension_dir, &manifest, wasm_host.clone(), &cx).await?);
+        let wasm_extension: Arc<dyn Extension> = Arc::new(
+            WasmExtension::load(
+                extension_dir,
+                &manifest,
+                extension.capabilities.clone(),
+                wasm_host.clone(),
+                &cx,
+            )
+            .await?,
+        );
 
         for (language_server_id, language_server_config) in &manifest.language_servers {
             for language in language_server_config.languages() {
@@ -260,16 +315,11 @@ impl HeadlessExtensionStore {
         envelope: TypedEnvelope<proto::SyncExtensions>,
         mut cx: AsyncAppContext,
     ) -> Result<proto::SyncExtensionsResponse> {
-        let requested_extensions =
-            envelope
-                .payload
-                .extensions
-                .into_iter()
-                .map(|p| ExtensionVersion {
-                    id: p.id,
-                    version: p.version,
-                    dev: p.dev,
-                });
+        let requested_extensions = envelope
+            .payload
+            .extensions
+            .into_iter()
+            .map(ExtensionVersion::from_proto);
         let missing_extensions = extension_store
             .update(&mut cx, |extension_store, cx| {
                 extension_store.sync_extensions(requested_extensions.collect(), cx)
@@ -279,11 +329,7 @@ impl HeadlessExtensionStore {
         Ok(proto::SyncExtensionsResponse {
             missing_extensions: missing_extensions
                 .into_iter()
-                .map(|e| proto::Extension {
-                    id: e.id,
-                    version: e.version,
-                    dev: e.dev,
-                })
+                .map(ExtensionVersion::to_proto)
                 .collect(),
             tmp_dir: paths::remote_extensions_uploads_dir()
                 .to_string_lossy()
@@ -304,11 +350,7 @@ impl HeadlessExtensionStore {
         extensions
             .update(&mut cx, |extensions, cx| {
                 extensions.install_extension(
-                    ExtensionVersion {
-                        id: extension.id,
-                        version: extension.version,
-                        dev: extension.dev,
-                    },
+                    ExtensionVersion::from_proto(extension),
                     PathBuf::from(envelope.payload.tmp_dir),
                     cx,
                 )
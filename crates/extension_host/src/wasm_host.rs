@@ -4,8 +4,9 @@ use crate::ExtensionManifest;
 use anyhow::{anyhow, bail, Context as _, Result};
 use async_trait::async_trait;
 use extension::{
-    CodeLabel, Command, Completion, ExtensionHostProxy, KeyValueStoreDelegate, ProjectDelegate,
-    SlashCommand, SlashCommandArgumentCompletion, SlashCommandOutput, Symbol, WorktreeDelegate,
+    CodeLabel, Command, Completion, ExtensionCapabilities, ExtensionHostProxy,
+    KeyValueStoreDelegate, LanguageServerDelegate, ProjectDelegate, SlashCommand,
+    SlashCommandArgumentCompletion, SlashCommandOutput, Symbol, WorktreeDelegate,
 };
 use fs::{normalize_path, Fs};
 use futures::future::LocalBoxFuture;
@@ -19,22 +20,37 @@ use futures::{
 };
 use gpui::{AppContext, AsyncAppContext, BackgroundExecutor, Task};
 use http_client::HttpClient;
-use language::LanguageName;
+use language::{LanguageName, LanguageServerCommandContext, LanguageServerCommandOutput};
 use lsp::LanguageServerName;
 use node_runtime::NodeRuntime;
 use release_channel::ReleaseChannel;
 use semantic_version::SemanticVersion;
 use std::{
+    fmt,
     path::{Path, PathBuf},
     sync::{Arc, OnceLock},
 };
+use url::Url;
+use util::ResultExt as _;
 use wasmtime::{
     component::{Component, ResourceTable},
-    Engine, Store,
+    Engine, ResourceLimiter, Store,
 };
 use wasmtime_wasi::{self as wasi, WasiView};
 use wit::Extension;
 
+/// The amount of fuel that a single call into an extension may consume.
+///
+/// Each WebAssembly instruction consumes roughly one unit of fuel.
+const EXTENSION_FUEL_PER_CALL: u64 = 10_000_000_000;
+
+/// How much fuel an extension consumes between yielding to the executor, so that
+/// long-running calls don't starve other tasks.
+const EXTENSION_FUEL_YIELD_INTERVAL: u64 = 10_000_000;
+
+/// The maximum size of an extension's linear memory.
+const EXTENSION_MEMORY_LIMIT: usize = 512 * 1024 * 1024;
+
 pub struct WasmHost {
     engine: Engine,
     release_channel: ReleaseChannel,
@@ -74,7 +90,7 @@ impl extension::Extension for WasmExtension {
     ) -> Result<Command> {
         self.call(|extension, store| {
             async move {
-                let resource = store.data_mut().table().push(worktree)?;
+                let resource = store.data_mut().table().push(worktree.clone())?;
                 let command = extension
                     .call_language_server_command(
                         store,
@@ -84,6 +100,10 @@ impl extension::Extension for WasmExtension {
                     )
                     .await?
                     .map_err(|err| anyhow!("{err}"))?;
+                store
+                    .data_mut()
+                    .ensure_binary_allowed(&command.command, Some(&worktree))
+                    .await?;
 
                 Ok(command.into())
             }
@@ -140,6 +160,87 @@ impl extension::Extension for WasmExtension {
         .await
     }
 
+    async fn handle_language_server_request(
+        &self,
+        language_server_id: LanguageServerName,
+        method: String,
+        params: String,
+        worktree: Arc<dyn WorktreeDelegate>,
+    ) -> Result<String> {
+        self.call(|extension, store| {
+            async move {
+                let resource = store.data_mut().table().push(worktree)?;
+                let result = extension
+                    .call_handle_language_server_request(
+                        store,
+                        &language_server_id,
+                        &method,
+                        &params,
+                        resource,
+                    )
+                    .await?
+                    .map_err(|err| anyhow!("{err}"))?;
+                anyhow::Ok(result)
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn handle_language_server_notification(
+        &self,
+        language_server_id: LanguageServerName,
+        method: String,
+        params: String,
+        worktree: Arc<dyn WorktreeDelegate>,
+    ) -> Result<()> {
+        self.call(|extension, store| {
+            async move {
+                let resource = store.data_mut().table().push(worktree)?;
+                extension
+                    .call_handle_language_server_notification(
+                        store,
+                        &language_server_id,
+                        &method,
+                        &params,
+                        resource,
+                    )
+                    .await?
+                    .map_err(|err| anyhow!("{err}"))?;
+                anyhow::Ok(())
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn run_language_server_command(
+        &self,
+        language_server_id: LanguageServerName,
+        command: String,
+        context: LanguageServerCommandContext,
+        language_server: Arc<dyn LanguageServerDelegate>,
+    ) -> Result<LanguageServerCommandOutput> {
+        self.call(|extension, store| {
+            async move {
+                let resource = store.data_mut().table().push(language_server)?;
+                let output = extension
+                    .call_run_language_server_command(
+                        store,
+                        &language_server_id,
+                        &command,
+                        &context.into(),
+                        resource,
+                    )
+                    .await?
+                    .map_err(|err| anyhow!("{err}"))?;
+                output.try_into()
+            }
+            .boxed()
+        })
+        .await
+    }
+
     async fn labels_for_completions(
         &self,
         language_server_id: LanguageServerName,
@@ -249,6 +350,10 @@ impl extension::Extension for WasmExtension {
                     .call_context_server_command(store, context_server_id.clone(), project_resource)
                     .await?
                     .map_err(|err| anyhow!("{err}"))?;
+                store
+                    .data_mut()
+                    .ensure_command_allowed(&command.command, None)
+                    .await?;
                 anyhow::Ok(command.into())
             }
             .boxed()
@@ -296,6 +401,32 @@ impl extension::Extension for WasmExtension {
         })
         .await
     }
+
+    async fn task_templates(
+        &self,
+        provider: Arc<str>,
+        worktree: Arc<dyn WorktreeDelegate>,
+    ) -> Result<Vec<extension::TaskTemplate>> {
+        self.call(|extension, store| {
+            async move {
+                let resource = store.data_mut().table().push(worktree.clone())?;
+                let templates = extension
+                    .call_task_templates(store, provider.as_ref(), resource)
+                    .await?
+                    .map_err(|err| anyhow!("{err}"))?;
+                for template in &templates {
+                    store
+                        .data_mut()
+                        .ensure_command_allowed(&template.command, Some(&worktree))
+                        .await?;
+                }
+
+                anyhow::Ok(templates)
+            }
+            .boxed()
+        })
+        .await
+    }
 }
 
 pub struct WasmState {
@@ -303,6 +434,65 @@ pub struct WasmState {
     pub table: ResourceTable,
     ctx: wasi::WasiCtx,
     pub host: Arc<WasmHost>,
+    /// The capabilities that the user has granted to the extension.
+    capabilities: ExtensionCapabilities,
+    limiter: ExtensionResourceLimiter,
+}
+
+/// A resource limit that an extension exceeded, causing it to be disabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceLimitExceeded {
+    /// The extension ran for too long in a single call.
+    Fuel,
+    /// The extension tried to use too much memory.
+    Memory,
+}
+
+impl fmt::Display for ResourceLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fuel => write!(
+                f,
+                "exceeded its execution limit of {EXTENSION_FUEL_PER_CALL} instructions per call"
+            ),
+            Self::Memory => write!(
+                f,
+                "exceeded its memory limit of {} MiB",
+                EXTENSION_MEMORY_LIMIT / 1024 / 1024
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ResourceLimitExceeded {}
+
+struct ExtensionResourceLimiter {
+    memory_limit: usize,
+    memory_limit_exceeded: bool,
+}
+
+impl ResourceLimiter for ExtensionResourceLimiter {
+    fn memory_growing(
+        &mut self,
+        _current: usize,
+        desired: usize,
+        _maximum: Option<usize>,
+    ) -> Result<bool> {
+        if desired > self.memory_limit {
+            self.memory_limit_exceeded = true;
+            return Err(ResourceLimitExceeded::Memory.into());
+        }
+        Ok(true)
+    }
+
+    fn table_growing(
+        &mut self,
+        _current: usize,
+        _desired: usize,
+        _maximum: Option<usize>,
+    ) -> Result<bool> {
+        Ok(true)
+    }
 }
 
 type MainThreadCall =
@@ -320,11 +510,23 @@ fn wasm_engine() -> wasmtime::Engine {
             let mut config = wasmtime::Config::new();
             config.wasm_component_model(true);
             config.async_support(true);
+            config.consume_fuel(true);
             wasmtime::Engine::new(&config).unwrap()
         })
         .clone()
 }
 
+/// Returns the resource limit that the extension in the given store exceeded, if any.
+fn resource_limit_exceeded(store: &Store<WasmState>) -> Option<ResourceLimitExceeded> {
+    if store.data().limiter.memory_limit_exceeded {
+        Some(ResourceLimitExceeded::Memory)
+    } else if store.get_fuel().map_or(false, |fuel| fuel == 0) {
+        Some(ResourceLimitExceeded::Fuel)
+    } else {
+        None
+    }
+}
+
 impl WasmHost {
     pub fn new(
         fs: Arc<dyn Fs>,
@@ -353,10 +555,12 @@ impl WasmHost {
         })
     }
 
+    /// Loads an extension, which may only use the given `capabilities`.
     pub fn load_extension(
         self: &Arc<Self>,
         wasm_bytes: Vec<u8>,
         manifest: &Arc<ExtensionManifest>,
+        capabilities: ExtensionCapabilities,
         executor: BackgroundExecutor,
     ) -> Task<Result<WasmExtension>> {
         let this = self.clone();
@@ -374,8 +578,16 @@ impl WasmHost {
                     manifest: manifest.clone(),
                     table: ResourceTable::new(),
                     host: this.clone(),
+                    capabilities,
+                    limiter: ExtensionResourceLimiter {
+                        memory_limit: EXTENSION_MEMORY_LIMIT,
+                        memory_limit_exceeded: false,
+                    },
                 },
             );
+            store.limiter(|state| &mut state.limiter);
+            store.fuel_async_yield_interval(Some(EXTENSION_FUEL_YIELD_INTERVAL))?;
+            store.set_fuel(EXTENSION_FUEL_PER_CALL)?;
 
             let mut extension = Extension::instantiate_async(
                 &mut store,
@@ -385,16 +597,29 @@ impl WasmHost {
             )
             .await?;
 
-            extension
-                .call_init_extension(&mut store)
-                .await
-                .context("failed to initialize wasm extension")?;
+            let result = extension.call_init_extension(&mut store).await;
+            if let Some(exceeded) = resource_limit_exceeded(&store) {
+                return Err(anyhow!(exceeded)).context("failed to initialize wasm extension");
+            }
+            result.context("failed to initialize wasm extension")?;
 
             let (tx, mut rx) = mpsc::unbounded::<ExtensionCall>();
             executor
-                .spawn(async move {
-                    while let Some(call) = rx.next().await {
-                        (call)(&mut extension, &mut store).await;
+                .spawn({
+                    let this = this.clone();
+                    let extension_id = manifest.id.clone();
+                    async move {
+                        while let Some(call) = rx.next().await {
+                            store.set_fuel(EXTENSION_FUEL_PER_CALL).log_err();
+                            (call)(&mut extension, &mut store).await;
+
+                            // Once an extension exceeds its resource limits, it is disabled and
+                            // no longer receives calls.
+                            if let Some(exceeded) = resource_limit_exceeded(&store) {
+                                this.disable_extension(extension_id, exceeded);
+                                break;
+                            }
+                        }
                     }
                 })
                 .detach();
@@ -432,6 +657,31 @@ impl WasmHost {
             .build())
     }
 
+    /// Disables an extension that exceeded its resource limits.
+    fn disable_extension(&self, extension_id: Arc<str>, exceeded: ResourceLimitExceeded) {
+        log::error!("disabling extension {extension_id}: it {exceeded}");
+        self.main_thread_message_tx
+            .unbounded_send(Box::new(move |cx| {
+                async move {
+                    cx.update(|cx| {
+                        let Some(store) = crate::ExtensionStore::try_global(cx) else {
+                            return;
+                        };
+                        store.update(cx, |store, cx| {
+                            store.disable_extension(
+                                extension_id,
+                                format!("Disabled because it {exceeded}.").into(),
+                                cx,
+                            )
+                        });
+                    })
+                    .ok();
+                }
+                .boxed_local()
+            }))
+            .ok();
+    }
+
     pub fn writeable_path_from_extension(&self, id: &Arc<str>, path: &Path) -> Result<PathBuf> {
         let extension_work_dir = self.work_dir.join(id.as_ref());
         let path = normalize_path(&extension_work_dir.join(path));
@@ -490,6 +740,7 @@ impl WasmExtension {
     pub async fn load(
         extension_dir: PathBuf,
         manifest: &Arc<ExtensionManifest>,
+        capabilities: ExtensionCapabilities,
         wasm_host: Arc<WasmHost>,
         cx: &AsyncAppContext,
     ) -> Result<Self> {
@@ -507,30 +758,45 @@ impl WasmExtension {
             .context("failed to read wasm")?;
 
         wasm_host
-            .load_extension(wasm_bytes, manifest, cx.background_executor().clone())
+            .load_extension(
+                wasm_bytes,
+                manifest,
+                capabilities,
+                cx.background_executor().clone(),
+            )
             .await
             .with_context(|| format!("failed to load wasm extension {}", manifest.id))
     }
 
-    pub async fn call<T, Fn>(&self, f: Fn) -> T
+    pub async fn call<T, Fn>(&self, f: Fn) -> Result<T>
     where
         T: 'static + Send,
         Fn: 'static
             + Send
-            + for<'a> FnOnce(&'a mut Extension, &'a mut Store<WasmState>) -> BoxFuture<'a, T>,
+            + for<'a> FnOnce(&'a mut Extension, &'a mut Store<WasmState>) -> BoxFuture<'a, Result<T>>,
     {
         let (return_tx, return_rx) = oneshot::channel();
         self.tx
             .clone()
             .unbounded_send(Box::new(move |extension, store| {
                 async {
-                    let result = f(extension, store).await;
+                    let mut result = f(extension, store).await;
+                    if let Some(exceeded) = resource_limit_exceeded(store) {
+                        result = Err(anyhow!(exceeded));
+                    }
                     return_tx.send(result).ok();
                 }
                 .boxed()
             }))
-            .expect("wasm extension channel should not be closed yet");
-        return_rx.await.expect("wasm extension channel")
+            .map_err(|_| self.disabled_error())?;
+        return_rx.await.map_err(|_| self.disabled_error())?
+    }
+
+    fn disabled_error(&self) -> anyhow::Error {
+        anyhow!(
+            "extension {} was disabled after exceeding its resource limits",
+            self.manifest.id
+        )
     }
 }
 
@@ -558,6 +824,104 @@ impl WasmState {
     fn work_dir(&self) -> PathBuf {
         self.host.work_dir.join(self.manifest.id.as_ref())
     }
+
+    /// Returns an error unless the extension has been granted access to the host of the given URL.
+    pub(crate) fn ensure_url_allowed(&self, url: &str) -> Result<()> {
+        let url = Url::parse(url).with_context(|| format!("invalid URL {url:?}"))?;
+        let host = url
+            .host_str()
+            .ok_or_else(|| anyhow!("URL {url} has no host"))?;
+        self.ensure_host_allowed(host)
+    }
+
+    /// Returns an error unless the extension has been granted access to the given host.
+    pub(crate) fn ensure_host_allowed(&self, host: &str) -> Result<()> {
+        if self.capabilities.allows_host(host) {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "extension {} has not been granted network access to {host}",
+                self.manifest.id
+            ))
+        }
+    }
+
+    /// Returns an error unless the extension has been granted permission to run the given
+    /// command, which is looked up on the user's `PATH` if it's just a name.
+    pub(crate) async fn ensure_command_allowed(
+        &mut self,
+        command: &str,
+        worktree: Option<&Arc<dyn WorktreeDelegate>>,
+    ) -> Result<()> {
+        let allowed = if Path::new(command).components().count() == 1 {
+            self.capabilities.allows_command(command)
+        } else {
+            self.allows_binary(Path::new(command), worktree).await
+        };
+        if allowed {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "extension {} has not been granted permission to run {command}",
+                self.manifest.id
+            ))
+        }
+    }
+
+    /// Returns an error unless the extension has been granted permission to run the binary at
+    /// the given path, which is relative to the extension's working directory.
+    pub(crate) async fn ensure_binary_allowed(
+        &mut self,
+        path: &str,
+        worktree: Option<&Arc<dyn WorktreeDelegate>>,
+    ) -> Result<()> {
+        let resolved_path = normalize_path(&self.work_dir().join(path));
+        if self.allows_binary(&resolved_path, worktree).await {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "extension {} has not been granted permission to run {}",
+                self.manifest.id,
+                resolved_path.display()
+            ))
+        }
+    }
+
+    /// Returns whether the binary at the given path is the one that a granted command resolves
+    /// to, either on the user's `PATH` or, for `node`, Zed's own Node.js.
+    async fn allows_binary(
+        &mut self,
+        path: &Path,
+        worktree: Option<&Arc<dyn WorktreeDelegate>>,
+    ) -> bool {
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            return false;
+        };
+        let mut resolved_path = None;
+        if self.capabilities.allows_command(name) {
+            resolved_path = match worktree {
+                Some(worktree) => worktree.which(name.to_string()).await.map(PathBuf::from),
+                None => which::which(name).ok(),
+            };
+            if name == "node" && resolved_path.as_deref() != Some(path) {
+                resolved_path = self.host.node_runtime.binary_path().await.ok();
+            }
+        }
+        self.capabilities
+            .allows_binary(path, resolved_path.as_deref())
+    }
+
+    /// Returns an error unless the extension has been granted access to files in worktrees.
+    pub(crate) fn ensure_worktree_read_allowed(&self) -> Result<()> {
+        if self.capabilities.allows_worktree_read() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "extension {} has not been granted access to files in the project",
+                self.manifest.id
+            ))
+        }
+    }
 }
 
 impl wasi::WasiView for WasmState {
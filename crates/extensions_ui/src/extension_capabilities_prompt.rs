@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use extension_host::{ExtensionCapabilities, ExtensionStore};
+use gpui::VisualContext;
+use ui::{SharedString, ViewContext};
+use workspace::{
+    notifications::{simple_message_notification, NotificationId},
+    Workspace,
+};
+
+/// Asks the user to review the capabilities requested by every loaded extension that is
+/// still waiting for a review.
+pub(crate) fn prompt_for_pending_capabilities(
+    workspace: &mut Workspace,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let pending = ExtensionStore::global(cx)
+        .read(cx)
+        .extensions_pending_capability_review();
+    for extension_id in pending {
+        prompt_for_capabilities_if_pending(workspace, extension_id, cx);
+    }
+}
+
+pub(crate) fn prompt_for_capabilities_if_pending(
+    workspace: &mut Workspace,
+    extension_id: Arc<str>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(requested) = ExtensionStore::global(cx)
+        .read(cx)
+        .capabilities_pending_review(&extension_id)
+    else {
+        return;
+    };
+    prompt_for_capabilities(workspace, extension_id, requested, cx);
+}
+
+/// Asks the user whether to grant the given extension the capabilities it requests.
+pub(crate) fn prompt_for_capabilities(
+    workspace: &mut Workspace,
+    extension_id: Arc<str>,
+    requested: ExtensionCapabilities,
+    cx: &mut ViewContext<Workspace>,
+) {
+    if requested.is_empty() {
+        return;
+    }
+
+    let mut message = format!("The '{extension_id}' extension would like to:");
+    for description in requested.descriptions() {
+        message.push_str("\n• ");
+        message.push_str(&description);
+    }
+
+    struct ExtensionCapabilitiesNotification;
+
+    let notification_id = NotificationId::composite::<ExtensionCapabilitiesNotification>(
+        SharedString::from(extension_id.clone()),
+    );
+
+    workspace.show_notification(notification_id, cx, move |cx| {
+        cx.new_view(move |_cx| {
+            simple_message_notification::MessageNotification::new(message)
+                .with_click_message("Allow")
+                .on_click({
+                    let extension_id = extension_id.clone();
+                    move |cx| {
+                        let extension_id = extension_id.clone();
+                        let requested = requested.clone();
+                        ExtensionStore::global(cx).update(cx, move |store, cx| {
+                            store.grant_capabilities(extension_id, requested, cx);
+                        });
+                    }
+                })
+                .with_secondary_click_message("Deny")
+                .on_secondary_click(move |cx| {
+                    let extension_id = extension_id.clone();
+                    ExtensionStore::global(cx).update(cx, move |store, cx| {
+                        // Record the review without granting anything new, so that the
+                        // user isn't asked again until the extension requests more.
+                        let granted = store.effective_capabilities(&extension_id);
+                        store.grant_capabilities(extension_id, granted, cx);
+                    });
+                })
+        })
+    });
+}
+
+/// Tells the user that an extension was disabled for exceeding its resource limits, and
+/// offers to re-enable it.
+pub(crate) fn notify_extension_disabled(
+    workspace: &mut Workspace,
+    extension_id: Arc<str>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(reason) = ExtensionStore::global(cx)
+        .read(cx)
+        .disabled_reason(&extension_id)
+        .cloned()
+    else {
+        return;
+    };
+
+    struct ExtensionDisabledNotification;
+
+    let notification_id = NotificationId::composite::<ExtensionDisabledNotification>(
+        SharedString::from(extension_id.clone()),
+    );
+
+    workspace.show_notification(notification_id, cx, move |cx| {
+        cx.new_view(move |_cx| {
+            simple_message_notification::MessageNotification::new(format!(
+                "The '{extension_id}' extension was disabled. {reason}"
+            ))
+            .with_click_message("Re-enable")
+            .on_click(move |cx| {
+                let extension_id = extension_id.clone();
+                ExtensionStore::global(cx).update(cx, move |store, cx| {
+                    store.enable_extension(extension_id, cx);
+                });
+            })
+        })
+    });
+}
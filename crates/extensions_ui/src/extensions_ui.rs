@@ -1,4 +1,5 @@
 mod components;
+mod extension_capabilities_prompt;
 mod extension_suggest;
 mod extension_version_selector;
 
@@ -101,6 +102,28 @@ pub fn init(cx: &mut AppContext) {
             }
         })
         .detach();
+
+        if let Some(extension_store) = ExtensionStore::try_global(cx) {
+            cx.subscribe(&extension_store, |workspace, _, event, cx| match event {
+                extension_host::Event::ExtensionCapabilitiesRequested(extension_id) => {
+                    extension_capabilities_prompt::prompt_for_capabilities_if_pending(
+                        workspace,
+                        extension_id.clone(),
+                        cx,
+                    )
+                }
+                extension_host::Event::ExtensionDisabled(extension_id) => {
+                    extension_capabilities_prompt::notify_extension_disabled(
+                        workspace,
+                        extension_id.clone(),
+                        cx,
+                    )
+                }
+                _ => {}
+            })
+            .detach();
+            extension_capabilities_prompt::prompt_for_pending_capabilities(workspace, cx);
+        }
     })
     .detach();
 }
@@ -260,6 +283,24 @@ impl ExtensionsPage {
                     );
                 })
                 .ok();
+            return;
+        }
+
+        let icon_themes = extension_store
+            .extension_icon_themes(extension_id)
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>();
+        if !icon_themes.is_empty() {
+            workspace
+                .update(cx, |_workspace, cx| {
+                    cx.dispatch_action(
+                        zed_actions::icon_theme_selector::Toggle {
+                            themes_filter: Some(icon_themes),
+                        }
+                        .boxed_clone(),
+                    );
+                })
+                .ok();
         }
     }
 
@@ -496,6 +537,7 @@ impl ExtensionsPage {
                         .tooltip(move |cx| Tooltip::text(repository_url.clone(), cx))
                     })),
             )
+            .children(Self::render_disabled_reason(&extension.id, cx))
     }
 
     fn render_remote_extension(
@@ -629,6 +671,45 @@ impl ExtensionsPage {
                             ),
                     ),
             )
+            .children(Self::render_disabled_reason(&extension.id, cx))
+    }
+
+    fn render_disabled_reason(
+        extension_id: &Arc<str>,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<impl IntoElement> {
+        let reason = ExtensionStore::global(cx)
+            .read(cx)
+            .disabled_reason(extension_id)?
+            .clone();
+
+        Some(
+            h_flex()
+                .gap_2()
+                .justify_between()
+                .child(
+                    div().overflow_x_hidden().text_ellipsis().child(
+                        Label::new(reason)
+                            .size(LabelSize::Small)
+                            .color(Color::Error),
+                    ),
+                )
+                .child(
+                    Button::new(
+                        SharedString::from(format!("enable-{}", extension_id)),
+                        "Re-enable",
+                    )
+                    .color(Color::Accent)
+                    .on_click({
+                        let extension_id = extension_id.clone();
+                        move |_, cx| {
+                            ExtensionStore::global(cx).update(cx, |store, cx| {
+                                store.enable_extension(extension_id.clone(), cx)
+                            });
+                        }
+                    }),
+                ),
+        )
     }
 
     fn render_remote_extension_context_menu(
@@ -636,8 +717,16 @@ impl ExtensionsPage {
         extension_id: Arc<str>,
         cx: &mut WindowContext,
     ) -> View<ContextMenu> {
+        let requests_capabilities = ExtensionStore::global(cx)
+            .read(cx)
+            .installed_extensions()
+            .get(&extension_id)
+            .map_or(false, |extension| {
+                !extension.manifest.requested_capabilities().is_empty()
+            });
+
         let context_menu = ContextMenu::build(cx, |context_menu, cx| {
-            context_menu
+            let context_menu = context_menu
                 .entry(
                     "Install Another Version...",
                     None,
@@ -651,12 +740,51 @@ impl ExtensionsPage {
                     move |cx| {
                         cx.write_to_clipboard(ClipboardItem::new_string(extension_id.to_string()));
                     }
-                })
+                });
+
+            if requests_capabilities {
+                context_menu.entry(
+                    "Review Permissions...",
+                    None,
+                    cx.handler_for(this, {
+                        let extension_id = extension_id.clone();
+                        move |this, cx| this.review_extension_capabilities(extension_id.clone(), cx)
+                    }),
+                )
+            } else {
+                context_menu
+            }
         });
 
         context_menu
     }
 
+    fn review_extension_capabilities(
+        &mut self,
+        extension_id: Arc<str>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(requested) = ExtensionStore::global(cx)
+            .read(cx)
+            .installed_extensions()
+            .get(&extension_id)
+            .map(|extension| extension.manifest.requested_capabilities())
+        else {
+            return;
+        };
+
+        self.workspace
+            .update(cx, |workspace, cx| {
+                extension_capabilities_prompt::prompt_for_capabilities(
+                    workspace,
+                    extension_id,
+                    requested,
+                    cx,
+                )
+            })
+            .ok();
+    }
+
     fn show_extension_version_list(&mut self, extension_id: Arc<str>, cx: &mut ViewContext<Self>) {
         let Some(workspace) = self.workspace.upgrade() else {
             return;
@@ -4,7 +4,9 @@ use collections::HashMap;
 
 use gpui::{AppContext, AssetSource, Global, SharedString};
 use serde_derive::Deserialize;
-use util::{maybe, paths::PathExt};
+use settings::Settings;
+use theme::ThemeSettings;
+use util::paths::PathExt;
 
 #[derive(Deserialize, Debug)]
 struct TypeConfig {
@@ -49,22 +51,29 @@ impl FileIcons {
     }
 
     pub fn get_icon(path: &Path, cx: &AppContext) -> Option<SharedString> {
-        let this = cx.try_global::<Self>()?;
+        let icon_theme = &ThemeSettings::get_global(cx).active_icon_theme;
+        let this = cx.try_global::<Self>();
+        let stem_or_suffix = path.icon_stem_or_suffix();
+
+        // An icon for this kind of file is preferred to a default icon, even if it's a built-in
+        // one and the default comes from the icon theme.
+        stem_or_suffix
+            .and_then(|stem_or_suffix| icon_theme.file_icon(stem_or_suffix))
+            .or_else(|| this?.get_icon_for_stem_or_suffix(stem_or_suffix?))
+            .or_else(|| icon_theme.icon_for_key("default"))
+            .or_else(|| this?.get_type_icon("default"))
+    }
 
+    fn get_icon_for_stem_or_suffix(&self, stem_or_suffix: &str) -> Option<SharedString> {
         // FIXME: Associate a type with the languages and have the file's language
         //        override these associations
-        maybe!({
-            let suffix = path.icon_stem_or_suffix()?;
-
-            if let Some(type_str) = this.stems.get(suffix) {
-                return this.get_type_icon(type_str);
-            }
-
-            this.suffixes
-                .get(suffix)
-                .and_then(|type_str| this.get_type_icon(type_str))
-        })
-        .or_else(|| this.get_type_icon("default"))
+        if let Some(type_str) = self.stems.get(stem_or_suffix) {
+            return self.get_type_icon(type_str);
+        }
+
+        self.suffixes
+            .get(stem_or_suffix)
+            .and_then(|type_str| self.get_type_icon(type_str))
     }
 
     pub fn get_type_icon(&self, typ: &str) -> Option<SharedString> {
@@ -74,6 +83,11 @@ impl FileIcons {
     }
 
     pub fn get_folder_icon(expanded: bool, cx: &AppContext) -> Option<SharedString> {
+        let icon_theme = &ThemeSettings::get_global(cx).active_icon_theme;
+        if let Some(icon) = icon_theme.directory_icon(expanded) {
+            return Some(icon);
+        }
+
         let this = cx.try_global::<Self>()?;
 
         let key = if expanded {
@@ -86,6 +100,11 @@ impl FileIcons {
     }
 
     pub fn get_chevron_icon(expanded: bool, cx: &AppContext) -> Option<SharedString> {
+        let icon_theme = &ThemeSettings::get_global(cx).active_icon_theme;
+        if let Some(icon) = icon_theme.chevron_icon(expanded) {
+            return Some(icon);
+        }
+
         let this = cx.try_global::<Self>()?;
 
         let key = if expanded {
@@ -97,3 +116,69 @@ impl FileIcons {
         this.get_type_icon(key)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use settings::SettingsStore;
+    use std::sync::Arc;
+    use theme::{Appearance, ChevronIcons, DirectoryIcons, IconDefinition, IconTheme};
+
+    #[gpui::test]
+    fn test_get_icon_with_partial_icon_theme(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+
+            let type_config = |icon: &str| TypeConfig { icon: icon.into() };
+            cx.set_global(FileIcons {
+                stems: HashMap::from_iter([("Makefile".into(), "make".into())]),
+                suffixes: HashMap::from_iter([
+                    ("rs".into(), "rust".into()),
+                    ("toml".into(), "toml".into()),
+                ]),
+                types: HashMap::from_iter([
+                    ("make".into(), type_config("icons/make.svg")),
+                    ("rust".into(), type_config("icons/rust.svg")),
+                    ("toml".into(), type_config("icons/toml.svg")),
+                    ("default".into(), type_config("icons/file.svg")),
+                ]),
+            });
+
+            // With the default icon theme, the built-in icons are used.
+            assert_eq!(icon("src/main.rs", cx), Some("icons/rust.svg".into()));
+            assert_eq!(icon("Makefile", cx), Some("icons/make.svg".into()));
+            assert_eq!(icon("notes.txt", cx), Some("icons/file.svg".into()));
+
+            // The icon theme only has icons for Rust files and a default icon.
+            let icon_definition = |path: &str| IconDefinition { path: path.into() };
+            let mut theme_settings = ThemeSettings::get_global(cx).clone();
+            theme_settings.active_icon_theme = Arc::new(IconTheme {
+                name: "Partial".into(),
+                appearance: Appearance::Dark,
+                directory_icons: DirectoryIcons::default(),
+                chevron_icons: ChevronIcons::default(),
+                file_stems: HashMap::default(),
+                file_suffixes: HashMap::from_iter([("rs".into(), "rust".into())]),
+                file_icons: HashMap::from_iter([
+                    ("rust".into(), icon_definition("/theme/rust.svg")),
+                    ("default".into(), icon_definition("/theme/file.svg")),
+                ]),
+            });
+            ThemeSettings::override_global(theme_settings, cx);
+
+            // The theme's icon for a kind of file is used first, then the built-in one, and
+            // only then the theme's default icon.
+            assert_eq!(icon("src/main.rs", cx), Some("/theme/rust.svg".into()));
+            assert_eq!(icon("Cargo.toml", cx), Some("icons/toml.svg".into()));
+            assert_eq!(icon("Makefile", cx), Some("icons/make.svg".into()));
+            assert_eq!(icon("notes.txt", cx), Some("/theme/file.svg".into()));
+        });
+
+        fn icon(path: &str, cx: &AppContext) -> Option<SharedString> {
+            FileIcons::get_icon(Path::new(path), cx)
+        }
+    }
+}
@@ -4,8 +4,10 @@ use cursor_position::LineIndicatorFormat;
 use editor::{scroll::Autoscroll, Editor};
 use gpui::{
     div, prelude::*, AnyWindowHandle, AppContext, DismissEvent, EventEmitter, FocusHandle,
-    FocusableView, Render, SharedString, Styled, Subscription, View, ViewContext, VisualContext,
+    FocusableView, Model, Render, SharedString, Styled, Subscription, View, ViewContext,
+    VisualContext,
 };
+use language::Buffer;
 use settings::Settings;
 use text::{Bias, Point};
 use theme::ActiveTheme;
@@ -23,6 +25,7 @@ pub struct GoToLine {
     active_editor: View<Editor>,
     current_text: SharedString,
     prev_scroll_position: Option<gpui::Point<f32>>,
+    prev_large_file_page: Option<usize>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -59,7 +62,18 @@ impl GoToLine {
         let cursor =
             active_editor.update(cx, |editor, cx| editor.selections.last::<Point>(cx).head());
 
-        let line = cursor.row + 1;
+        let editor = active_editor.read(cx);
+        let buffer = editor.buffer().read(cx);
+        let large_file = buffer.as_singleton().and_then(|buffer| {
+            let buffer = buffer.read(cx);
+            let (file, page) = buffer.large_file_page()?;
+            Some((buffer.large_file_start_row(), file.row_count(), page))
+        });
+        let last_line = match large_file {
+            Some((_, row_count, _)) => row_count - 1,
+            None => buffer.snapshot(cx).max_point().row as u64,
+        };
+        let line = large_file.map_or(0, |(start_row, _, _)| start_row) + cursor.row as u64 + 1;
         let column = cursor.column + 1;
 
         let line_editor = cx.new_view(|cx| {
@@ -69,8 +83,6 @@ impl GoToLine {
         });
         let line_editor_change = cx.subscribe(&line_editor, Self::on_line_editor_event);
 
-        let editor = active_editor.read(cx);
-        let last_line = editor.buffer().read(cx).snapshot(cx).max_point().row;
         let scroll_position = active_editor.update(cx, |editor, cx| editor.scroll_position(cx));
 
         let current_text = format!("{} of {} (column {})", line, last_line + 1, column);
@@ -80,6 +92,7 @@ impl GoToLine {
             active_editor,
             current_text: current_text.into(),
             prev_scroll_position: Some(scroll_position),
+            prev_large_file_page: large_file.map(|(_, _, page)| page),
             _subscriptions: vec![line_editor_change, cx.on_release(Self::release)],
         }
     }
@@ -88,6 +101,11 @@ impl GoToLine {
         window
             .update(cx, |_, cx| {
                 let scroll_position = self.prev_scroll_position.take();
+                if let Some(page) = self.prev_large_file_page.take() {
+                    if let Some(buffer) = self.active_buffer(cx) {
+                        buffer.update(cx, |buffer, cx| buffer.set_large_file_page(page, cx));
+                    }
+                }
                 self.active_editor.update(cx, |editor, cx| {
                     editor.clear_row_highlights::<GoToLineRowHighlights>();
                     if let Some(scroll_position) = scroll_position {
@@ -114,6 +132,7 @@ impl GoToLine {
 
     fn highlight_current_line(&mut self, cx: &mut ViewContext<Self>) {
         if let Some(point) = self.point_from_query(cx) {
+            let point = self.point_in_large_file_page(point, cx);
             self.active_editor.update(cx, |active_editor, cx| {
                 let snapshot = active_editor.snapshot(cx).display_snapshot;
                 let start = snapshot.buffer_snapshot.clip_point(point, Bias::Left);
@@ -141,6 +160,27 @@ impl GoToLine {
         ))
     }
 
+    fn active_buffer(&self, cx: &AppContext) -> Option<Model<Buffer>> {
+        self.active_editor.read(cx).buffer().read(cx).as_singleton()
+    }
+
+    /// Large files show one page at a time, so a line on another page is shown by changing
+    /// pages first. Returns the point within the page.
+    fn point_in_large_file_page(&self, point: Point, cx: &mut ViewContext<Self>) -> Point {
+        let Some(buffer) = self.active_buffer(cx) else {
+            return point;
+        };
+        buffer.update(cx, |buffer, cx| {
+            let Some((file, _)) = buffer.large_file_page() else {
+                return point;
+            };
+            let page = file.page_for_row(point.row as u64);
+            buffer.set_large_file_page(page, cx);
+            let start_row = buffer.large_file_start_row() as u32;
+            Point::new(point.row - start_row, point.column)
+        })
+    }
+
     fn line_column_from_query(&self, cx: &ViewContext<Self>) -> (Option<u32>, Option<u32>) {
         let input = self.line_editor.read(cx).text(cx);
         let mut components = input
@@ -158,6 +198,7 @@ impl GoToLine {
 
     fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
         if let Some(point) = self.point_from_query(cx) {
+            let point = self.point_in_large_file_page(point, cx);
             self.active_editor.update(cx, |editor, cx| {
                 let snapshot = editor.snapshot(cx).display_snapshot;
                 let point = snapshot.buffer_snapshot.clip_point(point, Bias::Left);
@@ -168,6 +209,7 @@ impl GoToLine {
                 cx.notify();
             });
             self.prev_scroll_position.take();
+            self.prev_large_file_page.take();
         }
 
         cx.emit(DismissEvent);
@@ -218,8 +260,9 @@ mod tests {
     use editor::actions::SelectAll;
     use gpui::{TestAppContext, VisualTestContext};
     use indoc::indoc;
-    use project::{FakeFs, Project};
+    use project::{project_settings::ProjectSettings, FakeFs, Project};
     use serde_json::json;
+    use settings::SettingsStore;
     use std::{sync::Arc, time::Duration};
     use workspace::{AppState, Workspace};
 
@@ -409,6 +452,82 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_go_to_line_in_large_file(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<ProjectSettings>(cx, |settings| {
+                    settings.large_file_threshold = Some(16);
+                });
+            });
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "a.log": "line 1\nline 2\nline 3\nline 4\nline 5\n"
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let worktree_id = workspace.update(cx, |workspace, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            })
+        });
+        let editor = workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path((worktree_id, "a.log"), None, true, cx)
+            })
+            .await
+            .unwrap()
+            .downcast::<Editor>()
+            .unwrap();
+        editor.update(cx, |editor, cx| {
+            let buffer = editor.buffer().read(cx).as_singleton().unwrap();
+            assert!(buffer.read(cx).is_large_file());
+            assert!(editor.read_only(cx));
+        });
+
+        // The file is split into pages of at most 16 bytes, so line 4 is on the second page.
+        let go_to_line_view = open_go_to_line_view(&workspace, cx);
+        cx.simulate_input("4");
+        assert_eq!(highlighted_display_rows(&editor, cx), vec![1]);
+        cx.dispatch_action(menu::Confirm);
+        drop(go_to_line_view);
+        editor.update(cx, |_, _| {});
+        assert_single_caret_at_row(&editor, 1, cx);
+        editor.update(cx, |editor, cx| {
+            let buffer = editor.buffer().read(cx).as_singleton().unwrap();
+            assert_eq!(buffer.read(cx).text(), "line 3\nline 4\n");
+            assert_eq!(buffer.read(cx).large_file_start_row(), 2);
+        });
+
+        cx.dispatch_action(editor::actions::NextLargeFilePage);
+        assert_single_caret_at_row(&editor, 0, cx);
+        editor.update(cx, |editor, cx| {
+            let buffer = editor.buffer().read(cx).as_singleton().unwrap();
+            assert_eq!(buffer.read(cx).text(), "line 5\n");
+            assert_eq!(buffer.read(cx).large_file_start_row(), 4);
+        });
+
+        // Dismissing the modal after previewing a line on another page goes back to the page
+        // that was shown before.
+        let go_to_line_view = open_go_to_line_view(&workspace, cx);
+        cx.simulate_input("1");
+        assert_eq!(highlighted_display_rows(&editor, cx), vec![0]);
+        cx.dispatch_action(menu::Cancel);
+        drop(go_to_line_view);
+        editor.update(cx, |editor, cx| {
+            let buffer = editor.buffer().read(cx).as_singleton().unwrap();
+            assert_eq!(buffer.read(cx).text(), "line 5\n");
+        });
+    }
+
     fn open_go_to_line_view(
         workspace: &View<Workspace>,
         cx: &mut VisualTestContext,
@@ -0,0 +1,316 @@
+//! Semantic information about elements, which is exposed to assistive technologies such as
+//! screen readers.
+//!
+//! Elements describe themselves with [`AccessibilityProperties`] while they are painted, and the
+//! window assembles them into an [`AccessibilityTree`] that mirrors the nesting of those elements.
+
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    ops::Range,
+};
+
+use collections::FxHashMap;
+
+use crate::{Bounds, GlobalElementId, Pixels, SharedString};
+
+/// What an element represents to assistive technologies.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Role {
+    /// The window containing all other nodes.
+    Window,
+    /// A container for related elements.
+    #[default]
+    Group,
+    /// An element that performs an action when clicked.
+    Button,
+    /// An element that can be checked or unchecked.
+    CheckBox,
+    /// A link to another location.
+    Link,
+    /// Static text.
+    Label,
+    /// An image or icon.
+    Image,
+    /// A single line of editable text.
+    TextInput,
+    /// Multiple lines of editable text.
+    MultilineTextInput,
+    /// A list of items.
+    List,
+    /// An item within a [`Role::List`] or [`Role::Tree`].
+    ListItem,
+    /// A hierarchical list of items.
+    Tree,
+    /// A set of tabs.
+    TabList,
+    /// A tab within a [`Role::TabList`].
+    Tab,
+    /// A menu of commands.
+    Menu,
+    /// A command within a [`Role::Menu`].
+    MenuItem,
+    /// A modal dialog.
+    Dialog,
+    /// A toolbar containing buttons.
+    Toolbar,
+}
+
+/// The state of an element, as reported to assistive technologies.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AccessibilityState {
+    /// Whether the element is checked, if it can be.
+    pub checked: Option<bool>,
+    /// Whether the element is selected, if it can be.
+    pub selected: Option<bool>,
+    /// Whether the element is expanded, if it can be.
+    pub expanded: Option<bool>,
+    /// Whether the element is disabled.
+    pub disabled: bool,
+    /// Whether the element has keyboard focus.
+    pub focused: bool,
+}
+
+/// Describes an element to assistive technologies.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AccessibilityProperties {
+    /// What the element represents.
+    pub role: Role,
+    /// The name announced for the element, such as the text of a button.
+    pub label: Option<SharedString>,
+    /// The value of the element, such as the contents of a text input.
+    pub value: Option<SharedString>,
+    /// The state of the element.
+    pub state: AccessibilityState,
+    /// The selected range within `value`, in characters, from where the selection started to
+    /// the caret. The caret may come before the start, and an empty range is just a caret.
+    pub text_selection: Option<Range<usize>>,
+}
+
+/// Identifies a node in an [`AccessibilityTree`].
+///
+/// Nodes for elements with an [`ElementId`](crate::ElementId) keep the same id across frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AccessibilityNodeId(pub u64);
+
+/// A node in an [`AccessibilityTree`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessibilityNode {
+    /// The node's id.
+    pub id: AccessibilityNodeId,
+    /// The description of the element that produced this node.
+    pub properties: AccessibilityProperties,
+    /// The bounds of the element, in logical pixels relative to the window.
+    pub bounds: Bounds<Pixels>,
+    /// The node's children, in paint order.
+    pub children: Vec<AccessibilityNodeId>,
+}
+
+/// The accessible elements of a window, as painted in its most recent frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessibilityTree {
+    root: AccessibilityNodeId,
+    focus: AccessibilityNodeId,
+    nodes: FxHashMap<AccessibilityNodeId, AccessibilityNode>,
+}
+
+impl AccessibilityTree {
+    /// The id of the node representing the window itself.
+    pub fn root(&self) -> AccessibilityNodeId {
+        self.root
+    }
+
+    /// The id of the focused node, or the root if no accessible element has focus.
+    pub fn focus(&self) -> AccessibilityNodeId {
+        self.focus
+    }
+
+    /// Returns the node with the given id.
+    pub fn node(&self, id: AccessibilityNodeId) -> Option<&AccessibilityNode> {
+        self.nodes.get(&id)
+    }
+
+    /// Iterates over all nodes in the tree, in no particular order.
+    pub fn nodes(&self) -> impl Iterator<Item = &AccessibilityNode> {
+        self.nodes.values()
+    }
+
+    /// Builds the tree recorded while painting a frame, beneath a root node for the window.
+    pub(crate) fn build(operations: &[AccessibilityOperation], bounds: Bounds<Pixels>) -> Self {
+        let root = AccessibilityNodeId(0);
+        let mut nodes = FxHashMap::default();
+        nodes.insert(
+            root,
+            AccessibilityNode {
+                id: root,
+                properties: AccessibilityProperties {
+                    role: Role::Window,
+                    ..Default::default()
+                },
+                bounds,
+                children: Vec::new(),
+            },
+        );
+
+        let mut focus = root;
+        let mut stack = vec![root];
+        for operation in operations {
+            match operation {
+                AccessibilityOperation::Push {
+                    key,
+                    properties,
+                    bounds,
+                } => {
+                    let parent = *stack.last().unwrap_or(&root);
+                    // Elements without an id are identified by their position within their parent.
+                    let id = AccessibilityNodeId(match key {
+                        Some(key) => *key,
+                        None => hash(&(parent, nodes[&parent].children.len())),
+                    });
+                    // Ids must be unique, so an element painted more than once is only exposed once.
+                    if nodes.contains_key(&id) {
+                        stack.push(id);
+                        continue;
+                    }
+
+                    if let Some(parent_node) = nodes.get_mut(&parent) {
+                        parent_node.children.push(id);
+                    }
+                    if properties.state.focused {
+                        focus = id;
+                    }
+                    nodes.insert(
+                        id,
+                        AccessibilityNode {
+                            id,
+                            properties: properties.clone(),
+                            bounds: *bounds,
+                            children: Vec::new(),
+                        },
+                    );
+                    stack.push(id);
+                }
+                AccessibilityOperation::Pop => {
+                    if stack.len() > 1 {
+                        stack.pop();
+                    }
+                }
+            }
+        }
+
+        Self { root, focus, nodes }
+    }
+}
+
+/// An accessible element entered or exited while painting a frame.
+#[derive(Debug, Clone)]
+pub(crate) enum AccessibilityOperation {
+    Push {
+        key: Option<u64>,
+        properties: AccessibilityProperties,
+        bounds: Bounds<Pixels>,
+    },
+    Pop,
+}
+
+impl AccessibilityOperation {
+    pub(crate) fn push(
+        global_id: Option<&GlobalElementId>,
+        properties: AccessibilityProperties,
+        bounds: Bounds<Pixels>,
+    ) -> Self {
+        Self::Push {
+            // The root's id is reserved for the window.
+            key: global_id.map(|global_id| hash(global_id).max(1)),
+            properties,
+            bounds,
+        }
+    }
+}
+
+fn hash(value: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        div, px, size, InteractiveElement as _, IntoElement, ParentElement as _, Render,
+        Styled as _, TestAppContext, ViewContext,
+    };
+
+    struct Form;
+
+    impl Render for Form {
+        fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+            div()
+                .size_full()
+                .role(Role::Dialog)
+                .accessibility_label("Settings")
+                .child(
+                    div()
+                        .id("remember")
+                        .size(px(10.))
+                        .role(Role::CheckBox)
+                        .accessibility_label("Remember me")
+                        .accessibility_checked(true),
+                )
+                .child(
+                    div()
+                        .child(div().role(Role::Button).accessibility_label("Cancel"))
+                        .child(
+                            div()
+                                .role(Role::Button)
+                                .accessibility_label("Save")
+                                .accessibility_disabled(true),
+                        ),
+                )
+        }
+    }
+
+    #[crate::test]
+    fn test_accessibility_tree(cx: &mut TestAppContext) {
+        let (_, cx) = cx.add_window_view(|_| Form);
+        cx.simulate_resize(size(px(100.), px(100.)));
+        let tree = cx.update(|cx| cx.accessibility_tree());
+
+        let describe = |id| {
+            let node = tree.node(id).unwrap();
+            (node.properties.role, node.properties.label.clone())
+        };
+        let children = |id| tree.node(id).unwrap().children.clone();
+
+        let root = tree.root();
+        assert_eq!(tree.focus(), root);
+        assert_eq!(describe(root).0, Role::Window);
+
+        let [dialog] = children(root)[..] else {
+            panic!("expected a single dialog");
+        };
+        assert_eq!(describe(dialog), (Role::Dialog, Some("Settings".into())));
+
+        // Elements without semantics don't appear in the tree, so the buttons are
+        // siblings of the checkbox.
+        let nodes = children(dialog);
+        assert_eq!(
+            nodes.iter().map(|id| describe(*id)).collect::<Vec<_>>(),
+            [
+                (Role::CheckBox, Some("Remember me".into())),
+                (Role::Button, Some("Cancel".into())),
+                (Role::Button, Some("Save".into())),
+            ]
+        );
+        let checkbox = tree.node(nodes[0]).unwrap();
+        assert_eq!(checkbox.properties.state.checked, Some(true));
+        assert_eq!(checkbox.bounds.size, size(px(10.), px(10.)));
+        assert!(tree.node(nodes[2]).unwrap().properties.state.disabled);
+
+        // Ids are stable across frames.
+        cx.update(|cx| cx.refresh());
+        cx.run_until_parked();
+        let next_tree = cx.update(|cx| cx.accessibility_tree());
+        assert_eq!(next_tree, tree);
+    }
+}
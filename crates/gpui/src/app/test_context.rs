@@ -778,6 +778,59 @@ impl VisualTestContext {
         self.update(|cx| cx.window.rendered_frame.debug_bounds.get(selector).copied())
     }
 
+    /// Simulates an assistive technology, such as a screen reader, starting to read the window.
+    /// Elements that only describe themselves while accessibility is active then appear in
+    /// [`WindowContext::accessibility_tree`] from the next frame on.
+    pub fn simulate_accessibility_activation(&mut self) {
+        self.cx
+            .test_window(self.window)
+            .0
+            .lock()
+            .accessibility_active = true;
+        self.update(|cx| cx.refresh_if_accessibility_activated());
+        self.run_until_parked();
+    }
+
+    /// Rasterizes the most recently drawn frame of the window on the CPU, so that tests
+    /// can check what was painted without a GPU.
+    pub fn render_to_image(&mut self) -> image::RgbaImage {
+        self.run_until_parked();
+        let test_window = self.cx.test_window(self.window);
+        self.update(|cx| test_window.render_scene(&cx.window.rendered_frame.scene))
+    }
+
+    /// Renders the window and compares it with the PNG image at `path`, panicking if they differ.
+    ///
+    /// When the `UPDATE_GOLDENS` environment variable is set, the rendered image is written to
+    /// `path` instead. On a mismatch, the rendered image is saved next to the golden image with
+    /// an `.actual.png` extension so the two can be compared.
+    pub fn assert_matches_golden(&mut self, path: impl AsRef<std::path::Path>) {
+        let path = path.as_ref();
+        let image = self.render_to_image();
+        if std::env::var_os("UPDATE_GOLDENS").is_some() {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).unwrap();
+            }
+            image
+                .save(path)
+                .unwrap_or_else(|error| panic!("failed to write golden image {path:?}: {error}"));
+            return;
+        }
+
+        let golden = image::open(path)
+            .unwrap_or_else(|error| {
+                panic!(
+                    "failed to read golden image {path:?}: {error}. Run with UPDATE_GOLDENS=1 to create it."
+                )
+            })
+            .into_rgba8();
+        if let Some(mismatch) = image_mismatch(&golden, &image) {
+            let actual_path = path.with_extension("actual.png");
+            image.save(&actual_path).ok();
+            panic!("rendered window does not match {path:?}: {mismatch}. The rendered image was saved to {actual_path:?}.");
+        }
+    }
+
     /// Draw an element to the window. Useful for simulating events or actions
     pub fn draw<E>(
         &mut self,
@@ -982,6 +1035,34 @@ impl VisualContext for VisualTestContext {
     }
 }
 
+/// The largest difference in any color channel for two pixels to be considered equal, which
+/// absorbs small differences in how glyphs are rasterized across machines.
+const GOLDEN_TOLERANCE: u8 = 2;
+
+/// Describes how the rendered image differs from the golden one, if it does.
+fn image_mismatch(golden: &image::RgbaImage, actual: &image::RgbaImage) -> Option<String> {
+    if golden.dimensions() != actual.dimensions() {
+        return Some(format!(
+            "expected an image of {:?} pixels, but rendered {:?}",
+            golden.dimensions(),
+            actual.dimensions()
+        ));
+    }
+
+    let mismatched_pixels = golden
+        .pixels()
+        .zip(actual.pixels())
+        .filter(|(golden, actual)| {
+            golden
+                .0
+                .iter()
+                .zip(actual.0.iter())
+                .any(|(golden, actual)| golden.abs_diff(*actual) > GOLDEN_TOLERANCE)
+        })
+        .count();
+    (mismatched_pixels > 0).then(|| format!("{mismatched_pixels} pixels differ"))
+}
+
 impl AnyWindowHandle {
     /// Creates the given view in this window.
     pub fn build_view<V: Render + 'static>(
@@ -16,11 +16,11 @@
 //! constructed by combining these two systems into an all-in-one element.
 
 use crate::{
-    point, px, size, Action, AnyDrag, AnyElement, AnyTooltip, AnyView, AppContext, Bounds,
-    ClickEvent, DispatchPhase, Element, ElementId, FocusHandle, Global, GlobalElementId, Hitbox,
-    HitboxId, IntoElement, IsZero, KeyContext, KeyDownEvent, KeyUpEvent, LayoutId,
-    ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
-    ParentElement, Pixels, Point, Render, ScrollWheelEvent, SharedString, Size, Style,
+    point, px, size, AccessibilityProperties, Action, AnyDrag, AnyElement, AnyTooltip, AnyView,
+    AppContext, Bounds, ClickEvent, DispatchPhase, Element, ElementId, FocusHandle, Global,
+    GlobalElementId, Hitbox, HitboxId, IntoElement, IsZero, KeyContext, KeyDownEvent, KeyUpEvent,
+    LayoutId, ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
+    ParentElement, Pixels, Point, Render, Role, ScrollWheelEvent, SharedString, Size, Style,
     StyleRefinement, Styled, Task, TooltipId, View, Visibility, WindowContext,
 };
 use collections::HashMap;
@@ -538,6 +538,60 @@ pub trait InteractiveElement: Sized {
         self
     }
 
+    /// Expose this element to assistive technologies, such as screen readers, with the given role
+    fn role(mut self, role: Role) -> Self {
+        self.interactivity().accessibility_properties().role = role;
+        self
+    }
+
+    /// Set the name that assistive technologies announce for this element
+    fn accessibility_label(mut self, label: impl Into<SharedString>) -> Self {
+        self.interactivity().accessibility_properties().label = Some(label.into());
+        self
+    }
+
+    /// Set the value that assistive technologies report for this element, such as the text of an input
+    fn accessibility_value(mut self, value: impl Into<SharedString>) -> Self {
+        self.interactivity().accessibility_properties().value = Some(value.into());
+        self
+    }
+
+    /// Report to assistive technologies whether this element is checked
+    fn accessibility_checked(mut self, checked: bool) -> Self {
+        self.interactivity()
+            .accessibility_properties()
+            .state
+            .checked = Some(checked);
+        self
+    }
+
+    /// Report to assistive technologies whether this element is selected
+    fn accessibility_selected(mut self, selected: bool) -> Self {
+        self.interactivity()
+            .accessibility_properties()
+            .state
+            .selected = Some(selected);
+        self
+    }
+
+    /// Report to assistive technologies whether this element is expanded
+    fn accessibility_expanded(mut self, expanded: bool) -> Self {
+        self.interactivity()
+            .accessibility_properties()
+            .state
+            .expanded = Some(expanded);
+        self
+    }
+
+    /// Report to assistive technologies whether this element is disabled
+    fn accessibility_disabled(mut self, disabled: bool) -> Self {
+        self.interactivity()
+            .accessibility_properties()
+            .state
+            .disabled = disabled;
+        self
+    }
+
     /// Assign this element an ID, so that it can be used with interactivity
     fn id(mut self, id: impl Into<ElementId>) -> Stateful<Self> {
         self.interactivity().element_id = Some(id.into());
@@ -1303,6 +1357,7 @@ pub struct Interactivity {
     pub(crate) hover_listener: Option<Box<dyn Fn(&bool, &mut WindowContext)>>,
     pub(crate) tooltip_builder: Option<TooltipBuilder>,
     pub(crate) occlude_mouse: bool,
+    pub(crate) accessibility: Option<Box<AccessibilityProperties>>,
 
     #[cfg(debug_assertions)]
     pub(crate) location: Option<core::panic::Location<'static>>,
@@ -1312,6 +1367,12 @@ pub struct Interactivity {
 }
 
 impl Interactivity {
+    /// The properties exposing this element to assistive technologies, which are
+    /// created when first accessed.
+    pub fn accessibility_properties(&mut self) -> &mut AccessibilityProperties {
+        self.accessibility.get_or_insert_with(Default::default)
+    }
+
     /// Layout this element according to this interactivity state's configured styles
     pub fn request_layout(
         &mut self,
@@ -1554,7 +1615,22 @@ impl Interactivity {
                                     }
 
                                     self.paint_keyboard_listeners(cx);
-                                    f(&style, cx);
+
+                                    let accessibility =
+                                        self.accessibility.as_deref().map(|properties| {
+                                            let mut properties = properties.clone();
+                                            properties.state.focused |= self
+                                                .tracked_focus_handle
+                                                .as_ref()
+                                                .map_or(false, |handle| handle.is_focused(cx));
+                                            properties
+                                        });
+                                    cx.with_accessibility_node(
+                                        global_id,
+                                        accessibility,
+                                        bounds,
+                                        |cx| f(&style, cx),
+                                    );
 
                                     if hitbox.is_some() {
                                         if let Some(group) = self.group.as_ref() {
@@ -0,0 +1,328 @@
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use accesskit::{
+    ActionHandler, ActionRequest, ActivationHandler, DeactivationHandler, Node, NodeBuilder,
+    NodeId, Rect, TextPosition, TextSelection, Toggled, Tree, TreeUpdate,
+};
+use accesskit_unix::Adapter;
+use collections::FxHashMap;
+use parking_lot::Mutex;
+
+use crate::{AccessibilityNode, AccessibilityTree, Bounds, Pixels, Role, SharedString};
+
+const ROOT_ID: NodeId = NodeId(0);
+
+/// Exposes the accessibility tree of a window to assistive technologies over AT-SPI.
+pub(crate) struct AccessibilityBridge {
+    adapter: Adapter,
+    active: Arc<AtomicBool>,
+    title: Arc<Mutex<String>>,
+    text_runs: Arc<Mutex<FxHashMap<NodeId, TextRuns>>>,
+}
+
+impl AccessibilityBridge {
+    pub fn new() -> Self {
+        let active = Arc::new(AtomicBool::new(false));
+        let title = Arc::new(Mutex::new(String::new()));
+        let text_runs = Arc::new(Mutex::new(FxHashMap::default()));
+        let adapter = Adapter::new(
+            ActivationListener {
+                active: active.clone(),
+                title: title.clone(),
+                text_runs: text_runs.clone(),
+            },
+            NoActions,
+            DeactivationListener {
+                active: active.clone(),
+            },
+        );
+        Self {
+            adapter,
+            active,
+            title,
+            text_runs,
+        }
+    }
+
+    /// Whether an assistive technology is listening, and the window should send its tree.
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::Acquire)
+    }
+
+    pub fn set_title(&mut self, title: &str) {
+        *self.title.lock() = title.to_string();
+    }
+
+    pub fn set_focused(&mut self, focused: bool) {
+        self.adapter.update_window_focus_state(focused);
+    }
+
+    /// Updates the position of the window on screen, so that assistive technologies can
+    /// locate its elements.
+    pub fn set_window_bounds(&mut self, bounds: Bounds<Pixels>, scale_factor: f32) {
+        let rect = to_rect(bounds, scale_factor);
+        self.adapter.set_root_window_bounds(rect, rect);
+    }
+
+    pub fn update(&mut self, tree: AccessibilityTree, scale_factor: f32) {
+        let title = self.title.lock().clone();
+        let mut text_runs = self.text_runs.lock();
+        self.adapter
+            .update_if_active(|| tree_update(&tree, &title, scale_factor, &mut text_runs));
+    }
+}
+
+struct ActivationListener {
+    active: Arc<AtomicBool>,
+    title: Arc<Mutex<String>>,
+    text_runs: Arc<Mutex<FxHashMap<NodeId, TextRuns>>>,
+}
+
+impl ActivationHandler for ActivationListener {
+    fn request_initial_tree(&mut self) -> Option<TreeUpdate> {
+        // The new listener hasn't seen any text runs yet, so they're all sent with the next frame.
+        self.text_runs.lock().clear();
+        self.active.store(true, Ordering::Release);
+        // Start with an empty window, which is filled in when the next frame is drawn.
+        let mut root = NodeBuilder::new(accesskit::Role::Window);
+        root.set_name(self.title.lock().clone());
+        Some(TreeUpdate {
+            nodes: vec![(ROOT_ID, root.build())],
+            tree: Some(Tree::new(ROOT_ID)),
+            focus: ROOT_ID,
+        })
+    }
+}
+
+struct DeactivationListener {
+    active: Arc<AtomicBool>,
+}
+
+impl DeactivationHandler for DeactivationListener {
+    fn deactivate_accessibility(&mut self) {
+        self.active.store(false, Ordering::Release);
+    }
+}
+
+/// Actions requested by assistive technologies, such as clicking an element, aren't supported
+/// yet; elements are activated with the keyboard instead.
+struct NoActions;
+
+impl ActionHandler for NoActions {
+    fn do_action(&mut self, _request: ActionRequest) {}
+}
+
+/// The text runs last sent for a text input. Assistive technologies keep the nodes they were
+/// sent, so the runs are only rebuilt when the text or its position changes.
+struct TextRuns {
+    value: SharedString,
+    bounds: Bounds<Pixels>,
+    scale_factor: f32,
+    run_starts: Vec<(NodeId, usize)>,
+    len: usize,
+}
+
+impl TextRuns {
+    fn is_current(&self, value: &SharedString, bounds: Bounds<Pixels>, scale_factor: f32) -> bool {
+        // Comparing the pointers first skips comparing the text of an unchanged document, which
+        // elements report with the same string on every frame.
+        let same_value = (self.value.as_ptr() == value.as_ptr() && self.value.len() == value.len())
+            || self.value == *value;
+        same_value && self.bounds == bounds && self.scale_factor == scale_factor
+    }
+}
+
+fn tree_update(
+    tree: &AccessibilityTree,
+    title: &str,
+    scale_factor: f32,
+    text_runs_by_node: &mut FxHashMap<NodeId, TextRuns>,
+) -> TreeUpdate {
+    let mut nodes = Vec::new();
+    let mut text_input_ids = Vec::new();
+    for node in tree.nodes() {
+        let id = node_id(node.id, tree);
+        let mut builder = NodeBuilder::new(role(node.properties.role));
+        builder.set_bounds(to_rect(node.bounds, scale_factor));
+        if id == ROOT_ID {
+            builder.set_name(title);
+        } else if let Some(label) = &node.properties.label {
+            builder.set_name(label.to_string());
+        }
+
+        let state = &node.properties.state;
+        if let Some(checked) = state.checked {
+            builder.set_toggled(if checked {
+                Toggled::True
+            } else {
+                Toggled::False
+            });
+        }
+        if let Some(selected) = state.selected {
+            builder.set_selected(selected);
+        }
+        if let Some(expanded) = state.expanded {
+            builder.set_expanded(expanded);
+        }
+        if state.disabled {
+            builder.set_disabled();
+        }
+
+        let mut children = node
+            .children
+            .iter()
+            .map(|child| node_id(*child, tree))
+            .collect::<Vec<_>>();
+        if let Some(value) = &node.properties.value {
+            builder.set_value(value.to_string());
+            if is_text_input(node.properties.role) {
+                text_input_ids.push(id);
+                let is_current = text_runs_by_node.get(&id).map_or(false, |text_runs| {
+                    text_runs.is_current(value, node.bounds, scale_factor)
+                });
+                if !is_current {
+                    let (runs, text_runs) = build_text_runs(node, value, scale_factor);
+                    nodes.extend(runs);
+                    text_runs_by_node.insert(id, text_runs);
+                }
+                let text_runs = &text_runs_by_node[&id];
+                children.extend(text_runs.run_starts.iter().map(|(id, _)| *id));
+                if let Some(selection) = text_selection(node, text_runs) {
+                    builder.set_text_selection(selection);
+                }
+            }
+        }
+        builder.set_children(children);
+        nodes.push((id, builder.build()));
+    }
+    text_runs_by_node.retain(|id, _| text_input_ids.contains(id));
+
+    TreeUpdate {
+        nodes,
+        tree: Some(Tree::new(ROOT_ID)),
+        focus: node_id(tree.focus(), tree),
+    }
+}
+
+fn node_id(id: crate::AccessibilityNodeId, tree: &AccessibilityTree) -> NodeId {
+    if id == tree.root() {
+        ROOT_ID
+    } else {
+        NodeId(id.0)
+    }
+}
+
+fn is_text_input(role: Role) -> bool {
+    matches!(role, Role::TextInput | Role::MultilineTextInput)
+}
+
+/// Splits the value of a text input into one text run per line, which is how assistive
+/// technologies navigate text.
+fn build_text_runs(
+    node: &AccessibilityNode,
+    value: &SharedString,
+    scale_factor: f32,
+) -> (Vec<(NodeId, Node)>, TextRuns) {
+    // An empty value still needs a run for the caret to be placed in.
+    let lines = if value.is_empty() {
+        vec![""]
+    } else {
+        value.split_inclusive('\n').collect()
+    };
+
+    let mut runs = Vec::new();
+    let mut run_starts = Vec::new();
+    let mut offset = 0;
+    for (ix, line) in lines.into_iter().enumerate() {
+        let id = text_run_id(node.id.0, ix);
+        let mut builder = NodeBuilder::new(accesskit::Role::TextRun);
+        builder.set_bounds(to_rect(node.bounds, scale_factor));
+        builder.set_value(line.to_string());
+        builder.set_character_lengths(
+            line.chars()
+                .map(|character| character.len_utf8() as u8)
+                .collect::<Vec<_>>(),
+        );
+        run_starts.push((id, offset));
+        offset += line.chars().count();
+        runs.push((id, builder.build()));
+    }
+
+    let text_runs = TextRuns {
+        value: value.clone(),
+        bounds: node.bounds,
+        scale_factor,
+        run_starts,
+        len: offset,
+    };
+    (runs, text_runs)
+}
+
+/// Resolves the selection of a text input to positions within its text runs.
+fn text_selection(node: &AccessibilityNode, text_runs: &TextRuns) -> Option<TextSelection> {
+    let run_starts = &text_runs.run_starts;
+    let position = |character_index: usize| {
+        let (node, start) = run_starts
+            .iter()
+            .rev()
+            .find(|(_, start)| *start <= character_index)
+            .copied()
+            .unwrap_or(run_starts[0]);
+        TextPosition {
+            node,
+            character_index: character_index - start,
+        }
+    };
+    node.properties
+        .text_selection
+        .as_ref()
+        .map(|selection| TextSelection {
+            anchor: position(selection.start.min(text_runs.len)),
+            focus: position(selection.end.min(text_runs.len)),
+        })
+}
+
+fn text_run_id(node_id: u64, ix: usize) -> NodeId {
+    let mut hasher = DefaultHasher::new();
+    (node_id, "text run", ix).hash(&mut hasher);
+    NodeId(hasher.finish().max(1))
+}
+
+fn to_rect(bounds: Bounds<Pixels>, scale_factor: f32) -> Rect {
+    let bounds = bounds.scale(scale_factor);
+    Rect {
+        x0: bounds.origin.x.0 as f64,
+        y0: bounds.origin.y.0 as f64,
+        x1: (bounds.origin.x + bounds.size.width).0 as f64,
+        y1: (bounds.origin.y + bounds.size.height).0 as f64,
+    }
+}
+
+fn role(role: Role) -> accesskit::Role {
+    match role {
+        Role::Window => accesskit::Role::Window,
+        Role::Group => accesskit::Role::Group,
+        Role::Button => accesskit::Role::Button,
+        Role::CheckBox => accesskit::Role::CheckBox,
+        Role::Link => accesskit::Role::Link,
+        Role::Label => accesskit::Role::Label,
+        Role::Image => accesskit::Role::Image,
+        Role::TextInput => accesskit::Role::TextInput,
+        Role::MultilineTextInput => accesskit::Role::MultilineTextInput,
+        Role::List => accesskit::Role::List,
+        Role::ListItem => accesskit::Role::ListItem,
+        Role::Tree => accesskit::Role::Tree,
+        Role::TabList => accesskit::Role::TabList,
+        Role::Tab => accesskit::Role::Tab,
+        Role::Menu => accesskit::Role::Menu,
+        Role::MenuItem => accesskit::Role::MenuItem,
+        Role::Dialog => accesskit::Role::Dialog,
+        Role::Toolbar => accesskit::Role::Toolbar,
+    }
+}
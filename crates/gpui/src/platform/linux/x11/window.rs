@@ -2,11 +2,11 @@ use anyhow::{anyhow, Context};
 
 use crate::{
     platform::blade::{BladeRenderer, BladeSurfaceConfig},
-    px, size, AnyWindowHandle, Bounds, Decorations, DevicePixels, ForegroundExecutor, GPUSpecs,
-    Modifiers, Pixels, PlatformAtlas, PlatformDisplay, PlatformInput, PlatformInputHandler,
-    PlatformWindow, Point, PromptLevel, RequestFrameOptions, ResizeEdge, ScaledPixels, Scene, Size,
-    Tiling, WindowAppearance, WindowBackgroundAppearance, WindowBounds, WindowDecorations,
-    WindowKind, WindowParams, X11ClientStatePtr,
+    px, size, AccessibilityBridge, AccessibilityTree, AnyWindowHandle, Bounds, Decorations,
+    DevicePixels, ForegroundExecutor, GPUSpecs, Modifiers, Pixels, PlatformAtlas, PlatformDisplay,
+    PlatformInput, PlatformInputHandler, PlatformWindow, Point, PromptLevel, RequestFrameOptions,
+    ResizeEdge, ScaledPixels, Scene, Size, Tiling, WindowAppearance, WindowBackgroundAppearance,
+    WindowBounds, WindowDecorations, WindowKind, WindowParams, X11ClientStatePtr,
 };
 
 use blade_graphics as gpu;
@@ -266,6 +266,7 @@ pub struct X11WindowState {
     edge_constraints: Option<EdgeConstraints>,
     pub handle: AnyWindowHandle,
     last_insets: [u32; 4],
+    accessibility: AccessibilityBridge,
 }
 
 impl X11WindowState {
@@ -614,6 +615,7 @@ impl X11WindowState {
                 client_side_decorations_supported,
                 decorations: WindowDecorations::Server,
                 last_insets: [0, 0, 0, 0],
+                accessibility: AccessibilityBridge::new(),
                 edge_constraints: None,
                 counter_id: sync_request_counter,
                 last_sync_counter: None,
@@ -1030,6 +1032,8 @@ impl X11WindowStatePtr {
             } else {
                 state.bounds = bounds;
             }
+            let (bounds, scale_factor) = (state.bounds, state.scale_factor);
+            state.accessibility.set_window_bounds(bounds, scale_factor);
 
             let gpu_size = query_render_extent(&self.xcb, self.x_window)?;
             if true {
@@ -1063,6 +1067,7 @@ impl X11WindowStatePtr {
     }
 
     pub fn set_active(&self, focus: bool) {
+        self.state.borrow_mut().accessibility.set_focused(focus);
         if let Some(ref mut fun) = self.callbacks.borrow_mut().active_status_change {
             fun(focus);
         }
@@ -1205,6 +1210,7 @@ impl PlatformWindow for X11Window {
     }
 
     fn set_title(&mut self, title: &str) {
+        self.0.state.borrow_mut().accessibility.set_title(title);
         check_reply(
             || "X11 ChangeProperty8 on WM_NAME failed.",
             self.0.xcb.change_property8(
@@ -1539,4 +1545,14 @@ impl PlatformWindow for X11Window {
     fn gpu_specs(&self) -> Option<GPUSpecs> {
         self.0.state.borrow().renderer.gpu_specs().into()
     }
+
+    fn is_accessibility_active(&self) -> bool {
+        self.0.state.borrow().accessibility.is_active()
+    }
+
+    fn update_accessibility_tree(&self, tree: AccessibilityTree) {
+        let mut state = self.0.state.borrow_mut();
+        let scale_factor = state.scale_factor;
+        state.accessibility.update(tree, scale_factor);
+    }
 }
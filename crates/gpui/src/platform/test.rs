@@ -1,9 +1,11 @@
 mod dispatcher;
 mod display;
 mod platform;
+mod renderer;
 mod window;
 
 pub(crate) use dispatcher::*;
 pub(crate) use display::*;
 pub(crate) use platform::*;
+pub(crate) use renderer::*;
 pub(crate) use window::*;
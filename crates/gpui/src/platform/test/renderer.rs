@@ -0,0 +1,903 @@
+use crate::{
+    point, Bounds, ContentMask, Corners, DevicePixels, Hsla, MonochromeSprite, Path, PathVertex,
+    Point, PolychromeSprite, PrimitiveBatch, Quad, ScaledPixels, Scene, Shadow, Size, TestAtlas,
+    TestAtlasTexture, TransformationMatrix, Underline,
+};
+use image::RgbaImage;
+use std::f32::consts::PI;
+
+const GRAYSCALE_FACTORS: [f32; 3] = [0.2126, 0.7152, 0.0722];
+
+/// The sample positions within a pixel used to anti-alias paths.
+const PATH_SAMPLES: [(f32, f32); 4] = [(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)];
+
+/// Rasterizes a [`Scene`] into an image on the CPU, so that tests can inspect what a window drew.
+///
+/// Each primitive is shaded the same way as in the GPU renderers' shaders, but colors are blended
+/// in sRGB space and paths are anti-aliased by supersampling, so the output is close to, but not
+/// pixel-identical with, what is shown on screen.
+pub(crate) fn render_scene(
+    scene: &Scene,
+    size: Size<DevicePixels>,
+    atlas: &TestAtlas,
+) -> RgbaImage {
+    let mut canvas = Canvas::new(size);
+    for batch in scene.batches() {
+        match batch {
+            PrimitiveBatch::Shadows(shadows) => {
+                for shadow in shadows {
+                    canvas.draw_shadow(shadow);
+                }
+            }
+            PrimitiveBatch::Quads(quads) => {
+                for quad in quads {
+                    canvas.draw_quad(quad);
+                }
+            }
+            PrimitiveBatch::Paths(paths) => {
+                for path in paths {
+                    canvas.draw_path(path);
+                }
+            }
+            PrimitiveBatch::Underlines(underlines) => {
+                for underline in underlines {
+                    canvas.draw_underline(underline);
+                }
+            }
+            PrimitiveBatch::MonochromeSprites {
+                texture_id,
+                sprites,
+            } => {
+                atlas.with_texture(texture_id, |texture| {
+                    for sprite in sprites {
+                        canvas.draw_monochrome_sprite(sprite, texture);
+                    }
+                });
+            }
+            PrimitiveBatch::PolychromeSprites {
+                texture_id,
+                sprites,
+            } => {
+                atlas.with_texture(texture_id, |texture| {
+                    for sprite in sprites {
+                        canvas.draw_polychrome_sprite(sprite, texture);
+                    }
+                });
+            }
+            // Surfaces are video frames that only exist on macOS.
+            PrimitiveBatch::Surfaces(_) => {}
+        }
+    }
+    canvas.into_image()
+}
+
+/// A straight (not premultiplied) RGBA color with components between 0 and 1.
+type Color = [f32; 4];
+
+struct Canvas {
+    width: i32,
+    height: i32,
+    /// Premultiplied RGBA pixels, row by row.
+    pixels: Vec<Color>,
+}
+
+impl Canvas {
+    fn new(size: Size<DevicePixels>) -> Self {
+        let width = size.width.0.max(0);
+        let height = size.height.0.max(0);
+        Self {
+            width,
+            height,
+            pixels: vec![[0.; 4]; width as usize * height as usize],
+        }
+    }
+
+    /// Calls `shade` with the center of each pixel within `bounds` and `content_mask`,
+    /// blending the color it returns over that pixel.
+    fn fill(
+        &mut self,
+        bounds: Bounds<ScaledPixels>,
+        content_mask: &ContentMask<ScaledPixels>,
+        mut shade: impl FnMut(Point<f32>) -> Option<Color>,
+    ) {
+        let clip = bounds.intersect(&content_mask.bounds);
+        let left = clip.origin.x.0;
+        let top = clip.origin.y.0;
+        let right = left + clip.size.width.0;
+        let bottom = top + clip.size.height.0;
+
+        let min_x = (left.floor() as i32).max(0);
+        let min_y = (top.floor() as i32).max(0);
+        let max_x = (right.ceil() as i32).min(self.width);
+        let max_y = (bottom.ceil() as i32).min(self.height);
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                let center = point(x as f32 + 0.5, y as f32 + 0.5);
+                if center.x < left || center.x >= right || center.y < top || center.y >= bottom {
+                    continue;
+                }
+                if let Some(color) = shade(center) {
+                    self.blend(x, y, color);
+                }
+            }
+        }
+    }
+
+    fn blend(&mut self, x: i32, y: i32, [r, g, b, a]: Color) {
+        if a <= 0. {
+            return;
+        }
+        let pixel = &mut self.pixels[(y * self.width + x) as usize];
+        let a = a.min(1.);
+        for (channel, source) in pixel.iter_mut().zip([r * a, g * a, b * a, a]) {
+            *channel = source + *channel * (1. - a);
+        }
+    }
+
+    fn into_image(self) -> RgbaImage {
+        let to_byte = |value: f32| (value.clamp(0., 1.) * 255.).round() as u8;
+        let mut bytes = Vec::with_capacity(self.pixels.len() * 4);
+        for [r, g, b, a] in self.pixels {
+            if a <= 0. {
+                bytes.extend([0; 4]);
+            } else {
+                bytes.extend([to_byte(r / a), to_byte(g / a), to_byte(b / a), to_byte(a)]);
+            }
+        }
+        RgbaImage::from_raw(self.width as u32, self.height as u32, bytes)
+            .expect("buffer size matches image dimensions")
+    }
+
+    fn draw_quad(&mut self, quad: &Quad) {
+        let background = to_color(quad.background);
+        let border_color = to_color(quad.border_color);
+        let radii = &quad.corner_radii;
+        let widths = &quad.border_widths;
+        let is_plain = [
+            radii.top_left,
+            radii.top_right,
+            radii.bottom_right,
+            radii.bottom_left,
+        ]
+        .into_iter()
+        .chain([widths.top, widths.right, widths.bottom, widths.left])
+        .all(|value| value.0 == 0.);
+
+        let half_size = point(
+            quad.bounds.size.width.0 / 2.,
+            quad.bounds.size.height.0 / 2.,
+        );
+        let center = point(
+            quad.bounds.origin.x.0 + half_size.x,
+            quad.bounds.origin.y.0 + half_size.y,
+        );
+        self.fill(quad.bounds, &quad.content_mask, |position| {
+            if is_plain {
+                return Some(background);
+            }
+
+            let center_to_point = point(position.x - center.x, position.y - center.y);
+            let corner_radius = pick_corner_radius(center_to_point, radii);
+            let distance = rounded_rect_distance(center_to_point, half_size, corner_radius);
+
+            let vertical_border = if center_to_point.x > 0. {
+                widths.right.0
+            } else {
+                widths.left.0
+            };
+            let horizontal_border = if center_to_point.y > 0. {
+                widths.bottom.0
+            } else {
+                widths.top.0
+            };
+            let point_to_inset_corner = point(
+                center_to_point.x.abs() - (half_size.x - corner_radius - vertical_border),
+                center_to_point.y.abs() - (half_size.y - corner_radius - horizontal_border),
+            );
+            let border_width = if point_to_inset_corner.x < 0. && point_to_inset_corner.y < 0. {
+                0.
+            } else if point_to_inset_corner.y > point_to_inset_corner.x {
+                horizontal_border
+            } else {
+                vertical_border
+            };
+
+            let mut color = background;
+            if border_width > 0. {
+                let inset_distance = distance + border_width;
+                let blended_border = over(background, border_color);
+                color = mix(blended_border, background, saturate(0.5 - inset_distance));
+            }
+            Some(with_alpha(color, saturate(0.5 - distance)))
+        });
+    }
+
+    fn draw_shadow(&mut self, shadow: &Shadow) {
+        let color = to_color(shadow.color);
+        let blur_radius = shadow.blur_radius.0;
+        let half_size = point(
+            shadow.bounds.size.width.0 / 2.,
+            shadow.bounds.size.height.0 / 2.,
+        );
+        let center = point(
+            shadow.bounds.origin.x.0 + half_size.x,
+            shadow.bounds.origin.y.0 + half_size.y,
+        );
+
+        let margin = ScaledPixels(3. * blur_radius);
+        let bounds = Bounds {
+            origin: point(
+                shadow.bounds.origin.x - margin,
+                shadow.bounds.origin.y - margin,
+            ),
+            size: Size {
+                width: shadow.bounds.size.width + margin + margin,
+                height: shadow.bounds.size.height + margin + margin,
+            },
+        };
+        self.fill(bounds, &shadow.content_mask, |position| {
+            let center_to_point = point(position.x - center.x, position.y - center.y);
+            let corner_radius = pick_corner_radius(center_to_point, &shadow.corner_radii);
+            if blur_radius <= 0. {
+                let distance = rounded_rect_distance(center_to_point, half_size, corner_radius);
+                return Some(with_alpha(color, saturate(0.5 - distance)));
+            }
+
+            let low = center_to_point.y - half_size.y;
+            let high = center_to_point.y + half_size.y;
+            let start = (-3. * blur_radius).clamp(low, high);
+            let end = (3. * blur_radius).clamp(low, high);
+
+            let step = (end - start) / 4.;
+            let mut y = start + step * 0.5;
+            let mut alpha = 0.;
+            for _ in 0..4 {
+                let blur = blur_along_x(
+                    center_to_point.x,
+                    center_to_point.y - y,
+                    blur_radius,
+                    corner_radius,
+                    half_size,
+                );
+                alpha += blur * gaussian(y, blur_radius) * step;
+                y += step;
+            }
+            Some(with_alpha(color, alpha))
+        });
+    }
+
+    fn draw_path(&mut self, path: &Path<ScaledPixels>) {
+        let color = to_color(path.color);
+        self.fill(path.bounds, &path.content_mask, |position| {
+            let mut coverage = 0.;
+            for (dx, dy) in PATH_SAMPLES {
+                let sample = point(position.x - 0.5 + dx, position.y - 0.5 + dy);
+                // Like the GPU renderers, count the triangles covering the sample and
+                // fill it using the even-odd rule.
+                let winding = path
+                    .vertices
+                    .chunks_exact(3)
+                    .filter_map(|triangle| interpolate_st(triangle, sample))
+                    .filter(|st| st.x * st.x - st.y <= 0.)
+                    .count();
+                coverage += (winding % 2) as f32;
+            }
+            Some(with_alpha(color, coverage / PATH_SAMPLES.len() as f32))
+        });
+    }
+
+    fn draw_underline(&mut self, underline: &Underline) {
+        let color = to_color(underline.color);
+        let origin = underline.bounds.origin;
+        let height = underline.bounds.size.height.0;
+        let thickness = underline.thickness.0;
+        self.fill(underline.bounds, &underline.content_mask, |position| {
+            if !underline.wavy {
+                return Some(color);
+            }
+
+            let half_thickness = thickness * 0.5;
+            let st = point(
+                (position.x - origin.x.0) / height,
+                (position.y - origin.y.0) / height - 0.5,
+            );
+            let frequency = PI * 3. * thickness / 3.;
+            let amplitude = 1. / (4. * thickness);
+            let sine = (st.x * frequency).sin() * amplitude;
+            let d_sine = (st.x * frequency).cos() * amplitude * frequency;
+            let distance = (st.y - sine) / (1. + d_sine * d_sine).sqrt();
+            let distance_in_pixels = distance * height;
+            let distance_from_top_border = distance_in_pixels - half_thickness;
+            let distance_from_bottom_border = distance_in_pixels + half_thickness;
+            let alpha =
+                saturate(0.5 - (-distance_from_bottom_border).max(distance_from_top_border));
+            Some(with_alpha(color, alpha))
+        });
+    }
+
+    fn draw_monochrome_sprite(&mut self, sprite: &MonochromeSprite, texture: &TestAtlasTexture) {
+        let color = to_color(sprite.color);
+        let transformation = &sprite.transformation;
+        let is_transformed = *transformation != TransformationMatrix::unit();
+        let inverse = invert(transformation);
+
+        let (bounds, content_mask) = if is_transformed {
+            // The content mask applies before the sprite is transformed, so it is checked
+            // against the untransformed position below.
+            let bounds = transformed_bounds(sprite.bounds, transformation);
+            (bounds, ContentMask { bounds })
+        } else {
+            (sprite.bounds, sprite.content_mask.clone())
+        };
+        self.fill(bounds, &content_mask, |position| {
+            let position = if is_transformed {
+                let position = apply(&inverse?, position);
+                if !contains(&sprite.content_mask.bounds, position) {
+                    return None;
+                }
+                position
+            } else {
+                position
+            };
+            let [alpha] = sample_texture::<1>(texture, sprite.bounds, position)?;
+            Some(with_alpha(color, alpha))
+        });
+    }
+
+    fn draw_polychrome_sprite(&mut self, sprite: &PolychromeSprite, texture: &TestAtlasTexture) {
+        let half_size = point(
+            sprite.bounds.size.width.0 / 2.,
+            sprite.bounds.size.height.0 / 2.,
+        );
+        let center = point(
+            sprite.bounds.origin.x.0 + half_size.x,
+            sprite.bounds.origin.y.0 + half_size.y,
+        );
+        self.fill(sprite.bounds, &sprite.content_mask, |position| {
+            let [b, g, r, a] = sample_texture::<4>(texture, sprite.bounds, position)?;
+            let mut color = [r, g, b, a];
+            if sprite.grayscale {
+                let grayscale =
+                    r * GRAYSCALE_FACTORS[0] + g * GRAYSCALE_FACTORS[1] + b * GRAYSCALE_FACTORS[2];
+                color = [grayscale, grayscale, grayscale, a];
+            }
+
+            let center_to_point = point(position.x - center.x, position.y - center.y);
+            let corner_radius = pick_corner_radius(center_to_point, &sprite.corner_radii);
+            let distance = rounded_rect_distance(center_to_point, half_size, corner_radius);
+            Some(with_alpha(color, sprite.opacity * saturate(0.5 - distance)))
+        });
+    }
+}
+
+fn to_color(color: Hsla) -> Color {
+    let rgba = color.to_rgb();
+    [rgba.r, rgba.g, rgba.b, rgba.a]
+}
+
+fn with_alpha([r, g, b, a]: Color, alpha_factor: f32) -> Color {
+    [r, g, b, a * alpha_factor]
+}
+
+fn over(below: Color, above: Color) -> Color {
+    let alpha = above[3] + below[3] * (1. - above[3]);
+    if alpha <= 0. {
+        return [0.; 4];
+    }
+    let channel =
+        |ix: usize| (above[ix] * above[3] + below[ix] * below[3] * (1. - above[3])) / alpha;
+    [channel(0), channel(1), channel(2), alpha]
+}
+
+fn mix(a: Color, b: Color, t: f32) -> Color {
+    [0, 1, 2, 3].map(|ix| a[ix] + (b[ix] - a[ix]) * t)
+}
+
+fn saturate(value: f32) -> f32 {
+    value.clamp(0., 1.)
+}
+
+fn pick_corner_radius(center_to_point: Point<f32>, radii: &Corners<ScaledPixels>) -> f32 {
+    match (center_to_point.x < 0., center_to_point.y < 0.) {
+        (true, true) => radii.top_left.0,
+        (true, false) => radii.bottom_left.0,
+        (false, true) => radii.top_right.0,
+        (false, false) => radii.bottom_right.0,
+    }
+}
+
+/// The signed distance from a point, relative to the center of a rounded rectangle, to its edge.
+fn rounded_rect_distance(
+    center_to_point: Point<f32>,
+    half_size: Point<f32>,
+    corner_radius: f32,
+) -> f32 {
+    let edge_x = center_to_point.x.abs() - half_size.x + corner_radius;
+    let edge_y = center_to_point.y.abs() - half_size.y + corner_radius;
+    edge_x.max(0.).hypot(edge_y.max(0.)) + edge_x.max(edge_y).min(0.) - corner_radius
+}
+
+fn gaussian(x: f32, sigma: f32) -> f32 {
+    (-(x * x) / (2. * sigma * sigma)).exp() / ((2. * PI).sqrt() * sigma)
+}
+
+/// Approximates the error function, needed for the gaussian integral.
+fn erf(value: f32) -> f32 {
+    let sign = value.signum();
+    let a = value.abs();
+    let r1 = 1. + (0.278393 + (0.230389 + (0.000972 + 0.078108 * a) * a) * a) * a;
+    let r2 = r1 * r1;
+    sign - sign / (r2 * r2)
+}
+
+fn blur_along_x(x: f32, y: f32, sigma: f32, corner: f32, half_size: Point<f32>) -> f32 {
+    let delta = (half_size.y - corner - y.abs()).min(0.);
+    let curved = half_size.x - corner + (corner * corner - delta * delta).max(0.).sqrt();
+    let scale = 0.5f32.sqrt() / sigma;
+    let start = 0.5 + 0.5 * erf((x - curved) * scale);
+    let end = 0.5 + 0.5 * erf((x + curved) * scale);
+    end - start
+}
+
+/// Returns the interpolated curve coordinates of a path triangle at the given position,
+/// or `None` if the position lies outside of the triangle.
+fn interpolate_st(
+    triangle: &[PathVertex<ScaledPixels>],
+    position: Point<f32>,
+) -> Option<Point<f32>> {
+    let [a, b, c] = [0, 1, 2].map(|ix| {
+        let position = triangle[ix].xy_position;
+        point(position.x.0, position.y.0)
+    });
+    let determinant = (b.y - c.y) * (a.x - c.x) + (c.x - b.x) * (a.y - c.y);
+    if determinant.abs() <= f32::EPSILON {
+        return None;
+    }
+
+    let weight_a =
+        ((b.y - c.y) * (position.x - c.x) + (c.x - b.x) * (position.y - c.y)) / determinant;
+    let weight_b =
+        ((c.y - a.y) * (position.x - c.x) + (a.x - c.x) * (position.y - c.y)) / determinant;
+    let weight_c = 1. - weight_a - weight_b;
+    if weight_a < 0. || weight_b < 0. || weight_c < 0. {
+        return None;
+    }
+
+    let [st_a, st_b, st_c] = [0, 1, 2].map(|ix| triangle[ix].st_position);
+    Some(point(
+        weight_a * st_a.x + weight_b * st_b.x + weight_c * st_c.x,
+        weight_a * st_a.y + weight_b * st_b.y + weight_c * st_c.y,
+    ))
+}
+
+fn contains(bounds: &Bounds<ScaledPixels>, position: Point<f32>) -> bool {
+    position.x >= bounds.origin.x.0
+        && position.x < (bounds.origin.x + bounds.size.width).0
+        && position.y >= bounds.origin.y.0
+        && position.y < (bounds.origin.y + bounds.size.height).0
+}
+
+fn transformed_bounds(
+    bounds: Bounds<ScaledPixels>,
+    transformation: &TransformationMatrix,
+) -> Bounds<ScaledPixels> {
+    let left = bounds.origin.x.0;
+    let top = bounds.origin.y.0;
+    let right = left + bounds.size.width.0;
+    let bottom = top + bounds.size.height.0;
+    let corners = [
+        point(left, top),
+        point(right, top),
+        point(left, bottom),
+        point(right, bottom),
+    ]
+    .map(|corner| apply(transformation, corner));
+
+    let min_x = corners
+        .iter()
+        .map(|corner| corner.x)
+        .fold(f32::MAX, f32::min);
+    let min_y = corners
+        .iter()
+        .map(|corner| corner.y)
+        .fold(f32::MAX, f32::min);
+    let max_x = corners
+        .iter()
+        .map(|corner| corner.x)
+        .fold(f32::MIN, f32::max);
+    let max_y = corners
+        .iter()
+        .map(|corner| corner.y)
+        .fold(f32::MIN, f32::max);
+    Bounds {
+        origin: point(ScaledPixels(min_x), ScaledPixels(min_y)),
+        size: Size {
+            width: ScaledPixels(max_x - min_x),
+            height: ScaledPixels(max_y - min_y),
+        },
+    }
+}
+
+fn apply(transformation: &TransformationMatrix, position: Point<f32>) -> Point<f32> {
+    let [[a, b], [c, d]] = transformation.rotation_scale;
+    let [tx, ty] = transformation.translation;
+    point(
+        a * position.x + b * position.y + tx,
+        c * position.x + d * position.y + ty,
+    )
+}
+
+fn invert(transformation: &TransformationMatrix) -> Option<TransformationMatrix> {
+    let [[a, b], [c, d]] = transformation.rotation_scale;
+    let [tx, ty] = transformation.translation;
+    let determinant = a * d - b * c;
+    if determinant.abs() <= f32::EPSILON {
+        return None;
+    }
+
+    let rotation_scale = [
+        [d / determinant, -b / determinant],
+        [-c / determinant, a / determinant],
+    ];
+    let translation = [
+        -(rotation_scale[0][0] * tx + rotation_scale[0][1] * ty),
+        -(rotation_scale[1][0] * tx + rotation_scale[1][1] * ty),
+    ];
+    Some(TransformationMatrix {
+        rotation_scale,
+        translation,
+    })
+}
+
+/// Samples the texel of a sprite's texture beneath the given position, returning its
+/// `N` channels as values between 0 and 1.
+fn sample_texture<const N: usize>(
+    texture: &TestAtlasTexture,
+    bounds: Bounds<ScaledPixels>,
+    position: Point<f32>,
+) -> Option<[f32; N]> {
+    let width = texture.size.width.0;
+    let height = texture.size.height.0;
+    if width <= 0 || height <= 0 || bounds.size.width.0 <= 0. || bounds.size.height.0 <= 0. {
+        return None;
+    }
+
+    let u = (position.x - bounds.origin.x.0) / bounds.size.width.0;
+    let v = (position.y - bounds.origin.y.0) / bounds.size.height.0;
+    let x = ((u * width as f32) as i32).clamp(0, width - 1);
+    let y = ((v * height as f32) as i32).clamp(0, height - 1);
+    let offset = (y * width + x) as usize * N;
+    let texel = texture.bytes.get(offset..offset + N)?;
+    Some(std::array::from_fn(|ix| texel[ix] as f32 / 255.))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        div, px, rgb, size, AtlasKey, AtlasTile, Edges, IntoElement, ParentElement as _,
+        PlatformAtlas as _, Render, RenderSvgParams, Styled as _, TestAppContext, ViewContext,
+    };
+    use std::borrow::Cow;
+
+    fn scaled_bounds(x: f32, y: f32, width: f32, height: f32) -> Bounds<ScaledPixels> {
+        Bounds {
+            origin: point(ScaledPixels(x), ScaledPixels(y)),
+            size: Size {
+                width: ScaledPixels(width),
+                height: ScaledPixels(height),
+            },
+        }
+    }
+
+    fn quad(bounds: Bounds<ScaledPixels>, background: Hsla) -> Quad {
+        Quad {
+            order: 0,
+            pad: 0,
+            bounds,
+            content_mask: ContentMask {
+                bounds: scaled_bounds(0., 0., 100., 100.),
+            },
+            background,
+            border_color: Hsla::default(),
+            corner_radii: Corners::default(),
+            border_widths: Edges::default(),
+        }
+    }
+
+    fn render(scene: &mut Scene, width: i32, height: i32) -> RgbaImage {
+        render_with_atlas(scene, width, height, &TestAtlas::new())
+    }
+
+    fn render_with_atlas(
+        scene: &mut Scene,
+        width: i32,
+        height: i32,
+        atlas: &TestAtlas,
+    ) -> RgbaImage {
+        scene.finish();
+        render_scene(
+            scene,
+            Size {
+                width: DevicePixels(width),
+                height: DevicePixels(height),
+            },
+            atlas,
+        )
+    }
+
+    /// Inserts a tile with the given pixels into the atlas, as if a glyph, svg or image
+    /// had been rasterized into it.
+    fn insert_tile(atlas: &TestAtlas, key: AtlasKey, width: i32, bytes: Vec<u8>) -> AtlasTile {
+        let channels = match key {
+            AtlasKey::Image(_) => 4,
+            _ => 1,
+        };
+        let size = Size {
+            width: DevicePixels(width),
+            height: DevicePixels(bytes.len() as i32 / channels / width),
+        };
+        atlas
+            .get_or_insert_with(&key, &mut || Ok(Some((size, Cow::Owned(bytes.clone())))))
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_quads() {
+        let mut scene = Scene::default();
+        scene.insert_primitive(quad(scaled_bounds(0., 0., 8., 8.), rgb(0xff0000).into()));
+        let mut bordered = quad(scaled_bounds(2., 2., 4., 4.), rgb(0x0000ff).into());
+        bordered.border_color = rgb(0x00ff00).into();
+        bordered.border_widths = Edges::all(ScaledPixels(1.));
+        scene.insert_primitive(bordered);
+        let mut clipped = quad(scaled_bounds(6., 6., 4., 4.), rgb(0xffffff).into());
+        clipped.content_mask.bounds = scaled_bounds(0., 0., 7., 7.);
+        scene.insert_primitive(clipped);
+
+        let image = render(&mut scene, 10, 10);
+        assert_eq!(image.get_pixel(0, 0).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(2, 2).0, [0, 255, 0, 255]);
+        assert_eq!(image.get_pixel(3, 3).0, [0, 0, 255, 255]);
+        assert_eq!(image.get_pixel(6, 6).0, [255, 255, 255, 255]);
+        assert_eq!(image.get_pixel(7, 7).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(9, 9).0, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_translucent_quads() {
+        let mut scene = Scene::default();
+        scene.insert_primitive(quad(scaled_bounds(0., 0., 4., 4.), rgb(0xffffff).into()));
+        let mut black = Hsla::black();
+        black.a = 0.5;
+        scene.insert_primitive(quad(scaled_bounds(0., 0., 4., 2.), black));
+
+        let image = render(&mut scene, 4, 4);
+        assert_eq!(image.get_pixel(0, 0).0, [128, 128, 128, 255]);
+        assert_eq!(image.get_pixel(0, 3).0, [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_paths() {
+        let mut path = Path::new(point(px(0.), px(0.)));
+        path.line_to(point(px(8.), px(0.)));
+        path.line_to(point(px(0.), px(8.)));
+        path.line_to(point(px(0.), px(0.)));
+        path.color = rgb(0x00ff00).into();
+        path.content_mask.bounds = Bounds {
+            origin: point(px(0.), px(0.)),
+            size: size(px(8.), px(8.)),
+        };
+
+        let mut scene = Scene::default();
+        scene.insert_primitive(path.scale(1.));
+        let image = render(&mut scene, 8, 8);
+        assert_eq!(image.get_pixel(1, 1).0, [0, 255, 0, 255]);
+        assert_eq!(image.get_pixel(7, 7).0, [0, 0, 0, 0]);
+        // Pixels along the diagonal edge are partially covered.
+        let edge = image.get_pixel(3, 4).0;
+        assert_eq!(edge[..3], [0, 255, 0]);
+        assert!(edge[3] > 0 && edge[3] < 255, "{edge:?}");
+    }
+
+    #[test]
+    fn test_shadows() {
+        let shadow = |bounds, blur_radius| Shadow {
+            order: 0,
+            blur_radius: ScaledPixels(blur_radius),
+            bounds,
+            corner_radii: Corners::default(),
+            content_mask: ContentMask {
+                bounds: scaled_bounds(0., 0., 24., 24.),
+            },
+            color: Hsla::black(),
+        };
+
+        // Shadows without blur have sharp edges.
+        let mut scene = Scene::default();
+        scene.insert_primitive(shadow(scaled_bounds(2., 2., 4., 4.), 0.));
+        let image = render(&mut scene, 8, 8);
+        assert_eq!(image.get_pixel(2, 2).0, [0, 0, 0, 255]);
+        assert_eq!(image.get_pixel(5, 5).0, [0, 0, 0, 255]);
+        assert_eq!(image.get_pixel(1, 1).0, [0, 0, 0, 0]);
+        assert_eq!(image.get_pixel(6, 6).0, [0, 0, 0, 0]);
+
+        // Blurred shadows fade out symmetrically over three times the blur radius.
+        let mut scene = Scene::default();
+        scene.insert_primitive(shadow(scaled_bounds(8., 8., 8., 8.), 2.));
+        let image = render(&mut scene, 24, 24);
+        let alphas = (0..24)
+            .map(|x| image.get_pixel(x, 12).0[3])
+            .collect::<Vec<_>>();
+        assert!(alphas[12] > 200, "{alphas:?}");
+        assert!(alphas[8] > 100 && alphas[8] < 200, "{alphas:?}");
+        assert!(alphas[4] > 0 && alphas[4] < 20, "{alphas:?}");
+        assert_eq!(alphas[0], 0, "{alphas:?}");
+        assert_eq!(alphas[23], 0, "{alphas:?}");
+        assert!(
+            alphas[2..=12].windows(2).all(|pair| pair[0] <= pair[1]),
+            "{alphas:?}"
+        );
+        assert!(
+            (0..12).all(|x| alphas[x].abs_diff(alphas[23 - x]) <= 1),
+            "{alphas:?}"
+        );
+    }
+
+    #[test]
+    fn test_underlines() {
+        let mut scene = Scene::default();
+        scene.insert_primitive(Underline {
+            order: 0,
+            pad: 0,
+            bounds: scaled_bounds(0., 1., 16., 1.),
+            content_mask: ContentMask {
+                bounds: scaled_bounds(0., 0., 16., 16.),
+            },
+            color: rgb(0x0000ff).into(),
+            thickness: ScaledPixels(1.),
+            wavy: false,
+        });
+        scene.insert_primitive(Underline {
+            order: 0,
+            pad: 0,
+            bounds: scaled_bounds(0., 4., 16., 4.),
+            content_mask: ContentMask {
+                bounds: scaled_bounds(0., 0., 16., 16.),
+            },
+            color: rgb(0xff0000).into(),
+            thickness: ScaledPixels(1.),
+            wavy: true,
+        });
+
+        let image = render(&mut scene, 16, 16);
+        assert_eq!(image.get_pixel(0, 1).0, [0, 0, 255, 255]);
+        assert_eq!(image.get_pixel(15, 1).0, [0, 0, 255, 255]);
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 0, 0]);
+        assert_eq!(image.get_pixel(0, 2).0, [0, 0, 0, 0]);
+
+        // Wavy underlines are anti-aliased, stay within their bounds, and move up and down.
+        let alpha = |x, y| image.get_pixel(x, y).0[3];
+        assert_eq!(image.get_pixel(0, 6).0[..3], [255, 0, 0]);
+        assert!(alpha(0, 6) > alpha(0, 5));
+        assert!(alpha(4, 5) > alpha(4, 6));
+        assert!(alpha(8, 6) > alpha(8, 5));
+        assert!((0..16).all(|x| alpha(x, 3) == 0 && alpha(x, 8) == 0));
+        assert!((0..16).any(|x| alpha(x, 6) > 0 && alpha(x, 6) < 255));
+    }
+
+    #[test]
+    fn test_monochrome_sprites() {
+        // Glyphs and svgs are drawn as monochrome sprites, whose texture holds their coverage.
+        let atlas = TestAtlas::new();
+        let key = AtlasKey::Svg(RenderSvgParams {
+            path: "glyph".into(),
+            size: Size {
+                width: DevicePixels(2),
+                height: DevicePixels(2),
+            },
+        });
+        let tile = insert_tile(&atlas, key, 2, vec![255, 0, 255, 128]);
+
+        let mut scene = Scene::default();
+        scene.insert_primitive(MonochromeSprite {
+            order: 0,
+            pad: 0,
+            bounds: scaled_bounds(0., 0., 4., 4.),
+            content_mask: ContentMask {
+                bounds: scaled_bounds(0., 0., 4., 3.),
+            },
+            color: rgb(0xff0000).into(),
+            tile: tile.clone(),
+            transformation: TransformationMatrix::unit(),
+        });
+        // Mirrors the sprite horizontally.
+        scene.insert_primitive(MonochromeSprite {
+            order: 0,
+            pad: 0,
+            bounds: scaled_bounds(0., 0., 4., 4.),
+            content_mask: ContentMask {
+                bounds: scaled_bounds(0., 0., 4., 4.),
+            },
+            color: rgb(0x00ff00).into(),
+            tile,
+            transformation: TransformationMatrix {
+                rotation_scale: [[-1., 0.], [0., 1.]],
+                translation: [8., 0.],
+            },
+        });
+
+        let image = render_with_atlas(&mut scene, 8, 4, &atlas);
+        assert_eq!(image.get_pixel(0, 0).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(2, 0).0, [0, 0, 0, 0]);
+        assert_eq!(image.get_pixel(0, 2).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(2, 2).0, [255, 0, 0, 128]);
+        // Clipped by the content mask.
+        assert_eq!(image.get_pixel(0, 3).0, [0, 0, 0, 0]);
+
+        assert_eq!(image.get_pixel(7, 0).0, [0, 255, 0, 255]);
+        assert_eq!(image.get_pixel(5, 0).0, [0, 0, 0, 0]);
+        assert_eq!(image.get_pixel(5, 3).0, [0, 255, 0, 128]);
+    }
+
+    #[test]
+    fn test_polychrome_sprites() {
+        // Emoji and images are drawn as polychrome sprites, whose texture is in BGRA.
+        let atlas = TestAtlas::new();
+        let key = AtlasKey::Image(crate::RenderImageParams {
+            image_id: crate::ImageId(0),
+            frame_index: 0,
+        });
+        let tile = insert_tile(&atlas, key, 1, vec![255, 0, 0, 255]);
+
+        let sprite = |x: f32, grayscale: bool, opacity: f32| PolychromeSprite {
+            order: 0,
+            pad: 0,
+            grayscale,
+            opacity,
+            bounds: scaled_bounds(x, 0., 4., 4.),
+            content_mask: ContentMask {
+                bounds: scaled_bounds(0., 0., 12., 4.),
+            },
+            corner_radii: Corners::default(),
+            tile: tile.clone(),
+        };
+        let mut scene = Scene::default();
+        scene.insert_primitive(sprite(0., false, 1.));
+        scene.insert_primitive(sprite(4., true, 0.5));
+        let mut rounded = sprite(8., false, 1.);
+        rounded.corner_radii = Corners::all(ScaledPixels(2.));
+        scene.insert_primitive(rounded);
+
+        let image = render_with_atlas(&mut scene, 12, 4, &atlas);
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 255, 255]);
+        assert_eq!(image.get_pixel(5, 1).0, [18, 18, 18, 128]);
+        assert_eq!(image.get_pixel(9, 1).0, [0, 0, 255, 255]);
+        let corner = image.get_pixel(8, 0).0;
+        assert_eq!(corner[..3], [0, 0, 255]);
+        assert!(corner[3] < 255, "{corner:?}");
+    }
+
+    #[crate::test]
+    fn test_render_window(cx: &mut TestAppContext) {
+        struct Swatch;
+
+        impl Render for Swatch {
+            fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+                div()
+                    .size_full()
+                    .bg(rgb(0xffffff))
+                    .child(div().size(px(4.)).bg(rgb(0xff0000)))
+            }
+        }
+
+        let (_, cx) = cx.add_window_view(|_| Swatch);
+        cx.simulate_resize(size(px(8.), px(8.)));
+
+        // Test windows have a scale factor of 2.
+        let image = cx.render_to_image();
+        assert_eq!(image.dimensions(), (16, 16));
+        assert_eq!(image.get_pixel(7, 7).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(8, 8).0, [255, 255, 255, 255]);
+    }
+}
@@ -1,10 +1,11 @@
 use crate::{
-    AnyWindowHandle, AtlasKey, AtlasTextureId, AtlasTile, Bounds, DispatchEventResult, GPUSpecs,
-    Pixels, PlatformAtlas, PlatformDisplay, PlatformInput, PlatformInputHandler, PlatformWindow,
-    Point, RequestFrameOptions, ScaledPixels, Size, TestPlatform, TileId, WindowAppearance,
-    WindowBackgroundAppearance, WindowBounds, WindowParams,
+    render_scene, AnyWindowHandle, AtlasKey, AtlasTextureId, AtlasTile, Bounds, DevicePixels,
+    DispatchEventResult, GPUSpecs, Pixels, PlatformAtlas, PlatformDisplay, PlatformInput,
+    PlatformInputHandler, PlatformWindow, Point, RequestFrameOptions, ScaledPixels, Scene, Size,
+    TestPlatform, TileId, WindowAppearance, WindowBackgroundAppearance, WindowBounds, WindowParams,
 };
 use collections::HashMap;
+use image::RgbaImage;
 use parking_lot::Mutex;
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
 use std::{
@@ -19,7 +20,7 @@ pub(crate) struct TestWindowState {
     pub(crate) title: Option<String>,
     pub(crate) edited: bool,
     platform: Weak<TestPlatform>,
-    sprite_atlas: Arc<dyn PlatformAtlas>,
+    sprite_atlas: Arc<TestAtlas>,
     pub(crate) should_close_handler: Option<Box<dyn FnMut() -> bool>>,
     input_callback: Option<Box<dyn FnMut(PlatformInput) -> DispatchEventResult>>,
     active_status_change_callback: Option<Box<dyn FnMut(bool)>>,
@@ -28,6 +29,7 @@ pub(crate) struct TestWindowState {
     moved_callback: Option<Box<dyn FnMut()>>,
     input_handler: Option<PlatformInputHandler>,
     is_fullscreen: bool,
+    pub(crate) accessibility_active: bool,
 }
 
 #[derive(Clone)]
@@ -72,9 +74,21 @@ impl TestWindow {
             moved_callback: None,
             input_handler: None,
             is_fullscreen: false,
+            accessibility_active: false,
         })))
     }
 
+    /// Rasterizes a scene drawn in this window on the CPU, at the window's scale factor.
+    pub(crate) fn render_scene(&self, scene: &Scene) -> RgbaImage {
+        let size = self.content_size().scale(self.scale_factor());
+        let size = Size {
+            width: DevicePixels(size.width.0.ceil() as i32),
+            height: DevicePixels(size.height.0.ceil() as i32),
+        };
+        let atlas = self.0.lock().sprite_atlas.clone();
+        render_scene(scene, size, &atlas)
+    }
+
     pub fn simulate_resize(&mut self, size: Size<Pixels>) {
         let scale_factor = self.scale_factor();
         let mut lock = self.0.lock();
@@ -279,11 +293,22 @@ impl PlatformWindow for TestWindow {
     fn gpu_specs(&self) -> Option<GPUSpecs> {
         None
     }
+
+    fn is_accessibility_active(&self) -> bool {
+        self.0.lock().accessibility_active
+    }
 }
 
 pub(crate) struct TestAtlasState {
     next_id: u32,
     tiles: HashMap<AtlasKey, AtlasTile>,
+    textures: HashMap<u32, TestAtlasTexture>,
+}
+
+/// The pixels of a single tile, kept so that scenes can be rasterized in tests.
+pub(crate) struct TestAtlasTexture {
+    pub size: Size<DevicePixels>,
+    pub bytes: Vec<u8>,
 }
 
 pub(crate) struct TestAtlas(Mutex<TestAtlasState>);
@@ -293,8 +318,18 @@ impl TestAtlas {
         TestAtlas(Mutex::new(TestAtlasState {
             next_id: 0,
             tiles: HashMap::default(),
+            textures: HashMap::default(),
         }))
     }
+
+    /// Calls `f` with the pixels of the given texture, if it is still in the atlas.
+    pub(crate) fn with_texture<R>(
+        &self,
+        id: AtlasTextureId,
+        f: impl FnOnce(&TestAtlasTexture) -> R,
+    ) -> Option<R> {
+        self.0.lock().textures.get(&id.index).map(f)
+    }
 }
 
 impl PlatformAtlas for TestAtlas {
@@ -311,7 +346,7 @@ impl PlatformAtlas for TestAtlas {
         }
         drop(state);
 
-        let Some((size, _)) = build()? else {
+        let Some((size, bytes)) = build()? else {
             return Ok(None);
         };
 
@@ -326,7 +361,7 @@ impl PlatformAtlas for TestAtlas {
             crate::AtlasTile {
                 texture_id: AtlasTextureId {
                     index: texture_id,
-                    kind: crate::AtlasTextureKind::Path,
+                    kind: key.texture_kind(),
                 },
                 tile_id: TileId(tile_id),
                 padding: 0,
@@ -337,11 +372,21 @@ impl PlatformAtlas for TestAtlas {
             },
         );
 
+        state.textures.insert(
+            texture_id,
+            TestAtlasTexture {
+                size,
+                bytes: bytes.into_owned(),
+            },
+        );
+
         Ok(Some(state.tiles[key].clone()))
     }
 
     fn remove(&self, key: &AtlasKey) {
         let mut state = self.0.lock();
-        state.tiles.remove(key);
+        if let Some(tile) = state.tiles.remove(key) {
+            state.textures.remove(&tile.texture_id.index);
+        }
     }
 }
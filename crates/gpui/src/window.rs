@@ -1,20 +1,20 @@
 use crate::{
-    point, prelude::*, px, size, transparent_black, Action, AnyDrag, AnyElement, AnyTooltip,
-    AnyView, AppContext, Arena, Asset, AsyncWindowContext, AvailableSpace, Bounds, BoxShadow,
-    Context, Corners, CursorStyle, Decorations, DevicePixels, DispatchActionListener,
-    DispatchNodeId, DispatchTree, DisplayId, Edges, Effect, Entity, EntityId, EventEmitter,
-    FileDropEvent, Flatten, FontId, GPUSpecs, Global, GlobalElementId, GlyphId, Hsla, InputHandler,
-    IsZero, KeyBinding, KeyContext, KeyDownEvent, KeyEvent, Keystroke, KeystrokeEvent,
-    KeystrokeObserver, LayoutId, LineLayoutIndex, Model, ModelContext, Modifiers,
-    ModifiersChangedEvent, MonochromeSprite, MouseButton, MouseEvent, MouseMoveEvent, MouseUpEvent,
-    Path, Pixels, PlatformAtlas, PlatformDisplay, PlatformInput, PlatformInputHandler,
-    PlatformWindow, Point, PolychromeSprite, PromptLevel, Quad, Render, RenderGlyphParams,
-    RenderImage, RenderImageParams, RenderSvgParams, Replay, ResizeEdge, ScaledPixels, Scene,
-    Shadow, SharedString, Size, StrikethroughStyle, Style, SubscriberSet, Subscription,
-    TaffyLayoutEngine, Task, TextStyle, TextStyleRefinement, TransformationMatrix, Underline,
-    UnderlineStyle, View, VisualContext, WeakView, WindowAppearance, WindowBackgroundAppearance,
-    WindowBounds, WindowControls, WindowDecorations, WindowOptions, WindowParams, WindowTextSystem,
-    SUBPIXEL_VARIANTS,
+    point, prelude::*, px, size, transparent_black, AccessibilityOperation,
+    AccessibilityProperties, AccessibilityTree, Action, AnyDrag, AnyElement, AnyTooltip, AnyView,
+    AppContext, Arena, Asset, AsyncWindowContext, AvailableSpace, Bounds, BoxShadow, Context,
+    Corners, CursorStyle, Decorations, DevicePixels, DispatchActionListener, DispatchNodeId,
+    DispatchTree, DisplayId, Edges, Effect, Entity, EntityId, EventEmitter, FileDropEvent, Flatten,
+    FontId, GPUSpecs, Global, GlobalElementId, GlyphId, Hsla, InputHandler, IsZero, KeyBinding,
+    KeyContext, KeyDownEvent, KeyEvent, Keystroke, KeystrokeEvent, KeystrokeObserver, LayoutId,
+    LineLayoutIndex, Model, ModelContext, Modifiers, ModifiersChangedEvent, MonochromeSprite,
+    MouseButton, MouseEvent, MouseMoveEvent, MouseUpEvent, Path, Pixels, PlatformAtlas,
+    PlatformDisplay, PlatformInput, PlatformInputHandler, PlatformWindow, Point, PolychromeSprite,
+    PromptLevel, Quad, Render, RenderGlyphParams, RenderImage, RenderImageParams, RenderSvgParams,
+    Replay, ResizeEdge, ScaledPixels, Scene, Shadow, SharedString, Size, StrikethroughStyle, Style,
+    SubscriberSet, Subscription, TaffyLayoutEngine, Task, TextStyle, TextStyleRefinement,
+    TransformationMatrix, Underline, UnderlineStyle, View, VisualContext, WeakView,
+    WindowAppearance, WindowBackgroundAppearance, WindowBounds, WindowControls, WindowDecorations,
+    WindowOptions, WindowParams, WindowTextSystem, SUBPIXEL_VARIANTS,
 };
 use anyhow::{anyhow, Context as _, Result};
 use collections::{FxHashMap, FxHashSet};
@@ -408,6 +408,7 @@ pub(crate) struct Frame {
     pub(crate) input_handlers: Vec<Option<PlatformInputHandler>>,
     pub(crate) tooltip_requests: Vec<Option<TooltipRequest>>,
     pub(crate) cursor_styles: Vec<CursorStyleRequest>,
+    pub(crate) accessibility: Vec<AccessibilityOperation>,
     #[cfg(any(test, feature = "test-support"))]
     pub(crate) debug_bounds: FxHashMap<String, Bounds<Pixels>>,
 }
@@ -428,6 +429,7 @@ pub(crate) struct PaintIndex {
     mouse_listeners_index: usize,
     input_handlers_index: usize,
     cursor_styles_index: usize,
+    accessibility_index: usize,
     accessed_element_states_index: usize,
     line_layout_index: LineLayoutIndex,
 }
@@ -447,6 +449,7 @@ impl Frame {
             input_handlers: Vec::new(),
             tooltip_requests: Vec::new(),
             cursor_styles: Vec::new(),
+            accessibility: Vec::new(),
 
             #[cfg(any(test, feature = "test-support"))]
             debug_bounds: FxHashMap::default(),
@@ -462,6 +465,7 @@ impl Frame {
         self.input_handlers.clear();
         self.tooltip_requests.clear();
         self.cursor_styles.clear();
+        self.accessibility.clear();
         self.hitboxes.clear();
         self.deferred_draws.clear();
         self.focus = None;
@@ -548,6 +552,7 @@ pub struct Window {
     pub(crate) needs_present: Rc<Cell<bool>>,
     pub(crate) last_input_timestamp: Rc<Cell<Instant>>,
     pub(crate) refreshing: bool,
+    accessibility_active: bool,
     pub(crate) draw_phase: DrawPhase,
     activation_observers: SubscriberSet<(), AnyObserver>,
     pub(crate) focus: Option<FocusId>,
@@ -682,6 +687,10 @@ impl Window {
             let next_frame_callbacks = next_frame_callbacks.clone();
             let last_input_timestamp = last_input_timestamp.clone();
             move |request_frame_options| {
+                handle
+                    .update(&mut cx, |_, cx| cx.refresh_if_accessibility_activated())
+                    .log_err();
+
                 let next_frame_callbacks = next_frame_callbacks.take();
                 if !next_frame_callbacks.is_empty() {
                     handle
@@ -826,6 +835,7 @@ impl Window {
             needs_present,
             last_input_timestamp,
             refreshing: false,
+            accessibility_active: false,
             draw_phase: DrawPhase::None,
             activation_observers: SubscriberSet::new(),
             focus: None,
@@ -1458,6 +1468,10 @@ impl<'a> WindowContext<'a> {
         let previous_window_active = self.window.rendered_frame.window_active;
         mem::swap(&mut self.window.rendered_frame, &mut self.window.next_frame);
         self.window.next_frame.clear();
+        if self.is_accessibility_active() {
+            let tree = self.accessibility_tree();
+            self.window.platform_window.update_accessibility_tree(tree);
+        }
         let current_focus_path = self.window.rendered_frame.focus_path();
         let current_window_active = self.window.rendered_frame.window_active;
 
@@ -1730,6 +1744,7 @@ impl<'a> WindowContext<'a> {
             mouse_listeners_index: self.window.next_frame.mouse_listeners.len(),
             input_handlers_index: self.window.next_frame.input_handlers.len(),
             cursor_styles_index: self.window.next_frame.cursor_styles.len(),
+            accessibility_index: self.window.next_frame.accessibility.len(),
             accessed_element_states_index: self.window.next_frame.accessed_element_states.len(),
             line_layout_index: self.window.text_system.layout_index(),
         }
@@ -1744,6 +1759,12 @@ impl<'a> WindowContext<'a> {
                 .iter()
                 .cloned(),
         );
+        window.next_frame.accessibility.extend(
+            window.rendered_frame.accessibility
+                [range.start.accessibility_index..range.end.accessibility_index]
+                .iter()
+                .cloned(),
+        );
         window.next_frame.input_handlers.extend(
             window.rendered_frame.input_handlers
                 [range.start.input_handlers_index..range.end.input_handlers_index]
@@ -1801,6 +1822,67 @@ impl<'a> WindowContext<'a> {
         }
     }
 
+    /// Exposes an element to assistive technologies while painting it, with any accessible
+    /// elements painted by `f` becoming its children. When `properties` is `None`, `f` is
+    /// called without adding a node. This method should only be called during the paint phase
+    /// of element drawing.
+    pub fn with_accessibility_node<R>(
+        &mut self,
+        global_id: Option<&GlobalElementId>,
+        properties: Option<AccessibilityProperties>,
+        bounds: Bounds<Pixels>,
+        f: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        debug_assert_eq!(
+            self.window.draw_phase,
+            DrawPhase::Paint,
+            "this method can only be called during paint"
+        );
+        let Some(properties) = properties else {
+            return f(self);
+        };
+
+        self.window
+            .next_frame
+            .accessibility
+            .push(AccessibilityOperation::push(global_id, properties, bounds));
+        let result = f(self);
+        self.window
+            .next_frame
+            .accessibility
+            .push(AccessibilityOperation::Pop);
+        result
+    }
+
+    /// Whether an assistive technology is reading this window's accessibility tree. Elements
+    /// can check this to avoid computing expensive properties, such as the full text of a
+    /// document, when nobody is listening.
+    pub fn is_accessibility_active(&self) -> bool {
+        self.window.platform_window.is_accessibility_active()
+    }
+
+    /// Assistive technologies can start reading the window at any time, and elements skip their
+    /// accessibility properties while nobody is listening, so the window is redrawn as soon as
+    /// that changes rather than on the next unrelated update.
+    pub(crate) fn refresh_if_accessibility_activated(&mut self) {
+        let active = self.is_accessibility_active();
+        if active && !self.window.accessibility_active {
+            self.refresh();
+        }
+        self.window.accessibility_active = active;
+    }
+
+    /// Returns the tree of accessible elements painted in the most recent frame.
+    pub fn accessibility_tree(&self) -> AccessibilityTree {
+        AccessibilityTree::build(
+            &self.window.rendered_frame.accessibility,
+            Bounds {
+                origin: Point::default(),
+                size: self.window.viewport_size,
+            },
+        )
+    }
+
     /// Updates the cursor style at the platform level. This method should only be called
     /// during the prepaint phase of element drawing.
     pub fn set_cursor_style(&mut self, style: CursorStyle, hitbox: &Hitbox) {
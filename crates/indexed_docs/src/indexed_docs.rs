@@ -6,7 +6,12 @@ mod store;
 use gpui::AppContext;
 
 pub use crate::extension_indexed_docs_provider::ExtensionIndexedDocsProvider;
+pub use crate::providers::html::*;
+pub use crate::providers::markdown::*;
+pub use crate::providers::node_modules::*;
+pub use crate::providers::python::*;
 pub use crate::providers::rustdoc::*;
+pub use crate::providers::LocalDocsRoots;
 pub use crate::registry::*;
 pub use crate::store::*;
 
@@ -1 +1,32 @@
+pub mod html;
+pub mod markdown;
+pub mod node_modules;
+pub mod python;
 pub mod rustdoc;
+mod walk;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+
+/// The directories in which the local docs providers look for docs.
+///
+/// Providers are registered once for the whole app, so the roots are shared with whoever
+/// registered them, who keeps them up to date with the project the docs are requested from.
+#[derive(Clone, Default)]
+pub struct LocalDocsRoots(Arc<RwLock<Vec<PathBuf>>>);
+
+impl LocalDocsRoots {
+    pub fn new(roots: Vec<PathBuf>) -> Self {
+        Self(Arc::new(RwLock::new(roots)))
+    }
+
+    pub fn set(&self, roots: Vec<PathBuf>) {
+        *self.0.write() = roots;
+    }
+
+    pub fn get(&self) -> Vec<PathBuf> {
+        self.0.read().clone()
+    }
+}
@@ -0,0 +1,274 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use collections::{HashSet, VecDeque};
+use fs::Fs;
+use html_to_markdown::markdown::{
+    CodeHandler, HeadingHandler, ListHandler, ParagraphHandler, StyledTextHandler, TableHandler,
+    WebpageChromeRemover,
+};
+use html_to_markdown::{
+    convert_html_to_markdown, HandleTag, HtmlElement, MarkdownWriter, StartTagOutcome, TagHandler,
+};
+
+use crate::providers::walk::walk_files;
+use crate::{IndexedDocsDatabase, IndexedDocsProvider, LocalDocsRoots, PackageName, ProviderId};
+
+/// The maximum number of pages crawled for a single site.
+const MAX_PAGES_PER_SITE: usize = 2000;
+
+/// Indexes static HTML documentation sites found on disk, such as the output of Sphinx,
+/// Javadoc or TypeDoc.
+///
+/// Any directory within the given roots that contains an `index.html` is treated as a site,
+/// named after the directory. Pages are crawled by following relative links starting at
+/// `index.html`.
+pub struct LocalHtmlDocsProvider {
+    fs: Arc<dyn Fs>,
+    roots: LocalDocsRoots,
+}
+
+impl LocalHtmlDocsProvider {
+    pub fn id() -> ProviderId {
+        ProviderId("html".into())
+    }
+
+    pub fn new(fs: Arc<dyn Fs>, roots: LocalDocsRoots) -> Self {
+        Self { fs, roots }
+    }
+
+    async fn sites(&self) -> Result<BTreeMap<PackageName, PathBuf>> {
+        let mut sites = BTreeMap::new();
+        for root in &self.roots.get() {
+            let mut index_files = walk_files(self.fs.as_ref(), root, 4, |path| {
+                path.file_name().map_or(false, |name| name == "index.html")
+            })
+            .await?;
+            index_files.sort_by_key(|path| path.components().count());
+
+            for index_file in index_files {
+                let Some(site_root) = index_file.parent() else {
+                    continue;
+                };
+                let Some(name) = site_root.file_name().and_then(|name| name.to_str()) else {
+                    continue;
+                };
+
+                // Nested sites are usually sections of an enclosing site, so prefer the outermost.
+                if sites
+                    .values()
+                    .any(|existing_root: &PathBuf| site_root.starts_with(existing_root))
+                {
+                    continue;
+                }
+                sites
+                    .entry(PackageName::from(name))
+                    .or_insert_with(|| site_root.to_path_buf());
+            }
+        }
+        Ok(sites)
+    }
+}
+
+#[async_trait]
+impl IndexedDocsProvider for LocalHtmlDocsProvider {
+    fn id(&self) -> ProviderId {
+        Self::id()
+    }
+
+    fn database_path(&self) -> PathBuf {
+        paths::support_dir().join("docs/html/html-db.0.mdb")
+    }
+
+    async fn suggest_packages(&self) -> Result<Vec<PackageName>> {
+        Ok(self.sites().await?.into_keys().collect())
+    }
+
+    async fn index(&self, package: PackageName, database: Arc<IndexedDocsDatabase>) -> Result<()> {
+        let sites = self.sites().await?;
+        let site_root = sites
+            .get(&package)
+            .ok_or_else(|| anyhow!("no HTML docs site named '{package}' found in the project"))?;
+
+        let index_page = PathBuf::from("index.html");
+        let mut seen_pages = HashSet::from_iter([index_page.clone()]);
+        let mut pages_to_visit = VecDeque::from_iter([index_page]);
+        let mut visited_page_count = 0;
+        while let Some(page) = pages_to_visit.pop_front() {
+            if visited_page_count >= MAX_PAGES_PER_SITE {
+                break;
+            }
+            visited_page_count += 1;
+
+            let Ok(html) = self.fs.load(&site_root.join(&page)).await else {
+                continue;
+            };
+            let (markdown, links) = convert_page_to_markdown(&html)
+                .with_context(|| format!("failed to convert {page:?} to Markdown"))?;
+
+            database
+                .insert(key_for_page(&package, &page), markdown)
+                .await?;
+
+            for link in links {
+                if let Some(linked_page) = resolve_link(&page, &link) {
+                    if seen_pages.insert(linked_page.clone()) {
+                        pages_to_visit.push_back(linked_page);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Collects the `href`s of all links on a page.
+#[derive(Default)]
+struct LinkCollector {
+    links: Vec<String>,
+}
+
+impl HandleTag for LinkCollector {
+    fn should_handle(&self, tag: &str) -> bool {
+        tag == "a"
+    }
+
+    fn handle_tag_start(
+        &mut self,
+        tag: &HtmlElement,
+        _writer: &mut MarkdownWriter,
+    ) -> StartTagOutcome {
+        if let Some(href) = tag.attr("href") {
+            self.links.push(href);
+        }
+
+        StartTagOutcome::Continue
+    }
+}
+
+fn convert_page_to_markdown(html: &str) -> Result<(String, Vec<String>)> {
+    let link_collector = Rc::new(RefCell::new(LinkCollector::default()));
+    let mut handlers: Vec<TagHandler> = vec![
+        Rc::new(RefCell::new(WebpageChromeRemover)),
+        Rc::new(RefCell::new(ParagraphHandler)),
+        Rc::new(RefCell::new(HeadingHandler)),
+        Rc::new(RefCell::new(ListHandler)),
+        Rc::new(RefCell::new(TableHandler::new())),
+        Rc::new(RefCell::new(StyledTextHandler)),
+        Rc::new(RefCell::new(CodeHandler)),
+        link_collector.clone(),
+    ];
+
+    let markdown = convert_html_to_markdown(html.as_bytes(), &mut handlers)?;
+    let links = std::mem::take(&mut link_collector.borrow_mut().links);
+    Ok((markdown, links))
+}
+
+/// Resolves a link on the given page to another page within the same site.
+///
+/// Returns `None` for external links, in-page anchors, and links that leave the site.
+fn resolve_link(page: &Path, href: &str) -> Option<PathBuf> {
+    let href = href.split(['#', '?']).next()?;
+    if href.is_empty() || href.contains(':') || href.starts_with('/') {
+        return None;
+    }
+
+    let mut path = page.parent().map(Path::to_path_buf).unwrap_or_default();
+    for component in href.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                if !path.pop() {
+                    return None;
+                }
+            }
+            component => path.push(component),
+        }
+    }
+    if href.ends_with('/') {
+        path.push("index.html");
+    }
+
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("html" | "htm") => Some(path),
+        _ => None,
+    }
+}
+
+fn key_for_page(package: &PackageName, page: &Path) -> String {
+    let mut components = page
+        .with_extension("")
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    if components.last().map_or(false, |last| last == "index") {
+        components.pop();
+    }
+
+    if components.is_empty() {
+        package.to_string()
+    } else {
+        format!("{package}/{}", components.join("/"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_link() {
+        let page = Path::new("guide/intro.html");
+        assert_eq!(
+            resolve_link(page, "setup.html#install"),
+            Some(PathBuf::from("guide/setup.html"))
+        );
+        assert_eq!(
+            resolve_link(page, "../api/index.html?v=2"),
+            Some(PathBuf::from("api/index.html"))
+        );
+        assert_eq!(
+            resolve_link(page, "./reference/"),
+            Some(PathBuf::from("guide/reference/index.html"))
+        );
+        assert_eq!(resolve_link(page, "../../outside.html"), None);
+        assert_eq!(resolve_link(page, "https://example.com/a.html"), None);
+        assert_eq!(resolve_link(page, "mailto:someone@example.com"), None);
+        assert_eq!(resolve_link(page, "#section"), None);
+        assert_eq!(resolve_link(page, "/absolute.html"), None);
+        assert_eq!(resolve_link(page, "style.css"), None);
+    }
+
+    #[test]
+    fn test_key_for_page() {
+        let package = PackageName::from("mysite");
+        assert_eq!(key_for_page(&package, Path::new("index.html")), "mysite");
+        assert_eq!(
+            key_for_page(&package, Path::new("guide/index.html")),
+            "mysite/guide"
+        );
+        assert_eq!(
+            key_for_page(&package, Path::new("guide/intro.html")),
+            "mysite/guide/intro"
+        );
+    }
+
+    #[test]
+    fn test_convert_page_to_markdown() {
+        let (markdown, links) = convert_page_to_markdown(
+            r#"<html><head><title>Docs</title></head><body>
+            <h1>Welcome</h1>
+            <p>Read the <a href="guide.html">guide</a>.</p>
+            </body></html>"#,
+        )
+        .unwrap();
+        assert!(markdown.contains("# Welcome"));
+        assert_eq!(links, vec!["guide.html".to_string()]);
+    }
+}
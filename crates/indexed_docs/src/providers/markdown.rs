@@ -0,0 +1,187 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use fs::Fs;
+
+use crate::providers::walk::{key_path, walk_files};
+use crate::{IndexedDocsDatabase, IndexedDocsProvider, LocalDocsRoots, PackageName, ProviderId};
+
+/// The maximum number of Markdown files indexed for a single book.
+const MAX_FILES_PER_BOOK: usize = 1000;
+
+/// The names of directories that are indexed as books even without a `book.toml`.
+const DOCS_DIR_NAMES: &[&str] = &["docs", "doc"];
+
+/// The names of the files that are used as the overview of a book, in order of preference.
+const OVERVIEW_FILE_NAMES: &[&str] = &["README", "index", "SUMMARY"];
+
+/// Indexes Markdown documentation in the project's worktrees.
+///
+/// Every directory containing an mdBook `book.toml` is indexed as a book named after that
+/// directory, as are top-level `docs` directories.
+pub struct MarkdownDocsProvider {
+    fs: Arc<dyn Fs>,
+    roots: LocalDocsRoots,
+}
+
+impl MarkdownDocsProvider {
+    pub fn id() -> ProviderId {
+        ProviderId("markdown".into())
+    }
+
+    pub fn new(fs: Arc<dyn Fs>, roots: LocalDocsRoots) -> Self {
+        Self { fs, roots }
+    }
+
+    /// Returns the directories containing the Markdown sources of each book.
+    async fn books(&self) -> Result<BTreeMap<PackageName, PathBuf>> {
+        let mut books = BTreeMap::new();
+        for root in &self.roots.get() {
+            let book_tomls = walk_files(self.fs.as_ref(), root, 3, |path| {
+                path.file_name().map_or(false, |name| name == "book.toml")
+            })
+            .await?;
+
+            for book_toml in book_tomls {
+                let Some(book_dir) = book_toml.parent() else {
+                    continue;
+                };
+                let Some(name) = book_dir.file_name().and_then(|name| name.to_str()) else {
+                    continue;
+                };
+                let src_dir = self
+                    .fs
+                    .load(&book_toml)
+                    .await
+                    .ok()
+                    .and_then(|book_toml| mdbook_src_dir(&book_toml))
+                    .unwrap_or_else(|| "src".to_string());
+                books
+                    .entry(PackageName::from(name))
+                    .or_insert_with(|| book_dir.join(src_dir));
+            }
+
+            for docs_dir_name in DOCS_DIR_NAMES {
+                let docs_dir = root.join(docs_dir_name);
+                if self.fs.is_dir(&docs_dir).await
+                    && !books.values().any(|src_dir| src_dir.starts_with(&docs_dir))
+                {
+                    books
+                        .entry(PackageName::from(*docs_dir_name))
+                        .or_insert(docs_dir);
+                }
+            }
+        }
+        Ok(books)
+    }
+}
+
+#[async_trait]
+impl IndexedDocsProvider for MarkdownDocsProvider {
+    fn id(&self) -> ProviderId {
+        Self::id()
+    }
+
+    fn database_path(&self) -> PathBuf {
+        paths::support_dir().join("docs/markdown/markdown-db.0.mdb")
+    }
+
+    async fn suggest_packages(&self) -> Result<Vec<PackageName>> {
+        Ok(self.books().await?.into_keys().collect())
+    }
+
+    async fn index(&self, package: PackageName, database: Arc<IndexedDocsDatabase>) -> Result<()> {
+        let books = self.books().await?;
+        let src_dir = books
+            .get(&package)
+            .ok_or_else(|| anyhow!("no Markdown book named '{package}' found in the project"))?;
+
+        let markdown_files = walk_files(self.fs.as_ref(), src_dir, 8, |path| {
+            path.extension()
+                .map_or(false, |extension| extension == "md")
+        })
+        .await?;
+
+        let mut overview = None;
+        for markdown_file in markdown_files.into_iter().take(MAX_FILES_PER_BOOK) {
+            let Some(key_path) = key_path(src_dir, &markdown_file, ".md") else {
+                continue;
+            };
+            let Ok(markdown) = self.fs.load(&markdown_file).await else {
+                continue;
+            };
+
+            if let Some(priority) = OVERVIEW_FILE_NAMES
+                .iter()
+                .position(|name| *name == key_path)
+            {
+                if overview
+                    .as_ref()
+                    .map_or(true, |(overview_priority, _)| priority < *overview_priority)
+                {
+                    overview = Some((priority, markdown.clone()));
+                }
+            }
+
+            database
+                .insert(format!("{package}/{key_path}"), markdown)
+                .await?;
+        }
+
+        if let Some((_, overview)) = overview {
+            database.insert(package.to_string(), overview).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the `src` directory configured in the `[book]` table of an mdBook `book.toml`.
+fn mdbook_src_dir(book_toml: &str) -> Option<String> {
+    let mut in_book_table = false;
+    for line in book_toml.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_book_table = line == "[book]";
+        } else if in_book_table {
+            if let Some((key, value)) = line.split_once('=') {
+                if key.trim() == "src" {
+                    return Some(value.trim().trim_matches(['"', '\'']).to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_mdbook_src_dir() {
+        assert_eq!(
+            mdbook_src_dir(indoc! {r#"
+                [book]
+                title = "The Book"
+                src = "chapters"
+
+                [build]
+                src = "ignored"
+            "#}),
+            Some("chapters".to_string())
+        );
+        assert_eq!(
+            mdbook_src_dir(indoc! {r#"
+                [book]
+                title = "The Book"
+            "#}),
+            None
+        );
+    }
+}
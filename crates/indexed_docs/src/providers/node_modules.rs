@@ -0,0 +1,203 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use fs::Fs;
+use serde::Deserialize;
+
+use crate::providers::walk::{child_dirs, key_path, walk_files};
+use crate::{IndexedDocsDatabase, IndexedDocsProvider, LocalDocsRoots, PackageName, ProviderId};
+
+/// The maximum number of type declaration files indexed for a single package.
+const MAX_DECLARATION_FILES_PER_PACKAGE: usize = 200;
+
+/// Indexes the READMEs and TypeScript declarations of packages installed in the project's
+/// `node_modules` directories.
+///
+/// Scoped packages are named using the same convention as DefinitelyTyped, so `@scope/name`
+/// becomes `scope__name`.
+pub struct NodeModulesDocsProvider {
+    fs: Arc<dyn Fs>,
+    roots: LocalDocsRoots,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PackageJson {
+    name: Option<String>,
+    version: Option<String>,
+    description: Option<String>,
+}
+
+impl NodeModulesDocsProvider {
+    pub fn id() -> ProviderId {
+        ProviderId("npm".into())
+    }
+
+    pub fn new(fs: Arc<dyn Fs>, roots: LocalDocsRoots) -> Self {
+        Self { fs, roots }
+    }
+
+    async fn packages(&self) -> BTreeMap<PackageName, PathBuf> {
+        let mut packages = BTreeMap::new();
+        for root in &self.roots.get() {
+            for dir in child_dirs(self.fs.as_ref(), &root.join("node_modules")).await {
+                let Some(dir_name) = dir.file_name().and_then(|name| name.to_str()) else {
+                    continue;
+                };
+
+                if let Some(scope) = dir_name.strip_prefix('@') {
+                    for scoped_dir in child_dirs(self.fs.as_ref(), &dir).await {
+                        if let Some(name) = scoped_dir.file_name().and_then(|name| name.to_str()) {
+                            packages
+                                .entry(PackageName::from(format!("{scope}__{name}").as_str()))
+                                .or_insert(scoped_dir);
+                        }
+                    }
+                } else if !dir_name.starts_with('.') {
+                    packages.entry(PackageName::from(dir_name)).or_insert(dir);
+                }
+            }
+        }
+        packages
+    }
+
+    async fn load_readme(&self, package_dir: &Path) -> Option<String> {
+        for file_name in [
+            "README.md",
+            "readme.md",
+            "Readme.md",
+            "README.markdown",
+            "README",
+        ] {
+            if let Ok(readme) = self.fs.load(&package_dir.join(file_name)).await {
+                return Some(readme);
+            }
+        }
+        None
+    }
+}
+
+#[async_trait]
+impl IndexedDocsProvider for NodeModulesDocsProvider {
+    fn id(&self) -> ProviderId {
+        Self::id()
+    }
+
+    fn database_path(&self) -> PathBuf {
+        paths::support_dir().join("docs/npm/node-modules-db.0.mdb")
+    }
+
+    async fn suggest_packages(&self) -> Result<Vec<PackageName>> {
+        Ok(self.packages().await.into_keys().collect())
+    }
+
+    async fn index(&self, package: PackageName, database: Arc<IndexedDocsDatabase>) -> Result<()> {
+        let packages = self.packages().await;
+        let package_dir = packages
+            .get(&package)
+            .ok_or_else(|| anyhow!("package '{package}' is not installed in node_modules"))?;
+
+        let package_json = self
+            .fs
+            .load(&package_dir.join("package.json"))
+            .await
+            .ok()
+            .and_then(|package_json| serde_json::from_str::<PackageJson>(&package_json).ok())
+            .unwrap_or_default();
+
+        let mut overview = format!(
+            "# {}",
+            package_json.name.as_deref().unwrap_or(package.as_ref())
+        );
+        if let Some(version) = &package_json.version {
+            overview.push_str(&format!(" {version}"));
+        }
+        overview.push_str("\n\n");
+        if let Some(description) = &package_json.description {
+            overview.push_str(description);
+            overview.push_str("\n\n");
+        }
+        if let Some(readme) = self.load_readme(package_dir).await {
+            overview.push_str(&readme);
+        }
+        database.insert(package.to_string(), overview).await?;
+
+        let declaration_files = walk_files(self.fs.as_ref(), package_dir, 4, |path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .map_or(false, |name| name.ends_with(".d.ts"))
+        })
+        .await?;
+
+        for declaration_file in declaration_files
+            .into_iter()
+            .take(MAX_DECLARATION_FILES_PER_PACKAGE)
+        {
+            let Some(key_path) = key_path(package_dir, &declaration_file, ".d.ts") else {
+                continue;
+            };
+            let Ok(declarations) = self.fs.load(&declaration_file).await else {
+                continue;
+            };
+
+            database
+                .insert(
+                    format!("{package}/{key_path}"),
+                    format!("```ts\n{}\n```", declarations.trim_end()),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use serde_json::json;
+
+    use super::*;
+
+    #[gpui::test]
+    async fn test_packages_follow_roots(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/projects",
+            json!({
+                "web": {
+                    "node_modules": {
+                        "left-pad": { "package.json": "{}" },
+                        "@types": { "node": { "package.json": "{}" } },
+                    },
+                },
+                "api": {
+                    "node_modules": {
+                        "express": { "package.json": "{}" },
+                    },
+                },
+            }),
+        )
+        .await;
+
+        let roots = LocalDocsRoots::new(vec![PathBuf::from("/projects/web")]);
+        let provider = NodeModulesDocsProvider::new(fs, roots.clone());
+        assert_eq!(
+            provider.suggest_packages().await.unwrap(),
+            [
+                PackageName::from("left-pad"),
+                PackageName::from("types__node")
+            ]
+        );
+
+        // The provider picks up changes to the roots it was created with.
+        roots.set(vec![PathBuf::from("/projects/api")]);
+        assert_eq!(
+            provider.suggest_packages().await.unwrap(),
+            [PackageName::from("express")]
+        );
+    }
+}
@@ -0,0 +1,369 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use fs::Fs;
+
+use crate::providers::walk::{child_dirs, walk_files};
+use crate::{IndexedDocsDatabase, IndexedDocsProvider, LocalDocsRoots, PackageName, ProviderId};
+
+/// The maximum number of modules indexed for a single package.
+const MAX_MODULES_PER_PACKAGE: usize = 500;
+
+/// The names of the virtual environment directories that are searched for `site-packages`.
+const VIRTUAL_ENV_DIRS: &[&str] = &[".venv", "venv", "env", ".env"];
+
+/// Indexes docstrings of Python packages installed in the project's virtual environments.
+///
+/// Docstrings are extracted from the source without running Python, so indexing works even
+/// when no interpreter is available.
+pub struct PythonDocsProvider {
+    fs: Arc<dyn Fs>,
+    roots: LocalDocsRoots,
+}
+
+impl PythonDocsProvider {
+    pub fn id() -> ProviderId {
+        ProviderId("python".into())
+    }
+
+    pub fn new(fs: Arc<dyn Fs>, roots: LocalDocsRoots) -> Self {
+        Self { fs, roots }
+    }
+
+    async fn site_packages_dirs(&self) -> Vec<PathBuf> {
+        let mut site_packages_dirs = Vec::new();
+        for root in &self.roots.get() {
+            for virtual_env_dir in VIRTUAL_ENV_DIRS {
+                let virtual_env_dir = root.join(virtual_env_dir);
+
+                // Windows virtual environments don't include the Python version in the path.
+                let windows_site_packages = virtual_env_dir.join("Lib").join("site-packages");
+                if self.fs.is_dir(&windows_site_packages).await {
+                    site_packages_dirs.push(windows_site_packages);
+                }
+
+                for python_dir in child_dirs(self.fs.as_ref(), &virtual_env_dir.join("lib")).await {
+                    let site_packages = python_dir.join("site-packages");
+                    if self.fs.is_dir(&site_packages).await {
+                        site_packages_dirs.push(site_packages);
+                    }
+                }
+            }
+        }
+        site_packages_dirs
+    }
+
+    async fn packages(&self) -> BTreeMap<PackageName, PathBuf> {
+        let mut packages = BTreeMap::new();
+        for site_packages in self.site_packages_dirs().await {
+            for dir in child_dirs(self.fs.as_ref(), &site_packages).await {
+                let Some(name) = dir.file_name().and_then(|name| name.to_str()) else {
+                    continue;
+                };
+                if name.starts_with('_') || name.contains('.') {
+                    continue;
+                }
+                if self.fs.is_file(&dir.join("__init__.py")).await {
+                    packages.entry(PackageName::from(name)).or_insert(dir);
+                }
+            }
+        }
+        packages
+    }
+}
+
+#[async_trait]
+impl IndexedDocsProvider for PythonDocsProvider {
+    fn id(&self) -> ProviderId {
+        Self::id()
+    }
+
+    fn database_path(&self) -> PathBuf {
+        paths::support_dir().join("docs/python/site-packages-db.0.mdb")
+    }
+
+    async fn suggest_packages(&self) -> Result<Vec<PackageName>> {
+        Ok(self.packages().await.into_keys().collect())
+    }
+
+    async fn index(&self, package: PackageName, database: Arc<IndexedDocsDatabase>) -> Result<()> {
+        let packages = self.packages().await;
+        let package_dir = packages.get(&package).ok_or_else(|| {
+            anyhow!("package '{package}' is not installed in a virtual environment")
+        })?;
+
+        let module_files = walk_files(self.fs.as_ref(), package_dir, 6, |path| {
+            path.extension()
+                .map_or(false, |extension| extension == "py")
+        })
+        .await?;
+
+        for module_file in module_files.into_iter().take(MAX_MODULES_PER_PACKAGE) {
+            let Some(module_name) = module_name(&package, package_dir, &module_file) else {
+                continue;
+            };
+            let Ok(source) = self.fs.load(&module_file).await else {
+                continue;
+            };
+
+            if let Some(markdown) = python_docs_to_markdown(&module_name, &source) {
+                database.insert(module_name, markdown).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the dotted module name for a file within a package, e.g. `requests.adapters`.
+fn module_name(package: &PackageName, package_dir: &Path, module_file: &Path) -> Option<String> {
+    let relative_path = module_file
+        .strip_prefix(package_dir)
+        .ok()?
+        .with_extension("");
+    let mut components = vec![package.to_string()];
+    for component in relative_path.components() {
+        let component = component.as_os_str().to_str()?;
+        if component != "__init__" {
+            components.push(component.to_string());
+        }
+    }
+    Some(components.join("."))
+}
+
+/// Renders the docstrings of a module and its public classes and functions as Markdown.
+///
+/// Returns `None` if the module contains neither a docstring nor any public definitions.
+fn python_docs_to_markdown(module_name: &str, source: &str) -> Option<String> {
+    let lines = source.lines().collect::<Vec<_>>();
+    let mut markdown = format!("# `{module_name}`\n\n");
+    let mut has_content = false;
+
+    let first_statement = lines
+        .iter()
+        .position(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'));
+    if let Some((docstring, _)) = first_statement.and_then(|ix| parse_docstring(&lines, ix)) {
+        markdown.push_str(&docstring);
+        markdown.push_str("\n\n");
+        has_content = true;
+    }
+
+    let mut ix = 0;
+    while ix < lines.len() {
+        let line = lines[ix];
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        let definition = trimmed
+            .strip_prefix("async def ")
+            .or_else(|| trimmed.strip_prefix("def "))
+            .or_else(|| trimmed.strip_prefix("class "));
+        let Some(definition) = definition else {
+            ix += 1;
+            continue;
+        };
+
+        let name = definition
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .next()
+            .unwrap_or_default();
+        let (signature, signature_end) = parse_signature(&lines, ix);
+        ix = signature_end + 1;
+        if name.is_empty() || (name.starts_with('_') && name != "__init__") {
+            continue;
+        }
+
+        let heading = if indent == 0 { "##" } else { "###" };
+        markdown.push_str(&format!("{heading} `{signature}`\n\n"));
+        has_content = true;
+
+        let body_start = (ix..lines.len()).find(|ix| !lines[*ix].trim().is_empty());
+        if let Some((docstring, docstring_end)) =
+            body_start.and_then(|body_start| parse_docstring(&lines, body_start))
+        {
+            markdown.push_str(&docstring);
+            markdown.push_str("\n\n");
+            ix = docstring_end + 1;
+        }
+    }
+
+    has_content.then(|| markdown.trim_end().to_string())
+}
+
+/// Returns the signature of the definition starting at `start`, which may span several lines,
+/// along with the index of its last line.
+fn parse_signature(lines: &[&str], start: usize) -> (String, usize) {
+    let mut signature = String::new();
+    let mut depth = 0i32;
+    for (ix, line) in lines.iter().enumerate().skip(start).take(20) {
+        let line = line.split(" #").next().unwrap_or_default().trim();
+        for c in line.chars() {
+            match c {
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth -= 1,
+                _ => {}
+            }
+        }
+
+        if !signature.is_empty() && !signature.ends_with('(') && !line.starts_with(')') {
+            signature.push(' ');
+        }
+        signature.push_str(line);
+
+        if depth <= 0 && line.ends_with(':') {
+            signature.pop();
+            return (signature, ix);
+        }
+    }
+    (signature, start)
+}
+
+/// Parses a docstring that starts on the given line, returning its dedented contents and the
+/// index of its last line.
+fn parse_docstring(lines: &[&str], start: usize) -> Option<(String, usize)> {
+    let first_line = lines[start].trim();
+    let first_line = first_line.trim_start_matches(['r', 'R', 'u', 'U']);
+    let quote = if first_line.starts_with("\"\"\"") {
+        "\"\"\""
+    } else if first_line.starts_with("'''") {
+        "'''"
+    } else {
+        return None;
+    };
+
+    let rest = &first_line[quote.len()..];
+    if let Some(end) = rest.find(quote) {
+        return Some((rest[..end].trim().to_string(), start));
+    }
+
+    let mut docstring_lines = vec![rest];
+    for (ix, line) in lines.iter().enumerate().skip(start + 1) {
+        if let Some(end) = line.find(quote) {
+            docstring_lines.push(&line[..end]);
+            return Some((dedent(&docstring_lines), ix));
+        }
+        docstring_lines.push(line);
+    }
+    None
+}
+
+fn dedent(lines: &[&str]) -> String {
+    let indent = lines
+        .iter()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    let mut text = lines[0].trim().to_string();
+    for line in &lines[1..] {
+        text.push('\n');
+        text.push_str(line.get(indent..).unwrap_or(line.trim_start()).trim_end());
+    }
+    text.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_module_name() {
+        let package = PackageName::from("requests");
+        let package_dir = Path::new("/venv/lib/python3.12/site-packages/requests");
+        assert_eq!(
+            module_name(&package, package_dir, &package_dir.join("__init__.py")),
+            Some("requests".to_string())
+        );
+        assert_eq!(
+            module_name(&package, package_dir, &package_dir.join("adapters.py")),
+            Some("requests.adapters".to_string())
+        );
+        assert_eq!(
+            module_name(
+                &package,
+                package_dir,
+                &package_dir.join("packages/__init__.py")
+            ),
+            Some("requests.packages".to_string())
+        );
+    }
+
+    #[test]
+    fn test_python_docs_to_markdown() {
+        let source = indoc! {r#"
+            # Copyright notice.
+            """Utilities for sessions.
+
+            Sessions persist parameters across requests.
+            """
+
+            import os
+
+
+            class Session:
+                """A Requests session."""
+
+                def __init__(self):
+                    self.headers = {}
+
+                def request(
+                    self,
+                    method,
+                    url,
+                ) -> Response:
+                    '''
+                    Constructs a request.
+
+                        Indented example.
+                    '''
+                    pass
+
+                def _private(self):
+                    """Not included."""
+
+
+            async def fetch(url: str) -> bytes:  # comment
+                return b""
+        "#};
+
+        assert_eq!(
+            python_docs_to_markdown("requests.sessions", source).unwrap(),
+            indoc! {"
+                # `requests.sessions`
+
+                Utilities for sessions.
+
+                Sessions persist parameters across requests.
+
+                ## `class Session`
+
+                A Requests session.
+
+                ### `def __init__(self)`
+
+                ### `def request(self, method, url,) -> Response`
+
+                Constructs a request.
+
+                    Indented example.
+
+                ## `async def fetch(url: str) -> bytes`"
+            }
+        );
+    }
+
+    #[test]
+    fn test_python_docs_to_markdown_without_content() {
+        assert_eq!(
+            python_docs_to_markdown("pkg._internal", "import os\n\ndef _helper():\n    pass\n"),
+            None
+        );
+    }
+}
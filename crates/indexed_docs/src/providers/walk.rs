@@ -0,0 +1,114 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use fs::Fs;
+use futures::StreamExt;
+
+/// Directories that are never descended into when looking for documentation.
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "__pycache__"];
+
+/// Returns the files beneath `root` for which `include` returns `true`.
+///
+/// Hidden directories and dependency/build directories are skipped, and the walk stops
+/// `max_depth` directories below `root`.
+pub(crate) async fn walk_files(
+    fs: &dyn Fs,
+    root: &Path,
+    max_depth: usize,
+    include: impl Fn(&Path) -> bool,
+) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut dirs_to_visit = vec![(root.to_path_buf(), 0)];
+    while let Some((dir, depth)) = dirs_to_visit.pop() {
+        let mut entries = match fs.read_dir(&dir).await {
+            Ok(entries) => entries,
+            Err(err) if depth == 0 => return Err(err),
+            Err(_) => continue,
+        };
+
+        while let Some(entry) = entries.next().await {
+            let Ok(path) = entry else {
+                continue;
+            };
+
+            if fs.is_dir(&path).await {
+                let is_skipped = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .map_or(true, |name| {
+                        name.starts_with('.') || SKIPPED_DIRS.contains(&name)
+                    });
+                if depth < max_depth && !is_skipped {
+                    dirs_to_visit.push((path, depth + 1));
+                }
+            } else if include(&path) {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Returns the immediate subdirectories of `dir`, sorted by path.
+pub(crate) async fn child_dirs(fs: &dyn Fs, dir: &Path) -> Vec<PathBuf> {
+    let Ok(mut entries) = fs.read_dir(dir).await else {
+        return Vec::new();
+    };
+
+    let mut dirs = Vec::new();
+    while let Some(entry) = entries.next().await {
+        if let Ok(path) = entry {
+            if fs.is_dir(&path).await {
+                dirs.push(path);
+            }
+        }
+    }
+    dirs.sort();
+    dirs
+}
+
+/// Returns the path of `path` relative to `root`, with `/` separators and without the
+/// given extension, for use in docs keys.
+pub(crate) fn key_path(root: &Path, path: &Path, extension: &str) -> Option<String> {
+    let relative_path = path.strip_prefix(root).ok()?;
+    let mut components = relative_path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    let file_name = components.pop()?;
+    components.push(
+        file_name
+            .strip_suffix(extension)
+            .unwrap_or(&file_name)
+            .to_string(),
+    );
+    Some(components.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_path() {
+        assert_eq!(
+            key_path(
+                Path::new("/book/src"),
+                Path::new("/book/src/guide/intro.md"),
+                ".md"
+            ),
+            Some("guide/intro".to_string())
+        );
+        assert_eq!(
+            key_path(
+                Path::new("/pkg"),
+                Path::new("/pkg/types/index.d.ts"),
+                ".d.ts"
+            ),
+            Some("types/index".to_string())
+        );
+        assert_eq!(key_path(Path::new("/a"), Path::new("/b/c.md"), ".md"), None);
+    }
+}
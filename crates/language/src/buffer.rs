@@ -14,7 +14,7 @@ use crate::{
         SyntaxMapMatches, SyntaxSnapshot, ToTreeSitterPoint,
     },
     task_context::RunnableRange,
-    LanguageScope, Outline, OutlineConfig, RunnableCapture, RunnableTag, TextObject,
+    LanguageScope, LargeFile, Outline, OutlineConfig, RunnableCapture, RunnableTag, TextObject,
     TreeSitterOptions,
 };
 use anyhow::{anyhow, Context, Result};
@@ -123,6 +123,10 @@ pub struct Buffer {
     completion_triggers_timestamp: clock::Lamport,
     deferred_ops: OperationQueue<Operation>,
     capability: Capability,
+    /// Whether the buffer holds a file that was too large to load normally.
+    large_file: bool,
+    /// The file that the buffer shows a page of, on the replica that loaded it.
+    large_file_page: Option<LargeFilePage>,
     has_conflict: bool,
     /// Memoize calls to has_changes_since(saved_version).
     /// The contents of a cell are (self.version, has_changes) at the time of a last call.
@@ -130,6 +134,11 @@ pub struct Buffer {
     _subscriptions: Vec<gpui::Subscription>,
 }
 
+struct LargeFilePage {
+    file: Arc<LargeFile>,
+    page: usize,
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum ParseStatus {
     Idle,
@@ -139,6 +148,7 @@ pub enum ParseStatus {
 struct BufferBranchState {
     base_buffer: Model<Buffer>,
     merged_operations: Vec<Lamport>,
+    merged_transactions: Vec<TransactionId>,
 }
 
 /// An immutable, cheaply cloneable representation of a fixed
@@ -668,6 +678,9 @@ impl Buffer {
         ));
         this.saved_version = proto::deserialize_version(&message.saved_version);
         this.saved_mtime = message.saved_mtime.map(|time| time.into());
+        if message.large_file {
+            this = this.with_large_file_mode();
+        }
         Ok(this)
     }
 
@@ -680,6 +693,7 @@ impl Buffer {
             line_ending: proto::serialize_line_ending(self.line_ending()) as i32,
             saved_version: proto::serialize_version(&self.saved_version),
             saved_mtime: self.saved_mtime.map(|time| time.into()),
+            large_file: self.large_file,
         }
     }
 
@@ -739,6 +753,66 @@ impl Buffer {
         self
     }
 
+    /// Marks the buffer as holding a file that was too large to load normally, returning the
+    /// buffer. Large files are read-only, and are neither parsed nor reloaded when they change
+    /// on disk.
+    pub fn with_large_file_mode(mut self) -> Self {
+        self.large_file = true;
+        self.capability = Capability::ReadOnly;
+        self
+    }
+
+    /// Puts the buffer in large file mode, showing a page of the given file, returning the
+    /// buffer. The buffer's text must be the file's first page.
+    pub fn with_large_file(mut self, file: Arc<LargeFile>) -> Self {
+        self.large_file_page = Some(LargeFilePage { file, page: 0 });
+        self.with_large_file_mode()
+    }
+
+    /// Whether this buffer holds a file that was too large to load normally.
+    pub fn is_large_file(&self) -> bool {
+        self.large_file
+    }
+
+    /// The file that this buffer shows a page of, if it's a large file that was loaded by this
+    /// replica, along with the index of the page.
+    pub fn large_file_page(&self) -> Option<(&Arc<LargeFile>, usize)> {
+        self.large_file_page
+            .as_ref()
+            .map(|state| (&state.file, state.page))
+    }
+
+    /// The row, in the whole file, of the buffer's first row. This is only nonzero for large
+    /// files, where the buffer shows one page of the file.
+    pub fn large_file_start_row(&self) -> u64 {
+        self.large_file_page
+            .as_ref()
+            .map_or(0, |state| state.file.page_start_row(state.page))
+    }
+
+    /// Replaces the buffer's text with another page of its large file. Changing pages can't be
+    /// undone and doesn't make the buffer dirty.
+    ///
+    /// The pages that were shown before remain in the buffer's history, so memory use grows
+    /// with the number of pages that are viewed, rather than with the size of the file.
+    pub fn set_large_file_page(&mut self, page: usize, cx: &mut ModelContext<Self>) {
+        let Some(state) = self.large_file_page.as_mut() else {
+            return;
+        };
+        if page == state.page || page >= state.file.page_count() {
+            return;
+        }
+        state.page = page;
+        let text = state.file.read_page(page);
+
+        self.start_transaction();
+        self.set_text(text, cx);
+        if let Some(transaction_id) = self.end_transaction(cx) {
+            self.forget_transaction(transaction_id);
+        }
+        self.did_reload(self.version(), self.line_ending(), self.saved_mtime, cx);
+    }
+
     /// Returns the [`Capability`] of this buffer.
     pub fn capability(&self) -> Capability {
         self.capability
@@ -766,6 +840,8 @@ impl Buffer {
             branch_state: None,
             file,
             capability,
+            large_file: false,
+            large_file_page: None,
             syntax_map,
             parsing_in_background: false,
             non_text_state_update_count: 0,
@@ -812,6 +888,7 @@ impl Buffer {
                 branch_state: Some(BufferBranchState {
                     base_buffer: this.clone(),
                     merged_operations: Default::default(),
+                    merged_transactions: Default::default(),
                 }),
                 language: self.language.clone(),
                 has_conflict: self.has_conflict,
@@ -871,21 +948,68 @@ impl Buffer {
             }
         }
 
-        let operation = base_buffer.update(cx, |base_buffer, cx| {
+        let (operation, transaction_id) = base_buffer.update(cx, |base_buffer, cx| {
             // cx.emit(BufferEvent::DiffBaseChanged);
-            base_buffer.edit(edits, None, cx)
+            // Each merge gets its own transaction, so that it can be undone on its own.
+            base_buffer.finalize_last_transaction();
+            base_buffer.start_transaction();
+            let operation = base_buffer.edit(edits, None, cx);
+            let transaction_id = base_buffer.end_transaction(cx);
+            base_buffer.finalize_last_transaction();
+            (operation, transaction_id)
         });
 
-        if let Some(operation) = operation {
-            if let Some(BufferBranchState {
-                merged_operations, ..
-            }) = &mut self.branch_state
-            {
+        if let Some(BufferBranchState {
+            merged_operations,
+            merged_transactions,
+            ..
+        }) = &mut self.branch_state
+        {
+            if let Some(operation) = operation {
                 merged_operations.push(operation);
             }
+            if let Some(transaction_id) = transaction_id {
+                merged_transactions.push(transaction_id);
+            }
         }
     }
 
+    /// Undoes every change that was applied to the base buffer by
+    /// [`Buffer::merge_into_base`], most recent first.
+    ///
+    /// Merges that were already undone in the base buffer are skipped. Returns
+    /// whether anything was undone. This buffer must be a branch buffer to call
+    /// this method.
+    pub fn undo_merges_into_base(&mut self, cx: &mut ModelContext<Self>) -> bool {
+        let Some(BufferBranchState {
+            base_buffer,
+            merged_transactions,
+            ..
+        }) = &mut self.branch_state
+        else {
+            debug_panic!("not a branch buffer");
+            return false;
+        };
+
+        let transaction_ids = mem::take(merged_transactions);
+        let base_buffer = base_buffer.clone();
+        base_buffer.update(cx, |base_buffer, cx| {
+            let mut undone = false;
+            for transaction_id in transaction_ids.into_iter().rev() {
+                undone |= base_buffer.undo_transaction(transaction_id, cx);
+            }
+            undone
+        })
+    }
+
+    /// Returns whether any changes were applied to the base buffer by
+    /// [`Buffer::merge_into_base`].
+    pub fn has_merges_into_base(&self) -> bool {
+        self.branch_state
+            .as_ref()
+            .map_or(false, |state| !state.merged_transactions.is_empty())
+    }
+
     fn on_base_buffer_event(
         &mut self,
         _: Model<Buffer>,
@@ -1068,7 +1192,9 @@ impl Buffer {
             let new_state = new_file.disk_state();
             if old_state != new_state {
                 file_changed = true;
-                if !was_dirty && matches!(new_state, DiskState::Present { .. }) {
+                // Large files aren't reloaded, as that would read the whole file into memory.
+                if !was_dirty && !self.large_file && matches!(new_state, DiskState::Present { .. })
+                {
                     cx.emit(BufferEvent::ReloadNeeded)
                 }
             }
@@ -1154,7 +1280,7 @@ impl Buffer {
     /// for the same buffer, we only initiate a new parse if we are not already
     /// parsing in the background.
     pub fn reparse(&mut self, cx: &mut ModelContext<Self>) {
-        if self.parsing_in_background {
+        if self.parsing_in_background || self.large_file {
             return;
         }
         let language = if let Some(language) = self.language.clone() {
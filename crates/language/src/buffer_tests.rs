@@ -701,6 +701,91 @@ async fn test_outline(cx: &mut gpui::TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_structural_search(cx: &mut gpui::TestAppContext) {
+    let text = r#"
+        fn main() {
+            let a = foo.unwrap();
+            let b = bar(1).unwrap();
+            // baz.unwrap()
+            let c = qux.unwrap_or(2);
+            let d = x + x;
+            let e = x + y;
+        }
+    "#
+    .unindent();
+
+    let buffer =
+        cx.new_model(|cx| Buffer::local(text, cx).with_language(Arc::new(rust_lang()), cx));
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+    let search = |pattern: &StructuralPattern| {
+        pattern
+            .matches(&snapshot, 0..snapshot.len())
+            .into_iter()
+            .map(|mat| {
+                let mut captures = mat
+                    .captures
+                    .iter()
+                    .map(|(name, range)| {
+                        let text = snapshot.text_for_range(range.clone()).collect::<String>();
+                        (name.clone(), text)
+                    })
+                    .collect::<Vec<_>>();
+                captures.sort();
+                let text = snapshot.text_for_range(mat.range).collect::<String>();
+                (text, captures)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // Metavariables match any node, but comments and other kinds of nodes don't match.
+    let pattern = StructuralPattern::new("$a.unwrap()");
+    assert_eq!(pattern.literal_words(), ["unwrap"]);
+    assert_eq!(
+        search(&pattern),
+        [
+            (
+                "foo.unwrap()".to_string(),
+                vec![("a".to_string(), "foo".to_string())]
+            ),
+            (
+                "bar(1).unwrap()".to_string(),
+                vec![("a".to_string(), "bar(1)".to_string())]
+            ),
+        ]
+    );
+    let mat = &pattern.matches(&snapshot, 0..snapshot.len())[1];
+    assert_eq!(
+        StructuralPattern::expand_replacement("$a.expect(\"$$a\")", mat, &snapshot),
+        "bar(1).expect(\"$a\")"
+    );
+
+    // A metavariable that occurs twice must match the same text.
+    assert_eq!(
+        search(&StructuralPattern::new("$x + $x")),
+        [(
+            "x + x".to_string(),
+            vec![("x".to_string(), "x".to_string())]
+        )]
+    );
+
+    // Queries are matched with their predicates, and their captures become metavariables.
+    let pattern = StructuralPattern::new(
+        r#"(call_expression function: (identifier) @name (#eq? @name "bar")) @match"#,
+    );
+    assert!(pattern.literal_words().is_empty());
+    assert_eq!(
+        search(&pattern),
+        [(
+            "bar(1)".to_string(),
+            vec![
+                ("match".to_string(), "bar(1)".to_string()),
+                ("name".to_string(), "bar".to_string())
+            ]
+        )]
+    );
+}
+
 #[gpui::test]
 async fn test_outline_nodes_with_newlines(cx: &mut gpui::TestAppContext) {
     let text = r#"
@@ -2706,6 +2791,39 @@ fn test_undo_after_merge_into_base(cx: &mut TestAppContext) {
     branch.read_with(cx, |branch, _| assert_eq!(branch.text(), "ABCdefgHIjk"));
 }
 
+#[gpui::test]
+fn test_undo_merges_into_base(cx: &mut TestAppContext) {
+    cx.update(|cx| init_settings(cx, |_| {}));
+
+    let base = cx.new_model(|cx| Buffer::local("abcdefghijk", cx));
+    let branch = base.update(cx, |buffer, cx| buffer.branch(cx));
+
+    // Merge two edits into the base separately.
+    branch.update(cx, |branch, cx| {
+        branch.edit([(0..3, "ABC"), (7..9, "HI")], None, cx);
+        branch.merge_into_base(vec![0..0], cx);
+        branch.merge_into_base(vec![7..7], cx);
+        assert!(branch.has_merges_into_base());
+    });
+    base.read_with(cx, |base, _| assert_eq!(base.text(), "ABCdefgHIjk"));
+
+    // A user edit in the base buffer is kept when the merges are undone.
+    base.update(cx, |base, cx| {
+        base.edit([(11..11, "LMN")], None, cx);
+    });
+    branch.update(cx, |branch, cx| {
+        assert!(branch.undo_merges_into_base(cx));
+        assert!(!branch.has_merges_into_base());
+    });
+    base.read_with(cx, |base, _| assert_eq!(base.text(), "abcdefghijkLMN"));
+
+    // Undoing again has no effect.
+    branch.update(cx, |branch, cx| {
+        assert!(!branch.undo_merges_into_base(cx));
+    });
+    base.read_with(cx, |base, _| assert_eq!(base.text(), "abcdefghijkLMN"));
+}
+
 #[gpui::test(iterations = 100)]
 fn test_random_collaboration(cx: &mut AppContext, mut rng: StdRng) {
     let min_peers = env::var("MIN_PEERS")
@@ -11,8 +11,10 @@ mod diagnostic_set;
 mod highlight_map;
 mod language_registry;
 pub mod language_settings;
+mod large_file;
 mod outline;
 pub mod proto;
+mod structural_search;
 mod syntax_map;
 mod task_context;
 mod toolchain;
@@ -30,9 +32,10 @@ use gpui::{AppContext, AsyncAppContext, Model, SharedString, Task};
 pub use highlight_map::HighlightMap;
 use http_client::HttpClient;
 pub use language_registry::{LanguageName, LoadedLanguage};
+pub use large_file::*;
 use lsp::{
-    CodeActionKind, InitializeParams, LanguageServerBinary, LanguageServerBinaryOptions,
-    LanguageServerName,
+    CodeActionKind, InitializeParams, LanguageServer, LanguageServerBinary,
+    LanguageServerBinaryOptions, LanguageServerName,
 };
 use parking_lot::Mutex;
 use regex::Regex;
@@ -63,7 +66,7 @@ use std::{
 };
 use syntax_map::{QueryCursorHandle, SyntaxSnapshot};
 use task::RunnableTag;
-pub use task_context::{ContextProvider, RunnableRange};
+pub use task_context::{ContextProvider, RunnableRange, WorktreeTaskProvider};
 use theme::SyntaxTheme;
 pub use toolchain::{LanguageToolchainStore, Toolchain, ToolchainList, ToolchainLister};
 use tree_sitter::{self, wasmtime, Query, QueryCursor, WasmStore};
@@ -78,6 +81,7 @@ pub use language_registry::{
 };
 pub use lsp::LanguageServerId;
 pub use outline::*;
+pub use structural_search::{StructuralMatch, StructuralPattern};
 pub use syntax_map::{OwnedSyntaxLayer, SyntaxLayer, TreeSitterOptions};
 pub use text::{AnchorRangeExt, LineEnding};
 pub use tree_sitter::{Node, Parser, Tree, TreeCursor};
@@ -496,6 +500,77 @@ pub trait LspAdapter: 'static + Send + Sync {
     fn prepare_initialize_params(&self, original: InitializeParams) -> Result<InitializeParams> {
         Ok(original)
     }
+
+    /// Returns the methods of requests from the language server that aren't part of the LSP
+    /// specification, which are answered by [`Self::handle_custom_request`].
+    fn custom_request_methods(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Returns the methods of notifications from the language server that aren't part of the LSP
+    /// specification, which are handled by [`Self::handle_custom_notification`].
+    fn custom_notification_methods(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    async fn handle_custom_request(
+        self: Arc<Self>,
+        method: String,
+        _params: Value,
+        _: &Arc<dyn LspAdapterDelegate>,
+    ) -> Result<Value> {
+        Err(anyhow!("unhandled request {method:?}"))
+    }
+
+    async fn handle_custom_notification(
+        self: Arc<Self>,
+        _method: String,
+        _params: Value,
+        _: &Arc<dyn LspAdapterDelegate>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Returns the names of the editor commands provided for buffers using this language server,
+    /// which are run by [`Self::run_command`].
+    fn commands(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    async fn run_command(
+        self: Arc<Self>,
+        command: String,
+        _context: LanguageServerCommandContext,
+        _server: Arc<LanguageServer>,
+    ) -> Result<LanguageServerCommandOutput> {
+        Err(anyhow!("unknown command {command:?}"))
+    }
+}
+
+/// Where in a buffer a language server command was run from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LanguageServerCommandContext {
+    /// The URI of the buffer's file.
+    pub uri: lsp::Url,
+    /// The position of the cursor.
+    pub cursor: lsp::Position,
+    /// The range of the selection containing the cursor.
+    pub selection: lsp::Range,
+}
+
+/// What the editor should do after running a language server command.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LanguageServerCommandOutput {
+    /// Nothing.
+    None,
+    /// Open the document with the given URI.
+    OpenDocument(lsp::Url),
+    /// Open a new buffer with the given text.
+    OpenBuffer {
+        title: String,
+        text: String,
+        language: Option<LanguageName>,
+    },
 }
 
 async fn try_fetch_server_binary<L: LspAdapter + 'static + Send + Sync + ?Sized>(
@@ -2,7 +2,7 @@ use crate::{
     language_settings::{
         all_language_settings, AllLanguageSettingsContent, LanguageSettingsContent,
     },
-    task_context::ContextProvider,
+    task_context::{ContextProvider, WorktreeTaskProvider},
     with_parser, CachedLspAdapter, File, Language, LanguageConfig, LanguageId, LanguageMatcher,
     LanguageServerName, LspAdapter, ToolchainLister, PLAIN_TEXT,
 };
@@ -98,6 +98,7 @@ struct LanguageRegistryState {
     lsp_adapters: HashMap<LanguageName, Vec<Arc<CachedLspAdapter>>>,
     available_lsp_adapters:
         HashMap<LanguageServerName, Arc<dyn Fn() -> Arc<CachedLspAdapter> + 'static + Send + Sync>>,
+    worktree_task_providers: Vec<Arc<dyn WorktreeTaskProvider>>,
     loading_languages: HashMap<LanguageId, Vec<oneshot::Sender<Result<Arc<Language>>>>>,
     subscription: (watch::Sender<()>, watch::Receiver<()>),
     theme: Option<Arc<Theme>>,
@@ -223,6 +224,7 @@ impl LanguageRegistry {
                 loading_languages: Default::default(),
                 lsp_adapters: Default::default(),
                 available_lsp_adapters: HashMap::default(),
+                worktree_task_providers: Vec::new(),
                 subscription: watch::channel(),
                 theme: Default::default(),
                 version: 0,
@@ -288,6 +290,32 @@ impl LanguageRegistry {
         *state.subscription.0.borrow_mut() = ();
     }
 
+    /// Registers a provider of tasks for whole worktrees, replacing any existing provider
+    /// with the same name.
+    pub fn register_worktree_task_provider(&self, provider: Arc<dyn WorktreeTaskProvider>) {
+        let mut state = self.state.write();
+        let name = provider.name();
+        state
+            .worktree_task_providers
+            .retain(|existing| existing.name() != name);
+        state.worktree_task_providers.push(provider);
+        state.version += 1;
+        *state.subscription.0.borrow_mut() = ();
+    }
+
+    pub fn remove_worktree_task_provider(&self, name: &str) {
+        let mut state = self.state.write();
+        state
+            .worktree_task_providers
+            .retain(|provider| provider.name().as_ref() != name);
+        state.version += 1;
+        *state.subscription.0.borrow_mut() = ();
+    }
+
+    pub fn worktree_task_providers(&self) -> Vec<Arc<dyn WorktreeTaskProvider>> {
+        self.state.read().worktree_task_providers.clone()
+    }
+
     #[cfg(any(feature = "test-support", test))]
     pub fn register_test_language(&self, config: LanguageConfig) {
         self.register_language(
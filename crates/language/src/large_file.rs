@@ -0,0 +1,228 @@
+use std::{fs, io, ops::Range, path::Path};
+use text::LineEnding;
+
+/// The most bytes of a large file that are loaded into a buffer at once.
+pub const LARGE_FILE_PAGE_LEN: usize = 4 * 1024 * 1024;
+
+/// How many bytes are read at a time while indexing a large file.
+const SCAN_CHUNK_LEN: usize = 64 * 1024;
+
+/// A file that's too large to load into memory, which is viewed a page at a time.
+///
+/// The file is memory-mapped, so the operating system only keeps the pages that are being read
+/// in memory. Opening the file reads through it once, to find where each page starts, but only
+/// the offset and row of each page are kept. Pages end at line boundaries, unless a line is
+/// longer than a page.
+pub struct LargeFile {
+    bytes: LargeFileBytes,
+    pages: Vec<LargeFilePage>,
+    row_count: u64,
+    line_ending: LineEnding,
+}
+
+enum LargeFileBytes {
+    Mapped(memmap2::Mmap),
+    Owned(Vec<u8>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct LargeFilePage {
+    offset: u64,
+    /// The row, in the whole file, that the page starts on.
+    row: u64,
+}
+
+impl LargeFile {
+    /// Memory-maps the file at the given path, splitting it into pages of at most `page_len`
+    /// bytes.
+    ///
+    /// If the file is truncated by another process while it's mapped, reading the missing part
+    /// will crash, so this should only be used for files that are too large to read into memory.
+    pub fn open(path: &Path, page_len: usize) -> io::Result<Self> {
+        let file = fs::File::open(path)?;
+        // SAFETY: The file is only ever read through the map, and large files aren't reloaded
+        // when they change on disk.
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        Ok(Self::index(LargeFileBytes::Mapped(map), page_len))
+    }
+
+    /// Splits the given contents into pages of at most `page_len` bytes.
+    pub fn from_bytes(bytes: Vec<u8>, page_len: usize) -> Self {
+        Self::index(LargeFileBytes::Owned(bytes), page_len)
+    }
+
+    fn index(bytes: LargeFileBytes, page_len: usize) -> Self {
+        // Leave room for a whole character in each page.
+        let page_len = page_len.max(4);
+        let mut pages = vec![LargeFilePage { offset: 0, row: 0 }];
+        let mut page_start = 0;
+        let mut line_start = 0;
+        let mut row = 0;
+        for (chunk_ix, chunk) in bytes.as_slice().chunks(SCAN_CHUNK_LEN).enumerate() {
+            let chunk_start = chunk_ix * SCAN_CHUNK_LEN;
+            for (ix, byte) in chunk.iter().enumerate() {
+                let offset = chunk_start + ix;
+                if offset - page_start == page_len {
+                    // This byte doesn't fit in the current page, so the page ends after its last
+                    // line. If it has no line break, it ends on the last character boundary.
+                    let mut next_page_start = line_start;
+                    if next_page_start <= page_start {
+                        next_page_start = offset;
+                        while next_page_start > page_start + 1
+                            && next_page_start > chunk_start
+                            && is_continuation_byte(chunk[next_page_start - chunk_start])
+                        {
+                            next_page_start -= 1;
+                        }
+                    }
+                    pages.push(LargeFilePage {
+                        offset: next_page_start as u64,
+                        row,
+                    });
+                    page_start = next_page_start;
+                }
+                if *byte == b'\n' {
+                    row += 1;
+                    line_start = offset + 1;
+                }
+            }
+        }
+
+        let first_page = &bytes.as_slice()[..bytes.as_slice().len().min(1000)];
+        Self {
+            line_ending: LineEnding::detect(&String::from_utf8_lossy(first_page)),
+            bytes,
+            pages,
+            row_count: row + 1,
+        }
+    }
+
+    /// The size of the file in bytes.
+    pub fn len(&self) -> u64 {
+        self.bytes.as_slice().len() as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of lines in the file.
+    pub fn row_count(&self) -> u64 {
+        self.row_count
+    }
+
+    pub fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// The byte range of the given page within the file.
+    pub fn page_range(&self, page: usize) -> Range<u64> {
+        let start = self.pages[page].offset;
+        let end = self
+            .pages
+            .get(page + 1)
+            .map_or(self.len(), |next_page| next_page.offset);
+        start..end
+    }
+
+    /// The row, in the whole file, that the given page starts on.
+    pub fn page_start_row(&self, page: usize) -> u64 {
+        self.pages[page].row
+    }
+
+    /// Returns the page containing the start of the given row.
+    pub fn page_for_row(&self, row: u64) -> usize {
+        self.pages
+            .partition_point(|page| page.row <= row)
+            .saturating_sub(1)
+    }
+
+    /// Reads the text of the given page. Invalid UTF-8 is replaced, and line endings are
+    /// normalized.
+    pub fn read_page(&self, page: usize) -> String {
+        let range = self.page_range(page);
+        let bytes = &self.bytes.as_slice()[range.start as usize..range.end as usize];
+        let mut text = String::from_utf8_lossy(bytes).into_owned();
+        LineEnding::normalize(&mut text);
+        text
+    }
+}
+
+impl LargeFileBytes {
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            LargeFileBytes::Mapped(map) => map,
+            LargeFileBytes::Owned(bytes) => bytes,
+        }
+    }
+}
+
+fn is_continuation_byte(byte: u8) -> bool {
+    byte & 0b1100_0000 == 0b1000_0000
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pages(file: &LargeFile) -> Vec<(u64, String)> {
+        (0..file.page_count())
+            .map(|page| (file.page_start_row(page), file.read_page(page)))
+            .collect()
+    }
+
+    #[test]
+    fn test_large_file_pages() {
+        let file = LargeFile::from_bytes(b"one\r\ntwo\r\nthree\r\nfour".to_vec(), 11);
+        assert_eq!(file.row_count(), 4);
+        assert_eq!(file.line_ending(), LineEnding::Windows);
+        assert_eq!(
+            pages(&file),
+            [
+                (0, "one\ntwo\n".to_string()),
+                (2, "three\nfour".to_string())
+            ]
+        );
+        assert_eq!(file.page_range(1), 10..21);
+        assert_eq!(file.page_for_row(0), 0);
+        assert_eq!(file.page_for_row(1), 0);
+        assert_eq!(file.page_for_row(2), 1);
+        assert_eq!(file.page_for_row(3), 1);
+        assert_eq!(file.page_for_row(10), 1);
+
+        // Lines that are longer than a page are split between characters.
+        let file = LargeFile::from_bytes("aé🙂bcdef\ng".as_bytes().to_vec(), 6);
+        assert_eq!(
+            pages(&file),
+            [
+                (0, "aé".to_string()),
+                (0, "🙂bc".to_string()),
+                (0, "def\ng".to_string())
+            ]
+        );
+        assert_eq!(file.page_for_row(1), 2);
+
+        let file = LargeFile::from_bytes(Vec::new(), 6);
+        assert_eq!(file.row_count(), 1);
+        assert_eq!(pages(&file), [(0, String::new())]);
+    }
+
+    #[test]
+    fn test_open_large_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("large.log");
+        let text = "line\n".repeat(1000);
+        fs::write(&path, &text).unwrap();
+
+        let file = LargeFile::open(&path, 100).unwrap();
+        assert_eq!(file.len(), text.len() as u64);
+        assert_eq!(file.row_count(), 1001);
+        assert_eq!(file.page_count(), 50);
+        assert_eq!(file.page_start_row(49), 980);
+        assert_eq!(file.read_page(49), "line\n".repeat(20));
+    }
+}
@@ -0,0 +1,355 @@
+//! Structural search, which matches patterns against the syntax trees of a buffer
+//! rather than against its text.
+
+use crate::{
+    syntax_map::TextProvider, with_parser, with_query_cursor, BufferSnapshot, Grammar, GrammarId,
+};
+use collections::HashMap;
+use parking_lot::Mutex;
+use regex::{Captures, Regex};
+use std::{
+    fmt,
+    ops::Range,
+    sync::{Arc, LazyLock},
+};
+use tree_sitter::{Node, Query, Tree};
+
+/// Metavariables are replaced by identifiers with this prefix before the pattern is parsed,
+/// so that the pattern is valid code in most languages.
+const METAVARIABLE_PREFIX: &str = "zed_metavar_";
+
+/// The name of the capture whose node is the range of a query match.
+const MATCH_CAPTURE_NAME: &str = "match";
+
+static METAVARIABLE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\$(\$|[A-Za-z_][A-Za-z0-9_]*)").unwrap());
+
+static WORD_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\$?[A-Za-z_][A-Za-z0-9_]*").unwrap());
+
+/// A pattern that is matched against the syntax trees of buffers.
+///
+/// The pattern is either a snippet of code, such as `$a.unwrap()`, or a Tree-sitter query,
+/// such as `(call_expression function: (identifier) @name) @match`.
+///
+/// In code patterns, each `$name` metavariable matches any single syntax node, and a
+/// metavariable that occurs more than once must match the same text each time. `$_` matches
+/// any node without capturing it. Other nodes match nodes of the same kind with the same
+/// children, ignoring whitespace and comments.
+///
+/// Patterns that start with `(` or `[` and are valid queries for a buffer's language are
+/// matched as queries. Each capture becomes a metavariable, and the range of a match is that
+/// of its `@match` capture, or the range spanned by all of its captures.
+pub struct StructuralPattern {
+    source: String,
+    /// The source, with its metavariables replaced by identifiers.
+    code: String,
+    compiled: Mutex<HashMap<GrammarId, Option<Arc<CompiledPattern>>>>,
+}
+
+enum CompiledPattern {
+    Code(Tree),
+    Query(Query),
+}
+
+/// A match of a [`StructuralPattern`] in a buffer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StructuralMatch {
+    /// The range of the matched syntax node.
+    pub range: Range<usize>,
+    /// The range of the node captured by each metavariable, by name.
+    pub captures: Vec<(String, Range<usize>)>,
+}
+
+impl StructuralPattern {
+    /// Creates a pattern from its source.
+    pub fn new(source: impl Into<String>) -> Self {
+        let source = source.into();
+        let code = METAVARIABLE_REGEX
+            .replace_all(&source, |captures: &Captures| match &captures[1] {
+                "$" => "$".to_string(),
+                name => format!("{METAVARIABLE_PREFIX}{name}"),
+            })
+            .into_owned();
+        Self {
+            source,
+            code,
+            compiled: Default::default(),
+        }
+    }
+
+    /// Returns the source of the pattern.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    fn looks_like_query(&self) -> bool {
+        matches!(self.source.trim_start().chars().next(), Some('(' | '['))
+    }
+
+    /// Returns words that occur in the text of every match of the pattern, which can be used
+    /// to skip files that can't contain a match without parsing them.
+    pub fn literal_words(&self) -> Vec<&str> {
+        if self.looks_like_query() {
+            return Vec::new();
+        }
+        WORD_REGEX
+            .find_iter(&self.source)
+            .map(|word| word.as_str())
+            .filter(|word| !word.starts_with('$'))
+            .collect()
+    }
+
+    /// Returns the non-overlapping matches of the pattern that lie within the given range
+    /// of the buffer, in every syntax layer whose language the pattern can be parsed in.
+    pub fn matches(&self, buffer: &BufferSnapshot, range: Range<usize>) -> Vec<StructuralMatch> {
+        let mut matches = Vec::new();
+        for layer in buffer.syntax_layers() {
+            let Some(compiled) = layer
+                .language
+                .grammar()
+                .and_then(|grammar| self.compile(grammar))
+            else {
+                continue;
+            };
+            match compiled.as_ref() {
+                CompiledPattern::Code(tree) => {
+                    self.match_code(tree, layer.node(), buffer, &range, &mut matches)
+                }
+                CompiledPattern::Query(query) => {
+                    match_query(query, layer.node(), buffer, &range, &mut matches)
+                }
+            }
+        }
+
+        matches.sort_by_key(|mat| (mat.range.start, usize::MAX - mat.range.end));
+        let mut last_end = None;
+        matches.retain(|mat| {
+            if last_end.map_or(false, |last_end| mat.range.start < last_end) {
+                false
+            } else {
+                last_end = Some(mat.range.end);
+                true
+            }
+        });
+        matches
+    }
+
+    /// Returns the replacement text for a match, where each `$name` in `replacement` is
+    /// replaced by the text captured by that metavariable, and `$$` by `$`.
+    pub fn expand_replacement(
+        replacement: &str,
+        mat: &StructuralMatch,
+        buffer: &BufferSnapshot,
+    ) -> String {
+        METAVARIABLE_REGEX
+            .replace_all(replacement, |captures: &Captures| {
+                let name = &captures[1];
+                if name == "$" {
+                    return "$".to_string();
+                }
+                mat.captures
+                    .iter()
+                    .find(|(capture_name, _)| capture_name == name)
+                    .map(|(_, range)| buffer.text_for_range(range.clone()).collect())
+                    .unwrap_or_else(|| captures[0].to_string())
+            })
+            .into_owned()
+    }
+
+    fn compile(&self, grammar: &Arc<Grammar>) -> Option<Arc<CompiledPattern>> {
+        self.compiled
+            .lock()
+            .entry(grammar.id())
+            .or_insert_with(|| {
+                if self.looks_like_query() {
+                    if let Ok(query) = Query::new(&grammar.ts_language, &self.source) {
+                        return Some(Arc::new(CompiledPattern::Query(query)));
+                    }
+                }
+                let tree = with_parser(|parser| {
+                    parser.set_language(&grammar.ts_language).ok()?;
+                    parser.parse(&self.code, None)
+                })?;
+                Some(Arc::new(CompiledPattern::Code(tree)))
+            })
+            .clone()
+    }
+
+    fn match_code(
+        &self,
+        tree: &Tree,
+        root: Node,
+        buffer: &BufferSnapshot,
+        range: &Range<usize>,
+        matches: &mut Vec<StructuralMatch>,
+    ) {
+        // The pattern is the smallest node that spans all of its code, which skips the nodes
+        // that wrap it, like the source file and the statement around an expression.
+        let code_start = self.code.len() - self.code.trim_start().len();
+        let code_end = self.code.trim_end().len();
+        if code_start >= code_end {
+            return;
+        }
+        let Some(pattern) = tree
+            .root_node()
+            .descendant_for_byte_range(code_start, code_end)
+        else {
+            return;
+        };
+
+        let mut cursor = root.walk();
+        'outer: loop {
+            let node = cursor.node();
+            let node_range = node.byte_range();
+            let mut visit_children = node_range.start < range.end && node_range.end > range.start;
+            if visit_children && node_range.start >= range.start && node_range.end <= range.end {
+                let mut captures = Vec::new();
+                if self.match_node(pattern, node, buffer, &mut captures) {
+                    matches.push(StructuralMatch {
+                        range: node_range,
+                        captures,
+                    });
+                    visit_children = false;
+                }
+            }
+
+            if visit_children && cursor.goto_first_child() {
+                continue;
+            }
+            while !cursor.goto_next_sibling() {
+                if !cursor.goto_parent() {
+                    break 'outer;
+                }
+            }
+        }
+    }
+
+    fn match_node(
+        &self,
+        pattern: Node,
+        candidate: Node,
+        buffer: &BufferSnapshot,
+        captures: &mut Vec<(String, Range<usize>)>,
+    ) -> bool {
+        let pattern_text = &self.code[pattern.byte_range()];
+        if let Some(name) = metavariable_name(pattern_text) {
+            if !candidate.is_named() {
+                return false;
+            }
+            let candidate_range = candidate.byte_range();
+            if name != "_" {
+                if let Some((_, captured_range)) = captures
+                    .iter()
+                    .find(|(captured_name, _)| captured_name == name)
+                {
+                    let captured_text = buffer
+                        .text_for_range(captured_range.clone())
+                        .collect::<String>();
+                    return text_eq(buffer, candidate_range, &captured_text);
+                }
+                captures.push((name.to_string(), candidate_range));
+            }
+            return true;
+        }
+
+        if pattern.kind() != candidate.kind() || pattern.is_named() != candidate.is_named() {
+            return false;
+        }
+        let pattern_children = significant_children(pattern);
+        let candidate_children = significant_children(candidate);
+        if pattern_children.is_empty() {
+            return candidate_children.is_empty()
+                && text_eq(buffer, candidate.byte_range(), pattern_text);
+        }
+        pattern_children.len() == candidate_children.len()
+            && pattern_children
+                .into_iter()
+                .zip(candidate_children)
+                .all(|(pattern, candidate)| self.match_node(pattern, candidate, buffer, captures))
+    }
+}
+
+impl fmt::Debug for StructuralPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StructuralPattern")
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+fn match_query(
+    query: &Query,
+    root: Node,
+    buffer: &BufferSnapshot,
+    range: &Range<usize>,
+    matches: &mut Vec<StructuralMatch>,
+) {
+    let capture_names = query.capture_names();
+    with_query_cursor(|cursor| {
+        cursor.set_byte_range(range.clone());
+        for mat in cursor.matches(query, root, TextProvider(buffer.as_rope())) {
+            let captures = mat
+                .captures
+                .iter()
+                .map(|capture| {
+                    (
+                        capture_names[capture.index as usize].to_string(),
+                        capture.node.byte_range(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            let match_range = captures
+                .iter()
+                .find(|(name, _)| name == MATCH_CAPTURE_NAME)
+                .map(|(_, range)| range.clone())
+                .or_else(|| {
+                    let start = captures.iter().map(|(_, range)| range.start).min()?;
+                    let end = captures.iter().map(|(_, range)| range.end).max()?;
+                    Some(start..end)
+                });
+            if let Some(match_range) = match_range {
+                if match_range.start >= range.start && match_range.end <= range.end {
+                    matches.push(StructuralMatch {
+                        range: match_range,
+                        captures,
+                    });
+                }
+            }
+        }
+    });
+}
+
+/// Returns the name of the metavariable that a pattern node consists of, if its text is
+/// entirely a single metavariable. Nodes that merely start with one, like `$a.unwrap()`,
+/// aren't metavariables.
+fn metavariable_name(pattern_text: &str) -> Option<&str> {
+    let name = pattern_text.strip_prefix(METAVARIABLE_PREFIX)?;
+    let mut chars = name.chars();
+    let is_identifier = chars
+        .next()
+        .map_or(false, |c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    is_identifier.then_some(name)
+}
+
+/// Returns the children of a node, excluding comments and the empty nodes that error
+/// recovery inserts.
+fn significant_children(node: Node) -> Vec<Node> {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .filter(|child| !child.is_extra() && !child.is_missing())
+        .collect()
+}
+
+fn text_eq(buffer: &BufferSnapshot, range: Range<usize>, text: &str) -> bool {
+    if range.len() != text.len() {
+        return false;
+    }
+    let mut offset = 0;
+    buffer.text_for_range(range).all(|chunk| {
+        let eq = text[offset..].starts_with(chunk);
+        offset += chunk.len();
+        eq
+    })
+}
@@ -1,8 +1,9 @@
-use std::{ops::Range, sync::Arc};
+use std::{ops::Range, path::Path, sync::Arc};
 
-use crate::{LanguageToolchainStore, Location, Runnable};
+use crate::{LanguageToolchainStore, Location, LspAdapterDelegate, Runnable};
 
 use anyhow::Result;
+use async_trait::async_trait;
 use collections::HashMap;
 use gpui::{AppContext, Task};
 use task::{TaskTemplates, TaskVariables};
@@ -41,3 +42,18 @@ pub trait ContextProvider: Send + Sync {
         None
     }
 }
+
+/// Provides the tasks of a whole worktree, rather than of the buffers in a particular language,
+/// e.g. the scripts declared in its `package.json` or the targets of its `Makefile`.
+#[async_trait]
+pub trait WorktreeTaskProvider: Send + Sync {
+    /// The name of the provider, which identifies the source of its tasks.
+    fn name(&self) -> Arc<str>;
+
+    /// The worktree-relative paths of the files that the tasks are computed from.
+    /// The tasks of a worktree are recomputed whenever one of these files changes.
+    fn watched_paths(&self) -> Vec<Arc<Path>>;
+
+    /// Computes the tasks for the worktree of the given delegate.
+    async fn task_templates(&self, delegate: Arc<dyn LspAdapterDelegate>) -> Result<TaskTemplates>;
+}
@@ -4,17 +4,23 @@ use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::Arc;
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use async_trait::async_trait;
 use collections::HashMap;
-use extension::{Extension, ExtensionLanguageServerProxy, WorktreeDelegate};
+use extension::{
+    Extension, ExtensionLanguageServerProxy, LanguageServerDelegate, WorktreeDelegate,
+};
 use futures::{Future, FutureExt};
 use gpui::AsyncAppContext;
 use language::{
     CodeLabel, HighlightId, Language, LanguageName, LanguageServerBinaryStatus,
-    LanguageToolchainStore, LspAdapter, LspAdapterDelegate,
+    LanguageServerCommandContext, LanguageServerCommandOutput, LanguageToolchainStore, LspAdapter,
+    LspAdapterDelegate,
+};
+use lsp::{
+    CodeActionKind, LanguageServer, LanguageServerBinary, LanguageServerBinaryOptions,
+    LanguageServerName,
 };
-use lsp::{CodeActionKind, LanguageServerBinary, LanguageServerBinaryOptions, LanguageServerName};
 use serde::Serialize;
 use serde_json::Value;
 use util::{maybe, ResultExt};
@@ -22,7 +28,7 @@ use util::{maybe, ResultExt};
 use crate::LanguageServerRegistryProxy;
 
 /// An adapter that allows an [`LspAdapterDelegate`] to be used as a [`WorktreeDelegate`].
-struct WorktreeDelegateAdapter(pub Arc<dyn LspAdapterDelegate>);
+pub(crate) struct WorktreeDelegateAdapter(pub Arc<dyn LspAdapterDelegate>);
 
 #[async_trait]
 impl WorktreeDelegate for WorktreeDelegateAdapter {
@@ -50,6 +56,25 @@ impl WorktreeDelegate for WorktreeDelegateAdapter {
     }
 }
 
+/// An adapter that allows a [`LanguageServer`] to be used as a [`LanguageServerDelegate`].
+struct LanguageServerDelegateAdapter(pub Arc<LanguageServer>);
+
+#[async_trait]
+impl LanguageServerDelegate for LanguageServerDelegateAdapter {
+    async fn request(&self, method: String, params: String) -> Result<String> {
+        let params = serde_json::from_str(&params)
+            .with_context(|| format!("failed to parse params for {method:?}"))?;
+        let response = self.0.custom_request(method, params).await?;
+        Ok(serde_json::to_string(&response)?)
+    }
+
+    fn notify(&self, method: String, params: String) -> Result<()> {
+        let params = serde_json::from_str(&params)
+            .with_context(|| format!("failed to parse params for {method:?}"))?;
+        self.0.custom_notify(&method, params)
+    }
+}
+
 impl ExtensionLanguageServerProxy for LanguageServerRegistryProxy {
     fn register_language_server(
         &self,
@@ -264,6 +289,101 @@ impl LspAdapter for ExtensionLspAdapter {
         })
     }
 
+    fn custom_request_methods(&self) -> Vec<String> {
+        self.extension
+            .manifest()
+            .language_servers
+            .get(&self.language_server_id)
+            .map(|server| server.custom_requests.clone())
+            .unwrap_or_default()
+    }
+
+    fn custom_notification_methods(&self) -> Vec<String> {
+        self.extension
+            .manifest()
+            .language_servers
+            .get(&self.language_server_id)
+            .map(|server| server.custom_notifications.clone())
+            .unwrap_or_default()
+    }
+
+    async fn handle_custom_request(
+        self: Arc<Self>,
+        method: String,
+        params: Value,
+        delegate: &Arc<dyn LspAdapterDelegate>,
+    ) -> Result<Value> {
+        if !self.custom_request_methods().contains(&method) {
+            bail!(
+                "{} doesn't handle the request {method:?}",
+                self.language_server_id
+            );
+        }
+        let delegate = Arc::new(WorktreeDelegateAdapter(delegate.clone())) as _;
+        let response = self
+            .extension
+            .handle_language_server_request(
+                self.language_server_id.clone(),
+                method,
+                serde_json::to_string(&params)?,
+                delegate,
+            )
+            .await?;
+        serde_json::from_str(&response).with_context(|| {
+            format!("failed to parse language server response from extension: {response}")
+        })
+    }
+
+    async fn handle_custom_notification(
+        self: Arc<Self>,
+        method: String,
+        params: Value,
+        delegate: &Arc<dyn LspAdapterDelegate>,
+    ) -> Result<()> {
+        if !self.custom_notification_methods().contains(&method) {
+            bail!(
+                "{} doesn't handle the notification {method:?}",
+                self.language_server_id
+            );
+        }
+        let delegate = Arc::new(WorktreeDelegateAdapter(delegate.clone())) as _;
+        self.extension
+            .handle_language_server_notification(
+                self.language_server_id.clone(),
+                method,
+                serde_json::to_string(&params)?,
+                delegate,
+            )
+            .await
+    }
+
+    fn commands(&self) -> Vec<String> {
+        self.extension
+            .manifest()
+            .language_servers
+            .get(&self.language_server_id)
+            .map(|server| server.commands.clone())
+            .unwrap_or_default()
+    }
+
+    async fn run_command(
+        self: Arc<Self>,
+        command: String,
+        context: LanguageServerCommandContext,
+        server: Arc<LanguageServer>,
+    ) -> Result<LanguageServerCommandOutput> {
+        if !self.commands().contains(&command) {
+            bail!(
+                "{} doesn't provide the command {command:?}",
+                self.language_server_id
+            );
+        }
+        let server = Arc::new(LanguageServerDelegateAdapter(server)) as _;
+        self.extension
+            .run_language_server_command(self.language_server_id.clone(), command, context, server)
+            .await
+    }
+
     async fn labels_for_completions(
         self: Arc<Self>,
         completions: &[lsp::CompletionItem],
@@ -0,0 +1,75 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use extension::{Extension, ExtensionTaskProviderProxy};
+use language::{LspAdapterDelegate, WorktreeTaskProvider};
+use task::{TaskTemplate, TaskTemplates, VariableName};
+
+use crate::extension_lsp_adapter::WorktreeDelegateAdapter;
+use crate::LanguageServerRegistryProxy;
+
+impl ExtensionTaskProviderProxy for LanguageServerRegistryProxy {
+    fn register_task_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        watched_paths: Vec<PathBuf>,
+    ) {
+        self.language_registry
+            .register_worktree_task_provider(Arc::new(ExtensionWorktreeTaskProvider {
+                extension,
+                provider_id,
+                watched_paths: watched_paths.into_iter().map(Arc::from).collect(),
+            }));
+    }
+
+    fn remove_task_provider(&self, provider_id: &str) {
+        self.language_registry
+            .remove_worktree_task_provider(provider_id);
+    }
+}
+
+struct ExtensionWorktreeTaskProvider {
+    extension: Arc<dyn Extension>,
+    provider_id: Arc<str>,
+    watched_paths: Vec<Arc<Path>>,
+}
+
+#[async_trait]
+impl WorktreeTaskProvider for ExtensionWorktreeTaskProvider {
+    fn name(&self) -> Arc<str> {
+        self.provider_id.clone()
+    }
+
+    fn watched_paths(&self) -> Vec<Arc<Path>> {
+        self.watched_paths.clone()
+    }
+
+    async fn task_templates(&self, delegate: Arc<dyn LspAdapterDelegate>) -> Result<TaskTemplates> {
+        let delegate = Arc::new(WorktreeDelegateAdapter(delegate)) as _;
+        let templates = self
+            .extension
+            .task_templates(self.provider_id.clone(), delegate)
+            .await?;
+
+        Ok(TaskTemplates(
+            templates
+                .into_iter()
+                .map(|template| TaskTemplate {
+                    label: template.label,
+                    command: template.command,
+                    args: template.args,
+                    env: template.env.into_iter().collect(),
+                    // Extensions give directories relative to the worktree root.
+                    cwd: template
+                        .cwd
+                        .map(|cwd| format!("${}/{cwd}", VariableName::WorktreeRoot)),
+                    tags: template.tags,
+                    ..TaskTemplate::default()
+                })
+                .collect(),
+        ))
+    }
+}
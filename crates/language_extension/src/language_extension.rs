@@ -1,4 +1,5 @@
 mod extension_lsp_adapter;
+mod extension_task_provider;
 
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -14,7 +15,8 @@ pub fn init(
     let language_server_registry_proxy = LanguageServerRegistryProxy { language_registry };
     extension_host_proxy.register_grammar_proxy(language_server_registry_proxy.clone());
     extension_host_proxy.register_language_proxy(language_server_registry_proxy.clone());
-    extension_host_proxy.register_language_server_proxy(language_server_registry_proxy);
+    extension_host_proxy.register_language_server_proxy(language_server_registry_proxy.clone());
+    extension_host_proxy.register_task_provider_proxy(language_server_registry_proxy);
 }
 
 #[derive(Clone)]
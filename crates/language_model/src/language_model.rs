@@ -3,6 +3,7 @@ mod rate_limiter;
 mod registry;
 mod request;
 mod role;
+mod usage;
 
 #[cfg(any(test, feature = "test-support"))]
 pub mod fake_provider;
@@ -22,11 +23,13 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::fmt;
 use std::{future::Future, sync::Arc};
 use ui::IconName;
+pub use usage::*;
 
 pub const ZED_CLOUD_PROVIDER_ID: &str = "zed.dev";
 
 pub fn init(cx: &mut AppContext) {
     registry::init(cx);
+    usage::init(cx);
 }
 
 /// The availability of a [`LanguageModel`].
@@ -53,6 +56,7 @@ pub enum LanguageModelCompletionEvent {
     Text(String),
     ToolUse(LanguageModelToolUse),
     StartMessage { message_id: String },
+    UsageUpdate(TokenUsage),
 }
 
 #[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
@@ -127,6 +131,11 @@ pub trait LanguageModel: Send + Sync {
         None
     }
 
+    /// Returns the list price of this model, if known, used to estimate the cost of requests.
+    fn pricing(&self) -> Option<LanguageModelPricing> {
+        None
+    }
+
     fn count_tokens(
         &self,
         request: LanguageModelRequest,
@@ -139,11 +148,14 @@ pub trait LanguageModel: Send + Sync {
         cx: &AsyncAppContext,
     ) -> BoxFuture<'static, Result<BoxStream<'static, Result<LanguageModelCompletionEvent>>>>;
 
+    /// Streams the text of a completion, recording its usage against the given project.
     fn stream_completion_text(
         &self,
         request: LanguageModelRequest,
+        project: Option<SharedString>,
         cx: &AsyncAppContext,
     ) -> BoxFuture<'static, Result<LanguageModelTextStream>> {
+        let usage_reporter = UsageReporter::new(self, project, cx);
         let events = self.stream_completion(request, cx);
 
         async move {
@@ -164,13 +176,20 @@ pub trait LanguageModel: Send + Sync {
             }
 
             let stream = futures::stream::iter(first_item_text.map(Ok))
-                .chain(events.filter_map(|result| async move {
-                    match result {
-                        Ok(LanguageModelCompletionEvent::StartMessage { .. }) => None,
-                        Ok(LanguageModelCompletionEvent::Text(text)) => Some(Ok(text)),
-                        Ok(LanguageModelCompletionEvent::Stop(_)) => None,
-                        Ok(LanguageModelCompletionEvent::ToolUse(_)) => None,
-                        Err(err) => Some(Err(err)),
+                .chain(events.filter_map(move |result| {
+                    let usage_reporter = usage_reporter.clone();
+                    async move {
+                        match result {
+                            Ok(LanguageModelCompletionEvent::StartMessage { .. }) => None,
+                            Ok(LanguageModelCompletionEvent::Text(text)) => Some(Ok(text)),
+                            Ok(LanguageModelCompletionEvent::Stop(_)) => None,
+                            Ok(LanguageModelCompletionEvent::ToolUse(_)) => None,
+                            Ok(LanguageModelCompletionEvent::UsageUpdate(usage)) => {
+                                usage_reporter.report(usage);
+                                None
+                            }
+                            Err(err) => Some(Err(err)),
+                        }
                     }
                 }))
                 .boxed();
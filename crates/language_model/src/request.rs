@@ -261,6 +261,9 @@ impl LanguageModelRequest {
                 })
                 .collect(),
             stream,
+            stream_options: stream.then_some(open_ai::StreamOptions {
+                include_usage: true,
+            }),
             stop: self.stop,
             temperature: self.temperature.unwrap_or(1.0),
             max_tokens: max_output_tokens,
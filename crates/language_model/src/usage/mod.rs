@@ -0,0 +1,374 @@
+mod persistence;
+
+use std::ops::{Add, AddAssign};
+
+use anyhow::Result;
+use chrono::{Datelike as _, Local, NaiveDate};
+use futures::{channel::mpsc, StreamExt as _};
+use gpui::{
+    AppContext, AsyncAppContext, Context as _, EventEmitter, Global, Model, ModelContext,
+    SharedString, Task,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use util::ResultExt as _;
+
+use crate::LanguageModel;
+use persistence::USAGE_DB;
+
+pub(crate) fn init(cx: &mut AppContext) {
+    let usage = cx.new_model(|_| LanguageModelUsage);
+    cx.set_global(GlobalLanguageModelUsage(usage));
+}
+
+struct GlobalLanguageModelUsage(Model<LanguageModelUsage>);
+
+impl Global for GlobalLanguageModelUsage {}
+
+/// The number of tokens consumed by a completion request.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub struct TokenUsage {
+    /// The tokens in the prompt that were neither read from nor written to a cache.
+    pub input_tokens: u32,
+    /// The tokens generated by the model.
+    pub output_tokens: u32,
+    /// The tokens in the prompt that were written to the provider's prompt cache.
+    #[serde(default)]
+    pub cache_creation_input_tokens: u32,
+    /// The tokens in the prompt that were read from the provider's prompt cache.
+    #[serde(default)]
+    pub cache_read_input_tokens: u32,
+}
+
+impl TokenUsage {
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens as u64
+            + self.output_tokens as u64
+            + self.cache_creation_input_tokens as u64
+            + self.cache_read_input_tokens as u64
+    }
+}
+
+impl Add for TokenUsage {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            input_tokens: self.input_tokens.saturating_add(other.input_tokens),
+            output_tokens: self.output_tokens.saturating_add(other.output_tokens),
+            cache_creation_input_tokens: self
+                .cache_creation_input_tokens
+                .saturating_add(other.cache_creation_input_tokens),
+            cache_read_input_tokens: self
+                .cache_read_input_tokens
+                .saturating_add(other.cache_read_input_tokens),
+        }
+    }
+}
+
+impl AddAssign for TokenUsage {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+/// The list price of a language model, used to estimate what local usage costs.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct LanguageModelPricing {
+    pub input_usd_per_million_tokens: f64,
+    pub output_usd_per_million_tokens: f64,
+    pub cache_creation_input_usd_per_million_tokens: f64,
+    pub cache_read_input_usd_per_million_tokens: f64,
+}
+
+impl LanguageModelPricing {
+    /// Creates the pricing of a model whose cached prompt tokens cost the same as any other
+    /// prompt tokens.
+    pub const fn new(
+        input_usd_per_million_tokens: f64,
+        output_usd_per_million_tokens: f64,
+    ) -> Self {
+        Self {
+            input_usd_per_million_tokens,
+            output_usd_per_million_tokens,
+            cache_creation_input_usd_per_million_tokens: input_usd_per_million_tokens,
+            cache_read_input_usd_per_million_tokens: input_usd_per_million_tokens,
+        }
+    }
+
+    pub const fn with_cache_pricing(
+        self,
+        cache_creation_input_usd_per_million_tokens: f64,
+        cache_read_input_usd_per_million_tokens: f64,
+    ) -> Self {
+        Self {
+            cache_creation_input_usd_per_million_tokens,
+            cache_read_input_usd_per_million_tokens,
+            ..self
+        }
+    }
+
+    pub fn cost_usd(&self, usage: TokenUsage) -> f64 {
+        (usage.input_tokens as f64 * self.input_usd_per_million_tokens
+            + usage.output_tokens as f64 * self.output_usd_per_million_tokens
+            + usage.cache_creation_input_tokens as f64
+                * self.cache_creation_input_usd_per_million_tokens
+            + usage.cache_read_input_tokens as f64 * self.cache_read_input_usd_per_million_tokens)
+            / 1_000_000.
+    }
+}
+
+/// Soft limits on the estimated cost of language model requests.
+///
+/// Once a budget has been used up, the user is asked to confirm each request before it's sent.
+#[derive(Debug, Default, PartialEq, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+pub struct UsageBudget {
+    /// The amount in US dollars that may be spent per day.
+    pub daily_usd: Option<f64>,
+    /// The amount in US dollars that may be spent per calendar month.
+    pub monthly_usd: Option<f64>,
+}
+
+impl UsageBudget {
+    pub fn is_empty(&self) -> bool {
+        self.daily_usd.is_none() && self.monthly_usd.is_none()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BudgetPeriod {
+    Day,
+    Month,
+}
+
+/// Describes a [`UsageBudget`] that has been used up.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct BudgetWarning {
+    pub period: BudgetPeriod,
+    pub spent_usd: f64,
+    pub budget_usd: f64,
+}
+
+impl BudgetWarning {
+    pub fn message(&self) -> String {
+        let period = match self.period {
+            BudgetPeriod::Day => "today",
+            BudgetPeriod::Month => "this month",
+        };
+        format!(
+            "Estimated language model spending {period} is ${:.2}, which exceeds your budget of ${:.2}.",
+            self.spent_usd, self.budget_usd
+        )
+    }
+}
+
+/// The usage of a single completion request.
+#[derive(Debug, PartialEq, Clone)]
+pub struct UsageRecord {
+    pub day: NaiveDate,
+    pub provider_id: String,
+    pub model_id: String,
+    pub model_name: String,
+    pub project: Option<String>,
+    pub usage: TokenUsage,
+    /// The estimated cost, if the price of the model is known.
+    pub cost_usd: Option<f64>,
+}
+
+/// Aggregated usage of a model on a given day.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DailyModelUsage {
+    pub day: NaiveDate,
+    pub provider_id: String,
+    pub model_name: String,
+    pub request_count: u32,
+    pub usage: TokenUsage,
+    pub cost_usd: f64,
+}
+
+/// Aggregated usage of all models within a project.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ProjectUsage {
+    pub project: Option<String>,
+    pub request_count: u32,
+    pub usage: TokenUsage,
+    pub cost_usd: f64,
+}
+
+pub enum LanguageModelUsageEvent {
+    Recorded,
+}
+
+/// Tracks the tokens consumed by language model requests, persisting them locally.
+pub struct LanguageModelUsage;
+
+impl EventEmitter<LanguageModelUsageEvent> for LanguageModelUsage {}
+
+impl LanguageModelUsage {
+    pub fn global(cx: &AppContext) -> Option<Model<Self>> {
+        cx.try_global::<GlobalLanguageModelUsage>()
+            .map(|usage| usage.0.clone())
+    }
+
+    pub fn record(&mut self, record: UsageRecord, cx: &mut ModelContext<Self>) {
+        cx.spawn(|this, mut cx| async move {
+            USAGE_DB.insert_usage(record).await?;
+            this.update(&mut cx, |_, cx| cx.emit(LanguageModelUsageEvent::Recorded))
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Returns the usage of each model per day, starting at the given day.
+    pub fn daily_model_usage(
+        &self,
+        since: NaiveDate,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<DailyModelUsage>>> {
+        cx.background_executor()
+            .spawn(async move { USAGE_DB.daily_model_usage(since) })
+    }
+
+    /// Returns the usage of each project, starting at the given day.
+    pub fn project_usage(
+        &self,
+        since: NaiveDate,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<ProjectUsage>>> {
+        cx.background_executor()
+            .spawn(async move { USAGE_DB.project_usage(since) })
+    }
+
+    /// Checks whether the estimated spending has exceeded the given budget.
+    pub fn check_budget(
+        &self,
+        budget: UsageBudget,
+        cx: &AppContext,
+    ) -> Task<Result<Option<BudgetWarning>>> {
+        if budget.is_empty() {
+            return Task::ready(Ok(None));
+        }
+
+        cx.background_executor().spawn(async move {
+            let today = Local::now().date_naive();
+            if let Some(budget_usd) = budget.daily_usd {
+                let spent_usd = USAGE_DB.cost_since(today)?;
+                if spent_usd >= budget_usd {
+                    return Ok(Some(BudgetWarning {
+                        period: BudgetPeriod::Day,
+                        spent_usd,
+                        budget_usd,
+                    }));
+                }
+            }
+            if let Some(budget_usd) = budget.monthly_usd {
+                let spent_usd = USAGE_DB.cost_since(today.with_day(1).unwrap_or(today))?;
+                if spent_usd >= budget_usd {
+                    return Ok(Some(BudgetWarning {
+                        period: BudgetPeriod::Month,
+                        spent_usd,
+                        budget_usd,
+                    }));
+                }
+            }
+            Ok(None)
+        })
+    }
+}
+
+/// Collects the [`TokenUsage`] reported while a completion streams, and records the latest
+/// report once every clone of the reporter has been dropped.
+#[derive(Clone)]
+pub struct UsageReporter {
+    tx: Option<mpsc::UnboundedSender<TokenUsage>>,
+}
+
+impl UsageReporter {
+    pub fn new(
+        model: &(impl LanguageModel + ?Sized),
+        project: Option<SharedString>,
+        cx: &AsyncAppContext,
+    ) -> Self {
+        let Some(usage) = cx.try_read_global(|usage: &GlobalLanguageModelUsage, _| usage.0.clone())
+        else {
+            return Self { tx: None };
+        };
+
+        let provider_id = model.provider_id().0.to_string();
+        let model_id = model.id().0.to_string();
+        let model_name = model.name().0.to_string();
+        let pricing = model.pricing();
+        let (tx, mut rx) = mpsc::unbounded();
+        cx.spawn(|mut cx| async move {
+            let mut latest_usage = None;
+            while let Some(usage) = rx.next().await {
+                latest_usage = Some(usage);
+            }
+
+            if let Some(token_usage) = latest_usage {
+                let record = UsageRecord {
+                    day: Local::now().date_naive(),
+                    provider_id,
+                    model_id,
+                    model_name,
+                    project: project.map(|project| project.to_string()),
+                    usage: token_usage,
+                    cost_usd: pricing.map(|pricing| pricing.cost_usd(token_usage)),
+                };
+                usage
+                    .update(&mut cx, |usage, cx| usage.record(record, cx))
+                    .log_err();
+            }
+        })
+        .detach();
+
+        Self { tx: Some(tx) }
+    }
+
+    /// Reports the total usage of the completion so far, replacing any earlier report.
+    pub fn report(&self, usage: TokenUsage) {
+        if let Some(tx) = &self.tx {
+            tx.unbounded_send(usage).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pricing_cost() {
+        let pricing = LanguageModelPricing::new(3., 15.);
+        let usage = TokenUsage {
+            input_tokens: 2_000,
+            output_tokens: 500,
+            ..Default::default()
+        };
+        assert!((pricing.cost_usd(usage) - 0.0135).abs() < 1e-9);
+        assert_eq!(pricing.cost_usd(TokenUsage::default()), 0.);
+
+        // Cached prompt tokens are priced separately.
+        let pricing = pricing.with_cache_pricing(3.75, 0.3);
+        let usage = TokenUsage {
+            input_tokens: 2_000,
+            output_tokens: 500,
+            cache_creation_input_tokens: 10_000,
+            cache_read_input_tokens: 100_000,
+        };
+        assert_eq!(usage.total_tokens(), 112_500);
+        assert!((pricing.cost_usd(usage) - 0.081).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_budget_warning_message() {
+        let warning = BudgetWarning {
+            period: BudgetPeriod::Month,
+            spent_usd: 12.346,
+            budget_usd: 10.,
+        };
+        assert_eq!(
+            warning.message(),
+            "Estimated language model spending this month is $12.35, which exceeds your budget of $10.00."
+        );
+    }
+}
@@ -0,0 +1,320 @@
+use anyhow::{Context as _, Result};
+use chrono::NaiveDate;
+use db::sqlez::bindable::{Bind, Column, StaticColumnCount};
+use db::sqlez::statement::Statement;
+use db::sqlez_macros::sql;
+use db::{define_connection, query};
+
+use super::{DailyModelUsage, ProjectUsage, TokenUsage, UsageRecord};
+
+const DAY_FORMAT: &str = "%Y-%m-%d";
+
+fn format_day(day: NaiveDate) -> String {
+    day.format(DAY_FORMAT).to_string()
+}
+
+fn parse_day(day: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(day, DAY_FORMAT).with_context(|| format!("invalid day {day:?}"))
+}
+
+impl StaticColumnCount for UsageRecord {
+    fn column_count() -> usize {
+        10
+    }
+}
+
+impl Bind for UsageRecord {
+    fn bind(&self, statement: &Statement, start_index: i32) -> Result<i32> {
+        let start_index = statement.bind(&format_day(self.day), start_index)?;
+        let start_index = statement.bind(&self.provider_id, start_index)?;
+        let start_index = statement.bind(&self.model_id, start_index)?;
+        let start_index = statement.bind(&self.model_name, start_index)?;
+        let start_index = statement.bind(&self.project, start_index)?;
+        let start_index = statement.bind(&self.usage.input_tokens, start_index)?;
+        let start_index = statement.bind(&self.usage.output_tokens, start_index)?;
+        let start_index = statement.bind(&self.usage.cache_creation_input_tokens, start_index)?;
+        let start_index = statement.bind(&self.usage.cache_read_input_tokens, start_index)?;
+        statement.bind(&self.cost_usd, start_index)
+    }
+}
+
+impl StaticColumnCount for DailyModelUsage {
+    fn column_count() -> usize {
+        9
+    }
+}
+
+impl Column for DailyModelUsage {
+    fn column(statement: &mut Statement, start_index: i32) -> Result<(Self, i32)> {
+        let (day, start_index): (String, i32) = Column::column(statement, start_index)?;
+        let (provider_id, start_index) = Column::column(statement, start_index)?;
+        let (model_name, start_index) = Column::column(statement, start_index)?;
+        let (request_count, start_index) = Column::column(statement, start_index)?;
+        let (input_tokens, start_index) = Column::column(statement, start_index)?;
+        let (output_tokens, start_index) = Column::column(statement, start_index)?;
+        let (cache_creation_input_tokens, start_index) = Column::column(statement, start_index)?;
+        let (cache_read_input_tokens, start_index) = Column::column(statement, start_index)?;
+        let (cost_usd, start_index) = Column::column(statement, start_index)?;
+
+        let usage = Self {
+            day: parse_day(&day)?,
+            provider_id,
+            model_name,
+            request_count,
+            usage: TokenUsage {
+                input_tokens,
+                output_tokens,
+                cache_creation_input_tokens,
+                cache_read_input_tokens,
+            },
+            cost_usd,
+        };
+        Ok((usage, start_index))
+    }
+}
+
+impl StaticColumnCount for ProjectUsage {
+    fn column_count() -> usize {
+        7
+    }
+}
+
+impl Column for ProjectUsage {
+    fn column(statement: &mut Statement, start_index: i32) -> Result<(Self, i32)> {
+        let (project, start_index) = Column::column(statement, start_index)?;
+        let (request_count, start_index) = Column::column(statement, start_index)?;
+        let (input_tokens, start_index) = Column::column(statement, start_index)?;
+        let (output_tokens, start_index) = Column::column(statement, start_index)?;
+        let (cache_creation_input_tokens, start_index) = Column::column(statement, start_index)?;
+        let (cache_read_input_tokens, start_index) = Column::column(statement, start_index)?;
+        let (cost_usd, start_index) = Column::column(statement, start_index)?;
+
+        let usage = Self {
+            project,
+            request_count,
+            usage: TokenUsage {
+                input_tokens,
+                output_tokens,
+                cache_creation_input_tokens,
+                cache_read_input_tokens,
+            },
+            cost_usd,
+        };
+        Ok((usage, start_index))
+    }
+}
+
+define_connection!(
+    // Current schema shape using pseudo-rust syntax:
+    // language_model_usage(
+    //   id: usize,
+    //   day: String, // The local date of the request, formatted as YYYY-MM-DD
+    //   provider_id: String,
+    //   model_id: String,
+    //   model_name: String,
+    //   project: Option<String>,
+    //   input_tokens: u32,
+    //   output_tokens: u32,
+    //   cost_usd: Option<f64>,
+    //   cache_creation_input_tokens: u32,
+    //   cache_read_input_tokens: u32,
+    // )
+    pub static ref USAGE_DB: LanguageModelUsageDb<()> =
+        &[sql!(
+            CREATE TABLE language_model_usage(
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                day TEXT NOT NULL,
+                provider_id TEXT NOT NULL,
+                model_id TEXT NOT NULL,
+                model_name TEXT NOT NULL,
+                project TEXT,
+                input_tokens INTEGER NOT NULL,
+                output_tokens INTEGER NOT NULL,
+                cost_usd REAL
+            ) STRICT;
+
+            CREATE INDEX language_model_usage_day ON language_model_usage(day);
+        ),
+        sql!(
+            ALTER TABLE language_model_usage ADD COLUMN cache_creation_input_tokens INTEGER NOT NULL DEFAULT 0;
+            ALTER TABLE language_model_usage ADD COLUMN cache_read_input_tokens INTEGER NOT NULL DEFAULT 0;
+        )];
+);
+
+impl LanguageModelUsageDb {
+    query! {
+        pub async fn insert_usage(record: UsageRecord) -> Result<()> {
+            INSERT INTO language_model_usage
+                (day, provider_id, model_id, model_name, project, input_tokens, output_tokens, cache_creation_input_tokens, cache_read_input_tokens, cost_usd)
+            VALUES
+                (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        }
+    }
+
+    query! {
+        fn daily_model_usage_since(day: String) -> Result<Vec<DailyModelUsage>> {
+            SELECT
+                day,
+                provider_id,
+                model_name,
+                COUNT(*),
+                SUM(input_tokens),
+                SUM(output_tokens),
+                SUM(cache_creation_input_tokens),
+                SUM(cache_read_input_tokens),
+                TOTAL(cost_usd)
+            FROM language_model_usage
+            WHERE day >= ?
+            GROUP BY day, provider_id, model_name
+            ORDER BY day DESC, TOTAL(cost_usd) DESC
+        }
+    }
+
+    query! {
+        fn project_usage_since(day: String) -> Result<Vec<ProjectUsage>> {
+            SELECT
+                project,
+                COUNT(*),
+                SUM(input_tokens),
+                SUM(output_tokens),
+                SUM(cache_creation_input_tokens),
+                SUM(cache_read_input_tokens),
+                TOTAL(cost_usd)
+            FROM language_model_usage
+            WHERE day >= ?
+            GROUP BY project
+            ORDER BY
+                TOTAL(cost_usd) DESC,
+                SUM(input_tokens + output_tokens + cache_creation_input_tokens + cache_read_input_tokens) DESC
+        }
+    }
+
+    query! {
+        fn cost_since_day(day: String) -> Result<Option<f64>> {
+            SELECT TOTAL(cost_usd)
+            FROM language_model_usage
+            WHERE day >= ?
+        }
+    }
+
+    pub fn daily_model_usage(&self, since: NaiveDate) -> Result<Vec<DailyModelUsage>> {
+        self.daily_model_usage_since(format_day(since))
+    }
+
+    pub fn project_usage(&self, since: NaiveDate) -> Result<Vec<ProjectUsage>> {
+        self.project_usage_since(format_day(since))
+    }
+
+    pub fn cost_since(&self, since: NaiveDate) -> Result<f64> {
+        Ok(self.cost_since_day(format_day(since))?.unwrap_or(0.))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(
+        day: &str,
+        model_name: &str,
+        project: Option<&str>,
+        cost_usd: Option<f64>,
+    ) -> UsageRecord {
+        UsageRecord {
+            day: parse_day(day).unwrap(),
+            provider_id: "anthropic".into(),
+            model_id: model_name.into(),
+            model_name: model_name.into(),
+            project: project.map(Into::into),
+            usage: TokenUsage {
+                input_tokens: 100,
+                output_tokens: 10,
+                cache_creation_input_tokens: 20,
+                cache_read_input_tokens: 1000,
+            },
+            cost_usd,
+        }
+    }
+
+    #[gpui::test]
+    async fn test_usage_queries() {
+        let db = LanguageModelUsageDb(db::open_test_db("test_usage_queries").await);
+
+        db.insert_usage(record("2024-10-30", "sonnet", Some("zed"), Some(1.5)))
+            .await
+            .unwrap();
+        db.insert_usage(record("2024-11-01", "sonnet", Some("zed"), Some(0.25)))
+            .await
+            .unwrap();
+        db.insert_usage(record("2024-11-01", "sonnet", None, Some(0.5)))
+            .await
+            .unwrap();
+        db.insert_usage(record("2024-11-02", "llama", Some("zed"), None))
+            .await
+            .unwrap();
+
+        let since = parse_day("2024-11-01").unwrap();
+        assert_eq!(db.cost_since(since).unwrap(), 0.75);
+        assert_eq!(db.cost_since(parse_day("2024-12-01").unwrap()).unwrap(), 0.);
+
+        assert_eq!(
+            db.daily_model_usage(since).unwrap(),
+            vec![
+                DailyModelUsage {
+                    day: parse_day("2024-11-02").unwrap(),
+                    provider_id: "anthropic".into(),
+                    model_name: "llama".into(),
+                    request_count: 1,
+                    usage: TokenUsage {
+                        input_tokens: 100,
+                        output_tokens: 10,
+                        cache_creation_input_tokens: 20,
+                        cache_read_input_tokens: 1000,
+                    },
+                    cost_usd: 0.,
+                },
+                DailyModelUsage {
+                    day: since,
+                    provider_id: "anthropic".into(),
+                    model_name: "sonnet".into(),
+                    request_count: 2,
+                    usage: TokenUsage {
+                        input_tokens: 200,
+                        output_tokens: 20,
+                        cache_creation_input_tokens: 40,
+                        cache_read_input_tokens: 2000,
+                    },
+                    cost_usd: 0.75,
+                },
+            ]
+        );
+
+        assert_eq!(
+            db.project_usage(since).unwrap(),
+            vec![
+                ProjectUsage {
+                    project: None,
+                    request_count: 1,
+                    usage: TokenUsage {
+                        input_tokens: 100,
+                        output_tokens: 10,
+                        cache_creation_input_tokens: 20,
+                        cache_read_input_tokens: 1000,
+                    },
+                    cost_usd: 0.5,
+                },
+                ProjectUsage {
+                    project: Some("zed".into()),
+                    request_count: 2,
+                    usage: TokenUsage {
+                        input_tokens: 200,
+                        output_tokens: 20,
+                        cache_creation_input_tokens: 40,
+                        cache_read_input_tokens: 2000,
+                    },
+                    cost_usd: 0.25,
+                },
+            ]
+        );
+    }
+}
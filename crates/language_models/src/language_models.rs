@@ -1,9 +1,16 @@
 use std::sync::Arc;
 
+use anyhow::Result;
 use client::{Client, UserStore};
 use fs::Fs;
-use gpui::{AppContext, Model, ModelContext};
-use language_model::{LanguageModelProviderId, LanguageModelRegistry, ZED_CLOUD_PROVIDER_ID};
+use gpui::{AppContext, Model, ModelContext, PromptLevel, SharedString, Task, WindowContext};
+use language_model::{
+    BudgetWarning, LanguageModelProviderId, LanguageModelRegistry, LanguageModelUsage,
+    ZED_CLOUD_PROVIDER_ID,
+};
+use project::Project;
+use settings::Settings as _;
+use util::ResultExt as _;
 
 mod logging;
 pub mod provider;
@@ -31,6 +38,45 @@ pub fn init(
     });
 }
 
+/// Checks the recorded usage against the budget configured in the settings, returning a
+/// warning when it has been used up.
+pub fn check_usage_budget(cx: &AppContext) -> Task<Result<Option<BudgetWarning>>> {
+    let budget = AllLanguageModelSettings::get_global(cx).usage_budget;
+    match LanguageModelUsage::global(cx) {
+        Some(usage) => usage.read(cx).check_budget(budget, cx),
+        None => Task::ready(Ok(None)),
+    }
+}
+
+/// Checks the usage budget before a request is sent. If it has been used up, the user is asked
+/// whether to send the request anyway, and the task resolves to `false` if they decline.
+pub fn confirm_usage_budget(cx: &mut WindowContext) -> Task<Result<bool>> {
+    let check_budget = check_usage_budget(cx);
+    cx.spawn(|mut cx| async move {
+        let Some(warning) = check_budget.await.log_err().flatten() else {
+            return Ok(true);
+        };
+        let answer = cx.update(|cx| {
+            cx.prompt(
+                PromptLevel::Warning,
+                &warning.message(),
+                Some("You can review your usage with the `assistant: show usage` action."),
+                &["Send Anyway", "Cancel"],
+            )
+        })?;
+        Ok(answer.await? == 0)
+    })
+}
+
+/// The name that usage of language models is attributed to in the given project, which is the
+/// name of its first visible worktree.
+pub fn usage_project_name(project: &Project, cx: &AppContext) -> Option<SharedString> {
+    project
+        .worktree_root_names(cx)
+        .next()
+        .map(|name| SharedString::from(name.to_string()))
+}
+
 fn register_language_model_providers(
     registry: &mut LanguageModelRegistry,
     user_store: Model<UserStore>,
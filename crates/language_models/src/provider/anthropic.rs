@@ -12,8 +12,9 @@ use gpui::{
 use http_client::HttpClient;
 use language_model::{
     LanguageModel, LanguageModelCacheConfiguration, LanguageModelId, LanguageModelName,
-    LanguageModelProvider, LanguageModelProviderId, LanguageModelProviderName,
-    LanguageModelProviderState, LanguageModelRequest, RateLimiter, Role,
+    LanguageModelPricing, LanguageModelProvider, LanguageModelProviderId,
+    LanguageModelProviderName, LanguageModelProviderState, LanguageModelRequest, RateLimiter, Role,
+    TokenUsage,
 };
 use language_model::{LanguageModelCompletionEvent, LanguageModelToolUse, StopReason};
 use schemars::JsonSchema;
@@ -359,6 +360,10 @@ impl LanguageModel for AnthropicModel {
         Some(self.model.max_output_tokens())
     }
 
+    fn pricing(&self) -> Option<LanguageModelPricing> {
+        pricing(&self.model)
+    }
+
     fn count_tokens(
         &self,
         request: LanguageModelRequest,
@@ -432,6 +437,22 @@ impl LanguageModel for AnthropicModel {
     }
 }
 
+/// Returns the list price of the given model, in US dollars per million tokens.
+pub fn pricing(model: &anthropic::Model) -> Option<LanguageModelPricing> {
+    match model {
+        anthropic::Model::Claude3_5Sonnet | anthropic::Model::Claude3Sonnet => {
+            Some(LanguageModelPricing::new(3., 15.).with_cache_pricing(3.75, 0.3))
+        }
+        anthropic::Model::Claude3Opus => {
+            Some(LanguageModelPricing::new(15., 75.).with_cache_pricing(18.75, 1.5))
+        }
+        anthropic::Model::Claude3Haiku => {
+            Some(LanguageModelPricing::new(0.25, 1.25).with_cache_pricing(0.3, 0.03))
+        }
+        anthropic::Model::Custom { .. } => None,
+    }
+}
+
 pub fn map_to_language_model_completion_events(
     events: Pin<Box<dyn Send + Stream<Item = Result<Event, AnthropicError>>>>,
 ) -> impl Stream<Item = Result<LanguageModelCompletionEvent>> {
@@ -444,12 +465,14 @@ pub fn map_to_language_model_completion_events(
     struct State {
         events: Pin<Box<dyn Send + Stream<Item = Result<Event, AnthropicError>>>>,
         tool_uses_by_index: HashMap<usize, RawToolUse>,
+        usage: TokenUsage,
     }
 
     futures::stream::unfold(
         State {
             events,
             tool_uses_by_index: HashMap::default(),
+            usage: TokenUsage::default(),
         },
         |mut state| async move {
             while let Some(event) = state.events.next().await {
@@ -516,14 +539,26 @@ pub fn map_to_language_model_completion_events(
                             }
                         }
                         Event::MessageStart { message } => {
+                            let usage = message.usage;
+                            state.usage = TokenUsage {
+                                input_tokens: usage.input_tokens.unwrap_or(0),
+                                output_tokens: usage.output_tokens.unwrap_or(0),
+                                cache_creation_input_tokens: usage
+                                    .cache_creation_input_tokens
+                                    .unwrap_or(0),
+                                cache_read_input_tokens: usage.cache_read_input_tokens.unwrap_or(0),
+                            };
                             return Some((
                                 Some(Ok(LanguageModelCompletionEvent::StartMessage {
                                     message_id: message.id,
                                 })),
                                 state,
-                            ))
+                            ));
                         }
-                        Event::MessageDelta { delta, .. } => {
+                        Event::MessageDelta { delta, usage } => {
+                            if let Some(output_tokens) = usage.output_tokens {
+                                state.usage.output_tokens = output_tokens;
+                            }
                             if let Some(stop_reason) = delta.stop_reason.as_deref() {
                                 let stop_reason = match stop_reason {
                                     "end_turn" => StopReason::EndTurn,
@@ -538,6 +573,12 @@ pub fn map_to_language_model_completion_events(
                                 ));
                             }
                         }
+                        Event::MessageStop => {
+                            return Some((
+                                Some(Ok(LanguageModelCompletionEvent::UsageUpdate(state.usage))),
+                                state,
+                            ));
+                        }
                         Event::Error { error } => {
                             return Some((
                                 Some(Err(anyhow!(AnthropicError::ApiError(error)))),
@@ -6,7 +6,7 @@ use language_model::LanguageModelCompletionEvent;
 use language_model::{
     LanguageModel, LanguageModelId, LanguageModelName, LanguageModelProvider,
     LanguageModelProviderId, LanguageModelProviderName, LanguageModelProviderState,
-    LanguageModelRequest, RateLimiter, Role,
+    LanguageModelRequest, RateLimiter, Role, TokenUsage,
 };
 use ollama::{
     get_models, preload_model, stream_chat_completion, ChatMessage, ChatOptions, ChatRequest,
@@ -337,7 +337,8 @@ impl LanguageModel for OllamaLanguageModel {
         let future = self.request_limiter.stream(async move {
             let response = stream_chat_completion(http_client.as_ref(), &api_url, request).await?;
             let stream = response
-                .filter_map(|response| async move {
+                .flat_map(|response| {
+                    let mut events = Vec::new();
                     match response {
                         Ok(delta) => {
                             let content = match delta.message {
@@ -345,22 +346,28 @@ impl LanguageModel for OllamaLanguageModel {
                                 ChatMessage::Assistant { content, .. } => content,
                                 ChatMessage::System { content } => content,
                             };
-                            Some(Ok(content))
+                            events.push(Ok(LanguageModelCompletionEvent::Text(content)));
+                            if let (Some(input_tokens), Some(output_tokens)) =
+                                (delta.prompt_eval_count, delta.eval_count)
+                            {
+                                events.push(Ok(LanguageModelCompletionEvent::UsageUpdate(
+                                    TokenUsage {
+                                        input_tokens,
+                                        output_tokens,
+                                        ..Default::default()
+                                    },
+                                )));
+                            }
                         }
-                        Err(error) => Some(Err(error)),
+                        Err(error) => events.push(Err(error)),
                     }
+                    futures::stream::iter(events)
                 })
                 .boxed();
             Ok(stream)
         });
 
-        async move {
-            Ok(future
-                .await?
-                .map(|result| result.map(LanguageModelCompletionEvent::Text))
-                .boxed())
-        }
-        .boxed()
+        async move { Ok(future.await?.boxed()) }.boxed()
     }
 
     fn use_any_tool(
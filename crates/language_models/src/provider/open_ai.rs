@@ -1,7 +1,7 @@
 use anyhow::{anyhow, Result};
 use collections::BTreeMap;
 use editor::{Editor, EditorElement, EditorStyle};
-use futures::{future::BoxFuture, FutureExt, StreamExt};
+use futures::{future::BoxFuture, FutureExt, Stream, StreamExt};
 use gpui::{
     AnyView, AppContext, AsyncAppContext, FontStyle, ModelContext, Subscription, Task, TextStyle,
     View, WhiteSpace,
@@ -9,8 +9,9 @@ use gpui::{
 use http_client::HttpClient;
 use language_model::{
     LanguageModel, LanguageModelCompletionEvent, LanguageModelId, LanguageModelName,
-    LanguageModelProvider, LanguageModelProviderId, LanguageModelProviderName,
-    LanguageModelProviderState, LanguageModelRequest, RateLimiter, Role,
+    LanguageModelPricing, LanguageModelProvider, LanguageModelProviderId,
+    LanguageModelProviderName, LanguageModelProviderState, LanguageModelRequest, RateLimiter, Role,
+    TokenUsage,
 };
 use open_ai::{
     stream_completion, FunctionDefinition, ResponseStreamEvent, ToolChoice, ToolDefinition,
@@ -275,6 +276,10 @@ impl LanguageModel for OpenAiLanguageModel {
         self.model.max_output_tokens()
     }
 
+    fn pricing(&self) -> Option<LanguageModelPricing> {
+        pricing(&self.model)
+    }
+
     fn count_tokens(
         &self,
         request: LanguageModelRequest,
@@ -293,12 +298,8 @@ impl LanguageModel for OpenAiLanguageModel {
     > {
         let request = request.into_open_ai(self.model.id().into(), self.max_output_tokens());
         let completions = self.stream_completion(request, cx);
-        async move {
-            Ok(open_ai::extract_text_from_events(completions.await?)
-                .map(|result| result.map(LanguageModelCompletionEvent::Text))
-                .boxed())
-        }
-        .boxed()
+        async move { Ok(map_to_language_model_completion_events(completions.await?).boxed()) }
+            .boxed()
     }
 
     fn use_any_tool(
@@ -339,6 +340,46 @@ impl LanguageModel for OpenAiLanguageModel {
     }
 }
 
+/// Returns the list price of the given model, in US dollars per million tokens.
+pub fn pricing(model: &open_ai::Model) -> Option<LanguageModelPricing> {
+    match model {
+        open_ai::Model::ThreePointFiveTurbo => Some(LanguageModelPricing::new(0.5, 1.5)),
+        open_ai::Model::Four => Some(LanguageModelPricing::new(30., 60.)),
+        open_ai::Model::FourTurbo => Some(LanguageModelPricing::new(10., 30.)),
+        open_ai::Model::FourOmni => Some(LanguageModelPricing::new(2.5, 10.)),
+        open_ai::Model::FourOmniMini => Some(LanguageModelPricing::new(0.15, 0.6)),
+        open_ai::Model::O1Preview => Some(LanguageModelPricing::new(15., 60.)),
+        open_ai::Model::O1Mini => Some(LanguageModelPricing::new(3., 12.)),
+        open_ai::Model::Custom { .. } => None,
+    }
+}
+
+pub fn map_to_language_model_completion_events(
+    events: impl Stream<Item = Result<ResponseStreamEvent>>,
+) -> impl Stream<Item = Result<LanguageModelCompletionEvent>> {
+    events.flat_map(|event| {
+        let mut completion_events = Vec::new();
+        match event {
+            Ok(mut event) => {
+                if let Some(text) = event.choices.pop().and_then(|choice| choice.delta.content) {
+                    completion_events.push(Ok(LanguageModelCompletionEvent::Text(text)));
+                }
+                if let Some(usage) = event.usage {
+                    completion_events.push(Ok(LanguageModelCompletionEvent::UsageUpdate(
+                        TokenUsage {
+                            input_tokens: usage.prompt_tokens,
+                            output_tokens: usage.completion_tokens,
+                            ..Default::default()
+                        },
+                    )));
+                }
+            }
+            Err(error) => completion_events.push(Err(error)),
+        }
+        futures::stream::iter(completion_events)
+    })
+}
+
 pub fn count_open_ai_tokens(
     request: LanguageModelRequest,
     model: open_ai::Model,
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use anyhow::Result;
 use gpui::AppContext;
-use language_model::LanguageModelCacheConfiguration;
+use language_model::{LanguageModelCacheConfiguration, UsageBudget};
 use project::Fs;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -59,6 +59,7 @@ pub struct AllLanguageModelSettings {
     pub zed_dot_dev: ZedDotDevSettings,
     pub google: GoogleSettings,
     pub copilot_chat: CopilotChatSettings,
+    pub usage_budget: UsageBudget,
 }
 
 #[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
@@ -70,6 +71,9 @@ pub struct AllLanguageModelSettingsContent {
     pub zed_dot_dev: Option<ZedDotDevSettingsContent>,
     pub google: Option<GoogleSettingsContent>,
     pub copilot_chat: Option<CopilotChatSettingsContent>,
+    /// Soft limits on the estimated cost of language model requests.
+    /// A warning is shown before sending a request once a limit has been reached.
+    pub usage_budget: Option<UsageBudget>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
@@ -312,6 +316,16 @@ impl settings::Settings for AllLanguageModelSettings {
                     .as_ref()
                     .and_then(|s| s.available_models.clone()),
             );
+
+            // Usage budget
+            if let Some(usage_budget) = value.usage_budget {
+                if usage_budget.daily_usd.is_some() {
+                    settings.usage_budget.daily_usd = usage_budget.daily_usd;
+                }
+                if usage_budget.monthly_usd.is_some() {
+                    settings.usage_budget.monthly_usd = usage_budget.monthly_usd;
+                }
+            }
         }
 
         Ok(settings)
@@ -1089,6 +1089,7 @@ impl SearchableItem for LspLogView {
             case: true,
             word: true,
             regex: true,
+            structural: false,
             // LSP log is read-only.
             replacement: false,
             selection: false,
@@ -0,0 +1,236 @@
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, ParentElement,
+    Render, Styled, Task, View, ViewContext, VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing, Tooltip};
+use util::ResultExt;
+use workspace::{notifications::DetachAndPromptErr, ModalView, Workspace};
+
+actions!(layout_selector, [Toggle]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(LayoutSelector::register).detach();
+}
+
+pub struct LayoutSelector {
+    picker: View<Picker<LayoutSelectorDelegate>>,
+}
+
+impl LayoutSelector {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &Toggle, cx| {
+            let weak_workspace = cx.view().downgrade();
+            workspace.toggle_modal(cx, |cx| LayoutSelector::new(weak_workspace, cx));
+        });
+    }
+
+    fn new(workspace: WeakView<Workspace>, cx: &mut ViewContext<Self>) -> Self {
+        let layouts = workspace::named_layouts().log_err().unwrap_or_default();
+        let delegate = LayoutSelectorDelegate {
+            layout_selector: cx.view().downgrade(),
+            workspace,
+            layouts,
+            matches: Vec::new(),
+            new_layout_name: None,
+            selected_index: 0,
+        };
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl Render for LayoutSelector {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for LayoutSelector {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for LayoutSelector {}
+impl ModalView for LayoutSelector {}
+
+pub struct LayoutSelectorDelegate {
+    layout_selector: WeakView<LayoutSelector>,
+    workspace: WeakView<Workspace>,
+    layouts: Vec<String>,
+    matches: Vec<StringMatch>,
+    /// The query, when it isn't the name of an existing layout, so that the current layout
+    /// can be saved under it.
+    new_layout_name: Option<String>,
+    selected_index: usize,
+}
+
+impl LayoutSelectorDelegate {
+    fn save_layout(&self, name: String, cx: &mut ViewContext<Picker<Self>>) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.save_named_layout(name, cx).detach_and_prompt_err(
+                    "Failed to save layout",
+                    cx,
+                    |_, _| None,
+                );
+            })
+            .log_err();
+    }
+
+    fn delete_layout(&mut self, ix: usize, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(mat) = self.matches.get(ix) else {
+            return;
+        };
+        let name = self.layouts[mat.candidate_id].clone();
+        let delete = workspace::delete_named_layout(name, cx);
+        cx.spawn(move |this, mut cx| async move {
+            delete.await?;
+            let layouts = workspace::named_layouts()?;
+            this.update(&mut cx, |picker, cx| {
+                picker.delegate.layouts = layouts;
+                picker.delegate.selected_index = ix.saturating_sub(1);
+                picker.update_matches(picker.query(cx), cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+impl PickerDelegate for LayoutSelectorDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Load a layout, or type a name to save the current one…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len() + self.new_layout_name.is_some() as usize
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .layouts
+            .iter()
+            .enumerate()
+            .map(|(id, name)| StringMatchCandidate::new(id, name.clone()))
+            .collect::<Vec<_>>();
+        cx.spawn(|this, mut cx| async move {
+            let query = query.trim();
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.new_layout_name = (!query.is_empty()
+                    && !delegate.layouts.iter().any(|name| name == query))
+                .then(|| query.to_string());
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.match_count().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let name = self.layouts[mat.candidate_id].clone();
+            if secondary {
+                self.save_layout(name, cx);
+            } else {
+                self.workspace
+                    .update(cx, |workspace, cx| {
+                        workspace
+                            .apply_named_layout(name, cx)
+                            .detach_and_prompt_err("Failed to load layout", cx, |_, _| None);
+                    })
+                    .log_err();
+            }
+        } else if let Some(name) = self.new_layout_name.clone() {
+            self.save_layout(name, cx);
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.layout_selector
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        "Type a name to save the current layout".into()
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let item = ListItem::new(ix)
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .selected(selected);
+
+        let Some(mat) = self.matches.get(ix) else {
+            let name = self.new_layout_name.as_ref()?;
+            return Some(
+                item.start_slot(Icon::new(IconName::Plus).color(Color::Muted))
+                    .child(Label::new(format!("Save current layout as \"{name}\""))),
+            );
+        };
+
+        let delete_button = IconButton::new("delete", IconName::Close)
+            .icon_size(IconSize::Small)
+            .on_click(cx.listener(move |this, _, cx| {
+                cx.stop_propagation();
+                cx.prevent_default();
+                this.delegate.delete_layout(ix, cx);
+            }))
+            .tooltip(|cx| Tooltip::text("Delete Layout", cx));
+        let item = item.child(HighlightedLabel::new(
+            mat.string.clone(),
+            mat.positions.clone(),
+        ));
+        Some(if selected {
+            item.end_slot(delete_button)
+        } else {
+            item.end_hover_slot(delete_button)
+        })
+    }
+}
@@ -84,7 +84,7 @@ pub struct LanguageServer {
     process_name: Arc<str>,
     capabilities: RwLock<ServerCapabilities>,
     code_action_kinds: Option<Vec<CodeActionKind>>,
-    notification_handlers: Arc<Mutex<HashMap<&'static str, NotificationHandler>>>,
+    notification_handlers: Arc<Mutex<HashMap<SharedString, NotificationHandler>>>,
     response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
     io_handlers: Arc<Mutex<HashMap<i32, IoHandler>>>,
     executor: BackgroundExecutor,
@@ -166,8 +166,8 @@ impl<'a> From<&'a str> for LanguageServerName {
 /// Handle to a language server RPC activity subscription.
 pub enum Subscription {
     Notification {
-        method: &'static str,
-        notification_handlers: Option<Arc<Mutex<HashMap<&'static str, NotificationHandler>>>>,
+        method: SharedString,
+        notification_handlers: Option<Arc<Mutex<HashMap<SharedString, NotificationHandler>>>>,
     },
     Io {
         id: i32,
@@ -487,7 +487,7 @@ impl LanguageServer {
     async fn handle_input<Stdout, F>(
         stdout: Stdout,
         mut on_unhandled_notification: F,
-        notification_handlers: Arc<Mutex<HashMap<&'static str, NotificationHandler>>>,
+        notification_handlers: Arc<Mutex<HashMap<SharedString, NotificationHandler>>>,
         response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
         io_handlers: Arc<Mutex<HashMap<i32, IoHandler>>>,
         cx: AsyncAppContext,
@@ -907,14 +907,27 @@ impl LanguageServer {
         self.notification_handlers.lock().contains_key(T::METHOD)
     }
 
+    /// Checks if a handler has been registered for the given method, which may be a request or a
+    /// notification.
+    pub fn has_handler(&self, method: &str) -> bool {
+        self.notification_handlers.lock().contains_key(method)
+    }
+
+    /// Register a handler to handle incoming notifications whose method is only known at runtime,
+    /// such as those that aren't part of the LSP specification.
     #[must_use]
-    fn on_custom_notification<Params, F>(&self, method: &'static str, mut f: F) -> Subscription
+    pub fn on_custom_notification<Params, F>(
+        &self,
+        method: impl Into<SharedString>,
+        mut f: F,
+    ) -> Subscription
     where
         F: 'static + FnMut(Params, AsyncAppContext) + Send,
         Params: DeserializeOwned,
     {
+        let method = method.into();
         let prev_handler = self.notification_handlers.lock().insert(
-            method,
+            method.clone(),
             Box::new(move |_, params, cx| {
                 if let Some(params) = serde_json::from_value(params).log_err() {
                     f(params, cx);
@@ -931,17 +944,25 @@ impl LanguageServer {
         }
     }
 
+    /// Register a handler to handle incoming requests whose method is only known at runtime,
+    /// such as those that aren't part of the LSP specification.
     #[must_use]
-    fn on_custom_request<Params, Res, Fut, F>(&self, method: &'static str, mut f: F) -> Subscription
+    pub fn on_custom_request<Params, Res, Fut, F>(
+        &self,
+        method: impl Into<SharedString>,
+        mut f: F,
+    ) -> Subscription
     where
         F: 'static + FnMut(Params, AsyncAppContext) -> Fut + Send,
         Fut: 'static + Future<Output = Result<Res>>,
         Params: DeserializeOwned + Send + 'static,
         Res: Serialize,
     {
+        let method = method.into();
+        let subscribed_method = method.clone();
         let outbound_tx = self.outbound_tx.clone();
         let prev_handler = self.notification_handlers.lock().insert(
-            method,
+            method.clone(),
             Box::new(move |id, params, cx| {
                 if let Some(id) = id {
                     match serde_json::from_value(params) {
@@ -998,7 +1019,7 @@ impl LanguageServer {
             "registered multiple handlers for the same LSP method"
         );
         Subscription::Notification {
-            method,
+            method: subscribed_method,
             notification_handlers: Some(self.notification_handlers.clone()),
         }
     }
@@ -1059,6 +1080,23 @@ impl LanguageServer {
         )
     }
 
+    /// Sends a RPC request whose method is only known at runtime, such as one that isn't part of
+    /// the LSP specification.
+    pub fn custom_request(
+        &self,
+        method: impl Into<SharedString>,
+        params: Value,
+    ) -> impl LspRequestFuture<Result<Value>> {
+        Self::send_request(
+            &self.next_id,
+            &self.response_handlers,
+            &self.outbound_tx,
+            &self.executor,
+            method.into(),
+            params,
+        )
+    }
+
     fn request_internal<T: request::Request>(
         next_id: &AtomicI32,
         response_handlers: &Mutex<Option<HashMap<RequestId, ResponseHandler>>>,
@@ -1068,12 +1106,34 @@ impl LanguageServer {
     ) -> impl LspRequestFuture<Result<T::Result>>
     where
         T::Result: 'static + Send,
+    {
+        Self::send_request(
+            next_id,
+            response_handlers,
+            outbound_tx,
+            executor,
+            T::METHOD.into(),
+            params,
+        )
+    }
+
+    fn send_request<Params, Res>(
+        next_id: &AtomicI32,
+        response_handlers: &Mutex<Option<HashMap<RequestId, ResponseHandler>>>,
+        outbound_tx: &channel::Sender<String>,
+        executor: &BackgroundExecutor,
+        method: SharedString,
+        params: Params,
+    ) -> impl LspRequestFuture<Result<Res>>
+    where
+        Params: Serialize,
+        Res: 'static + Send + DeserializeOwned,
     {
         let id = next_id.fetch_add(1, SeqCst);
         let message = serde_json::to_string(&Request {
             jsonrpc: JSON_RPC_VERSION,
             id: RequestId::Int(id),
-            method: T::METHOD,
+            method: &method,
             params,
         })
         .unwrap();
@@ -1130,7 +1190,6 @@ impl LanguageServer {
                 }
             });
 
-            let method = T::METHOD;
             select! {
                 response = rx.fuse() => {
                     let elapsed = started.elapsed();
@@ -1154,13 +1213,27 @@ impl LanguageServer {
         Self::notify_internal::<T>(&self.outbound_tx, params)
     }
 
+    /// Sends a RPC notification whose method is only known at runtime, such as one that isn't part
+    /// of the LSP specification.
+    pub fn custom_notify(&self, method: &str, params: Value) -> Result<()> {
+        Self::send_notification(&self.outbound_tx, method, params)
+    }
+
     fn notify_internal<T: notification::Notification>(
         outbound_tx: &channel::Sender<String>,
         params: T::Params,
+    ) -> Result<()> {
+        Self::send_notification(outbound_tx, T::METHOD, params)
+    }
+
+    fn send_notification<Params: Serialize>(
+        outbound_tx: &channel::Sender<String>,
+        method: &str,
+        params: Params,
     ) -> Result<()> {
         let message = serde_json::to_string(&Notification {
             jsonrpc: JSON_RPC_VERSION,
-            method: T::METHOD,
+            method,
             params,
         })
         .unwrap();
@@ -194,6 +194,10 @@ pub struct ChatResponseDelta {
     pub done_reason: Option<String>,
     #[allow(unused)]
     pub done: bool,
+    /// The number of tokens in the prompt, sent with the final response.
+    pub prompt_eval_count: Option<u32>,
+    /// The number of tokens generated, sent with the final response.
+    pub eval_count: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize)]
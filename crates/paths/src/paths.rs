@@ -125,6 +125,14 @@ pub fn database_dir() -> &'static PathBuf {
     DATABASE_DIR.get_or_init(|| support_dir().join("db"))
 }
 
+/// Returns the path to the local history directory.
+///
+/// This is where the contents files had each time they were saved are kept.
+pub fn local_history_dir() -> &'static PathBuf {
+    static LOCAL_HISTORY_DIR: OnceLock<PathBuf> = OnceLock::new();
+    LOCAL_HISTORY_DIR.get_or_init(|| support_dir().join("local_history"))
+}
+
 /// Returns the path to the crashes directory, if it exists for the current platform.
 pub fn crashes_dir() -> &'static Option<PathBuf> {
     static CRASHES_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
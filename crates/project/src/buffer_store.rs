@@ -1,4 +1,6 @@
 use crate::{
+    local_history::LocalHistory,
+    project_settings::ProjectSettings,
     search::SearchQuery,
     worktree_store::{WorktreeStore, WorktreeStoreEvent},
     ProjectItem as _, ProjectPath,
@@ -6,6 +8,7 @@ use crate::{
 use ::git::{parse_git_remote_url, BuildPermalinkParams, GitHostingProviderRegistry};
 use anyhow::{anyhow, Context as _, Result};
 use client::Client;
+use clock::ReplicaId;
 use collections::{hash_map, HashMap, HashSet};
 use fs::Fs;
 use futures::{channel::oneshot, future::Shared, Future, FutureExt as _, StreamExt};
@@ -23,6 +26,7 @@ use language::{
     Buffer, BufferEvent, Capability, DiskState, File as _, Language, Operation,
 };
 use rpc::{proto, AnyProtoClient, ErrorExt as _, TypedEnvelope};
+use settings::Settings as _;
 use smol::channel::Receiver;
 use std::{io, ops::Range, path::Path, str::FromStr as _, sync::Arc, time::Instant};
 use text::{BufferId, LineEnding, Rope};
@@ -41,6 +45,9 @@ pub struct BufferStore {
     opened_buffers: HashMap<BufferId, OpenBuffer>,
     downstream_client: Option<(AnyProtoClient, u64)>,
     shared_buffers: HashMap<proto::PeerId, HashMap<BufferId, SharedBuffer>>,
+    /// Buffers that were shared with guests who lost their connection, kept open so that the
+    /// edits those guests made while offline can be applied to them when they reconnect.
+    offline_shared_buffers: HashMap<ReplicaId, HashMap<BufferId, SharedBuffer>>,
 }
 
 #[derive(Hash, Eq, PartialEq, Clone)]
@@ -429,12 +436,28 @@ impl LocalBufferStore {
             has_changed_file = true;
         }
 
+        let record_in_local_history = LocalHistory::global(cx).and_then(|local_history| {
+            let settings = ProjectSettings::get_global(cx).local_history;
+            let abs_path = worktree.read(cx).absolutize(&path).ok()?;
+            settings
+                .enabled
+                .then(|| local_history.record_save(abs_path, buffer, settings))
+        });
+
         let save = worktree.update(cx, |worktree, cx| {
             worktree.write_file(path.as_ref(), text, line_ending, cx)
         });
 
         cx.spawn(move |this, mut cx| async move {
             let new_file = save.await?;
+            // Recorded before the buffer is marked as saved, so that the new snapshot is
+            // there for anyone reacting to the save.
+            if let Some(record_in_local_history) = record_in_local_history {
+                cx.background_executor()
+                    .spawn(record_in_local_history)
+                    .await
+                    .log_err();
+            }
             let mtime = new_file.disk_state().mtime();
             this.update(&mut cx, |this, cx| {
                 if let Some((downstream_client, project_id)) = this.downstream_client.clone() {
@@ -781,12 +804,33 @@ impl LocalBufferStore {
         worktree: Model<Worktree>,
         cx: &mut ModelContext<BufferStore>,
     ) -> Task<Result<Model<Buffer>>> {
+        let large_file_threshold = ProjectSettings::get_global(cx).large_file_threshold();
         let load_buffer = worktree.update(cx, |worktree, cx| {
-            let load_file = worktree.load_file(path.as_ref(), cx);
             let reservation = cx.reserve_model();
             let buffer_id = BufferId::from(reservation.entity_id().as_non_zero_u64());
-            cx.spawn(move |_, mut cx| async move {
-                let loaded = load_file.await?;
+            let load_large_file = worktree.load_large_file(path.as_ref(), large_file_threshold, cx);
+            let path = path.clone();
+            cx.spawn(move |this, mut cx| async move {
+                if let Some(loaded) = load_large_file.await? {
+                    let content = loaded.content.clone();
+                    let text_buffer = cx
+                        .background_executor()
+                        .spawn(async move {
+                            let text = text::Rope::from(content.read_page(0).as_str());
+                            text::Buffer::new_normalized(0, buffer_id, content.line_ending(), text)
+                        })
+                        .await;
+                    return cx.insert_model(reservation, |_| {
+                        Buffer::build(text_buffer, Some(loaded.file), Capability::ReadOnly)
+                            .with_large_file(loaded.content)
+                    });
+                }
+
+                let loaded = this
+                    .update(&mut cx, |worktree, cx| {
+                        worktree.load_file(path.as_ref(), cx)
+                    })?
+                    .await?;
                 let text_buffer = cx
                     .background_executor()
                     .spawn(async move { text::Buffer::new(0, buffer_id, loaded.text) })
@@ -913,6 +957,7 @@ impl BufferStore {
             downstream_client: None,
             opened_buffers: Default::default(),
             shared_buffers: Default::default(),
+            offline_shared_buffers: Default::default(),
             loading_buffers: Default::default(),
             loading_change_sets: Default::default(),
             worktree_store,
@@ -939,6 +984,7 @@ impl BufferStore {
             loading_buffers: Default::default(),
             loading_change_sets: Default::default(),
             shared_buffers: Default::default(),
+            offline_shared_buffers: Default::default(),
             worktree_store,
         }
     }
@@ -1019,6 +1065,11 @@ impl BufferStore {
         if let Some(change_set) = self.get_unstaged_changes(buffer_id) {
             return Task::ready(Ok(change_set));
         }
+        if buffer.read(cx).is_large_file() {
+            return Task::ready(Err(anyhow!(
+                "unstaged changes aren't computed for large files"
+            )));
+        }
 
         let task = match self.loading_change_sets.entry(buffer_id) {
             hash_map::Entry::Occupied(e) => e.get().clone(),
@@ -1382,17 +1433,21 @@ impl BufferStore {
     }
 
     pub fn disconnected_from_host(&mut self, cx: &mut AppContext) {
-        for open_buffer in self.opened_buffers.values_mut() {
-            if let Some(buffer) = open_buffer.upgrade() {
-                buffer.update(cx, |buffer, _| buffer.give_up_waiting());
-            }
-        }
-
+        self.went_offline(cx);
         for buffer in self.buffers() {
             buffer.update(cx, |buffer, cx| {
                 buffer.set_capability(Capability::ReadOnly, cx)
             });
         }
+    }
+
+    /// Stops waiting for anything from the host, while leaving the buffers editable.
+    pub fn went_offline(&mut self, cx: &mut AppContext) {
+        for open_buffer in self.opened_buffers.values_mut() {
+            if let Some(buffer) = open_buffer.upgrade() {
+                buffer.update(cx, |buffer, _| buffer.give_up_waiting());
+            }
+        }
 
         if let Some(remote) = self.as_remote_mut() {
             // Wake up all futures currently waiting on a buffer to get opened,
@@ -1578,7 +1633,11 @@ impl BufferStore {
             anyhow::bail!("missing original_sender_id on SynchronizeBuffers request");
         };
 
-        self.shared_buffers.entry(guest_id).or_default().clear();
+        // The guest's shared buffers may be the only handles keeping some buffers alive (e.g.
+        // ones the guest edited while offline, restored by `restore_shared_buffers`). Hold
+        // them until the buffers the guest still has are shared again below, otherwise
+        // `self.get` could no longer find those buffers.
+        let previously_shared_buffers = self.shared_buffers.remove(&guest_id);
         for buffer in envelope.payload.buffers {
             let buffer_id = BufferId::new(buffer.id)?;
             let remote_version = language::proto::deserialize_version(&buffer.version);
@@ -1650,6 +1709,9 @@ impl BufferStore {
                     .detach();
             }
         }
+
+        // Release the buffers the guest no longer has.
+        drop(previously_shared_buffers);
         Ok(response)
     }
 
@@ -2062,12 +2124,31 @@ impl BufferStore {
 
     pub fn forget_shared_buffers(&mut self) {
         self.shared_buffers.clear();
+        self.offline_shared_buffers.clear();
     }
 
     pub fn forget_shared_buffers_for(&mut self, peer_id: &proto::PeerId) {
         self.shared_buffers.remove(peer_id);
     }
 
+    /// Keeps the buffers shared with a guest that lost their connection open until they
+    /// reconnect with the same replica id.
+    pub fn keep_shared_buffers_for_reconnection(
+        &mut self,
+        peer_id: &proto::PeerId,
+        replica_id: ReplicaId,
+    ) {
+        if let Some(buffers) = self.shared_buffers.remove(peer_id) {
+            self.offline_shared_buffers.insert(replica_id, buffers);
+        }
+    }
+
+    pub fn restore_shared_buffers(&mut self, replica_id: ReplicaId, peer_id: proto::PeerId) {
+        if let Some(buffers) = self.offline_shared_buffers.remove(&replica_id) {
+            self.shared_buffers.insert(peer_id, buffers);
+        }
+    }
+
     pub fn update_peer_id(&mut self, old_peer_id: &proto::PeerId, new_peer_id: proto::PeerId) {
         if let Some(buffers) = self.shared_buffers.remove(old_peer_id) {
             self.shared_buffers.insert(new_peer_id, buffers);
@@ -0,0 +1,318 @@
+//! A history of the contents files had each time they were saved, kept independently of any
+//! version control, so that files that were overwritten by mistake can be recovered.
+//!
+//! Each file gets its own directory, named after a digest of its absolute path, containing an
+//! index of its snapshots and the contents of each snapshot, stored once per distinct content.
+
+use crate::project_settings::LocalHistorySettings;
+use anyhow::{Context as _, Result};
+use collections::HashSet;
+use fs::{Fs, MTime, RemoveOptions};
+use futures::Future;
+use gpui::{AppContext, Global};
+use language::Buffer;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use smol::lock::Mutex;
+use std::{
+    fmt::Write as _,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+const INDEX_FILE_NAME: &str = "entries.json";
+
+pub fn init(fs: Arc<dyn Fs>, cx: &mut AppContext) {
+    let local_history = LocalHistory::new(fs, paths::local_history_dir().clone());
+    cx.set_global(GlobalLocalHistory(Arc::new(local_history)));
+}
+
+struct GlobalLocalHistory(Arc<LocalHistory>);
+
+impl Global for GlobalLocalHistory {}
+
+pub struct LocalHistory {
+    fs: Arc<dyn Fs>,
+    dir: PathBuf,
+    /// Held while a file's index is read and written back, so that concurrent saves don't
+    /// drop each other's snapshots.
+    index_lock: Mutex<()>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocalHistoryEntry {
+    /// When the file was saved with these contents.
+    pub timestamp: SystemTime,
+    /// The digest of the contents, which they're stored under.
+    pub digest: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FileHistory {
+    path: PathBuf,
+    /// Ordered from oldest to newest.
+    entries: Vec<LocalHistoryEntry>,
+}
+
+impl LocalHistory {
+    pub fn new(fs: Arc<dyn Fs>, dir: PathBuf) -> Self {
+        Self {
+            fs,
+            dir,
+            index_lock: Mutex::new(()),
+        }
+    }
+
+    pub fn global(cx: &AppContext) -> Option<Arc<Self>> {
+        cx.try_global::<GlobalLocalHistory>()
+            .map(|local_history| local_history.0.clone())
+    }
+
+    /// Records that the file at `abs_path` now has the given contents.
+    ///
+    /// Nothing is recorded if the contents are the same as in the newest snapshot. Snapshots
+    /// beyond the limits in `settings` are removed, along with any contents that no remaining
+    /// snapshot refers to.
+    pub async fn record(
+        &self,
+        abs_path: &Path,
+        contents: &str,
+        timestamp: SystemTime,
+        settings: &LocalHistorySettings,
+    ) -> Result<()> {
+        let _lock = self.index_lock.lock().await;
+        let file_dir = self.file_dir(abs_path);
+        let mut history = self
+            .read_index(&file_dir)
+            .await?
+            .unwrap_or_else(|| FileHistory {
+                path: abs_path.to_path_buf(),
+                entries: Vec::new(),
+            });
+
+        let digest = content_digest(contents);
+        if history.entries.last().map(|entry| &entry.digest) != Some(&digest) {
+            self.fs.create_dir(&file_dir).await?;
+            let contents_path = file_dir.join(&digest);
+            if !self.fs.is_file(&contents_path).await {
+                self.fs
+                    .atomic_write(contents_path, contents.to_string())
+                    .await?;
+            }
+            history
+                .entries
+                .push(LocalHistoryEntry { timestamp, digest });
+        }
+
+        let previous_digests = history
+            .entries
+            .iter()
+            .map(|entry| entry.digest.clone())
+            .collect::<HashSet<_>>();
+        if let Some(oldest_timestamp) =
+            timestamp.checked_sub(Duration::from_secs(settings.max_age_days * 24 * 60 * 60))
+        {
+            history
+                .entries
+                .retain(|entry| entry.timestamp >= oldest_timestamp);
+        }
+        let excess_entries = history.entries.len().saturating_sub(settings.max_entries);
+        history.entries.drain(..excess_entries);
+
+        if history.entries.is_empty() {
+            return self
+                .fs
+                .remove_dir(
+                    &file_dir,
+                    RemoveOptions {
+                        recursive: true,
+                        ignore_if_not_exists: true,
+                    },
+                )
+                .await;
+        }
+
+        let remaining_digests = history
+            .entries
+            .iter()
+            .map(|entry| entry.digest.clone())
+            .collect::<HashSet<_>>();
+        for digest in previous_digests.difference(&remaining_digests) {
+            self.fs
+                .remove_file(
+                    &file_dir.join(digest),
+                    RemoveOptions {
+                        recursive: false,
+                        ignore_if_not_exists: true,
+                    },
+                )
+                .await?;
+        }
+
+        let index = serde_json::to_string(&history)?;
+        self.fs
+            .atomic_write(file_dir.join(INDEX_FILE_NAME), index)
+            .await
+    }
+
+    /// Returns a future that records the contents `buffer` is being saved with.
+    ///
+    /// If the file doesn't have any snapshots yet, the contents it had on disk before are
+    /// recorded first, so that they can be recovered too.
+    pub fn record_save(
+        self: Arc<Self>,
+        abs_path: PathBuf,
+        buffer: &Buffer,
+        settings: LocalHistorySettings,
+    ) -> impl Future<Output = Result<()>> {
+        let snapshot = buffer.text_snapshot();
+        let saved_version = buffer.saved_version().clone();
+        let saved_timestamp = buffer.saved_mtime().map(MTime::timestamp_for_user);
+        async move {
+            if let Some(saved_timestamp) = saved_timestamp {
+                if self.entries(&abs_path).await?.is_empty() {
+                    let previous_contents = snapshot.rope_for_version(&saved_version).to_string();
+                    self.record(&abs_path, &previous_contents, saved_timestamp, &settings)
+                        .await?;
+                }
+            }
+            self.record(&abs_path, &snapshot.text(), SystemTime::now(), &settings)
+                .await
+        }
+    }
+
+    /// Returns the snapshots of the file at `abs_path`, from oldest to newest.
+    pub async fn entries(&self, abs_path: &Path) -> Result<Vec<LocalHistoryEntry>> {
+        Ok(self
+            .read_index(&self.file_dir(abs_path))
+            .await?
+            .map(|history| history.entries)
+            .unwrap_or_default())
+    }
+
+    /// Returns the contents of the file at `abs_path` when the given snapshot was taken.
+    pub async fn load(&self, abs_path: &Path, entry: &LocalHistoryEntry) -> Result<String> {
+        let contents_path = self.file_dir(abs_path).join(&entry.digest);
+        self.fs
+            .load(&contents_path)
+            .await
+            .with_context(|| format!("failed to load local history of {abs_path:?}"))
+    }
+
+    fn file_dir(&self, abs_path: &Path) -> PathBuf {
+        self.dir.join(content_digest(&abs_path.to_string_lossy()))
+    }
+
+    async fn read_index(&self, file_dir: &Path) -> Result<Option<FileHistory>> {
+        let index_path = file_dir.join(INDEX_FILE_NAME);
+        if !self.fs.is_file(&index_path).await {
+            return Ok(None);
+        }
+        let index = self.fs.load(&index_path).await?;
+        let history = serde_json::from_str(&index)
+            .with_context(|| format!("failed to parse local history index {index_path:?}"))?;
+        Ok(Some(history))
+    }
+}
+
+fn content_digest(contents: &str) -> String {
+    let mut digest = String::with_capacity(64);
+    for byte in Sha256::digest(contents.as_bytes()) {
+        write!(digest, "{byte:02x}").unwrap();
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    async fn test_local_history(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        let history = LocalHistory::new(fs.clone(), PathBuf::from("/history"));
+        let settings = LocalHistorySettings {
+            enabled: true,
+            max_entries: 3,
+            max_age_days: 1,
+        };
+        let path = Path::new("/project/file.txt");
+        let day = Duration::from_secs(24 * 60 * 60);
+        let start = SystemTime::UNIX_EPOCH + 100 * day;
+        let contents_of = |entries: Vec<LocalHistoryEntry>| {
+            let history = &history;
+            async move {
+                let mut contents = Vec::new();
+                for entry in entries {
+                    contents.push(history.load(path, &entry).await.unwrap());
+                }
+                contents
+            }
+        };
+
+        assert_eq!(history.entries(path).await.unwrap(), Vec::new());
+
+        // Saving the same contents again isn't recorded
+        let minute = Duration::from_secs(60);
+        history.record(path, "one", start, &settings).await.unwrap();
+        history
+            .record(path, "one", start + minute, &settings)
+            .await
+            .unwrap();
+        history
+            .record(path, "two", start + 2 * minute, &settings)
+            .await
+            .unwrap();
+        let entries = history.entries(path).await.unwrap();
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| entry.timestamp)
+                .collect::<Vec<_>>(),
+            vec![start, start + 2 * minute]
+        );
+        assert_eq!(contents_of(entries).await, vec!["one", "two"]);
+
+        // Only the newest snapshots are kept, and contents are stored once
+        history
+            .record(path, "one", start + 3 * minute, &settings)
+            .await
+            .unwrap();
+        history
+            .record(path, "three", start + 4 * minute, &settings)
+            .await
+            .unwrap();
+        let entries = history.entries(path).await.unwrap();
+        assert_eq!(contents_of(entries).await, vec!["two", "one", "three"]);
+        let file_dir = history.file_dir(path);
+        assert_eq!(
+            fs.files()
+                .iter()
+                .filter(|path| path.starts_with(&file_dir))
+                .count(),
+            4
+        );
+
+        // Snapshots older than the maximum age are removed, with their contents
+        history
+            .record(path, "four", start + day + 3 * minute, &settings)
+            .await
+            .unwrap();
+        let entries = history.entries(path).await.unwrap();
+        assert_eq!(contents_of(entries).await, vec!["one", "three", "four"]);
+        assert!(!fs.is_file(&file_dir.join(content_digest("two"))).await);
+
+        // Other files have their own history
+        let other_path = Path::new("/project/other.txt");
+        history
+            .record(other_path, "other", start, &settings)
+            .await
+            .unwrap();
+        let entries = history.entries(other_path).await.unwrap();
+        assert_eq!(contents_of(entries).await, vec!["other"]);
+        assert_eq!(history.entries(path).await.unwrap().len(), 3);
+    }
+}
@@ -3331,6 +3331,11 @@ impl LspStore {
         buffer_handle: &Model<Buffer>,
         cx: &mut ModelContext<Self>,
     ) {
+        // Large files are neither parsed nor sent to language servers.
+        if buffer_handle.read(cx).is_large_file() {
+            return;
+        }
+
         let available_language = self.detect_language_for_buffer(buffer_handle, cx);
 
         let buffer = buffer_handle.read(cx);
@@ -6494,6 +6499,56 @@ impl LspStore {
                 }
             })
             .detach();
+
+        // Methods outside of the LSP specification can be handled by the adapter, such as
+        // language extensions, unless we already handle them ourselves.
+        for method in adapter.adapter.custom_request_methods() {
+            if language_server.has_handler(&method) {
+                log::warn!("{name} adapter can't handle {method:?}, which is already handled");
+                continue;
+            }
+            language_server
+                .on_custom_request(method.clone(), {
+                    let adapter = adapter.adapter.clone();
+                    let delegate = delegate.clone();
+                    move |params: serde_json::Value, _| {
+                        let adapter = adapter.clone();
+                        let delegate = delegate.clone();
+                        let method = method.clone();
+                        async move {
+                            adapter
+                                .handle_custom_request(method, params, &delegate)
+                                .await
+                        }
+                    }
+                })
+                .detach();
+        }
+        for method in adapter.adapter.custom_notification_methods() {
+            if language_server.has_handler(&method) {
+                log::warn!("{name} adapter can't handle {method:?}, which is already handled");
+                continue;
+            }
+            language_server
+                .on_custom_notification(method.clone(), {
+                    let adapter = adapter.adapter.clone();
+                    let delegate = delegate.clone();
+                    move |params: serde_json::Value, cx| {
+                        let adapter = adapter.clone();
+                        let delegate = delegate.clone();
+                        let method = method.clone();
+                        cx.foreground_executor()
+                            .spawn(async move {
+                                adapter
+                                    .handle_custom_notification(method, params, &delegate)
+                                    .await
+                                    .log_err();
+                            })
+                            .detach();
+                    }
+                })
+                .detach();
+        }
     }
 
     pub fn update_diagnostics(
@@ -7895,7 +7950,7 @@ pub struct LocalLspAdapterDelegate {
 }
 
 impl LocalLspAdapterDelegate {
-    fn for_local(
+    pub(crate) fn for_local(
         lsp_store: &LspStore,
         worktree: &Model<Worktree>,
         cx: &mut ModelContext<LspStore>,
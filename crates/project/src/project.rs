@@ -3,6 +3,7 @@ mod color_extractor;
 pub mod connection_manager;
 pub mod debounced_delay;
 pub mod image_store;
+pub mod local_history;
 pub mod lsp_command;
 pub mod lsp_ext_command;
 pub mod lsp_store;
@@ -11,8 +12,10 @@ pub mod project_settings;
 pub mod search;
 mod task_inventory;
 pub mod task_store;
+pub mod terminal_sharing;
 pub mod terminals;
 pub mod toolchain_store;
+pub mod workspace_file;
 pub mod worktree_store;
 
 #[cfg(test)]
@@ -32,7 +35,10 @@ use collections::{BTreeSet, HashMap, HashSet};
 use debounced_delay::DebouncedDelay;
 pub use environment::ProjectEnvironment;
 use futures::{
-    channel::mpsc::{self, UnboundedReceiver},
+    channel::{
+        mpsc::{self, UnboundedReceiver},
+        oneshot,
+    },
     future::try_join_all,
     StreamExt,
 };
@@ -47,9 +53,9 @@ use gpui::{
 use itertools::Itertools;
 use language::{
     language_settings::InlayHintKind, proto::split_operations, Buffer, BufferEvent,
-    CachedLspAdapter, Capability, CodeLabel, DiagnosticEntry, Documentation, File as _, Language,
-    LanguageName, LanguageRegistry, PointUtf16, ToOffset, ToPointUtf16, Toolchain, ToolchainList,
-    Transaction, Unclipped,
+    CachedLspAdapter, Capability, CodeLabel, DiagnosticEntry, DiskState, Documentation, File as _,
+    Language, LanguageName, LanguageRegistry, ParseStatus, PointUtf16, ToOffset, ToPointUtf16,
+    Toolchain, ToolchainList, Transaction, Unclipped,
 };
 use lsp::{
     CodeActionKind, CompletionContext, CompletionItemKind, DocumentHighlightKind, LanguageServer,
@@ -63,7 +69,7 @@ use project_settings::{ProjectSettings, SettingsObserver, SettingsObserverEvent}
 use remote::{SshConnectionOptions, SshRemoteClient};
 use rpc::{
     proto::{LanguageServerPromptResponse, SSH_PROJECT_ID},
-    AnyProtoClient, ErrorCode,
+    AnyProtoClient, ErrorCode, ErrorExt as _,
 };
 use search::{SearchInputKind, SearchQuery, SearchResult};
 use search_history::SearchHistory;
@@ -207,6 +213,8 @@ enum BufferOrderedMessage {
         message: proto::update_language_server::Variant,
     },
     Resync,
+    /// Resolved once everything that was enqueued before it has been sent.
+    Flushed(oneshot::Sender<()>),
 }
 
 #[derive(Debug)]
@@ -218,6 +226,9 @@ enum ProjectClientState {
     /// Multi-player mode but working on a remote project.
     Remote {
         sharing_has_stopped: bool,
+        /// Set while a guest keeps editing after losing their connection to the host, with
+        /// the version each buffer had when the connection was lost.
+        offline_buffer_versions: Option<HashMap<BufferId, clock::Global>>,
         capability: Capability,
         remote_id: u64,
         replica_id: ReplicaId,
@@ -256,6 +267,7 @@ pub enum Event {
         language_server_id: LanguageServerId,
     },
     RemoteIdChanged(Option<u64>),
+    WentOffline,
     DisconnectedFromHost,
     DisconnectedFromSshRemote,
     Closed,
@@ -272,6 +284,7 @@ pub enum Event {
     RefreshInlayHints,
     RevealInProjectPanel(ProjectEntryId),
     SnippetEdit(BufferId, Vec<(lsp::Range, Snippet)>),
+    SharedTerminalsChanged,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
@@ -582,6 +595,7 @@ impl Project {
         client.add_model_request_handler(Self::handle_open_buffer_by_path);
         client.add_model_request_handler(Self::handle_open_new_buffer);
         client.add_model_message_handler(Self::handle_create_buffer_for_peer);
+        Self::init_terminal_sharing(&client);
 
         WorktreeStore::init(&client);
         BufferStore::init(&client);
@@ -636,6 +650,21 @@ impl Project {
                     cx,
                 )
             });
+            let lsp_store = cx.new_model(|cx| {
+                LspStore::new_local(
+                    buffer_store.clone(),
+                    worktree_store.clone(),
+                    prettier_store.clone(),
+                    toolchain_store.clone(),
+                    environment.clone(),
+                    languages.clone(),
+                    client.http_client(),
+                    fs.clone(),
+                    cx,
+                )
+            });
+            cx.subscribe(&lsp_store, Self::on_lsp_store_event).detach();
+
             let task_store = cx.new_model(|cx| {
                 TaskStore::local(
                     fs.clone(),
@@ -643,6 +672,8 @@ impl Project {
                     worktree_store.clone(),
                     toolchain_store.read(cx).as_language_toolchain_store(),
                     environment.clone(),
+                    languages.clone(),
+                    lsp_store.downgrade(),
                     cx,
                 )
             });
@@ -658,21 +689,6 @@ impl Project {
             cx.subscribe(&settings_observer, Self::on_settings_observer_event)
                 .detach();
 
-            let lsp_store = cx.new_model(|cx| {
-                LspStore::new_local(
-                    buffer_store.clone(),
-                    worktree_store.clone(),
-                    prettier_store.clone(),
-                    toolchain_store.clone(),
-                    environment.clone(),
-                    languages.clone(),
-                    client.http_client(),
-                    fs.clone(),
-                    cx,
-                )
-            });
-            cx.subscribe(&lsp_store, Self::on_lsp_store_event).detach();
-
             Self {
                 buffer_ordered_messages_tx: tx,
                 collaborators: Default::default(),
@@ -697,6 +713,7 @@ impl Project {
                 git_diff_debouncer: DebouncedDelay::new(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    sharing: Default::default(),
                 },
                 node: Some(node),
                 search_history: Self::new_search_history(),
@@ -830,6 +847,7 @@ impl Project {
                 git_diff_debouncer: DebouncedDelay::new(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    sharing: Default::default(),
                 },
                 node: Some(node),
                 search_history: Self::new_search_history(),
@@ -1027,6 +1045,7 @@ impl Project {
                 client: client.clone(),
                 client_state: ProjectClientState::Remote {
                     sharing_has_stopped: false,
+                    offline_buffer_versions: None,
                     capability: Capability::ReadWrite,
                     remote_id,
                     replica_id,
@@ -1035,6 +1054,7 @@ impl Project {
                 git_diff_debouncer: DebouncedDelay::new(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    sharing: Default::default(),
                 },
                 node: None,
                 search_history: Self::new_search_history(),
@@ -1369,6 +1389,19 @@ impl Project {
         self.collaborators.values().find(|c| c.is_host)
     }
 
+    /// Applies the settings and tasks of the workspace file at `abs_path` to the project, and
+    /// keeps them up to date as the file changes.
+    pub fn set_workspace_file(&mut self, abs_path: Option<Arc<Path>>, cx: &mut AppContext) {
+        self.settings_observer.update(cx, |settings_observer, cx| {
+            settings_observer.set_workspace_file(abs_path, cx)
+        });
+    }
+
+    /// Returns the path of the workspace file that the project was opened from.
+    pub fn workspace_file(&self, cx: &AppContext) -> Option<Arc<Path>> {
+        self.settings_observer.read(cx).workspace_file()
+    }
+
     pub fn set_worktrees_reordered(&mut self, worktrees_reordered: bool, cx: &mut AppContext) {
         self.worktree_store.update(cx, |store, _| {
             store.set_worktrees_reordered(worktrees_reordered);
@@ -1629,6 +1662,7 @@ impl Project {
             self.client_state = ProjectClientState::Local;
             self.collaborators.clear();
             self.client_subscriptions.clear();
+            self.unshare_all_terminals();
             self.worktree_store.update(cx, |store, cx| {
                 store.unshared(cx);
             });
@@ -1655,7 +1689,7 @@ impl Project {
     }
 
     pub fn disconnected_from_host(&mut self, cx: &mut ModelContext<Self>) {
-        if self.is_disconnected(cx) {
+        if self.is_disconnected(cx) && !self.is_offline() {
             return;
         }
         self.disconnected_from_host_internal(cx);
@@ -1663,6 +1697,162 @@ impl Project {
         cx.notify();
     }
 
+    /// Keeps a guest's project editable after they lost their connection to the host. The edits
+    /// they make in the meantime are sent to the host by [`Self::reconnect_to_host`].
+    pub fn went_offline(&mut self, cx: &mut ModelContext<Self>) {
+        if self.is_disconnected(cx) || self.is_read_only(cx) {
+            return;
+        }
+
+        let buffer_versions = self
+            .opened_buffers(cx)
+            .into_iter()
+            .map(|buffer| {
+                let buffer = buffer.read(cx);
+                (buffer.remote_id(), buffer.version())
+            })
+            .collect();
+        let ProjectClientState::Remote {
+            offline_buffer_versions,
+            remote_id,
+            ..
+        } = &mut self.client_state
+        else {
+            return;
+        };
+        *offline_buffer_versions = Some(buffer_versions);
+        let remote_id = *remote_id;
+
+        self.collaborators.clear();
+        self.buffer_store
+            .update(cx, |buffer_store, cx| buffer_store.went_offline(cx));
+        cx.emit(Event::Toast {
+            notification_id: offline_notification_id(remote_id),
+            message: "You're offline. You can keep editing, and your edits will be sent to the \
+                      host when you rejoin the call."
+                .into(),
+        });
+        cx.emit(Event::WentOffline);
+        cx.notify();
+    }
+
+    /// Reconnects to the host of a project that went offline, and sends it the edits that were
+    /// made in the meantime. If that isn't possible, the project becomes read-only so that the
+    /// edits can still be copied.
+    pub fn reconnect_to_host(&mut self, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+        let ProjectClientState::Remote {
+            offline_buffer_versions: Some(_),
+            remote_id,
+            replica_id,
+            ..
+        } = self.client_state
+        else {
+            return Task::ready(Err(anyhow!("project is not offline")));
+        };
+
+        let request = self.client.request_envelope(proto::ReconnectToProject {
+            project_id: remote_id,
+            replica_id: replica_id as u32,
+            worktrees: self
+                .worktrees(cx)
+                .map(|worktree| {
+                    let worktree = worktree.read(cx);
+                    proto::RejoinWorktree {
+                        id: worktree.id().to_proto(),
+                        scan_id: worktree.completed_scan_id() as u64,
+                    }
+                })
+                .collect(),
+        });
+        cx.spawn(|this, mut cx| async move {
+            let response = match request.await {
+                Ok(response) => response,
+                Err(error) => {
+                    this.update(&mut cx, |this, cx| this.failed_to_reconnect(&error, cx))?;
+                    return Err(error);
+                }
+            };
+            let message_id = response.message_id;
+            let role = response.payload.role();
+            let project = response
+                .payload
+                .project
+                .ok_or_else(|| anyhow!("invalid project"))?;
+
+            let (flushed, edited_buffers) = this.update(&mut cx, |this, cx| {
+                let edited_buffers = this.buffers_edited_offline(cx);
+                if let ProjectClientState::Remote {
+                    offline_buffer_versions,
+                    ..
+                } = &mut this.client_state
+                {
+                    offline_buffer_versions.take();
+                }
+                this.set_role(role, cx);
+                this.rejoined(project, message_id, cx)?;
+
+                // Rejoining resyncs the buffers with the host, which sends it our edits and
+                // gets the state of the buffers' files back.
+                let (tx, rx) = oneshot::channel();
+                this.enqueue_buffer_ordered_message(BufferOrderedMessage::Flushed(tx))?;
+                anyhow::Ok((rx, edited_buffers))
+            })??;
+            flushed.await.ok();
+
+            this.update(&mut cx, |this, cx| {
+                let deleted_buffers = edited_buffers
+                    .into_iter()
+                    .filter(|buffer| {
+                        buffer
+                            .read(cx)
+                            .file()
+                            .map_or(false, |file| file.disk_state() == DiskState::Deleted)
+                    })
+                    .collect::<Vec<_>>();
+                let notification_id = offline_notification_id(remote_id);
+                if deleted_buffers.is_empty() {
+                    cx.emit(Event::HideToast { notification_id });
+                } else {
+                    cx.emit(Event::Toast {
+                        notification_id,
+                        message: format!(
+                            "You're back online, but these files were deleted while you were \
+                             offline: {}. Save them to recreate them.",
+                            describe_buffers(&deleted_buffers, cx),
+                        ),
+                    });
+                }
+            })
+        })
+    }
+
+    fn failed_to_reconnect(&mut self, error: &anyhow::Error, cx: &mut ModelContext<Self>) {
+        let Some(remote_id) = self.remote_id() else {
+            return;
+        };
+        let edited_buffers = self.buffers_edited_offline(cx);
+        self.disconnected_from_host(cx);
+
+        let reason = if error.error_code() == ErrorCode::NoSuchProject {
+            "The host stopped sharing this project while you were offline".to_string()
+        } else {
+            format!("Couldn't reconnect to this project ({error})")
+        };
+        let message = if edited_buffers.is_empty() {
+            format!("{reason}.")
+        } else {
+            format!(
+                "{reason}, so your edits to {} couldn't be sent. They're still open, read-only, \
+                 so you can copy them.",
+                describe_buffers(&edited_buffers, cx)
+            )
+        };
+        cx.emit(Event::Toast {
+            notification_id: offline_notification_id(remote_id),
+            message,
+        });
+    }
+
     pub fn set_role(&mut self, role: proto::ChannelRole, cx: &mut ModelContext<Self>) {
         let new_capability =
             if role == proto::ChannelRole::Member || role == proto::ChannelRole::Admin {
@@ -1685,10 +1875,12 @@ impl Project {
     fn disconnected_from_host_internal(&mut self, cx: &mut AppContext) {
         if let ProjectClientState::Remote {
             sharing_has_stopped,
+            offline_buffer_versions,
             ..
         } = &mut self.client_state
         {
             *sharing_has_stopped = true;
+            offline_buffer_versions.take();
             self.collaborators.clear();
             self.worktree_store.update(cx, |store, cx| {
                 store.disconnected_from_host(cx);
@@ -1709,8 +1901,9 @@ impl Project {
         match &self.client_state {
             ProjectClientState::Remote {
                 sharing_has_stopped,
+                offline_buffer_versions,
                 ..
-            } => *sharing_has_stopped,
+            } => *sharing_has_stopped || offline_buffer_versions.is_some(),
             ProjectClientState::Local if self.is_via_ssh() => self.ssh_is_disconnected(cx),
             _ => false,
         }
@@ -1731,7 +1924,40 @@ impl Project {
     }
 
     pub fn is_read_only(&self, cx: &AppContext) -> bool {
-        self.is_disconnected(cx) || self.capability() == Capability::ReadOnly
+        (self.is_disconnected(cx) && !self.is_offline())
+            || self.capability() == Capability::ReadOnly
+    }
+
+    /// Whether this is a guest's project that is still being edited after their connection to
+    /// the host was lost.
+    pub fn is_offline(&self) -> bool {
+        matches!(
+            self.client_state,
+            ProjectClientState::Remote {
+                offline_buffer_versions: Some(_),
+                ..
+            }
+        )
+    }
+
+    /// Returns the buffers that were edited since this project went offline.
+    pub fn buffers_edited_offline(&self, cx: &AppContext) -> Vec<Model<Buffer>> {
+        let ProjectClientState::Remote {
+            offline_buffer_versions: Some(offline_buffer_versions),
+            ..
+        } = &self.client_state
+        else {
+            return Vec::new();
+        };
+        self.opened_buffers(cx)
+            .into_iter()
+            .filter(|buffer| {
+                let buffer = buffer.read(cx);
+                offline_buffer_versions
+                    .get(&buffer.remote_id())
+                    .map_or(true, |version| buffer.version().changed_since(version))
+            })
+            .collect()
     }
 
     pub fn is_local(&self) -> bool {
@@ -1881,6 +2107,11 @@ impl Project {
         buffer: Model<Buffer>,
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<()>> {
+        if self.is_offline() {
+            return Task::ready(Err(anyhow!(
+                "can't save while offline, rejoin the call to send your edits to the host"
+            )));
+        }
         self.buffer_store
             .update(cx, |buffer_store, cx| buffer_store.save_buffer(buffer, cx))
     }
@@ -2003,6 +2234,18 @@ impl Project {
                         }
                     }
 
+                    BufferOrderedMessage::Flushed(tx) => {
+                        flush_operations(
+                            &this,
+                            &mut operations_by_buffer_id,
+                            &mut needs_resync_with_host,
+                            is_local,
+                            &mut cx,
+                        )
+                        .await?;
+                        tx.send(()).ok();
+                    }
+
                     BufferOrderedMessage::LanguageServerUpdate {
                         language_server_id,
                         message,
@@ -2984,7 +3227,7 @@ impl Project {
             self.find_search_candidate_buffers(&query, MAX_SEARCH_RESULT_FILES + 1, cx)
         };
 
-        cx.spawn(|_, cx| async move {
+        cx.spawn(|this, mut cx| async move {
             let mut range_count = 0;
             let mut buffer_count = 0;
             let mut limit_reached = false;
@@ -2996,6 +3239,16 @@ impl Project {
             // opened buffer, we will spawn a background task that retrieves all the
             // ranges in the buffer matched by the query.
             'outer: while let Some(matching_buffer_chunk) = chunks.next().await {
+                if query.is_structural() {
+                    let syntax_parsed = this.update(&mut cx, |this, cx| {
+                        matching_buffer_chunk
+                            .iter()
+                            .map(|buffer| this.wait_for_buffer_syntax(buffer, cx))
+                            .collect::<Vec<_>>()
+                    })?;
+                    futures::future::join_all(syntax_parsed).await;
+                }
+
                 let mut chunk_results = Vec::new();
                 for buffer in matching_buffer_chunk {
                     let buffer = buffer.clone();
@@ -3018,6 +3271,10 @@ impl Project {
                 let chunk_results = futures::future::join_all(chunk_results).await;
                 for result in chunk_results {
                     if let Some((buffer, ranges)) = result.log_err() {
+                        // Candidates for structural queries are only filtered by their words.
+                        if ranges.is_empty() && query.is_structural() {
+                            continue;
+                        }
                         range_count += ranges.len();
                         buffer_count += 1;
                         result_tx
@@ -3044,6 +3301,51 @@ impl Project {
         result_rx
     }
 
+    /// Assigns a language to the buffer if it doesn't have one yet, and resolves once the
+    /// buffer's syntax has been parsed, so that structural searches can match against it.
+    fn wait_for_buffer_syntax(
+        &mut self,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<()> {
+        let languages = self.languages.clone();
+        let lsp_store = self.lsp_store.clone();
+        let available_language = {
+            let buffer = buffer.read(cx);
+            if buffer
+                .language()
+                .map_or(true, |language| language.grammar().is_none())
+            {
+                buffer
+                    .file()
+                    .and_then(|file| languages.language_for_file(file, Some(buffer.as_rope()), cx))
+            } else {
+                None
+            }
+        };
+        let buffer = buffer.clone();
+        cx.spawn(|_, mut cx| async move {
+            if let Some(available_language) = available_language {
+                if let Ok(Ok(language)) = languages.load_language(&available_language).await {
+                    lsp_store
+                        .update(&mut cx, |lsp_store, cx| {
+                            lsp_store.set_language_for_buffer(&buffer, language, cx)
+                        })
+                        .ok();
+                }
+            }
+            let Ok(mut parse_status) = buffer.read_with(&cx, |buffer, _| buffer.parse_status())
+            else {
+                return;
+            };
+            while *parse_status.borrow() == ParseStatus::Parsing {
+                if parse_status.changed().await.is_err() {
+                    break;
+                }
+            }
+        })
+    }
+
     fn find_search_candidate_buffers(
         &mut self,
         query: &SearchQuery,
@@ -3595,10 +3897,12 @@ impl Project {
         this.update(&mut cx, |this, cx| {
             this.buffer_store.update(cx, |buffer_store, _| {
                 buffer_store.forget_shared_buffers_for(&collaborator.peer_id);
+                buffer_store.restore_shared_buffers(collaborator.replica_id, collaborator.peer_id);
             });
             cx.emit(Event::CollaboratorJoined(collaborator.peer_id));
             this.collaborators
                 .insert(collaborator.peer_id, collaborator);
+            this.advertise_terminals_to_new_collaborator(cx);
             cx.notify();
         })?;
 
@@ -3664,7 +3968,11 @@ impl Project {
                 .ok_or_else(|| anyhow!("unknown peer {:?}", peer_id))?
                 .replica_id;
             this.buffer_store.update(cx, |buffer_store, cx| {
-                buffer_store.forget_shared_buffers_for(&peer_id);
+                if envelope.payload.can_reconnect {
+                    buffer_store.keep_shared_buffers_for_reconnection(&peer_id, replica_id);
+                } else {
+                    buffer_store.forget_shared_buffers_for(&peer_id);
+                }
                 for buffer in buffer_store.buffers() {
                     buffer.update(cx, |buffer, cx| buffer.remove_peer(replica_id, cx));
                 }
@@ -4164,6 +4472,28 @@ impl Project {
     }
 }
 
+fn offline_notification_id(remote_id: u64) -> SharedString {
+    format!("project-offline-{remote_id}").into()
+}
+
+/// Lists the paths of the given buffers, for use in notifications.
+fn describe_buffers(buffers: &[Model<Buffer>], cx: &AppContext) -> String {
+    const MAX_PATHS: usize = 3;
+    let mut paths = buffers
+        .iter()
+        .filter_map(|buffer| {
+            let file = buffer.read(cx).file()?;
+            Some(file.full_path(cx).to_string_lossy().into_owned())
+        })
+        .collect::<Vec<_>>();
+    if paths.len() > MAX_PATHS {
+        let remaining = paths.len() - MAX_PATHS;
+        paths.truncate(MAX_PATHS);
+        paths.push(format!("{remaining} more"));
+    }
+    paths.join(", ")
+}
+
 fn deserialize_code_actions(code_actions: &HashMap<String, bool>) -> Vec<lsp::CodeActionKind> {
     code_actions
         .iter()
@@ -1,7 +1,10 @@
 use anyhow::Context;
 use collections::HashMap;
 use fs::Fs;
-use gpui::{AppContext, AsyncAppContext, BorrowAppContext, EventEmitter, Model, ModelContext};
+use futures::StreamExt as _;
+use gpui::{
+    AppContext, AsyncAppContext, BorrowAppContext, EventEmitter, Model, ModelContext, Task,
+};
 use lsp::LanguageServerName;
 use paths::{
     local_settings_file_relative_path, local_tasks_file_relative_path,
@@ -11,8 +14,8 @@ use rpc::{proto, AnyProtoClient, TypedEnvelope};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{
-    parse_json_with_comments, InvalidSettingsError, LocalSettingsKind, Settings, SettingsLocation,
-    SettingsSources, SettingsStore,
+    parse_json_with_comments, watch_config_file, InvalidSettingsError, LocalSettingsKind, Settings,
+    SettingsLocation, SettingsSources, SettingsStore,
 };
 use std::{
     path::{Path, PathBuf},
@@ -25,6 +28,7 @@ use worktree::{PathChange, UpdatedEntriesSet, Worktree, WorktreeId};
 
 use crate::{
     task_store::TaskStore,
+    workspace_file::WorkspaceFileContent,
     worktree_store::{WorktreeStore, WorktreeStoreEvent},
 };
 
@@ -56,6 +60,27 @@ pub struct ProjectSettings {
     /// Configuration for session-related features
     #[serde(default)]
     pub session: SessionSettings,
+
+    /// Configuration for the local history of files
+    #[serde(default)]
+    pub local_history: LocalHistorySettings,
+
+    /// Files larger than this many bytes are opened in large file mode: they're opened
+    /// read-only, and aren't parsed, reloaded, or sent to language servers. They're shown a
+    /// page of at most 4 MiB at a time, and the rest of the file isn't read into memory.
+    ///
+    /// Default: 67108864 (64 MiB)
+    #[serde(default)]
+    pub large_file_threshold: Option<u64>,
+}
+
+impl ProjectSettings {
+    pub const DEFAULT_LARGE_FILE_THRESHOLD: u64 = 64 * 1024 * 1024;
+
+    pub fn large_file_threshold(&self) -> u64 {
+        self.large_file_threshold
+            .unwrap_or(Self::DEFAULT_LARGE_FILE_THRESHOLD)
+    }
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
@@ -187,6 +212,8 @@ pub struct SessionSettings {
     ///
     /// If this is true, user won't be prompted whether to save/discard
     /// dirty files when closing the application.
+    /// Unsaved buffers are also snapshotted periodically, so that they
+    /// can be restored after a crash.
     ///
     /// Default: true
     pub restore_unsaved_buffers: bool,
@@ -200,6 +227,33 @@ impl Default for SessionSettings {
     }
 }
 
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct LocalHistorySettings {
+    /// Whether or not to keep a snapshot of the contents of files each time
+    /// they're saved, so that they can be restored later.
+    ///
+    /// Default: true
+    pub enabled: bool,
+    /// The maximum number of snapshots to keep for each file.
+    ///
+    /// Default: 50
+    pub max_entries: usize,
+    /// The number of days after which snapshots are deleted.
+    ///
+    /// Default: 30
+    pub max_age_days: u64,
+}
+
+impl Default for LocalHistorySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_entries: 50,
+            max_age_days: 30,
+        }
+    }
+}
+
 impl Settings for ProjectSettings {
     const KEY: Option<&'static str> = None;
 
@@ -231,6 +285,15 @@ pub struct SettingsObserver {
     worktree_store: Model<WorktreeStore>,
     project_id: u64,
     task_store: Model<TaskStore>,
+    workspace_file: Option<WorkspaceFile>,
+}
+
+struct WorkspaceFile {
+    abs_path: Arc<Path>,
+    content: WorkspaceFileContent,
+    /// The canonical absolute paths of the folders in the workspace file.
+    folder_paths: Vec<PathBuf>,
+    _watch: Task<()>,
 }
 
 /// SettingsObserver observers changes to .zed/{settings, task}.json files in local worktrees
@@ -258,6 +321,7 @@ impl SettingsObserver {
             mode: SettingsObserverMode::Local(fs),
             downstream_client: None,
             project_id: 0,
+            workspace_file: None,
         }
     }
 
@@ -272,6 +336,7 @@ impl SettingsObserver {
             mode: SettingsObserverMode::Remote,
             downstream_client: None,
             project_id: 0,
+            workspace_file: None,
         }
     }
 
@@ -365,8 +430,111 @@ impl SettingsObserver {
                     this.update_local_worktree_settings(&worktree, changes, cx)
                 }
             })
-            .detach()
+            .detach();
+            if self.workspace_file.is_some() {
+                self.update_workspace_file_settings(worktree, cx);
+            }
+        }
+    }
+
+    pub fn workspace_file(&self) -> Option<Arc<Path>> {
+        self.workspace_file
+            .as_ref()
+            .map(|workspace_file| workspace_file.abs_path.clone())
+    }
+
+    pub fn set_workspace_file(&mut self, abs_path: Option<Arc<Path>>, cx: &mut ModelContext<Self>) {
+        if self.workspace_file() == abs_path {
+            return;
         }
+        let SettingsObserverMode::Local(fs) = &self.mode else {
+            return;
+        };
+
+        self.workspace_file = abs_path.map(|abs_path| WorkspaceFile {
+            _watch: Self::watch_workspace_file(fs.clone(), abs_path.clone(), cx),
+            abs_path,
+            content: WorkspaceFileContent::default(),
+            folder_paths: Vec::new(),
+        });
+        self.apply_workspace_file(cx);
+    }
+
+    fn watch_workspace_file(
+        fs: Arc<dyn Fs>,
+        abs_path: Arc<Path>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<()> {
+        let mut contents =
+            watch_config_file(cx.background_executor(), fs.clone(), abs_path.to_path_buf());
+        cx.spawn(|this, mut cx| async move {
+            while let Some(content) = contents.next().await {
+                let Some(content) = WorkspaceFileContent::parse(&content)
+                    .with_context(|| format!("parsing workspace file {abs_path:?}"))
+                    .log_err()
+                else {
+                    continue;
+                };
+                let mut folder_paths = Vec::new();
+                for folder_path in content.folder_paths(&abs_path) {
+                    folder_paths.push(fs.canonicalize(&folder_path).await.unwrap_or(folder_path));
+                }
+
+                let updated = this.update(&mut cx, |this, cx| {
+                    if let Some(workspace_file) = &mut this.workspace_file {
+                        workspace_file.content = content;
+                        workspace_file.folder_paths = folder_paths;
+                    }
+                    this.apply_workspace_file(cx);
+                });
+                if updated.is_err() {
+                    break;
+                }
+            }
+        })
+    }
+
+    fn apply_workspace_file(&mut self, cx: &mut ModelContext<Self>) {
+        let worktrees = self.worktree_store.read(cx).worktrees().collect::<Vec<_>>();
+        for worktree in &worktrees {
+            self.update_workspace_file_settings(worktree, cx);
+        }
+
+        let (abs_path, tasks) = match &self.workspace_file {
+            Some(workspace_file) => (
+                Some(workspace_file.abs_path.clone()),
+                workspace_file
+                    .content
+                    .tasks
+                    .as_ref()
+                    .map(|tasks| tasks.to_string()),
+            ),
+            None => (None, None),
+        };
+        self.task_store.update(cx, |task_store, cx| {
+            task_store
+                .update_workspace_file_tasks(abs_path, tasks.as_deref(), cx)
+                .log_err();
+        });
+    }
+
+    fn update_workspace_file_settings(
+        &mut self,
+        worktree: &Model<Worktree>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let worktree_id = worktree.read(cx).id();
+        let settings = self.workspace_file.as_ref().and_then(|workspace_file| {
+            workspace_file
+                .content
+                .settings_for_worktree(&workspace_file.folder_paths, &worktree.read(cx).abs_path())
+        });
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            if let Err(error) = store.set_workspace_file_settings(worktree_id, settings, cx) {
+                log::error!("Failed to set workspace file settings: {error}");
+                cx.emit(SettingsObserverEvent::LocalSettingsUpdated(Err(error)));
+            }
+        });
     }
 
     fn update_local_worktree_settings(
@@ -7,7 +7,8 @@ use http_client::Url;
 use language::{
     language_settings::{language_settings, AllLanguageSettings, LanguageSettingsContent},
     tree_sitter_rust, tree_sitter_typescript, Diagnostic, DiagnosticSet, DiskState, FakeLspAdapter,
-    LanguageConfig, LanguageMatcher, LanguageName, LineEnding, OffsetRangeExt, Point, ToPoint,
+    LanguageConfig, LanguageMatcher, LanguageName, LineEnding, LspAdapterDelegate, OffsetRangeExt,
+    Point, ToPoint, WorktreeTaskProvider,
 };
 use lsp::{DiagnosticSeverity, NumberOrString};
 use parking_lot::Mutex;
@@ -16,7 +17,13 @@ use serde_json::json;
 #[cfg(not(windows))]
 use std::os;
 
-use std::{mem, num::NonZeroU32, ops::Range, task::Poll};
+use std::{
+    mem,
+    num::NonZeroU32,
+    ops::Range,
+    sync::atomic::{AtomicUsize, Ordering::SeqCst},
+    task::Poll,
+};
 use task::{ResolvedTask, TaskContext};
 use unindent::Unindent as _;
 use util::{assert_set_eq, paths::PathMatcher, test::temp_tree, TryFutureExt as _};
@@ -376,6 +383,218 @@ async fn test_managing_project_specific_settings(cx: &mut gpui::TestAppContext)
     );
 }
 
+#[gpui::test]
+async fn test_workspace_file_settings_and_tasks(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    TaskStore::init(None);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/code",
+        json!({
+            "app": {
+                "app.zed-workspace": r#"{
+                    "folders": [
+                        { "path": "frontend", "settings": { "tab_size": 2 } },
+                        { "path": "../backend" }
+                    ],
+                    "settings": { "tab_size": 3 },
+                    "tasks": [{ "label": "build all", "command": "make" }]
+                }"#,
+                "frontend": {
+                    "a.rs": "fn a() {}",
+                },
+            },
+            "backend": {
+                ".zed": {
+                    "settings.json": r#"{ "hard_tabs": true }"#,
+                },
+                "b.rs": "fn b() {}",
+            },
+        }),
+    )
+    .await;
+
+    let project = Project::test(
+        fs.clone(),
+        ["/code/app/frontend".as_ref(), "/code/backend".as_ref()],
+        cx,
+    )
+    .await;
+    let workspace_file_path: Arc<Path> = Path::new("/code/app/app.zed-workspace").into();
+    project.update(cx, |project, cx| {
+        project.set_workspace_file(Some(workspace_file_path.clone()), cx)
+    });
+    cx.executor().run_until_parked();
+
+    let language_settings_for = |path: &str, cx: &mut gpui::TestAppContext| {
+        let path = Path::new(path);
+        cx.update(|cx| {
+            let (worktree, relative_path) = project.read(cx).find_worktree(path, cx).unwrap();
+            let entry = worktree
+                .read(cx)
+                .entry_for_path(relative_path)
+                .unwrap()
+                .clone();
+            let file = File::for_entry(entry, worktree) as _;
+            let settings = language_settings(None, Some(&file), cx);
+            (settings.tab_size.get(), settings.hard_tabs)
+        })
+    };
+    assert_eq!(
+        language_settings_for("/code/app/frontend/a.rs", cx),
+        (2, false)
+    );
+    // Settings files within the folders are merged with the workspace file's settings
+    assert_eq!(language_settings_for("/code/backend/b.rs", cx), (3, true));
+
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+    let task_labels = |cx: &mut gpui::TestAppContext| {
+        cx.update(|cx| get_all_tasks(&project, Some(worktree_id), &TaskContext::default(), cx))
+            .into_iter()
+            .map(|(source_kind, task)| (source_kind, task.resolved_label))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        task_labels(cx),
+        vec![(
+            TaskSourceKind::AbsPath {
+                id_base: "workspace file tasks".into(),
+                abs_path: workspace_file_path.to_path_buf(),
+            },
+            "build all".to_string()
+        )]
+    );
+
+    // Changes to the workspace file are picked up
+    fs.save(
+        &workspace_file_path,
+        &r#"{ "folders": [{ "path": "frontend" }, { "path": "../backend" }] }"#.into(),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(language_settings_for("/code/backend/b.rs", cx), (4, true));
+    assert_eq!(task_labels(cx), Vec::new());
+}
+
+#[gpui::test]
+async fn test_worktree_task_providers(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    TaskStore::init(None);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/the-root",
+        json!({
+            "scripts.txt": "build\ntest\n",
+            "a.rs": "fn a() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/the-root".as_ref()], cx).await;
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+    let task_labels = |cx: &mut gpui::TestAppContext| {
+        cx.update(|cx| get_all_tasks(&project, Some(worktree_id), &TaskContext::default(), cx))
+            .into_iter()
+            .map(|(source_kind, task)| (source_kind, task.resolved_label))
+            .collect::<Vec<_>>()
+    };
+    let provider_tasks = |labels: &[&str]| {
+        labels
+            .iter()
+            .map(|label| {
+                (
+                    TaskSourceKind::Provider {
+                        id: worktree_id,
+                        name: "scripts".into(),
+                    },
+                    label.to_string(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // Registering a provider computes its tasks for the existing worktrees
+    let computations = Arc::new(AtomicUsize::new(0));
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.register_worktree_task_provider(Arc::new(ScriptsTaskProvider {
+        computations: computations.clone(),
+    }));
+    cx.executor().run_until_parked();
+    assert_eq!(computations.load(SeqCst), 1);
+    assert_eq!(task_labels(cx), provider_tasks(&["build", "test"]));
+
+    // Changes to the watched files recompute the tasks
+    fs.save(
+        "/the-root/scripts.txt".as_ref(),
+        &"build\nlint\n".into(),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(computations.load(SeqCst), 2);
+    assert_eq!(task_labels(cx), provider_tasks(&["build", "lint"]));
+
+    // Changes to other files don't
+    fs.save(
+        "/the-root/a.rs".as_ref(),
+        &"fn a() { b() }".into(),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(computations.load(SeqCst), 2);
+
+    // Removing the provider removes its tasks
+    language_registry.remove_worktree_task_provider("scripts");
+    cx.executor().run_until_parked();
+    assert_eq!(task_labels(cx), Vec::new());
+}
+
+/// Provides a task for each line of the worktree's `scripts.txt`.
+struct ScriptsTaskProvider {
+    computations: Arc<AtomicUsize>,
+}
+
+#[async_trait::async_trait]
+impl WorktreeTaskProvider for ScriptsTaskProvider {
+    fn name(&self) -> Arc<str> {
+        "scripts".into()
+    }
+
+    fn watched_paths(&self) -> Vec<Arc<Path>> {
+        vec![Path::new("scripts.txt").into()]
+    }
+
+    async fn task_templates(
+        &self,
+        delegate: Arc<dyn LspAdapterDelegate>,
+    ) -> Result<task::TaskTemplates> {
+        self.computations.fetch_add(1, SeqCst);
+        let scripts = delegate.read_text_file("scripts.txt".into()).await?;
+        Ok(task::TaskTemplates(
+            scripts
+                .lines()
+                .map(|script| task::TaskTemplate {
+                    label: script.to_string(),
+                    command: "run".to_string(),
+                    args: vec![script.to_string()],
+                    ..task::TaskTemplate::default()
+                })
+                .collect(),
+        ))
+    }
+}
+
 #[gpui::test]
 async fn test_managing_language_servers(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2919,6 +3138,47 @@ async fn test_save_file(cx: &mut gpui::TestAppContext) {
     assert_eq!(new_text, buffer.update(cx, |buffer, _| buffer.text()));
 }
 
+#[gpui::test]
+async fn test_save_file_records_local_history(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.set_next_mtime(std::time::SystemTime::now());
+    fs.insert_tree("/dir", json!({ "file1": "the old contents" }))
+        .await;
+    cx.update(|cx| local_history::init(fs.clone(), cx));
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/file1", cx))
+        .await
+        .unwrap();
+    for new_contents in ["the new contents", "the newest contents"] {
+        buffer.update(cx, |buffer, cx| buffer.set_text(new_contents, cx));
+        project
+            .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+            .await
+            .unwrap();
+        cx.run_until_parked();
+    }
+
+    // The contents the file had before it was first saved are kept too
+    let local_history = cx.update(|cx| local_history::LocalHistory::global(cx).unwrap());
+    let path = Path::new("/dir/file1");
+    let mut contents = Vec::new();
+    for entry in local_history.entries(path).await.unwrap() {
+        contents.push(local_history.load(path, &entry).await.unwrap());
+    }
+    assert_eq!(
+        contents,
+        [
+            "the old contents",
+            "the new contents",
+            "the newest contents"
+        ]
+    );
+}
+
 #[gpui::test(iterations = 30)]
 async fn test_file_changes_multiple_times_on_disk(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -4124,6 +4384,191 @@ async fn test_search(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_structural_search(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "one.rs": "fn one() { a.unwrap(); }",
+            "two.rs": "fn two() { b().unwrap().len(); }",
+            "three.rs": "fn three() { c.unwrap_or(1); }",
+            "four.rs": "// d.unwrap()",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+
+    let query = SearchQuery::structural(
+        "$a.unwrap()",
+        false,
+        Default::default(),
+        Default::default(),
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        search(&project, query.clone(), cx).await.unwrap(),
+        HashMap::from_iter([
+            ("dir/one.rs".to_string(), vec![11..21]),
+            ("dir/two.rs".to_string(), vec![11..23]),
+        ])
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/two.rs", cx)
+        })
+        .await
+        .unwrap();
+    let query = query.with_replacement("$a?".to_string());
+    buffer.update(cx, |buffer, _| {
+        let snapshot = buffer.snapshot();
+        assert_eq!(
+            query.structural_replacement_for(&snapshot, 11..23),
+            Some("b()?".to_string())
+        );
+        assert_eq!(query.structural_replacement_for(&snapshot, 11..14), None);
+    });
+}
+
+#[gpui::test]
+async fn test_large_file_mode(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings::<ProjectSettings>(cx, |settings| {
+                settings.large_file_threshold = Some(32);
+            });
+            store.update_user_settings::<WorktreeSettings>(cx, |settings| {
+                settings.file_scan_exclusions = Some(vec!["**/*.log".to_string()]);
+            });
+        });
+    });
+
+    let large_text = "fn one() {}\r\nfn two() {}\r\nfn three() {}\r\n";
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "small.rs": "fn one() {}\n",
+            "large.rs": large_text,
+            "large.log": large_text,
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+
+    let small_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/small.rs", cx)
+        })
+        .await
+        .unwrap();
+    small_buffer.update(cx, |buffer, _| {
+        assert!(!buffer.is_large_file());
+        assert!(!buffer.read_only());
+    });
+
+    for path in ["/dir/large.rs", "/dir/large.log"] {
+        let buffer = project
+            .update(cx, |project, cx| project.open_local_buffer(path, cx))
+            .await
+            .unwrap();
+        cx.executor().run_until_parked();
+        buffer.update(cx, |buffer, cx| {
+            assert!(buffer.is_large_file(), "{path} should be a large file");
+            assert!(buffer.read_only(), "{path} should be read-only");
+            assert_eq!(buffer.line_ending(), LineEnding::Windows);
+            assert_eq!(buffer.snapshot().syntax_layers().count(), 0);
+
+            // Only the page of the file that's being viewed is loaded.
+            let (file, page) = buffer.large_file_page().unwrap();
+            assert_eq!(file.len(), large_text.len() as u64);
+            assert_eq!((file.page_count(), page), (2, 0));
+            assert_eq!(buffer.text(), "fn one() {}\nfn two() {}\n");
+            assert_eq!(buffer.large_file_start_row(), 0);
+
+            buffer.set_large_file_page(1, cx);
+            assert_eq!(buffer.text(), "fn three() {}\n");
+            assert_eq!(buffer.large_file_start_row(), 2);
+            assert!(!buffer.is_dirty());
+            assert!(buffer.undo(cx).is_none());
+
+            buffer.set_large_file_page(0, cx);
+            assert_eq!(buffer.text(), "fn one() {}\nfn two() {}\n");
+            assert_eq!(Point::new(1, 3).to_offset(&buffer.snapshot()), 15);
+
+            let state = buffer.to_proto(cx);
+            assert!(state.large_file);
+            let replica = Buffer::from_proto(1, Capability::ReadWrite, state, None).unwrap();
+            assert!(replica.is_large_file());
+            assert!(replica.read_only());
+        });
+    }
+
+    // Files that are open in large file mode are still searched.
+    let query = SearchQuery::text(
+        "two",
+        false,
+        true,
+        false,
+        Default::default(),
+        Default::default(),
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        search(&project, query, cx).await.unwrap(),
+        HashMap::from_iter([("dir/large.rs".to_string(), vec![15..18])])
+    );
+}
+
+#[gpui::test]
+async fn test_large_file_mode_maps_file_from_disk(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.executor().allow_parking();
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings::<ProjectSettings>(cx, |settings| {
+                settings.large_file_threshold = Some(1024);
+            });
+        });
+    });
+
+    let large_text = "a line of a large log\n".repeat(10_000);
+    let dir = temp_tree(json!({ "large.log": large_text }));
+    let project = Project::test(Arc::new(RealFs::default()), [dir.path()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(dir.path().join("large.log"), cx)
+        })
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, cx| {
+        let (file, _) = buffer.large_file_page().unwrap();
+        assert_eq!(file.len(), large_text.len() as u64);
+        assert_eq!(file.row_count(), 10_001);
+        let last_page = file.page_count() - 1;
+        assert!(buffer.len() <= 1024);
+        assert!(buffer.text().starts_with("a line of a large log\n"));
+
+        buffer.set_large_file_page(last_page, cx);
+        assert!(buffer.len() <= 1024);
+        assert!(buffer.text().ends_with("a line of a large log\n"));
+        assert_eq!(
+            buffer.large_file_start_row() + buffer.max_point().row as u64,
+            10_000
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_search_with_inclusions(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -28,11 +28,13 @@ use crate::worktree_store::WorktreeStore;
 pub struct Inventory {
     last_scheduled_tasks: VecDeque<(TaskSourceKind, ResolvedTask)>,
     templates_from_settings: ParsedTemplates,
+    templates_from_providers: HashMap<WorktreeId, HashMap<Arc<str>, Vec<TaskTemplate>>>,
 }
 
 #[derive(Debug, Default)]
 struct ParsedTemplates {
     global: Vec<TaskTemplate>,
+    workspace_file: Option<(Arc<Path>, Vec<TaskTemplate>)>,
     worktree: HashMap<WorktreeId, HashMap<Arc<Path>, Vec<TaskTemplate>>>,
 }
 
@@ -54,6 +56,8 @@ pub enum TaskSourceKind {
     },
     /// Languages-specific tasks coming from extensions.
     Language { name: Arc<str> },
+    /// Tasks computed for the whole worktree by a [`language::WorktreeTaskProvider`], e.g. from its package.json
+    Provider { id: WorktreeId, name: Arc<str> },
 }
 
 impl TaskSourceKind {
@@ -71,6 +75,7 @@ impl TaskSourceKind {
                 format!("{id_base}_{id}_{}", directory_in_worktree.display())
             }
             TaskSourceKind::Language { name } => format!("language_{name}"),
+            TaskSourceKind::Provider { id, name } => format!("provider_{name}_{id}"),
         }
     }
 }
@@ -99,6 +104,7 @@ impl Inventory {
             .flat_map(|task| Some((task_source_kind.clone()?, task)));
 
         self.templates_from_settings(worktree)
+            .chain(self.templates_from_providers(worktree))
             .chain(language_tasks)
             .collect()
     }
@@ -172,6 +178,7 @@ impl Inventory {
             .flat_map(|task| Some((task_source_kind.clone()?, task)));
         let new_resolved_tasks = self
             .templates_from_settings(worktree)
+            .chain(self.templates_from_providers(worktree))
             .chain(language_tasks)
             .filter_map(|(kind, task)| {
                 let id_base = kind.to_id_base();
@@ -252,6 +259,19 @@ impl Inventory {
                     template,
                 )
             })
+            .chain(self.templates_from_settings.workspace_file.iter().flat_map(
+                |(abs_path, templates)| {
+                    templates.iter().map(move |template| {
+                        (
+                            TaskSourceKind::AbsPath {
+                                id_base: Cow::Borrowed("workspace file tasks"),
+                                abs_path: abs_path.to_path_buf(),
+                            },
+                            template.clone(),
+                        )
+                    })
+                },
+            ))
             .chain(worktree.into_iter().flat_map(|worktree| {
                 self.templates_from_settings
                     .worktree
@@ -276,6 +296,58 @@ impl Inventory {
             }))
     }
 
+    fn templates_from_providers(
+        &self,
+        worktree: Option<WorktreeId>,
+    ) -> impl '_ + Iterator<Item = (TaskSourceKind, TaskTemplate)> {
+        worktree
+            .and_then(|worktree| Some((worktree, self.templates_from_providers.get(&worktree)?)))
+            .into_iter()
+            .flat_map(|(worktree, providers)| {
+                providers.iter().flat_map(move |(name, templates)| {
+                    templates.iter().map(move |template| {
+                        (
+                            TaskSourceKind::Provider {
+                                id: worktree,
+                                name: name.clone(),
+                            },
+                            template.clone(),
+                        )
+                    })
+                })
+            })
+    }
+
+    /// Replaces the tasks that the given provider computed for the worktree.
+    pub(crate) fn update_provider_tasks(
+        &mut self,
+        worktree: WorktreeId,
+        provider: Arc<str>,
+        templates: Vec<TaskTemplate>,
+    ) {
+        let providers = self.templates_from_providers.entry(worktree).or_default();
+        if templates.is_empty() {
+            providers.remove(&provider);
+        } else {
+            providers.insert(provider, templates);
+        }
+    }
+
+    /// Forgets the tasks computed for the worktree by the providers that `keep` returns false for.
+    pub(crate) fn retain_provider_tasks(
+        &mut self,
+        worktree: WorktreeId,
+        mut keep: impl FnMut(&str) -> bool,
+    ) {
+        if let Some(providers) = self.templates_from_providers.get_mut(&worktree) {
+            providers.retain(|name, _| keep(name));
+        }
+    }
+
+    pub(crate) fn remove_provider_tasks(&mut self, worktree: WorktreeId) {
+        self.templates_from_providers.remove(&worktree);
+    }
+
     /// Updates in-memory task metadata from the JSON string given.
     /// Will fail if the JSON is not a valid array of objects, but will continue if any object will not parse into a [`TaskTemplate`].
     ///
@@ -285,12 +357,7 @@ impl Inventory {
         location: Option<SettingsLocation<'_>>,
         raw_tasks_json: Option<&str>,
     ) -> anyhow::Result<()> {
-        let raw_tasks =
-            parse_json_with_comments::<Vec<serde_json::Value>>(raw_tasks_json.unwrap_or("[]"))
-                .context("parsing tasks file content as a JSON array")?;
-        let new_templates = raw_tasks.into_iter().filter_map(|raw_template| {
-            serde_json::from_value::<TaskTemplate>(raw_template).log_err()
-        });
+        let new_templates = parse_task_templates(raw_tasks_json)?.into_iter();
 
         let parsed_templates = &mut self.templates_from_settings;
         match location {
@@ -314,6 +381,30 @@ impl Inventory {
         }
         Ok(())
     }
+
+    /// Replaces the tasks defined in the project's workspace file, whose tasks apply to all of
+    /// its worktrees.
+    pub(crate) fn update_workspace_file_tasks(
+        &mut self,
+        abs_path: Option<Arc<Path>>,
+        raw_tasks_json: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.templates_from_settings.workspace_file = match abs_path {
+            Some(abs_path) => Some((abs_path, parse_task_templates(raw_tasks_json)?)),
+            None => None,
+        };
+        Ok(())
+    }
+}
+
+fn parse_task_templates(raw_tasks_json: Option<&str>) -> anyhow::Result<Vec<TaskTemplate>> {
+    let raw_tasks =
+        parse_json_with_comments::<Vec<serde_json::Value>>(raw_tasks_json.unwrap_or("[]"))
+            .context("parsing tasks file content as a JSON array")?;
+    Ok(raw_tasks
+        .into_iter()
+        .filter_map(|raw_template| serde_json::from_value::<TaskTemplate>(raw_template).log_err())
+        .collect())
 }
 
 fn task_lru_comparator(
@@ -344,7 +435,8 @@ fn task_source_kind_preference(kind: &TaskSourceKind) -> u32 {
         TaskSourceKind::Language { .. } => 1,
         TaskSourceKind::UserInput => 2,
         TaskSourceKind::Worktree { .. } => 3,
-        TaskSourceKind::AbsPath { .. } => 4,
+        TaskSourceKind::Provider { .. } => 4,
+        TaskSourceKind::AbsPath { .. } => 5,
     }
 }
 
@@ -814,6 +906,75 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_inventory_provider_tasks(cx: &mut TestAppContext) {
+        init_test(cx);
+        let inventory = cx.update(Inventory::new);
+        let worktree_1 = WorktreeId::from_usize(1);
+        let worktree_2 = WorktreeId::from_usize(2);
+        let npm: Arc<str> = "npm".into();
+        let make: Arc<str> = "make".into();
+        let templates = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| TaskTemplate {
+                    label: name.to_string(),
+                    command: "echo".to_string(),
+                    ..TaskTemplate::default()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        inventory.update(cx, |inventory, _| {
+            inventory.update_provider_tasks(worktree_1, npm.clone(), templates(&["build", "test"]));
+            inventory.update_provider_tasks(worktree_1, make.clone(), templates(&["all"]));
+            inventory.update_provider_tasks(worktree_2, npm.clone(), templates(&["lint"]));
+        });
+        assert_eq!(
+            task_template_names(&inventory, None, cx),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            task_template_names(&inventory, Some(worktree_1), cx),
+            ["all", "build", "test"]
+        );
+        assert_eq!(
+            task_template_names(&inventory, Some(worktree_2), cx),
+            ["lint"]
+        );
+        assert_eq!(
+            list_tasks(&inventory, Some(worktree_2), cx).await,
+            [(
+                TaskSourceKind::Provider {
+                    id: worktree_2,
+                    name: npm.clone(),
+                },
+                "lint".to_string()
+            )]
+        );
+
+        inventory.update(cx, |inventory, _| {
+            inventory.update_provider_tasks(worktree_1, npm.clone(), Vec::new());
+            inventory.retain_provider_tasks(worktree_2, |name| name != "npm");
+        });
+        assert_eq!(
+            task_template_names(&inventory, Some(worktree_1), cx),
+            ["all"]
+        );
+        assert_eq!(
+            task_template_names(&inventory, Some(worktree_2), cx),
+            Vec::<String>::new()
+        );
+
+        inventory.update(cx, |inventory, _| {
+            inventory.remove_provider_tasks(worktree_1)
+        });
+        assert_eq!(
+            task_template_names(&inventory, Some(worktree_1), cx),
+            Vec::<String>::new()
+        );
+    }
+
     fn init_test(_cx: &mut TestAppContext) {
         if std::env::var("RUST_LOG").is_ok() {
             env_logger::try_init().ok();
@@ -1,23 +1,32 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use anyhow::Context as _;
 use collections::HashMap;
 use fs::Fs;
 use futures::StreamExt as _;
-use gpui::{AppContext, AsyncAppContext, EventEmitter, Model, ModelContext, Task, WeakModel};
+use gpui::{
+    AppContext, AsyncAppContext, EventEmitter, Model, ModelContext, Subscription, Task, WeakModel,
+};
 use language::{
     proto::{deserialize_anchor, serialize_anchor},
-    ContextProvider as _, LanguageToolchainStore, Location,
+    ContextProvider as _, LanguageRegistry, LanguageToolchainStore, Location, LspAdapterDelegate,
+    WorktreeTaskProvider,
 };
 use rpc::{proto, AnyProtoClient, TypedEnvelope};
 use settings::{watch_config_file, SettingsLocation};
 use task::{TaskContext, TaskVariables, VariableName};
 use text::BufferId;
 use util::ResultExt;
+use worktree::{Worktree, WorktreeId};
 
 use crate::{
-    buffer_store::BufferStore, worktree_store::WorktreeStore, BasicContextProvider, Inventory,
-    ProjectEnvironment,
+    buffer_store::BufferStore,
+    lsp_store::LocalLspAdapterDelegate,
+    worktree_store::{WorktreeStore, WorktreeStoreEvent},
+    BasicContextProvider, Inventory, LspStore, ProjectEnvironment,
 };
 
 #[expect(clippy::large_enum_variant)]
@@ -39,6 +48,7 @@ enum StoreMode {
     Local {
         downstream_client: Option<(AnyProtoClient, u64)>,
         environment: Model<ProjectEnvironment>,
+        provider_tasks: ProviderTasks,
     },
     Remote {
         upstream_client: AnyProtoClient,
@@ -46,6 +56,16 @@ enum StoreMode {
     },
 }
 
+/// Keeps the tasks of [`WorktreeTaskProvider`]s up to date for the local worktrees.
+struct ProviderTasks {
+    languages: Arc<LanguageRegistry>,
+    lsp_store: WeakModel<LspStore>,
+    worktree_subscriptions: HashMap<WorktreeId, Subscription>,
+    refreshes: HashMap<(WorktreeId, Arc<str>), Task<()>>,
+    _worktree_store_subscription: Subscription,
+    _registry_watcher: Task<()>,
+}
+
 impl EventEmitter<crate::Event> for TaskStore {}
 
 impl TaskStore {
@@ -153,18 +173,30 @@ impl TaskStore {
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn local(
         fs: Arc<dyn Fs>,
         buffer_store: WeakModel<BufferStore>,
         worktree_store: Model<WorktreeStore>,
         toolchain_store: Arc<dyn LanguageToolchainStore>,
         environment: Model<ProjectEnvironment>,
+        languages: Arc<LanguageRegistry>,
+        lsp_store: WeakModel<LspStore>,
         cx: &mut ModelContext<'_, Self>,
     ) -> Self {
         Self::Functional(StoreState {
             mode: StoreMode::Local {
                 downstream_client: None,
                 environment,
+                provider_tasks: ProviderTasks {
+                    _worktree_store_subscription: cx
+                        .subscribe(&worktree_store, Self::on_worktree_store_event),
+                    _registry_watcher: Self::watch_worktree_task_providers(languages.clone(), cx),
+                    languages,
+                    lsp_store,
+                    worktree_subscriptions: HashMap::default(),
+                    refreshes: HashMap::default(),
+                },
             },
             task_inventory: Inventory::new(cx),
             buffer_store,
@@ -284,6 +316,208 @@ impl TaskStore {
         })
     }
 
+    pub(super) fn update_workspace_file_tasks(
+        &self,
+        abs_path: Option<Arc<Path>>,
+        raw_tasks_json: Option<&str>,
+        cx: &mut ModelContext<'_, Self>,
+    ) -> anyhow::Result<()> {
+        let task_inventory = match self {
+            TaskStore::Functional(state) => &state.task_inventory,
+            TaskStore::Noop => return Ok(()),
+        };
+
+        task_inventory.update(cx, |inventory, _| {
+            inventory.update_workspace_file_tasks(abs_path, raw_tasks_json)
+        })
+    }
+
+    fn on_worktree_store_event(
+        &mut self,
+        _: Model<WorktreeStore>,
+        event: &WorktreeStoreEvent,
+        cx: &mut ModelContext<Self>,
+    ) {
+        match event {
+            WorktreeStoreEvent::WorktreeAdded(worktree) => {
+                if !worktree.read(cx).is_local() {
+                    return;
+                }
+                let worktree_id = worktree.read(cx).id();
+                let subscription = cx.subscribe(worktree, |task_store, worktree, event, cx| {
+                    if let worktree::Event::UpdatedEntries(changes) = event {
+                        task_store.refresh_provider_tasks(
+                            &worktree,
+                            |provider| {
+                                provider.watched_paths().iter().any(|watched_path| {
+                                    changes.iter().any(|(path, _, _)| path == watched_path)
+                                })
+                            },
+                            cx,
+                        );
+                    }
+                });
+                if let Some((provider_tasks, _)) = self.provider_tasks() {
+                    provider_tasks
+                        .worktree_subscriptions
+                        .insert(worktree_id, subscription);
+                }
+                self.refresh_provider_tasks(worktree, |_| true, cx);
+            }
+            WorktreeStoreEvent::WorktreeRemoved(_, worktree_id) => {
+                if let Some((provider_tasks, task_inventory)) = self.provider_tasks() {
+                    provider_tasks.worktree_subscriptions.remove(worktree_id);
+                    provider_tasks
+                        .refreshes
+                        .retain(|(refreshed_worktree_id, _), _| {
+                            refreshed_worktree_id != worktree_id
+                        });
+                    task_inventory.update(cx, |inventory, _| {
+                        inventory.remove_provider_tasks(*worktree_id)
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn provider_tasks(&mut self) -> Option<(&mut ProviderTasks, &Model<Inventory>)> {
+        match self {
+            TaskStore::Functional(StoreState {
+                mode: StoreMode::Local { provider_tasks, .. },
+                task_inventory,
+                ..
+            }) => Some((provider_tasks, task_inventory)),
+            _ => None,
+        }
+    }
+
+    /// Recomputes the tasks of the providers matching the filter, for a local worktree.
+    fn refresh_provider_tasks(
+        &mut self,
+        worktree: &Model<Worktree>,
+        filter: impl Fn(&dyn WorktreeTaskProvider) -> bool,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some((provider_tasks, task_inventory)) = self.provider_tasks() else {
+            return;
+        };
+        let providers = provider_tasks
+            .languages
+            .worktree_task_providers()
+            .into_iter()
+            .filter(|provider| filter(provider.as_ref()))
+            .collect::<Vec<_>>();
+        if providers.is_empty() {
+            return;
+        }
+        let Some(lsp_store) = provider_tasks.lsp_store.upgrade() else {
+            return;
+        };
+
+        let task_inventory = task_inventory.downgrade();
+        let worktree_id = worktree.read(cx).id();
+        let delegate: Arc<dyn LspAdapterDelegate> = lsp_store.update(cx, |lsp_store, cx| {
+            LocalLspAdapterDelegate::for_local(lsp_store, worktree, cx)
+        });
+        for provider in providers {
+            let name = provider.name();
+            let delegate = delegate.clone();
+            let task_inventory = task_inventory.clone();
+            let refresh = cx.spawn(|_, mut cx| async move {
+                let templates = match provider.task_templates(delegate).await {
+                    Ok(templates) => templates.0,
+                    Err(error) => {
+                        log::error!("failed to compute the tasks of provider {name}: {error:#}");
+                        Vec::new()
+                    }
+                };
+                task_inventory
+                    .update(&mut cx, |inventory, _| {
+                        inventory.update_provider_tasks(worktree_id, name, templates)
+                    })
+                    .ok();
+            });
+            provider_tasks
+                .refreshes
+                .insert((worktree_id, provider.name()), refresh);
+        }
+    }
+
+    /// Recomputes the tasks of all local worktrees whenever a provider is registered, and
+    /// forgets the tasks of the providers that are removed.
+    fn watch_worktree_task_providers(
+        languages: Arc<LanguageRegistry>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<()> {
+        let mut subscription = languages.subscribe();
+        cx.spawn(move |task_store, mut cx| async move {
+            let mut known_providers = Vec::<Arc<dyn WorktreeTaskProvider>>::new();
+            while let Some(()) = subscription.next().await {
+                let providers = languages.worktree_task_providers();
+                let added_providers = providers
+                    .iter()
+                    .filter(|provider| {
+                        !known_providers
+                            .iter()
+                            .any(|known_provider| Arc::ptr_eq(known_provider, provider))
+                    })
+                    .map(|provider| provider.name())
+                    .collect::<Vec<_>>();
+                let removed_providers =
+                    known_providers.len() + added_providers.len() != providers.len();
+                known_providers = providers;
+                if added_providers.is_empty() && !removed_providers {
+                    continue;
+                }
+
+                let provider_names = known_providers
+                    .iter()
+                    .map(|provider| provider.name())
+                    .collect::<Vec<_>>();
+                let Ok(()) = task_store.update(&mut cx, |task_store, cx| {
+                    let Some((provider_tasks, task_inventory)) = task_store.provider_tasks() else {
+                        return;
+                    };
+                    let worktree_ids = provider_tasks
+                        .worktree_subscriptions
+                        .keys()
+                        .copied()
+                        .collect::<Vec<_>>();
+                    provider_tasks
+                        .refreshes
+                        .retain(|(_, name), _| provider_names.contains(name));
+                    task_inventory.update(cx, |inventory, _| {
+                        for worktree_id in worktree_ids {
+                            inventory.retain_provider_tasks(worktree_id, |name| {
+                                provider_names.iter().any(|known| known.as_ref() == name)
+                            });
+                        }
+                    });
+
+                    let worktrees = match task_store {
+                        TaskStore::Functional(state) => state
+                            .worktree_store
+                            .read(cx)
+                            .worktrees()
+                            .filter(|worktree| worktree.read(cx).is_local())
+                            .collect::<Vec<_>>(),
+                        TaskStore::Noop => Vec::new(),
+                    };
+                    for worktree in worktrees {
+                        task_store.refresh_provider_tasks(
+                            &worktree,
+                            |provider| added_providers.contains(&provider.name()),
+                            cx,
+                        );
+                    }
+                }) else {
+                    break;
+                };
+            }
+        })
+    }
+
     fn subscribe_to_global_task_file_changes(
         fs: Arc<dyn Fs>,
         cx: &mut ModelContext<'_, Self>,
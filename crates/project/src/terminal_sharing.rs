@@ -0,0 +1,648 @@
+//! Sharing the host's terminals with the guests of a collaborative project.
+//!
+//! The host streams the screen of each shared terminal to the guests, sending only the lines
+//! that changed since the previous update. Guests mirror those screens in remote
+//! [`Terminal`]s, and forward what's typed into them to the host when the terminal is shared
+//! read-write.
+//!
+//! Only the screen is shared, not the scrollback: the mirrors have no history of their own,
+//! so guests can't scroll back to output from before they opened a terminal, or that has
+//! scrolled off the host's screen since.
+
+use std::time::Duration;
+
+use anyhow::{anyhow, Context as _, Result};
+use client::{proto, TypedEnvelope};
+use collections::HashMap;
+use futures::{channel::mpsc, StreamExt as _};
+use gpui::{
+    AppContext, AsyncAppContext, Context as _, Model, ModelContext, Subscription, Task, WeakModel,
+};
+use rpc::AnyProtoClient;
+use settings::Settings as _;
+use terminal::{
+    alacritty_terminal::{
+        index::{Column, Line, Point as AlacPoint},
+        term::cell::{Cell, Flags},
+        vte::ansi::{Color, NamedColor, Rgb},
+    },
+    terminal_settings::TerminalSettings,
+    Terminal, TerminalBuilder, TerminalScreen,
+};
+use util::ResultExt as _;
+
+use crate::{Event, Project};
+
+/// How long the host waits after a shared terminal's screen changes before sending it to
+/// guests, so that bursts of output are batched into a single update.
+const UPDATE_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Whether the guests of a project can type into a terminal that the host shares.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TerminalAccess {
+    ReadOnly,
+    ReadWrite,
+}
+
+/// A terminal that the host of a remote project shares.
+#[derive(Clone, Debug)]
+pub struct SharedTerminalMetadata {
+    pub id: u64,
+    pub title: String,
+    pub access: TerminalAccess,
+}
+
+#[derive(Default)]
+pub(crate) struct TerminalSharing {
+    /// On the host, the terminals that are shared with guests.
+    shared: HashMap<u64, SharedTerminal>,
+    /// On guests, the terminals that the host shares.
+    remote: HashMap<u64, RemoteTerminal>,
+}
+
+struct SharedTerminal {
+    terminal: WeakModel<Terminal>,
+    access: TerminalAccess,
+    last_screen: TerminalScreen,
+    last_title: String,
+    pending_update: Option<Task<()>>,
+    _subscriptions: [Subscription; 2],
+}
+
+struct RemoteTerminal {
+    metadata: SharedTerminalMetadata,
+    terminal: Option<WeakModel<Terminal>>,
+}
+
+impl Project {
+    pub(crate) fn init_terminal_sharing(client: &AnyProtoClient) {
+        client.add_model_message_handler(Self::handle_advertise_terminals);
+        client.add_model_request_handler(Self::handle_open_terminal);
+        client.add_model_message_handler(Self::handle_update_terminal);
+        client.add_model_request_handler(Self::handle_terminal_input);
+    }
+
+    /// Shares a local terminal with the guests of this project, which must be shared.
+    ///
+    /// Returns the id that guests use to refer to the terminal. Sharing a terminal that's
+    /// already shared changes its access.
+    pub fn share_terminal(
+        &mut self,
+        terminal: &Model<Terminal>,
+        access: TerminalAccess,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<u64> {
+        if self.is_via_collab() || self.remote_id().is_none() {
+            return Err(anyhow!(
+                "only the host of a shared project can share terminals"
+            ));
+        }
+        if terminal.read(cx).is_remote() {
+            return Err(anyhow!("can't share a terminal of a remote project"));
+        }
+
+        let terminal_id = terminal.entity_id().as_u64();
+        if let Some(shared) = self.terminals.sharing.shared.get_mut(&terminal_id) {
+            shared.access = access;
+        } else {
+            let subscriptions = [
+                cx.subscribe(terminal, move |this, _, event, cx| match event {
+                    terminal::Event::Wakeup | terminal::Event::TitleChanged => {
+                        this.schedule_terminal_update(terminal_id, cx)
+                    }
+                    _ => {}
+                }),
+                cx.observe_release(terminal, move |this, _, cx| {
+                    this.unshare_terminal(terminal_id, cx)
+                }),
+            ];
+            let weak_terminal = terminal.downgrade();
+            let terminal = terminal.read(cx);
+            self.terminals.sharing.shared.insert(
+                terminal_id,
+                SharedTerminal {
+                    terminal: weak_terminal,
+                    access,
+                    last_screen: terminal.screen(),
+                    last_title: terminal.title(false),
+                    pending_update: None,
+                    _subscriptions: subscriptions,
+                },
+            );
+        }
+
+        self.advertise_terminals(cx);
+        cx.emit(Event::SharedTerminalsChanged);
+        cx.notify();
+        Ok(terminal_id)
+    }
+
+    /// Stops sharing a terminal with the guests of this project.
+    pub fn unshare_terminal(&mut self, terminal_id: u64, cx: &mut ModelContext<Self>) {
+        if self.terminals.sharing.shared.remove(&terminal_id).is_some() {
+            self.advertise_terminals(cx);
+            cx.emit(Event::SharedTerminalsChanged);
+            cx.notify();
+        }
+    }
+
+    /// Returns how the given local terminal is shared with guests, if it is.
+    pub fn terminal_access(&self, terminal: &Model<Terminal>) -> Option<TerminalAccess> {
+        self.terminals
+            .sharing
+            .shared
+            .get(&terminal.entity_id().as_u64())
+            .map(|shared| shared.access)
+    }
+
+    /// Returns the id that the given terminal is shared under, on either the host or a guest.
+    pub fn shared_terminal_id(&self, terminal: &Model<Terminal>, cx: &AppContext) -> Option<u64> {
+        let sharing = &self.terminals.sharing;
+        if terminal.read(cx).is_remote() {
+            sharing.remote.iter().find_map(|(id, remote)| {
+                let mirror = remote.terminal.as_ref()?;
+                (mirror.entity_id() == terminal.entity_id()).then_some(*id)
+            })
+        } else {
+            let id = terminal.entity_id().as_u64();
+            sharing.shared.contains_key(&id).then_some(id)
+        }
+    }
+
+    /// Returns the terminals that the host of this remote project shares.
+    pub fn remote_terminals(&self) -> impl Iterator<Item = &SharedTerminalMetadata> {
+        self.terminals
+            .sharing
+            .remote
+            .values()
+            .map(|remote| &remote.metadata)
+    }
+
+    /// Opens a terminal that the host of this remote project shares, mirroring its screen.
+    pub fn open_remote_terminal(
+        &mut self,
+        terminal_id: u64,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Model<Terminal>>> {
+        let Some(project_id) = self.remote_id().filter(|_| self.is_via_collab()) else {
+            return Task::ready(Err(anyhow!(
+                "only guests of a shared project can open its terminals"
+            )));
+        };
+        let Some(remote) = self.terminals.sharing.remote.get(&terminal_id) else {
+            return Task::ready(Err(anyhow!("terminal {terminal_id} isn't shared")));
+        };
+        if let Some(terminal) = remote.terminal.as_ref().and_then(|t| t.upgrade()) {
+            return Task::ready(Ok(terminal));
+        }
+
+        let client = self.client.clone();
+        cx.spawn(move |this, mut cx| async move {
+            let response = client
+                .request(proto::OpenTerminal {
+                    project_id,
+                    terminal_id,
+                })
+                .await?;
+            let screen = response.screen.context("missing terminal screen")?;
+
+            this.update(&mut cx, |this, cx| {
+                let remote = this
+                    .terminals
+                    .sharing
+                    .remote
+                    .get_mut(&terminal_id)
+                    .with_context(|| format!("terminal {terminal_id} is no longer shared"))?;
+                if let Some(terminal) = remote.terminal.as_ref().and_then(|t| t.upgrade()) {
+                    return Ok(terminal);
+                }
+
+                let (input_tx, input_rx) = mpsc::unbounded();
+                let cursor_shape = TerminalSettings::get_global(cx)
+                    .cursor_shape
+                    .unwrap_or_default();
+                let title = remote.metadata.title.clone();
+                let terminal = cx.new_model(|cx| {
+                    let mut terminal =
+                        TerminalBuilder::new_remote(title, cursor_shape, input_tx).subscribe(cx);
+                    apply_screen(&mut terminal, screen, cx);
+                    terminal
+                });
+                remote.terminal = Some(terminal.downgrade());
+                cx.spawn(move |this, cx| {
+                    Self::forward_terminal_input(this, project_id, terminal_id, input_rx, cx)
+                })
+                .detach();
+                Ok(terminal)
+            })?
+        })
+    }
+
+    async fn forward_terminal_input(
+        this: WeakModel<Self>,
+        project_id: u64,
+        terminal_id: u64,
+        mut input_rx: mpsc::UnboundedReceiver<Vec<u8>>,
+        mut cx: AsyncAppContext,
+    ) {
+        while let Some(input) = input_rx.next().await {
+            let Ok(state) = this.update(&mut cx, |this, _| {
+                let remote = this.terminals.sharing.remote.get(&terminal_id)?;
+                Some((this.client.clone(), remote.metadata.access))
+            }) else {
+                break;
+            };
+            // Input is dropped while the terminal isn't shared read-write.
+            let Some((client, TerminalAccess::ReadWrite)) = state else {
+                continue;
+            };
+            client
+                .request(proto::TerminalInput {
+                    project_id,
+                    terminal_id,
+                    input,
+                })
+                .await
+                .log_err();
+        }
+    }
+
+    fn advertise_terminals(&self, cx: &AppContext) {
+        let Some(project_id) = self.remote_id() else {
+            return;
+        };
+        if self.is_via_collab() {
+            return;
+        }
+
+        let terminals = self
+            .terminals
+            .sharing
+            .shared
+            .iter()
+            .filter_map(|(id, shared)| {
+                let terminal = shared.terminal.upgrade()?;
+                Some(proto::SharedTerminal {
+                    terminal_id: *id,
+                    title: terminal.read(cx).title(false),
+                    read_only: shared.access == TerminalAccess::ReadOnly,
+                })
+            })
+            .collect();
+        self.client
+            .send(proto::AdvertiseTerminals {
+                project_id,
+                terminals,
+            })
+            .log_err();
+    }
+
+    pub(crate) fn advertise_terminals_to_new_collaborator(&self, cx: &AppContext) {
+        if !self.terminals.sharing.shared.is_empty() {
+            self.advertise_terminals(cx);
+        }
+    }
+
+    pub(crate) fn unshare_all_terminals(&mut self) {
+        self.terminals.sharing.shared.clear();
+    }
+
+    fn schedule_terminal_update(&mut self, terminal_id: u64, cx: &mut ModelContext<Self>) {
+        let Some(shared) = self.terminals.sharing.shared.get_mut(&terminal_id) else {
+            return;
+        };
+        if shared.pending_update.is_some() {
+            return;
+        }
+
+        shared.pending_update = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(UPDATE_INTERVAL).await;
+            this.update(&mut cx, |this, cx| {
+                this.send_terminal_update(terminal_id, cx)
+            })
+            .ok();
+        }));
+    }
+
+    fn send_terminal_update(&mut self, terminal_id: u64, cx: &mut ModelContext<Self>) {
+        let Some(project_id) = self.remote_id() else {
+            return;
+        };
+        let Some(shared) = self.terminals.sharing.shared.get_mut(&terminal_id) else {
+            return;
+        };
+        shared.pending_update = None;
+        let Some(terminal) = shared.terminal.upgrade() else {
+            return;
+        };
+
+        let terminal = terminal.read(cx);
+        let screen = terminal.screen();
+        let title = terminal.title(false);
+        let changed_lines = screen
+            .changed_lines(&shared.last_screen)
+            .collect::<Vec<_>>();
+        if changed_lines.is_empty()
+            && screen.cursor == shared.last_screen.cursor
+            && title == shared.last_title
+        {
+            return;
+        }
+
+        let update = serialize_screen(&screen, changed_lines, title.clone());
+        shared.last_screen = screen;
+        shared.last_title = title;
+        self.client
+            .send(proto::UpdateTerminal {
+                project_id,
+                terminal_id,
+                screen: Some(update),
+            })
+            .log_err();
+    }
+
+    async fn handle_advertise_terminals(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::AdvertiseTerminals>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            let mut remote = std::mem::take(&mut this.terminals.sharing.remote);
+            for terminal in envelope.payload.terminals {
+                let metadata = SharedTerminalMetadata {
+                    id: terminal.terminal_id,
+                    title: terminal.title,
+                    access: if terminal.read_only {
+                        TerminalAccess::ReadOnly
+                    } else {
+                        TerminalAccess::ReadWrite
+                    },
+                };
+                let terminal = remote
+                    .remove(&metadata.id)
+                    .and_then(|remote| remote.terminal);
+                this.terminals
+                    .sharing
+                    .remote
+                    .insert(metadata.id, RemoteTerminal { metadata, terminal });
+            }
+
+            // Close the mirrors of terminals that the host stopped sharing.
+            for (_, unshared) in remote {
+                if let Some(terminal) = unshared.terminal.and_then(|t| t.upgrade()) {
+                    terminal.update(cx, |_, cx| cx.emit(terminal::Event::CloseTerminal));
+                }
+            }
+
+            cx.emit(Event::SharedTerminalsChanged);
+            cx.notify();
+        })
+    }
+
+    async fn handle_open_terminal(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::OpenTerminal>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::OpenTerminalResponse> {
+        this.update(&mut cx, |this, cx| {
+            let shared = this
+                .terminals
+                .sharing
+                .shared
+                .get(&envelope.payload.terminal_id)
+                .context("terminal isn't shared")?;
+            let terminal = shared.terminal.upgrade().context("terminal was closed")?;
+            let terminal = terminal.read(cx);
+            let screen = terminal.screen();
+            let lines = (0..screen.lines.len()).collect();
+            Ok(proto::OpenTerminalResponse {
+                screen: Some(serialize_screen(&screen, lines, terminal.title(false))),
+            })
+        })?
+    }
+
+    async fn handle_update_terminal(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UpdateTerminal>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            let terminal = this
+                .terminals
+                .sharing
+                .remote
+                .get(&envelope.payload.terminal_id)
+                .and_then(|remote| remote.terminal.as_ref()?.upgrade());
+            if let Some((terminal, screen)) = terminal.zip(envelope.payload.screen) {
+                terminal.update(cx, |terminal, cx| apply_screen(terminal, screen, cx));
+            }
+        })
+    }
+
+    async fn handle_terminal_input(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::TerminalInput>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |this, cx| {
+            let shared = this
+                .terminals
+                .sharing
+                .shared
+                .get(&envelope.payload.terminal_id)
+                .context("terminal isn't shared")?;
+            if shared.access != TerminalAccess::ReadWrite {
+                return Err(anyhow!("terminal is shared read-only"));
+            }
+            let terminal = shared.terminal.upgrade().context("terminal was closed")?;
+            terminal.update(cx, |terminal, _| {
+                terminal.input_bytes(envelope.payload.input)
+            });
+            Ok(proto::Ack {})
+        })?
+    }
+}
+
+fn serialize_screen(
+    screen: &TerminalScreen,
+    lines: Vec<usize>,
+    title: String,
+) -> proto::TerminalScreen {
+    proto::TerminalScreen {
+        columns: screen.columns as u32,
+        line_count: screen.lines.len() as u32,
+        lines: lines
+            .into_iter()
+            .filter_map(|line| {
+                Some(proto::TerminalLine {
+                    line: line as u32,
+                    runs: serialize_line(screen.lines.get(line)?),
+                })
+            })
+            .collect(),
+        cursor: screen.cursor.map(|point| proto::TerminalCursor {
+            line: point.line.0.max(0) as u32,
+            column: point.column.0 as u32,
+        }),
+        title,
+    }
+}
+
+fn apply_screen(
+    terminal: &mut Terminal,
+    screen: proto::TerminalScreen,
+    cx: &mut ModelContext<Terminal>,
+) {
+    let lines = screen
+        .lines
+        .into_iter()
+        .map(|line| (line.line as usize, deserialize_line(line.runs)));
+    let cursor = screen
+        .cursor
+        .map(|cursor| AlacPoint::new(Line(cursor.line as i32), Column(cursor.column as usize)));
+    terminal.update_remote_screen(
+        screen.columns as usize,
+        screen.line_count as usize,
+        lines,
+        cursor,
+        Some(screen.title),
+        cx,
+    );
+}
+
+fn serialize_line(cells: &[Cell]) -> Vec<proto::TerminalCellRun> {
+    let mut runs = Vec::<proto::TerminalCellRun>::new();
+    let mut last_style = None;
+    for cell in cells {
+        let style = (cell.fg, cell.bg, cell.flags);
+        match runs.last_mut() {
+            Some(run) if last_style == Some(style) => run.text.push(cell.c),
+            _ => {
+                runs.push(proto::TerminalCellRun {
+                    text: cell.c.to_string(),
+                    foreground: Some(serialize_color(cell.fg)),
+                    background: Some(serialize_color(cell.bg)),
+                    flags: cell.flags.bits() as u32,
+                });
+                last_style = Some(style);
+            }
+        }
+    }
+    runs
+}
+
+fn deserialize_line(runs: Vec<proto::TerminalCellRun>) -> Vec<Cell> {
+    let mut cells = Vec::new();
+    for run in runs {
+        let fg = deserialize_color(run.foreground, NamedColor::Foreground);
+        let bg = deserialize_color(run.background, NamedColor::Background);
+        let flags = Flags::from_bits_truncate(run.flags as u16);
+        cells.extend(run.text.chars().map(|c| Cell {
+            c,
+            fg,
+            bg,
+            flags,
+            ..Default::default()
+        }));
+    }
+    cells
+}
+
+const NAMED_COLORS: [NamedColor; 29] = [
+    NamedColor::Black,
+    NamedColor::Red,
+    NamedColor::Green,
+    NamedColor::Yellow,
+    NamedColor::Blue,
+    NamedColor::Magenta,
+    NamedColor::Cyan,
+    NamedColor::White,
+    NamedColor::BrightBlack,
+    NamedColor::BrightRed,
+    NamedColor::BrightGreen,
+    NamedColor::BrightYellow,
+    NamedColor::BrightBlue,
+    NamedColor::BrightMagenta,
+    NamedColor::BrightCyan,
+    NamedColor::BrightWhite,
+    NamedColor::Foreground,
+    NamedColor::Background,
+    NamedColor::Cursor,
+    NamedColor::DimBlack,
+    NamedColor::DimRed,
+    NamedColor::DimGreen,
+    NamedColor::DimYellow,
+    NamedColor::DimBlue,
+    NamedColor::DimMagenta,
+    NamedColor::DimCyan,
+    NamedColor::DimWhite,
+    NamedColor::BrightForeground,
+    NamedColor::DimForeground,
+];
+
+fn serialize_color(color: Color) -> proto::TerminalColor {
+    let variant = match color {
+        Color::Named(named) => proto::terminal_color::Variant::Named(named as u32),
+        Color::Indexed(index) => proto::terminal_color::Variant::Indexed(index as u32),
+        Color::Spec(Rgb { r, g, b }) => {
+            proto::terminal_color::Variant::Rgb(u32::from_be_bytes([0, r, g, b]))
+        }
+    };
+    proto::TerminalColor {
+        variant: Some(variant),
+    }
+}
+
+fn deserialize_color(color: Option<proto::TerminalColor>, default: NamedColor) -> Color {
+    match color.and_then(|color| color.variant) {
+        Some(proto::terminal_color::Variant::Named(named)) => Color::Named(
+            NAMED_COLORS
+                .into_iter()
+                .find(|color| *color as u32 == named)
+                .unwrap_or(default),
+        ),
+        Some(proto::terminal_color::Variant::Indexed(index)) => Color::Indexed(index as u8),
+        Some(proto::terminal_color::Variant::Rgb(rgb)) => {
+            let [_, r, g, b] = rgb.to_be_bytes();
+            Color::Spec(Rgb { r, g, b })
+        }
+        None => Color::Named(default),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_line() {
+        let cell = |c, fg, flags| Cell {
+            c,
+            fg,
+            flags,
+            ..Default::default()
+        };
+        let red = Color::Named(NamedColor::Red);
+        let orange = Color::Spec(Rgb {
+            r: 0xff,
+            g: 0x80,
+            b: 0x00,
+        });
+        let line = vec![
+            cell('e', red, Flags::BOLD),
+            cell('r', red, Flags::BOLD),
+            cell('r', red, Flags::BOLD),
+            cell(':', Color::Named(NamedColor::Foreground), Flags::empty()),
+            cell('界', orange, Flags::WIDE_CHAR),
+            cell(' ', orange, Flags::WIDE_CHAR_SPACER),
+            cell('x', Color::Indexed(208), Flags::UNDERLINE),
+        ];
+
+        let runs = serialize_line(&line);
+        assert_eq!(
+            runs.iter().map(|run| run.text.as_str()).collect::<Vec<_>>(),
+            ["err", ":", "界", " ", "x"]
+        );
+        assert_eq!(deserialize_line(runs), line);
+    }
+}
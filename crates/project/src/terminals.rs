@@ -1,4 +1,4 @@
-use crate::Project;
+use crate::{terminal_sharing::TerminalSharing, Project};
 use anyhow::{Context as _, Result};
 use collections::HashMap;
 use gpui::{AnyWindowHandle, AppContext, Context, Entity, Model, ModelContext, Task, WeakModel};
@@ -25,6 +25,7 @@ use util::ResultExt;
 
 pub struct Terminals {
     pub(crate) local_handles: Vec<WeakModel<terminal::Terminal>>,
+    pub(crate) sharing: TerminalSharing,
 }
 
 /// Terminals are opened either for the users shell, or to run a task.
@@ -0,0 +1,137 @@
+//! Workspace files describe a set of folders to open together as one project, along with
+//! settings and tasks for them, so that a multi-root project can be committed and shared:
+//!
+//! ```json
+//! {
+//!   "folders": [
+//!     { "path": "frontend", "settings": { "tab_size": 2 } },
+//!     { "path": "../backend" }
+//!   ],
+//!   "settings": { "format_on_save": "off" },
+//!   "tasks": [{ "label": "build", "command": "make" }]
+//! }
+//! ```
+
+use anyhow::Result;
+use fs::normalize_path;
+use serde::Deserialize;
+use settings::parse_json_with_comments;
+use std::path::{Path, PathBuf};
+use util::merge_non_null_json_value_into;
+
+pub const WORKSPACE_FILE_EXTENSION: &str = "zed-workspace";
+
+/// Returns whether the file at `path` is named like a workspace file.
+pub fn is_workspace_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == WORKSPACE_FILE_EXTENSION)
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct WorkspaceFileContent {
+    /// The folders to open as worktrees.
+    #[serde(default)]
+    pub folders: Vec<WorkspaceFolder>,
+    /// Settings for every worktree of the project.
+    #[serde(default)]
+    pub settings: Option<serde_json::Value>,
+    /// Tasks for every worktree of the project, in the same format as `tasks.json`.
+    #[serde(default)]
+    pub tasks: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WorkspaceFolder {
+    /// The path of the folder, relative to the directory containing the workspace file.
+    pub path: PathBuf,
+    /// Settings for this folder, which override the settings for every worktree.
+    #[serde(default)]
+    pub settings: Option<serde_json::Value>,
+}
+
+impl WorkspaceFileContent {
+    pub fn parse(content: &str) -> Result<Self> {
+        if content.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        parse_json_with_comments(content)
+    }
+
+    /// Returns the absolute paths of the folders, given the absolute path of the workspace file.
+    pub fn folder_paths(&self, abs_path: &Path) -> Vec<PathBuf> {
+        let dir = abs_path.parent().unwrap_or(abs_path);
+        self.folders
+            .iter()
+            .map(|folder| normalize_path(&dir.join(&folder.path)))
+            .collect()
+    }
+
+    /// Returns the settings for the worktree at `worktree_abs_path`, where `folder_paths` are
+    /// the absolute paths of the folders.
+    pub fn settings_for_worktree(
+        &self,
+        folder_paths: &[PathBuf],
+        worktree_abs_path: &Path,
+    ) -> Option<serde_json::Value> {
+        let folder_settings = self
+            .folders
+            .iter()
+            .zip(folder_paths)
+            .find(|(_, folder_path)| folder_path.as_path() == worktree_abs_path)
+            .and_then(|(folder, _)| folder.settings.clone());
+        match (self.settings.clone(), folder_settings) {
+            (Some(mut settings), Some(folder_settings)) => {
+                merge_non_null_json_value_into(folder_settings, &mut settings);
+                Some(settings)
+            }
+            (settings, folder_settings) => settings.or(folder_settings),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_workspace_file_content() {
+        let content = WorkspaceFileContent::parse(
+            r#"{
+                // Comments are allowed, like in settings files
+                "folders": [
+                    { "path": "frontend", "settings": { "tab_size": 2, "hard_tabs": true } },
+                    { "path": "../backend" }
+                ],
+                "settings": { "tab_size": 4, "format_on_save": "off" },
+                "tasks": [{ "label": "build", "command": "make" }]
+            }"#,
+        )
+        .unwrap();
+
+        let folder_paths = content.folder_paths(Path::new("/code/app/app.zed-workspace"));
+        assert_eq!(
+            folder_paths,
+            [
+                PathBuf::from("/code/app/frontend"),
+                PathBuf::from("/code/backend")
+            ]
+        );
+        assert_eq!(
+            content.settings_for_worktree(&folder_paths, Path::new("/code/app/frontend")),
+            Some(json!({ "tab_size": 2, "hard_tabs": true, "format_on_save": "off" }))
+        );
+        assert_eq!(
+            content.settings_for_worktree(&folder_paths, Path::new("/code/backend")),
+            Some(json!({ "tab_size": 4, "format_on_save": "off" }))
+        );
+        assert_eq!(
+            content.tasks,
+            Some(json!([{ "label": "build", "command": "make" }]))
+        );
+
+        assert!(WorkspaceFileContent::parse("").unwrap().folders.is_empty());
+        assert!(is_workspace_file(Path::new("/code/app/app.zed-workspace")));
+        assert!(!is_workspace_file(Path::new("/code/app/zed-workspace")));
+    }
+}
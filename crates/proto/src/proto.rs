@@ -256,6 +256,8 @@ messages!(
     (CountLanguageModelTokensResponse, Background),
     (RefreshLlmToken, Background),
     (RefreshInlayHints, Foreground),
+    (ReconnectToProject, Foreground),
+    (ReconnectToProjectResponse, Foreground),
     (RejoinChannelBuffers, Foreground),
     (RejoinChannelBuffersResponse, Foreground),
     (RejoinRoom, Foreground),
@@ -339,6 +341,18 @@ messages!(
     (UpdateContext, Foreground),
     (SynchronizeContexts, Foreground),
     (SynchronizeContextsResponse, Foreground),
+    (AdvertiseTerminals, Foreground),
+    (OpenTerminal, Foreground),
+    (OpenTerminalResponse, Foreground),
+    (UpdateTerminal, Foreground),
+    (TerminalInput, Foreground),
+    (GetReviewThreads, Foreground),
+    (GetReviewThreadsResponse, Foreground),
+    (CreateReviewThread, Foreground),
+    (AddReviewComment, Foreground),
+    (SetReviewThreadResolved, Foreground),
+    (ReviewThreadResponse, Foreground),
+    (ReviewThreadUpdated, Foreground),
     (LspExtSwitchSourceHeader, Background),
     (LspExtSwitchSourceHeaderResponse, Background),
     (AddWorktree, Foreground),
@@ -441,6 +455,7 @@ request_messages!(
     (PrepareRename, PrepareRenameResponse),
     (CountLanguageModelTokens, CountLanguageModelTokensResponse),
     (RefreshInlayHints, Ack),
+    (ReconnectToProject, ReconnectToProjectResponse),
     (RejoinChannelBuffers, RejoinChannelBuffersResponse),
     (RejoinRoom, RejoinRoomResponse),
     (ReloadBuffers, ReloadBuffersResponse),
@@ -481,6 +496,12 @@ request_messages!(
     (OpenContext, OpenContextResponse),
     (CreateContext, CreateContextResponse),
     (SynchronizeContexts, SynchronizeContextsResponse),
+    (OpenTerminal, OpenTerminalResponse),
+    (TerminalInput, Ack),
+    (GetReviewThreads, GetReviewThreadsResponse),
+    (CreateReviewThread, ReviewThreadResponse),
+    (AddReviewComment, ReviewThreadResponse),
+    (SetReviewThreadResolved, ReviewThreadResponse),
     (LspExtSwitchSourceHeader, LspExtSwitchSourceHeaderResponse),
     (AddWorktree, AddWorktreeResponse),
     (ShutdownRemoteServer, Ack),
@@ -533,6 +554,7 @@ entity_messages!(
     InlayHints,
     JoinProject,
     LeaveProject,
+    ReconnectToProject,
     LinkedEditingRange,
     MultiLspQuery,
     RestartLanguageServers,
@@ -570,6 +592,10 @@ entity_messages!(
     CreateContext,
     UpdateContext,
     SynchronizeContexts,
+    AdvertiseTerminals,
+    OpenTerminal,
+    UpdateTerminal,
+    TerminalInput,
     LspExtSwitchSourceHeader,
     LanguageServerLog,
     Toast,
@@ -591,6 +617,7 @@ entity_messages!(
     ChannelMessageSent,
     ChannelMessageUpdate,
     RemoveChannelMessage,
+    ReviewThreadUpdated,
     UpdateChannelMessage,
     UpdateChannelBuffer,
     UpdateChannelBufferCollaborators,
@@ -3,6 +3,7 @@ mod remote_servers;
 mod ssh_connections;
 pub use ssh_connections::{is_connecting_over_ssh, open_ssh_project};
 
+use collections::HashMap;
 use disconnected_overlay::DisconnectedOverlay;
 use fuzzy::{StringMatch, StringMatchCandidate};
 use gpui::{
@@ -25,8 +26,8 @@ use std::{
 use ui::{prelude::*, tooltip_container, KeyBinding, ListItem, ListItemSpacing, Tooltip};
 use util::{paths::PathExt, ResultExt};
 use workspace::{
-    CloseIntent, ModalView, OpenOptions, SerializedWorkspaceLocation, Workspace, WorkspaceId,
-    WORKSPACE_DB,
+    CloseIntent, LocalPaths, LocalPathsOrder, ModalView, OpenOptions, SerializedWorkspaceLocation,
+    Workspace, WorkspaceId, WORKSPACE_DB,
 };
 use zed_actions::{OpenRecent, OpenRemote};
 
@@ -65,8 +66,10 @@ impl RecentProjects {
                 .await
                 .log_err()
                 .unwrap_or_default();
+            let workspace_files = workspace_files_on_disk();
             this.update(&mut cx, move |this, cx| {
                 this.picker.update(cx, move |picker, cx| {
+                    picker.delegate.workspace_files = workspace_files;
                     picker.delegate.set_workspaces(workspaces);
                     picker.update_matches(picker.query(cx), cx)
                 })
@@ -134,6 +137,9 @@ impl Render for RecentProjects {
 pub struct RecentProjectsDelegate {
     workspace: WeakView<Workspace>,
     workspaces: Vec<(WorkspaceId, SerializedWorkspaceLocation)>,
+    /// The workspace files that local workspaces were opened from, which are shown and opened
+    /// instead of their folders.
+    workspace_files: HashMap<WorkspaceId, PathBuf>,
     selected_match_index: usize,
     matches: Vec<StringMatch>,
     render_paths: bool,
@@ -148,6 +154,7 @@ impl RecentProjectsDelegate {
         Self {
             workspace,
             workspaces: Vec::new(),
+            workspace_files: HashMap::default(),
             selected_match_index: 0,
             matches: Default::default(),
             create_new_window,
@@ -164,6 +171,35 @@ impl RecentProjectsDelegate {
             .iter()
             .all(|(_, location)| matches!(location, SerializedWorkspaceLocation::Local(_, _)));
     }
+
+    /// Returns the paths to show and open for a local workspace.
+    fn local_paths(
+        &self,
+        workspace_id: WorkspaceId,
+        paths: &LocalPaths,
+        order: &LocalPathsOrder,
+    ) -> Vec<PathBuf> {
+        if let Some(workspace_file) = self.workspace_files.get(&workspace_id) {
+            return vec![workspace_file.clone()];
+        }
+        order
+            .order()
+            .iter()
+            .zip(paths.paths().iter())
+            .sorted_by_key(|(i, _)| *i)
+            .map(|(_, path)| path.clone())
+            .collect()
+    }
+}
+
+fn workspace_files_on_disk() -> HashMap<WorkspaceId, PathBuf> {
+    WORKSPACE_DB
+        .workspace_files()
+        .log_err()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(_, workspace_file)| workspace_file.is_file())
+        .collect()
 }
 impl EventEmitter<DismissEvent> for RecentProjectsDelegate {}
 impl PickerDelegate for RecentProjectsDelegate {
@@ -210,14 +246,12 @@ impl PickerDelegate for RecentProjectsDelegate {
             .iter()
             .enumerate()
             .filter(|(_, (id, _))| !self.is_current_workspace(*id, cx))
-            .map(|(id, (_, location))| {
+            .map(|(id, (workspace_id, location))| {
                 let combined_string = match location {
-                    SerializedWorkspaceLocation::Local(paths, order) => order
-                        .order()
+                    SerializedWorkspaceLocation::Local(paths, order) => self
+                        .local_paths(*workspace_id, paths, order)
                         .iter()
-                        .zip(paths.paths().iter())
-                        .sorted_by_key(|(i, _)| *i)
-                        .map(|(_, path)| path.compact().to_string_lossy().into_owned())
+                        .map(|path| path.compact().to_string_lossy().into_owned())
                         .collect::<Vec<_>>()
                         .join(""),
                     SerializedWorkspaceLocation::Ssh(ssh_project) => ssh_project
@@ -274,8 +308,8 @@ impl PickerDelegate for RecentProjectsDelegate {
                         Task::ready(Ok(()))
                     } else {
                         match candidate_workspace_location {
-                            SerializedWorkspaceLocation::Local(paths, _) => {
-                                let paths = paths.paths().to_vec();
+                            SerializedWorkspaceLocation::Local(paths, order) => {
+                                let paths = self.local_paths(*candidate_workspace_id, paths, order);
                                 if replace_current_window {
                                     cx.spawn(move |workspace, mut cx| async move {
                                         let continue_replacing = workspace
@@ -361,17 +395,14 @@ impl PickerDelegate for RecentProjectsDelegate {
     ) -> Option<Self::ListItem> {
         let hit = self.matches.get(ix)?;
 
-        let (_, location) = self.workspaces.get(hit.candidate_id)?;
+        let (workspace_id, location) = self.workspaces.get(hit.candidate_id)?;
 
         let mut path_start_offset = 0;
         let paths = match location {
             SerializedWorkspaceLocation::Local(paths, order) => Arc::new(
-                order
-                    .order()
+                self.local_paths(*workspace_id, paths, order)
                     .iter()
-                    .zip(paths.paths().iter())
-                    .sorted_by_key(|(i, _)| **i)
-                    .map(|(_, path)| path.compact())
+                    .map(|path| path.compact())
                     .collect(),
             ),
             SerializedWorkspaceLocation::Ssh(ssh_project) => Arc::new(ssh_project.ssh_urls()),
@@ -536,7 +567,9 @@ impl RecentProjectsDelegate {
                     .recent_workspaces_on_disk()
                     .await
                     .unwrap_or_default();
+                let workspace_files = workspace_files_on_disk();
                 this.update(&mut cx, move |picker, cx| {
+                    picker.delegate.workspace_files = workspace_files;
                     picker.delegate.set_workspaces(workspaces);
                     picker.delegate.set_selected_index(ix.saturating_sub(1), cx);
                     picker.delegate.reset_selected_match_index = false;
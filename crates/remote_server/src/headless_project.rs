@@ -101,13 +101,31 @@ impl HeadlessProject {
             )
         });
 
+        let lsp_store = cx.new_model(|cx| {
+            let mut lsp_store = LspStore::new_local(
+                buffer_store.clone(),
+                worktree_store.clone(),
+                prettier_store.clone(),
+                toolchain_store.clone(),
+                environment.clone(),
+                languages.clone(),
+                http_client.clone(),
+                fs.clone(),
+                cx,
+            );
+            lsp_store.shared(SSH_PROJECT_ID, session.clone().into(), cx);
+            lsp_store
+        });
+
         let task_store = cx.new_model(|cx| {
             let mut task_store = TaskStore::local(
                 fs.clone(),
                 buffer_store.downgrade(),
                 worktree_store.clone(),
                 toolchain_store.read(cx).as_language_toolchain_store(),
-                environment.clone(),
+                environment,
+                languages.clone(),
+                lsp_store.downgrade(),
                 cx,
             );
             task_store.shared(SSH_PROJECT_ID, session.clone().into(), cx);
@@ -124,22 +142,6 @@ impl HeadlessProject {
             observer
         });
 
-        let lsp_store = cx.new_model(|cx| {
-            let mut lsp_store = LspStore::new_local(
-                buffer_store.clone(),
-                worktree_store.clone(),
-                prettier_store.clone(),
-                toolchain_store.clone(),
-                environment,
-                languages.clone(),
-                http_client.clone(),
-                fs.clone(),
-                cx,
-            );
-            lsp_store.shared(SSH_PROJECT_ID, session.clone().into(), cx);
-            lsp_store
-        });
-
         cx.subscribe(&lsp_store, Self::on_lsp_store_event).detach();
 
         cx.subscribe(
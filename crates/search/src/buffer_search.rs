@@ -3,7 +3,8 @@ mod registrar;
 use crate::{
     search_bar::render_nav_button, FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll,
     ReplaceNext, SearchOptions, SelectAllMatches, SelectNextMatch, SelectPrevMatch,
-    ToggleCaseSensitive, ToggleRegex, ToggleReplace, ToggleSelection, ToggleWholeWord,
+    ToggleCaseSensitive, ToggleRegex, ToggleReplace, ToggleSelection, ToggleStructural,
+    ToggleWholeWord,
 };
 use any_vec::AnyVec;
 use collections::HashMap;
@@ -251,6 +252,15 @@ impl Render for BufferSearchBar {
                                 cx.listener(|this, _, cx| this.toggle_regex(&ToggleRegex, cx)),
                             )
                         }))
+                        .children(supported_options.structural.then(|| {
+                            self.render_search_option_button(
+                                SearchOptions::STRUCTURAL,
+                                focus_handle.clone(),
+                                cx.listener(|this, _, cx| {
+                                    this.toggle_structural(&ToggleStructural, cx)
+                                }),
+                            )
+                        }))
                     }),
             )
             .child(
@@ -442,6 +452,9 @@ impl Render for BufferSearchBar {
             .when(self.supported_options().regex, |this| {
                 this.on_action(cx.listener(Self::toggle_regex))
             })
+            .when(self.supported_options().structural, |this| {
+                this.on_action(cx.listener(Self::toggle_structural))
+            })
             .when(self.supported_options().selection, |this| {
                 this.on_action(cx.listener(Self::toggle_selection))
             })
@@ -806,7 +819,7 @@ impl BufferSearchBar {
         search_option: SearchOptions,
         cx: &mut ViewContext<Self>,
     ) {
-        self.search_options.toggle(search_option);
+        self.search_options.toggle_option(search_option);
         self.default_options = self.search_options;
         drop(self.update_matches(false, cx));
         cx.notify();
@@ -976,6 +989,10 @@ impl BufferSearchBar {
         self.toggle_search_option(SearchOptions::REGEX, cx)
     }
 
+    fn toggle_structural(&mut self, _: &ToggleStructural, cx: &mut ViewContext<Self>) {
+        self.toggle_search_option(SearchOptions::STRUCTURAL, cx)
+    }
+
     fn clear_active_searchable_item_matches(&mut self, cx: &mut WindowContext) {
         if let Some(active_searchable_item) = self.active_searchable_item.as_ref() {
             self.active_match_index = None;
@@ -1028,7 +1045,23 @@ impl BufferSearchBar {
                 {
                     search
                 } else {
-                    if self.search_options.contains(SearchOptions::REGEX) {
+                    if self.search_options.contains(SearchOptions::STRUCTURAL) {
+                        match SearchQuery::structural(
+                            query,
+                            false,
+                            Default::default(),
+                            Default::default(),
+                            None,
+                        ) {
+                            Ok(query) => query.with_replacement(self.replacement(cx)),
+                            Err(_) => {
+                                self.query_contains_error = true;
+                                self.clear_active_searchable_item_matches(cx);
+                                cx.notify();
+                                return done_rx;
+                            }
+                        }
+                    } else if self.search_options.contains(SearchOptions::REGEX) {
                         match SearchQuery::regex(
                             query,
                             self.search_options.contains(SearchOptions::WHOLE_WORD),
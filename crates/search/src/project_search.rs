@@ -1,7 +1,8 @@
 use crate::{
-    buffer_search::Deploy, BufferSearchBar, FocusSearch, NextHistoryQuery, PreviousHistoryQuery,
-    ReplaceAll, ReplaceNext, SearchOptions, SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive,
-    ToggleIncludeIgnored, ToggleRegex, ToggleReplace, ToggleWholeWord,
+    buffer_search::Deploy, saved_searches::SavedSearch, BufferSearchBar, FocusSearch,
+    NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext, SearchOptions,
+    SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive, ToggleIncludeIgnored, ToggleRegex,
+    ToggleReplace, ToggleStructural, ToggleWholeWord,
 };
 use collections::{HashMap, HashSet};
 use editor::{
@@ -18,7 +19,7 @@ use gpui::{
     ModelContext, ParentElement, Point, Render, SharedString, Styled, Subscription, Task,
     TextStyle, UpdateGlobal, View, ViewContext, VisualContext, WeakModel, WeakView, WindowContext,
 };
-use language::Buffer;
+use language::{Buffer, ToPoint as _};
 use menu::Confirm;
 use project::{
     search::{SearchInputKind, SearchQuery},
@@ -28,6 +29,7 @@ use project::{
 use settings::Settings;
 use std::{
     any::{Any, TypeId},
+    fmt::Write as _,
     mem,
     ops::{Not, Range},
     path::Path,
@@ -40,6 +42,7 @@ use ui::{
 use util::paths::PathMatcher;
 use workspace::{
     item::{BreadcrumbText, Item, ItemEvent, ItemHandle},
+    notifications::DetachAndPromptErr,
     searchable::{Direction, SearchableItem, SearchableItemHandle},
     DeploySearch, ItemNavHistory, NewSearch, ToolbarItemEvent, ToolbarItemLocation,
     ToolbarItemView, Workspace, WorkspaceId,
@@ -47,7 +50,16 @@ use workspace::{
 
 actions!(
     project_search,
-    [SearchInNew, ToggleFocus, NextField, ToggleFilters]
+    [
+        SearchInNew,
+        ToggleFocus,
+        NextField,
+        ToggleFilters,
+        ToggleSearchWithinResults,
+        ToggleSavedSearches,
+        ExportResults,
+        OpenResultsAsList
+    ]
 );
 
 #[derive(Default)]
@@ -67,6 +79,12 @@ pub fn init(cx: &mut AppContext) {
         register_workspace_action(workspace, move |search_bar, _: &ToggleFilters, cx| {
             search_bar.toggle_filters(cx);
         });
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &ToggleSearchWithinResults, cx| {
+                search_bar.toggle_search_within_results(cx);
+            },
+        );
         register_workspace_action(workspace, move |search_bar, _: &ToggleCaseSensitive, cx| {
             search_bar.toggle_search_option(SearchOptions::CASE_SENSITIVE, cx);
         });
@@ -76,6 +94,9 @@ pub fn init(cx: &mut AppContext) {
         register_workspace_action(workspace, move |search_bar, _: &ToggleRegex, cx| {
             search_bar.toggle_search_option(SearchOptions::REGEX, cx);
         });
+        register_workspace_action(workspace, move |search_bar, _: &ToggleStructural, cx| {
+            search_bar.toggle_search_option(SearchOptions::STRUCTURAL, cx);
+        });
         register_workspace_action(workspace, move |search_bar, action: &ToggleReplace, cx| {
             search_bar.toggle_replace(action, cx)
         });
@@ -96,6 +117,12 @@ pub fn init(cx: &mut AppContext) {
         register_workspace_action_for_present_search(workspace, |workspace, action, cx| {
             ProjectSearchView::search_in_new(workspace, action, cx)
         });
+        register_workspace_action_for_present_search(workspace, |workspace, action, cx| {
+            ProjectSearchView::export_results(workspace, action, cx)
+        });
+        register_workspace_action_for_present_search(workspace, |workspace, action, cx| {
+            ProjectSearchView::open_results_as_list(workspace, action, cx)
+        });
 
         // Both on present and dismissed search, we need to unconditionally handle those actions to focus from the editor.
         workspace.register_action(move |workspace, action: &DeploySearch, cx| {
@@ -160,6 +187,9 @@ pub struct ProjectSearchView {
     filters_enabled: bool,
     replace_enabled: bool,
     included_opened_only: bool,
+    /// The buffers that had results when searching within results was enabled, which
+    /// subsequent searches are restricted to.
+    results_scope: Option<Vec<Model<Buffer>>>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -309,6 +339,42 @@ impl ProjectSearch {
         }));
         cx.notify();
     }
+
+    /// Formats the matches as a quickfix list, with one `path:row:column: line` entry per
+    /// match, where rows and columns start at 1.
+    pub fn quickfix_list(&self, cx: &AppContext) -> String {
+        let snapshot = self.excerpts.read(cx).snapshot(cx);
+        let mut list = String::new();
+        for range in &self.match_ranges {
+            let Some(buffer) = snapshot.buffer_for_excerpt(range.start.excerpt_id) else {
+                continue;
+            };
+            let path = match buffer.file() {
+                Some(file) => match file.as_local() {
+                    Some(file) => file.abs_path(cx),
+                    None => file.full_path(cx),
+                },
+                None => "untitled".into(),
+            };
+            let point = range.start.text_anchor.to_point(buffer);
+            let line = buffer
+                .text_for_range(
+                    language::Point::new(point.row, 0)
+                        ..language::Point::new(point.row, buffer.line_len(point.row)),
+                )
+                .collect::<String>();
+            writeln!(
+                list,
+                "{}:{}:{}: {}",
+                path.display(),
+                point.row + 1,
+                point.column + 1,
+                line.trim_end()
+            )
+            .ok();
+        }
+        list
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -576,7 +642,7 @@ impl ProjectSearchView {
     }
 
     fn toggle_search_option(&mut self, option: SearchOptions, cx: &mut ViewContext<Self>) {
-        self.search_options.toggle(option);
+        self.search_options.toggle_option(option);
         ActiveSettings::update_global(cx, |settings, cx| {
             settings.0.insert(
                 self.model.read(cx).project.downgrade(),
@@ -589,6 +655,133 @@ impl ProjectSearchView {
         self.included_opened_only = !self.included_opened_only;
     }
 
+    fn toggle_search_within_results(&mut self, cx: &mut ViewContext<Self>) {
+        if self.results_scope.take().is_none() && self.has_matches() {
+            self.results_scope = Some(
+                self.model
+                    .read(cx)
+                    .excerpts
+                    .read(cx)
+                    .all_buffers()
+                    .into_iter()
+                    .collect(),
+            );
+        }
+        cx.notify();
+    }
+
+    /// Captures the current query and its options, so that they can be saved under a name.
+    pub fn saved_search(&self, name: String, cx: &AppContext) -> SavedSearch {
+        SavedSearch {
+            name,
+            query: self.query_editor.read(cx).text(cx),
+            regex: self.search_options.contains(SearchOptions::REGEX),
+            structural: self.search_options.contains(SearchOptions::STRUCTURAL),
+            case_sensitive: self.search_options.contains(SearchOptions::CASE_SENSITIVE),
+            whole_word: self.search_options.contains(SearchOptions::WHOLE_WORD),
+            include_ignored: self.search_options.contains(SearchOptions::INCLUDE_IGNORED),
+            files_to_include: self.included_files_editor.read(cx).text(cx),
+            files_to_exclude: self.excluded_files_editor.read(cx).text(cx),
+        }
+    }
+
+    fn apply_saved_search(&mut self, search: &SavedSearch, cx: &mut ViewContext<Self>) {
+        self.set_search_editor(SearchInputKind::Query, &search.query, cx);
+        self.set_search_editor(SearchInputKind::Include, &search.files_to_include, cx);
+        self.set_search_editor(SearchInputKind::Exclude, &search.files_to_exclude, cx);
+        self.search_options = search.search_options();
+        if !search.files_to_include.is_empty()
+            || !search.files_to_exclude.is_empty()
+            || search.include_ignored
+        {
+            self.filters_enabled = true;
+        }
+        self.results_scope = None;
+        self.search(cx);
+    }
+
+    /// Runs a saved search in the active project search, or in a new one if a project search
+    /// isn't active.
+    pub fn run_saved_search(
+        workspace: &mut Workspace,
+        search: &SavedSearch,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let search_view = if let Some(search_view) = workspace
+            .active_item(cx)
+            .and_then(|item| item.downcast::<ProjectSearchView>())
+        {
+            search_view
+        } else {
+            let weak_workspace = cx.view().downgrade();
+            let model = cx.new_model(|cx| ProjectSearch::new(workspace.project().clone(), cx));
+            let search_view =
+                cx.new_view(|cx| ProjectSearchView::new(weak_workspace, model, cx, None));
+            workspace.add_item_to_active_pane(Box::new(search_view.clone()), None, true, cx);
+            search_view
+        };
+        search_view.update(cx, |search_view, cx| {
+            search_view.focus_query_editor(cx);
+            search_view.apply_saved_search(search, cx);
+        });
+    }
+
+    fn export_results(
+        workspace: &mut Workspace,
+        _: &ExportResults,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(search_view) = workspace
+            .active_item(cx)
+            .and_then(|item| item.downcast::<ProjectSearchView>())
+        else {
+            return;
+        };
+        let list = search_view.read(cx).model.read(cx).quickfix_list(cx);
+        let project = workspace.project().clone();
+        let path = workspace.prompt_for_new_path(cx);
+        cx.spawn(|_, mut cx| async move {
+            let Some(path) = path.await.ok().flatten() else {
+                return Ok(());
+            };
+            let buffer = project
+                .update(&mut cx, |project, cx| project.create_buffer(cx))?
+                .await?;
+            buffer.update(&mut cx, |buffer, cx| buffer.set_text(list, cx))?;
+            project
+                .update(&mut cx, |project, cx| {
+                    project.save_buffer_as(buffer, path, cx)
+                })?
+                .await
+        })
+        .detach_and_prompt_err("Failed to export search results", cx, |_, _| None);
+    }
+
+    fn open_results_as_list(
+        workspace: &mut Workspace,
+        _: &OpenResultsAsList,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(search_view) = workspace
+            .active_item(cx)
+            .and_then(|item| item.downcast::<ProjectSearchView>())
+        else {
+            return;
+        };
+        let list = search_view.read(cx).model.read(cx).quickfix_list(cx);
+        let project = workspace.project().clone();
+        let create_buffer = project.update(cx, |project, cx| project.create_buffer(cx));
+        cx.spawn(|workspace, mut cx| async move {
+            let buffer = create_buffer.await?;
+            buffer.update(&mut cx, |buffer, cx| buffer.set_text(list, cx))?;
+            workspace.update(&mut cx, |workspace, cx| {
+                let editor = cx.new_view(|cx| Editor::for_buffer(buffer, Some(project), cx));
+                workspace.add_item_to_active_pane(Box::new(editor), None, true, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn replace_next(&mut self, _: &ReplaceNext, cx: &mut ViewContext<Self>) {
         if self.model.read(cx).match_ranges.is_empty() {
             return;
@@ -773,6 +966,7 @@ impl ProjectSearchView {
             filters_enabled,
             replace_enabled: false,
             included_opened_only: false,
+            results_scope: None,
             _subscriptions: subscriptions,
         };
         this.model_changed(cx);
@@ -926,10 +1120,20 @@ impl ProjectSearchView {
     fn build_search_query(&mut self, cx: &mut ViewContext<Self>) -> Option<SearchQuery> {
         // Do not bail early in this function, as we want to fill out `self.panels_with_errors`.
         let text = self.query_editor.read(cx).text(cx);
-        let open_buffers = if self.included_opened_only {
-            Some(self.open_buffers(cx))
-        } else {
-            None
+        let open_buffers = match (&self.results_scope, self.included_opened_only) {
+            (Some(scope), true) => {
+                let open_buffers = self.open_buffers(cx);
+                Some(
+                    scope
+                        .iter()
+                        .filter(|buffer| open_buffers.contains(*buffer))
+                        .cloned()
+                        .collect(),
+                )
+            }
+            (Some(scope), false) => Some(scope.clone()),
+            (None, true) => Some(self.open_buffers(cx)),
+            (None, false) => None,
         };
         let included_files =
             match Self::parse_path_matches(&self.included_files_editor.read(cx).text(cx)) {
@@ -967,7 +1171,32 @@ impl ProjectSearchView {
                 }
             };
 
-        let query = if self.search_options.contains(SearchOptions::REGEX) {
+        let query = if self.search_options.contains(SearchOptions::STRUCTURAL) {
+            match SearchQuery::structural(
+                text,
+                self.search_options.contains(SearchOptions::INCLUDE_IGNORED),
+                included_files,
+                excluded_files,
+                open_buffers,
+            ) {
+                Ok(query) => {
+                    let should_unmark_error = self.panels_with_errors.remove(&InputPanel::Query);
+                    if should_unmark_error {
+                        cx.notify();
+                    }
+
+                    Some(query)
+                }
+                Err(_e) => {
+                    let should_mark_error = self.panels_with_errors.insert(InputPanel::Query);
+                    if should_mark_error {
+                        cx.notify();
+                    }
+
+                    None
+                }
+            }
+        } else if self.search_options.contains(SearchOptions::REGEX) {
             match SearchQuery::regex(
                 text,
                 self.search_options.contains(SearchOptions::WHOLE_WORD),
@@ -1204,6 +1433,18 @@ impl ProjectSearchView {
                     .key_binding(KeyBinding::for_action_in(&ToggleRegex, &focus_handle, cx))
                     .on_click(|_event, cx| cx.dispatch_action(ToggleRegex.boxed_clone())),
             )
+            .child(
+                Button::new("structural", "Match syntax with patterns like $a.unwrap()")
+                    .icon(IconName::ListTree)
+                    .icon_position(IconPosition::Start)
+                    .icon_size(IconSize::Small)
+                    .key_binding(KeyBinding::for_action_in(
+                        &ToggleStructural,
+                        &focus_handle,
+                        cx,
+                    ))
+                    .on_click(|_event, cx| cx.dispatch_action(ToggleStructural.boxed_clone())),
+            )
             .child(
                 Button::new("match-case", "Match case")
                     .icon(IconName::CaseSensitive)
@@ -1428,6 +1669,32 @@ impl ProjectSearchBar {
         }
     }
 
+    fn toggle_search_within_results(&mut self, cx: &mut ViewContext<Self>) -> bool {
+        if let Some(search_view) = self.active_project_search.as_ref() {
+            search_view.update(cx, |search_view, cx| {
+                search_view.toggle_search_within_results(cx);
+                if search_view.results_scope.is_none()
+                    && search_view.model.read(cx).active_query.is_some()
+                {
+                    search_view.search(cx);
+                }
+            });
+
+            cx.notify();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn is_search_within_results_enabled(&self, cx: &AppContext) -> bool {
+        if let Some(search_view) = self.active_project_search.as_ref() {
+            search_view.read(cx).results_scope.is_some()
+        } else {
+            false
+        }
+    }
+
     fn is_opened_only_enabled(&self, cx: &AppContext) -> bool {
         if let Some(search_view) = self.active_project_search.as_ref() {
             search_view.read(cx).included_opened_only
@@ -1632,11 +1899,54 @@ impl Render for ProjectSearchBar {
                         cx.listener(|this, _, cx| {
                             this.toggle_search_option(SearchOptions::REGEX, cx);
                         }),
+                    ))
+                    .child(SearchOptions::STRUCTURAL.as_button(
+                        self.is_option_enabled(SearchOptions::STRUCTURAL, cx),
+                        focus_handle.clone(),
+                        cx.listener(|this, _, cx| {
+                            this.toggle_search_option(SearchOptions::STRUCTURAL, cx);
+                        }),
                     )),
             );
 
         let mode_column = h_flex()
             .gap_1()
+            .child(
+                IconButton::new("project-search-within-results", IconName::SearchSelection)
+                    .shape(IconButtonShape::Square)
+                    .disabled(!search.has_matches() && search.results_scope.is_none())
+                    .selected(self.is_search_within_results_enabled(cx))
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.toggle_search_within_results(cx);
+                    }))
+                    .tooltip({
+                        let focus_handle = focus_handle.clone();
+                        move |cx| {
+                            Tooltip::for_action_in(
+                                "Search Within Results",
+                                &ToggleSearchWithinResults,
+                                &focus_handle,
+                                cx,
+                            )
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new("project-search-saved-searches", IconName::Star)
+                    .shape(IconButtonShape::Square)
+                    .on_click(|_, cx| cx.dispatch_action(ToggleSavedSearches.boxed_clone()))
+                    .tooltip({
+                        let focus_handle = focus_handle.clone();
+                        move |cx| {
+                            Tooltip::for_action_in(
+                                "Saved Searches",
+                                &ToggleSavedSearches,
+                                &focus_handle,
+                                cx,
+                            )
+                        }
+                    }),
+            )
             .child(
                 IconButton::new("project-search-filter-button", IconName::Filter)
                     .shape(IconButtonShape::Square)
@@ -1758,6 +2068,23 @@ impl Render for ProjectSearchBar {
                         }
                     }),
             )
+            .child(
+                IconButton::new("project-search-export-results", IconName::Download)
+                    .shape(IconButtonShape::Square)
+                    .disabled(search.active_match_index.is_none())
+                    .on_click(|_, cx| cx.dispatch_action(ExportResults.boxed_clone()))
+                    .tooltip({
+                        let focus_handle = focus_handle.clone();
+                        move |cx| {
+                            Tooltip::for_action_in(
+                                "Export Results",
+                                &ExportResults,
+                                &focus_handle,
+                                cx,
+                            )
+                        }
+                    }),
+            )
             .child(
                 div()
                     .id("matches")
@@ -2054,6 +2381,7 @@ pub mod tests {
     use std::{ops::Deref as _, sync::Arc};
 
     use super::*;
+    use crate::saved_searches;
     use editor::{display_map::DisplayRow, DisplayPoint};
     use gpui::{Action, TestAppContext, VisualTestContext, WindowHandle};
     use project::FakeFs;
@@ -3686,6 +4014,205 @@ pub mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_search_within_results(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "one.rs": "const ONE: usize = 1;",
+                "two.rs": "const TWO: usize = one::ONE + one::ONE;",
+                "three.rs": "const THREE: usize = one::ONE + two::TWO;",
+                "four.rs": "const FOUR: usize = one::ONE + three::THREE;",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let window = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let workspace = window.root(cx).unwrap();
+        let search = cx.new_model(|cx| ProjectSearch::new(project.clone(), cx));
+        let search_view = cx.add_window(|cx| {
+            ProjectSearchView::new(workspace.downgrade(), search.clone(), cx, None)
+        });
+
+        perform_search(search_view, "TWO", cx);
+        search_view
+            .update(cx, |search_view, cx| {
+                search_view.toggle_search_within_results(cx);
+                assert!(search_view.results_scope.is_some());
+            })
+            .unwrap();
+
+        perform_search(search_view, "ONE", cx);
+        search_view
+            .update(cx, |search_view, cx| {
+                assert_eq!(
+                    search_view.model.read(cx).quickfix_list(cx),
+                    concat!(
+                        "/dir/three.rs:1:27: const THREE: usize = one::ONE + two::TWO;\n",
+                        "/dir/two.rs:1:25: const TWO: usize = one::ONE + one::ONE;\n",
+                        "/dir/two.rs:1:36: const TWO: usize = one::ONE + one::ONE;\n",
+                    ),
+                    "Only files with results for the previous query should be searched"
+                );
+
+                search_view.toggle_search_within_results(cx);
+                assert!(search_view.results_scope.is_none());
+                search_view.search(cx);
+            })
+            .unwrap();
+        cx.background_executor.run_until_parked();
+
+        search_view
+            .update(cx, |search_view, cx| {
+                assert_eq!(search_view.model.read(cx).match_ranges.len(), 5);
+            })
+            .unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_quickfix_list(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "a.rs": "fn main() {\n    let one = 1;   \n}\n",
+                "b": {
+                    "c.txt": "one\ntwo one\n",
+                },
+                "d.txt": "two\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let window = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let workspace = window.root(cx).unwrap();
+        let search = cx.new_model(|cx| ProjectSearch::new(project.clone(), cx));
+        let search_view = cx.add_window(|cx| {
+            ProjectSearchView::new(workspace.downgrade(), search.clone(), cx, None)
+        });
+
+        search.update(cx, |search, cx| assert_eq!(search.quickfix_list(cx), ""));
+
+        perform_search(search_view, "one", cx);
+        search.update(cx, |search, cx| {
+            assert_eq!(
+                search.quickfix_list(cx),
+                concat!(
+                    "/dir/a.rs:2:9:     let one = 1;\n",
+                    "/dir/b/c.txt:1:1: one\n",
+                    "/dir/b/c.txt:2:5: two one\n",
+                ),
+                "Each match should be listed with its 1-based row and column and its line, \
+                without trailing whitespace"
+            );
+        });
+
+        perform_search(search_view, "three", cx);
+        search.update(cx, |search, cx| assert_eq!(search.quickfix_list(cx), ""));
+    }
+
+    #[gpui::test]
+    async fn test_saved_searches(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "one.rs": "const ONE: usize = 1;",
+                "two.rs": "const TWO: usize = one::ONE + one::ONE;",
+                "three.rs": "const THREE: usize = one::ONE + two::TWO;",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let window = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+
+        let search = SavedSearch {
+            name: "constants".to_string(),
+            query: "const [A-Z]+".to_string(),
+            regex: true,
+            case_sensitive: true,
+            whole_word: false,
+            structural: false,
+            include_ignored: false,
+            files_to_include: "*.rs".to_string(),
+            files_to_exclude: "three.rs".to_string(),
+        };
+        let other_search = SavedSearch {
+            name: "ones".to_string(),
+            query: "ONE".to_string(),
+            whole_word: true,
+            ..Default::default()
+        };
+        cx.update(|cx| saved_searches::save_search(other_search.clone(), cx))
+            .await
+            .unwrap();
+        cx.update(|cx| {
+            saved_searches::save_search(
+                SavedSearch {
+                    query: "const".to_string(),
+                    ..search.clone()
+                },
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+        cx.update(|cx| saved_searches::save_search(search.clone(), cx))
+            .await
+            .unwrap();
+        assert_eq!(
+            saved_searches::saved_searches().unwrap(),
+            [other_search.clone(), search.clone()],
+            "Saving under an existing name should replace that search and keep its position"
+        );
+
+        window
+            .update(cx, |workspace, cx| {
+                ProjectSearchView::run_saved_search(workspace, &search, cx)
+            })
+            .unwrap();
+        cx.background_executor.run_until_parked();
+        let search_view = window
+            .update(cx, |workspace, cx| {
+                workspace
+                    .active_item(cx)
+                    .and_then(|item| item.downcast::<ProjectSearchView>())
+                    .expect("A project search should be opened to run the saved search")
+            })
+            .unwrap();
+        window
+            .update(cx, |_, cx| {
+                search_view.update(cx, |search_view, cx| {
+                    assert_eq!(
+                        search_view.saved_search(search.name.clone(), cx),
+                        search,
+                        "The query, its options and its filters should be restored"
+                    );
+                    assert!(search_view.filters_enabled);
+                    assert_eq!(
+                        search_view.model.read(cx).quickfix_list(cx),
+                        concat!(
+                            "/dir/one.rs:1:1: const ONE: usize = 1;\n",
+                            "/dir/two.rs:1:1: const TWO: usize = one::ONE + one::ONE;\n",
+                        )
+                    );
+                })
+            })
+            .unwrap();
+
+        cx.update(|cx| saved_searches::delete_saved_search(search.name.clone(), cx))
+            .await
+            .unwrap();
+        assert_eq!(saved_searches::saved_searches().unwrap(), [other_search]);
+    }
+
     fn init_test(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let settings = SettingsStore::test(cx);
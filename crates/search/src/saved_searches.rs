@@ -0,0 +1,337 @@
+use crate::{
+    project_search::{ProjectSearchView, ToggleSavedSearches},
+    SearchOptions,
+};
+use anyhow::Result;
+use db::kvp::KEY_VALUE_STORE;
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, ParentElement, Render,
+    Styled, Task, View, ViewContext, VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing, Tooltip};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+const SAVED_SEARCHES_KEY: &str = "saved_project_searches";
+
+/// A project search query and its options, saved under a name.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavedSearch {
+    pub name: String,
+    pub query: String,
+    pub regex: bool,
+    pub structural: bool,
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    pub include_ignored: bool,
+    /// Comma-separated globs of the files to search.
+    pub files_to_include: String,
+    /// Comma-separated globs of the files to skip.
+    pub files_to_exclude: String,
+}
+
+impl SavedSearch {
+    pub fn search_options(&self) -> SearchOptions {
+        let mut options = SearchOptions::NONE;
+        options.set(SearchOptions::REGEX, self.regex);
+        options.set(SearchOptions::STRUCTURAL, self.structural);
+        options.set(SearchOptions::CASE_SENSITIVE, self.case_sensitive);
+        options.set(SearchOptions::WHOLE_WORD, self.whole_word);
+        options.set(SearchOptions::INCLUDE_IGNORED, self.include_ignored);
+        options
+    }
+}
+
+/// Returns the saved searches, in the order they were first saved.
+pub fn saved_searches() -> Result<Vec<SavedSearch>> {
+    match KEY_VALUE_STORE.read_kvp(SAVED_SEARCHES_KEY)? {
+        Some(json) => Ok(serde_json::from_str(&json)?),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Saves a search, replacing any saved search with the same name.
+pub fn save_search(search: SavedSearch, cx: &AppContext) -> Task<Result<()>> {
+    cx.background_executor().spawn(async move {
+        let mut searches = saved_searches()?;
+        if let Some(existing) = searches.iter_mut().find(|s| s.name == search.name) {
+            *existing = search;
+        } else {
+            searches.push(search);
+        }
+        write_saved_searches(&searches).await
+    })
+}
+
+pub fn delete_saved_search(name: String, cx: &AppContext) -> Task<Result<()>> {
+    cx.background_executor().spawn(async move {
+        let mut searches = saved_searches()?;
+        searches.retain(|search| search.name != name);
+        write_saved_searches(&searches).await
+    })
+}
+
+async fn write_saved_searches(searches: &[SavedSearch]) -> Result<()> {
+    KEY_VALUE_STORE
+        .write_kvp(
+            SAVED_SEARCHES_KEY.to_string(),
+            serde_json::to_string(searches)?,
+        )
+        .await
+}
+
+pub(crate) fn init(cx: &mut AppContext) {
+    cx.observe_new_views(SavedSearchSelector::register).detach();
+}
+
+pub struct SavedSearchSelector {
+    picker: View<Picker<SavedSearchSelectorDelegate>>,
+}
+
+impl SavedSearchSelector {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &ToggleSavedSearches, cx| {
+            let weak_workspace = cx.view().downgrade();
+            let search_view = workspace
+                .active_item(cx)
+                .and_then(|item| item.downcast::<ProjectSearchView>())
+                .map(|search_view| search_view.downgrade());
+            workspace.toggle_modal(cx, |cx| {
+                SavedSearchSelector::new(weak_workspace, search_view, cx)
+            });
+        });
+    }
+
+    fn new(
+        workspace: WeakView<Workspace>,
+        search_view: Option<WeakView<ProjectSearchView>>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let searches = saved_searches().log_err().unwrap_or_default();
+        let delegate = SavedSearchSelectorDelegate {
+            selector: cx.view().downgrade(),
+            workspace,
+            search_view,
+            searches,
+            matches: Vec::new(),
+            new_search_name: None,
+            selected_index: 0,
+        };
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl Render for SavedSearchSelector {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for SavedSearchSelector {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for SavedSearchSelector {}
+impl ModalView for SavedSearchSelector {}
+
+pub struct SavedSearchSelectorDelegate {
+    selector: WeakView<SavedSearchSelector>,
+    workspace: WeakView<Workspace>,
+    /// The project search that was active when the selector was opened, whose query can be
+    /// saved.
+    search_view: Option<WeakView<ProjectSearchView>>,
+    searches: Vec<SavedSearch>,
+    matches: Vec<StringMatch>,
+    /// The query, when it isn't the name of an existing search, so that the current search
+    /// can be saved under it.
+    new_search_name: Option<String>,
+    selected_index: usize,
+}
+
+impl SavedSearchSelectorDelegate {
+    fn save_current_search(&self, name: String, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(search_view) = self.search_view.as_ref().and_then(|view| view.upgrade()) else {
+            return;
+        };
+        let search = search_view.read(cx).saved_search(name, cx);
+        save_search(search, cx).detach_and_log_err(cx);
+    }
+
+    fn delete_search(&mut self, ix: usize, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(mat) = self.matches.get(ix) else {
+            return;
+        };
+        let name = self.searches[mat.candidate_id].name.clone();
+        let delete = delete_saved_search(name, cx);
+        cx.spawn(move |this, mut cx| async move {
+            delete.await?;
+            let searches = saved_searches()?;
+            this.update(&mut cx, |picker, cx| {
+                picker.delegate.searches = searches;
+                picker.delegate.selected_index = ix.saturating_sub(1);
+                picker.update_matches(picker.query(cx), cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+impl PickerDelegate for SavedSearchSelectorDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        if self.search_view.is_some() {
+            "Run a saved search, or type a name to save the current one…".into()
+        } else {
+            "Run a saved search…".into()
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len() + self.new_search_name.is_some() as usize
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .searches
+            .iter()
+            .enumerate()
+            .map(|(id, search)| StringMatchCandidate::new(id, search.name.clone()))
+            .collect::<Vec<_>>();
+        cx.spawn(|this, mut cx| async move {
+            let query = query.trim();
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.new_search_name = (delegate.search_view.is_some()
+                    && !query.is_empty()
+                    && !delegate.searches.iter().any(|search| search.name == query))
+                .then(|| query.to_string());
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.match_count().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let search = self.searches[mat.candidate_id].clone();
+            if secondary {
+                self.save_current_search(search.name, cx);
+            } else {
+                self.workspace
+                    .update(cx, |workspace, cx| {
+                        ProjectSearchView::run_saved_search(workspace, &search, cx);
+                    })
+                    .log_err();
+            }
+        } else if let Some(name) = self.new_search_name.clone() {
+            self.save_current_search(name, cx);
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.selector
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        if self.search_view.is_some() {
+            "Type a name to save the current search".into()
+        } else {
+            "No saved searches".into()
+        }
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let item = ListItem::new(ix)
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .selected(selected);
+
+        let Some(mat) = self.matches.get(ix) else {
+            let name = self.new_search_name.as_ref()?;
+            return Some(
+                item.start_slot(Icon::new(IconName::Plus).color(Color::Muted))
+                    .child(Label::new(format!("Save current search as \"{name}\""))),
+            );
+        };
+
+        let search = &self.searches[mat.candidate_id];
+        let delete_button = IconButton::new("delete", IconName::Close)
+            .icon_size(IconSize::Small)
+            .on_click(cx.listener(move |this, _, cx| {
+                cx.stop_propagation();
+                cx.prevent_default();
+                this.delegate.delete_search(ix, cx);
+            }))
+            .tooltip(|cx| Tooltip::text("Delete Saved Search", cx));
+        let item = item.child(
+            h_flex()
+                .gap_2()
+                .child(HighlightedLabel::new(
+                    mat.string.clone(),
+                    mat.positions.clone(),
+                ))
+                .child(
+                    Label::new(search.query.clone())
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+        );
+        Some(if selected {
+            item.end_slot(delete_button)
+        } else {
+            item.end_hover_slot(delete_button)
+        })
+    }
+}
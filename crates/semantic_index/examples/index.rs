@@ -4,7 +4,10 @@ use gpui::App;
 use http_client::HttpClientWithUrl;
 use language::language_settings::AllLanguageSettings;
 use project::Project;
-use semantic_index::{OpenAiEmbeddingModel, OpenAiEmbeddingProvider, SemanticDb};
+use semantic_index::{
+    BertEmbeddingModel, EmbeddingProvider, LocalEmbeddingProvider, OpenAiEmbeddingModel,
+    OpenAiEmbeddingProvider, SemanticDb,
+};
 use settings::SettingsStore;
 use std::{
     path::{Path, PathBuf},
@@ -46,21 +49,36 @@ fn main() {
 
         // let embedding_provider = semantic_index::FakeEmbeddingProvider;
 
-        let api_key = std::env::var("OPENAI_API_KEY").expect("OPENAI_API_KEY not set");
-
-        let embedding_provider = Arc::new(OpenAiEmbeddingProvider::new(
-            http.clone(),
-            OpenAiEmbeddingModel::TextEmbedding3Small,
-            open_ai::OPEN_AI_API_URL.to_string(),
-            api_key,
-        ));
+        // Without an OpenAI API key, fall back to embedding chunks locally with the model in
+        // the `LOCAL_EMBEDDING_MODEL` directory.
+        let (embedding_provider, db_path): (Arc<dyn EmbeddingProvider>, _) =
+            match std::env::var("OPENAI_API_KEY") {
+                Ok(api_key) => (
+                    Arc::new(OpenAiEmbeddingProvider::new(
+                        http.clone(),
+                        OpenAiEmbeddingModel::TextEmbedding3Small,
+                        open_ai::OPEN_AI_API_URL.to_string(),
+                        api_key,
+                    )),
+                    PathBuf::from("/tmp/semantic-index-db.mdb"),
+                ),
+                Err(_) => (
+                    Arc::new(LocalEmbeddingProvider::new(
+                        Arc::new(
+                            BertEmbeddingModel::load(Path::new(
+                                &std::env::var("LOCAL_EMBEDDING_MODEL")
+                                    .expect("neither OPENAI_API_KEY nor LOCAL_EMBEDDING_MODEL set"),
+                            ))
+                            .unwrap(),
+                        ),
+                        cx.background_executor().clone(),
+                    )),
+                    PathBuf::from("/tmp/semantic-index-db.local.mdb"),
+                ),
+            };
 
         cx.spawn(|mut cx| async move {
-            let semantic_index = SemanticDb::new(
-                PathBuf::from("/tmp/semantic-index-db.mdb"),
-                embedding_provider,
-                &mut cx,
-            );
+            let semantic_index = SemanticDb::new(db_path, embedding_provider, &mut cx);
 
             let mut semantic_index = semantic_index.await.unwrap();
 
@@ -0,0 +1,289 @@
+use crate::{Embedding, EmbeddingProvider, TextToEmbed};
+use anyhow::{anyhow, Context as _, Result};
+use candle_core::{Device, Tensor};
+use candle_nn::VarBuilder;
+use candle_transformers::models::bert::{BertModel, Config, DTYPE};
+use futures::{future::BoxFuture, FutureExt};
+use gpui::BackgroundExecutor;
+use std::{path::Path, sync::Arc};
+use tokenizers::{Tokenizer, TruncationParams};
+
+/// A model that computes embeddings in-process, without talking to a service.
+///
+/// Implementations are invoked from background threads and should be CPU-bound.
+pub trait LocalEmbeddingModel: Send + Sync {
+    fn name(&self) -> &str;
+    fn dimensions(&self) -> usize;
+    fn embed(&self, text: &str) -> Result<Vec<f32>>;
+}
+
+/// An [`EmbeddingProvider`] that runs a [`LocalEmbeddingModel`] on the background executor,
+/// so that indexing works offline.
+pub struct LocalEmbeddingProvider {
+    model: Arc<dyn LocalEmbeddingModel>,
+    executor: BackgroundExecutor,
+    batch_size: usize,
+    max_concurrency: usize,
+}
+
+impl LocalEmbeddingProvider {
+    pub const DEFAULT_BATCH_SIZE: usize = 64;
+
+    pub fn new(model: Arc<dyn LocalEmbeddingModel>, executor: BackgroundExecutor) -> Self {
+        let max_concurrency = executor.num_cpus();
+        Self {
+            model,
+            executor,
+            batch_size: Self::DEFAULT_BATCH_SIZE,
+            max_concurrency,
+        }
+    }
+
+    /// Sets how many chunks are handed to the provider at once by the indexer.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Sets how many background threads a single batch is spread across.
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency.max(1);
+        self
+    }
+
+    pub fn model(&self) -> &Arc<dyn LocalEmbeddingModel> {
+        &self.model
+    }
+}
+
+impl EmbeddingProvider for LocalEmbeddingProvider {
+    fn embed<'a>(&'a self, texts: &'a [TextToEmbed<'a>]) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        async move {
+            let mut results = texts.iter().map(|_| None).collect::<Vec<Option<_>>>();
+            let texts_per_worker = texts.len().div_ceil(self.max_concurrency).max(1);
+            let model = &self.model;
+            self.executor
+                .scoped(|scope| {
+                    for (texts, results) in texts
+                        .chunks(texts_per_worker)
+                        .zip(results.chunks_mut(texts_per_worker))
+                    {
+                        scope.spawn(async move {
+                            for (text, result) in texts.iter().zip(results.iter_mut()) {
+                                *result = Some(model.embed(text.text));
+                            }
+                        });
+                    }
+                })
+                .await;
+
+            let dimensions = model.dimensions();
+            results
+                .into_iter()
+                .map(|result| {
+                    let vector = result
+                        .ok_or_else(|| anyhow!("local embedding worker did not complete"))??;
+                    if vector.len() != dimensions {
+                        return Err(anyhow!(
+                            "local embedding model {} returned {} dimensions, expected {}",
+                            model.name(),
+                            vector.len(),
+                            dimensions
+                        ));
+                    }
+                    Ok(normalized_embedding(vector))
+                })
+                .collect()
+        }
+        .boxed()
+    }
+
+    fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+}
+
+fn normalized_embedding(vector: Vec<f32>) -> Embedding {
+    // Texts without any features (e.g. whitespace-only chunks) would otherwise normalize to NaN.
+    if vector.iter().all(|value| *value == 0.) {
+        Embedding(vector)
+    } else {
+        Embedding::new(vector)
+    }
+}
+
+/// A sentence embedding model with the BERT architecture that uses mean pooling, such as
+/// `all-MiniLM-L6-v2`, which is run on the CPU.
+///
+/// Models are loaded from a directory holding the `config.json`, `tokenizer.json` and
+/// `model.safetensors` files that they're published with, so they can be copied onto machines
+/// without network access. A text's embedding is the mean of its token embeddings, and texts
+/// that are longer than the model's context are truncated.
+pub struct BertEmbeddingModel {
+    name: String,
+    model: BertModel,
+    tokenizer: Tokenizer,
+    dimensions: usize,
+}
+
+impl BertEmbeddingModel {
+    pub fn load(dir: &Path) -> Result<Self> {
+        let config_path = dir.join("config.json");
+        let config = std::fs::read_to_string(&config_path)
+            .with_context(|| format!("reading {config_path:?}"))?;
+        let config: Config =
+            serde_json::from_str(&config).with_context(|| format!("parsing {config_path:?}"))?;
+
+        let tokenizer_path = dir.join("tokenizer.json");
+        let mut tokenizer = Tokenizer::from_file(&tokenizer_path)
+            .map_err(|error| anyhow!("loading {tokenizer_path:?}: {error}"))?;
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: config.max_position_embeddings,
+                ..Default::default()
+            }))
+            .map_err(|error| anyhow!("configuring {tokenizer_path:?}: {error}"))?;
+        tokenizer.with_padding(None);
+
+        let weights_path = dir.join("model.safetensors");
+        // SAFETY: The weights are memory-mapped, and aren't expected to change while the model
+        // is loaded.
+        let weights = unsafe {
+            VarBuilder::from_mmaped_safetensors(&[&weights_path], DTYPE, &Device::Cpu)
+                .with_context(|| format!("loading {weights_path:?}"))?
+        };
+        let model = BertModel::load(weights, &config)
+            .with_context(|| format!("loading {weights_path:?}"))?;
+
+        Ok(Self {
+            name: dir.file_name().map_or_else(
+                || "bert".to_string(),
+                |name| name.to_string_lossy().into_owned(),
+            ),
+            model,
+            tokenizer,
+            dimensions: config.hidden_size,
+        })
+    }
+}
+
+impl LocalEmbeddingModel for BertEmbeddingModel {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let encoding = self
+            .tokenizer
+            .encode(text, true)
+            .map_err(|error| anyhow!("tokenizing text for {}: {error}", self.name))?;
+        let token_ids = Tensor::new(encoding.get_ids(), &Device::Cpu)?.unsqueeze(0)?;
+        let token_type_ids = token_ids.zeros_like()?;
+        let token_embeddings = self.model.forward(&token_ids, &token_type_ids, None)?;
+        Ok(token_embeddings.mean(1)?.squeeze(0)?.to_vec1()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candle_nn::VarMap;
+    use gpui::TestAppContext;
+
+    /// Writes a tiny BERT model with random weights and a word-level tokenizer.
+    fn write_test_model(dir: &Path) {
+        let config = serde_json::json!({
+            "vocab_size": 8,
+            "hidden_size": 16,
+            "num_hidden_layers": 1,
+            "num_attention_heads": 2,
+            "intermediate_size": 32,
+            "hidden_act": "gelu",
+            "hidden_dropout_prob": 0.0,
+            "max_position_embeddings": 8,
+            "type_vocab_size": 2,
+            "initializer_range": 0.02,
+            "layer_norm_eps": 1e-12,
+            "pad_token_id": 0,
+            "classifier_dropout": null,
+            "model_type": "bert",
+        });
+        std::fs::write(dir.join("config.json"), config.to_string()).unwrap();
+
+        let tokenizer = serde_json::json!({
+            "version": "1.0",
+            "truncation": null,
+            "padding": null,
+            "added_tokens": [],
+            "normalizer": { "type": "Lowercase" },
+            "pre_tokenizer": { "type": "Whitespace" },
+            "post_processor": null,
+            "decoder": null,
+            "model": {
+                "type": "WordLevel",
+                "vocab": { "[UNK]": 0, "fn": 1, "parse": 2, "range": 3, "struct": 4, "point": 5 },
+                "unk_token": "[UNK]",
+            },
+        });
+        std::fs::write(dir.join("tokenizer.json"), tokenizer.to_string()).unwrap();
+
+        let config = serde_json::from_value::<Config>(config).unwrap();
+        let weights = VarMap::new();
+        BertModel::load(
+            VarBuilder::from_varmap(&weights, DTYPE, &Device::Cpu),
+            &config,
+        )
+        .unwrap();
+        weights.save(dir.join("model.safetensors")).unwrap();
+    }
+
+    #[test]
+    fn test_bert_embedding_model() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let model_dir = temp_dir.path().join("tiny-bert");
+        std::fs::create_dir(&model_dir).unwrap();
+        write_test_model(&model_dir);
+
+        let model = BertEmbeddingModel::load(&model_dir).unwrap();
+        assert_eq!(model.name(), "tiny-bert");
+        assert_eq!(model.dimensions(), 16);
+
+        let embedding = model.embed("fn parse range").unwrap();
+        assert_eq!(embedding.len(), 16);
+        assert_eq!(model.embed("FN Parse Range").unwrap(), embedding);
+        assert_ne!(model.embed("struct point").unwrap(), embedding);
+        // Texts that are longer than the model's context are truncated.
+        assert_eq!(model.embed(&"fn ".repeat(100)).unwrap().len(), 16);
+
+        assert!(BertEmbeddingModel::load(temp_dir.path()).is_err());
+    }
+
+    #[gpui::test]
+    async fn test_local_embedding_provider(cx: &mut TestAppContext) {
+        let temp_dir = tempfile::tempdir().unwrap();
+        write_test_model(temp_dir.path());
+        let model = Arc::new(BertEmbeddingModel::load(temp_dir.path()).unwrap());
+        let provider = LocalEmbeddingProvider::new(model.clone(), cx.executor())
+            .with_batch_size(4)
+            .with_max_concurrency(2);
+        assert_eq!(provider.batch_size(), 4);
+
+        let texts = [
+            TextToEmbed::new("fn parse range"),
+            TextToEmbed::new("struct point"),
+            TextToEmbed::new("parse"),
+        ];
+        let embeddings = provider.embed(&texts).await.unwrap();
+        assert_eq!(embeddings.len(), texts.len());
+        for (text, embedding) in texts.iter().zip(&embeddings) {
+            let expected = normalized_embedding(model.embed(text.text).unwrap());
+            assert_eq!(embedding.0, expected.0, "{:?}", text.text);
+            let norm = embedding.0.iter().map(|value| value * value).sum::<f32>();
+            assert!((norm - 1.).abs() < 1e-4);
+        }
+    }
+}
@@ -2,6 +2,7 @@ use crate::{
     chunking::{self, Chunk},
     embedding::{Embedding, EmbeddingProvider, TextToEmbed},
     indexing::{IndexingEntryHandle, IndexingEntrySet},
+    lexical_index::{LexicalFile, LexicalIndex},
 };
 use anyhow::{anyhow, Context as _, Result};
 use collections::Bound;
@@ -26,6 +27,7 @@ pub struct EmbeddingIndex {
     worktree: Model<Worktree>,
     db_connection: heed::Env,
     db: heed::Database<Str, SerdeBincode<EmbeddedFile>>,
+    lexical_index: LexicalIndex,
     fs: Arc<dyn Fs>,
     language_registry: Arc<LanguageRegistry>,
     embedding_provider: Arc<dyn EmbeddingProvider>,
@@ -33,11 +35,13 @@ pub struct EmbeddingIndex {
 }
 
 impl EmbeddingIndex {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         worktree: Model<Worktree>,
         fs: Arc<dyn Fs>,
         db_connection: heed::Env,
         embedding_db: heed::Database<Str, SerdeBincode<EmbeddedFile>>,
+        lexical_index: LexicalIndex,
         language_registry: Arc<LanguageRegistry>,
         embedding_provider: Arc<dyn EmbeddingProvider>,
         entry_ids_being_indexed: Arc<IndexingEntrySet>,
@@ -47,6 +51,7 @@ impl EmbeddingIndex {
             fs,
             db_connection,
             db: embedding_db,
+            lexical_index,
             language_registry,
             embedding_provider,
             entry_ids_being_indexed,
@@ -57,6 +62,10 @@ impl EmbeddingIndex {
         &self.db
     }
 
+    pub fn lexical_index(&self) -> &LexicalIndex {
+        &self.lexical_index
+    }
+
     pub fn index_entries_changed_on_disk(
         &self,
         cx: &AppContext,
@@ -105,6 +114,7 @@ impl EmbeddingIndex {
         let (deleted_entry_ranges_tx, deleted_entry_ranges_rx) = channel::bounded(128);
         let db_connection = self.db_connection.clone();
         let db = self.db;
+        let lexical_index = self.lexical_index;
         let entries_being_indexed = self.entry_ids_being_indexed.clone();
         let task = cx.background_executor().spawn(async move {
             let txn = db_connection
@@ -145,7 +155,11 @@ impl EmbeddingIndex {
                                         ))
                                         .await?;
                                 }
-                                saved_mtime = db_embedded_file.mtime;
+                                // Files embedded before the lexical index existed are
+                                // re-indexed, so that keyword search covers them too.
+                                if lexical_index.contains(&txn, db_path)? {
+                                    saved_mtime = db_embedded_file.mtime;
+                                }
                                 db_entries.next();
                                 break;
                             }
@@ -321,6 +335,11 @@ impl EmbeddingIndex {
 
                 let mut embeddings = embeddings.into_iter();
                 for chunked_file in chunked_files {
+                    let lexical_file = LexicalFile::new(
+                        chunked_file.path.clone(),
+                        &chunked_file.text,
+                        &chunked_file.chunks,
+                    );
                     let mut embedded_file = EmbeddedFile {
                         path: chunked_file.path,
                         mtime: chunked_file.mtime,
@@ -342,7 +361,7 @@ impl EmbeddingIndex {
 
                     if embedded_all_chunks {
                         embedded_files_tx
-                            .send((embedded_file, chunked_file.handle))
+                            .send((embedded_file, lexical_file, chunked_file.handle))
                             .await?;
                     }
                 }
@@ -359,11 +378,12 @@ impl EmbeddingIndex {
     fn persist_embeddings(
         &self,
         mut deleted_entry_ranges: channel::Receiver<(Bound<String>, Bound<String>)>,
-        mut embedded_files: channel::Receiver<(EmbeddedFile, IndexingEntryHandle)>,
+        mut embedded_files: channel::Receiver<(EmbeddedFile, LexicalFile, IndexingEntryHandle)>,
         cx: &AppContext,
     ) -> Task<Result<()>> {
         let db_connection = self.db_connection.clone();
         let db = self.db;
+        let lexical_index = self.lexical_index;
 
         cx.background_executor().spawn(async move {
             loop {
@@ -376,15 +396,17 @@ impl EmbeddingIndex {
                             let end = deletion_range.1.as_ref().map(|end| end.as_str());
                             log::debug!("deleting embeddings in range {:?}", &(start, end));
                             db.delete_range(&mut txn, &(start, end))?;
+                            lexical_index.remove_range(&mut txn, &(start, end))?;
                             txn.commit()?;
                         }
                     },
                     file = embedded_files.next() => {
-                        if let Some((file, _)) = file {
+                        if let Some((file, lexical_file, _)) = file {
                             let mut txn = db_connection.write_txn()?;
                             log::debug!("saving embedding for file {:?}", file.path);
                             let key = db_key_for_path(&file.path);
                             db.put(&mut txn, &key, &file)?;
+                            lexical_index.insert(&mut txn, &key, &lexical_file)?;
                             txn.commit()?;
                         }
                     },
@@ -452,7 +474,7 @@ pub struct ChunkedFile {
 }
 
 pub struct EmbedFiles {
-    pub files: channel::Receiver<(EmbeddedFile, IndexingEntryHandle)>,
+    pub files: channel::Receiver<(EmbeddedFile, LexicalFile, IndexingEntryHandle)>,
     pub task: Task<Result<()>>,
 }
 
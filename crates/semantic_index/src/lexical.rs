@@ -0,0 +1,309 @@
+use collections::{BTreeMap, HashMap};
+use std::{cmp::Ordering, hash::Hash};
+
+/// Terms longer than this are left out of the index, which keeps its keys within LMDB's
+/// size limit. They still count towards the length of the chunk they appear in.
+pub const MAX_TERM_LEN: usize = 128;
+
+/// Splits text into lowercase search terms.
+///
+/// Identifiers are kept whole and are additionally split on `snake_case` and `camelCase`
+/// boundaries, so that a query for `parse_range` matches `parse_range`, `parseRange` and
+/// prose mentioning "parse" and "range".
+pub fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|identifier| !identifier.trim_matches('_').is_empty())
+        .flat_map(|identifier| {
+            let word = identifier.trim_matches('_').to_lowercase();
+            let subwords = split_identifier(identifier);
+            let subwords = if subwords.len() > 1 {
+                subwords
+            } else {
+                Vec::new()
+            };
+            Some(word).into_iter().chain(subwords)
+        })
+}
+
+/// Counts how often each term occurs in the text.
+///
+/// Returns the counts sorted by term, along with the total number of terms in the text.
+pub fn term_frequencies(text: &str) -> (Vec<(String, u32)>, u32) {
+    let mut frequencies = BTreeMap::<String, u32>::default();
+    let mut len = 0;
+    for term in tokenize(text) {
+        len += 1;
+        if term.len() <= MAX_TERM_LEN {
+            *frequencies.entry(term).or_default() += 1;
+        }
+    }
+    (frequencies.into_iter().collect(), len)
+}
+
+/// Splits an identifier like `parseRange` or `parse_range` into `["parse", "range"]`.
+pub fn split_identifier(identifier: &str) -> Vec<String> {
+    let mut subwords = Vec::new();
+    let mut current = String::new();
+    let mut prev: Option<char> = None;
+    for c in identifier.chars() {
+        if c == '_' {
+            if !current.is_empty() {
+                subwords.push(std::mem::take(&mut current));
+            }
+        } else {
+            let is_boundary = prev.map_or(false, |prev| {
+                (prev.is_lowercase() || prev.is_numeric()) && c.is_uppercase()
+            });
+            if is_boundary && !current.is_empty() {
+                subwords.push(std::mem::take(&mut current));
+            }
+            current.extend(c.to_lowercase());
+        }
+        prev = Some(c);
+    }
+    if !current.is_empty() {
+        subwords.push(current);
+    }
+    subwords
+}
+
+/// The query side of a BM25 ranking over chunks.
+///
+/// Chunks are scored from the [`term_frequencies`] stored in the index:
+/// [`Bm25Query::count_indexed_terms`] picks out the query terms, which are scored against the
+/// corpus-wide [`Bm25Stats`] by [`Bm25Query::score`].
+#[derive(Debug, Clone)]
+pub struct Bm25Query {
+    terms: Vec<String>,
+}
+
+/// How often each query term occurs in a single document.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TermCounts {
+    pub counts: Vec<u32>,
+    pub len: u32,
+}
+
+impl TermCounts {
+    pub fn has_matches(&self) -> bool {
+        self.counts.iter().any(|count| *count > 0)
+    }
+}
+
+/// Corpus-wide statistics required to compute BM25 scores.
+#[derive(Debug, Clone, Default)]
+pub struct Bm25Stats {
+    pub document_count: usize,
+    pub total_len: u64,
+    pub document_frequencies: Vec<u32>,
+}
+
+impl Bm25Stats {
+    pub fn add_document(&mut self, counts: &TermCounts) {
+        self.document_count += 1;
+        self.total_len += counts.len as u64;
+        if self.document_frequencies.len() < counts.counts.len() {
+            self.document_frequencies.resize(counts.counts.len(), 0);
+        }
+        for (frequency, count) in self.document_frequencies.iter_mut().zip(&counts.counts) {
+            if *count > 0 {
+                *frequency += 1;
+            }
+        }
+    }
+
+    pub fn merge(&mut self, other: &Bm25Stats) {
+        self.document_count += other.document_count;
+        self.total_len += other.total_len;
+        if self.document_frequencies.len() < other.document_frequencies.len() {
+            self.document_frequencies
+                .resize(other.document_frequencies.len(), 0);
+        }
+        for (frequency, other) in self
+            .document_frequencies
+            .iter_mut()
+            .zip(&other.document_frequencies)
+        {
+            *frequency += other;
+        }
+    }
+}
+
+impl Bm25Query {
+    const K1: f32 = 1.2;
+    const B: f32 = 0.75;
+
+    pub fn new(query: &str) -> Self {
+        let mut terms = Vec::new();
+        for term in tokenize(query) {
+            if term.len() <= MAX_TERM_LEN && !terms.contains(&term) {
+                terms.push(term);
+            }
+        }
+        Self { terms }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn terms(&self) -> &[String] {
+        &self.terms
+    }
+
+    /// Counts the query terms in a chunk, given the chunk's [`term_frequencies`].
+    pub fn count_indexed_terms(&self, frequencies: &[(String, u32)], len: u32) -> TermCounts {
+        let counts = self
+            .terms
+            .iter()
+            .map(|term| {
+                frequencies
+                    .binary_search_by(|(indexed_term, _)| indexed_term.as_str().cmp(term))
+                    .map_or(0, |ix| frequencies[ix].1)
+            })
+            .collect();
+        TermCounts { counts, len }
+    }
+
+    pub fn score(&self, counts: &TermCounts, stats: &Bm25Stats) -> f32 {
+        if stats.document_count == 0 {
+            return 0.;
+        }
+
+        let document_count = stats.document_count as f32;
+        let average_len = (stats.total_len as f32 / document_count).max(1.);
+        let len_norm = 1. - Self::B + Self::B * counts.len as f32 / average_len;
+        counts
+            .counts
+            .iter()
+            .zip(&stats.document_frequencies)
+            .filter(|(count, _)| **count > 0)
+            .map(|(count, frequency)| {
+                let frequency = *frequency as f32;
+                let idf = ((document_count - frequency + 0.5) / (frequency + 0.5) + 1.).ln();
+                let count = *count as f32;
+                idf * count * (Self::K1 + 1.) / (count + Self::K1 * len_norm)
+            })
+            .sum()
+    }
+}
+
+/// Combines several rankings of the same kind of items using weighted reciprocal rank fusion.
+///
+/// Each ranking must be sorted from best to worst. Items that appear in several rankings
+/// accumulate score from each of them, so the output favors items that rank well under
+/// every method rather than items that score extremely high under a single one.
+pub fn reciprocal_rank_fusion<K: Eq + Hash + Clone>(rankings: &[(f32, Vec<K>)]) -> Vec<(K, f32)> {
+    const K: f32 = 60.;
+
+    let mut scores = HashMap::<K, f32>::default();
+    let mut order = Vec::new();
+    for (weight, ranking) in rankings {
+        for (rank, key) in ranking.iter().enumerate() {
+            let score = scores.entry(key.clone()).or_insert_with(|| {
+                order.push(key.clone());
+                0.
+            });
+            *score += weight / (K + rank as f32 + 1.);
+        }
+    }
+
+    let mut fused = order
+        .into_iter()
+        .map(|key| {
+            let score = scores[&key];
+            (key, score)
+        })
+        .collect::<Vec<_>>();
+    fused.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+    fused
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("fn parseRange(__x: u32) -> parse_range").collect::<Vec<_>>(),
+            vec![
+                "fn",
+                "parserange",
+                "parse",
+                "range",
+                "x",
+                "u32",
+                "parse_range",
+                "parse",
+                "range"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_term_frequencies() {
+        let long_identifier = "x".repeat(MAX_TERM_LEN + 1);
+        let (frequencies, len) = term_frequencies(&format!("parse_range(range) {long_identifier}"));
+        assert_eq!(
+            frequencies,
+            vec![
+                ("parse".to_string(), 1),
+                ("parse_range".to_string(), 1),
+                ("range".to_string(), 2),
+            ]
+        );
+        assert_eq!(len, 5);
+    }
+
+    #[test]
+    fn test_split_identifier() {
+        assert_eq!(split_identifier("parse_range"), vec!["parse", "range"]);
+        assert_eq!(split_identifier("parseRange"), vec!["parse", "range"]);
+        assert_eq!(split_identifier("HTTPClient"), vec!["httpclient"]);
+        assert_eq!(split_identifier("__init__"), vec!["init"]);
+    }
+
+    #[test]
+    fn test_bm25() {
+        let documents = [
+            "fn parse_range(text: &str) -> Range<usize> { todo!() }",
+            "fn parse(text: &str) -> Ast { todo!() }",
+            "the range of the parser is limited",
+            "struct Point { x: f32, y: f32 }",
+        ];
+        let query = Bm25Query::new("parse_range");
+        let counts = documents
+            .iter()
+            .map(|document| {
+                let (frequencies, len) = term_frequencies(document);
+                query.count_indexed_terms(&frequencies, len)
+            })
+            .collect::<Vec<_>>();
+        let mut stats = Bm25Stats::default();
+        for counts in &counts {
+            stats.add_document(counts);
+        }
+        let scores = counts
+            .iter()
+            .map(|counts| query.score(counts, &stats))
+            .collect::<Vec<_>>();
+
+        assert!(scores[0] > scores[1]);
+        assert!(scores[0] > scores[2]);
+        assert!(scores[1] > 0.);
+        assert_eq!(scores[3], 0.);
+    }
+
+    #[test]
+    fn test_reciprocal_rank_fusion() {
+        let fused = reciprocal_rank_fusion(&[(1., vec!["a", "b", "c"]), (1., vec!["c", "b", "d"])]);
+        let keys = fused.iter().map(|(key, _)| *key).collect::<Vec<_>>();
+        assert_eq!(keys[0], "c");
+        assert_eq!(keys[1], "b");
+        assert_eq!(keys.len(), 4);
+
+        let fused = reciprocal_rank_fusion(&[(0., vec!["a"]), (1., vec!["b"])]);
+        assert_eq!(fused[0].0, "b");
+    }
+}
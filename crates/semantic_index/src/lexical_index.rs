@@ -0,0 +1,311 @@
+use crate::{
+    chunking::Chunk,
+    lexical::{self, Bm25Query, Bm25Stats, TermCounts},
+};
+use anyhow::Result;
+use collections::{BTreeMap, BTreeSet, Bound};
+use heed::{
+    types::{DecodeIgnore, SerdeBincode, Str},
+    RoTxn, RwTxn,
+};
+use serde::{Deserialize, Serialize};
+use std::{ops::Range, path::Path, sync::Arc};
+
+const STATS_KEY: &str = "stats";
+
+/// An inverted index of the terms in a worktree's chunks, used for BM25 keyword search.
+///
+/// It lives in the same environment as the embeddings and is updated in the same write
+/// transactions, under the same keys, so both always cover the same files.
+#[derive(Clone, Copy)]
+pub struct LexicalIndex {
+    /// The term frequencies of every chunk in each file.
+    files: heed::Database<Str, SerdeBincode<LexicalFile>>,
+    /// For each term, the files containing it, keyed by the term followed by a NUL and a
+    /// digest of the file's key.
+    postings: heed::Database<Str, SerdeBincode<Posting>>,
+    /// The chunk count and total length of the corpus, under [`STATS_KEY`].
+    stats: heed::Database<Str, SerdeBincode<LexicalStats>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LexicalFile {
+    pub path: Arc<Path>,
+    pub chunks: Vec<LexicalChunk>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LexicalChunk {
+    pub range: Range<usize>,
+    /// The number of terms in the chunk.
+    pub len: u32,
+    /// How often each term occurs in the chunk, sorted by term.
+    pub terms: Vec<(String, u32)>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Posting {
+    file_key: String,
+    /// How many of the file's chunks contain the term.
+    chunk_count: u32,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LexicalStats {
+    chunk_count: u64,
+    total_len: u64,
+}
+
+impl LexicalFile {
+    pub fn new(path: Arc<Path>, text: &str, chunks: &[Chunk]) -> Self {
+        let chunks = chunks
+            .iter()
+            .map(|chunk| {
+                let (terms, len) = lexical::term_frequencies(&text[chunk.range.clone()]);
+                LexicalChunk {
+                    range: chunk.range.clone(),
+                    len,
+                    terms,
+                }
+            })
+            .collect();
+        Self { path, chunks }
+    }
+}
+
+impl LexicalIndex {
+    pub fn create(
+        db_connection: &heed::Env,
+        txn: &mut RwTxn,
+        worktree_abs_path: &Path,
+    ) -> Result<Self> {
+        // Prepend something that wouldn't be found at the beginning of an absolute path, so we
+        // don't get db key namespace conflicts with embeddings, which use the abs path as a key.
+        let worktree_abs_path = worktree_abs_path.to_string_lossy();
+        Ok(Self {
+            files: db_connection
+                .create_database(txn, Some(&format!("lexical-files-{worktree_abs_path}")))?,
+            postings: db_connection
+                .create_database(txn, Some(&format!("lexical-postings-{worktree_abs_path}")))?,
+            stats: db_connection
+                .create_database(txn, Some(&format!("lexical-stats-{worktree_abs_path}")))?,
+        })
+    }
+
+    pub fn contains(&self, txn: &RoTxn, file_key: &str) -> Result<bool> {
+        Ok(self.files.get(txn, file_key)?.is_some())
+    }
+
+    /// Indexes a file, replacing any previous version of it.
+    pub fn insert(&self, txn: &mut RwTxn, file_key: &str, file: &LexicalFile) -> Result<()> {
+        self.remove(txn, file_key)?;
+
+        let mut stats = self.stats.get(txn, STATS_KEY)?.unwrap_or_default();
+        let mut chunk_counts = BTreeMap::<&str, u32>::default();
+        for chunk in &file.chunks {
+            stats.chunk_count += 1;
+            stats.total_len += chunk.len as u64;
+            for (term, _) in &chunk.terms {
+                *chunk_counts.entry(term).or_default() += 1;
+            }
+        }
+
+        for (term, chunk_count) in chunk_counts {
+            let posting = Posting {
+                file_key: file_key.to_string(),
+                chunk_count,
+            };
+            self.postings
+                .put(txn, &posting_key(term, file_key), &posting)?;
+        }
+        self.stats.put(txn, STATS_KEY, &stats)?;
+        self.files.put(txn, file_key, file)?;
+        Ok(())
+    }
+
+    pub fn remove(&self, txn: &mut RwTxn, file_key: &str) -> Result<()> {
+        let Some(file) = self.files.get(txn, file_key)? else {
+            return Ok(());
+        };
+
+        let mut stats = self.stats.get(txn, STATS_KEY)?.unwrap_or_default();
+        let mut terms = BTreeSet::<&str>::default();
+        for chunk in &file.chunks {
+            stats.chunk_count = stats.chunk_count.saturating_sub(1);
+            stats.total_len = stats.total_len.saturating_sub(chunk.len as u64);
+            terms.extend(chunk.terms.iter().map(|(term, _)| term.as_str()));
+        }
+
+        for term in terms {
+            self.postings.delete(txn, &posting_key(term, file_key))?;
+        }
+        self.stats.put(txn, STATS_KEY, &stats)?;
+        self.files.delete(txn, file_key)?;
+        Ok(())
+    }
+
+    pub fn remove_range(&self, txn: &mut RwTxn, range: &(Bound<&str>, Bound<&str>)) -> Result<()> {
+        let file_keys = self
+            .files
+            .remap_data_type::<DecodeIgnore>()
+            .range(txn, range)?
+            .map(|entry| Ok(entry?.0.to_string()))
+            .collect::<Result<Vec<_>>>()?;
+        for file_key in file_keys {
+            self.remove(txn, &file_key)?;
+        }
+        Ok(())
+    }
+
+    /// Finds the chunks containing any of the query's terms, along with the statistics needed
+    /// to score them.
+    ///
+    /// Only the postings of the query's terms and the files they point to are read.
+    pub fn search(
+        &self,
+        txn: &RoTxn,
+        query: &Bm25Query,
+    ) -> Result<(Bm25Stats, Vec<(Arc<Path>, Range<usize>, TermCounts)>)> {
+        let mut file_keys = BTreeSet::default();
+        let mut document_frequencies = Vec::with_capacity(query.terms().len());
+        for term in query.terms() {
+            let mut document_frequency = 0;
+            for entry in self.postings.prefix_iter(txn, &format!("{term}\0"))? {
+                let (_, posting) = entry?;
+                document_frequency += posting.chunk_count;
+                file_keys.insert(posting.file_key);
+            }
+            document_frequencies.push(document_frequency);
+        }
+
+        let mut matches = Vec::new();
+        for file_key in file_keys {
+            let Some(file) = self.files.get(txn, &file_key)? else {
+                continue;
+            };
+            for chunk in file.chunks {
+                let counts = query.count_indexed_terms(&chunk.terms, chunk.len);
+                if counts.has_matches() {
+                    matches.push((file.path.clone(), chunk.range, counts));
+                }
+            }
+        }
+
+        let stats = self.stats.get(txn, STATS_KEY)?.unwrap_or_default();
+        let stats = Bm25Stats {
+            document_count: stats.chunk_count as usize,
+            total_len: stats.total_len,
+            document_frequencies,
+        };
+        Ok((stats, matches))
+    }
+}
+
+/// File keys can be long, so postings are keyed by a digest of them instead, which keeps the
+/// keys within LMDB's size limit.
+fn posting_key(term: &str, file_key: &str) -> String {
+    let digest = blake3::hash(file_key.as_bytes()).to_hex();
+    format!("{term}\0{}", &digest[..32])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, text: &str) -> LexicalFile {
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        for line in text.split_inclusive('\n') {
+            chunks.push(Chunk {
+                range: start..start + line.len(),
+                digest: Default::default(),
+            });
+            start += line.len();
+        }
+        LexicalFile::new(Path::new(path).into(), text, &chunks)
+    }
+
+    fn search(db_connection: &heed::Env, index: &LexicalIndex, query: &str) -> Vec<String> {
+        let query = Bm25Query::new(query);
+        let txn = db_connection.read_txn().unwrap();
+        let (stats, mut matches) = index.search(&txn, &query).unwrap();
+        matches.sort_by(|a, b| {
+            query
+                .score(&b.2, &stats)
+                .total_cmp(&query.score(&a.2, &stats))
+        });
+        matches
+            .into_iter()
+            .map(|(path, range, _)| format!("{}:{range:?}", path.display()))
+            .collect()
+    }
+
+    #[test]
+    fn test_lexical_index() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_connection = unsafe {
+            heed::EnvOpenOptions::new()
+                .map_size(1024 * 1024 * 1024)
+                .max_dbs(8)
+                .open(temp_dir.path())
+                .unwrap()
+        };
+        let mut txn = db_connection.write_txn().unwrap();
+        let index = LexicalIndex::create(&db_connection, &mut txn, Path::new("/root")).unwrap();
+        index
+            .insert(
+                &mut txn,
+                "a.rs",
+                &file("a.rs", "fn parse_range() {}\nfn parse(text: &str) {}\n"),
+            )
+            .unwrap();
+        index
+            .insert(&mut txn, "b.rs", &file("b.rs", "struct Range;\n"))
+            .unwrap();
+        index
+            .insert(&mut txn, "c.rs", &file("c.rs", "struct Point;\n"))
+            .unwrap();
+        txn.commit().unwrap();
+
+        assert_eq!(
+            search(&db_connection, &index, "parse_range"),
+            ["a.rs:0..20", "b.rs:0..14", "a.rs:20..44"]
+        );
+        assert_eq!(
+            search(&db_connection, &index, "missing"),
+            Vec::<String>::new()
+        );
+
+        // Re-indexing a file replaces its old terms.
+        let mut txn = db_connection.write_txn().unwrap();
+        index
+            .insert(&mut txn, "a.rs", &file("a.rs", "fn format() {}\n"))
+            .unwrap();
+        txn.commit().unwrap();
+        assert_eq!(
+            search(&db_connection, &index, "parse_range"),
+            ["b.rs:0..14"]
+        );
+        assert_eq!(search(&db_connection, &index, "format"), ["a.rs:0..15"]);
+
+        let mut txn = db_connection.write_txn().unwrap();
+        index
+            .remove_range(
+                &mut txn,
+                &(Bound::Included("a.rs"), Bound::Included("b.rs")),
+            )
+            .unwrap();
+        txn.commit().unwrap();
+        assert_eq!(
+            search(&db_connection, &index, "range"),
+            Vec::<String>::new()
+        );
+        assert_eq!(search(&db_connection, &index, "point"), ["c.rs:0..14"]);
+
+        let txn = db_connection.read_txn().unwrap();
+        let stats = index.stats.get(&txn, STATS_KEY).unwrap().unwrap();
+        assert_eq!(stats.chunk_count, 1);
+        assert!(!index.contains(&txn, "a.rs").unwrap());
+        assert!(index.contains(&txn, "c.rs").unwrap());
+    }
+}
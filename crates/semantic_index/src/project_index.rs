@@ -1,14 +1,16 @@
 use crate::{
     embedding::{EmbeddingProvider, TextToEmbed},
+    lexical::{self, Bm25Query, Bm25Stats},
     summary_index::FileSummary,
     worktree_index::{WorktreeIndex, WorktreeIndexHandle},
 };
 use anyhow::{anyhow, Context, Result};
-use collections::HashMap;
+use collections::{HashMap, HashSet};
 use fs::Fs;
 use futures::{stream::StreamExt, FutureExt};
 use gpui::{
-    AppContext, Entity, EntityId, EventEmitter, Model, ModelContext, Subscription, Task, WeakModel,
+    AppContext, AsyncAppContext, Entity, EntityId, EventEmitter, Model, ModelContext, Subscription,
+    Task, WeakModel,
 };
 use language::LanguageRegistry;
 use log;
@@ -51,6 +53,70 @@ pub struct WorktreeSearchResult {
     pub score: f32,
 }
 
+/// How many more candidates than requested are retrieved by each ranking before fusion.
+const HYBRID_SEARCH_CANDIDATE_FACTOR: usize = 4;
+
+/// Options for [`ProjectIndex::hybrid_search`].
+#[derive(Clone)]
+pub struct HybridSearchOptions {
+    pub limit: usize,
+    /// How much the embedding similarity ranking contributes to the fused score.
+    pub semantic_weight: f32,
+    /// How much the BM25 keyword ranking contributes to the fused score.
+    pub lexical_weight: f32,
+    /// Re-scores the best fused results before they are truncated to `limit`.
+    pub reranker: Option<Arc<dyn SearchReranker>>,
+}
+
+impl Default for HybridSearchOptions {
+    fn default() -> Self {
+        Self {
+            limit: 5,
+            semantic_weight: 1.,
+            lexical_weight: 1.,
+            reranker: None,
+        }
+    }
+}
+
+pub struct RerankCandidate<'a> {
+    pub path: &'a Path,
+    pub text: &'a str,
+    pub score: f32,
+}
+
+/// Re-scores search candidates once their text has been loaded.
+pub trait SearchReranker: Send + Sync {
+    /// Returns a new score for each candidate, in the same order. Higher is better.
+    fn rerank(&self, query: &str, candidates: &[RerankCandidate]) -> Vec<f32>;
+}
+
+/// Boosts candidates that contain the query verbatim, or that contain every query term.
+pub struct ExactMatchReranker;
+
+impl SearchReranker for ExactMatchReranker {
+    fn rerank(&self, query: &str, candidates: &[RerankCandidate]) -> Vec<f32> {
+        let query_lowercase = query.to_lowercase();
+        let query_terms = lexical::tokenize(query).collect::<HashSet<_>>();
+        candidates
+            .iter()
+            .map(|candidate| {
+                let boost = if candidate.text.to_lowercase().contains(&query_lowercase) {
+                    2.
+                } else {
+                    let terms = lexical::tokenize(candidate.text).collect::<HashSet<_>>();
+                    if query_terms.iter().all(|term| terms.contains(term)) {
+                        1.5
+                    } else {
+                        1.
+                    }
+                };
+                candidate.score * boost
+            })
+            .collect()
+    }
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Status {
     Idle,
@@ -371,6 +437,211 @@ impl ProjectIndex {
         })
     }
 
+    /// Searches the project by fusing embedding similarity with BM25 keyword scoring.
+    ///
+    /// Keyword scoring makes identifier-heavy queries like `parse_range` find exact matches that
+    /// embeddings tend to miss. If the embedding provider is unavailable, results are ranked by
+    /// keywords alone.
+    pub fn hybrid_search(
+        &self,
+        query: String,
+        options: HybridSearchOptions,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<SearchResult>>> {
+        let candidate_limit = options.limit * HYBRID_SEARCH_CANDIDATE_FACTOR;
+        let semantic_search = self.search(vec![query.clone()], candidate_limit, cx);
+        let lexical_search = self.lexical_search(query.clone(), candidate_limit, cx);
+        let fs = self.fs.clone();
+        cx.spawn(|cx| async move {
+            let (semantic_results, lexical_results) =
+                futures::join!(semantic_search, lexical_search);
+            let semantic_results = semantic_results.log_err().unwrap_or_default();
+            let lexical_results = lexical_results?;
+
+            let mut worktrees = HashMap::default();
+            let mut rankings = Vec::new();
+            for (weight, results) in [
+                (options.semantic_weight, semantic_results),
+                (options.lexical_weight, lexical_results),
+            ] {
+                let ranking = results
+                    .into_iter()
+                    .map(|result| {
+                        let worktree_id = result.worktree.entity_id();
+                        worktrees.insert(worktree_id, result.worktree);
+                        (worktree_id, result.path, result.range)
+                    })
+                    .collect::<Vec<_>>();
+                rankings.push((weight, ranking));
+            }
+
+            let mut results = lexical::reciprocal_rank_fusion(&rankings)
+                .into_iter()
+                .filter_map(|((worktree_id, path, range), score)| {
+                    Some(SearchResult {
+                        worktree: worktrees.get(&worktree_id)?.clone(),
+                        path,
+                        range,
+                        score,
+                        query_index: 0,
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            if let Some(reranker) = options.reranker {
+                results.truncate(candidate_limit);
+                Self::rerank(&query, reranker, &mut results, fs, &cx).await?;
+            }
+            results.truncate(options.limit);
+            Ok(results)
+        })
+    }
+
+    fn lexical_search(
+        &self,
+        query: String,
+        limit: usize,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<SearchResult>>> {
+        let query = Arc::new(Bm25Query::new(&query));
+        if query.is_empty() {
+            return Task::ready(Ok(Vec::new()));
+        }
+
+        let mut worktree_search_tasks = Vec::new();
+        for worktree_index in self.worktree_indices.values() {
+            let worktree_index = worktree_index.clone();
+            let query = query.clone();
+            worktree_search_tasks.push(cx.spawn(|cx| async move {
+                let index = match worktree_index {
+                    WorktreeIndexHandle::Loading { index } => {
+                        index.clone().await.map_err(|error| anyhow!(error))?
+                    }
+                    WorktreeIndexHandle::Loaded { index } => index.clone(),
+                };
+
+                index
+                    .read_with(&cx, |index, cx| {
+                        let worktree_id = index.worktree().read(cx).id();
+                        let db_connection = index.db_connection().clone();
+                        let lexical_index = *index.embedding_index().lexical_index();
+                        cx.background_executor().spawn(async move {
+                            let txn = db_connection
+                                .read_txn()
+                                .context("failed to create read transaction")?;
+                            let (stats, matches) = lexical_index.search(&txn, &query)?;
+                            anyhow::Ok((worktree_id, stats, matches))
+                        })
+                    })?
+                    .await
+            }));
+        }
+
+        let project = self.project.clone();
+        cx.spawn(|cx| async move {
+            // Chunks are scored against the statistics of every worktree, so that scores are
+            // comparable across worktrees.
+            let mut stats = Bm25Stats::default();
+            let mut matches = Vec::new();
+            for search_result in futures::future::join_all(worktree_search_tasks).await {
+                let Some((worktree_id, worktree_stats, worktree_matches)) = search_result.log_err()
+                else {
+                    continue;
+                };
+                stats.merge(&worktree_stats);
+                matches.extend(
+                    worktree_matches
+                        .into_iter()
+                        .map(|(path, range, counts)| (worktree_id, path, range, counts)),
+                );
+            }
+
+            let mut results = matches
+                .into_iter()
+                .map(|(worktree_id, path, range, counts)| WorktreeSearchResult {
+                    worktree_id,
+                    path,
+                    range,
+                    query_index: 0,
+                    score: query.score(&counts, &stats),
+                })
+                .collect::<Vec<_>>();
+            results
+                .sort_unstable_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+            results.truncate(limit);
+
+            project.read_with(&cx, |project, cx| {
+                results
+                    .into_iter()
+                    .filter_map(|result| {
+                        Some(SearchResult {
+                            worktree: project.worktree_for_id(result.worktree_id, cx)?,
+                            path: result.path,
+                            range: result.range,
+                            score: result.score,
+                            query_index: result.query_index,
+                        })
+                    })
+                    .collect()
+            })
+        })
+    }
+
+    async fn rerank(
+        query: &str,
+        reranker: Arc<dyn SearchReranker>,
+        results: &mut Vec<SearchResult>,
+        fs: Arc<dyn Fs>,
+        cx: &AsyncAppContext,
+    ) -> Result<()> {
+        let mut abs_paths = Vec::with_capacity(results.len());
+        let mut texts = HashMap::<PathBuf, String>::default();
+        for result in results.iter() {
+            let abs_path = result
+                .worktree
+                .read_with(cx, |worktree, _| worktree.abs_path().join(&result.path))?;
+            if !texts.contains_key(&abs_path) {
+                let text = fs.load(&abs_path).await.unwrap_or_default();
+                texts.insert(abs_path.clone(), text);
+            }
+            abs_paths.push(abs_path);
+        }
+
+        let candidates = results
+            .iter()
+            .zip(abs_paths)
+            .map(|(result, abs_path)| (abs_path, result.range.clone(), result.score))
+            .collect::<Vec<_>>();
+        let query = query.to_string();
+        let scores = cx
+            .background_executor()
+            .spawn(async move {
+                let candidates = candidates
+                    .iter()
+                    .map(|(abs_path, range, score)| RerankCandidate {
+                        path: abs_path,
+                        text: texts[abs_path].get(range.clone()).unwrap_or_default(),
+                        score: *score,
+                    })
+                    .collect::<Vec<_>>();
+                reranker.rerank(&query, &candidates)
+            })
+            .await;
+
+        if scores.len() != results.len() {
+            return Err(anyhow!(
+                "reranker returned {} scores for {} candidates",
+                scores.len(),
+                results.len()
+            ));
+        }
+        for (result, score) in results.iter_mut().zip(scores) {
+            result.score = score;
+        }
+        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+        Ok(())
+    }
+
     #[cfg(test)]
     pub fn path_count(&self, cx: &AppContext) -> Result<u64> {
         let mut result = 0;
@@ -2,8 +2,11 @@ mod chunking;
 mod embedding;
 mod embedding_index;
 mod indexing;
+mod lexical;
+mod lexical_index;
 mod project_index;
 mod project_index_debug_view;
+mod semantic_index_settings;
 mod summary_backlog;
 mod summary_index;
 mod worktree_index;
@@ -24,8 +27,12 @@ use util::ResultExt as _;
 use workspace::Workspace;
 
 pub use embedding::*;
-pub use project_index::{LoadedSearchResult, ProjectIndex, SearchResult, Status};
+pub use project_index::{
+    ExactMatchReranker, HybridSearchOptions, LoadedSearchResult, ProjectIndex, RerankCandidate,
+    SearchReranker, SearchResult, Status,
+};
 pub use project_index_debug_view::ProjectIndexDebugView;
+pub use semantic_index_settings::*;
 pub use summary_index::FileSummary;
 
 pub struct SemanticDb {
@@ -438,6 +445,23 @@ mod tests {
         let content = content[range.clone()].to_owned();
 
         assert!(content.contains("garbage in, garbage out"));
+
+        // Keyword search is answered from the lexical index built alongside the embeddings.
+        let results = cx
+            .update(|cx| {
+                project_index.read(cx).hybrid_search(
+                    "garbage".into(),
+                    HybridSearchOptions {
+                        semantic_weight: 0.,
+                        ..Default::default()
+                    },
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        assert_eq!(results[0].path.to_string_lossy(), "fixture/needle.md");
+        assert_eq!(results[0].range, range);
     }
 
     #[gpui::test]
@@ -498,12 +522,26 @@ mod tests {
 
         let mut embedded_files_rx = embed_files_task.files;
         let mut embedded_files = Vec::new();
-        while let Some((embedded_file, _)) = embedded_files_rx.next().await {
+        let mut lexical_files = Vec::new();
+        while let Some((embedded_file, lexical_file, _)) = embedded_files_rx.next().await {
             embedded_files.push(embedded_file);
+            lexical_files.push(lexical_file);
         }
 
         assert_eq!(embedded_files.len(), 1);
         assert_eq!(embedded_files[0].path.as_ref(), Path::new("test2.md"));
+        assert_eq!(
+            lexical_files[0]
+                .chunks
+                .iter()
+                .map(|chunk| (chunk.range.clone(), chunk.terms.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (0..4, vec![("qrst".to_string(), 1)]),
+                (4..8, vec![("uvwx".to_string(), 1)]),
+                (8..10, vec![("yz".to_string(), 1)]),
+            ],
+        );
         assert_eq!(
             embedded_files[0]
                 .chunks
@@ -0,0 +1,111 @@
+use crate::{
+    BertEmbeddingModel, CloudEmbeddingProvider, EmbeddingProvider, LocalEmbeddingProvider,
+};
+use anyhow::Result;
+use client::Client;
+use gpui::{AppContext, BackgroundExecutor, Task};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+use std::{path::PathBuf, sync::Arc};
+
+/// Settings for the index used by the `/search` and `/project` slash commands.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SemanticIndexSettings {
+    /// Which model computes the embeddings that files are indexed by.
+    ///
+    /// Default: cloud
+    #[serde(default)]
+    pub embedding_provider: EmbeddingProviderSetting,
+    /// Settings for the `local` embedding provider.
+    #[serde(default)]
+    pub local: LocalEmbeddingSettings,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingProviderSetting {
+    /// Zed's hosted embedding model. Requires being signed in.
+    #[default]
+    Cloud,
+    /// A BERT sentence embedding model that's run on this machine's CPU, so indexing works
+    /// offline.
+    Local,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct LocalEmbeddingSettings {
+    /// The directory containing the model's `config.json`, `tokenizer.json` and
+    /// `model.safetensors` files.
+    ///
+    /// Default: the `local-model` directory in Zed's embeddings directory
+    pub model_path: Option<PathBuf>,
+    /// How many chunks are embedded at once.
+    ///
+    /// Default: 64
+    pub batch_size: Option<usize>,
+    /// How many background threads each batch is spread across.
+    ///
+    /// Default: the number of CPUs
+    pub max_concurrency: Option<usize>,
+}
+
+impl LocalEmbeddingSettings {
+    pub fn model_path(&self) -> PathBuf {
+        self.model_path
+            .clone()
+            .unwrap_or_else(|| paths::embeddings_dir().join("local-model"))
+    }
+}
+
+impl SemanticIndexSettings {
+    /// The name of the database directory, which differs per provider and local model because
+    /// their embeddings can't be compared with each other.
+    pub fn db_name(&self) -> String {
+        match self.embedding_provider {
+            EmbeddingProviderSetting::Cloud => "semantic-index-db.0.mdb".to_string(),
+            EmbeddingProviderSetting::Local => {
+                let model_path = self.local.model_path();
+                let digest = blake3::hash(model_path.to_string_lossy().as_bytes()).to_hex();
+                format!("semantic-index-db.local-{}.0.mdb", &digest[..16])
+            }
+        }
+    }
+
+    /// Creates the configured embedding provider. Local models are loaded in the background.
+    pub fn embedding_provider(
+        &self,
+        client: Arc<Client>,
+        executor: BackgroundExecutor,
+    ) -> Task<Result<Arc<dyn EmbeddingProvider>>> {
+        match self.embedding_provider {
+            EmbeddingProviderSetting::Cloud => {
+                Task::ready(Ok(Arc::new(CloudEmbeddingProvider::new(client))))
+            }
+            EmbeddingProviderSetting::Local => {
+                let settings = self.local.clone();
+                executor.clone().spawn(async move {
+                    let model = BertEmbeddingModel::load(&settings.model_path())?;
+                    let mut provider = LocalEmbeddingProvider::new(Arc::new(model), executor);
+                    if let Some(batch_size) = settings.batch_size {
+                        provider = provider.with_batch_size(batch_size);
+                    }
+                    if let Some(max_concurrency) = settings.max_concurrency {
+                        provider = provider.with_max_concurrency(max_concurrency);
+                    }
+                    Ok(Arc::new(provider) as Arc<dyn EmbeddingProvider>)
+                })
+            }
+        }
+    }
+}
+
+impl Settings for SemanticIndexSettings {
+    const KEY: Option<&'static str> = Some("semantic_index");
+
+    type FileContent = Self;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
@@ -3,14 +3,14 @@ use arrayvec::ArrayString;
 use fs::{Fs, MTime};
 use futures::{stream::StreamExt, TryFutureExt};
 use futures_batch::ChunksTimeoutStreamExt;
-use gpui::{AppContext, Model, Task};
+use gpui::{AppContext, Model, SharedString, Task};
 use heed::{
     types::{SerdeBincode, Str},
     RoTxn,
 };
 use language_model::{
     LanguageModelCompletionEvent, LanguageModelId, LanguageModelRegistry, LanguageModelRequest,
-    LanguageModelRequestMessage, Role,
+    LanguageModelRequestMessage, Role, UsageReporter,
 };
 use log;
 use parking_lot::Mutex;
@@ -488,11 +488,14 @@ impl SummaryIndex {
         cx: &AppContext,
     ) -> SummarizeFiles {
         let (summarized_tx, summarized_rx) = channel::bounded(512);
+        let project_name = SharedString::from(self.worktree.read(cx).root_name().to_string());
         let task = cx.spawn(|cx| async move {
             while let Some(file) = unsummarized_files.next().await {
                 log::debug!("Summarizing {:?}", file);
                 let summary = cx
-                    .update(|cx| Self::summarize_code(&file.contents, &file.path, cx))?
+                    .update(|cx| {
+                        Self::summarize_code(&file.contents, &file.path, project_name.clone(), cx)
+                    })?
                     .await
                     .unwrap_or_else(|err| {
                         // Log a warning because we'll continue anyway.
@@ -529,6 +532,7 @@ impl SummaryIndex {
     fn summarize_code(
         code: &str,
         path: &Path,
+        project_name: SharedString,
         cx: &AppContext,
     ) -> impl Future<Output = Result<String>> {
         let start = Instant::now();
@@ -567,17 +571,22 @@ impl SummaryIndex {
 
         let code_len = code.len();
         cx.spawn(|cx| async move {
+            let usage_reporter = UsageReporter::new(model.as_ref(), Some(project_name), &cx);
             let stream = model.stream_completion(request, &cx);
             cx.background_executor()
                 .spawn(async move {
                     let answer: String = stream
                         .await?
-                        .filter_map(|event| async {
-                            if let Ok(LanguageModelCompletionEvent::Text(text)) = event {
-                                Some(text)
-                            } else {
-                                None
-                            }
+                        .filter_map(|event| {
+                            let text = match event {
+                                Ok(LanguageModelCompletionEvent::Text(text)) => Some(text),
+                                Ok(LanguageModelCompletionEvent::UsageUpdate(usage)) => {
+                                    usage_reporter.report(usage);
+                                    None
+                                }
+                                _ => None,
+                            };
+                            async move { text }
                         })
                         .collect()
                         .await;
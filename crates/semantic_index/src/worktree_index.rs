@@ -1,6 +1,7 @@
 use crate::embedding::EmbeddingProvider;
 use crate::embedding_index::EmbeddingIndex;
 use crate::indexing::IndexingEntrySet;
+use crate::lexical_index::LexicalIndex;
 use crate::summary_index::SummaryIndex;
 use anyhow::Result;
 use feature_flags::{AutoCommand, FeatureFlagAppExt};
@@ -63,12 +64,15 @@ impl WorktreeIndex {
                         let embedding_index = {
                             let db_name = worktree_abs_path.to_string_lossy();
                             let db = db_connection.create_database(&mut txn, Some(&db_name))?;
+                            let lexical_index =
+                                LexicalIndex::create(&db_connection, &mut txn, &worktree_abs_path)?;
 
                             EmbeddingIndex::new(
                                 worktree_for_index,
                                 embedding_fs,
                                 db_connection.clone(),
                                 db,
+                                lexical_index,
                                 language_registry,
                                 embedding_provider,
                                 Arc::clone(&entries_being_indexed),
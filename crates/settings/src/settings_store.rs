@@ -10,6 +10,7 @@ use serde::{de::DeserializeOwned, Deserialize as _, Serialize};
 use smallvec::SmallVec;
 use std::{
     any::{type_name, Any, TypeId},
+    borrow::Cow,
     fmt::Debug,
     ops::Range,
     path::{Path, PathBuf},
@@ -172,6 +173,7 @@ pub struct SettingsStore {
     raw_server_settings: Option<serde_json::Value>,
     raw_extension_settings: serde_json::Value,
     raw_local_settings: BTreeMap<(WorktreeId, Arc<Path>), serde_json::Value>,
+    raw_workspace_file_settings: BTreeMap<WorktreeId, serde_json::Value>,
     raw_editorconfig_settings: BTreeMap<(WorktreeId, Arc<Path>), (String, Option<Editorconfig>)>,
     tab_size_callback: Option<(
         TypeId,
@@ -250,6 +252,7 @@ impl SettingsStore {
             raw_server_settings: None,
             raw_extension_settings: serde_json::json!({}),
             raw_local_settings: Default::default(),
+            raw_workspace_file_settings: Default::default(),
             raw_editorconfig_settings: BTreeMap::default(),
             tab_size_callback: Default::default(),
             setting_file_updates_tx,
@@ -683,6 +686,29 @@ impl SettingsStore {
         Ok(())
     }
 
+    /// Add or remove the settings that a workspace file specifies for a worktree.
+    ///
+    /// They apply to the whole worktree, and are overridden by the settings files within it.
+    pub fn set_workspace_file_settings(
+        &mut self,
+        root_id: WorktreeId,
+        settings: Option<serde_json::Value>,
+        cx: &mut AppContext,
+    ) -> std::result::Result<(), InvalidSettingsError> {
+        let changed = match settings {
+            Some(settings) => {
+                self.raw_workspace_file_settings
+                    .insert(root_id, settings.clone())
+                    != Some(settings)
+            }
+            None => self.raw_workspace_file_settings.remove(&root_id).is_some(),
+        };
+        if changed {
+            self.recompute_values(Some((root_id, "".as_ref())), cx)?;
+        }
+        Ok(())
+    }
+
     pub fn set_extension_settings<T: Serialize>(
         &mut self,
         content: T,
@@ -699,6 +725,7 @@ impl SettingsStore {
     pub fn clear_local_settings(&mut self, root_id: WorktreeId, cx: &mut AppContext) -> Result<()> {
         self.raw_local_settings
             .retain(|(worktree_id, _), _| worktree_id != &root_id);
+        self.raw_workspace_file_settings.remove(&root_id);
         self.recompute_values(Some((root_id, "".as_ref())), cx)?;
         Ok(())
     }
@@ -847,6 +874,11 @@ impl SettingsStore {
         changed_local_path: Option<(WorktreeId, &Path)>,
         cx: &mut AppContext,
     ) -> std::result::Result<(), InvalidSettingsError> {
+        let local_settings = Self::local_settings_with_workspace_file_settings(
+            &self.raw_local_settings,
+            &self.raw_workspace_file_settings,
+        );
+
         // Reload the global and local values for every setting.
         let mut project_settings_stack = Vec::<DeserializedSetting>::new();
         let mut paths_stack = Vec::<Option<(WorktreeId, &Path)>>::new();
@@ -911,7 +943,7 @@ impl SettingsStore {
             // Reload the local values for the setting.
             paths_stack.clear();
             project_settings_stack.clear();
-            for ((root_id, directory_path), local_settings) in &self.raw_local_settings {
+            for ((root_id, directory_path), local_settings) in local_settings.iter() {
                 // Build a stack of all of the local values for that setting.
                 while let Some(prev_entry) = paths_stack.last() {
                     if let Some((prev_root_id, prev_path)) = prev_entry {
@@ -970,6 +1002,28 @@ impl SettingsStore {
         Ok(())
     }
 
+    /// Returns the local settings, with the settings from workspace files merged beneath the
+    /// settings at the root of each worktree.
+    fn local_settings_with_workspace_file_settings<'a>(
+        raw_local_settings: &'a BTreeMap<(WorktreeId, Arc<Path>), serde_json::Value>,
+        raw_workspace_file_settings: &BTreeMap<WorktreeId, serde_json::Value>,
+    ) -> Cow<'a, BTreeMap<(WorktreeId, Arc<Path>), serde_json::Value>> {
+        if raw_workspace_file_settings.is_empty() {
+            return Cow::Borrowed(raw_local_settings);
+        }
+
+        let mut local_settings = raw_local_settings.clone();
+        for (root_id, workspace_file_settings) in raw_workspace_file_settings {
+            let mut settings = workspace_file_settings.clone();
+            let root_key = (*root_id, Arc::from(Path::new("")));
+            if let Some(root_settings) = local_settings.remove(&root_key) {
+                merge_non_null_json_value_into(root_settings, &mut settings);
+            }
+            local_settings.insert(root_key, settings);
+        }
+        Cow::Owned(local_settings)
+    }
+
     pub fn editorconfig_properties(
         &self,
         for_worktree: WorktreeId,
@@ -1483,6 +1537,103 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_workspace_file_settings(cx: &mut AppContext) {
+        let mut store = SettingsStore::new(cx);
+        store.register_setting::<UserSettings>(cx);
+        store
+            .set_default_settings(
+                r#"{
+                    "user": {
+                        "name": "John Doe",
+                        "age": 30,
+                        "staff": false
+                    }
+                }"#,
+                cx,
+            )
+            .unwrap();
+
+        let worktree_id = WorktreeId::from_usize(1);
+        store
+            .set_workspace_file_settings(
+                worktree_id,
+                Some(serde_json::json!({ "user": { "age": 40, "staff": true } })),
+                cx,
+            )
+            .unwrap();
+        assert_eq!(
+            store.get::<UserSettings>(Some(SettingsLocation {
+                worktree_id,
+                path: Path::new("something"),
+            })),
+            &UserSettings {
+                name: "John Doe".to_string(),
+                age: 40,
+                staff: true
+            }
+        );
+
+        // Settings files in the worktree take precedence
+        store
+            .set_local_settings(
+                worktree_id,
+                Path::new("").into(),
+                LocalSettingsKind::Settings,
+                Some(r#"{ "user": { "age": 50 } }"#),
+                cx,
+            )
+            .unwrap();
+        store
+            .set_local_settings(
+                worktree_id,
+                Path::new("subdir").into(),
+                LocalSettingsKind::Settings,
+                Some(r#"{ "user": { "name": "Jane Doe" } }"#),
+                cx,
+            )
+            .unwrap();
+        assert_eq!(
+            store.get::<UserSettings>(Some(SettingsLocation {
+                worktree_id,
+                path: Path::new("subdir/something"),
+            })),
+            &UserSettings {
+                name: "Jane Doe".to_string(),
+                age: 50,
+                staff: true
+            }
+        );
+
+        // Other worktrees are unaffected
+        assert_eq!(
+            store.get::<UserSettings>(Some(SettingsLocation {
+                worktree_id: WorktreeId::from_usize(2),
+                path: Path::new("something"),
+            })),
+            &UserSettings {
+                name: "John Doe".to_string(),
+                age: 30,
+                staff: false
+            }
+        );
+
+        store
+            .set_workspace_file_settings(worktree_id, None, cx)
+            .unwrap();
+        assert_eq!(
+            store.get::<UserSettings>(Some(SettingsLocation {
+                worktree_id,
+                path: Path::new("something"),
+            })),
+            &UserSettings {
+                name: "John Doe".to_string(),
+                age: 50,
+                staff: false
+            }
+        );
+    }
+
     #[gpui::test]
     fn test_setting_store_assign_json_before_register(cx: &mut AppContext) {
         let mut store = SettingsStore::new(cx);
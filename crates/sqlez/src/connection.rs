@@ -68,6 +68,11 @@ impl Connection {
         *self.write.borrow()
     }
 
+    /// Whether a transaction has been started on the connection and not yet ended.
+    pub fn in_transaction(&self) -> bool {
+        unsafe { sqlite3_get_autocommit(self.sqlite3) == 0 }
+    }
+
     pub fn backup_main(&self, destination: &Connection) -> Result<()> {
         unsafe {
             let backup = sqlite3_backup_init(
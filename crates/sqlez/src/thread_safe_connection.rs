@@ -2,6 +2,7 @@ use anyhow::Context;
 use collections::HashMap;
 use futures::{channel::oneshot, Future, FutureExt};
 use parking_lot::{Mutex, RwLock};
+use smol::lock::Semaphore;
 use std::{
     marker::PhantomData,
     ops::Deref,
@@ -13,6 +14,7 @@ use thread_local::ThreadLocal;
 use crate::{connection::Connection, domain::Migrator, util::UnboundedSyncSender};
 
 const MIGRATION_RETRIES: usize = 10;
+const DEFAULT_MAX_READERS: usize = 4;
 
 type QueuedWrite = Box<dyn 'static + Send + FnOnce()>;
 type WriteQueue = Box<dyn 'static + Send + Sync + Fn(QueuedWrite)>;
@@ -26,15 +28,34 @@ static QUEUES: LazyLock<RwLock<HashMap<Arc<str>, WriteQueue>>> = LazyLock::new(D
 
 /// Thread safe connection to a given database file or in memory db. This can be cloned, shared, static,
 /// whatever. It derefs to a synchronous connection by thread that is read only. A write capable connection
-/// may be accessed by passing a callback to the `write` function which will queue the callback
+/// may be accessed by passing a callback to the `write` function which will queue the callback. Long
+/// read only queries can be run off the write queue by passing a callback to the `read` function.
 pub struct ThreadSafeConnection<M: Migrator + 'static = ()> {
     uri: Arc<str>,
     persistent: bool,
     connection_initialize_query: Option<&'static str>,
     connections: Arc<ThreadLocal<Connection>>,
+    readers: Arc<ReaderPool>,
     _migrator: PhantomData<*mut M>,
 }
 
+/// Read only connections that run queries concurrently with the write queue. Every read runs in
+/// its own transaction, so with a WAL journal it sees a consistent snapshot of the database even
+/// while writes are being committed.
+struct ReaderPool {
+    idle: Mutex<Vec<Connection>>,
+    permits: Arc<Semaphore>,
+}
+
+impl ReaderPool {
+    fn new(max_readers: usize) -> Self {
+        Self {
+            idle: Mutex::new(Vec::new()),
+            permits: Arc::new(Semaphore::new(max_readers.max(1))),
+        }
+    }
+}
+
 unsafe impl<M: Migrator> Send for ThreadSafeConnection<M> {}
 unsafe impl<M: Migrator> Sync for ThreadSafeConnection<M> {}
 
@@ -60,6 +81,13 @@ impl<M: Migrator> ThreadSafeConnectionBuilder<M> {
         self
     }
 
+    /// Sets the maximum number of `read` callbacks that may run at once, each on its own
+    /// connection. Defaults to 4.
+    pub fn with_max_readers(mut self, max_readers: usize) -> Self {
+        self.connection.readers = Arc::new(ReaderPool::new(max_readers));
+        self
+    }
+
     /// Specifies how the thread safe connection should serialize writes. If provided
     /// the connection will call the write_queue_constructor for each database file in
     /// this process. The constructor is responsible for setting up a background thread or
@@ -134,6 +162,7 @@ impl<M: Migrator> ThreadSafeConnection<M> {
                 persistent,
                 connection_initialize_query: None,
                 connections: Default::default(),
+                readers: Arc::new(ReaderPool::new(DEFAULT_MAX_READERS)),
                 _migrator: PhantomData,
             },
         }
@@ -154,6 +183,15 @@ impl<M: Migrator> ThreadSafeConnection<M> {
     pub fn write<T: 'static + Send + Sync>(
         &self,
         callback: impl 'static + Send + FnOnce(&Connection) -> T,
+    ) -> impl Future<Output = T> {
+        self.queue(move |connection| connection.with_write(callback))
+    }
+
+    /// Queues the callback on the write queue, running it on a connection that is only write
+    /// capable if the callback makes it so.
+    fn queue<T: 'static + Send>(
+        &self,
+        callback: impl 'static + Send + FnOnce(&Connection) -> T,
     ) -> impl Future<Output = T> {
         // Check and invalidate queue and maybe recreate queue
         let queues = QUEUES.read();
@@ -167,13 +205,52 @@ impl<M: Migrator> ThreadSafeConnection<M> {
 
         let thread_safe_connection = (*self).clone();
         write_channel(Box::new(move || {
-            let connection = thread_safe_connection.deref();
-            let result = connection.with_write(|connection| callback(connection));
+            let result = callback(thread_safe_connection.deref());
             sender.send(result).ok();
         }));
         receiver.map(|response| response.expect("Write queue unexpectedly closed"))
     }
 
+    /// Runs the callback on a read only connection in the background, inside a transaction.
+    ///
+    /// For persistent databases the callback runs concurrently with queued writes and with other
+    /// reads, and sees a snapshot of the database as of its first query. In memory databases
+    /// don't support concurrent readers, so for them the callback is run on the write queue, but
+    /// on a connection that still can't write.
+    pub fn read<T: 'static + Send>(
+        &self,
+        callback: impl 'static + Send + FnOnce(&Connection) -> anyhow::Result<T>,
+    ) -> impl Future<Output = anyhow::Result<T>> {
+        let thread_safe_connection = (*self).clone();
+        async move {
+            if !thread_safe_connection.persistent {
+                return thread_safe_connection
+                    .queue(move |connection| with_read_transaction(connection, callback))
+                    .await;
+            }
+
+            let _permit = thread_safe_connection.readers.permits.acquire_arc().await;
+            smol::unblock(move || {
+                let readers = &thread_safe_connection.readers;
+                let connection = readers.idle.lock().pop().unwrap_or_else(|| {
+                    Self::create_connection(
+                        true,
+                        &thread_safe_connection.uri,
+                        thread_safe_connection.connection_initialize_query,
+                    )
+                });
+                let result = with_read_transaction(&connection, callback);
+                // Connections whose transaction couldn't be ended would keep reading a stale
+                // snapshot, so they're closed instead of being reused.
+                if !connection.in_transaction() {
+                    readers.idle.lock().push(connection);
+                }
+                result
+            })
+            .await
+        }
+    }
+
     pub(crate) fn create_connection(
         persistent: bool,
         uri: &str,
@@ -214,6 +291,7 @@ impl ThreadSafeConnection<()> {
             persistent,
             connection_initialize_query,
             connections: Default::default(),
+            readers: Arc::new(ReaderPool::new(DEFAULT_MAX_READERS)),
             _migrator: PhantomData,
         };
 
@@ -229,6 +307,7 @@ impl<M: Migrator> Clone for ThreadSafeConnection<M> {
             persistent: self.persistent,
             connection_initialize_query: self.connection_initialize_query,
             connections: self.connections.clone(),
+            readers: self.readers.clone(),
             _migrator: PhantomData,
         }
     }
@@ -244,6 +323,26 @@ impl<M: Migrator> Deref for ThreadSafeConnection<M> {
     }
 }
 
+/// Runs the callback in a transaction, which is rolled back if either the callback or the commit
+/// fails.
+fn with_read_transaction<T>(
+    connection: &Connection,
+    callback: impl FnOnce(&Connection) -> anyhow::Result<T>,
+) -> anyhow::Result<T> {
+    connection.exec("BEGIN DEFERRED")?()?;
+    let result = callback(connection).and_then(|value| {
+        connection.exec("COMMIT")?()?;
+        Ok(value)
+    });
+    if result.is_err() && connection.in_transaction() {
+        connection
+            .exec("ROLLBACK")
+            .and_then(|mut rollback| rollback())
+            .ok();
+    }
+    result
+}
+
 pub fn background_thread_queue() -> WriteQueueConstructor {
     use std::sync::mpsc::channel;
 
@@ -280,9 +379,14 @@ mod test {
     use indoc::indoc;
     use std::ops::Deref;
 
-    use std::thread;
+    use std::{sync::mpsc, thread};
 
-    use crate::{domain::Domain, thread_safe_connection::ThreadSafeConnection};
+    use crate::{
+        connection::Connection,
+        domain::Domain,
+        thread_safe_connection::{with_read_transaction, ThreadSafeConnection},
+        typed_statements::Query,
+    };
 
     #[test]
     fn many_initialize_and_migrate_queries_at_once() {
@@ -319,6 +423,114 @@ mod test {
         }
     }
 
+    #[test]
+    fn reads_see_a_snapshot_while_writes_commit() {
+        enum TestDomain {}
+        impl Domain for TestDomain {
+            fn name() -> &'static str {
+                "test"
+            }
+            fn migrations() -> &'static [&'static str] {
+                &["CREATE TABLE test(value INTEGER) STRICT;"]
+            }
+        }
+
+        const INSERT: Query<i64> = Query::new("INSERT INTO test(value) VALUES (?)");
+        const COUNT: Query<(), i64> = Query::new("SELECT COUNT(*) FROM test");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db.sqlite");
+        let db = smol::block_on(
+            ThreadSafeConnection::<TestDomain>::builder(path.to_str().unwrap(), true)
+                .with_db_initialization_query("PRAGMA journal_mode=WAL")
+                .build(),
+        )
+        .unwrap();
+        smol::block_on(db.write(|connection| INSERT.exec(connection, 1))).unwrap();
+
+        // Hold a read open while a write commits, and check that it doesn't see the write.
+        let (read_started_tx, read_started_rx) = mpsc::channel();
+        let (write_finished_tx, write_finished_rx) = mpsc::channel();
+        let read = db.read(move |connection| {
+            let before = COUNT.row(connection, ())?;
+            read_started_tx.send(()).unwrap();
+            write_finished_rx.recv().unwrap();
+            let after = COUNT.row(connection, ())?;
+            Ok((before, after))
+        });
+        let read = thread::spawn(move || smol::block_on(read));
+
+        read_started_rx.recv().unwrap();
+        smol::block_on(db.write(|connection| INSERT.exec(connection, 2))).unwrap();
+        write_finished_tx.send(()).unwrap();
+
+        assert_eq!(read.join().unwrap().unwrap(), (Some(1), Some(1)));
+        assert_eq!(
+            smol::block_on(db.read(|connection| COUNT.row(connection, ()))).unwrap(),
+            Some(2)
+        );
+        assert!(smol::block_on(db.read(|connection| INSERT.exec(connection, 3))).is_err());
+    }
+
+    #[test]
+    fn in_memory_reads_cant_write() {
+        enum TestDomain {}
+        impl Domain for TestDomain {
+            fn name() -> &'static str {
+                "test"
+            }
+            fn migrations() -> &'static [&'static str] {
+                &["CREATE TABLE test(value INTEGER) STRICT;"]
+            }
+        }
+
+        const INSERT: Query<i64> = Query::new("INSERT INTO test(value) VALUES (?)");
+        const COUNT: Query<(), i64> = Query::new("SELECT COUNT(*) FROM test");
+
+        let db = smol::block_on(
+            ThreadSafeConnection::<TestDomain>::builder("in_memory_reads_cant_write", false)
+                .build(),
+        )
+        .unwrap();
+        smol::block_on(db.write(|connection| INSERT.exec(connection, 1))).unwrap();
+
+        assert!(smol::block_on(db.read(|connection| INSERT.exec(connection, 2))).is_err());
+        assert_eq!(
+            smol::block_on(db.read(|connection| COUNT.row(connection, ()))).unwrap(),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn read_transactions_are_rolled_back_on_failure() {
+        let connection = Connection::open_memory(Some("read_transactions_are_rolled_back"));
+        connection
+            .exec(indoc! {"
+                PRAGMA foreign_keys=TRUE;
+                CREATE TABLE parents(id INTEGER PRIMARY KEY) STRICT;
+                CREATE TABLE children(
+                    parent_id INTEGER REFERENCES parents(id) DEFERRABLE INITIALLY DEFERRED
+                ) STRICT;
+            "})
+            .unwrap()()
+        .unwrap();
+        const INSERT_CHILD: Query<i64> = Query::new("INSERT INTO children(parent_id) VALUES (?)");
+        const COUNT_CHILDREN: Query<(), i64> = Query::new("SELECT COUNT(*) FROM children");
+
+        let result = with_read_transaction(&connection, |_| -> anyhow::Result<()> {
+            anyhow::bail!("callback failed")
+        });
+        assert!(result.is_err());
+        assert!(!connection.in_transaction());
+
+        // Deferred foreign keys are only checked when the transaction commits.
+        let result =
+            with_read_transaction(&connection, |connection| INSERT_CHILD.exec(connection, 1));
+        assert!(result.unwrap_err().to_string().contains("FOREIGN KEY"));
+        assert!(!connection.in_transaction());
+        assert_eq!(COUNT_CHILDREN.row(&connection, ()).unwrap(), Some(0));
+    }
+
     #[test]
     #[should_panic]
     fn wild_zed_lost_failure() {
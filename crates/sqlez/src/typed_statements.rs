@@ -1,3 +1,5 @@
+use std::marker::PhantomData;
+
 use anyhow::{Context, Result};
 
 use crate::{
@@ -94,3 +96,57 @@ impl Connection {
         })
     }
 }
+
+/// A statement whose bindings and result columns are part of its type, so that it can be declared
+/// once as a constant and run against any connection, such as inside a `read` or `write` callback
+/// on a `ThreadSafeConnection`.
+///
+/// ```ignore
+/// const SELECT_VALUE: Query<&str, String> = Query::new("SELECT value FROM kv_store WHERE key = ?");
+///
+/// let value = db.read(|connection| SELECT_VALUE.row(connection, "key")).await?;
+/// ```
+pub struct Query<B, C = ()> {
+    sql: &'static str,
+    _types: PhantomData<fn(B) -> C>,
+}
+
+impl<B, C> Clone for Query<B, C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<B, C> Copy for Query<B, C> {}
+
+impl<B: Bind, C: Column> Query<B, C> {
+    pub const fn new(sql: &'static str) -> Self {
+        Self {
+            sql,
+            _types: PhantomData,
+        }
+    }
+
+    pub fn sql(&self) -> &'static str {
+        self.sql
+    }
+
+    /// Runs the statement with the given bindings, ignoring any rows it returns.
+    pub fn exec(&self, connection: &Connection, bindings: B) -> Result<()> {
+        connection.exec_bound::<B>(self.sql)?(bindings)
+            .with_context(|| format!("Error executing query: {}", self.sql))
+    }
+
+    /// Runs the statement with the given bindings and returns all of its rows.
+    pub fn rows(&self, connection: &Connection, bindings: B) -> Result<Vec<C>> {
+        connection.select_bound::<B, C>(self.sql)?(bindings)
+            .with_context(|| format!("Error selecting rows for query: {}", self.sql))
+    }
+
+    /// Runs the statement with the given bindings and returns its row, if any. Errors if more
+    /// than one row is returned.
+    pub fn row(&self, connection: &Connection, bindings: B) -> Result<Option<C>> {
+        connection.select_row_bound::<B, C>(self.sql)?(bindings)
+            .with_context(|| format!("Error selecting row for query: {}", self.sql))
+    }
+}
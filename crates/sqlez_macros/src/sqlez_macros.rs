@@ -31,6 +31,77 @@ pub fn sql(tokens: TokenStream) -> TokenStream {
     }
 }
 
+/// Checks a domain's migrations at compile time by running them, in order, against an empty in
+/// memory database. Each migration is written as an `sql!` invocation:
+///
+/// ```ignore
+/// migrations![
+///     sql!(CREATE TABLE kv_store(key TEXT PRIMARY KEY, value TEXT NOT NULL) STRICT;),
+///     sql!(ALTER TABLE kv_store ADD COLUMN timestamp INTEGER;),
+/// ]
+/// ```
+///
+/// Expands to a `&'static [&'static str]` of the formatted migrations. Migrations are checked in
+/// isolation, so they can't depend on tables created by other domains. Unlike `sql!`'s syntax
+/// check, this runs on every platform.
+#[proc_macro]
+pub fn migrations(tokens: TokenStream) -> TokenStream {
+    let mut migrations = Vec::new();
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        let migration = match (token, tokens.next(), tokens.next()) {
+            (
+                TokenTree::Ident(ident),
+                Some(TokenTree::Punct(bang)),
+                Some(TokenTree::Group(group)),
+            ) if ident.to_string() == "sql" && bang.as_char() == '!' => group,
+            (token, _, _) => {
+                return TokenStream::from(
+                    Error::new(
+                        token.span().into(),
+                        "Expected a comma separated list of sql!(...) migrations",
+                    )
+                    .into_compile_error(),
+                )
+            }
+        };
+        if let Some(TokenTree::Punct(comma)) = tokens.peek() {
+            if comma.as_char() == ',' {
+                tokens.next();
+            }
+        }
+
+        let (_, sql) = make_sql(migration.stream());
+        // Migrations take static strings, which is fine to leak for the lifetime of the macro.
+        let formatted_sql: &'static str = String::leak(sqlformat::format(
+            &sql,
+            &sqlformat::QueryParams::None,
+            Default::default(),
+        ));
+        migrations.push((migration.span(), formatted_sql));
+    }
+
+    let connection = sqlez::connection::Connection::open_memory(None);
+    for (ix, (span, formatted_sql)) in migrations.iter().enumerate() {
+        let applied = migrations[..=ix]
+            .iter()
+            .map(|(_, formatted_sql)| *formatted_sql)
+            .collect::<Vec<_>>();
+        if let Err(error) = connection.migrate("compile_time_check", &applied) {
+            let error_text =
+                format!("Migration {ix} failed: {error:?}\nFor Query: {formatted_sql}");
+            return TokenStream::from(Error::new((*span).into(), error_text).into_compile_error());
+        }
+    }
+
+    let migrations = migrations
+        .iter()
+        .map(|(_, formatted_sql)| format!("r#\"{}\"#", formatted_sql))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("&[{}]", migrations).parse().unwrap()
+}
+
 fn create_error(
     spans: Vec<(usize, Span)>,
     error_offset: usize,
@@ -0,0 +1,125 @@
+use std::{cmp::Ordering, sync::Arc};
+
+use crate::{KeyedItem, SumTree};
+
+/// A change between two versions of a [`SumTree`], as yielded by [`SumTree::diff`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum Change<'a, T> {
+    /// An item that is only present in the old tree.
+    Removed(&'a T),
+    /// An item that is only present in the new tree.
+    Added(&'a T),
+    /// An item whose key is present in both trees, but whose value differs.
+    Updated { old: &'a T, new: &'a T },
+}
+
+/// An iterator over the changes between two versions of a [`SumTree`], in key order.
+///
+/// Subtrees that are shared between the two versions are skipped without being visited, so
+/// diffing a tree against an edited copy of itself is proportional to the size of the edits
+/// rather than the size of the tree.
+pub struct Diff<'a, T: KeyedItem> {
+    old_stack: Vec<Frame<'a, T>>,
+    new_stack: Vec<Frame<'a, T>>,
+}
+
+enum Frame<'a, T: KeyedItem> {
+    Tree(&'a SumTree<T>),
+    Item(&'a T),
+}
+
+impl<'a, T: KeyedItem> Frame<'a, T> {
+    fn height(&self) -> i16 {
+        match self {
+            Frame::Tree(tree) => tree.0.height() as i16,
+            Frame::Item(_) => -1,
+        }
+    }
+}
+
+impl<'a, T: KeyedItem> Diff<'a, T> {
+    pub(crate) fn new(old: &'a SumTree<T>, new: &'a SumTree<T>) -> Self {
+        Self {
+            old_stack: vec![Frame::Tree(old)],
+            new_stack: vec![Frame::Tree(new)],
+        }
+    }
+}
+
+/// Replaces the tree at the top of the stack with its children.
+fn expand<'a, T: KeyedItem>(stack: &mut Vec<Frame<'a, T>>) {
+    if let Some(Frame::Tree(tree)) = stack.pop() {
+        if tree.0.is_leaf() {
+            stack.extend(tree.0.items().iter().rev().map(Frame::Item));
+        } else {
+            stack.extend(tree.0.child_trees().iter().rev().map(Frame::Tree));
+        }
+    }
+}
+
+/// Expands the top of the stack until it is an item, and pops it.
+fn pop_item<'a, T: KeyedItem>(stack: &mut Vec<Frame<'a, T>>) -> Option<&'a T> {
+    loop {
+        match stack.last()? {
+            Frame::Tree(_) => expand(stack),
+            Frame::Item(item) => {
+                let item = *item;
+                stack.pop();
+                return Some(item);
+            }
+        }
+    }
+}
+
+impl<'a, T: KeyedItem + PartialEq> Iterator for Diff<'a, T> {
+    type Item = Change<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (old, new) = match (self.old_stack.last(), self.new_stack.last()) {
+                (None, None) => return None,
+                (Some(_), None) => return pop_item(&mut self.old_stack).map(Change::Removed),
+                (None, Some(_)) => return pop_item(&mut self.new_stack).map(Change::Added),
+                (Some(old), Some(new)) => (old, new),
+            };
+
+            match (old, new) {
+                (Frame::Tree(old), Frame::Tree(new)) if Arc::ptr_eq(&old.0, &new.0) => {
+                    self.old_stack.pop();
+                    self.new_stack.pop();
+                }
+                (Frame::Item(old), Frame::Item(new)) => {
+                    let (old, new) = (*old, *new);
+                    match old.key().cmp(&new.key()) {
+                        Ordering::Less => {
+                            self.old_stack.pop();
+                            return Some(Change::Removed(old));
+                        }
+                        Ordering::Greater => {
+                            self.new_stack.pop();
+                            return Some(Change::Added(new));
+                        }
+                        Ordering::Equal => {
+                            self.old_stack.pop();
+                            self.new_stack.pop();
+                            if old != new {
+                                return Some(Change::Updated { old, new });
+                            }
+                        }
+                    }
+                }
+                (old, new) => {
+                    // Subtrees can only be shared at the same height, so descend into the
+                    // taller side until both sides line up, or into both when they're level.
+                    let (old_height, new_height) = (old.height(), new.height());
+                    if old_height >= new_height {
+                        expand(&mut self.old_stack);
+                    }
+                    if new_height >= old_height {
+                        expand(&mut self.new_stack);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,8 +1,10 @@
 mod cursor;
+mod diff;
 mod tree_map;
 
 use arrayvec::ArrayVec;
 pub use cursor::{Cursor, FilterCursor, Iter};
+pub use diff::{Change, Diff};
 use rayon::prelude::*;
 use std::marker::PhantomData;
 use std::mem;
@@ -753,6 +755,12 @@ impl<T: KeyedItem> SumTree<T> {
         removed
     }
 
+    /// Returns the changes between two versions of a tree in key order, skipping any subtrees
+    /// that they share.
+    pub fn diff<'a>(old: &'a Self, new: &'a Self) -> Diff<'a, T> {
+        Diff::new(old, new)
+    }
+
     pub fn get(&self, key: &T::Key, cx: &<T::Summary as Summary>::Context) -> Option<&T> {
         let mut cursor = self.cursor::<T::Key>(cx);
         if cursor.seek(key, Bias::Left, cx) {
@@ -903,6 +911,7 @@ mod tests {
     use super::*;
     use rand::{distributions, prelude::*};
     use std::cmp;
+    use std::collections::{BTreeMap, BTreeSet};
 
     #[ctor::ctor]
     fn init_logger() {
@@ -1314,6 +1323,163 @@ mod tests {
         assert_eq!(tree.get(&4, &()), Some(&4));
     }
 
+    #[test]
+    fn test_random_diff() {
+        let mut starting_seed = 0;
+        if let Ok(value) = std::env::var("SEED") {
+            starting_seed = value.parse().expect("invalid SEED variable");
+        }
+        let mut num_iterations = 100;
+        if let Ok(value) = std::env::var("ITERATIONS") {
+            num_iterations = value.parse().expect("invalid ITERATIONS variable");
+        }
+
+        for seed in starting_seed..(starting_seed + num_iterations) {
+            eprintln!("seed = {}", seed);
+            let mut rng = StdRng::seed_from_u64(seed);
+
+            // Edits that insert the same key more than once in a single batch insert it
+            // repeatedly, so generate distinct keys to keep each tree a set.
+            let mut old_tree = SumTree::<u8>::default();
+            let count = rng.gen_range(0..100);
+            let keys = (0..count).map(|_| rng.gen()).collect::<BTreeSet<u8>>();
+            old_tree.edit(keys.into_iter().map(Edit::Insert).collect(), &());
+
+            let mut new_tree = old_tree.clone();
+            let count = rng.gen_range(0..10);
+            let edits = (0..count)
+                .map(|_| {
+                    let key = rng.gen();
+                    let edit = if rng.gen() {
+                        Edit::Insert(key)
+                    } else {
+                        Edit::Remove(key)
+                    };
+                    (key, edit)
+                })
+                .collect::<BTreeMap<u8, _>>();
+            new_tree.edit(edits.into_values().collect(), &());
+
+            let old_items = old_tree.items(&());
+            let new_items = new_tree.items(&());
+            let expected_changes = (0..=u8::MAX)
+                .filter_map(
+                    |key| match (old_items.contains(&key), new_items.contains(&key)) {
+                        (true, false) => Some((key, false)),
+                        (false, true) => Some((key, true)),
+                        _ => None,
+                    },
+                )
+                .collect::<Vec<_>>();
+            let changes = SumTree::diff(&old_tree, &new_tree)
+                .map(|change| match change {
+                    Change::Removed(item) => (*item, false),
+                    Change::Added(item) => (*item, true),
+                    Change::Updated { .. } => panic!("u8 items can't be updated"),
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(changes, expected_changes);
+            assert_eq!(SumTree::diff(&new_tree, &new_tree).count(), 0);
+        }
+    }
+
+    #[test]
+    fn test_random_diff_with_updates() {
+        let mut starting_seed = 0;
+        if let Ok(value) = std::env::var("SEED") {
+            starting_seed = value.parse().expect("invalid SEED variable");
+        }
+        let mut num_iterations = 100;
+        if let Ok(value) = std::env::var("ITERATIONS") {
+            num_iterations = value.parse().expect("invalid ITERATIONS variable");
+        }
+
+        for seed in starting_seed..(starting_seed + num_iterations) {
+            eprintln!("seed = {}", seed);
+            let mut rng = StdRng::seed_from_u64(seed);
+
+            let count = rng.gen_range(0..=u8::MAX as usize);
+            let old_entries = (0..count)
+                .map(|_| (rng.gen(), rng.gen()))
+                .collect::<BTreeMap<u8, u8>>();
+            let mut old_tree = SumTree::<Entry>::default();
+            old_tree.edit(
+                old_entries
+                    .iter()
+                    .map(|(&key, &value)| Edit::Insert(Entry { key, value }))
+                    .collect(),
+                &(),
+            );
+
+            // Inserting an existing key replaces its entry, which updates its value unless the
+            // same value is chosen again.
+            let mut new_tree = old_tree.clone();
+            let mut new_entries = old_entries.clone();
+            let edit_count = rng.gen_range(0..10);
+            let edits = (0..edit_count)
+                .map(|_| {
+                    let key = rng.gen();
+                    let edit = if rng.gen_bool(0.7) {
+                        Edit::Insert(Entry {
+                            key,
+                            value: rng.gen_range(0..4),
+                        })
+                    } else {
+                        Edit::Remove(key)
+                    };
+                    (key, edit)
+                })
+                .collect::<BTreeMap<u8, _>>();
+            for edit in edits.values() {
+                match edit {
+                    Edit::Insert(entry) => new_entries.insert(entry.key, entry.value),
+                    Edit::Remove(key) => new_entries.remove(key),
+                };
+            }
+            new_tree.edit(edits.into_values().collect(), &());
+
+            let expected_changes = (0..=u8::MAX)
+                .filter_map(|key| {
+                    let change = (old_entries.get(&key), new_entries.get(&key));
+                    match change {
+                        (Some(_), None) | (None, Some(_)) => Some((key, change)),
+                        (Some(old), Some(new)) if old != new => Some((key, change)),
+                        _ => None,
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            KEY_CALLS.set(0);
+            let changes = SumTree::diff(&old_tree, &new_tree)
+                .map(|change| match change {
+                    Change::Removed(old) => (old.key, (Some(&old.value), None)),
+                    Change::Added(new) => (new.key, (None, Some(&new.value))),
+                    Change::Updated { old, new } => {
+                        assert_eq!(old.key, new.key);
+                        (old.key, (Some(&old.value), Some(&new.value)))
+                    }
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(changes, expected_changes);
+
+            // Each edit rebuilds the nodes on its path from the root, and can shift the
+            // boundaries of a neighboring node on either side, so at most three nodes per level
+            // differ between the trees. The items in every other subtree are shared by both and
+            // shouldn't be compared.
+            let compared_items = KEY_CALLS.get() / 2;
+            let height = old_tree.0.height().max(new_tree.0.height()) as usize;
+            let max_compared_items = edit_count * (height + 1) * 3 * 2 * TREE_BASE;
+            assert!(
+                compared_items <= max_compared_items,
+                "compared {compared_items} items for {edit_count} edits"
+            );
+
+            KEY_CALLS.set(0);
+            assert_eq!(SumTree::diff(&new_tree, &new_tree).count(), 0);
+            assert_eq!(KEY_CALLS.get(), 0);
+        }
+    }
+
     #[test]
     fn test_from_iter() {
         assert_eq!(
@@ -1335,6 +1501,39 @@ mod tests {
         assert_eq!(SumTree::from_iter(iterator, &()).items(&()), vec![1]);
     }
 
+    thread_local! {
+        /// How many times [`Entry::key`] has been called on this thread.
+        static KEY_CALLS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    /// An item whose value can change while its key stays the same.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    struct Entry {
+        key: u8,
+        value: u8,
+    }
+
+    impl Item for Entry {
+        type Summary = IntegersSummary;
+
+        fn summary(&self, cx: &()) -> Self::Summary {
+            IntegersSummary {
+                sum: self.value as usize,
+                contains_even: (self.value & 1) == 0,
+                ..self.key.summary(cx)
+            }
+        }
+    }
+
+    impl KeyedItem for Entry {
+        type Key = u8;
+
+        fn key(&self) -> Self::Key {
+            KEY_CALLS.set(KEY_CALLS.get() + 1);
+            self.key
+        }
+    }
+
     #[derive(Clone, Default, Debug)]
     pub struct IntegersSummary {
         count: usize,
@@ -315,7 +315,9 @@ impl PickerDelegate for TasksModalDelegate {
         let icon = match source_kind {
             TaskSourceKind::UserInput => Some(Icon::new(IconName::Terminal)),
             TaskSourceKind::AbsPath { .. } => Some(Icon::new(IconName::Settings)),
-            TaskSourceKind::Worktree { .. } => Some(Icon::new(IconName::FileTree)),
+            TaskSourceKind::Worktree { .. } | TaskSourceKind::Provider { .. } => {
+                Some(Icon::new(IconName::FileTree))
+            }
             TaskSourceKind::Language { name } => file_icons::FileIcons::get(cx)
                 .get_type_icon(&name.to_lowercase())
                 .map(Icon::from_path),
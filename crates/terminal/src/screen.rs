@@ -0,0 +1,199 @@
+use alacritty_terminal::{
+    grid::Dimensions,
+    index::{Column, Line, Point as AlacPoint},
+    term::{cell::Cell, TermMode},
+    vte::ansi::{Handler, NamedPrivateMode, PrivateMode},
+};
+use gpui::ModelContext;
+
+use crate::{Event, Terminal, TerminalConnection};
+
+/// The lines on a terminal's screen and its cursor, without any scrollback history.
+///
+/// This is what the host of a project sends to collaborators when sharing a terminal.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TerminalScreen {
+    pub columns: usize,
+    pub lines: Vec<Vec<Cell>>,
+    /// The position of the cursor, if it's visible.
+    pub cursor: Option<AlacPoint>,
+}
+
+impl TerminalScreen {
+    /// Returns the indices of the lines that differ from the ones in `old`.
+    pub fn changed_lines<'a>(&'a self, old: &'a Self) -> impl 'a + Iterator<Item = usize> {
+        let resized = self.columns != old.columns || self.lines.len() != old.lines.len();
+        self.lines
+            .iter()
+            .zip(old.lines.iter().map(Some).chain(std::iter::repeat(None)))
+            .enumerate()
+            .filter_map(move |(ix, (line, old_line))| {
+                (resized || old_line != Some(line)).then_some(ix)
+            })
+    }
+}
+
+struct ScreenDimensions {
+    columns: usize,
+    lines: usize,
+}
+
+impl Dimensions for ScreenDimensions {
+    fn total_lines(&self) -> usize {
+        self.lines
+    }
+
+    fn screen_lines(&self) -> usize {
+        self.lines
+    }
+
+    fn columns(&self) -> usize {
+        self.columns
+    }
+}
+
+impl Terminal {
+    /// Whether this terminal mirrors one shared by the host of a remote project.
+    pub fn is_remote(&self) -> bool {
+        matches!(self.connection, TerminalConnection::Remote { .. })
+    }
+
+    /// Returns what's currently on the terminal's screen, ignoring where it's scrolled to.
+    pub fn screen(&self) -> TerminalScreen {
+        let term = self.term.lock_unfair();
+        let columns = term.columns();
+        let lines = (0..term.screen_lines())
+            .map(|line| term.grid()[Line(line as i32)][..Column(columns)].to_vec())
+            .collect();
+        let cursor = term
+            .mode()
+            .contains(TermMode::SHOW_CURSOR)
+            .then_some(term.grid().cursor.point);
+        TerminalScreen {
+            columns,
+            lines,
+            cursor,
+        }
+    }
+
+    /// Applies an update sent by the host to the screen of a remote terminal.
+    ///
+    /// The screen is resized to `columns` by `line_count`, and then each of the given lines is
+    /// replaced. Lines that aren't given keep their contents.
+    pub fn update_remote_screen(
+        &mut self,
+        columns: usize,
+        line_count: usize,
+        lines: impl IntoIterator<Item = (usize, Vec<Cell>)>,
+        cursor: Option<AlacPoint>,
+        title: Option<String>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let TerminalConnection::Remote {
+            title: current_title,
+            ..
+        } = &mut self.connection
+        else {
+            return;
+        };
+        let title_changed = title.map_or(false, |title| {
+            if *current_title == title {
+                false
+            } else {
+                *current_title = title;
+                true
+            }
+        });
+
+        if columns > 0 && line_count > 0 {
+            let mut term = self.term.lock();
+            if term.columns() != columns || term.screen_lines() != line_count {
+                term.resize(ScreenDimensions {
+                    columns,
+                    lines: line_count,
+                });
+            }
+
+            for (line, cells) in lines {
+                if line >= line_count {
+                    continue;
+                }
+                let row = &mut term.grid_mut()[Line(line as i32)];
+                for (column, cell) in cells.into_iter().take(columns).enumerate() {
+                    row[Column(column)] = cell;
+                }
+            }
+
+            match cursor {
+                Some(point) if point.line.0 >= 0 && (point.line.0 as usize) < line_count => {
+                    term.grid_mut().cursor.point =
+                        AlacPoint::new(point.line, point.column.min(Column(columns - 1)));
+                    term.set_private_mode(PrivateMode::Named(NamedPrivateMode::ShowCursor));
+                }
+                _ => term.unset_private_mode(PrivateMode::Named(NamedPrivateMode::ShowCursor)),
+            }
+        }
+
+        if title_changed {
+            cx.emit(Event::TitleChanged);
+        }
+        cx.emit(Event::Wakeup);
+    }
+
+    /// Writes to the screen of a terminal created with
+    /// [`TerminalBuilder::new_fake`](crate::TerminalBuilder::new_fake), as if its process
+    /// had printed `output`.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn write_output(&mut self, output: &[u8], cx: &mut ModelContext<Self>) {
+        use alacritty_terminal::vte::ansi::Processor;
+
+        if !matches!(self.connection, TerminalConnection::Fake { .. }) {
+            return;
+        }
+        let mut processor: Processor = Processor::new();
+        let mut term = self.term.lock();
+        for byte in output {
+            processor.advance(&mut *term, *byte);
+        }
+        drop(term);
+        cx.emit(Event::Wakeup);
+        cx.notify();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(text: &str) -> Vec<Cell> {
+        text.chars()
+            .map(|c| Cell {
+                c,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn screen(lines: &[&str]) -> TerminalScreen {
+        TerminalScreen {
+            columns: lines.first().map_or(0, |line| line.len()),
+            lines: lines.iter().map(|text| line(text)).collect(),
+            cursor: None,
+        }
+    }
+
+    #[test]
+    fn test_changed_lines() {
+        let old = screen(&["$ ls ", "a.rs ", "$    "]);
+
+        let new = screen(&["$ ls ", "a.rs ", "$ pwd"]);
+        assert_eq!(new.changed_lines(&old).collect::<Vec<_>>(), [2]);
+        assert_eq!(new.changed_lines(&new).count(), 0);
+
+        // Every line is sent again when the screen is resized.
+        let new = screen(&["$ ls ", "a.rs ", "$    ", "     "]);
+        assert_eq!(new.changed_lines(&old).collect::<Vec<_>>(), [0, 1, 2, 3]);
+        let new = screen(&["$ ls  ", "a.rs  ", "$     "]);
+        assert_eq!(new.changed_lines(&old).collect::<Vec<_>>(), [0, 1, 2]);
+    }
+}
@@ -1,8 +1,10 @@
 pub mod mappings;
 
 pub use alacritty_terminal;
+pub use screen::TerminalScreen;
 
 mod pty_info;
+mod screen;
 pub mod terminal_settings;
 
 use alacritty_terminal::{
@@ -449,7 +451,10 @@ impl TerminalBuilder {
 
         let terminal = Terminal {
             task,
-            pty_tx: Notifier(pty_tx),
+            connection: TerminalConnection::Pty {
+                pty_tx: Notifier(pty_tx),
+                info: pty_info,
+            },
             completion_tx,
             term,
             term_config: config,
@@ -459,7 +464,6 @@ impl TerminalBuilder {
             last_mouse: None,
             matches: Vec::new(),
             selection_head: None,
-            pty_info,
             breadcrumb_text: String::new(),
             scroll_px: px(0.),
             last_mouse_position: None,
@@ -479,6 +483,75 @@ impl TerminalBuilder {
         })
     }
 
+    /// Creates a terminal that mirrors one shared by the host of a remote project.
+    ///
+    /// There's no process behind it: its screen is replaced by [`Terminal::update_remote_screen`],
+    /// and whatever is typed into it is sent to `input_tx`.
+    pub fn new_remote(
+        title: String,
+        cursor_shape: CursorShape,
+        input_tx: UnboundedSender<Vec<u8>>,
+    ) -> TerminalBuilder {
+        let config = Config {
+            scrolling_history: 0,
+            default_cursor_style: AlacCursorStyle::from(cursor_shape),
+            ..Config::default()
+        };
+        Self::without_pty(TerminalConnection::Remote { input_tx, title }, config)
+    }
+
+    /// Creates a local terminal with no process behind it, for tests.
+    ///
+    /// Output is written to it with [`Terminal::write_output`], and whatever is typed into it
+    /// is sent to `input_tx`.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn new_fake(title: String, input_tx: UnboundedSender<Vec<u8>>) -> TerminalBuilder {
+        Self::without_pty(
+            TerminalConnection::Fake { input_tx, title },
+            Config::default(),
+        )
+    }
+
+    fn without_pty(connection: TerminalConnection, config: Config) -> TerminalBuilder {
+        let (events_tx, events_rx) = unbounded();
+        let term = Term::new(
+            config.clone(),
+            &TerminalSize::default(),
+            ZedListener(events_tx),
+        );
+        let (completion_tx, _) = smol::channel::bounded(1);
+
+        let terminal = Terminal {
+            task: None,
+            connection,
+            completion_tx,
+            term: Arc::new(FairMutex::new(term)),
+            term_config: config,
+            title_override: None,
+            events: VecDeque::with_capacity(10),
+            last_content: Default::default(),
+            last_mouse: None,
+            matches: Vec::new(),
+            selection_head: None,
+            breadcrumb_text: String::new(),
+            scroll_px: px(0.),
+            last_mouse_position: None,
+            next_link_id: 0,
+            selection_phase: SelectionPhase::Ended,
+            secondary_pressed: false,
+            hovered_word: false,
+            url_regex: RegexSearch::new(URL_REGEX).unwrap(),
+            word_regex: RegexSearch::new(WORD_REGEX).unwrap(),
+            vi_mode_enabled: false,
+            is_ssh_terminal: false,
+        };
+
+        TerminalBuilder {
+            terminal,
+            events_rx,
+        }
+    }
+
     pub fn subscribe(mut self, cx: &ModelContext<Terminal>) -> Terminal {
         //Event loop
         cx.spawn(|terminal, mut cx| async move {
@@ -604,7 +677,7 @@ pub enum SelectionPhase {
 }
 
 pub struct Terminal {
-    pty_tx: Notifier,
+    connection: TerminalConnection,
     completion_tx: Sender<()>,
     term: Arc<FairMutex<Term<ZedListener>>>,
     term_config: Config,
@@ -617,7 +690,6 @@ pub struct Terminal {
     pub last_content: TerminalContent,
     pub selection_head: Option<AlacPoint>,
     pub breadcrumb_text: String,
-    pub pty_info: PtyProcessInfo,
     title_override: Option<SharedString>,
     scroll_px: Pixels,
     next_link_id: usize,
@@ -631,6 +703,26 @@ pub struct Terminal {
     is_ssh_terminal: bool,
 }
 
+/// Where a terminal's output comes from, and where its input goes.
+enum TerminalConnection {
+    /// A process running in a local PTY.
+    Pty {
+        pty_tx: Notifier,
+        info: PtyProcessInfo,
+    },
+    /// A terminal shared by the host of a remote project.
+    Remote {
+        input_tx: UnboundedSender<Vec<u8>>,
+        title: String,
+    },
+    /// A local terminal without a process, whose output is written by tests.
+    #[cfg(any(test, feature = "test-support"))]
+    Fake {
+        input_tx: UnboundedSender<Vec<u8>>,
+        title: String,
+    },
+}
+
 pub struct TaskState {
     pub id: TaskId,
     pub full_label: String,
@@ -711,8 +803,10 @@ impl Terminal {
             AlacTermEvent::Wakeup => {
                 cx.emit(Event::Wakeup);
 
-                if self.pty_info.has_changed() {
-                    cx.emit(Event::TitleChanged);
+                if let TerminalConnection::Pty { info, .. } = &self.connection {
+                    if info.has_changed() {
+                        cx.emit(Event::TitleChanged);
+                    }
                 }
             }
             AlacTermEvent::ColorRequest(index, format) => {
@@ -753,9 +847,11 @@ impl Terminal {
 
                 self.last_content.size = new_size;
 
-                self.pty_tx.0.send(Msg::Resize(new_size.into())).ok();
-
-                term.resize(new_size);
+                // The size of a remote terminal's grid is dictated by the host.
+                if let TerminalConnection::Pty { pty_tx, .. } = &self.connection {
+                    pty_tx.0.send(Msg::Resize(new_size.into())).ok();
+                    term.resize(new_size);
+                }
             }
             InternalEvent::Clear => {
                 // Clear back buffer
@@ -1142,11 +1238,20 @@ impl Terminal {
 
     ///Write the Input payload to the tty.
     fn write_to_pty(&self, input: String) {
-        self.pty_tx.notify(input.into_bytes());
+        self.write_bytes_to_pty(input.into_bytes());
     }
 
     fn write_bytes_to_pty(&self, input: Vec<u8>) {
-        self.pty_tx.notify(input);
+        match &self.connection {
+            TerminalConnection::Pty { pty_tx, .. } => pty_tx.notify(input),
+            TerminalConnection::Remote { input_tx, .. } => {
+                input_tx.unbounded_send(input).ok();
+            }
+            #[cfg(any(test, feature = "test-support"))]
+            TerminalConnection::Fake { input_tx, .. } => {
+                input_tx.unbounded_send(input).ok();
+            }
+        }
     }
 
     pub fn input(&mut self, input: String) {
@@ -1407,7 +1512,7 @@ impl Terminal {
 
             if self.mouse_changed(point, side) {
                 if let Some(bytes) = mouse_moved_report(point, e, self.last_content.mode) {
-                    self.pty_tx.notify(bytes);
+                    self.write_bytes_to_pty(bytes);
                 }
             }
         } else if self.secondary_pressed {
@@ -1486,7 +1591,7 @@ impl Terminal {
             if let Some(bytes) =
                 mouse_button_report(point, e.button, e.modifiers, true, self.last_content.mode)
             {
-                self.pty_tx.notify(bytes);
+                self.write_bytes_to_pty(bytes);
             }
         } else {
             match e.button {
@@ -1540,7 +1645,7 @@ impl Terminal {
             if let Some(bytes) =
                 mouse_button_report(point, e.button, e.modifiers, false, self.last_content.mode)
             {
-                self.pty_tx.notify(bytes);
+                self.write_bytes_to_pty(bytes);
             }
         } else {
             if e.button == MouseButton::Left && setting.copy_on_select {
@@ -1578,7 +1683,7 @@ impl Terminal {
                 if let Some(scrolls) = scroll_report(point, scroll_lines, e, self.last_content.mode)
                 {
                     for scroll in scrolls {
-                        self.pty_tx.notify(scroll);
+                        self.write_bytes_to_pty(scroll);
                     }
                 };
             } else if self
@@ -1587,7 +1692,7 @@ impl Terminal {
                 .contains(TermMode::ALT_SCREEN | TermMode::ALTERNATE_SCROLL)
                 && !e.shift
             {
-                self.pty_tx.notify(alt_scroll(scroll_lines))
+                self.write_bytes_to_pty(alt_scroll(scroll_lines))
             } else if scroll_lines != 0 {
                 let scroll = AlacScroll::Delta(scroll_lines);
 
@@ -1658,10 +1763,14 @@ impl Terminal {
     /// This does *not* return the working directory of the shell that runs on the
     /// remote host, in case Zed is connected to a remote host.
     fn client_side_working_directory(&self) -> Option<PathBuf> {
-        self.pty_info
-            .current
-            .as_ref()
-            .map(|process| process.cwd.clone())
+        match &self.connection {
+            TerminalConnection::Pty { info, .. } => {
+                info.current.as_ref().map(|process| process.cwd.clone())
+            }
+            TerminalConnection::Remote { .. } => None,
+            #[cfg(any(test, feature = "test-support"))]
+            TerminalConnection::Fake { .. } => None,
+        }
     }
 
     pub fn title(&self, truncate: bool) -> String {
@@ -1679,8 +1788,13 @@ impl Terminal {
                 .as_ref()
                 .map(|title_override| title_override.to_string())
                 .unwrap_or_else(|| {
-                    self.pty_info
-                        .current
+                    let info = match &self.connection {
+                        TerminalConnection::Pty { info, .. } => info,
+                        TerminalConnection::Remote { title, .. } => return title.clone(),
+                        #[cfg(any(test, feature = "test-support"))]
+                        TerminalConnection::Fake { title, .. } => return title.clone(),
+                    };
+                    info.current
                         .as_ref()
                         .map(|fpi| {
                             let process_file = fpi
@@ -1850,7 +1964,9 @@ unsafe fn append_text_to_term(term: &mut Term<ZedListener>, text_lines: &[&str])
 
 impl Drop for Terminal {
     fn drop(&mut self) {
-        self.pty_tx.0.send(Msg::Shutdown).ok();
+        if let TerminalConnection::Pty { pty_tx, .. } = &self.connection {
+            pty_tx.0.send(Msg::Shutdown).ok();
+        }
     }
 }
 
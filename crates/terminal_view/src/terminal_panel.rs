@@ -31,7 +31,7 @@ use ui::{
 };
 use util::{ResultExt, TryFutureExt};
 use workspace::{
-    dock::{DockPosition, Panel, PanelEvent},
+    dock::{DockPosition, Panel, PanelEvent, PanelId},
     item::SerializableItem,
     move_item, pane,
     ui::IconName,
@@ -1161,6 +1161,10 @@ impl Panel for TerminalPanel {
             return;
         }
         cx.defer(|this, cx| {
+            // When following a collaborator into the panel, their terminal is added before this runs.
+            if !this.has_no_terminals(cx) {
+                return;
+            }
             let Ok(kind) = this.workspace.update(cx, |workspace, cx| {
                 TerminalKind::Shell(default_working_directory(workspace, cx))
             }) else {
@@ -1209,6 +1213,10 @@ impl Panel for TerminalPanel {
     fn pane(&self) -> Option<View<Pane>> {
         Some(self.active_pane.clone())
     }
+
+    fn remote_id() -> Option<PanelId> {
+        Some(PanelId::TerminalPanel)
+    }
 }
 
 struct InlineAssistTabBarButton {
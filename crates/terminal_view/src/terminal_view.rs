@@ -2,6 +2,7 @@ mod persistence;
 pub mod terminal_element;
 pub mod terminal_panel;
 
+use client::proto::{self, PeerId};
 use collections::HashSet;
 use editor::{actions::SelectAll, scroll::Autoscroll, Editor};
 use futures::{stream::FuturesUnordered, StreamExt};
@@ -13,7 +14,10 @@ use gpui::{
 };
 use language::Bias;
 use persistence::TERMINAL_DB;
-use project::{search::SearchQuery, terminals::TerminalKind, Fs, Metadata, Project};
+use project::{
+    search::SearchQuery, terminal_sharing::TerminalAccess, terminals::TerminalKind, Fs, Metadata,
+    Project,
+};
 use terminal::{
     alacritty_terminal::{
         index::Point,
@@ -29,12 +33,15 @@ use terminal_panel::TerminalPanel;
 use ui::{h_flex, prelude::*, ContextMenu, Icon, IconName, Label, Tooltip};
 use util::{paths::PathWithPosition, ResultExt};
 use workspace::{
-    item::{BreadcrumbText, Item, ItemEvent, SerializableItem, TabContentParams},
+    item::{
+        BreadcrumbText, Dedup, FollowEvent, FollowableItem, Item, ItemEvent, SerializableItem,
+        TabContentParams,
+    },
     notifications::NotifyResultExt,
     register_serializable_item,
     searchable::{SearchEvent, SearchOptions, SearchableItem, SearchableItemHandle},
-    CloseActiveItem, NewCenterTerminal, NewTerminal, OpenVisible, ToolbarItemLocation, Workspace,
-    WorkspaceId,
+    CloseActiveItem, FollowableViewRegistry, NewCenterTerminal, NewTerminal, OpenVisible,
+    ToolbarItemLocation, ViewId, Workspace, WorkspaceId,
 };
 
 use anyhow::Context;
@@ -77,6 +84,7 @@ pub fn init(cx: &mut AppContext) {
     terminal::init(cx);
 
     register_serializable_item::<TerminalView>(cx);
+    FollowableViewRegistry::register::<TerminalView>(cx);
 
     cx.observe_new_views(|workspace: &mut Workspace, _| {
         workspace.register_action(TerminalView::deploy);
@@ -112,6 +120,7 @@ pub struct TerminalView {
     show_breadcrumbs: bool,
     block_below_cursor: Option<Rc<BlockProperties>>,
     scroll_top: Pixels,
+    remote_id: Option<ViewId>,
     _subscriptions: Vec<Subscription>,
     _terminal_subscriptions: Vec<Subscription>,
 }
@@ -204,6 +213,7 @@ impl TerminalView {
             show_breadcrumbs: TerminalSettings::get_global(cx).toolbar.breadcrumbs,
             block_below_cursor: None,
             scroll_top: Pixels::ZERO,
+            remote_id: None,
             _subscriptions: vec![
                 focus_in,
                 focus_out,
@@ -221,6 +231,21 @@ impl TerminalView {
         self.has_bell
     }
 
+    /// Returns how this terminal is shared with collaborators, if it is.
+    pub fn shared_access(&self, cx: &AppContext) -> Option<TerminalAccess> {
+        let workspace = self.workspace.upgrade()?;
+        let project = workspace.read(cx).project().read(cx);
+        if self.terminal.read(cx).is_remote() {
+            let terminal_id = project.shared_terminal_id(&self.terminal, cx)?;
+            project
+                .remote_terminals()
+                .find(|terminal| terminal.id == terminal_id)
+                .map(|terminal| terminal.access)
+        } else {
+            project.terminal_access(&self.terminal)
+        }
+    }
+
     pub fn clear_bell(&mut self, cx: &mut ViewContext<TerminalView>) {
         self.has_bell = false;
         cx.emit(Event::Wakeup);
@@ -1027,7 +1052,12 @@ impl Item for TerminalView {
     type Event = ItemEvent;
 
     fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString> {
-        Some(self.terminal().read(cx).title(false).into())
+        let title = self.terminal().read(cx).title(false);
+        Some(match self.shared_access(cx) {
+            Some(TerminalAccess::ReadOnly) => format!("{title} (shared read-only)").into(),
+            Some(TerminalAccess::ReadWrite) => format!("{title} (shared)").into(),
+            None => title.into(),
+        })
     }
 
     fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
@@ -1071,7 +1101,11 @@ impl Item for TerminalView {
                     }
                 }
             },
-            None => (IconName::Terminal, Color::Muted, None),
+            None => match self.shared_access(cx) {
+                Some(TerminalAccess::ReadOnly) => (IconName::Eye, Color::Accent, None),
+                Some(TerminalAccess::ReadWrite) => (IconName::Public, Color::Accent, None),
+                None => (IconName::Terminal, Color::Muted, None),
+            },
         };
 
         h_flex()
@@ -1255,6 +1289,90 @@ impl SerializableItem for TerminalView {
     }
 }
 
+impl FollowableItem for TerminalView {
+    fn remote_id(&self) -> Option<ViewId> {
+        self.remote_id
+    }
+
+    fn to_state_proto(&self, cx: &WindowContext) -> Option<proto::view::Variant> {
+        let workspace = self.workspace.upgrade()?;
+        let terminal_id = workspace
+            .read(cx)
+            .project()
+            .read(cx)
+            .shared_terminal_id(&self.terminal, cx)?;
+        Some(proto::view::Variant::Terminal(proto::view::Terminal {
+            terminal_id,
+        }))
+    }
+
+    fn from_state_proto(
+        workspace: View<Workspace>,
+        remote_id: ViewId,
+        state: &mut Option<proto::view::Variant>,
+        cx: &mut WindowContext,
+    ) -> Option<Task<anyhow::Result<View<Self>>>> {
+        let Some(proto::view::Variant::Terminal(_)) = state else {
+            return None;
+        };
+        let Some(proto::view::Variant::Terminal(state)) = state.take() else {
+            unreachable!()
+        };
+
+        let open_terminal = workspace.update(cx, |workspace, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.open_remote_terminal(state.terminal_id, cx)
+            })
+        });
+        Some(cx.spawn(|mut cx| async move {
+            let terminal = open_terminal.await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                let workspace_id = workspace.database_id();
+                let workspace = workspace.weak_handle();
+                cx.new_view(|cx| {
+                    let mut view = TerminalView::new(terminal, workspace, workspace_id, cx);
+                    view.remote_id = Some(remote_id);
+                    view
+                })
+            })
+        }))
+    }
+
+    fn to_follow_event(_: &Self::Event) -> Option<FollowEvent> {
+        None
+    }
+
+    fn add_event_to_update_proto(
+        &self,
+        _: &Self::Event,
+        _: &mut Option<proto::update_view::Variant>,
+        _: &WindowContext,
+    ) -> bool {
+        // A shared terminal's screen is streamed to guests by the project, rather than
+        // through view updates.
+        false
+    }
+
+    fn apply_update_proto(
+        &mut self,
+        _: &Model<Project>,
+        _: proto::update_view::Variant,
+        _: &mut ViewContext<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        Task::ready(Ok(()))
+    }
+
+    fn is_project_item(&self, _: &WindowContext) -> bool {
+        true
+    }
+
+    fn set_leader_peer_id(&mut self, _: Option<PeerId>, _: &mut ViewContext<Self>) {}
+
+    fn dedup(&self, existing: &Self, _: &WindowContext) -> Option<Dedup> {
+        (existing.terminal == self.terminal).then_some(Dedup::KeepExisting)
+    }
+}
+
 impl SearchableItem for TerminalView {
     type Match = RangeInclusive<Point>;
 
@@ -1263,6 +1381,7 @@ impl SearchableItem for TerminalView {
             case: false,
             word: false,
             regex: true,
+            structural: false,
             replacement: false,
             selection: false,
         }
@@ -1323,6 +1442,8 @@ impl SearchableItem for TerminalView {
                 .unwrap()),
             ),
             SearchQuery::Regex { .. } => regex_search_for_query(&query),
+            // Terminal output has no syntax tree to match.
+            SearchQuery::Structural { .. } => None,
         };
 
         if let Some(s) = searcher {
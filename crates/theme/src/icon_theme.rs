@@ -0,0 +1,103 @@
+use collections::HashMap;
+use gpui::SharedString;
+
+use crate::Appearance;
+
+/// The name of the default icon theme, which uses the file icons bundled with Zed.
+pub const DEFAULT_ICON_THEME_NAME: &str = "Zed (Default)";
+
+/// A set of icons for files and directories.
+///
+/// Any icon an icon theme doesn't define falls back to the one bundled with Zed.
+#[derive(Debug, PartialEq)]
+pub struct IconTheme {
+    /// The name of the icon theme.
+    pub name: SharedString,
+    /// The appearance of the icon theme.
+    pub appearance: Appearance,
+    /// The icons used for directories.
+    pub directory_icons: DirectoryIcons,
+    /// The icons used for the chevrons next to directories.
+    pub chevron_icons: ChevronIcons,
+    /// The mapping of file stems to their associated icon keys.
+    pub file_stems: HashMap<String, String>,
+    /// The mapping of file suffixes to their associated icon keys.
+    pub file_suffixes: HashMap<String, String>,
+    /// The mapping of icon keys to icon definitions.
+    pub file_icons: HashMap<String, IconDefinition>,
+}
+
+/// The icons used for directories.
+#[derive(Debug, PartialEq, Default)]
+pub struct DirectoryIcons {
+    /// The path to the icon for a collapsed directory.
+    pub collapsed: Option<SharedString>,
+    /// The path to the icon for an expanded directory.
+    pub expanded: Option<SharedString>,
+}
+
+/// The icons used for the chevrons next to directories.
+#[derive(Debug, PartialEq, Default)]
+pub struct ChevronIcons {
+    /// The path to the chevron icon for a collapsed directory.
+    pub collapsed: Option<SharedString>,
+    /// The path to the chevron icon for an expanded directory.
+    pub expanded: Option<SharedString>,
+}
+
+/// An icon in an [`IconTheme`].
+#[derive(Debug, PartialEq)]
+pub struct IconDefinition {
+    /// The absolute path to the icon file.
+    pub path: SharedString,
+}
+
+impl IconTheme {
+    /// Returns the icon for a file with the given stem or suffix, if this icon theme defines one.
+    ///
+    /// `stem_or_suffix` is matched against [`Self::file_stems`] first, then
+    /// [`Self::file_suffixes`].
+    pub fn file_icon(&self, stem_or_suffix: &str) -> Option<SharedString> {
+        let key = self
+            .file_stems
+            .get(stem_or_suffix)
+            .or_else(|| self.file_suffixes.get(stem_or_suffix))?;
+        self.icon_for_key(key)
+    }
+
+    /// Returns the icon associated with the given icon key.
+    pub fn icon_for_key(&self, key: &str) -> Option<SharedString> {
+        self.file_icons.get(key).map(|icon| icon.path.clone())
+    }
+
+    /// Returns the icon for a directory.
+    pub fn directory_icon(&self, expanded: bool) -> Option<SharedString> {
+        if expanded {
+            self.directory_icons.expanded.clone()
+        } else {
+            self.directory_icons.collapsed.clone()
+        }
+    }
+
+    /// Returns the chevron icon for a directory.
+    pub fn chevron_icon(&self, expanded: bool) -> Option<SharedString> {
+        if expanded {
+            self.chevron_icons.expanded.clone()
+        } else {
+            self.chevron_icons.collapsed.clone()
+        }
+    }
+}
+
+/// Returns the default icon theme, which defers to the file icons bundled with Zed.
+pub fn default_icon_theme() -> IconTheme {
+    IconTheme {
+        name: DEFAULT_ICON_THEME_NAME.into(),
+        appearance: Appearance::Dark,
+        directory_icons: DirectoryIcons::default(),
+        chevron_icons: ChevronIcons::default(),
+        file_stems: HashMap::default(),
+        file_suffixes: HashMap::default(),
+        file_icons: HashMap::default(),
+    }
+}
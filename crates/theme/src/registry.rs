@@ -11,7 +11,9 @@ use parking_lot::RwLock;
 use util::ResultExt;
 
 use crate::{
-    read_user_theme, refine_theme_family, Appearance, Theme, ThemeFamily, ThemeFamilyContent,
+    default_icon_theme, read_icon_theme, read_user_theme, refine_theme_family, Appearance,
+    AppearanceContent, ChevronIcons, DirectoryIcons, IconDefinition, IconTheme, Theme, ThemeFamily,
+    ThemeFamilyContent,
 };
 
 /// The metadata for a theme.
@@ -36,6 +38,7 @@ impl Global for GlobalThemeRegistry {}
 
 struct ThemeRegistryState {
     themes: HashMap<SharedString, Arc<Theme>>,
+    icon_themes: HashMap<SharedString, Arc<IconTheme>>,
 }
 
 /// The registry for themes.
@@ -67,6 +70,7 @@ impl ThemeRegistry {
         let registry = Self {
             state: RwLock::new(ThemeRegistryState {
                 themes: HashMap::default(),
+                icon_themes: HashMap::default(),
             }),
             assets,
         };
@@ -75,6 +79,12 @@ impl ThemeRegistry {
         // for tests.
         registry.insert_theme_families([crate::fallback_themes::zed_default_themes()]);
 
+        let default_icon_theme = default_icon_theme();
+        registry.state.write().icon_themes.insert(
+            default_icon_theme.name.clone(),
+            Arc::new(default_icon_theme),
+        );
+
         registry
     }
 
@@ -196,6 +206,105 @@ impl ThemeRegistry {
 
         Ok(())
     }
+
+    /// Returns the metadata of all icon themes in the registry.
+    pub fn list_icon_themes(&self) -> Vec<ThemeMeta> {
+        self.state
+            .read()
+            .icon_themes
+            .values()
+            .map(|theme| ThemeMeta {
+                name: theme.name.clone(),
+                appearance: theme.appearance,
+            })
+            .collect()
+    }
+
+    /// Returns the names of all icon themes in the registry.
+    pub fn list_icon_theme_names(&self) -> Vec<SharedString> {
+        let mut names = self
+            .state
+            .read()
+            .icon_themes
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    /// Returns the icon theme with the given name.
+    pub fn get_icon_theme(&self, name: &str) -> Result<Arc<IconTheme>> {
+        self.state
+            .read()
+            .icon_themes
+            .get(name)
+            .ok_or_else(|| anyhow!("icon theme not found: {name}"))
+            .cloned()
+    }
+
+    /// Removes the icon themes with the given names from the registry.
+    pub fn remove_icon_themes(&self, icon_themes_to_remove: &[SharedString]) {
+        self.state
+            .write()
+            .icon_themes
+            .retain(|name, _| !icon_themes_to_remove.contains(name))
+    }
+
+    /// Loads the icon theme from the specified path and adds it to the registry.
+    ///
+    /// The paths of the icons in the icon theme are resolved relative to `icons_root_dir`.
+    pub async fn load_icon_theme(
+        &self,
+        icon_theme_path: &Path,
+        icons_root_dir: &Path,
+        fs: Arc<dyn Fs>,
+    ) -> Result<()> {
+        let icon_theme_family = read_icon_theme(icon_theme_path, fs).await?;
+
+        let resolve_icon_path = |path: String| -> SharedString {
+            icons_root_dir
+                .join(path)
+                .to_string_lossy()
+                .to_string()
+                .into()
+        };
+
+        let mut state = self.state.write();
+        for icon_theme in icon_theme_family.themes {
+            let icon_theme = IconTheme {
+                name: icon_theme.name.into(),
+                appearance: match icon_theme.appearance {
+                    AppearanceContent::Light => Appearance::Light,
+                    AppearanceContent::Dark => Appearance::Dark,
+                },
+                directory_icons: DirectoryIcons {
+                    collapsed: icon_theme.directory_icons.collapsed.map(resolve_icon_path),
+                    expanded: icon_theme.directory_icons.expanded.map(resolve_icon_path),
+                },
+                chevron_icons: ChevronIcons {
+                    collapsed: icon_theme.chevron_icons.collapsed.map(resolve_icon_path),
+                    expanded: icon_theme.chevron_icons.expanded.map(resolve_icon_path),
+                },
+                file_stems: icon_theme.file_stems,
+                file_suffixes: icon_theme.file_suffixes,
+                file_icons: icon_theme
+                    .file_icons
+                    .into_iter()
+                    .map(|(key, icon)| {
+                        let path = resolve_icon_path(icon.path);
+                        (key, IconDefinition { path })
+                    })
+                    .collect(),
+            };
+
+            state
+                .icon_themes
+                .insert(icon_theme.name.clone(), Arc::new(icon_theme));
+        }
+
+        Ok(())
+    }
 }
 
 impl Default for ThemeRegistry {
@@ -203,3 +312,77 @@ impl Default for ThemeRegistry {
         Self::new(Box::new(()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use serde_json::json;
+
+    use super::*;
+
+    #[gpui::test]
+    async fn test_load_icon_theme(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/extensions/material-icons",
+            json!({
+                "icon_themes": {
+                    "material.json": r#"{
+                        "name": "Material",
+                        "author": "Zed Industries",
+                        "themes": [
+                            {
+                                "name": "Material Icons",
+                                "appearance": "dark",
+                                "directory_icons": {
+                                    "expanded": "icons/folder-open.svg"
+                                },
+                                "file_stems": { "Makefile": "make" },
+                                "file_suffixes": { "rs": "rust" },
+                                "file_icons": {
+                                    "make": { "path": "icons/make.svg" },
+                                    "rust": { "path": "icons/rust.svg" }
+                                }
+                            }
+                        ]
+                    }"#
+                }
+            }),
+        )
+        .await;
+
+        let registry = ThemeRegistry::default();
+        registry
+            .load_icon_theme(
+                Path::new("/extensions/material-icons/icon_themes/material.json"),
+                Path::new("/extensions/material-icons"),
+                fs,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            registry.list_icon_theme_names(),
+            ["Material Icons", "Zed (Default)"]
+        );
+
+        let icon_theme = registry.get_icon_theme("Material Icons").unwrap();
+        assert_eq!(
+            icon_theme.file_icon("rs"),
+            Some("/extensions/material-icons/icons/rust.svg".into())
+        );
+        assert_eq!(
+            icon_theme.file_icon("Makefile"),
+            Some("/extensions/material-icons/icons/make.svg".into())
+        );
+        assert_eq!(icon_theme.file_icon("py"), None);
+        assert_eq!(
+            icon_theme.directory_icon(true),
+            Some("/extensions/material-icons/icons/folder-open.svg".into())
+        );
+        assert_eq!(icon_theme.directory_icon(false), None);
+
+        registry.remove_icon_themes(&["Material Icons".into()]);
+        assert_eq!(registry.list_icon_theme_names(), ["Zed (Default)"]);
+    }
+}
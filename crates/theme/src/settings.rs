@@ -1,5 +1,8 @@
 use crate::fallback_themes::zed_default_dark;
-use crate::{Appearance, SyntaxTheme, Theme, ThemeRegistry, ThemeStyleContent};
+use crate::{
+    Appearance, IconTheme, SyntaxTheme, Theme, ThemeRegistry, ThemeStyleContent,
+    DEFAULT_ICON_THEME_NAME,
+};
 use anyhow::Result;
 use derive_more::{Deref, DerefMut};
 use gpui::{
@@ -114,6 +117,10 @@ pub struct ThemeSettings {
     pub theme_selection: Option<ThemeSelection>,
     /// The active theme.
     pub active_theme: Arc<Theme>,
+    /// The name of the selected icon theme.
+    pub icon_theme_selection: Option<String>,
+    /// The active icon theme.
+    pub active_icon_theme: Arc<IconTheme>,
     /// Manual overrides for the active theme.
     ///
     /// Note: This setting is still experimental. See [this tracking issue](https://github.com/zed-industries/zed/issues/18078)
@@ -160,6 +167,26 @@ impl ThemeSettings {
             }
         }
     }
+
+    /// Reloads the current icon theme.
+    ///
+    /// Reads the [`ThemeSettings`] to know which icon theme should be loaded,
+    /// falling back to the default icon theme if it doesn't exist.
+    pub fn reload_current_icon_theme(cx: &mut AppContext) {
+        let mut theme_settings = ThemeSettings::get_global(cx).clone();
+
+        if let Some(icon_theme_name) = theme_settings.icon_theme_selection.clone() {
+            let theme_registry = ThemeRegistry::global(cx);
+            let icon_theme = theme_registry
+                .get_icon_theme(&icon_theme_name)
+                .or_else(|_| theme_registry.get_icon_theme(DEFAULT_ICON_THEME_NAME));
+
+            if let Some(icon_theme) = icon_theme.log_err() {
+                theme_settings.active_icon_theme = icon_theme;
+                ThemeSettings::override_global(theme_settings, cx);
+            }
+        }
+    }
 }
 
 /// The appearance of the system.
@@ -324,6 +351,9 @@ pub struct ThemeSettingsContent {
     /// The name of the Zed theme to use.
     #[serde(default)]
     pub theme: Option<ThemeSelection>,
+    /// The name of the icon theme to use.
+    #[serde(default)]
+    pub icon_theme: Option<String>,
 
     /// UNSTABLE: Expect many elements to be broken.
     ///
@@ -372,6 +402,11 @@ impl ThemeSettingsContent {
         }
     }
 
+    /// Sets the icon theme to the icon theme with the specified name.
+    pub fn set_icon_theme(&mut self, icon_theme_name: String) {
+        self.icon_theme = Some(icon_theme_name);
+    }
+
     /// Sets the mode for the theme.
     pub fn set_mode(&mut self, mode: ThemeMode) {
         if let Some(selection) = self.theme.as_mut() {
@@ -631,6 +666,12 @@ impl settings::Settings for ThemeSettings {
                 .get(defaults.theme.as_ref().unwrap().theme(*system_appearance))
                 .or(themes.get(&zed_default_dark().name))
                 .unwrap(),
+            icon_theme_selection: defaults.icon_theme.clone(),
+            active_icon_theme: defaults
+                .icon_theme
+                .as_ref()
+                .and_then(|name| themes.get_icon_theme(name).ok())
+                .unwrap_or_else(|| themes.get_icon_theme(DEFAULT_ICON_THEME_NAME).unwrap()),
             theme_overrides: None,
             ui_density: defaults.ui_density.unwrap_or(UiDensity::Default),
             unnecessary_code_fade: defaults.unnecessary_code_fade.unwrap_or(0.0),
@@ -682,6 +723,14 @@ impl settings::Settings for ThemeSettings {
                 }
             }
 
+            if let Some(value) = &value.icon_theme {
+                this.icon_theme_selection = Some(value.clone());
+
+                if let Some(icon_theme) = themes.get_icon_theme(value).log_err() {
+                    this.active_icon_theme = icon_theme;
+                }
+            }
+
             this.theme_overrides.clone_from(&value.theme_overrides);
             this.apply_theme_overrides();
 
@@ -722,8 +771,21 @@ impl settings::Settings for ThemeSettings {
             ..Default::default()
         };
 
+        let icon_theme_names = ThemeRegistry::global(cx)
+            .list_icon_theme_names()
+            .into_iter()
+            .map(|icon_theme_name| Value::String(icon_theme_name.to_string()))
+            .collect();
+
+        let icon_theme_name_schema = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            enum_values: Some(icon_theme_names),
+            ..Default::default()
+        };
+
         root_schema.definitions.extend([
             ("ThemeName".into(), theme_name_schema.into()),
+            ("IconThemeName".into(), icon_theme_name_schema.into()),
             ("FontFamilies".into(), params.font_family_schema()),
             ("FontFallbacks".into(), params.font_fallback_schema()),
         ]);
@@ -735,6 +797,7 @@ impl settings::Settings for ThemeSettings {
                 ("buffer_font_fallbacks", "#/definitions/FontFallbacks"),
                 ("ui_font_family", "#/definitions/FontFamilies"),
                 ("ui_font_fallbacks", "#/definitions/FontFallbacks"),
+                ("icon_theme", "#/definitions/IconThemeName"),
             ],
         );
 
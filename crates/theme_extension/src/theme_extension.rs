@@ -44,4 +44,41 @@ impl ExtensionThemeProxy for ThemeRegistryProxy {
     fn reload_current_theme(&self, cx: &mut AppContext) {
         ThemeSettings::reload_current_theme(cx)
     }
+
+    fn list_icon_theme_names(
+        &self,
+        icon_theme_path: PathBuf,
+        fs: Arc<dyn Fs>,
+    ) -> Task<Result<Vec<String>>> {
+        self.executor.spawn(async move {
+            let icon_themes = theme::read_icon_theme(&icon_theme_path, fs).await?;
+            Ok(icon_themes
+                .themes
+                .into_iter()
+                .map(|icon_theme| icon_theme.name)
+                .collect())
+        })
+    }
+
+    fn remove_icon_themes(&self, icon_themes: Vec<SharedString>) {
+        self.theme_registry.remove_icon_themes(&icon_themes);
+    }
+
+    fn load_icon_theme(
+        &self,
+        icon_theme_path: PathBuf,
+        icons_root_dir: PathBuf,
+        fs: Arc<dyn Fs>,
+    ) -> Task<Result<()>> {
+        let theme_registry = self.theme_registry.clone();
+        self.executor.spawn(async move {
+            theme_registry
+                .load_icon_theme(&icon_theme_path, &icons_root_dir, fs)
+                .await
+        })
+    }
+
+    fn reload_current_icon_theme(&self, cx: &mut AppContext) {
+        ThemeSettings::reload_current_icon_theme(cx)
+    }
 }
@@ -1,3 +1,5 @@
+mod icon_theme_selector;
+
 use client::telemetry::Telemetry;
 use fs::Fs;
 use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
@@ -20,6 +22,7 @@ pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(
         |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
             workspace.register_action(toggle);
+            workspace.register_action(icon_theme_selector::toggle);
         },
     )
     .detach();
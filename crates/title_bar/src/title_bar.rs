@@ -334,6 +334,23 @@ impl TitleBar {
             return self.render_ssh_project_host(cx);
         }
 
+        if self.project.read(cx).is_offline() {
+            return Some(
+                Button::new("offline", "Offline")
+                    .style(ButtonStyle::Subtle)
+                    .label_size(LabelSize::Small)
+                    .tooltip(|cx| {
+                        Tooltip::with_meta(
+                            "You're offline",
+                            None,
+                            "Rejoin the call to send your edits to the host",
+                            cx,
+                        )
+                    })
+                    .into_any_element(),
+            );
+        }
+
         if self.project.read(cx).is_disconnected(cx) {
             return Some(
                 Button::new("disconnected", "Disconnected")
@@ -583,6 +600,10 @@ impl TitleBar {
                             "Themes…",
                             zed_actions::theme_selector::Toggle::default().boxed_clone(),
                         )
+                        .action(
+                            "Icon Themes…",
+                            zed_actions::icon_theme_selector::Toggle::default().boxed_clone(),
+                        )
                         .action("Extensions", zed_actions::Extensions.boxed_clone())
                         .separator()
                         .link(
@@ -626,6 +647,10 @@ impl TitleBar {
                                 "Themes…",
                                 zed_actions::theme_selector::Toggle::default().boxed_clone(),
                             )
+                            .action(
+                                "Icon Themes…",
+                                zed_actions::icon_theme_selector::Toggle::default().boxed_clone(),
+                            )
                             .action("Extensions", zed_actions::Extensions.boxed_clone())
                             .separator()
                             .link(
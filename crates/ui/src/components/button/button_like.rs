@@ -1,5 +1,5 @@
 #![allow(missing_docs)]
-use gpui::{relative, CursorStyle, DefiniteLength, MouseButton};
+use gpui::{relative, CursorStyle, DefiniteLength, MouseButton, Role};
 use gpui::{transparent_black, AnyElement, AnyView, ClickEvent, Hsla, Rems};
 use smallvec::SmallVec;
 
@@ -391,6 +391,13 @@ impl ButtonLike {
         self.rounding = rounding.into();
         self
     }
+
+    /// Sets the name announced for the button by assistive technologies, for buttons
+    /// without a text label of their own.
+    pub fn accessibility_label(mut self, label: impl Into<SharedString>) -> Self {
+        self.base = self.base.accessibility_label(label);
+        self
+    }
 }
 
 impl Disableable for ButtonLike {
@@ -487,6 +494,9 @@ impl RenderOnce for ButtonLike {
         self.base
             .h_flex()
             .id(self.id.clone())
+            .role(Role::Button)
+            .accessibility_disabled(self.disabled)
+            .when(self.selected, |this| this.accessibility_selected(true))
             .group("")
             .flex_none()
             .h(self.height.unwrap_or(self.size.rems().into()))
@@ -1,6 +1,6 @@
 #![allow(missing_docs)]
 
-use gpui::{div, prelude::*, ElementId, IntoElement, Styled, WindowContext};
+use gpui::{div, prelude::*, ElementId, IntoElement, Role, Styled, WindowContext};
 
 use crate::prelude::*;
 use crate::{Color, Icon, IconName, Selection};
@@ -83,6 +83,9 @@ impl RenderOnce for Checkbox {
 
         h_flex()
             .id(self.id)
+            .role(Role::CheckBox)
+            .accessibility_checked(self.checked == Selection::Selected)
+            .accessibility_disabled(self.disabled)
             .justify_center()
             .items_center()
             .size(DynamicSpacing::Base20.rems(cx))
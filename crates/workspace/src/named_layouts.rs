@@ -0,0 +1,671 @@
+use crate::{
+    dock::{Dock, PanelHandle as _},
+    item::ItemHandle,
+    persistence::DB,
+    Member, Pane, PaneAxis, PaneGroup, SaveIntent, Workspace,
+};
+use anyhow::{Context as _, Result};
+use async_recursion::async_recursion;
+use gpui::{
+    px, AppContext, AsyncWindowContext, Axis, Task, View, ViewContext, WeakView, WindowContext,
+};
+use project::{Project, ProjectPath};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use util::ResultExt;
+
+/// A layout that was saved under a name, such as "debugging" or "review".
+///
+/// Unlike a `SerializedWorkspace`, this isn't tied to the workspace it was saved in, so items
+/// are stored by their paths, and are skipped when applying the layout to a workspace that
+/// doesn't have them.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub(crate) struct SerializedNamedLayout {
+    center_group: LayoutPaneGroup,
+    left_dock: LayoutDock,
+    right_dock: LayoutDock,
+    bottom_dock: LayoutDock,
+    centered_layout: bool,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum LayoutPaneGroup {
+    Group {
+        axis: LayoutAxis,
+        flexes: Vec<f32>,
+        children: Vec<LayoutPaneGroup>,
+    },
+    Pane(LayoutPane),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum LayoutAxis {
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+struct LayoutPane {
+    items: Vec<LayoutItem>,
+    active: bool,
+    pinned_count: usize,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+struct LayoutItem {
+    worktree_root_name: String,
+    path: PathBuf,
+    active: bool,
+    preview: bool,
+}
+
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+struct LayoutDock {
+    visible: bool,
+    active_panel: Option<String>,
+    size: Option<f32>,
+    zoom: bool,
+}
+
+/// Returns the names of the saved layouts, in alphabetical order.
+pub fn named_layouts() -> Result<Vec<String>> {
+    DB.named_layout_names()
+}
+
+pub fn delete_named_layout(name: String, cx: &AppContext) -> Task<Result<()>> {
+    cx.background_executor().spawn(DB.delete_named_layout(name))
+}
+
+impl Workspace {
+    /// Saves the current layout under the given name, replacing any layout with that name.
+    pub fn save_named_layout(&self, name: String, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let layout = SerializedNamedLayout::capture(self, cx);
+        cx.background_executor().spawn(async move {
+            let layout = serde_json::to_string(&layout)?;
+            DB.save_named_layout(name, layout).await
+        })
+    }
+
+    /// Replaces the panes and dock state of this workspace with a saved layout.
+    ///
+    /// Items that are open now are closed, after prompting to save them, and the items in the
+    /// layout that exist in this workspace's worktrees are opened in their place.
+    pub fn apply_named_layout(
+        &mut self,
+        name: String,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        cx.spawn(|this, mut cx| async move {
+            let layout = DB
+                .named_layout(name.clone())
+                .await?
+                .with_context(|| format!("there's no layout named {name:?}"))?;
+            let layout: SerializedNamedLayout =
+                serde_json::from_str(&layout).context("deserializing layout")?;
+
+            if !prompt_to_save_dirty_items(&this, &mut cx).await? {
+                return Ok(());
+            }
+
+            layout.apply(this, &mut cx).await
+        })
+    }
+}
+
+/// Unlike closing a window, items aren't kept for the next time the workspace is opened when
+/// switching layouts, so this prompts for every dirty item, including ones that would be
+/// serialized instead.
+async fn prompt_to_save_dirty_items(
+    workspace: &WeakView<Workspace>,
+    cx: &mut AsyncWindowContext,
+) -> Result<bool> {
+    let (project, dirty_items) = workspace.update(cx, |workspace, cx| {
+        let dirty_items = workspace
+            .panes
+            .iter()
+            .flat_map(|pane| {
+                pane.read(cx)
+                    .items()
+                    .filter(|item| item.is_dirty(cx))
+                    .map(|item| (pane.downgrade(), item.boxed_clone()))
+            })
+            .collect::<Vec<_>>();
+        (workspace.project.clone(), dirty_items)
+    })?;
+
+    for (pane, item) in dirty_items {
+        let Some(item_ix) = pane.update(cx, |pane, _| pane.index_for_item(item.as_ref()))? else {
+            continue;
+        };
+        if !Pane::save_item(
+            project.clone(),
+            &pane,
+            item_ix,
+            item.as_ref(),
+            SaveIntent::Close,
+            cx,
+        )
+        .await?
+        {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+impl SerializedNamedLayout {
+    fn capture(workspace: &Workspace, cx: &WindowContext) -> Self {
+        Self {
+            center_group: LayoutPaneGroup::capture(&workspace.center.root, workspace, cx),
+            left_dock: LayoutDock::capture(&workspace.left_dock, cx),
+            right_dock: LayoutDock::capture(&workspace.right_dock, cx),
+            bottom_dock: LayoutDock::capture(&workspace.bottom_dock, cx),
+            centered_layout: workspace.centered_layout,
+        }
+    }
+
+    async fn apply(
+        self,
+        workspace: WeakView<Workspace>,
+        cx: &mut AsyncWindowContext,
+    ) -> Result<()> {
+        let center_group = self.center_group.open(&workspace, cx).await;
+        workspace.update(cx, |workspace, cx| {
+            // The old panes are removed after the new ones are opened, so that buffers that
+            // are open in both are kept, along with any unsaved changes.
+            let (center_group, active_pane) = center_group.unwrap_or_else(|| {
+                let pane = workspace.add_pane(cx);
+                (Member::Pane(pane.clone()), Some(pane))
+            });
+            workspace.remove_panes(workspace.center.root.clone(), cx);
+            workspace.center = PaneGroup::with_root(center_group);
+            let active_pane = active_pane.unwrap_or_else(|| workspace.center.first_pane());
+            workspace.set_active_pane(&active_pane, cx);
+            active_pane.update(cx, |pane, cx| pane.focus(cx));
+
+            for (dock, layout_dock) in [
+                (&workspace.left_dock, self.left_dock),
+                (&workspace.right_dock, self.right_dock),
+                (&workspace.bottom_dock, self.bottom_dock),
+            ] {
+                dock.update(cx, |dock, cx| layout_dock.apply(dock, cx));
+            }
+
+            workspace.centered_layout = self.centered_layout;
+            workspace.serialize_workspace(cx);
+            cx.notify();
+        })
+    }
+}
+
+impl LayoutPaneGroup {
+    fn capture(member: &Member, workspace: &Workspace, cx: &WindowContext) -> Self {
+        match member {
+            Member::Axis(PaneAxis {
+                axis,
+                members,
+                flexes,
+                ..
+            }) => Self::Group {
+                axis: match axis {
+                    Axis::Horizontal => LayoutAxis::Horizontal,
+                    Axis::Vertical => LayoutAxis::Vertical,
+                },
+                flexes: flexes.lock().clone(),
+                children: members
+                    .iter()
+                    .map(|member| Self::capture(member, workspace, cx))
+                    .collect(),
+            },
+            Member::Pane(pane) => Self::Pane(LayoutPane::capture(pane, workspace, cx)),
+        }
+    }
+
+    #[async_recursion(?Send)]
+    async fn open(
+        self,
+        workspace: &WeakView<Workspace>,
+        cx: &mut AsyncWindowContext,
+    ) -> Option<(Member, Option<View<Pane>>)> {
+        match self {
+            Self::Group {
+                axis,
+                flexes,
+                children,
+            } => {
+                let mut members = Vec::new();
+                let mut member_flexes = Vec::new();
+                let mut active_pane = None;
+                for (ix, child) in children.into_iter().enumerate() {
+                    if let Some((member, child_active_pane)) = child.open(workspace, cx).await {
+                        members.push(member);
+                        member_flexes.push(flexes.get(ix).copied().unwrap_or(1.));
+                        active_pane = active_pane.or(child_active_pane);
+                    }
+                }
+
+                if members.len() <= 1 {
+                    return members.pop().map(|member| (member, active_pane));
+                }
+
+                // Flexes add up to the number of members, so scale them to account for the
+                // members that were skipped.
+                let total_flex = member_flexes.iter().sum::<f32>();
+                let flexes = (total_flex > 0.).then(|| {
+                    member_flexes
+                        .iter()
+                        .map(|flex| flex * members.len() as f32 / total_flex)
+                        .collect()
+                });
+                let axis = match axis {
+                    LayoutAxis::Horizontal => Axis::Horizontal,
+                    LayoutAxis::Vertical => Axis::Vertical,
+                };
+                Some((
+                    Member::Axis(PaneAxis::load(axis, members, flexes)),
+                    active_pane,
+                ))
+            }
+            Self::Pane(pane) => pane.open(workspace, cx).await,
+        }
+    }
+}
+
+impl LayoutPane {
+    fn capture(pane_handle: &View<Pane>, workspace: &Workspace, cx: &WindowContext) -> Self {
+        let project = workspace.project.read(cx);
+        let pane = pane_handle.read(cx);
+        let mut items = Vec::new();
+        let mut pinned_count = 0;
+        for (ix, item) in pane.items().enumerate() {
+            let Some(item) = LayoutItem::capture(item.as_ref(), pane, project, cx) else {
+                continue;
+            };
+            if ix < pane.pinned_count() {
+                pinned_count += 1;
+            }
+            items.push(item);
+        }
+
+        Self {
+            items,
+            active: workspace.active_pane == *pane_handle,
+            pinned_count,
+        }
+    }
+
+    async fn open(
+        self,
+        workspace: &WeakView<Workspace>,
+        cx: &mut AsyncWindowContext,
+    ) -> Option<(Member, Option<View<Pane>>)> {
+        let pane = workspace
+            .update(cx, |workspace, cx| workspace.add_pane(cx))
+            .log_err()?;
+
+        // Items are opened one at a time, so that they end up in the same order as before.
+        let mut active_item = None;
+        let mut pinned_count = 0;
+        for (ix, item) in self.items.iter().enumerate() {
+            let Some(open_task) = workspace
+                .update(cx, |workspace, cx| {
+                    let project_path = item.project_path(workspace.project.read(cx), cx)?;
+                    Some(workspace.open_path_preview(
+                        project_path,
+                        Some(pane.downgrade()),
+                        false,
+                        item.preview,
+                        cx,
+                    ))
+                })
+                .ok()
+                .flatten()
+            else {
+                continue;
+            };
+            let Some(item_handle) = open_task.await.log_err() else {
+                continue;
+            };
+            if item.active {
+                active_item = Some(item_handle);
+            }
+            if ix < self.pinned_count {
+                pinned_count += 1;
+            }
+        }
+
+        let has_items = pane
+            .update(cx, |pane, cx| {
+                if let Some(ix) = active_item
+                    .as_ref()
+                    .and_then(|item| pane.index_for_item(item.as_ref()))
+                {
+                    pane.activate_item(ix, false, false, cx);
+                }
+                pane.set_pinned_count(pinned_count.min(pane.items_len()));
+                pane.items_len() != 0
+            })
+            .log_err()?;
+
+        if has_items {
+            Some((Member::Pane(pane.clone()), self.active.then_some(pane)))
+        } else {
+            workspace
+                .update(cx, |workspace, cx| {
+                    workspace.force_remove_pane(&pane, &None, cx)
+                })
+                .log_err();
+            None
+        }
+    }
+}
+
+impl LayoutItem {
+    fn capture(
+        item: &dyn ItemHandle,
+        pane: &Pane,
+        project: &Project,
+        cx: &AppContext,
+    ) -> Option<Self> {
+        let project_path = item.project_path(cx)?;
+        let worktree = project.worktree_for_id(project_path.worktree_id, cx)?;
+        Some(Self {
+            worktree_root_name: worktree.read(cx).root_name().to_string(),
+            path: project_path.path.to_path_buf(),
+            active: pane.active_item().map(|item| item.item_id()) == Some(item.item_id()),
+            preview: pane.is_active_preview_item(item.item_id()),
+        })
+    }
+
+    /// Finds the file in the project, preferring the worktree that it was in when the layout
+    /// was saved, and falling back to any worktree that has a file at the same path.
+    fn project_path(&self, project: &Project, cx: &AppContext) -> Option<ProjectPath> {
+        let mut worktrees = project.visible_worktrees(cx).collect::<Vec<_>>();
+        worktrees.sort_by_key(|worktree| worktree.read(cx).root_name() != self.worktree_root_name);
+        worktrees.into_iter().find_map(|worktree| {
+            let worktree = worktree.read(cx);
+            worktree
+                .entry_for_path(&self.path)
+                .filter(|entry| entry.is_file())?;
+            Some(ProjectPath {
+                worktree_id: worktree.id(),
+                path: self.path.as_path().into(),
+            })
+        })
+    }
+}
+
+impl LayoutDock {
+    fn capture(dock: &View<Dock>, cx: &WindowContext) -> Self {
+        let dock = dock.read(cx);
+        let Some(panel) = dock.active_panel() else {
+            return Self::default();
+        };
+        Self {
+            visible: dock.is_open(),
+            active_panel: Some(panel.persistent_name().to_string()),
+            size: Some(panel.size(cx).0),
+            zoom: panel.is_zoomed(cx),
+        }
+    }
+
+    fn apply(self, dock: &mut Dock, cx: &mut ViewContext<Dock>) {
+        // Panels that aren't in this workspace, such as ones from extensions that have since
+        // been uninstalled, are skipped, leaving the dock's active panel as it is.
+        if let Some(panel_ix) = self
+            .active_panel
+            .as_deref()
+            .and_then(|name| dock.panel_index_for_persistent_name(name, cx))
+        {
+            dock.activate_panel(panel_ix, cx);
+            if let Some(size) = self.size {
+                dock.resize_active_panel(Some(px(size)), cx);
+            }
+        }
+        if let Some(panel) = dock.active_panel().cloned() {
+            if panel.is_zoomed(cx) != self.zoom {
+                panel.set_zoomed(self.zoom, cx);
+            }
+        }
+        dock.set_open(self.visible, cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dock::{test::TestPanel, DockPosition},
+        item::Item,
+        register_project_item,
+        tests::init_test,
+        ProjectItem, SplitDirection,
+    };
+    use fs::FakeFs;
+    use gpui::{
+        Context as _, Empty, EntityId, EventEmitter, FocusHandle, FocusableView, IntoElement,
+        Model, Render, TestAppContext, VisualTestContext,
+    };
+    use project::ProjectEntryId;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[gpui::test]
+    async fn test_named_layouts(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(register_project_item::<TestFileView>);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root1", json!({ "a.txt": "", "b.txt": "", "c.txt": "" }))
+            .await;
+        fs.insert_tree("/root2", json!({ "a.txt": "" })).await;
+
+        // Save a layout with two panes and an open dock.
+        let (workspace, cx1) = new_workspace(&fs, "/root1", cx).await;
+        let worktree_id = workspace.update(cx1, |workspace, cx| {
+            workspace
+                .project()
+                .read(cx)
+                .worktrees(cx)
+                .next()
+                .unwrap()
+                .read(cx)
+                .id()
+        });
+        let left_pane = workspace.update(cx1, |workspace, _| workspace.active_pane().clone());
+        open_paths(&workspace, &left_pane, worktree_id, &["a.txt"], cx1).await;
+        let right_pane = workspace.update(cx1, |workspace, cx| {
+            workspace.split_pane(left_pane.clone(), SplitDirection::Right, cx)
+        });
+        open_paths(
+            &workspace,
+            &right_pane,
+            worktree_id,
+            &["b.txt", "c.txt"],
+            cx1,
+        )
+        .await;
+        right_pane.update(cx1, |pane, cx| pane.activate_item(0, false, false, cx));
+        workspace.update(cx1, |workspace, cx| {
+            workspace.left_dock().update(cx, |dock, cx| {
+                dock.set_open(true, cx);
+                dock.resize_active_panel(Some(px(200.)), cx);
+            });
+        });
+        workspace
+            .update(cx1, |workspace, cx| {
+                workspace.save_named_layout("review".into(), cx)
+            })
+            .await
+            .unwrap();
+        assert!(named_layouts().unwrap().contains(&"review".to_string()));
+
+        // Apply it to another workspace with the same files.
+        let (workspace, cx2) = new_workspace(&fs, "/root1", cx).await;
+        workspace
+            .update(cx2, |workspace, cx| {
+                workspace.apply_named_layout("review".into(), cx)
+            })
+            .await
+            .unwrap();
+        workspace.update(cx2, |workspace, cx| {
+            assert_eq!(
+                pane_paths(workspace, cx),
+                [vec!["a.txt"], vec!["b.txt", "c.txt"]]
+            );
+            let right_pane = workspace.center.panes()[1].read(cx);
+            assert_eq!(right_pane.active_item_index(), 0);
+
+            let left_dock = workspace.left_dock().read(cx);
+            assert!(left_dock.is_open());
+            assert_eq!(left_dock.active_panel_size(cx), Some(px(200.)));
+        });
+
+        // In a workspace that only has some of the files, the others are skipped, along with
+        // the panes that end up empty.
+        let (workspace, cx3) = new_workspace(&fs, "/root2", cx).await;
+        workspace
+            .update(cx3, |workspace, cx| {
+                workspace.apply_named_layout("review".into(), cx)
+            })
+            .await
+            .unwrap();
+        workspace.update(cx3, |workspace, cx| {
+            assert_eq!(pane_paths(workspace, cx), [vec!["a.txt"]]);
+            assert_eq!(workspace.panes().len(), 1);
+        });
+
+        cx3.update(|cx| delete_named_layout("review".into(), cx))
+            .await
+            .unwrap();
+        assert!(!named_layouts().unwrap().contains(&"review".to_string()));
+    }
+
+    async fn new_workspace<'a>(
+        fs: &Arc<FakeFs>,
+        root: &str,
+        cx: &'a mut TestAppContext,
+    ) -> (View<Workspace>, &'a mut VisualTestContext) {
+        let project = Project::test(fs.clone(), [root.as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        workspace.update(cx, |workspace, cx| {
+            let panel = cx.new_view(|cx| TestPanel::new(DockPosition::Left, cx));
+            workspace.add_panel(panel, cx);
+        });
+        cx.run_until_parked();
+        (workspace, cx)
+    }
+
+    async fn open_paths(
+        workspace: &View<Workspace>,
+        pane: &View<Pane>,
+        worktree_id: project::WorktreeId,
+        paths: &[&str],
+        cx: &mut VisualTestContext,
+    ) {
+        for path in paths {
+            workspace
+                .update(cx, |workspace, cx| {
+                    workspace.open_path((worktree_id, *path), Some(pane.downgrade()), true, cx)
+                })
+                .await
+                .unwrap();
+        }
+    }
+
+    fn pane_paths(workspace: &Workspace, cx: &AppContext) -> Vec<Vec<String>> {
+        workspace
+            .center
+            .panes()
+            .into_iter()
+            .map(|pane| {
+                pane.read(cx)
+                    .items()
+                    .map(|item| {
+                        let project_path = item.project_path(cx).unwrap();
+                        project_path.path.to_string_lossy().into_owned()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    struct TestFile {
+        project_path: ProjectPath,
+    }
+
+    impl project::ProjectItem for TestFile {
+        fn try_open(
+            _project: &Model<Project>,
+            path: &ProjectPath,
+            cx: &mut AppContext,
+        ) -> Option<Task<gpui::Result<Model<Self>>>> {
+            let project_path = path.clone();
+            Some(cx.spawn(|mut cx| async move { cx.new_model(|_| TestFile { project_path }) }))
+        }
+
+        fn entry_id(&self, _: &AppContext) -> Option<ProjectEntryId> {
+            None
+        }
+
+        fn project_path(&self, _: &AppContext) -> Option<ProjectPath> {
+            Some(self.project_path.clone())
+        }
+
+        fn is_dirty(&self) -> bool {
+            false
+        }
+    }
+
+    struct TestFileView {
+        file: Model<TestFile>,
+        focus_handle: FocusHandle,
+    }
+
+    impl Item for TestFileView {
+        type Event = ();
+
+        fn for_each_project_item(
+            &self,
+            cx: &AppContext,
+            f: &mut dyn FnMut(EntityId, &dyn project::ProjectItem),
+        ) {
+            f(self.file.entity_id(), self.file.read(cx))
+        }
+
+        fn is_singleton(&self, _: &AppContext) -> bool {
+            true
+        }
+    }
+
+    impl EventEmitter<()> for TestFileView {}
+
+    impl FocusableView for TestFileView {
+        fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+            self.focus_handle.clone()
+        }
+    }
+
+    impl Render for TestFileView {
+        fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+            Empty
+        }
+    }
+
+    impl ProjectItem for TestFileView {
+        type Item = TestFile;
+
+        fn for_project_item(
+            _project: Model<Project>,
+            file: Model<Self::Item>,
+            cx: &mut ViewContext<Self>,
+        ) -> Self {
+            Self {
+                file,
+                focus_handle: cx.focus_handle(),
+            }
+        }
+    }
+}
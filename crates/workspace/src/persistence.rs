@@ -1,10 +1,17 @@
 pub mod model;
 
-use std::{path::Path, str::FromStr};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use anyhow::{anyhow, bail, Context, Result};
 use client::DevServerProjectId;
-use db::{define_connection, query, sqlez::connection::Connection, sqlez_macros::sql};
+use db::{
+    define_connection, query,
+    sqlez::connection::Connection,
+    sqlez_macros::{migrations, sql},
+};
 use gpui::{point, size, Axis, Bounds, WindowBounds, WindowId};
 
 use language::{LanguageName, Toolchain};
@@ -13,6 +20,7 @@ use remote::ssh_session::SshProjectId;
 use sqlez::{
     bindable::{Bind, Column, StaticColumnCount},
     statement::Statement,
+    typed_statements::Query,
 };
 
 use ui::px;
@@ -173,6 +181,7 @@ define_connection! {
     //   centered_layout: Option<bool>, // Is the Centered Layout mode activated?
     //   session_id: Option<String>, // Session id
     //   window_id: Option<u64>, // Window Id
+    //   workspace_file: Option<PathBuf>, // The workspace file the workspace was opened from
     // )
     //
     // pane_groups(
@@ -205,8 +214,13 @@ define_connection! {
     //     active: bool, // Indicates if this item is the active one in the pane
     //     preview: bool // Indicates if this item is a preview item
     // )
+    //
+    // named_layouts(
+    //     name: String, // Primary key for named_layouts
+    //     layout: String, // A JSON SerializedNamedLayout, which isn't tied to any workspace
+    // )
     pub static ref DB: WorkspaceDb<()> =
-    &[
+    migrations![
         sql!(
         CREATE TABLE workspaces(
             workspace_id INTEGER PRIMARY KEY,
@@ -383,9 +397,30 @@ define_connection! {
     sql!(
         ALTER TABLE toolchains ADD COLUMN raw_json TEXT DEFAULT "{}";
     ),
+    sql!(
+        CREATE TABLE named_layouts (
+            name TEXT PRIMARY KEY,
+            layout TEXT NOT NULL // A JSON SerializedNamedLayout
+        ) STRICT;
+    ),
+    sql!(
+        ALTER TABLE workspaces ADD COLUMN workspace_file BLOB DEFAULT NULL;
+    ),
     ];
 }
 
+const SELECT_TOOLCHAIN: Query<(WorkspaceId, String, usize), (String, String, String)> = Query::new(
+    sql!(
+        SELECT name, path, raw_json FROM toolchains WHERE workspace_id = ? AND language_name = ? AND worktree_id = ?
+    ),
+);
+
+const SELECT_TOOLCHAINS: Query<WorkspaceId, (String, String, u64, String, String)> = Query::new(
+    sql!(
+        SELECT name, path, worktree_id, language_name, raw_json FROM toolchains WHERE workspace_id = ?
+    ),
+);
+
 impl WorkspaceDb {
     /// Returns a serialized workspace for the given worktree_roots. If the passed array
     /// is empty, the most recent workspace is returned instead. If no workspace for the
@@ -1074,28 +1109,87 @@ impl WorkspaceDb {
         }
     }
 
+    query! {
+        pub(crate) async fn set_workspace_file(workspace_id: WorkspaceId, workspace_file: Option<PathBuf>) -> Result<()> {
+            UPDATE workspaces
+            SET workspace_file = ?2
+            WHERE workspace_id = ?1
+        }
+    }
+
+    query! {
+        pub(crate) fn workspace_file(workspace_id: WorkspaceId) -> Result<Option<PathBuf>> {
+            SELECT workspace_file
+            FROM workspaces
+            WHERE workspace_id = ? AND workspace_file IS NOT NULL
+        }
+    }
+
+    query! {
+        pub fn workspace_files() -> Result<Vec<(WorkspaceId, PathBuf)>> {
+            SELECT workspace_id, workspace_file
+            FROM workspaces
+            WHERE workspace_file IS NOT NULL
+        }
+    }
+
+    query! {
+        pub async fn save_named_layout(name: String, layout: String) -> Result<()> {
+            INSERT OR REPLACE INTO named_layouts(name, layout)
+            VALUES (?1, ?2)
+        }
+    }
+
+    query! {
+        pub read async fn named_layout(name: String) -> Result<Option<String>> {
+            SELECT layout
+            FROM named_layouts
+            WHERE name = ?
+        }
+    }
+
+    query! {
+        pub fn named_layout_names() -> Result<Vec<String>> {
+            SELECT name
+            FROM named_layouts
+            ORDER BY name
+        }
+    }
+
+    query! {
+        pub async fn delete_named_layout(name: String) -> Result<()> {
+            DELETE FROM named_layouts
+            WHERE name = ?
+        }
+    }
+
     pub async fn toolchain(
         &self,
         workspace_id: WorkspaceId,
         worktree_id: WorktreeId,
         language_name: LanguageName,
     ) -> Result<Option<Toolchain>> {
-        self.write(move |this| {
-            let mut select = this
-                .select_bound(sql!(
-                    SELECT name, path, raw_json FROM toolchains WHERE workspace_id = ? AND language_name = ? AND worktree_id = ?
-                ))
-                .context("Preparing insertion")?;
-
-            let toolchain: Vec<(String, String, String)> =
-                select((workspace_id, language_name.0.to_owned(), worktree_id.to_usize()))?;
-
-            Ok(toolchain.into_iter().next().and_then(|(name, path, raw_json)| Some(Toolchain {
-                name: name.into(),
-                path: path.into(),
-                language_name,
-                as_json: serde_json::Value::from_str(&raw_json).ok()?
-            })))
+        self.read(move |this| {
+            let toolchain = SELECT_TOOLCHAIN.rows(
+                this,
+                (
+                    workspace_id,
+                    language_name.0.to_string(),
+                    worktree_id.to_usize(),
+                ),
+            )?;
+
+            Ok(toolchain
+                .into_iter()
+                .next()
+                .and_then(|(name, path, raw_json)| {
+                    Some(Toolchain {
+                        name: name.into(),
+                        path: path.into(),
+                        language_name,
+                        as_json: serde_json::Value::from_str(&raw_json).ok()?,
+                    })
+                }))
         })
         .await
     }
@@ -1104,22 +1198,23 @@ impl WorkspaceDb {
         &self,
         workspace_id: WorkspaceId,
     ) -> Result<Vec<(Toolchain, WorktreeId)>> {
-        self.write(move |this| {
-            let mut select = this
-                .select_bound(sql!(
-                    SELECT name, path, worktree_id, language_name, raw_json FROM toolchains WHERE workspace_id = ?
-                ))
-                .context("Preparing insertion")?;
-
-            let toolchain: Vec<(String, String, u64, String, String)> =
-                select(workspace_id)?;
-
-            Ok(toolchain.into_iter().filter_map(|(name, path, worktree_id, language_name, raw_json)| Some((Toolchain {
-                name: name.into(),
-                path: path.into(),
-                language_name: LanguageName::new(&language_name),
-                as_json: serde_json::Value::from_str(&raw_json).ok()?
-            }, WorktreeId::from_proto(worktree_id)))).collect())
+        self.read(move |this| {
+            let toolchain = SELECT_TOOLCHAINS.rows(this, workspace_id)?;
+
+            Ok(toolchain
+                .into_iter()
+                .filter_map(|(name, path, worktree_id, language_name, raw_json)| {
+                    Some((
+                        Toolchain {
+                            name: name.into(),
+                            path: path.into(),
+                            language_name: LanguageName::new(&language_name),
+                            as_json: serde_json::Value::from_str(&raw_json).ok()?,
+                        },
+                        WorktreeId::from_proto(worktree_id),
+                    ))
+                })
+                .collect())
         })
         .await
     }
@@ -1592,6 +1687,37 @@ mod tests {
         assert_eq!(locations[0].2, Some(60));
     }
 
+    #[gpui::test]
+    async fn test_workspace_file() {
+        env_logger::try_init().ok();
+
+        let db = WorkspaceDb(open_test_db("test_workspace_file").await);
+
+        let workspace =
+            default_workspace(&["/code/frontend", "/code/backend"], &Default::default());
+        db.save_workspace(workspace.clone()).await;
+        assert_eq!(db.workspace_file(workspace.id).unwrap(), None);
+
+        let workspace_file = PathBuf::from("/code/app.zed-workspace");
+        db.set_workspace_file(workspace.id, Some(workspace_file.clone()))
+            .await
+            .unwrap();
+        // Saving the workspace again keeps its workspace file
+        db.save_workspace(workspace.clone()).await;
+        assert_eq!(
+            db.workspace_file(workspace.id).unwrap(),
+            Some(workspace_file.clone())
+        );
+        assert_eq!(
+            db.workspace_files().unwrap(),
+            vec![(workspace.id, workspace_file)]
+        );
+
+        db.set_workspace_file(workspace.id, None).await.unwrap();
+        assert_eq!(db.workspace_file(workspace.id).unwrap(), None);
+        assert_eq!(db.workspace_files().unwrap(), Vec::new());
+    }
+
     fn default_workspace<P: AsRef<Path>>(
         workspace_id: &[P],
         center_group: &SerializedPaneGroup,
@@ -1,6 +1,7 @@
 pub mod dock;
 pub mod item;
 mod modal_layer;
+mod named_layouts;
 pub mod notifications;
 pub mod pane;
 pub mod pane_group;
@@ -46,6 +47,7 @@ pub use item::{
 use itertools::Itertools;
 use language::{LanguageRegistry, Rope};
 pub use modal_layer::*;
+pub use named_layouts::{delete_named_layout, named_layouts};
 use node_runtime::NodeRuntime;
 use notifications::{
     simple_message_notification::MessageNotification, DetachAndPromptErr, NotificationHandle,
@@ -53,7 +55,7 @@ use notifications::{
 pub use pane::*;
 pub use pane_group::*;
 pub use persistence::{
-    model::{ItemId, LocalPaths, SerializedWorkspaceLocation},
+    model::{ItemId, LocalPaths, LocalPathsOrder, SerializedWorkspaceLocation},
     WorkspaceDb, DB as WORKSPACE_DB,
 };
 use persistence::{
@@ -62,6 +64,7 @@ use persistence::{
 };
 use postage::stream::Stream;
 use project::{
+    workspace_file::{is_workspace_file, WorkspaceFileContent},
     DirectoryLister, Project, ProjectEntryId, ProjectPath, ResolvedPath, Worktree, WorktreeId,
 };
 use remote::{ssh_session::ConnectionIdentifier, SshClientDelegate, SshConnectionOptions};
@@ -204,6 +207,18 @@ pub struct CloseInactiveTabsAndPanes {
 #[derive(Clone, Deserialize, PartialEq)]
 pub struct SendKeystrokes(pub String);
 
+/// Saves the current layout of panes, items and docks under a name.
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct SaveLayout {
+    pub name: String,
+}
+
+/// Replaces the current layout with one that was saved under a name.
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct LoadLayout {
+    pub name: String,
+}
+
 #[derive(Clone, Deserialize, PartialEq, Default)]
 pub struct Reload {
     pub binary_path: Option<PathBuf>,
@@ -226,10 +241,12 @@ impl_actions!(
         ActivatePaneInDirection,
         CloseAllItemsAndPanes,
         CloseInactiveTabsAndPanes,
+        LoadLayout,
         OpenTerminal,
         Reload,
         Save,
         SaveAll,
+        SaveLayout,
         SwapPaneInDirection,
         SendKeystrokes,
     ]
@@ -691,6 +708,7 @@ pub enum Event {
     },
     ContactRequestedJoin(u64),
     WorkspaceCreated(WeakView<Workspace>),
+    WorkspaceRestored,
     SpawnTask(Box<SpawnInTerminal>),
     OpenBundledFile {
         text: Cow<'static, str>,
@@ -815,7 +833,7 @@ impl Workspace {
                     this.serialize_workspace(cx);
                 }
 
-                project::Event::DisconnectedFromHost => {
+                project::Event::DisconnectedFromHost | project::Event::WentOffline => {
                     this.update_window_edited(cx);
                     let leaders_to_unfollow =
                         this.follower_states.keys().copied().collect::<Vec<_>>();
@@ -1163,6 +1181,12 @@ impl Workspace {
                 DB.next_id().await.unwrap_or_else(|_| Default::default())
             };
 
+            if let Some(workspace_file) = DB.workspace_file(workspace_id).log_err().flatten() {
+                project_handle.update(&mut cx, |project, cx| {
+                    project.set_workspace_file(Some(workspace_file.into()), cx)
+                })?;
+            }
+
             let toolchains = DB.toolchains(workspace_id).await?;
             for (toolchain, worktree_id) in toolchains {
                 project_handle
@@ -1844,7 +1868,21 @@ impl Workspace {
         mut save_intent: SaveIntent,
         cx: &mut ViewContext<Self>,
     ) -> Task<Result<bool>> {
-        if self.project.read(cx).is_disconnected(cx) {
+        let project = self.project.read(cx);
+        if project.is_offline() {
+            // Edits made offline can't be saved, and are lost unless they're sent to the host.
+            if save_intent != SaveIntent::Close || project.buffers_edited_offline(cx).is_empty() {
+                return Task::ready(Ok(true));
+            }
+            let answer = cx.prompt(
+                PromptLevel::Warning,
+                "Discard the edits you made while offline?",
+                Some("They haven't been sent to the host yet. Rejoin the call to send them."),
+                &["Discard", "Cancel"],
+            );
+            return cx.spawn(|_, _| async move { Ok(answer.await? == 0) });
+        }
+        if project.is_disconnected(cx) {
             return Task::ready(Ok(true));
         }
         let dirty_items = self
@@ -3506,7 +3544,8 @@ impl Workspace {
     }
 
     fn update_window_edited(&mut self, cx: &mut WindowContext) {
-        let is_edited = !self.project.read(cx).is_disconnected(cx)
+        let project = self.project.read(cx);
+        let is_edited = (!project.is_disconnected(cx) || project.is_offline())
             && self
                 .items(cx)
                 .any(|item| item.has_conflict(cx) || item.is_dirty(cx));
@@ -4383,6 +4422,7 @@ impl Workspace {
 
                     // Ensure that we mark the window as edited if we did load dirty items
                     workspace.update_window_edited(cx);
+                    cx.emit(Event::WorkspaceRestored);
                 })
                 .ok();
 
@@ -4462,6 +4502,16 @@ impl Workspace {
                 }),
             )
             .on_action(cx.listener(Workspace::toggle_centered_layout))
+            .on_action(cx.listener(|workspace, action: &SaveLayout, cx| {
+                workspace
+                    .save_named_layout(action.name.clone(), cx)
+                    .detach_and_prompt_err("Failed to save layout", cx, |_, _| None);
+            }))
+            .on_action(cx.listener(|workspace, action: &LoadLayout, cx| {
+                workspace
+                    .apply_named_layout(action.name.clone(), cx)
+                    .detach_and_prompt_err("Failed to load layout", cx, |_, _| None);
+            }))
     }
 
     #[cfg(any(test, feature = "test-support"))]
@@ -5468,6 +5518,16 @@ pub fn open_paths(
         Vec<Option<Result<Box<dyn ItemHandle>, anyhow::Error>>>,
     )>,
 > {
+    if let Some(workspace_file_ix) = abs_paths.iter().position(|path| is_workspace_file(path)) {
+        return open_workspace_file(
+            abs_paths.to_vec(),
+            workspace_file_ix,
+            app_state,
+            open_options,
+            cx,
+        );
+    }
+
     let abs_paths = abs_paths.to_vec();
     let mut existing = None;
     let mut best_match = None;
@@ -5540,6 +5600,91 @@ pub fn open_paths(
     })
 }
 
+/// Opens the folders listed in the workspace file at `abs_paths[workspace_file_ix]` as a
+/// project with the file's settings and tasks, along with the rest of `abs_paths`.
+///
+/// A window that already has the project open is reused.
+#[allow(clippy::type_complexity)]
+fn open_workspace_file(
+    mut abs_paths: Vec<PathBuf>,
+    workspace_file_ix: usize,
+    app_state: Arc<AppState>,
+    open_options: OpenOptions,
+    cx: &mut AppContext,
+) -> Task<
+    anyhow::Result<(
+        WindowHandle<Workspace>,
+        Vec<Option<Result<Box<dyn ItemHandle>, anyhow::Error>>>,
+    )>,
+> {
+    cx.spawn(move |mut cx| async move {
+        let workspace_file_path = abs_paths.remove(workspace_file_ix);
+        let workspace_file_path: Arc<Path> = app_state
+            .fs
+            .canonicalize(&workspace_file_path)
+            .await
+            .unwrap_or(workspace_file_path)
+            .into();
+        let content = app_state
+            .fs
+            .load(&workspace_file_path)
+            .await
+            .with_context(|| format!("loading workspace file {workspace_file_path:?}"))?;
+        let folder_paths = WorkspaceFileContent::parse(&content)
+            .with_context(|| format!("parsing workspace file {workspace_file_path:?}"))?
+            .folder_paths(&workspace_file_path);
+
+        let existing = cx.update(|cx| {
+            local_workspace_windows(cx).into_iter().find(|window| {
+                window.read(cx).is_ok_and(|workspace| {
+                    workspace.project.read(cx).workspace_file(cx).as_ref()
+                        == Some(&workspace_file_path)
+                })
+            })
+        })?;
+
+        let (window, mut opened_items) = if let Some(existing) = existing {
+            let opened_items = existing
+                .update(&mut cx, |workspace, cx| {
+                    cx.activate_window();
+                    workspace.open_paths(abs_paths, OpenVisible::None, None, cx)
+                })?
+                .await;
+            (existing, opened_items)
+        } else {
+            let folder_count = folder_paths.len();
+            let (window, mut opened_items) = cx
+                .update(|cx| {
+                    Workspace::new_local(
+                        folder_paths.into_iter().chain(abs_paths).collect(),
+                        app_state,
+                        open_options.replace_window,
+                        open_options.env,
+                        cx,
+                    )
+                })?
+                .await?;
+            opened_items.drain(..folder_count);
+
+            let workspace_id = window.update(&mut cx, |workspace, cx| {
+                workspace.project.update(cx, |project, cx| {
+                    project.set_workspace_file(Some(workspace_file_path.clone()), cx)
+                });
+                workspace.database_id()
+            })?;
+            if let Some(workspace_id) = workspace_id {
+                DB.set_workspace_file(workspace_id, Some(workspace_file_path.to_path_buf()))
+                    .await
+                    .log_err();
+            }
+            (window, opened_items)
+        };
+
+        opened_items.insert(workspace_file_ix, None);
+        Ok((window, opened_items))
+    })
+}
+
 pub fn open_new(
     open_options: OpenOptions,
     app_state: Arc<AppState>,
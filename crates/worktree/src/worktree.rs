@@ -29,7 +29,7 @@ use gpui::{
     Task,
 };
 use ignore::IgnoreStack;
-use language::DiskState;
+use language::{DiskState, LargeFile, LARGE_FILE_PAGE_LEN};
 use parking_lot::Mutex;
 use paths::local_settings_folder_relative_path;
 use postage::{
@@ -111,6 +111,11 @@ pub struct LoadedBinaryFile {
     pub content: Vec<u8>,
 }
 
+pub struct LoadedLargeFile {
+    pub file: Arc<File>,
+    pub content: Arc<LargeFile>,
+}
+
 pub struct LocalWorktree {
     snapshot: LocalSnapshot,
     scan_requests_tx: channel::Sender<ScanRequest>,
@@ -706,6 +711,25 @@ impl Worktree {
         }
     }
 
+    /// Loads a file in large file mode if it's larger than `threshold` bytes, or returns `None`
+    /// for smaller files, which should be loaded with [`Worktree::load_file`].
+    ///
+    /// The file is memory-mapped and split into pages of at most `threshold` bytes, so only the
+    /// pages that are viewed are read into memory.
+    pub fn load_large_file(
+        &self,
+        path: &Path,
+        threshold: u64,
+        cx: &ModelContext<Worktree>,
+    ) -> Task<Result<Option<LoadedLargeFile>>> {
+        match self {
+            Worktree::Local(this) => this.load_large_file(path, threshold, cx),
+            Worktree::Remote(_) => {
+                Task::ready(Err(anyhow!("remote worktrees can't yet load files")))
+            }
+        }
+    }
+
     pub fn load_staged_file(&self, path: &Path, cx: &AppContext) -> Task<Result<Option<String>>> {
         match self {
             Worktree::Local(this) => {
@@ -1378,6 +1402,75 @@ impl LocalWorktree {
         })
     }
 
+    fn load_large_file(
+        &self,
+        path: &Path,
+        threshold: u64,
+        cx: &ModelContext<Worktree>,
+    ) -> Task<Result<Option<LoadedLargeFile>>> {
+        let path: Arc<Path> = Arc::from(path);
+        let abs_path = self.absolutize(&path);
+        let fs = self.fs.clone();
+        let is_private = self.is_path_private(path.as_ref());
+
+        // The size is read from the file system rather than the entry, so that excluded files,
+        // which have no entry, are also loaded in large file mode.
+        cx.spawn(|this, mut cx| async move {
+            let abs_path = abs_path?;
+            let Some(metadata) = fs.metadata(&abs_path).await? else {
+                return Ok(None);
+            };
+            if metadata.is_dir || metadata.len <= threshold {
+                return Ok(None);
+            }
+            let page_len = LARGE_FILE_PAGE_LEN.min(threshold.try_into().unwrap_or(usize::MAX));
+
+            let entry = this.update(&mut cx, |this, cx| {
+                this.as_local()
+                    .context("worktree is not local")
+                    .map(|this| this.refresh_entry(path.clone(), None, cx))
+            })??;
+            let content = cx
+                .background_executor()
+                .spawn({
+                    let abs_path = abs_path.clone();
+                    async move {
+                        // The fake file system has no files to map, so its contents are read.
+                        if fs.is_fake() {
+                            let bytes = fs.load_bytes(&abs_path).await?;
+                            anyhow::Ok(LargeFile::from_bytes(bytes, page_len))
+                        } else {
+                            LargeFile::open(&abs_path, page_len)
+                                .with_context(|| format!("opening large file {abs_path:?}"))
+                        }
+                    }
+                })
+                .await?;
+
+            let worktree = this
+                .upgrade()
+                .ok_or_else(|| anyhow!("worktree was dropped"))?;
+            let file = match entry.await? {
+                Some(entry) => File::for_entry(entry, worktree),
+                None => Arc::new(File {
+                    entry_id: None,
+                    worktree,
+                    path,
+                    disk_state: DiskState::Present {
+                        mtime: metadata.mtime,
+                    },
+                    is_local: true,
+                    is_private,
+                }),
+            };
+
+            Ok(Some(LoadedLargeFile {
+                file,
+                content: Arc::new(content),
+            }))
+        })
+    }
+
     fn load_file(&self, path: &Path, cx: &ModelContext<Worktree>) -> Task<Result<LoadedFile>> {
         let path = Arc::from(path);
         let abs_path = self.absolutize(&path);
@@ -4922,14 +5015,85 @@ impl BackgroundScanner {
         event_paths: &[Arc<Path>],
     ) -> UpdatedEntriesSet {
         use BackgroundScannerPhase::*;
-        use PathChange::{Added, AddedOrUpdated, Loaded, Removed, Updated};
+        use PathChange::{Added, Loaded, Removed, Updated};
+
+        // If the worktree was not fully initialized when these events were generated, we can't
+        // know whether the entries beneath them were added during the scan or merely updated, so
+        // every one of them needs to be reported, including those that are unchanged.
+        if self.phase == EventsReceivedDuringInitialScan {
+            return Self::build_change_set_for_initial_scan_events(
+                old_snapshot,
+                new_snapshot,
+                event_paths,
+            );
+        }
+
+        // Identify which paths have changed beneath the known set of changed parent paths. Diffing
+        // the trees skips over the subtrees that the snapshots share, so this is proportional to
+        // the number of changed entries rather than the size of the worktree.
+        let is_event_path = |path: &Path| {
+            path.ancestors().any(|ancestor| {
+                event_paths
+                    .binary_search_by(|event_path| event_path.as_ref().cmp(ancestor))
+                    .is_ok()
+            })
+        };
+        let mut changes = Vec::new();
+        let mut last_newly_loaded_dir_path = None;
+        for change in SumTree::diff(&old_snapshot.entries_by_path, &new_snapshot.entries_by_path) {
+            match change {
+                sum_tree::Change::Removed(old_entry) => {
+                    if is_event_path(&old_entry.path) {
+                        changes.push((old_entry.path.clone(), old_entry.id, Removed));
+                    }
+                }
+                sum_tree::Change::Added(new_entry) => {
+                    if is_event_path(&new_entry.path) {
+                        let is_newly_loaded = self.phase == InitialScan
+                            || last_newly_loaded_dir_path
+                                .as_ref()
+                                .map_or(false, |dir| new_entry.path.starts_with(dir));
+                        changes.push((
+                            new_entry.path.clone(),
+                            new_entry.id,
+                            if is_newly_loaded { Loaded } else { Added },
+                        ));
+                    }
+                }
+                sum_tree::Change::Updated {
+                    old: old_entry,
+                    new: new_entry,
+                } => {
+                    if !is_event_path(&new_entry.path) {
+                        continue;
+                    }
+
+                    if old_entry.id != new_entry.id {
+                        changes.push((old_entry.path.clone(), old_entry.id, Removed));
+                        changes.push((new_entry.path.clone(), new_entry.id, Added));
+                    } else if old_entry.kind.is_unloaded() {
+                        last_newly_loaded_dir_path = Some(&new_entry.path);
+                        changes.push((new_entry.path.clone(), new_entry.id, Loaded));
+                    } else {
+                        changes.push((new_entry.path.clone(), new_entry.id, Updated));
+                    }
+                }
+            }
+        }
+
+        changes.into()
+    }
+
+    fn build_change_set_for_initial_scan_events(
+        old_snapshot: &Snapshot,
+        new_snapshot: &Snapshot,
+        event_paths: &[Arc<Path>],
+    ) -> UpdatedEntriesSet {
+        use PathChange::{Added, AddedOrUpdated, Removed};
 
-        // Identify which paths have changed. Use the known set of changed
-        // parent paths to optimize the search.
         let mut changes = Vec::new();
         let mut old_paths = old_snapshot.entries_by_path.cursor::<PathKey>(&());
         let mut new_paths = new_snapshot.entries_by_path.cursor::<PathKey>(&());
-        let mut last_newly_loaded_dir_path = None;
         old_paths.next(&());
         new_paths.next(&());
         for path in event_paths {
@@ -4957,54 +5121,19 @@ impl BackgroundScanner {
                                 old_paths.next(&());
                             }
                             Ordering::Equal => {
-                                if self.phase == EventsReceivedDuringInitialScan {
-                                    if old_entry.id != new_entry.id {
-                                        changes.push((
-                                            old_entry.path.clone(),
-                                            old_entry.id,
-                                            Removed,
-                                        ));
-                                    }
-                                    // If the worktree was not fully initialized when this event was generated,
-                                    // we can't know whether this entry was added during the scan or whether
-                                    // it was merely updated.
-                                    changes.push((
-                                        new_entry.path.clone(),
-                                        new_entry.id,
-                                        AddedOrUpdated,
-                                    ));
-                                } else if old_entry.id != new_entry.id {
+                                if old_entry.id != new_entry.id {
                                     changes.push((old_entry.path.clone(), old_entry.id, Removed));
-                                    changes.push((new_entry.path.clone(), new_entry.id, Added));
-                                } else if old_entry != new_entry {
-                                    if old_entry.kind.is_unloaded() {
-                                        last_newly_loaded_dir_path = Some(&new_entry.path);
-                                        changes.push((
-                                            new_entry.path.clone(),
-                                            new_entry.id,
-                                            Loaded,
-                                        ));
-                                    } else {
-                                        changes.push((
-                                            new_entry.path.clone(),
-                                            new_entry.id,
-                                            Updated,
-                                        ));
-                                    }
                                 }
-                                old_paths.next(&());
-                                new_paths.next(&());
-                            }
-                            Ordering::Greater => {
-                                let is_newly_loaded = self.phase == InitialScan
-                                    || last_newly_loaded_dir_path
-                                        .as_ref()
-                                        .map_or(false, |dir| new_entry.path.starts_with(dir));
                                 changes.push((
                                     new_entry.path.clone(),
                                     new_entry.id,
-                                    if is_newly_loaded { Loaded } else { Added },
+                                    AddedOrUpdated,
                                 ));
+                                old_paths.next(&());
+                                new_paths.next(&());
+                            }
+                            Ordering::Greater => {
+                                changes.push((new_entry.path.clone(), new_entry.id, Added));
                                 new_paths.next(&());
                             }
                         }
@@ -5014,15 +5143,7 @@ impl BackgroundScanner {
                         old_paths.next(&());
                     }
                     (None, Some(new_entry)) => {
-                        let is_newly_loaded = self.phase == InitialScan
-                            || last_newly_loaded_dir_path
-                                .as_ref()
-                                .map_or(false, |dir| new_entry.path.starts_with(dir));
-                        changes.push((
-                            new_entry.path.clone(),
-                            new_entry.id,
-                            if is_newly_loaded { Loaded } else { Added },
-                        ));
+                        changes.push((new_entry.path.clone(), new_entry.id, Added));
                         new_paths.next(&());
                     }
                     (None, None) => break,
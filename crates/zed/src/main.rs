@@ -326,6 +326,7 @@ fn main() {
 
         zed::init(cx);
         project::Project::init(&client, cx);
+        project::local_history::init(fs.clone(), cx);
         client::init(&client, cx);
         let telemetry = client.telemetry();
         telemetry.start(
@@ -451,6 +452,7 @@ fn main() {
         terminal_view::init(cx);
         journal::init(app_state.clone(), cx);
         language_selector::init(cx);
+        layout_selector::init(cx);
         toolchain_selector::init(cx);
         theme_selector::init(cx);
         language_tools::init(cx);
@@ -1338,6 +1338,78 @@ mod tests {
             .unwrap();
     }
 
+    #[gpui::test]
+    async fn test_open_workspace_file(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree(
+                "/root",
+                json!({
+                    "app": {
+                        "app.zed-workspace": r#"{
+                            "folders": [{ "path": "frontend" }, { "path": "../backend" }]
+                        }"#,
+                        "frontend": { "a.txt": "a" },
+                    },
+                    "backend": { "b.txt": "b" },
+                }),
+            )
+            .await;
+
+        let (window, items) = cx
+            .update(|cx| {
+                open_paths(
+                    &[
+                        PathBuf::from("/root/app/app.zed-workspace"),
+                        PathBuf::from("/root/backend/b.txt"),
+                    ],
+                    app_state.clone(),
+                    workspace::OpenOptions::default(),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        assert_eq!(cx.read(|cx| cx.windows().len()), 1);
+        // The workspace file itself isn't opened, but the other paths are
+        assert!(items[0].is_none());
+        assert!(matches!(items[1], Some(Ok(_))));
+        window
+            .update(cx, |workspace, cx| {
+                let worktree_roots = workspace
+                    .worktrees(cx)
+                    .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+                    .collect::<Vec<_>>();
+                assert_eq!(
+                    worktree_roots,
+                    [
+                        PathBuf::from("/root/app/frontend"),
+                        PathBuf::from("/root/backend")
+                    ]
+                );
+                assert_eq!(
+                    workspace.project().read(cx).workspace_file(cx).as_deref(),
+                    Some(Path::new("/root/app/app.zed-workspace"))
+                );
+            })
+            .unwrap();
+
+        // Opening the workspace file again reuses its window
+        cx.update(|cx| {
+            open_paths(
+                &[PathBuf::from("/root/app/app.zed-workspace")],
+                app_state.clone(),
+                workspace::OpenOptions::default(),
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+        assert_eq!(cx.read(|cx| cx.windows().len()), 1);
+    }
+
     #[gpui::test]
     async fn test_open_add_new(cx: &mut TestAppContext) {
         let app_state = init_test(cx);
@@ -75,12 +75,26 @@ pub mod theme_selector {
     impl_actions!(theme_selector, [Toggle]);
 }
 
+pub mod icon_theme_selector {
+    use gpui::impl_actions;
+    use serde::Deserialize;
+
+    #[derive(PartialEq, Clone, Default, Debug, Deserialize)]
+    pub struct Toggle {
+        /// A list of icon theme names to filter the icon theme selector down to.
+        pub themes_filter: Option<Vec<String>>,
+    }
+
+    impl_actions!(icon_theme_selector, [Toggle]);
+}
+
 #[derive(Clone, Default, Deserialize, PartialEq)]
 pub struct InlineAssist {
     pub prompt: Option<String>,
 }
 
 impl_actions!(assistant, [InlineAssist]);
+actions!(assistant, [ShowUsage]);
 
 #[derive(PartialEq, Clone, Deserialize, Default)]
 pub struct OpenRecent {
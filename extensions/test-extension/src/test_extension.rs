@@ -1,10 +1,16 @@
 use std::fs;
 use zed::lsp::CompletionKind;
-use zed::{CodeLabel, CodeLabelSpan, LanguageServerId};
+use zed::serde_json::{json, Value};
+use zed::{
+    CodeLabel, CodeLabelSpan, LanguageServerCommandBuffer, LanguageServerCommandContext,
+    LanguageServerCommandOutput, LanguageServerId, TaskTemplate,
+};
 use zed_extension_api::{self as zed, Result};
 
 struct TestExtension {
     cached_binary_path: Option<String>,
+    /// The parameters of the `gleam/log` notifications sent by the language server.
+    logs: Vec<Value>,
 }
 
 impl TestExtension {
@@ -88,6 +94,7 @@ impl zed::Extension for TestExtension {
     fn new() -> Self {
         Self {
             cached_binary_path: None,
+            logs: Vec::new(),
         }
     }
 
@@ -103,6 +110,55 @@ impl zed::Extension for TestExtension {
         })
     }
 
+    fn handle_language_server_request(
+        &mut self,
+        _language_server_id: &LanguageServerId,
+        method: String,
+        params: Value,
+        _worktree: &zed::Worktree,
+    ) -> Result<Value> {
+        match method.as_str() {
+            "gleam/echo" => Ok(json!({ "params": params, "logs": self.logs })),
+            _ => Err(format!("unhandled request `{method}`")),
+        }
+    }
+
+    fn handle_language_server_notification(
+        &mut self,
+        _language_server_id: &LanguageServerId,
+        method: String,
+        params: Value,
+        _worktree: &zed::Worktree,
+    ) -> Result<()> {
+        if method == "gleam/log" {
+            self.logs.push(params);
+        }
+        Ok(())
+    }
+
+    fn run_language_server_command(
+        &mut self,
+        _language_server_id: &LanguageServerId,
+        command: String,
+        context: LanguageServerCommandContext,
+        language_server: &zed::LanguageServer,
+    ) -> Result<LanguageServerCommandOutput> {
+        match command.as_str() {
+            "gleam.ping" => {
+                let response =
+                    language_server.request_json("gleam/ping", &json!({ "uri": context.uri }))?;
+                Ok(LanguageServerCommandOutput::OpenBuffer(
+                    LanguageServerCommandBuffer {
+                        title: "Ping".into(),
+                        text: response.to_string(),
+                        language: None,
+                    },
+                ))
+            }
+            _ => Err(format!("unknown command `{command}`")),
+        }
+    }
+
     fn label_for_completion(
         &self,
         _language_server_id: &LanguageServerId,
@@ -138,6 +194,30 @@ impl zed::Extension for TestExtension {
             code,
         })
     }
+
+    fn task_templates(
+        &self,
+        provider_id: String,
+        worktree: &zed::Worktree,
+    ) -> Result<Vec<TaskTemplate>> {
+        match provider_id.as_str() {
+            "scripts" => {
+                let scripts = worktree.read_text_file("scripts.txt")?;
+                Ok(scripts
+                    .lines()
+                    .map(|script| TaskTemplate {
+                        label: format!("run {script}"),
+                        command: "./run".to_string(),
+                        args: vec![script.to_string()],
+                        env: Vec::new(),
+                        cwd: Some("scripts".to_string()),
+                        tags: Vec::new(),
+                    })
+                    .collect())
+            }
+            _ => Err(format!("unknown task provider `{provider_id}`")),
+        }
+    }
 }
 
 zed::register_extension!(TestExtension);